@@ -0,0 +1,465 @@
+//! Consolidated parts list for purchasing
+//!
+//! `aeda export bom` lists every generated part once, with its ratings,
+//! manufacturer part numbers, distributor part numbers and, for libraries
+//! enriched with `aeda enrich`, the lowest unit price Octopart reported.
+//! With an `[ipn]` scheme in config.toml every part also gets its internal
+//! part number. The list is written as CSV, as an XLSX workbook, or as CSV
+//! in KiCad's field names (Value, Footprint, MPN, ...) so it joins a KiCad
+//! project BOM on value and footprint.
+
+use super::generate::load_manifest;
+use super::octopart::{self, PartInfo};
+use component::limits::Limits;
+use component::{Distributor, Manufacturer, Resistance};
+use rust_xlsxwriter::{Format, Workbook};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BomFormat {
+    Csv,
+    Xlsx,
+    Kicad,
+}
+
+impl BomFormat {
+    fn parse(format: &str) -> Result<Self, String> {
+        match format.to_lowercase().as_str() {
+            "csv" => Ok(BomFormat::Csv),
+            "xlsx" => Ok(BomFormat::Xlsx),
+            "kicad" => Ok(BomFormat::Kicad),
+            _ => Err(format!("Unknown BOM format '{}', expected csv, xlsx or kicad", format)),
+        }
+    }
+
+    fn file_name(self) -> &'static str {
+        match self {
+            BomFormat::Csv => "bom.csv",
+            BomFormat::Xlsx => "bom.xlsx",
+            BomFormat::Kicad => "bom_kicad.csv",
+        }
+    }
+}
+
+/// One line of the BOM, and one row of the parts database
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub(crate) struct BomRow {
+    pub(crate) part: String,
+    /// Internal part number, see `component::ipn`
+    pub(crate) ipn: String,
+    pub(crate) library: String,
+    pub(crate) value: String,
+    pub(crate) package: String,
+    pub(crate) footprint: String,
+    pub(crate) tolerance: String,
+    pub(crate) power: String,
+    pub(crate) voltage: String,
+    pub(crate) manufacturer: String,
+    pub(crate) mpn: String,
+    /// Further sources as `Manufacturer MPN`
+    pub(crate) alternates: Vec<String>,
+    pub(crate) digikey: String,
+    pub(crate) mouser: String,
+    pub(crate) lcsc: String,
+    pub(crate) unit_price: Option<f64>,
+    pub(crate) currency: String,
+}
+
+pub(crate) const HEADER: [&str; 17] = [
+    "Part",
+    "IPN",
+    "Library",
+    "Value",
+    "Package",
+    "Footprint",
+    "Tolerance",
+    "Power",
+    "Voltage",
+    "Manufacturer",
+    "MPN",
+    "Alternates",
+    "Digi-Key PN",
+    "Mouser PN",
+    "LCSC",
+    "Unit Price",
+    "Currency",
+];
+
+/// Columns of the KiCad flavour, named like the fields of the generated symbols
+const KICAD_HEADER: [&str; 9] = ["Value", "Footprint", "Datasheet", "Manufacturer", "MPN", "Supplier", "SupplierPN", "LCSC", "IPN"];
+
+impl BomRow {
+    /// Take the lowest unit price of the primary MPN from the library's
+    /// Octopart data
+    fn price_from(&mut self, octopart: &BTreeMap<String, PartInfo>) {
+        if let Some(price) = octopart.get(&self.mpn).and_then(octopart::unit_price) {
+            self.unit_price = Some(price.price);
+            self.currency = price.currency.clone();
+        }
+    }
+
+    pub(crate) fn fields(&self) -> [String; 17] {
+        [
+            self.part.clone(),
+            self.ipn.clone(),
+            self.library.clone(),
+            self.value.clone(),
+            self.package.clone(),
+            self.footprint.clone(),
+            self.tolerance.clone(),
+            self.power.clone(),
+            self.voltage.clone(),
+            self.manufacturer.clone(),
+            self.mpn.clone(),
+            self.alternates.join("; "),
+            self.digikey.clone(),
+            self.mouser.clone(),
+            self.lcsc.clone(),
+            self.unit_price.map(|price| price.to_string()).unwrap_or_default(),
+            self.currency.clone(),
+        ]
+    }
+
+    fn kicad_fields(&self) -> [String; 9] {
+        let (supplier, supplier_pn) = if !self.digikey.is_empty() {
+            ("Digikey", self.digikey.as_str())
+        } else if !self.mouser.is_empty() {
+            ("Mouser", self.mouser.as_str())
+        } else {
+            ("", "")
+        };
+        [
+            self.value.clone(),
+            self.footprint.clone(),
+            component::datasheet::field(&self.manufacturer, &self.mpn),
+            self.manufacturer.clone(),
+            self.mpn.clone(),
+            supplier.to_string(),
+            supplier_pn.to_string(),
+            self.lcsc.clone(),
+            self.ipn.clone(),
+        ]
+    }
+}
+
+/// CSV field, quoted when it holds a separator, quote or line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Fields of a row cut to the `[limits.bom]` of their columns
+fn fitted(limits: &Limits, columns: &[&str], part: &str, fields: &[String]) -> Vec<String> {
+    columns
+        .iter()
+        .zip(fields)
+        .map(|(column, field)| limits.fit("bom", column, part, field).into_owned())
+        .collect()
+}
+
+pub(crate) fn csv(rows: &[BomRow], limits: &Limits) -> String {
+    let mut csv = format!("{}\n", HEADER.join(","));
+    for row in rows {
+        let fields = fitted(limits, &HEADER, &row.part, &row.fields());
+        let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&format!("{}\n", fields.join(",")));
+    }
+    csv
+}
+
+/// KiCad's BOM export quotes every field
+fn kicad_csv(rows: &[BomRow], limits: &Limits) -> String {
+    let quote = |field: &str| format!("\"{}\"", field.replace('"', "\"\""));
+    let mut csv = format!("{}\n", KICAD_HEADER.map(quote).join(","));
+    for row in rows {
+        let fields = fitted(limits, &KICAD_HEADER, &row.part, &row.kicad_fields());
+        let fields: Vec<String> = fields.iter().map(|field| quote(field)).collect();
+        csv.push_str(&format!("{}\n", fields.join(",")));
+    }
+    csv
+}
+
+fn xlsx(rows: &[BomRow], limits: &Limits) -> Result<Vec<u8>, String> {
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("BOM").map_err(|e| format!("Failed to create worksheet: {}", e))?;
+    let bold = Format::new().set_bold();
+    let price_column = HEADER.iter().position(|&column| column == "Unit Price").unwrap_or_default() as u16;
+    for (column, title) in HEADER.iter().enumerate() {
+        sheet
+            .write_string_with_format(0, column as u16, *title, &bold)
+            .map_err(|e| format!("Failed to write BOM header: {}", e))?;
+    }
+    for (index, row) in rows.iter().enumerate() {
+        let line = index as u32 + 1;
+        for (column, field) in fitted(limits, &HEADER, &row.part, &row.fields()).iter().enumerate() {
+            let column = column as u16;
+            let written = match row.unit_price {
+                Some(price) if column == price_column => sheet.write_number(line, column, price),
+                _ => sheet.write_string(line, column, field),
+            };
+            written.map_err(|e| format!("Failed to write BOM row {}: {}", line, e))?;
+        }
+    }
+    sheet.set_freeze_panes(1, 0).map_err(|e| format!("Failed to freeze BOM header: {}", e))?;
+    sheet.autofit();
+    workbook.save_to_buffer().map_err(|e| format!("Failed to build workbook: {}", e))
+}
+
+/// Recorded Octopart data of a library, keyed by MPN
+fn octopart_data(library: &serde_json::Value) -> BTreeMap<String, PartInfo> {
+    serde_json::from_value(library["octopart"].clone()).unwrap_or_default()
+}
+
+fn resistor_rows(name: &str, library: &serde_json::Value) -> Result<Vec<BomRow>, String> {
+    let mut rows = Vec::new();
+    for series in super::export::resistor_series(library) {
+        rows.extend(series_rows(name, &series)?);
+    }
+    component::order::sort(&mut rows, |row| (row.part.as_str(), row.value.as_str()));
+    Ok(rows)
+}
+
+fn series_rows(name: &str, library: &serde_json::Value) -> Result<Vec<BomRow>, String> {
+    let package = library["package"].as_str().unwrap_or_default();
+    let tolerance = library["tolerance"].as_str().unwrap_or("1%");
+    let automotive = library["automotive"].as_bool().unwrap_or(false);
+    let primary = library["manufacturer"].as_str().unwrap_or("Vishay");
+    let mut manufacturers = vec![primary.parse::<Manufacturer>()?];
+    for alternate in library["alternate_manufacturers"].as_array().into_iter().flatten() {
+        manufacturers.push(alternate.as_str().unwrap_or_default().parse()?);
+    }
+    let octopart = octopart_data(library);
+
+    let mut rows = Vec::new();
+    for resistance in super::export::library_resistances(library) {
+        if !component::availability::available(primary, package, tolerance, resistance) {
+            continue;
+        }
+        // Same sources, in the same order, as the generated symbols
+        let mut sources: Vec<(&Manufacturer, String)> = manufacturers
+            .iter()
+            .filter_map(|manufacturer| {
+                let mpn = if automotive {
+                    manufacturer.aec_q200_mpn(package, resistance, tolerance)
+                } else {
+                    manufacturer.resistor_mpn(package, resistance, tolerance)
+                };
+                mpn.map(|mpn| (manufacturer, mpn))
+            })
+            .collect();
+        component::sourcing::rank(&mut sources, |(_, mpn)| Some(mpn.as_str()));
+        let mut row = resistor_row(name, library, resistance);
+        if let Some(((manufacturer, mpn), alternates)) = sources.split_first() {
+            let distributor_pn = |distributor| {
+                if automotive {
+                    manufacturer.aec_q200_distributor_pn(distributor, package, resistance, tolerance)
+                } else {
+                    manufacturer.distributor_pn(distributor, package, resistance, tolerance)
+                }
+            };
+            row.manufacturer = manufacturer.name().to_string();
+            row.mpn = mpn.clone();
+            row.alternates = alternates.iter().map(|(m, mpn)| format!("{} {}", m.name(), mpn)).collect();
+            row.digikey = distributor_pn(Distributor::Digikey).unwrap_or_default();
+            row.mouser = distributor_pn(Distributor::Mouser).unwrap_or_default();
+        }
+        row.price_from(&octopart);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// A resistor row without sources
+fn resistor_row(name: &str, library: &serde_json::Value, resistance: Resistance) -> BomRow {
+    let package = library["package"].as_str().unwrap_or_default();
+    let tolerance = library["tolerance"].as_str().unwrap_or_default();
+    let in_name = library["tolerance_in_name"].as_bool().unwrap_or(false);
+    BomRow {
+        part: component::resistor_name(package, resistance, in_name.then_some(tolerance)),
+        library: format!("resistor::{}", name),
+        value: resistance.label(),
+        package: package.to_string(),
+        footprint: library["footprint"].as_str().unwrap_or_default().to_string(),
+        tolerance: tolerance.to_string(),
+        power: library["power_rating"].as_str().unwrap_or_default().to_string(),
+        lcsc: component::lcsc::resistor(package, resistance)
+            .and_then(|part| part.sku)
+            .unwrap_or_default()
+            .to_string(),
+        ..Default::default()
+    }
+}
+
+fn capacitor_rows(name: &str, library: &serde_json::Value) -> Vec<BomRow> {
+    let package = library["package"].as_str().unwrap_or_default();
+    let dielectric = library["dielectric"].as_str().unwrap_or_default();
+    let octopart = octopart_data(library);
+    let mut rows: Vec<BomRow> = library["values"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|value| value.as_str())
+        .map(|value| {
+            let part = component::lcsc::capacitor(package, dielectric, value);
+            let mut row = BomRow {
+                part: super::generate::capacitor_name(package, dielectric, value),
+                library: format!("capacitor::{}", name),
+                value: value.to_string(),
+                package: package.to_string(),
+                footprint: library["footprint"].as_str().unwrap_or_default().to_string(),
+                tolerance: library["tolerance"].as_str().unwrap_or_default().to_string(),
+                voltage: library["voltage_rating"].as_str().unwrap_or_default().to_string(),
+                ..Default::default()
+            };
+            if let Some(part) = part {
+                row.manufacturer = part.manufacturer.to_string();
+                row.mpn = part.mpn;
+                row.lcsc = part.sku.unwrap_or_default().to_string();
+            }
+            row.price_from(&octopart);
+            row
+        })
+        .collect();
+    component::order::sort(&mut rows, |row| (row.part.as_str(), row.value.as_str()));
+    rows
+}
+
+/// Parts of a registered family's library
+fn family_rows(category: &str, name: &str, library: &serde_json::Value) -> Vec<BomRow> {
+    let octopart = octopart_data(library);
+    let mut rows: Vec<BomRow> = library["parts"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|part| {
+            let text = |key: &str| part[key].as_str().unwrap_or_default().to_string();
+            let mut row = BomRow {
+                part: text("name"),
+                library: format!("{}::{}", category, name),
+                value: text("value"),
+                package: library["package"].as_str().unwrap_or_default().to_string(),
+                footprint: library["footprint"].as_str().unwrap_or_default().to_string(),
+                manufacturer: text("manufacturer"),
+                mpn: text("mpn"),
+                ..Default::default()
+            };
+            row.price_from(&octopart);
+            row
+        })
+        .collect();
+    component::order::sort(&mut rows, |row| (row.part.as_str(), row.value.as_str()));
+    rows
+}
+
+/// Every part of the resistor, capacitor and family libraries, by library,
+/// built from the library files
+pub(crate) fn bom_rows(data_dir: &Path) -> Result<Vec<BomRow>, String> {
+    let source_dir = data_dir.join("libraries");
+    let manifest = load_manifest(data_dir)?;
+    let families: Vec<String> = component::family::families().iter().map(|f| f.name().to_string()).collect();
+
+    let mut rows = Vec::new();
+    let categories = ["resistor", "capacitor"].into_iter().chain(families.iter().map(String::as_str));
+    for category in categories {
+        let Some(items) = manifest.libraries.get(category) else {
+            continue;
+        };
+        let mut libraries: Vec<(&String, &String)> = items.iter().collect();
+        libraries.sort_by_key(|(_, path)| *path);
+        for (name, path) in libraries {
+            let content = fs::read_to_string(source_dir.join(path))
+                .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            let library: serde_json::Value = serde_json::from_str(&content)
+                .map_err(|e| format!("Failed to parse {}: {}", path, e))?;
+            match category {
+                "resistor" => rows.extend(resistor_rows(name, &library)?),
+                "capacitor" => rows.extend(capacitor_rows(name, &library)),
+                _ => rows.extend(family_rows(category, name, &library)),
+            }
+        }
+    }
+    Ok(rows)
+}
+
+/// Write the BOM of every generated library in `format` (csv, xlsx or kicad)
+pub fn export(data_dir: &Path, format: &str, output: Option<&Path>) -> Result<(), String> {
+    let format = BomFormat::parse(format)?;
+    let output_dir = output.unwrap_or_else(|| Path::new("./bom"));
+    println!("Exporting bill of materials...");
+    println!("Output directory: {}", output_dir.display());
+
+    let mut rows = super::parts_db::rows(data_dir)?;
+    for row in &mut rows {
+        let category = row.library.split("::").next().unwrap_or_default();
+        row.ipn = component::ipn::assign(category, &row.package, &row.part, &row.tolerance).unwrap_or_default();
+    }
+    let limits = component::limits::limits("bom");
+    let content = match format {
+        BomFormat::Csv => csv(&rows, &limits).into_bytes(),
+        BomFormat::Kicad => kicad_csv(&rows, &limits).into_bytes(),
+        BomFormat::Xlsx => xlsx(&rows, &limits)?,
+    };
+
+    component::atomic::create_dir_all(output_dir)?;
+    let path = output_dir.join(format.file_name());
+    component::atomic::write(&path, content)?;
+
+    let priced = rows.iter().filter(|row| row.unit_price.is_some()).count();
+    println!();
+    println!("Wrote {} parts ({} with prices) to: {}", rows.len(), priced, path.display());
+    if priced == 0 && !rows.is_empty() {
+        println!("Run 'aeda enrich <library>' to add Octopart prices.");
+    }
+    super::limits::report();
+    super::export::write_usage_notes(data_dir, output_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resistor_rows_carry_sources_and_prices() {
+        let library = serde_json::json!({
+            "package": "0603",
+            "footprint": "Resistor_SMD:R_0603_1608Metric",
+            "tolerance": "1%",
+            "power_rating": "1/10W",
+            "manufacturer": "Vishay",
+            "alternate_manufacturers": ["Yageo"],
+            "base_values": [4.99],
+            "multipliers": { "K": 1000.0 },
+            "octopart": { "CRCW06034K99FKEA": {
+                "mpn": "CRCW06034K99FKEA",
+                "manufacturer": "Vishay",
+                "offers": [{ "distributor": "Mouser", "sku": "71-CRCW0603-4.99K-E3", "prices": [
+                    { "quantity": 10, "price": 0.05, "currency": "USD" },
+                    { "quantity": 1, "price": 0.1, "currency": "USD" }
+                ]}],
+                "fetched": "2026-01-01T00:00:00Z"
+            }}
+        });
+        let rows = resistor_rows("E96_0603", &library).unwrap();
+        assert_eq!(rows.len(), 1);
+        let row = &rows[0];
+        assert_eq!((row.value.as_str(), row.power.as_str()), ("4.99K", "1/10W"));
+        assert_eq!(row.mpn, "CRCW06034K99FKEA");
+        assert_eq!(row.alternates, ["Yageo RC0603FR-074K99L"]);
+        assert_eq!(row.unit_price, Some(0.1));
+
+        let limits = Limits::default();
+        let csv = csv(&rows, &limits);
+        assert_eq!(csv.lines().count(), 2);
+        assert!(csv.lines().nth(1).unwrap().ends_with(",0.1,USD"), "{}", csv);
+        assert_eq!(csv_field("RES 0603 4.99K, 1%"), "\"RES 0603 4.99K, 1%\"");
+        assert!(kicad_csv(&rows, &limits).lines().nth(1).unwrap().starts_with("\"4.99K\",\"Resistor_SMD:R_0603_1608Metric\""));
+        assert!(xlsx(&rows, &limits).unwrap().starts_with(b"PK"));
+    }
+}
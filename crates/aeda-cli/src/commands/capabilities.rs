@@ -0,0 +1,40 @@
+//! Show what this build can generate
+//!
+//! `aeda capabilities` lists the packages, E-series, manufacturers, exporters
+//! and KiCad versions of [`component::capabilities`], with the packages and
+//! manufacturers configured in config.toml. Front ends read them with
+//! `aeda --json capabilities` to fill their pickers.
+
+use super::output;
+
+pub fn run() -> Result<(), String> {
+    let capabilities = component::capabilities();
+    if output::json() {
+        return output::print(&capabilities);
+    }
+
+    println!("Packages:");
+    for package in &capabilities.packages {
+        let spec = &package.spec;
+        let size = format!("{} x {} mm", spec.body_length, spec.body_width);
+        let power = package.power.map(|power| power.to_string()).unwrap_or_default();
+        println!("  {:<8} {:<12} {:<14} {}", spec.imperial, spec.metric, size, power);
+    }
+    println!("\nE-series:");
+    for series in &capabilities.series {
+        println!("  {:<6} {}", series.name, series.tolerance);
+    }
+    println!("\nManufacturers:");
+    for manufacturer in &capabilities.manufacturers {
+        println!("  {}", manufacturer);
+    }
+    println!("\nExporters:");
+    for exporter in &capabilities.exporters {
+        println!("  {:<10} {}", exporter.name, exporter.description);
+    }
+    println!("\nKiCad versions:");
+    for version in &capabilities.kicad_versions {
+        println!("  KiCad {} (format {})", version.major, version.format);
+    }
+    Ok(())
+}
@@ -1,8 +1,46 @@
 //! Show configuration and paths
 
+use super::output;
 use std::path::Path;
 
+/// Directories of the data directory, with what they hold
+const DIRECTORIES: [(&str, &str); 5] = [
+    ("libraries/", "Component library manifests (JSON)"),
+    ("footprints/", "KiCad footprint files (.kicad_mod)"),
+    ("symbols/", "KiCad symbol files (.kicad_sym)"),
+    ("3d_models/", "3D models (STEP, WRL)"),
+    ("cache/", "Downloaded/temporary files"),
+];
+
+fn json(data_dir: &Path) -> Result<(), String> {
+    let config_path = data_dir.join("config.toml");
+    let manifest_path = data_dir.join("libraries/manifest.json");
+    let (profiles, warnings) = match super::profile::names(data_dir) {
+        Ok(names) => (names, Vec::new()),
+        Err(e) => (Vec::new(), vec![e]),
+    };
+    let directories: Vec<serde_json::Value> = DIRECTORIES
+        .iter()
+        .map(|(dir, description)| {
+            let path = data_dir.join(dir);
+            serde_json::json!({ "path": path, "description": description, "exists": path.exists() })
+        })
+        .collect();
+    output::print(&serde_json::json!({
+        "data_dir": data_dir,
+        "directories": directories,
+        "config": { "path": config_path, "exists": config_path.exists() },
+        "manifest": { "path": manifest_path, "exists": manifest_path.exists() },
+        "profiles": profiles,
+        "home": std::env::var("HOME").ok(),
+        "warnings": warnings,
+    }))
+}
+
 pub fn run(data_dir: &Path) -> Result<(), String> {
+    if output::json() {
+        return json(data_dir);
+    }
     println!("Atlantix EDA Configuration");
     println!("==========================\n");
 
@@ -10,15 +48,7 @@ pub fn run(data_dir: &Path) -> Result<(), String> {
     println!();
 
     println!("Directory structure:");
-    let dirs = [
-        ("libraries/", "Component library manifests (JSON)"),
-        ("footprints/", "KiCad footprint files (.kicad_mod)"),
-        ("symbols/", "KiCad symbol files (.kicad_sym)"),
-        ("3d_models/", "3D models (STEP, WRL)"),
-        ("cache/", "Downloaded/temporary files"),
-    ];
-
-    for (dir, desc) in &dirs {
+    for (dir, desc) in &DIRECTORIES {
         let path = data_dir.join(dir);
         let status = if path.exists() { "✓" } else { "✗" };
         println!("  {} {} - {}", status, dir, desc);
@@ -34,6 +64,12 @@ pub fn run(data_dir: &Path) -> Result<(), String> {
         println!("Config file: {} (not found - run 'aeda init')", config_path.display());
     }
 
+    match super::profile::names(data_dir) {
+        Ok(names) if names.is_empty() => {}
+        Ok(names) => println!("Profiles: {}", names.join(", ")),
+        Err(e) => println!("Profiles: {}", e),
+    }
+
     // Check manifest
     let manifest_path = data_dir.join("libraries/manifest.json");
     if manifest_path.exists() {
@@ -0,0 +1,59 @@
+//! Library health overview of a data directory
+//!
+//! The terminal report of [`component::dashboard::summarize`], which the
+//! GUI's Dashboard tab shows as well.
+
+use component::dashboard::{self, STALE_DAYS};
+use std::path::Path;
+
+pub fn run(data_dir: &Path) -> Result<(), String> {
+    let summary = dashboard::summarize(data_dir).map_err(|e| e.to_string())?;
+
+    println!("{} {} ({})", summary.name, summary.version, data_dir.display());
+    match summary.last_generated {
+        Some(time) => println!("Last generated: {}", time.format("%Y-%m-%d %H:%M")),
+        None => println!("Last generated: never (run 'aeda generate')"),
+    }
+    println!();
+
+    println!("Libraries:");
+    for (category, entry) in &summary.categories {
+        let statuses: Vec<String> = entry.by_status.iter().map(|(status, n)| format!("{} {}", n, status)).collect();
+        if entry.libraries == 0 {
+            println!("  {:<12} none", category);
+        } else {
+            println!("  {:<12} {:>3} ({})", category, entry.libraries, statuses.join(", "));
+        }
+    }
+    println!();
+
+    let missing = summary.missing();
+    println!("Validation:");
+    if missing.is_empty() {
+        println!("  All manifest entries present on disk");
+    } else {
+        println!("  Missing library files: {}", missing.join(", "));
+    }
+    println!("  Awaiting review: {} draft libraries", summary.drafts());
+    println!();
+
+    println!("Distributor data (Octopart cache):");
+    match summary.oldest_fetch {
+        Some(oldest) => {
+            println!("  {} parts cached, oldest fetched {}", summary.cached_parts, oldest.format("%Y-%m-%d"));
+            println!("  {} older than {} days (refresh with 'aeda enrich')", summary.stale_parts, STALE_DAYS);
+        }
+        None => println!("  No cached data"),
+    }
+    println!();
+
+    let pending = summary.pending();
+    println!("Pending part requests: {}", pending);
+    if pending > 0 {
+        println!("  See 'aeda request list'");
+    }
+    if !summary.locked.is_empty() {
+        println!("Locked categories: {} (clear stale locks with 'aeda unlock')", summary.locked.join(", "));
+    }
+    Ok(())
+}
@@ -0,0 +1,68 @@
+//! Datasheet links from config.toml
+//!
+//! Symbols and Altium CSV rows link the datasheet of the part's series. The
+//! built-in links are overridden or extended per manufacturer, or per
+//! manufacturer and part number prefix, with `{mpn}` standing for the part
+//! number:
+//!
+//! ```toml
+//! [datasheets]
+//! Yageo = "https://www.yageo.com/en/Chart/Download/pdf/{mpn}"
+//! "Vishay/TNPW" = "https://www.vishay.com/docs/28758/tnpw_e3.pdf"
+//! ```
+
+use component::settings::Settings;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// URL templates of a `[datasheets]` table
+fn parse(section: &toml::Value) -> Result<HashMap<String, String>, String> {
+    let section = section.as_table().ok_or("[datasheets] must be a table")?;
+    section
+        .iter()
+        .map(|(key, value)| match value.as_str() {
+            Some(template) => Ok((key.clone(), template.to_string())),
+            None => Err(format!("Datasheet link of '{}' must be a string", key)),
+        })
+        .collect()
+}
+
+/// Take the `[datasheets]` links of config.toml, if any, into `settings`
+pub fn install(data_dir: &Path, settings: &mut Settings) -> Result<(), String> {
+    let config_path = data_dir.join("config.toml");
+    let Ok(content) = fs::read_to_string(&config_path) else {
+        return Ok(());
+    };
+    let config: toml::Table = content
+        .parse()
+        .map_err(|e| format!("Failed to parse {}: {}", config_path.display(), e))?;
+    let Some(section) = config.get("datasheets") else {
+        return Ok(());
+    };
+    let templates = parse(section).map_err(|e| format!("{} in {}", e, config_path.display()))?;
+    settings.datasheets = component::datasheet::templates(templates);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn templates_are_keyed_by_manufacturer_and_prefix() {
+        let config: toml::Table = r#"
+            [datasheets]
+            Yageo = "https://example.com/{mpn}"
+            "Vishay/TNPW" = "https://example.com/tnpw.pdf"
+        "#
+        .parse()
+        .unwrap();
+        let templates = parse(&config["datasheets"]).unwrap();
+        assert_eq!(templates["Yageo"], "https://example.com/{mpn}");
+        assert_eq!(templates["Vishay/TNPW"], "https://example.com/tnpw.pdf");
+
+        let number: toml::Table = "[datasheets]\nYageo = 3".parse().unwrap();
+        assert!(parse(&number["datasheets"]).unwrap_err().contains("Yageo"));
+    }
+}
@@ -0,0 +1,231 @@
+//! Compare two generations of a library
+//!
+//! `aeda diff <old> <new>` reports the parts added, removed and changed
+//! between two KiCad symbol libraries, two directories of them, or two data
+//! directories, so a regenerated library can be reviewed before it replaces
+//! one that active projects use. Symbols are compared field by field, with
+//! their graphics and pins as a whole; data directories compare the BOM
+//! fields of every part their library files generate. To check a library
+//! against fresh output, generate into a scratch directory and diff that.
+
+use super::bom::{self, BomRow};
+use kiparse::sexpr::{parse_sexpr, SExpr};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Fields of one part, and the rest of its symbol
+#[derive(Debug, Default, PartialEq)]
+struct Part {
+    fields: BTreeMap<String, String>,
+    /// Graphics and pins, for symbols
+    body: Option<SExpr>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct FieldChange {
+    field: String,
+    old: Option<String>,
+    new: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Changed {
+    part: String,
+    fields: Vec<FieldChange>,
+    /// Graphics or pins differ
+    body: bool,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct Diff {
+    added: Vec<String>,
+    removed: Vec<String>,
+    changed: Vec<Changed>,
+    unchanged: usize,
+}
+
+impl Diff {
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+fn compare(old: &BTreeMap<String, Part>, new: &BTreeMap<String, Part>) -> Diff {
+    let mut diff = Diff {
+        added: new.keys().filter(|name| !old.contains_key(*name)).cloned().collect(),
+        removed: old.keys().filter(|name| !new.contains_key(*name)).cloned().collect(),
+        ..Default::default()
+    };
+    for (name, old_part) in old {
+        let Some(new_part) = new.get(name) else {
+            continue;
+        };
+        let mut fields: Vec<&String> = old_part.fields.keys().chain(new_part.fields.keys()).collect();
+        fields.sort();
+        fields.dedup();
+        let changes: Vec<FieldChange> = fields
+            .into_iter()
+            .filter(|field| old_part.fields.get(*field) != new_part.fields.get(*field))
+            .map(|field| FieldChange {
+                field: field.clone(),
+                old: old_part.fields.get(field).cloned(),
+                new: new_part.fields.get(field).cloned(),
+            })
+            .collect();
+        let body = old_part.body != new_part.body;
+        if changes.is_empty() && !body {
+            diff.unchanged += 1;
+        } else {
+            diff.changed.push(Changed { part: name.clone(), fields: changes, body });
+        }
+    }
+    diff
+}
+
+/// Symbols of a parsed library by name, with `prefix` before each name
+fn symbols(library: &SExpr, prefix: &str, parts: &mut BTreeMap<String, Part>) {
+    for symbol in library.find_all("symbol") {
+        let Some(name) = symbol.value(1) else {
+            continue;
+        };
+        let fields = symbol
+            .find_all("property")
+            .filter_map(|property| Some((property.value(1)?.to_string(), property.value(2)?.to_string())))
+            .collect();
+        let mut body = symbol.clone();
+        if let Some(items) = body.items_mut() {
+            items.retain(|item| item.head() != Some("property"));
+        }
+        parts.insert(format!("{}{}", prefix, name), Part { fields, body: Some(body) });
+    }
+}
+
+fn read_symbols(path: &Path, prefix: &str, parts: &mut BTreeMap<String, Part>) -> Result<(), String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let library = parse_sexpr(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    symbols(&library, prefix, parts);
+    Ok(())
+}
+
+/// Parts of a data directory, keyed by library and part
+fn data_dir_parts(data_dir: &Path) -> Result<BTreeMap<String, Part>, String> {
+    let rows: Vec<BomRow> = bom::bom_rows(data_dir)?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let fields = bom::HEADER.iter().zip(row.fields()).skip(1).map(|(column, field)| (column.to_string(), field)).collect();
+            (format!("{}:{}", row.library, row.part), Part { fields, body: None })
+        })
+        .collect())
+}
+
+/// Parts of a symbol library, a directory of them, or a data directory
+fn load(path: &Path) -> Result<BTreeMap<String, Part>, String> {
+    if path.join("libraries/manifest.json").exists() {
+        return data_dir_parts(path);
+    }
+    let mut parts = BTreeMap::new();
+    if path.is_dir() {
+        let mut files: Vec<PathBuf> = Vec::new();
+        super::report::symbol_files(path, &mut files);
+        if files.is_empty() {
+            return Err(format!("No symbol libraries or libraries/manifest.json in {}", path.display()));
+        }
+        // Symbols are told apart by library nickname, as KiCad does
+        for file in files {
+            let nickname = file.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            read_symbols(&file, &format!("{}:", nickname), &mut parts)?;
+        }
+    } else {
+        read_symbols(path, "", &mut parts)?;
+    }
+    Ok(parts)
+}
+
+fn print(diff: &Diff) {
+    for name in &diff.added {
+        println!("+ {}", name);
+    }
+    for name in &diff.removed {
+        println!("- {}", name);
+    }
+    for changed in &diff.changed {
+        println!("~ {}", changed.part);
+        for change in &changed.fields {
+            println!(
+                "    {}: {} -> {}",
+                change.field,
+                change.old.as_deref().unwrap_or("(none)"),
+                change.new.as_deref().unwrap_or("(none)")
+            );
+        }
+        if changed.body {
+            println!("    graphics or pins changed");
+        }
+    }
+    if !diff.is_empty() {
+        println!();
+    }
+    println!(
+        "{} added, {} removed, {} changed, {} unchanged",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.changed.len(),
+        diff.unchanged
+    );
+}
+
+pub fn run(old: &Path, new: &Path, json: bool) -> Result<(), String> {
+    let old_is_data_dir = old.join("libraries/manifest.json").exists();
+    if old_is_data_dir != new.join("libraries/manifest.json").exists() {
+        return Err("Compare two data directories, or two symbol libraries or directories of them".to_string());
+    }
+    let diff = compare(&load(old)?, &load(new)?);
+    if json {
+        let json = serde_json::to_string_pretty(&diff).map_err(|e| format!("Failed to serialize diff: {}", e))?;
+        println!("{}", json);
+    } else {
+        println!("Comparing {} -> {}\n", old.display(), new.display());
+        print(&diff);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbols_differ_by_field_and_body() {
+        let old = parse_sexpr(
+            r#"(kicad_symbol_lib (version 20211014)
+                (symbol "R1" (property "MPN" "A") (property "Value" "1k") (symbol "R1_0_1" (rectangle)))
+                (symbol "R2" (property "MPN" "B"))
+                (symbol "R3" (property "MPN" "C")))"#,
+        )
+        .unwrap();
+        let new = parse_sexpr(
+            r#"(kicad_symbol_lib (version 20211014)
+                (symbol "R1" (property "MPN" "A2") (property "Datasheet" "d.pdf") (property "Value" "1k") (symbol "R1_0_1" (polyline)))
+                (symbol "R3" (property "MPN" "C"))
+                (symbol "R4" (property "MPN" "D")))"#,
+        )
+        .unwrap();
+        let (mut old_parts, mut new_parts) = (BTreeMap::new(), BTreeMap::new());
+        symbols(&old, "", &mut old_parts);
+        symbols(&new, "", &mut new_parts);
+
+        let diff = compare(&old_parts, &new_parts);
+        assert_eq!(diff.added, ["R4"]);
+        assert_eq!(diff.removed, ["R2"]);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.changed.len(), 1);
+        let changed = &diff.changed[0];
+        assert!(changed.body);
+        let fields: Vec<(&str, Option<&str>, Option<&str>)> =
+            changed.fields.iter().map(|c| (c.field.as_str(), c.old.as_deref(), c.new.as_deref())).collect();
+        assert_eq!(fields, [("Datasheet", None, Some("d.pdf")), ("MPN", Some("A"), Some("A2"))]);
+    }
+}
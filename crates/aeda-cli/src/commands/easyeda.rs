@@ -0,0 +1,214 @@
+//! EasyEDA library export
+//!
+//! Writes one EasyEDA (Standard) JSON document per symbol and one per
+//! footprint, the format EasyEDA Pro imports as well. Coordinates are in
+//! EasyEDA's 10 mil units with y pointing down. Shapes are `~` separated
+//! records, see the EasyEDA document format description.
+
+use super::export::{part_rows, PartRow};
+use component::kicad_footprint::{get_package_specs, PackageSpec};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const EDITOR_VERSION: &str = "6.5.22";
+const CANVAS: &str = "CA~1000~1000~#FFFFFF~yes~#CCCCCC~5~1000~1000~line~5~pixel~5~400~300";
+const SYMBOL_COLOR: &str = "#A00000";
+
+/// Layer ids of the footprint editor
+const TOP_LAYER: u32 = 1;
+const TOP_SILK_LAYER: u32 = 3;
+const COMPONENT_SHAPE_LAYER: u32 = 99;
+
+/// Millimetres to EasyEDA units
+fn units(mm: f64) -> f64 {
+    (mm / 0.254 * 1000.0).round() / 1000.0
+}
+
+/// Footprint title of a chip package, e.g. `R0603`
+fn footprint_title(prefix: &str, package: &str) -> String {
+    format!("{}{}", prefix, package)
+}
+
+/// Pin `number` at (400, `y`), reaching 10 units towards the body
+fn pin(number: &str, y: i32, id: usize) -> String {
+    let (path, rotation) = if y < 300 { ("v 10", 90) } else { ("v -10", 270) };
+    format!(
+        "P~show~0~{n}~400~{y}~{r}~gge{id}~0^^400~{y}^^M 400 {y} {path}~{c}^^0~404~{y}~0~{n}~start~~~{c}^^0~404~{y}~0~{n}~start~~~{c}^^0~400~{y}^^0~",
+        n = number,
+        y = y,
+        r = rotation,
+        id = id,
+        path = path,
+        c = SYMBOL_COLOR
+    )
+}
+
+/// Symbol document of one part. The LCSC supplier fields are only set when
+/// `lcsc` is true and the part has an LCSC source.
+pub(crate) fn symbol(row: &PartRow, lcsc: bool) -> Value {
+    let body = match row.prefix.as_str() {
+        // Two plates
+        "C" => vec![
+            format!("PL~392 298 408 298~{}~1~0~none~gge1~0", SYMBOL_COLOR),
+            format!("PL~392 302 408 302~{}~1~0~none~gge2~0", SYMBOL_COLOR),
+            format!("PL~400 290 400 298~{}~1~0~none~gge3~0", SYMBOL_COLOR),
+            format!("PL~400 302 400 310~{}~1~0~none~gge4~0", SYMBOL_COLOR),
+        ],
+        // IEC rectangle, the same body the KiCad symbols use
+        _ => vec![format!("R~396~290~~~8~20~{}~1~0~none~gge1~0~", SYMBOL_COLOR)],
+    };
+    let mut shape = body;
+    shape.push(pin("1", 280, 10));
+    shape.push(pin("2", 320, 11));
+
+    let mut c_para = BTreeMap::new();
+    c_para.insert("pre", format!("{}?", row.prefix));
+    c_para.insert("name", row.comment.clone());
+    c_para.insert("package", footprint_title(&row.prefix, &row.package));
+    if let (true, Some(part)) = (lcsc, &row.part) {
+        c_para.insert("Manufacturer", part.manufacturer.to_string());
+        c_para.insert("Manufacturer Part", part.mpn.clone());
+        c_para.insert("Supplier", "LCSC".to_string());
+        c_para.insert("Supplier Part", part.sku.unwrap_or_default().to_string());
+        c_para.insert("JLCPCB Part Class", if part.basic { "Basic Part" } else { "Extended Part" }.to_string());
+    }
+
+    json!({
+        "docType": "2",
+        "editorVersion": EDITOR_VERSION,
+        "title": row.name,
+        "description": "",
+        "head": { "docType": "2", "x": "400", "y": "300", "c_para": c_para },
+        "canvas": CANVAS,
+        "shape": shape,
+    })
+}
+
+/// Footprint document of a two-terminal chip package
+pub(crate) fn footprint(title: &str, prefix: &str, spec: &PackageSpec) -> Value {
+    let (w, h) = (units(spec.pad_width), units(spec.pad_height));
+    let pads = [("1", -spec.pad_center_x), ("2", spec.pad_center_x)].map(|(number, x)| {
+        let x = units(x);
+        let corners = [(x - w / 2.0, -h / 2.0), (x + w / 2.0, -h / 2.0), (x + w / 2.0, h / 2.0), (x - w / 2.0, h / 2.0)]
+            .iter()
+            .map(|(x, y)| format!("{} {}", x + 400.0, y + 300.0))
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!(
+            "PAD~RECT~{}~300~{}~{}~{}~~{}~0~{}~0~gge{}~0~~Y~0~0~0~",
+            x + 400.0,
+            w,
+            h,
+            TOP_LAYER,
+            number,
+            corners,
+            number
+        )
+    });
+    let (bl, bw) = (units(spec.body_length) / 2.0, units(spec.body_width) / 2.0);
+    let outline = format!(
+        "TRACK~0.5~{}~~{x0} {y0} {x1} {y0} {x1} {y1} {x0} {y1} {x0} {y0}~gge3~0",
+        COMPONENT_SHAPE_LAYER,
+        x0 = 400.0 - bl,
+        x1 = 400.0 + bl,
+        y0 = 300.0 - bw,
+        y1 = 300.0 + bw
+    );
+    // Silkscreen bars along the long sides, clear of the pads
+    let silk_x = (units(spec.pad_center_x) - w / 2.0 - 1.0).max(0.0);
+    let silk = [(-(bw + 1.0), 4), (bw + 1.0, 5)].map(|(y, id)| {
+        format!("TRACK~0.6~{}~~{} {} {} {}~gge{}~0", TOP_SILK_LAYER, 400.0 - silk_x, 300.0 + y, 400.0 + silk_x, 300.0 + y, id)
+    });
+
+    let mut shape: Vec<String> = pads.to_vec();
+    shape.push(outline);
+    if silk_x > 0.0 {
+        shape.extend(silk);
+    }
+    json!({
+        "docType": "4",
+        "editorVersion": EDITOR_VERSION,
+        "title": title,
+        "description": format!("{} {} ({})", prefix, spec.imperial, spec.metric),
+        "head": { "docType": "4", "x": "400", "y": "300", "c_para": { "pre": format!("{}?", prefix), "package": title } },
+        "canvas": CANVAS,
+        "shape": shape,
+    })
+}
+
+fn write_json(path: &Path, document: &Value) -> Result<(), String> {
+    let content = serde_json::to_string(document).map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+    Ok(component::atomic::write(path, content)?)
+}
+
+/// Write EasyEDA symbols and footprints for the resistor and capacitor libraries
+pub fn export(data_dir: &Path, output_dir: &Path, lcsc: bool) -> Result<(), String> {
+    let rows = part_rows(data_dir)?;
+    let symbols_dir = output_dir.join("symbols");
+    let footprints_dir = output_dir.join("footprints");
+    for dir in [&symbols_dir, &footprints_dir] {
+        component::atomic::create_dir_all(dir)?;
+    }
+
+    let mut footprints = BTreeMap::new();
+    for row in &rows {
+        write_json(&symbols_dir.join(format!("{}.json", row.name)), &symbol(row, lcsc))?;
+        footprints.entry(footprint_title(&row.prefix, &row.package)).or_insert((row.prefix.as_str(), &row.package));
+    }
+    let mut missing = Vec::new();
+    for (title, (prefix, package)) in &footprints {
+        let Some(spec) = get_package_specs(package) else {
+            missing.push(title.as_str());
+            continue;
+        };
+        write_json(&footprints_dir.join(format!("{}.json", title)), &footprint(title, prefix, &spec))?;
+    }
+
+    println!();
+    println!("Wrote {} symbols to: {}", rows.len(), symbols_dir.display());
+    println!("Wrote {} footprints to: {}", footprints.len() - missing.len(), footprints_dir.display());
+    if !missing.is_empty() {
+        println!("No land pattern for {}, those symbols reference a footprint that was not written", missing.join(", "));
+    }
+    if lcsc {
+        let sourced = rows.iter().filter(|row| row.part.is_some()).count();
+        println!("LCSC supplier fields on {} of {} parts", sourced, rows.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbol_carries_lcsc_fields_only_when_enabled() {
+        let row = PartRow {
+            name: "R0603_10.0K".into(),
+            prefix: "R".into(),
+            package: "0603".into(),
+            comment: "10.0K".into(),
+            footprint: String::new(),
+            part: component::lcsc::resistor("0603", component::Resistance::from_ohms(10_000.0)),
+        };
+        let with_lcsc = symbol(&row, true);
+        assert_eq!(with_lcsc["head"]["c_para"]["Supplier Part"], "C25804");
+        assert_eq!(with_lcsc["head"]["c_para"]["package"], "R0603");
+        assert!(symbol(&row, false)["head"]["c_para"].get("Supplier").is_none());
+
+        let spec = get_package_specs("0603").unwrap();
+        let footprint = footprint("R0603", "R", &spec);
+        let pads: Vec<&str> = footprint["shape"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|shape| shape.as_str())
+            .filter(|shape| shape.starts_with("PAD~"))
+            .collect();
+        assert_eq!(pads.len(), 2);
+        // Pad 2 sits right of the origin by the pad centre distance
+        let x: f64 = pads[1].split('~').nth(2).unwrap().parse().unwrap();
+        assert!((x - (400.0 + units(spec.pad_center_x))).abs() < 1e-9);
+    }
+}
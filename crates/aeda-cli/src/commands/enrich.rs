@@ -0,0 +1,258 @@
+//! Enrich libraries with Octopart data
+//!
+//! Collects the MPNs of a library (every value of a resistor series, plus
+//! the MPN property of any symbol files given), looks them up through
+//! Octopart and records offers, datasheets and lifecycle status in the
+//! library JSON under `octopart`. Symbol files get their Datasheet filled in
+//! and a Lifecycle property, and SupplierURL pointed at the product page when
+//! config.toml sets `[distributors] supplier_url = "product"`.
+
+use super::generate::load_manifest;
+use super::octopart::{self, Nexar, PartInfo};
+use component::distributor::Distributor;
+use component::settings::Settings;
+use kiparse::sexpr::{parse_sexpr, SExpr};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn run(data_dir: &Path, library: &str, symbols: &[PathBuf], max_age_days: i64) -> Result<(), String> {
+    let (category, name) = library.split_once("::").ok_or_else(|| {
+        format!(
+            "Invalid library path '{}'. Expected format: category::name (e.g., resistor::E96_0603)",
+            library
+        )
+    })?;
+    let manifest = load_manifest(data_dir)?;
+    let relative = [&manifest.libraries, &manifest.pending]
+        .iter()
+        .find_map(|map| map.get(category).and_then(|items| items.get(name)))
+        .ok_or_else(|| format!("Library not found in manifest: {}", library))?;
+    let library_path = data_dir.join("libraries").join(relative);
+    let content = fs::read_to_string(&library_path)
+        .map_err(|e| format!("Failed to read {}: {}", library_path.display(), e))?;
+    let mut json: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", library_path.display(), e))?;
+
+    let mut symbol_libs = Vec::new();
+    for path in symbols {
+        let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let parsed = parse_sexpr(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        symbol_libs.push((path, content, parsed));
+    }
+
+    let mut mpns = BTreeSet::new();
+    // Each series of a combined library in its own tolerance
+    let series = if category == "resistor" { super::export::resistor_series(&json) } else { Vec::new() };
+    for json in &series {
+        let package = json["package"].as_str().unwrap_or_default().to_string();
+        let tolerance = json["tolerance"].as_str().unwrap_or("1%").to_string();
+        let automotive = json["automotive"].as_bool().unwrap_or(false);
+        let mut manufacturers = vec![json["manufacturer"].as_str().unwrap_or("Vishay").parse::<component::Manufacturer>()?];
+        for alternate in json["alternate_manufacturers"].as_array().into_iter().flatten() {
+            manufacturers.push(alternate.as_str().unwrap_or_default().parse()?);
+        }
+        for resistance in super::export::library_resistances(json) {
+            // Same part numbers as the generated symbols
+            for manufacturer in &manufacturers {
+                if automotive {
+                    mpns.extend(manufacturer.aec_q200_mpn(&package, resistance, &tolerance));
+                } else {
+                    mpns.extend(manufacturer.resistor_mpn(&package, resistance, &tolerance));
+                }
+            }
+        }
+    }
+    for (_, _, parsed) in &symbol_libs {
+        for symbol in parsed.find_all("symbol") {
+            // Primary "MPN" and alternates "MPN 2", "MPN 3", ...
+            let mpn_properties = symbol.find_all("property").filter(|p| {
+                p.value(1).is_some_and(|name| name == "MPN" || name.starts_with("MPN "))
+            });
+            for mpn in mpn_properties.filter_map(|p| p.value(2)).filter(|mpn| !mpn.is_empty()) {
+                mpns.insert(mpn.to_string());
+            }
+        }
+    }
+    if mpns.is_empty() {
+        return Err(format!("No MPNs found for {}", library));
+    }
+
+    println!("Enriching {} ({} MPNs)...", library, mpns.len());
+    let max_age = chrono::Duration::days(max_age_days);
+    let mut found: BTreeMap<String, PartInfo> = BTreeMap::new();
+    let mut missing = Vec::new();
+    for mpn in &mpns {
+        match octopart::cached(data_dir, mpn, max_age) {
+            Some(info) => {
+                found.insert(mpn.clone(), info);
+            }
+            None => missing.push(mpn.clone()),
+        }
+    }
+    let from_cache = found.len();
+
+    if !missing.is_empty() {
+        match Nexar::connect()? {
+            Some(nexar) => {
+                for info in nexar.match_parts(&missing)? {
+                    octopart::store(data_dir, &info)?;
+                    found.insert(info.mpn.clone(), info);
+                }
+            }
+            None => println!(
+                "  {} MPNs are not cached. Set NEXAR_CLIENT_ID and NEXAR_CLIENT_SECRET to query Octopart.",
+                missing.len()
+            ),
+        }
+    }
+    found.retain(|_, info| info.manufacturer.is_some());
+    // The pages found now link the symbols below along with the cached ones
+    let mut settings = Settings::clone(&component::settings::current());
+    if octopart::product_urls_enabled(data_dir)? {
+        for info in found.values() {
+            octopart::register_product_urls(info, &mut settings.product_pages);
+        }
+    }
+
+    json["octopart"] = serde_json::to_value(&found).map_err(|e| format!("Failed to serialize Octopart data: {}", e))?;
+    let content = serde_json::to_string_pretty(&json)
+        .map_err(|e| format!("Failed to serialize library: {}", e))?;
+    component::atomic::write(&library_path, content)?;
+
+    for (path, original, mut parsed) in symbol_libs {
+        let updated = settings.scope(|| apply_to_symbols(&mut parsed, &found));
+        let content = format!("{}\n", parsed.to_kicad_string());
+        if content != original {
+            component::atomic::write(path, content)?;
+        }
+        println!("  Updated {} symbols in {}", updated, path.display());
+    }
+
+    let obsolete = found
+        .values()
+        .filter(|info| info.lifecycle.as_deref().is_some_and(|l| l.eq_ignore_ascii_case("obsolete")))
+        .count();
+    println!(
+        "Octopart lists {} of {} MPNs ({} from cache){}",
+        found.len(),
+        mpns.len(),
+        from_cache,
+        if obsolete > 0 { format!(", {} obsolete", obsolete) } else { String::new() }
+    );
+    Ok(())
+}
+
+fn property<'a>(symbol: &'a SExpr, name: &str) -> Option<&'a SExpr> {
+    symbol.find_all("property").find(|p| p.value(1) == Some(name))
+}
+
+/// Fill in Datasheet and Lifecycle of every symbol with a known MPN, and
+/// SupplierURL when a product page is registered for its supplier part.
+/// Returns the number of symbols touched.
+fn apply_to_symbols(library: &mut SExpr, parts: &BTreeMap<String, PartInfo>) -> usize {
+    let mut updated = 0;
+    for symbol in library.items_mut().into_iter().flatten() {
+        if symbol.head() != Some("symbol") {
+            continue;
+        }
+        let Some(info) = property(symbol, "MPN").and_then(|p| p.value(2)).and_then(|mpn| parts.get(mpn)) else {
+            continue;
+        };
+        if let Some(url) = &info.datasheet {
+            set_property(symbol, "Datasheet", url);
+        }
+        if let Some(lifecycle) = &info.lifecycle {
+            set_property(symbol, "Lifecycle", lifecycle);
+        }
+        let distributor = property(symbol, "Supplier").and_then(|p| p.value(2)).and_then(|s| s.parse::<Distributor>().ok());
+        let product_url = distributor
+            .zip(property(symbol, "SupplierPN").and_then(|p| p.value(2)))
+            .and_then(|(distributor, part_number)| distributor.product_url(part_number));
+        if let Some(url) = product_url {
+            set_property(symbol, "SupplierURL", &url);
+        }
+        updated += 1;
+    }
+    updated
+}
+
+/// Set a property value, adding a hidden property after the last one when missing
+fn set_property(symbol: &mut SExpr, name: &str, value: &str) {
+    let items = symbol.items_mut().expect("symbol is a list");
+    // Strings keep their escapes, so backslashes go first
+    let value = SExpr::String(value.replace('\\', "\\\\").replace('"', "\\\""));
+    if let Some(existing) = items.iter_mut().find(|item| item.head() == Some("property") && item.value(1) == Some(name)) {
+        existing.set_value(2, value);
+        return;
+    }
+    let Some(last) = items.iter().rposition(|item| item.head() == Some("property")) else {
+        return;
+    };
+    // Copy the layout of the last property, giving it the next free id if the file uses ids
+    let next_id = items
+        .iter()
+        .filter_map(|item| item.find("id").and_then(|id| id.value(1)).and_then(|id| id.parse::<u32>().ok()))
+        .max()
+        .map(|id| id + 1);
+    let mut added = items[last].clone();
+    added.set_value(1, SExpr::String(name.to_string()));
+    added.set_value(2, value);
+    if let (Some(id), Some(slot)) = (next_id, added.find_mut("id")) {
+        slot.set_value(1, SExpr::Atom(id.to_string()));
+    }
+    items.insert(last + 1, added);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_datasheet_and_lifecycle() {
+        let mut library = parse_sexpr(
+            r#"(kicad_symbol_lib (version 20211014) (generator atlantix-eda)
+  (symbol "R0603_1.00K"
+    (property "Datasheet" "" (at 0 0 0) (effects (font (size 1.27 1.27)) hide))
+    (property "MPN" "CRCW06031K00FKEA" (at 0 0 0) (effects (font (size 1.27 1.27)) hide))
+  )
+  (symbol "R0603_1.05K"
+    (property "MPN" "CRCW06031K05FKEA" (at 0 0 0) (effects (font (size 1.27 1.27)) hide))
+  )
+)"#,
+        )
+        .unwrap();
+        let info = PartInfo {
+            mpn: "CRCW06031K00FKEA".into(),
+            manufacturer: Some("Vishay".into()),
+            datasheet: Some("https://www.vishay.com/docs/20035/dcrcwe3.pdf".into()),
+            lifecycle: Some("Production".into()),
+            offers: Vec::new(),
+            fetched: "2024-01-01T00:00:00+00:00".into(),
+        };
+        let parts = [(info.mpn.clone(), info)].into_iter().collect();
+
+        assert_eq!(apply_to_symbols(&mut library, &parts), 1);
+        let symbol = library.find("symbol").unwrap();
+        assert_eq!(
+            property(symbol, "Datasheet").and_then(|p| p.value(2)),
+            Some("https://www.vishay.com/docs/20035/dcrcwe3.pdf")
+        );
+        let lifecycle = property(symbol, "Lifecycle").unwrap();
+        assert_eq!(lifecycle.value(2), Some("Production"));
+        assert!(lifecycle.find("effects").is_some());
+    }
+
+    #[test]
+    fn escapes_property_values() {
+        let mut symbol = parse_sexpr(
+            r#"(symbol "R0603_1.00K" (property "MPN" "CRCW06031K00FKEA" (at 0 0 0) (effects (font (size 1.27 1.27)) hide)))"#,
+        )
+        .unwrap();
+        set_property(&mut symbol, "Description", r#"1K "thin film" C:\parts"#);
+        assert_eq!(
+            property(&symbol, "Description").and_then(|p| p.value(2)),
+            Some(r#"1K \"thin film\" C:\\parts"#)
+        );
+    }
+}
@@ -1,66 +1,472 @@
 //! Export libraries to different formats
 
-use std::path::Path;
+use super::generate::load_manifest;
+use super::readme::write_readmes;
+use component::package_naming;
+use component::settings::Settings;
+use component::PartStatus;
+use std::fs;
+use std::path::{Path, PathBuf};
 
-pub fn to_kicad(data_dir: &Path, output: Option<&Path>) -> Result<(), String> {
+/// Expand the placeholders of an export directory, from `--output` or the
+/// profile: `{format}`, `{profile}` (`default` without one) and the run's
+/// `{date}` and `{time}`, e.g. `exports/{date}/{format}`. Exports never
+/// write into the data directory's sources.
+pub(crate) fn output_dir(
+    data_dir: &Path,
+    output: Option<PathBuf>,
+    format: &str,
+    profile: Option<&str>,
+) -> Result<Option<PathBuf>, String> {
+    let Some(output) = output else {
+        return Ok(None);
+    };
+    let values = [("format", format), ("profile", profile.unwrap_or("default"))];
+    let output = PathBuf::from(component::output_path::expand(&output.to_string_lossy(), &values)?);
+    let target = absolute(&output);
+    for source in SOURCE_DIRS {
+        if target.starts_with(absolute(&data_dir.join(source))) {
+            return Err(format!(
+                "{} is inside the data directory's {}/, export somewhere else",
+                output.display(),
+                source
+            ));
+        }
+    }
+    Ok(Some(output))
+}
+
+/// Directories of the data directory that exports read from
+const SOURCE_DIRS: [&str; 5] = ["libraries", "symbols", "footprints", "altium", "requests"];
+
+/// `path` made absolute through its longest existing ancestor, so
+/// directories an export is about to create compare like existing ones
+fn absolute(path: &Path) -> PathBuf {
+    let mut missing = Vec::new();
+    let mut existing = path;
+    loop {
+        let base = if existing.as_os_str().is_empty() { Path::new(".") } else { existing };
+        if let Ok(resolved) = base.canonicalize() {
+            return missing.iter().rev().fold(resolved, |path, name| path.join(name));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+/// Write the usage notes, a README.md per library category, into an
+/// export directory
+pub(crate) fn write_usage_notes(data_dir: &Path, output_dir: &Path) -> Result<(), String> {
+    let manifest = load_manifest(data_dir)?;
+    let readmes = write_readmes(&data_dir.join("libraries"), output_dir, &manifest)?;
+    if readmes > 0 {
+        println!("Wrote usage notes (README.md) for {} categories", readmes);
+    }
+    Ok(())
+}
+
+/// Copy the generated KiCad symbols and footprints into `output`. An
+/// explicit `output` is a target library, registered in KiCad's global
+/// tables (or those in `kicad_config`) like `aeda register-lib-tables`.
+pub fn to_kicad(data_dir: &Path, output: Option<&Path>, kicad_config: Option<&Path>, on_collision: &str) -> Result<(), String> {
     let output_dir = output.unwrap_or_else(|| Path::new("./kicad_libs"));
 
     println!("Exporting to KiCad format...");
     println!("Output directory: {}", output_dir.display());
 
-    // TODO: Implement KiCad symbol and footprint generation
-    // This would use atlantix-core's KicadSymbol and KicadFootprint
-
-    println!();
-    println!("KiCad export not yet implemented.");
-    println!("Use atlantix-core directly for now:");
-    println!("  cargo run --example gen_kicad_resistor");
+    let copied = super::lib_table::copy_libraries(data_dir, output_dir)?;
+    if copied == 0 {
+        println!();
+        println!("No KiCad libraries found. Generate them first, e.g.:");
+        println!("  aeda generate leds --packages 0603");
+        println!("or request and approve a resistor:");
+        println!("  aeda request new --value 4.99k --package 0603");
+        println!("  aeda approve resistor::R0603_4.99K");
+        return Ok(());
+    }
+    println!("Copied {} files", copied);
+    write_usage_notes(data_dir, output_dir)?;
 
+    if output.is_some() {
+        // The tables need absolute paths
+        let target = output_dir
+            .canonicalize()
+            .map_err(|e| format!("Failed to resolve {}: {}", output_dir.display(), e))?;
+        println!();
+        super::lib_table::register(&target, kicad_config, on_collision)?;
+    }
     Ok(())
 }
 
-pub fn to_stencil(data_dir: &Path, output: Option<&Path>) -> Result<(), String> {
-    let default_output = data_dir.join("libraries");
-    let output_dir = output.unwrap_or(&default_output);
+/// Copy the Stencil libraries with a matching manifest and their usage
+/// notes, only those with review `status` when given
+pub fn to_stencil(data_dir: &Path, output: Option<&Path>, status: Option<&str>) -> Result<(), String> {
+    let status: Option<PartStatus> = status.map(str::parse).transpose()?;
+    let output_dir = output.unwrap_or_else(|| Path::new("./stencil_libs"));
+    let source_dir = data_dir.join("libraries");
+    let mut manifest = load_manifest(data_dir)?;
 
-    println!("Exporting to Stencil DSL format...");
+    match status {
+        Some(status) => println!("Exporting {} libraries to Stencil DSL format...", status),
+        None => println!("Exporting to Stencil DSL format..."),
+    }
     println!("Output directory: {}", output_dir.display());
 
-    // Stencil format is already the native format in data/libraries/
-    // This command just confirms the libraries are ready
-
-    let manifest_path = output_dir.join("manifest.json");
-    if manifest_path.exists() {
-        println!();
-        println!("Libraries already in Stencil format at: {}", output_dir.display());
-        println!();
-        println!("To use in Stencil Designer, ensure library_manager points to:");
-        println!("  {}", output_dir.display());
-        println!();
-        println!("Example usage in .stencil file:");
-        println!("  local r = library(\"resistor::E96_0603\")");
-        println!("  local r1 = r(\"10k\").at(10, 10).place()");
-    } else {
+    if manifest.libraries.values().all(|items| items.is_empty()) {
         println!();
         println!("No libraries found. Generate them first:");
         println!("  aeda generate resistors --series E96 --packages 0603,0805");
+        return Ok(());
+    }
+
+    component::atomic::create_dir_all(output_dir)?;
+
+    let mut exported = 0;
+    let mut libraries = std::mem::take(&mut manifest.libraries);
+    for (category, items) in libraries.iter_mut() {
+        items.retain(|name, _| status.is_none_or(|status| manifest.status_of(category, name) == status));
+        for path in items.values() {
+            let target = output_dir.join(path);
+            if let Some(parent) = target.parent() {
+                component::atomic::create_dir_all(parent)?;
+            }
+            let content = fs::read(source_dir.join(path))
+                .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            component::atomic::write(&target, content)?;
+            exported += 1;
+        }
+    }
+    manifest.libraries = libraries;
+    // Pending requests stay behind in the data directory
+    manifest.pending.clear();
+    if let Some(status) = status {
+        manifest.status.retain(|_, record| record.status == status);
     }
 
+    let manifest_path = output_dir.join("manifest.json");
+    let content = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    component::atomic::write(&manifest_path, content)?;
+    let readmes = write_readmes(&source_dir, output_dir, &manifest)?;
+
+    println!();
+    println!("Exported {} libraries to: {}", exported, output_dir.display());
+    println!("Wrote usage notes (README.md) for {} categories", readmes);
+    println!();
+    println!("To use in Stencil Designer, ensure library_manager points to:");
+    println!("  {}", output_dir.display());
+    println!();
+    println!("Example usage in .stencil file:");
+    println!("  local r = library(\"resistor::E96_0603\")");
+    println!("  local r1 = r(\"10k\").at(10, 10).place()");
+    Ok(())
+}
+
+/// Comment column of the Altium CSV rows from `altium.comment` in
+/// config.toml: `"=Value"`, the default, or `"value"` for the value itself
+pub fn install_altium(data_dir: &Path, settings: &mut Settings) -> Result<(), String> {
+    let config_path = data_dir.join("config.toml");
+    let Ok(content) = fs::read_to_string(&config_path) else {
+        return Ok(());
+    };
+    let config: toml::Table = content
+        .parse()
+        .map_err(|e| format!("Failed to parse {}: {}", config_path.display(), e))?;
+    let Some(comment) = config.get("altium").and_then(|a| a.get("comment")) else {
+        return Ok(());
+    };
+    let comment = comment
+        .as_str()
+        .ok_or_else(|| format!("altium.comment in {} must be a string", config_path.display()))?
+        .parse()
+        .map_err(|e| format!("altium.comment in {}: {}", config_path.display(), e))?;
+    settings.altium_comment = comment;
     Ok(())
 }
 
+/// Copy the Altium CSV libraries, such as approved part requests, with
+/// their usage notes
 pub fn to_altium(data_dir: &Path, output: Option<&Path>) -> Result<(), String> {
     let output_dir = output.unwrap_or_else(|| Path::new("./altium_libs"));
 
     println!("Exporting to Altium format...");
     println!("Output directory: {}", output_dir.display());
 
-    // TODO: Implement Altium export
-    // Would generate .SchLib and .PcbLib files
+    let files: Vec<PathBuf> = fs::read_dir(data_dir.join("altium"))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "csv"))
+        .collect();
+    if files.is_empty() {
+        println!();
+        println!("No Altium CSV libraries found. Request and approve a resistor first:");
+        println!("  aeda request new --value 4.99k --package 0603");
+        println!("  aeda approve resistor::R0603_4.99K");
+        return Ok(());
+    }
+
+    component::atomic::create_dir_all(output_dir)?;
+    for file in &files {
+        let content = fs::read(file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+        component::atomic::write(output_dir.join(file.file_name().unwrap_or_default()), content)?;
+    }
+    println!("Copied {} CSV libraries", files.len());
+    write_usage_notes(data_dir, output_dir)
+}
+
+/// Write EasyEDA symbols and footprints. With `lcsc` the symbols carry the
+/// LCSC supplier part fields used by JLCPCB assembly.
+pub fn to_easyeda(data_dir: &Path, output: Option<&Path>, lcsc: bool) -> Result<(), String> {
+    let output_dir = output.unwrap_or_else(|| Path::new("./easyeda"));
+
+    println!("Exporting to EasyEDA format...");
+    println!("Output directory: {}", output_dir.display());
+
+    super::easyeda::export(data_dir, output_dir, lcsc)?;
+    write_usage_notes(data_dir, output_dir)
+}
+
+/// Header of the JLCPCB parts list. JLCPCB's BOM import matches on the
+/// LCSC column and falls back to the manufacturer part number.
+const JLCPCB_HEADER: &str = "Part,Comment,Footprint,Manufacturer,MPN,LCSC Part #,JLCPCB Part Type\n";
+
+/// Write a JLCPCB assembly parts list with LCSC part numbers for the
+/// resistor and capacitor libraries
+pub fn to_jlcpcb(data_dir: &Path, output: Option<&Path>, basic_only: bool) -> Result<(), String> {
+    let output_dir = output.unwrap_or_else(|| Path::new("./jlcpcb"));
+    println!("Exporting JLCPCB assembly parts...");
+    println!("Output directory: {}", output_dir.display());
+
+    let mut rows = part_rows(data_dir)?;
+    let total = rows.len();
+    if basic_only {
+        rows.retain(|row| row.part.as_ref().is_some_and(|part| part.basic));
+    }
+    let basic = rows.iter().filter(|row| row.part.as_ref().is_some_and(|part| part.basic)).count();
+
+    let mut csv = String::from(JLCPCB_HEADER);
+    for row in &rows {
+        let (manufacturer, mpn, sku, kind) = match &row.part {
+            Some(part) => (
+                part.manufacturer,
+                part.mpn.as_str(),
+                part.sku.unwrap_or_default(),
+                if part.basic { "Basic" } else { "Extended" },
+            ),
+            None => ("", "", "", "Extended"),
+        };
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            row.name, row.comment, row.footprint, manufacturer, mpn, sku, kind
+        ));
+    }
+
+    component::atomic::create_dir_all(output_dir)?;
+    let csv_path = output_dir.join("jlcpcb_parts.csv");
+    component::atomic::write(&csv_path, csv)?;
 
     println!();
-    println!("Altium export not yet implemented.");
-    println!("This feature is planned for a future release.");
+    println!("Wrote {} parts ({} basic, {} extended) to: {}", rows.len(), basic, rows.len() - basic, csv_path.display());
+    if basic_only && total > rows.len() {
+        println!("Skipped {} extended parts (--basic-only)", total - rows.len());
+    }
+    write_usage_notes(data_dir, output_dir)
+}
 
-    Ok(())
+/// One part of the resistor and capacitor libraries with its LCSC sourcing
+pub(crate) struct PartRow {
+    pub(crate) name: String,
+    /// Reference designator prefix, R, C or that of a registered family
+    pub(crate) prefix: String,
+    pub(crate) package: String,
+    pub(crate) comment: String,
+    pub(crate) footprint: String,
+    pub(crate) part: Option<component::lcsc::LcscPart>,
+}
+
+/// Every part of the resistor and capacitor libraries, sorted by library file
+/// and within each library into the selected part order
+pub(crate) fn part_rows(data_dir: &Path) -> Result<Vec<PartRow>, String> {
+    let source_dir = data_dir.join("libraries");
+    let manifest = load_manifest(data_dir)?;
+
+    let mut rows = Vec::new();
+    for category in ["resistor", "capacitor"] {
+        let Some(items) = manifest.libraries.get(category) else {
+            continue;
+        };
+        let mut paths: Vec<&String> = items.values().collect();
+        paths.sort();
+        for path in paths {
+            let content = fs::read_to_string(source_dir.join(path))
+                .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            super::schema::validate(category, path, &content)?;
+            let library: serde_json::Value = serde_json::from_str(&content)
+                .map_err(|e| format!("Failed to parse {}: {}", path, e))?;
+            let mut library_rows = match category {
+                "resistor" => resistor_rows(&library),
+                _ => capacitor_rows(&library),
+            };
+            component::order::sort(&mut library_rows, |row| (row.name.as_str(), row.comment.as_str()));
+            rows.extend(library_rows);
+        }
+    }
+    Ok(rows)
+}
+
+/// Every part of the libraries of registered families that support
+/// templated export, sorted like [`part_rows`]
+pub(crate) fn family_rows(data_dir: &Path) -> Result<Vec<PartRow>, String> {
+    let source_dir = data_dir.join("libraries");
+    let manifest = load_manifest(data_dir)?;
+
+    let mut rows = Vec::new();
+    for family in component::family::families() {
+        if !family.exporters().contains(&component::family::Exporter::Template) {
+            continue;
+        }
+        let Some(items) = manifest.libraries.get(family.name()) else {
+            continue;
+        };
+        let mut paths: Vec<&String> = items.values().collect();
+        paths.sort();
+        for path in paths {
+            let content = fs::read_to_string(source_dir.join(path))
+                .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            let library: serde_json::Value = serde_json::from_str(&content)
+                .map_err(|e| format!("Failed to parse {}: {}", path, e))?;
+            let package = library["package"].as_str().unwrap_or_default();
+            let footprint = library["footprint"].as_str().unwrap_or_default();
+            let mut library_rows: Vec<PartRow> = library["parts"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|part| PartRow {
+                    name: part["name"].as_str().unwrap_or_default().to_string(),
+                    prefix: family.prefix().to_string(),
+                    package: package.to_string(),
+                    comment: part["value"].as_str().unwrap_or_default().to_string(),
+                    footprint: footprint.to_string(),
+                    part: None,
+                })
+                .collect();
+            component::order::sort(&mut library_rows, |row| (row.name.as_str(), row.comment.as_str()));
+            rows.extend(library_rows);
+        }
+    }
+    Ok(rows)
+}
+
+/// A resistor library JSON per series of a combined library, E24_E96_0603
+/// as its E24 5% and E96 1% parts, or the library itself
+pub(crate) fn resistor_series(library: &serde_json::Value) -> Vec<serde_json::Value> {
+    let Some(combined) = library["combined"].as_array() else {
+        return vec![library.clone()];
+    };
+    combined
+        .iter()
+        .map(|member| {
+            let mut series = library.clone();
+            if let Some(fields) = series.as_object_mut() {
+                fields.remove("combined");
+                for key in ["series", "tolerance", "base_values"] {
+                    fields.insert(key.to_string(), member[key].clone());
+                }
+            }
+            series
+        })
+        .collect()
+}
+
+/// Every value of a resistor library JSON, less the parts the part hook
+/// dropped
+pub(crate) fn library_resistances(library: &serde_json::Value) -> Vec<component::Resistance> {
+    if library["combined"].is_array() {
+        return resistor_series(library).iter().flat_map(library_resistances).collect();
+    }
+    let dropped: Vec<&str> = library["dropped"].as_array().into_iter().flatten().filter_map(|name| name.as_str()).collect();
+    let package = library["package"].as_str().unwrap_or_default();
+    let tolerance = library["tolerance"].as_str().filter(|_| library["tolerance_in_name"].as_bool().unwrap_or(false));
+    let mut resistances = series_resistances(library);
+    resistances.retain(|&resistance| !dropped.contains(&component::resistor_name(package, resistance, tolerance).as_str()));
+    resistances
+}
+
+fn series_resistances(library: &serde_json::Value) -> Vec<component::Resistance> {
+    // Libraries generated from a value list hold every value
+    if let Some(values) = library["values"].as_array().filter(|values| !values.is_empty()) {
+        return values.iter().filter_map(|value| value.as_str()?.parse().ok()).collect();
+    }
+    // Series libraries cover the standard decades or those of their value
+    // range, their multipliers are the value suffixes. Request stubs hold a
+    // single value in one decade.
+    let range = component::ValueRange::parse(library["min_value"].as_str(), library["max_value"].as_str()).unwrap_or_default();
+    let decades: Vec<f64> = match library["multipliers"].as_object() {
+        Some(multipliers) if multipliers.len() == 1 => multipliers.values().filter_map(|v| v.as_f64()).collect(),
+        _ => range.decades(),
+    };
+    let base_values: Vec<f64> = library["base_values"]
+        .as_array()
+        .map(|values| values.iter().filter_map(|v| v.as_f64()).collect())
+        .unwrap_or_default();
+
+    decades
+        .iter()
+        .flat_map(|decade| base_values.iter().map(move |base| component::Resistance::from_ohms(base * decade)))
+        .filter(|&resistance| range.contains(resistance))
+        .collect()
+}
+
+fn resistor_rows(library: &serde_json::Value) -> Vec<PartRow> {
+    resistor_series(library).iter().flat_map(series_rows).collect()
+}
+
+fn series_rows(library: &serde_json::Value) -> Vec<PartRow> {
+    let package = library["package"].as_str().unwrap_or_default();
+    let footprint = library["footprint"].as_str().unwrap_or_default();
+    let manufacturer = library["manufacturer"].as_str().unwrap_or_default();
+    let tolerance = library["tolerance"].as_str().unwrap_or_default();
+    let in_name = library["tolerance_in_name"].as_bool().unwrap_or(false);
+    library_resistances(library)
+        .into_iter()
+        .filter(|&resistance| component::availability::available(manufacturer, package, tolerance, resistance))
+        .map(|resistance| PartRow {
+            name: component::resistor_name(package, resistance, in_name.then_some(tolerance)),
+            prefix: "R".into(),
+            package: package.to_string(),
+            comment: resistance.label(),
+            footprint: footprint.to_string(),
+            part: component::lcsc::resistor(package, resistance),
+        })
+        .collect()
+}
+
+fn capacitor_rows(library: &serde_json::Value) -> Vec<PartRow> {
+    let package = library["package"].as_str().unwrap_or_default();
+    let dielectric = library["dielectric"].as_str().unwrap_or_default();
+    let footprint = library["footprint"].as_str().unwrap_or_default();
+    library["values"]
+        .as_array()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(|value| PartRow {
+                    name: format!("C{}_{}_{}", package_naming::label(package), dielectric, value),
+                    prefix: "C".into(),
+                    package: package.to_string(),
+                    comment: value.to_string(),
+                    footprint: footprint.to_string(),
+                    part: component::lcsc::capacitor(package, dielectric, value),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
 }
@@ -0,0 +1,83 @@
+//! Fabrication house rules from config.toml
+//!
+//! Solder mask and paste margins and the zone connection style are written
+//! into every generated footprint, with overrides per package size:
+//!
+//! ```toml
+//! [fabrication]
+//! solder_mask_margin = 0.05
+//! solder_paste_ratio = -0.05
+//! zone_connect = "thermal"
+//! thermal_width = 0.3
+//!
+//! [fabrication.packages.0402]
+//! solder_paste_ratio = -0.1
+//! ```
+
+use component::kicad_footprint::{FabricationConfig, FabricationRules};
+use component::settings::Settings;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Default rules and per-package overrides of a `[fabrication]` table
+fn parse(section: &toml::Value) -> Result<(FabricationRules, HashMap<String, FabricationRules>), String> {
+    let mut section = section
+        .as_table()
+        .cloned()
+        .ok_or("[fabrication] must be a table")?;
+    let packages = match section.remove("packages") {
+        Some(packages) => packages
+            .try_into()
+            .map_err(|e| format!("Invalid [fabrication.packages]: {}", e))?,
+        None => HashMap::new(),
+    };
+    let defaults = toml::Value::Table(section)
+        .try_into()
+        .map_err(|e| format!("Invalid [fabrication]: {}", e))?;
+    Ok((defaults, packages))
+}
+
+/// Take the `[fabrication]` rules of config.toml, if any, into `settings`
+pub fn install(data_dir: &Path, settings: &mut Settings) -> Result<(), String> {
+    let config_path = data_dir.join("config.toml");
+    let Ok(content) = fs::read_to_string(&config_path) else {
+        return Ok(());
+    };
+    let config: toml::Table = content
+        .parse()
+        .map_err(|e| format!("Failed to parse {}: {}", config_path.display(), e))?;
+    let Some(section) = config.get("fabrication") else {
+        return Ok(());
+    };
+    let (defaults, packages) = parse(section).map_err(|e| format!("{} in {}", e, config_path.display()))?;
+    settings.fabrication = Some(FabricationConfig { defaults, packages });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use component::kicad_footprint::ZoneConnection;
+
+    #[test]
+    fn package_overrides_sit_next_to_the_defaults() {
+        let config: toml::Table = r#"
+            [fabrication]
+            solder_mask_margin = 0.05
+            zone_connect = "solid"
+
+            [fabrication.packages.0402]
+            solder_paste_ratio = -0.1
+        "#
+        .parse()
+        .unwrap();
+        let (defaults, packages) = parse(&config["fabrication"]).unwrap();
+        assert_eq!(defaults.solder_mask_margin, Some(0.05));
+        assert_eq!(defaults.zone_connect, Some(ZoneConnection::Solid));
+        assert_eq!(packages["0402"].solder_paste_ratio, Some(-0.1));
+
+        let typo: toml::Table = "[fabrication]\nsolder_mask_marign = 0.05".parse().unwrap();
+        assert!(parse(&typo["fabrication"]).unwrap_err().contains("solder_mask_marign"));
+    }
+}
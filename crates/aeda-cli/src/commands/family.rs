@@ -0,0 +1,261 @@
+//! Component families beyond the built-in ones
+//!
+//! Crates linking atlantix-core register a `component::family::ComponentFamily`
+//! themselves. Families that only need a list of values per package are
+//! described in `<data-dir>/families/<name>.toml` instead and registered at
+//! startup:
+//!
+//! ```toml
+//! description = "Crystal oscillators"
+//! prefix = "Y"
+//! packages = ["3225", "2520"]
+//! values = ["8MHz", "12MHz", "25MHz"]
+//! footprint = "Oscillator:Oscillator_SMD_{package}"
+//! manufacturer = "Abracon"
+//! mpn = "ABM8-{value}"
+//! ```
+//!
+//! Every registered family is generated with `aeda generate <name>`, its
+//! parameters given as `--name value`.
+
+use super::generate::{update_manifest, LibraryMethods};
+use super::lock::CategoryLock;
+use super::output::{self, say};
+use component::family::{self, ComponentFamily, Exporter, FamilyLibrary, FamilyPart, Parameter};
+use component::AtlantixError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Names of the built-in generators and library categories, which a family
+/// may not shadow
+const BUILT_IN: [&str; 13] = [
+    "resistors", "capacitors", "inductors", "ferrites", "leds", "current-sense", "diodes",
+    "resistor", "capacitor", "inductor", "ferrite", "led", "diode",
+];
+
+/// A family described by a TOML file
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TableFamily {
+    #[serde(skip)]
+    name: String,
+    description: String,
+    prefix: String,
+    packages: Vec<String>,
+    values: Vec<String>,
+    /// Footprint with a `{package}` placeholder
+    #[serde(default)]
+    footprint: String,
+    #[serde(default)]
+    manufacturer: String,
+    /// Part number with `{value}` and `{package}` placeholders
+    #[serde(default)]
+    mpn: String,
+}
+
+impl TableFamily {
+    fn fill(template: &str, package: &str, value: &str) -> String {
+        template.replace("{package}", package).replace("{value}", value)
+    }
+}
+
+/// Comma-separated list, empty entries dropped
+fn list(value: &str) -> Vec<&str> {
+    value.split(',').map(str::trim).filter(|s| !s.is_empty()).collect()
+}
+
+impl ComponentFamily for TableFamily {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    fn parameters(&self) -> Vec<Parameter> {
+        vec![
+            Parameter::new("packages", "Packages to generate (comma-separated)").with_default(&self.packages.join(",")),
+            Parameter::new("values", "Values to generate (comma-separated)").with_default(&self.values.join(",")),
+        ]
+    }
+
+    fn generate(&self, parameters: &BTreeMap<String, String>) -> Result<Vec<FamilyLibrary>, AtlantixError> {
+        let mut libraries = Vec::new();
+        for package in list(&parameters["packages"]) {
+            if !self.packages.iter().any(|p| p == package) {
+                return Err(AtlantixError::invalid_package(&self.name, package, &self.packages));
+            }
+            let parts = list(&parameters["values"])
+                .into_iter()
+                .map(|value| FamilyPart {
+                    name: format!("{}{}_{}", self.prefix, package, value),
+                    value: value.to_string(),
+                    description: format!("{} {}, {}", self.description, value, package),
+                    manufacturer: self.manufacturer.clone(),
+                    mpn: Self::fill(&self.mpn, package, value),
+                })
+                .collect();
+            libraries.push(FamilyLibrary {
+                name: format!("{}_{}", self.prefix, package),
+                description: format!("{} in {} package", self.description, package),
+                package: package.to_string(),
+                footprint: Self::fill(&self.footprint, package, ""),
+                parts,
+            });
+        }
+        Ok(libraries)
+    }
+}
+
+/// Register the families of `<data-dir>/families/*.toml`
+pub fn install(data_dir: &Path) -> Result<(), String> {
+    let Ok(entries) = fs::read_dir(data_dir.join("families")) else {
+        return Ok(());
+    };
+    let mut paths: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let mut table: TableFamily =
+            toml::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        table.name = path.file_stem().unwrap_or_default().to_string_lossy().to_lowercase();
+        if BUILT_IN.contains(&table.name.as_str()) {
+            return Err(format!("{} shadows the built-in {} family", path.display(), table.name));
+        }
+        family::register(table)?;
+    }
+    Ok(())
+}
+
+/// `--name value` and `--name=value` pairs
+fn parse_args(args: &[String]) -> Result<Vec<(String, String)>, String> {
+    let mut pairs = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let name = arg
+            .strip_prefix("--")
+            .ok_or_else(|| format!("Unexpected argument '{}', parameters are given as --name value", arg))?;
+        let (name, value) = match name.split_once('=') {
+            Some((name, value)) => (name, value.to_string()),
+            None => (name, args.next().ok_or_else(|| format!("--{} needs a value", name))?.clone()),
+        };
+        pairs.push((name.replace('-', "_"), value));
+    }
+    Ok(pairs)
+}
+
+fn print_help(family: &dyn ComponentFamily) {
+    say!("{}\n\nUsage: aeda generate {} [--<parameter> <value>]...\n\nParameters:", family.description(), family.name());
+    for parameter in family.parameters() {
+        match &parameter.default {
+            Some(default) => say!("  --{:<16} {} [default: {}]", parameter.name, parameter.description, default),
+            None => say!("  --{:<16} {} (required)", parameter.name, parameter.description),
+        }
+    }
+    let exporters: Vec<String> = family.exporters().iter().map(Exporter::to_string).collect();
+    say!("\nExporters: {}", exporters.join(", "));
+}
+
+#[derive(Serialize)]
+struct FamilyLibraryFile<'a> {
+    name: &'a str,
+    #[serde(rename = "type")]
+    component_type: &'a str,
+    description: &'a str,
+    package: &'a str,
+    footprint: &'a str,
+    prefix: &'a str,
+    values: Vec<&'a str>,
+    parts: &'a [FamilyPart],
+    methods: LibraryMethods,
+}
+
+/// `aeda generate <family> ...`: `args` are the family name and its parameters
+pub fn generate(data_dir: &Path, args: &[String]) -> Result<(), String> {
+    let (name, args) = args.split_first().ok_or("Missing component family")?;
+    let family = family::find(name).ok_or_else(|| {
+        let registered: Vec<String> = family::families().iter().map(|f| f.name().to_string()).collect();
+        let known = BUILT_IN[..7].iter().map(|s| s.to_string()).chain(registered);
+        format!("Unknown component family '{}', expected one of: {}", name, known.collect::<Vec<_>>().join(", "))
+    })?;
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        print_help(family.as_ref());
+        return Ok(());
+    }
+    let parameters = family::resolve(family.as_ref(), &parse_args(args)?)?;
+    let libraries = family.generate(&parameters)?;
+
+    let category = family.name();
+    let _lock = CategoryLock::acquire(data_dir, category)?;
+    say!("Generating {} libraries...", category);
+    let family_dir = data_dir.join("libraries").join(category);
+    component::atomic::create_dir_all(&family_dir)?;
+
+    for library in &libraries {
+        let file = FamilyLibraryFile {
+            name: &library.name,
+            component_type: category,
+            description: &library.description,
+            package: &library.package,
+            footprint: &library.footprint,
+            prefix: family.prefix(),
+            values: library.parts.iter().map(|p| p.value.as_str()).collect(),
+            parts: &library.parts,
+            methods: LibraryMethods::default(),
+        };
+        let lib_path = family_dir.join(format!("{}.json", library.name));
+        let content = serde_json::to_string_pretty(&file).map_err(|e| format!("Failed to serialize library: {}", e))?;
+        component::atomic::write(&lib_path, content)?;
+        update_manifest(data_dir, category, &library.name, &format!("{}/{}.json", category, library.name))?;
+        say!("  Created: {}::{} ({} values)", category, library.name, library.parts.len());
+        output::created(&format!("{}::{}", category, library.name), &lib_path, library.parts.len());
+    }
+
+    say!("\nDone! Libraries available at: {}", family_dir.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_family_generates_one_library_per_package() {
+        let mut table: TableFamily = toml::from_str(
+            r#"
+            description = "Crystal oscillators"
+            prefix = "Y"
+            packages = ["3225", "2520"]
+            values = ["8MHz", "25MHz"]
+            footprint = "Oscillator:Oscillator_SMD_{package}"
+            mpn = "ABM8-{value}"
+        "#,
+        )
+        .unwrap();
+        table.name = "oscillator".into();
+
+        let given = parse_args(&["--packages=2520".into(), "--values".into(), "12MHz".into()]).unwrap();
+        let parameters = family::resolve(&table, &given).unwrap();
+        let libraries = table.generate(&parameters).unwrap();
+        assert_eq!(libraries.len(), 1);
+        assert_eq!(libraries[0].name, "Y_2520");
+        assert_eq!(libraries[0].footprint, "Oscillator:Oscillator_SMD_2520");
+        assert_eq!(libraries[0].parts[0].mpn, "ABM8-12MHz");
+
+        let defaults = family::resolve(&table, &[]).unwrap();
+        assert_eq!(table.generate(&defaults).unwrap()[0].parts.len(), 2);
+        let unsupported = family::resolve(&table, &[("packages".into(), "0603".into())]).unwrap();
+        assert!(table.generate(&unsupported).is_err());
+    }
+}
@@ -0,0 +1,276 @@
+//! Clean up existing KiCad symbol libraries
+//!
+//! Libraries generated by older releases and then edited by hand often stop
+//! loading: duplicated symbols, duplicated or out of order properties, clashing
+//! property ids or a pre-release format version. `aeda fix-library` parses the
+//! file with kiparse, repairs those issues and writes it back in KiCad's layout,
+//! keeping the original next to it as `<file>.bak`.
+
+use component::kicad_symbol::KicadFormatVersion;
+use kiparse::sexpr::{parse_sexpr, SExpr};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Format version written by atlantix-core, older files are upgraded to it
+const FORMAT_VERSION: u32 = KicadFormatVersion::V6.version();
+
+/// First format version without property ids (KiCad 7)
+const ID_FREE_VERSION: u32 = KicadFormatVersion::V7.version();
+
+/// KiCad's mandatory fields, always first and numbered 0 to 3. User fields
+/// are numbered from 4 even when a mandatory field is missing.
+const MANDATORY_PROPERTIES: [&str; 4] = ["Reference", "Value", "Footprint", "Datasheet"];
+
+#[derive(Debug, Default, PartialEq)]
+struct FixReport {
+    upgraded_from: Option<u32>,
+    duplicate_symbols: Vec<String>,
+    duplicate_properties: usize,
+    renumbered_ids: usize,
+    reordered_symbols: usize,
+    sorted: bool,
+}
+
+pub fn run(paths: &[PathBuf]) -> Result<(), String> {
+    for path in paths {
+        fix_file(path)?;
+    }
+    Ok(())
+}
+
+fn fix_file(path: &Path) -> Result<(), String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let mut library = parse_sexpr(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+
+    let report = fix_library(&mut library)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    let fixed = format!("{}\n", library.to_kicad_string());
+    if fixed == content {
+        println!("{}: already clean", path.display());
+        return Ok(());
+    }
+
+    let backup = path.with_extension("kicad_sym.bak");
+    fs::copy(path, &backup)
+        .map_err(|e| format!("Failed to back up {} to {}: {}", path.display(), backup.display(), e))?;
+    component::atomic::write(path, fixed)?;
+
+    println!("Fixed {} (backup: {})", path.display(), backup.display());
+    if let Some(version) = report.upgraded_from {
+        println!("  Format version {} -> {}", version, FORMAT_VERSION);
+    }
+    for name in &report.duplicate_symbols {
+        println!("  Removed duplicate symbol {}", name);
+    }
+    if report.duplicate_properties > 0 {
+        println!("  Removed {} duplicate properties", report.duplicate_properties);
+    }
+    if report.renumbered_ids > 0 {
+        println!("  Normalized {} property ids", report.renumbered_ids);
+    }
+    if report.reordered_symbols > 0 {
+        println!("  Reordered properties in {} symbols", report.reordered_symbols);
+    }
+    if report.sorted {
+        println!("  Sorted symbols by name");
+    }
+    Ok(())
+}
+
+fn fix_library(library: &mut SExpr) -> Result<FixReport, String> {
+    if library.head() != Some("kicad_symbol_lib") {
+        return Err("not a KiCad symbol library".into());
+    }
+    let mut report = FixReport::default();
+
+    let version = library.find("version").and_then(|v| v.value(1)).and_then(|v| v.parse::<u32>().ok());
+    let version = match version {
+        Some(v) if v >= FORMAT_VERSION => v,
+        _ => {
+            report.upgraded_from = Some(version.unwrap_or(0));
+            let upgraded = SExpr::List(vec![SExpr::Atom("version".into()), SExpr::Atom(FORMAT_VERSION.to_string())]);
+            match library.find_mut("version") {
+                Some(slot) => *slot = upgraded,
+                None => library.items_mut().expect("root is a list").insert(1, upgraded),
+            }
+            FORMAT_VERSION
+        }
+    };
+
+    let items = std::mem::take(library.items_mut().expect("root is a list"));
+    let (mut symbols, mut rest): (Vec<SExpr>, Vec<SExpr>) =
+        items.into_iter().partition(|item| item.head() == Some("symbol"));
+
+    let mut seen = HashSet::new();
+    symbols.retain(|symbol| {
+        let name = symbol_name(symbol).to_string();
+        if seen.insert(name.clone()) {
+            true
+        } else {
+            report.duplicate_symbols.push(name);
+            false
+        }
+    });
+
+    for symbol in &mut symbols {
+        normalize_properties(symbol, version < ID_FREE_VERSION, &mut report);
+    }
+
+    // Derived symbols have to follow the symbol they extend
+    let sort_key = |symbol: &SExpr| {
+        let parent = symbol.find("extends").and_then(|e| e.value(1)).map(str::to_string);
+        let name = symbol_name(symbol).to_string();
+        (parent.clone().unwrap_or_else(|| name.clone()), parent.is_some(), name)
+    };
+    let before: Vec<String> = symbols.iter().map(|s| symbol_name(s).to_string()).collect();
+    symbols.sort_by_key(sort_key);
+    report.sorted = symbols.iter().map(symbol_name).ne(before.iter().map(String::as_str));
+
+    rest.extend(symbols);
+    *library.items_mut().expect("root is a list") = rest;
+    Ok(report)
+}
+
+fn symbol_name(symbol: &SExpr) -> &str {
+    symbol.value(1).unwrap_or_default()
+}
+
+/// Deduplicate properties, move the mandatory fields to the front and
+/// renumber ids (or drop them for formats that no longer use them)
+fn normalize_properties(symbol: &mut SExpr, use_ids: bool, report: &mut FixReport) {
+    let items = symbol.items_mut().expect("symbol is a list");
+    let Some(first) = items.iter().position(|item| item.head() == Some("property")) else {
+        return;
+    };
+    let (properties, others): (Vec<SExpr>, Vec<SExpr>) =
+        std::mem::take(items).into_iter().partition(|item| item.head() == Some("property"));
+
+    let mut seen = HashSet::new();
+    let mut unique: Vec<SExpr> = Vec::new();
+    for property in properties {
+        if seen.insert(property.value(1).unwrap_or_default().to_string()) {
+            unique.push(property);
+        } else {
+            report.duplicate_properties += 1;
+        }
+    }
+    let rank = |property: &SExpr| {
+        let name = property.value(1).unwrap_or_default();
+        MANDATORY_PROPERTIES.iter().position(|m| *m == name).unwrap_or(MANDATORY_PROPERTIES.len())
+    };
+    if !unique.is_sorted_by_key(rank) {
+        unique.sort_by_key(rank);
+        report.reordered_symbols += 1;
+    }
+
+    let mut next_user_id = MANDATORY_PROPERTIES.len();
+    for property in unique.iter_mut() {
+        let index = match rank(property) {
+            mandatory if mandatory < MANDATORY_PROPERTIES.len() => mandatory,
+            _ => {
+                next_user_id += 1;
+                next_user_id - 1
+            }
+        };
+        let children = property.items_mut().expect("property is a list");
+        let existing = children.iter().position(|child| child.head() == Some("id"));
+        match (use_ids, existing) {
+            (true, Some(at)) => {
+                if children[at].value(1) != Some(index.to_string().as_str()) {
+                    children[at].set_value(1, SExpr::Atom(index.to_string()));
+                    report.renumbered_ids += 1;
+                }
+            }
+            (true, None) => {
+                let id = SExpr::List(vec![SExpr::Atom("id".into()), SExpr::Atom(index.to_string())]);
+                children.insert(3.min(children.len()), id);
+                report.renumbered_ids += 1;
+            }
+            (false, Some(at)) => {
+                children.remove(at);
+                report.renumbered_ids += 1;
+            }
+            (false, None) => {}
+        }
+    }
+
+    let mut rebuilt = others;
+    let insert_at = first.min(rebuilt.len());
+    rebuilt.splice(insert_at..insert_at, unique);
+    *items = rebuilt;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed(content: &str) -> (String, FixReport) {
+        let mut library = parse_sexpr(content).unwrap();
+        let report = fix_library(&mut library).unwrap();
+        (library.to_kicad_string(), report)
+    }
+
+    #[test]
+    fn upgrades_version_and_numbers_ids() {
+        let (text, report) = fixed(
+            r#"(kicad_symbol_lib (version 20200126) (generator kicad_symbol_editor)
+  (symbol "R" (in_bom yes)
+    (property "Value" "R" (id 5) (at 0 0 0))
+    (property "Reference" "R" (id 5) (at 0 0 0))
+    (property "MPN" "CRCW06031K00FKEA" (at 0 0 0))
+  )
+)"#,
+        );
+        assert_eq!(report.upgraded_from, Some(20200126));
+        assert_eq!(report.reordered_symbols, 1);
+        assert_eq!(report.renumbered_ids, 3);
+        assert_eq!(
+            text,
+            r#"(kicad_symbol_lib (version 20211014) (generator kicad_symbol_editor)
+  (symbol "R" (in_bom yes)
+    (property "Reference" "R" (id 0) (at 0 0 0))
+    (property "Value" "R" (id 1) (at 0 0 0))
+    (property "MPN" "CRCW06031K00FKEA" (id 4) (at 0 0 0))
+  )
+)"#
+        );
+    }
+
+    #[test]
+    fn removes_duplicates_and_sorts_with_parents_first() {
+        let (text, report) = fixed(
+            r#"(kicad_symbol_lib (version 20220914) (generator atlantix-eda)
+  (symbol "R_B" (extends "R_Z") (property "Reference" "R" (id 0)))
+  (symbol "R_Z" (property "Reference" "R") (property "Reference" "R"))
+  (symbol "R_A" (property "Reference" "R"))
+  (symbol "R_A" (property "Reference" "X"))
+)"#,
+        );
+        assert_eq!(report.duplicate_symbols, ["R_A"]);
+        assert_eq!(report.duplicate_properties, 1);
+        assert!(report.sorted);
+        // KiCad 7 and later files carry no property ids
+        assert_eq!(report.renumbered_ids, 1);
+        assert!(!text.contains("(id "));
+
+        let library = parse_sexpr(&text).unwrap();
+        let names: Vec<&str> = library.find_all("symbol").map(symbol_name).collect();
+        assert_eq!(names, ["R_A", "R_Z", "R_B"]);
+    }
+
+    #[test]
+    fn generated_libraries_need_no_fixes() {
+        let mut symbol_lib = component::kicad_symbol::KicadSymbolLib::new().with_format(KicadFormatVersion::V6);
+        symbol_lib.add_symbol(component::kicad_symbol::KicadSymbol::new(
+            "R0603_1.00K".into(),
+            "1.00K".into(),
+            "Atlantix_Resistors:R_0603_1608Metric".into(),
+            "european",
+        ));
+        let (_, report) = fixed(&symbol_lib.generate_library());
+        assert_eq!(report, FixReport::default());
+    }
+}
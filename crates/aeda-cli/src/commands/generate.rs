@@ -1,13 +1,41 @@
 //! Generate component libraries
 
+use super::lock::CategoryLock;
+use super::output::{self, say};
+use component::capabilities::E_SERIES;
+use component::kicad_footprint::FootprintNaming;
+use component::{package_naming, AtlantixError, PartStatus, Power};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// E-series base values
 fn get_e_series(series: &str) -> Result<Vec<f64>, String> {
     match series.to_uppercase().as_str() {
+        "E192" => Ok(vec![
+            1.00, 1.01, 1.02, 1.04, 1.05, 1.06, 1.07, 1.09, 1.10, 1.11,
+            1.13, 1.14, 1.15, 1.17, 1.18, 1.20, 1.21, 1.23, 1.24, 1.26,
+            1.27, 1.29, 1.30, 1.32, 1.33, 1.35, 1.37, 1.38, 1.40, 1.42,
+            1.43, 1.45, 1.47, 1.49, 1.50, 1.52, 1.54, 1.56, 1.58, 1.60,
+            1.62, 1.64, 1.65, 1.67, 1.69, 1.72, 1.74, 1.76, 1.78, 1.80,
+            1.82, 1.84, 1.87, 1.89, 1.91, 1.93, 1.96, 1.98, 2.00, 2.03,
+            2.05, 2.08, 2.10, 2.13, 2.15, 2.18, 2.21, 2.23, 2.26, 2.29,
+            2.32, 2.34, 2.37, 2.40, 2.43, 2.46, 2.49, 2.52, 2.55, 2.58,
+            2.61, 2.64, 2.67, 2.71, 2.74, 2.77, 2.80, 2.84, 2.87, 2.91,
+            2.94, 2.98, 3.01, 3.05, 3.09, 3.12, 3.16, 3.20, 3.24, 3.28,
+            3.32, 3.36, 3.40, 3.44, 3.48, 3.52, 3.57, 3.61, 3.65, 3.70,
+            3.74, 3.79, 3.83, 3.88, 3.92, 3.97, 4.02, 4.07, 4.12, 4.17,
+            4.22, 4.27, 4.32, 4.37, 4.42, 4.48, 4.53, 4.59, 4.64, 4.70,
+            4.75, 4.81, 4.87, 4.93, 4.99, 5.05, 5.11, 5.17, 5.23, 5.30,
+            5.36, 5.42, 5.49, 5.56, 5.62, 5.69, 5.76, 5.83, 5.90, 5.97,
+            6.04, 6.12, 6.19, 6.26, 6.34, 6.42, 6.49, 6.57, 6.65, 6.73,
+            6.81, 6.90, 6.98, 7.06, 7.15, 7.23, 7.32, 7.41, 7.50, 7.59,
+            7.68, 7.77, 7.87, 7.96, 8.06, 8.16, 8.25, 8.35, 8.45, 8.56,
+            8.66, 8.76, 8.87, 8.98, 9.09, 9.20, 9.31, 9.42, 9.53, 9.65,
+            9.76, 9.88,
+        ]),
         "E96" => Ok(vec![
             1.00, 1.02, 1.05, 1.07, 1.10, 1.13, 1.15, 1.18, 1.21, 1.24,
             1.27, 1.30, 1.33, 1.37, 1.40, 1.43, 1.47, 1.50, 1.54, 1.58,
@@ -35,12 +63,13 @@ fn get_e_series(series: &str) -> Result<Vec<f64>, String> {
             1.0, 1.2, 1.5, 1.8, 2.2, 2.7, 3.3, 3.9, 4.7, 5.6, 6.8, 8.2,
         ]),
         "E6" => Ok(vec![1.0, 1.5, 2.2, 3.3, 4.7, 6.8]),
-        _ => Err(format!("Unknown E-series: {}", series)),
+        _ => Err(AtlantixError::invalid_series(series, E_SERIES.map(|n| format!("E{}", n))).into()),
     }
 }
 
-fn get_tolerance(series: &str) -> &'static str {
+pub(crate) fn get_tolerance(series: &str) -> &'static str {
     match series.to_uppercase().as_str() {
+        "E192" => "0.5%",
         "E96" => "1%",
         "E48" => "2%",
         "E24" => "5%",
@@ -50,21 +79,26 @@ fn get_tolerance(series: &str) -> &'static str {
     }
 }
 
-fn get_power_rating(package: &str) -> &'static str {
-    match package {
-        "0201" => "1/20W",
-        "0402" => "1/16W",
-        "0603" => "1/10W",
-        "0805" => "1/8W",
-        "1206" => "1/4W",
-        "1210" => "1/2W",
-        "2010" => "3/4W",
-        "2512" => "1W",
-        _ => "1/10W",
-    }
+pub(crate) fn get_power_rating(package: &str) -> Power {
+    component::chip_resistor_power(package).unwrap_or(Power(100))
+}
+
+/// Parse an optional `--min-power` argument
+pub(crate) fn parse_min_power(min_power: Option<&str>) -> Result<Option<Power>, String> {
+    Ok(min_power.map(str::parse).transpose()?)
+}
+
+/// Packages of a comma-separated list by their imperial code, either code
+/// accepted (`1608` or `0603`)
+pub(crate) fn package_list(packages: &str) -> Vec<String> {
+    packages
+        .split(',')
+        .map(str::trim)
+        .map(|package| package_naming::resolve(package).unwrap_or_else(|| package.to_string()))
+        .collect()
 }
 
-fn get_metric_suffix(package: &str) -> &'static str {
+pub(crate) fn get_metric_suffix(package: &str) -> &'static str {
     match package {
         "0201" => "_0603Metric",
         "0402" => "_1005Metric",
@@ -78,44 +112,277 @@ fn get_metric_suffix(package: &str) -> &'static str {
     }
 }
 
-#[derive(Serialize)]
-struct ResistorLibrary {
+/// A resistor series library, `libraries/resistor/*.json`
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub(crate) struct ResistorLibrary {
+    pub(crate) name: String,
+    #[serde(rename = "type")]
+    pub(crate) component_type: String,
+    pub(crate) description: String,
+    pub(crate) package: String,
+    pub(crate) footprint: String,
+    pub(crate) tolerance: String,
+    pub(crate) manufacturer: String,
+    /// Second sources listed as alternate MPNs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) alternate_manufacturers: Vec<String>,
+    /// AEC-Q200 qualified part numbers
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) automotive: bool,
+    pub(crate) power_rating: String,
+    /// Power rating in milliwatts, absent in libraries written before it existed
+    #[serde(default)]
+    pub(crate) power_mw: u32,
+    pub(crate) series: String,
+    pub(crate) pins: Vec<String>,
+    pub(crate) prefix: String,
+    pub(crate) base_values: Vec<f64>,
+    pub(crate) multipliers: HashMap<String, f64>,
+    /// Every value of a library generated from a value list, which has no
+    /// base values then
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) values: Vec<String>,
+    /// How part names write values, absent for the default decimal notation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) value_notation: Option<String>,
+    /// KiCad symbol body, absent for the default european rectangle
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) symbol_style: Option<String>,
+    /// Part names end in the tolerance, `R0603_10.0K_1%`, in libraries
+    /// generated in several tolerances
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) tolerance_in_name: bool,
+    /// Lowest value of a trimmed library, e.g. "10"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) min_value: Option<String>,
+    /// Highest value of a trimmed library, e.g. "1M"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) max_value: Option<String>,
+    /// Series of a combined library, E24 and E96 each in its own tolerance,
+    /// which has no base values of its own then
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) combined: Vec<CombinedSeries>,
+    /// Parts the part hook dropped, left out of every export
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) dropped: Vec<String>,
+    pub(crate) methods: LibraryMethods,
+}
+
+/// One series of a combined resistor library
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub(crate) struct CombinedSeries {
+    pub(crate) series: String,
+    pub(crate) tolerance: String,
+    pub(crate) base_values: Vec<f64>,
+}
+
+/// Value suffixes of series libraries
+fn resistor_multipliers() -> HashMap<String, f64> {
+    [
+        ("".into(), 1.0),
+        ("k".into(), 1000.0),
+        ("K".into(), 1000.0),
+        ("M".into(), 1_000_000.0),
+    ]
+    .into_iter()
+    .collect()
+}
+
+/// The selected value notation for library JSON, `None` when it is the default
+pub(crate) fn value_notation() -> Option<String> {
+    let notation = component::resistance::notation();
+    (notation != component::ValueNotation::default()).then(|| notation.to_string())
+}
+
+/// A resistor symbol style for library JSON, `None` when it is the default
+pub(crate) fn symbol_style(style: &str) -> Option<String> {
+    (style != component::kicad_symbol::ResistorStyles::default().default).then(|| style.to_string())
+}
+
+/// A capacitor library, `libraries/capacitor/*.json`
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub(crate) struct CapacitorLibrary {
     name: String,
     #[serde(rename = "type")]
     component_type: String,
     description: String,
     package: String,
     footprint: String,
+    dielectric: String,
+    voltage_rating: String,
     tolerance: String,
-    power_rating: String,
-    series: String,
     pins: Vec<String>,
     prefix: String,
-    base_values: Vec<f64>,
-    multipliers: HashMap<String, f64>,
+    values: Vec<String>,
+    value_suffixes: HashMap<String, f64>,
+    /// Marking code per value, `104` for 100nF
+    #[serde(default)]
+    value_codes: BTreeMap<String, String>,
+    /// Internal part number per value, empty without an `[ipn]` scheme
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    ipns: BTreeMap<String, String>,
     methods: LibraryMethods,
 }
 
+/// Part name of a capacitor value, `C0603_X7R_100nF`
+pub(crate) fn capacitor_name(package: &str, dielectric: &str, value: &str) -> String {
+    format!("C{}_{}_{}", package_naming::label(package), dielectric, value)
+}
+
 #[derive(Serialize)]
-struct CapacitorLibrary {
+struct InductorLibrary {
     name: String,
     #[serde(rename = "type")]
     component_type: String,
     description: String,
     package: String,
     footprint: String,
-    dielectric: String,
-    voltage_rating: String,
+    series: String,
+    shielded: bool,
     tolerance: String,
     pins: Vec<String>,
     prefix: String,
     values: Vec<String>,
     value_suffixes: HashMap<String, f64>,
+    parts: Vec<InductorEntry>,
+    methods: LibraryMethods,
+}
+
+#[derive(Serialize)]
+struct InductorEntry {
+    value: String,
+    /// Marking code, `4R7` for 4.7uH
+    code: String,
+    mpn: String,
+    rated_current: f64,
+    saturation_current: f64,
+    dcr_mohm: f64,
+    /// Internal part number, absent without an `[ipn]` scheme
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ipn: Option<String>,
+}
+
+#[derive(Serialize)]
+struct FerriteLibrary {
+    name: String,
+    #[serde(rename = "type")]
+    component_type: String,
+    description: String,
+    package: String,
+    footprint: String,
+    pins: Vec<String>,
+    prefix: String,
+    values: Vec<String>,
+    parts: Vec<FerriteEntry>,
+    methods: LibraryMethods,
+}
+
+#[derive(Serialize)]
+struct FerriteEntry {
+    value: String,
+    impedance_ohms: f64,
+    rated_current: f64,
+    dcr_mohm: f64,
+    murata_mpn: String,
+    tdk_mpn: String,
+    /// Internal part number, absent without an `[ipn]` scheme
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ipn: Option<String>,
+}
+
+#[derive(Serialize)]
+struct LedLibrary {
+    name: String,
+    #[serde(rename = "type")]
+    component_type: String,
+    description: String,
+    package: String,
+    footprint: String,
+    pins: Vec<String>,
+    prefix: String,
+    values: Vec<String>,
+    parts: Vec<LedEntry>,
+    methods: LibraryMethods,
+}
+
+#[derive(Serialize)]
+struct LedEntry {
+    color: String,
+    wavelength_nm: Option<u32>,
+    forward_voltage: f64,
+    intensity_mcd: f64,
+    kingbright_mpn: String,
+    liteon_mpn: String,
+    digikey_pn: String,
+    /// Internal part number, absent without an `[ipn]` scheme
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ipn: Option<String>,
+}
+
+#[derive(Serialize)]
+struct CurrentSenseLibrary {
+    name: String,
+    #[serde(rename = "type")]
+    component_type: String,
+    description: String,
+    package: String,
+    footprint: String,
+    kelvin: bool,
+    tolerance: String,
+    power_rating: String,
+    power_mw: u32,
+    pins: Vec<String>,
+    prefix: String,
+    values: Vec<String>,
+    parts: Vec<CurrentSenseEntry>,
+    methods: LibraryMethods,
+}
+
+#[derive(Serialize)]
+struct CurrentSenseEntry {
+    value: String,
+    ohms: f64,
+    vishay_mpn: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bourns_mpn: Option<String>,
+    /// Internal part number, absent without an `[ipn]` scheme
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ipn: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DiodeLibrary {
+    name: String,
+    #[serde(rename = "type")]
+    component_type: String,
+    kind: String,
+    description: String,
+    package: String,
+    footprint: String,
+    pins: Vec<String>,
+    prefix: String,
+    values: Vec<String>,
+    parts: Vec<DiodeEntry>,
     methods: LibraryMethods,
 }
 
 #[derive(Serialize)]
-struct LibraryMethods {
+struct DiodeEntry {
+    value: String,
+    voltage: f64,
+    rating: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nexperia_mpn: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    onsemi_mpn: Option<String>,
+    /// Internal part number, absent without an `[ipn]` scheme
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ipn: Option<String>,
+}
+
+/// Builder methods the Stencil tool offers on a part
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub(crate) struct LibraryMethods {
     after_factory: Vec<String>,
     after_value: Vec<String>,
 }
@@ -143,17 +410,58 @@ impl Default for LibraryMethods {
 }
 
 #[derive(Serialize, Deserialize)]
-struct Manifest {
-    name: String,
-    version: String,
-    description: String,
-    libraries: HashMap<String, HashMap<String, String>>,
+pub(crate) struct Manifest {
+    pub(crate) name: String,
+    pub(crate) version: String,
+    pub(crate) description: String,
+    pub(crate) libraries: HashMap<String, HashMap<String, String>>,
+    /// Part requests awaiting approval, keyed like `libraries`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub(crate) pending: HashMap<String, HashMap<String, String>>,
+    /// Review status per library, keyed by `category::name`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub(crate) status: HashMap<String, StatusRecord>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct StatusRecord {
+    pub(crate) status: PartStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) reviewer: Option<String>,
+    pub(crate) updated: String,
 }
 
-fn update_manifest(data_dir: &Path, category: &str, name: &str, path: &str) -> Result<(), String> {
+impl Manifest {
+    /// Status of a library, libraries without a record are drafts
+    pub(crate) fn status_of(&self, category: &str, name: &str) -> PartStatus {
+        self.status
+            .get(&format!("{}::{}", category, name))
+            .map(|record| record.status)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn set_status(
+        &mut self,
+        category: &str,
+        name: &str,
+        status: PartStatus,
+        reviewer: Option<String>,
+    ) {
+        self.status.insert(
+            format!("{}::{}", category, name),
+            StatusRecord {
+                status,
+                reviewer,
+                updated: chrono::Local::now().format("%Y-%m-%d").to_string(),
+            },
+        );
+    }
+}
+
+pub(crate) fn load_manifest(data_dir: &Path) -> Result<Manifest, String> {
     let manifest_path = data_dir.join("libraries/manifest.json");
 
-    let mut manifest: Manifest = if manifest_path.exists() {
+    let manifest: Manifest = if manifest_path.exists() {
         let content = fs::read_to_string(&manifest_path)
             .map_err(|e| format!("Failed to read manifest: {}", e))?;
         serde_json::from_str(&content)
@@ -164,96 +472,498 @@ fn update_manifest(data_dir: &Path, category: &str, name: &str, path: &str) -> R
             version: "1.0.0".into(),
             description: "Atlantix EDA Component Libraries".into(),
             libraries: HashMap::new(),
+            pending: HashMap::new(),
+            status: HashMap::new(),
         }
     };
 
+    Ok(manifest)
+}
+
+pub(crate) fn save_manifest(data_dir: &Path, manifest: &Manifest) -> Result<(), String> {
+    let manifest_path = data_dir.join("libraries/manifest.json");
+
+    let content = serde_json::to_string_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+
+    // The manifest is aeda's own index, rewritten without asking
+    component::atomic::replace(&manifest_path, content)?;
+
+    Ok(())
+}
+
+pub(crate) fn update_manifest(data_dir: &Path, category: &str, name: &str, path: &str) -> Result<(), String> {
+    let _lock = CategoryLock::acquire_manifest(data_dir)?;
+    let mut manifest = load_manifest(data_dir)?;
+
     manifest
         .libraries
         .entry(category.to_string())
         .or_insert_with(HashMap::new)
         .insert(name.to_string(), path.to_string());
 
-    let content = serde_json::to_string_pretty(&manifest)
-        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
-
-    fs::write(&manifest_path, content)
-        .map_err(|e| format!("Failed to write manifest: {}", e))?;
+    // Regenerated content has to go through review again
+    if manifest.status_of(category, name) != PartStatus::Draft {
+        say!("  Note: {}::{} was reset to Draft, re-approve with 'aeda approve'", category, name);
+        output::warning(format!("Note: {}::{} was reset to Draft, re-approve with 'aeda approve'", category, name));
+        manifest.set_status(category, name, PartStatus::Draft, None);
+    }
 
+    save_manifest(data_dir, &manifest)?;
+    output::written(&data_dir.join("libraries/manifest.json"));
     Ok(())
 }
 
-pub fn resistors(data_dir: &Path, series: &str, packages: &str) -> Result<(), String> {
+/// When resistor part names end in the tolerance, `R0603_10.0K_1%`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ToleranceNaming {
+    /// Only in runs with several tolerances, where the names would collide
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl std::str::FromStr for ToleranceNaming {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "auto" => Ok(ToleranceNaming::Auto),
+            "always" => Ok(ToleranceNaming::Always),
+            "never" => Ok(ToleranceNaming::Never),
+            _ => Err(format!("Unknown tolerance naming '{}'. Expected auto, always or never", value)),
+        }
+    }
+}
+
+/// Values of a `--values` argument, a comma-separated list such as
+/// "1k,4.7k,10k" or a file of them, comma-separated or one per line, with
+/// `#` comments
+pub(crate) fn value_list(values: &str) -> Result<Vec<component::Resistance>, String> {
+    let path = Path::new(values);
+    let content = if path.is_file() {
+        fs::read_to_string(path).map_err(|e| AtlantixError::io("read", path, e))?
+    } else {
+        values.to_string()
+    };
+    let mut list = content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .flat_map(|line| line.split(','))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::parse)
+        .collect::<Result<Vec<component::Resistance>, _>>()?;
+    if list.is_empty() {
+        return Err(format!("No values in '{}'", values));
+    }
+    list.sort();
+    list.dedup();
+    Ok(list)
+}
+
+/// The value of the series with base values `base_values` nearest to
+/// `value`, `value` itself when the series has it
+pub(crate) fn nearest_series_value(base_values: &[f64], value: component::Resistance) -> component::Resistance {
+    let Some((base, decade)) = component::split_decade(value.ohms()) else {
+        return value;
+    };
+    // 10.0 stands for the first value of the next decade
+    let nearest = base_values
+        .iter()
+        .chain([10.0].iter())
+        .min_by(|a, b| (*a / base).ln().abs().total_cmp(&(*b / base).ln().abs()))
+        .copied()
+        .unwrap_or(base);
+    component::Resistance::from_ohms(nearest * decade)
+}
+
+/// Options of `aeda generate resistors`, defaulting as the CLI does
+#[derive(Debug, Clone)]
+pub struct ResistorOptions {
+    /// E-series, or several separated by commas, E24,E96
+    pub series: Option<String>,
+    pub tolerance: Option<String>,
+    /// Several tolerances, each in its own library
+    pub tolerances: Option<String>,
+    /// Packages separated by commas
+    pub packages: String,
+    pub min_power: Option<String>,
+    /// Primary manufacturer followed by alternates, separated by commas
+    pub manufacturer: String,
+    pub automotive: bool,
+    pub symbol_style: String,
+    /// `auto`, `always` or `never`
+    pub tolerance_naming: String,
+    pub min_value: Option<String>,
+    pub max_value: Option<String>,
+    /// File listing the values to generate in place of a whole series
+    pub values: Option<String>,
+}
+
+impl Default for ResistorOptions {
+    fn default() -> Self {
+        Self {
+            series: None,
+            tolerance: None,
+            tolerances: None,
+            packages: "0603,0805,1206".into(),
+            min_power: None,
+            manufacturer: "vishay".into(),
+            automotive: false,
+            symbol_style: "european".into(),
+            tolerance_naming: "auto".into(),
+            min_value: None,
+            max_value: None,
+            values: None,
+        }
+    }
+}
+
+pub fn resistors(data_dir: &Path, options: &ResistorOptions) -> Result<(), String> {
+    let series = options.series.as_deref();
+    let tolerance = options.tolerance.as_deref();
+    let tolerances = options.tolerances.as_deref();
+    let packages = options.packages.as_str();
+    let min_power = options.min_power.as_deref();
+    let manufacturer = options.manufacturer.as_str();
+    let automotive = options.automotive;
+    let symbol_style = options.symbol_style.as_str();
+    let min_value = options.min_value.as_deref();
+    let max_value = options.max_value.as_deref();
+    let values = options.values.as_deref();
+    let manufacturers = component::manufacturer::parse_list(manufacturer)?;
+    let tolerance_naming: ToleranceNaming = options.tolerance_naming.parse()?;
+    if let Some(list) = series.filter(|series| series.contains(',')) {
+        return combined_resistors(data_dir, options, list);
+    }
+    let range = component::ValueRange::parse(min_value, max_value)?;
+    let values = values.map(value_list).transpose()?;
+    let symbol_styles: component::kicad_symbol::ResistorStyles = symbol_style.parse()?;
+    let tolerance = tolerance.map(component::tolerance::parse).transpose()?;
+    let tolerances = tolerances
+        .map(|list| list.split(',').map(component::tolerance::parse).collect::<Result<Vec<_>, _>>())
+        .transpose()?;
+    // Precision tolerances default to E192, which is only made in thin film
+    let precision = tolerance.iter().chain(tolerances.iter().flatten()).any(|t| component::tolerance::is_thin_film(t));
+    let series = series.unwrap_or(if precision { "E192" } else { "E96" });
     let base_values = get_e_series(series)?;
-    let tolerance = get_tolerance(series);
-    let packages: Vec<&str> = packages.split(',').map(|s| s.trim()).collect();
+    // A value list is checked against the series, values off it are kept
+    // but likely a typo or a part nobody stocks
+    let values: Option<Vec<component::Resistance>> = values.map(|values| {
+        for &value in &values {
+            let nearest = nearest_series_value(&base_values, value);
+            if nearest != value {
+                say!("  Warning: {} is not an {} value, the nearest is {}", value, series, nearest);
+                output::warning(format!("{} is not an {} value, the nearest is {}", value, series, nearest));
+            }
+        }
+        values.into_iter().filter(|&value| range.contains(value)).collect()
+    });
+    let parts = match &values {
+        Some(values) => values.len(),
+        None => range.values(&base_values).len(),
+    };
+    if parts == 0 {
+        return Err(format!("No {} value lies between {} and {}", series, min_value.unwrap_or("1"), max_value.unwrap_or("1M")));
+    }
+    let series_tolerance = get_tolerance(series);
+    // Parts of the same value in several tolerances need the tolerance in
+    // their names to tell them apart, R0603_10.0K_1%
+    let tolerance_in_name = match tolerance_naming {
+        ToleranceNaming::Auto => tolerances.is_some(),
+        ToleranceNaming::Always => true,
+        ToleranceNaming::Never => false,
+    };
+    let tolerances = tolerances.unwrap_or_else(|| vec![tolerance.unwrap_or(series_tolerance)]);
+    let min_power = parse_min_power(min_power)?;
+    let packages = package_list(packages);
+    let packages: Vec<&str> = packages.iter().map(String::as_str).collect();
 
-    println!("Generating {} resistor libraries...", series);
+    let _lock = CategoryLock::acquire(data_dir, "resistor")?;
+    match &values {
+        Some(values) => say!("Generating resistor libraries of {} {} values...", values.len(), series),
+        None => say!("Generating {} resistor libraries...", series),
+    }
 
     // Ensure directory exists
     let resistor_dir = data_dir.join("libraries/resistor");
-    fs::create_dir_all(&resistor_dir)
-        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    component::atomic::create_dir_all(&resistor_dir)?;
 
-    for package in &packages {
-        let name = format!("{}_{}", series, package);
+    let libraries: Vec<_> = packages.iter().flat_map(|package| tolerances.iter().map(move |tolerance| (package, tolerance))).collect();
+    let progress = output::progress((libraries.len() * parts) as u64);
+    for (package, &tolerance) in libraries {
+        progress.advance(package, 0);
+        // A tolerance other than the series default gets its own library,
+        // E192_0603_0.1 next to the 0.5% E192_0603
+        // Value lists are named Custom in place of the series, Custom_0603
+        let label = if values.is_some() { "Custom" } else { series };
+        let (mut name, mut description) = if tolerance == series_tolerance {
+            (
+                component::naming::library_name(label, package, None),
+                format!("{} Resistors in {} package", series, package_naming::describe(package)),
+            )
+        } else {
+            (
+                component::naming::library_name(label, package, Some(tolerance)),
+                format!("{} {} Resistors in {} package", series, tolerance, package_naming::describe(package)),
+            )
+        };
+        if let Some(values) = &values {
+            description = format!("{} values of {}", values.len(), description);
+        }
+        if automotive {
+            // Only manufacturers with a qualified series in this size
+            let sample = component::Resistance::from_ohms(1_000.0);
+            if manufacturers[0].aec_q200_mpn(package, sample, tolerance).is_none() {
+                say!("  Skipped: no AEC-Q200 qualified {} {} part in {}", manufacturers[0], tolerance, package);
+                output::warning(format!("Skipped: no AEC-Q200 qualified {} {} part in {}", manufacturers[0], tolerance, package));
+                continue;
+            }
+            name.push_str("_AEC");
+            description.push_str(", AEC-Q200");
+        }
+        if !range.is_full() {
+            description.push_str(&format!(", {} to {}", min_value.unwrap_or("1"), max_value.unwrap_or("1M")));
+        }
         let metric = get_metric_suffix(package);
         let footprint = format!("Resistor_SMD:R_{}{}", package, metric);
         let power = get_power_rating(package);
+        if min_power.is_some_and(|min| power < min) {
+            say!("  Skipped: {} is rated {}", package, power);
+            output::warning(format!("Skipped: {} is rated {}", package, power));
+            continue;
+        }
 
         let library = ResistorLibrary {
             name: name.clone(),
             component_type: "resistor".into(),
-            description: format!("{} Resistors in {} package", series, package),
+            description,
             package: package.to_string(),
             footprint,
             tolerance: tolerance.into(),
-            power_rating: power.into(),
+            manufacturer: manufacturers[0].to_string(),
+            alternate_manufacturers: manufacturers[1..].iter().map(|m| m.to_string()).collect(),
+            automotive,
+            power_rating: power.to_string(),
+            power_mw: power.milliwatts(),
             series: series.into(),
             pins: vec!["1".into(), "2".into()],
             prefix: "R".into(),
-            base_values: base_values.clone(),
-            multipliers: [
-                ("".into(), 1.0),
-                ("k".into(), 1000.0),
-                ("K".into(), 1000.0),
-                ("M".into(), 1_000_000.0),
-            ]
-            .into_iter()
-            .collect(),
+            base_values: if values.is_some() { Vec::new() } else { base_values.clone() },
+            multipliers: if values.is_some() { HashMap::new() } else { resistor_multipliers() },
+            values: values.iter().flatten().map(|value| value.to_string()).collect(),
+            value_notation: value_notation(),
+            symbol_style: self::symbol_style(symbol_styles.for_package(package)),
+            tolerance_in_name,
+            min_value: range.min.map(|value| value.to_string()),
+            max_value: range.max.map(|value| value.to_string()),
+            combined: Vec::new(),
+            dropped: Vec::new(),
             methods: LibraryMethods::default(),
         };
+        let dropped = dropped_parts(&library, &manufacturers, tolerance, &base_values, values.as_deref())?;
+        let library = ResistorLibrary { dropped, ..library };
+        write_resistor_library(data_dir, &library, parts)?;
+        progress.advance(package, parts as u64);
+    }
+    drop(progress);
 
-        let lib_path = resistor_dir.join(format!("{}.json", name));
-        let content = serde_json::to_string_pretty(&library)
-            .map_err(|e| format!("Failed to serialize library: {}", e))?;
+    say!("\nDone! Libraries available at: {}", resistor_dir.display());
+    Ok(())
+}
 
-        fs::write(&lib_path, content)
-            .map_err(|e| format!("Failed to write library: {}", e))?;
+/// Several series, E24,E96, combined into one library per package, each
+/// series in its own tolerance. Their parts are named with it so that the
+/// same value in two series never shares a name.
+fn combined_resistors(data_dir: &Path, options: &ResistorOptions, list: &str) -> Result<(), String> {
+    if options.tolerance.is_some() || options.tolerances.is_some() || options.values.is_some() {
+        return Err("Several series each take their own tolerance, they cannot be combined with --tolerance, --tolerances or --values".into());
+    }
+    if options.tolerance_naming.parse::<ToleranceNaming>()? == ToleranceNaming::Never {
+        return Err("Several series share values, their part names need the tolerance and cannot use --tolerance-naming never".into());
+    }
+    let mut members = list
+        .split(',')
+        .map(|series| {
+            let series = series.trim().to_uppercase();
+            Ok(CombinedSeries { base_values: get_e_series(&series)?, tolerance: get_tolerance(&series).into(), series })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    // Smallest series first, one listed twice is kept once
+    members.sort_by_key(|member| member.base_values.len());
+    members.dedup_by(|a, b| a.series == b.series);
 
-        // Update manifest
-        update_manifest(
-            data_dir,
-            "resistor",
-            &name,
-            &format!("resistor/{}.json", name),
-        )?;
+    let manufacturers = component::manufacturer::parse_list(&options.manufacturer)?;
+    let range = component::ValueRange::parse(options.min_value.as_deref(), options.max_value.as_deref())?;
+    let parts: usize = members.iter().map(|member| range.values(&member.base_values).len()).sum();
+    if parts == 0 {
+        return Err(format!(
+            "No {} value lies between {} and {}",
+            list,
+            options.min_value.as_deref().unwrap_or("1"),
+            options.max_value.as_deref().unwrap_or("1M")
+        ));
+    }
+    let symbol_styles: component::kicad_symbol::ResistorStyles = options.symbol_style.parse()?;
+    let min_power = parse_min_power(options.min_power.as_deref())?;
+    let series: Vec<&str> = members.iter().map(|member| member.series.as_str()).collect();
+    let tolerances: Vec<&str> = members.iter().map(|member| member.tolerance.as_str()).collect();
+    let described: Vec<String> = members.iter().map(|member| format!("{} {}", member.series, member.tolerance)).collect();
+
+    let _lock = CategoryLock::acquire(data_dir, "resistor")?;
+    say!("Generating combined {} resistor libraries...", series.join(" and "));
+
+    let resistor_dir = data_dir.join("libraries/resistor");
+    component::atomic::create_dir_all(&resistor_dir)?;
+
+    let packages = package_list(&options.packages);
+    let progress = output::progress((packages.len() * parts) as u64);
+    for package in &packages {
+        progress.advance(package, 0);
+        let mut name = component::naming::library_name(&series.join("_"), package, None);
+        let mut description = format!("{} Resistors in {} package", described.join(" and "), package_naming::describe(package));
+        if options.automotive {
+            // Only manufacturers with a qualified series in this size
+            let sample = component::Resistance::from_ohms(1_000.0);
+            if let Some(tolerance) = tolerances.iter().find(|&&tolerance| manufacturers[0].aec_q200_mpn(package, sample, tolerance).is_none()) {
+                say!("  Skipped: no AEC-Q200 qualified {} {} part in {}", manufacturers[0], tolerance, package);
+                output::warning(format!("Skipped: no AEC-Q200 qualified {} {} part in {}", manufacturers[0], tolerance, package));
+                continue;
+            }
+            name.push_str("_AEC");
+            description.push_str(", AEC-Q200");
+        }
+        if !range.is_full() {
+            description.push_str(&format!(
+                ", {} to {}",
+                options.min_value.as_deref().unwrap_or("1"),
+                options.max_value.as_deref().unwrap_or("1M")
+            ));
+        }
+        let power = get_power_rating(package);
+        if min_power.is_some_and(|min| power < min) {
+            say!("  Skipped: {} is rated {}", package, power);
+            output::warning(format!("Skipped: {} is rated {}", package, power));
+            continue;
+        }
+
+        let library = ResistorLibrary {
+            name,
+            component_type: "resistor".into(),
+            description,
+            package: package.to_string(),
+            footprint: format!("Resistor_SMD:R_{}{}", package, get_metric_suffix(package)),
+            tolerance: tolerances.join("/"),
+            manufacturer: manufacturers[0].to_string(),
+            alternate_manufacturers: manufacturers[1..].iter().map(|m| m.to_string()).collect(),
+            automotive: options.automotive,
+            power_rating: power.to_string(),
+            power_mw: power.milliwatts(),
+            series: series.join("+"),
+            pins: vec!["1".into(), "2".into()],
+            prefix: "R".into(),
+            base_values: Vec::new(),
+            multipliers: resistor_multipliers(),
+            values: Vec::new(),
+            value_notation: value_notation(),
+            symbol_style: self::symbol_style(symbol_styles.for_package(package)),
+            tolerance_in_name: true,
+            min_value: range.min.map(|value| value.to_string()),
+            max_value: range.max.map(|value| value.to_string()),
+            combined: members.clone(),
+            dropped: Vec::new(),
+            methods: LibraryMethods::default(),
+        };
+        let mut dropped = Vec::new();
+        for member in &members {
+            dropped.extend(dropped_parts(&library, &manufacturers, get_tolerance(&member.series), &member.base_values, None)?);
+        }
+        let library = ResistorLibrary { dropped, ..library };
+        write_resistor_library(data_dir, &library, parts)?;
+        progress.advance(package, parts as u64);
+    }
+    drop(progress);
 
-        println!("  Created: resistor::{} ({} base values)", name, base_values.len());
+    say!("\nDone! Libraries available at: {}", resistor_dir.display());
+    Ok(())
+}
+
+/// Whether the part hook dropped all `parts` of library `name`, which is
+/// then left out
+fn all_dropped(name: &str, parts: usize) -> bool {
+    if parts > 0 {
+        return false;
+    }
+    say!("  Skipped: the part hook dropped every part of {}", name);
+    output::warning(format!("Skipped: the part hook dropped every part of {}", name));
+    true
+}
+
+/// Names of the parts of one series of a resistor library that the part
+/// hook drops, none without a hook. `values` is the list of a library
+/// generated from one.
+fn dropped_parts(
+    library: &ResistorLibrary,
+    manufacturers: &[component::manufacturer::Manufacturer],
+    tolerance: &'static str,
+    base_values: &[f64],
+    values: Option<&[component::Resistance]>,
+) -> Result<Vec<String>, String> {
+    let range = component::ValueRange::parse(library.min_value.as_deref(), library.max_value.as_deref())?;
+    let (base_values, decades) = match values {
+        Some(values) => (values.iter().map(|value| value.ohms()).collect(), vec![1.0]),
+        None => (base_values.to_vec(), range.decades()),
+    };
+    let mut resistor = component::Resistor::with_values(base_values.len(), library.package.clone(), base_values);
+    resistor.set_manufacturer(manufacturers[0].clone());
+    resistor.set_alternate_manufacturers(manufacturers[1..].to_vec());
+    resistor.set_tolerance(tolerance);
+    resistor.set_automotive(library.automotive);
+    resistor.set_tolerance_in_name(library.tolerance_in_name);
+    resistor.set_value_range(range);
+    Ok(resistor.dropped_parts(&decades))
+}
+
+/// Write a resistor library of `parts` values, less those the part hook
+/// dropped, and list it in the manifest. A library whose every part was
+/// dropped is left out.
+fn write_resistor_library(data_dir: &Path, library: &ResistorLibrary, parts: usize) -> Result<(), String> {
+    let name = &library.name;
+    let parts = parts.saturating_sub(library.dropped.len());
+    if all_dropped(name, parts) {
+        return Ok(());
     }
+    let lib_path = data_dir.join("libraries/resistor").join(format!("{}.json", name));
+    let content = serde_json::to_string_pretty(library)
+        .map_err(|e| format!("Failed to serialize library: {}", e))?;
 
-    println!("\nDone! Libraries available at: {}", resistor_dir.display());
+    component::atomic::write(&lib_path, content)?;
+
+    // Update manifest
+    update_manifest(
+        data_dir,
+        "resistor",
+        name,
+        &format!("resistor/{}.json", name),
+    )?;
+
+    say!("  Created: resistor::{} ({} values)", name, parts);
+    output::created(&format!("resistor::{}", name), &lib_path, parts);
     Ok(())
 }
 
 pub fn capacitors(data_dir: &Path, dielectric: &str, packages: &str) -> Result<(), String> {
-    let packages: Vec<&str> = packages.split(',').map(|s| s.trim()).collect();
+    let packages = package_list(packages);
+    let packages: Vec<&str> = packages.iter().map(String::as_str).collect();
 
-    println!("Generating {} capacitor libraries...", dielectric);
+    let _lock = CategoryLock::acquire(data_dir, "capacitor")?;
+    say!("Generating {} capacitor libraries...", dielectric);
 
     // Ensure directory exists
     let capacitor_dir = data_dir.join("libraries/capacitor");
-    fs::create_dir_all(&capacitor_dir)
-        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    component::atomic::create_dir_all(&capacitor_dir)?;
 
     // Standard capacitor values
     let values = vec![
@@ -266,16 +976,17 @@ pub fn capacitors(data_dir: &Path, dielectric: &str, packages: &str) -> Result<(
         let name = format!("{}_{}", dielectric, package);
         let metric = get_metric_suffix(package);
         let footprint = format!("Capacitor_SMD:C_{}{}", package, metric);
+        let tolerance = "10%";
 
         let library = CapacitorLibrary {
             name: name.clone(),
             component_type: "capacitor".into(),
-            description: format!("{} MLCC Capacitors in {} package", dielectric, package),
+            description: format!("{} MLCC Capacitors in {} package", dielectric, package_naming::describe(package)),
             package: package.to_string(),
             footprint,
             dielectric: dielectric.into(),
             voltage_rating: "16V".into(),
-            tolerance: "10%".into(),
+            tolerance: tolerance.into(),
             pins: vec!["1".into(), "2".into()],
             prefix: "C".into(),
             values: values.iter().map(|s| s.to_string()).collect(),
@@ -287,6 +998,20 @@ pub fn capacitors(data_dir: &Path, dielectric: &str, packages: &str) -> Result<(
             ]
             .into_iter()
             .collect(),
+            value_codes: values
+                .iter()
+                .filter_map(|value| {
+                    let code = component::marking::parse_capacitance(value).and_then(component::marking::capacitor_code)?;
+                    Some((value.to_string(), code))
+                })
+                .collect(),
+            ipns: values
+                .iter()
+                .filter_map(|value| {
+                    let ipn = component::ipn::assign("capacitor", package, &capacitor_name(package, dielectric, value), tolerance)?;
+                    Some((value.to_string(), ipn))
+                })
+                .collect(),
             methods: LibraryMethods::default(),
         };
 
@@ -294,8 +1019,7 @@ pub fn capacitors(data_dir: &Path, dielectric: &str, packages: &str) -> Result<(
         let content = serde_json::to_string_pretty(&library)
             .map_err(|e| format!("Failed to serialize library: {}", e))?;
 
-        fs::write(&lib_path, content)
-            .map_err(|e| format!("Failed to write library: {}", e))?;
+        component::atomic::write(&lib_path, content)?;
 
         // Update manifest
         update_manifest(
@@ -305,9 +1029,732 @@ pub fn capacitors(data_dir: &Path, dielectric: &str, packages: &str) -> Result<(
             &format!("capacitor/{}.json", name),
         )?;
 
-        println!("  Created: capacitor::{} ({} values)", name, values.len());
+        say!("  Created: capacitor::{} ({} values)", name, values.len());
+        output::created(&format!("capacitor::{}", name), &lib_path, values.len());
+    }
+
+    say!("\nDone! Libraries available at: {}", capacitor_dir.display());
+    Ok(())
+}
+
+/// How `aeda generate` writes the KiCad symbol libraries of inductors,
+/// ferrites, LEDs, diodes and current-sense resistors
+#[derive(Debug, Clone, Default)]
+pub struct SymbolOutput {
+    /// Footprints the symbols reference, the generated or the stock KiCad ones
+    pub footprint_naming: FootprintNaming,
+    /// Symbol library file name without extension, with `{name}`, `{package}`
+    /// and `{category}` placeholders, e.g. `{category}/{package}`
+    pub file_name: Option<String>,
+}
+
+impl SymbolOutput {
+    pub fn new(footprint_naming: &str, file_name: Option<String>) -> Result<Self, String> {
+        Ok(Self { footprint_naming: footprint_naming.parse()?, file_name })
+    }
+
+    /// Path of the symbol library `name`, `default` without a file name
+    /// template, added to `written`. Two libraries of a run may not share one.
+    fn symbol_path(
+        &self,
+        symbols_dir: &Path,
+        written: &mut Vec<PathBuf>,
+        default: &str,
+        values: [(&str, &str); 3],
+    ) -> Result<PathBuf, String> {
+        let file_name = match &self.file_name {
+            Some(template) => component::output_path::expand(template, &values)?,
+            None => default.to_string(),
+        };
+        let path = symbols_dir.join(format!("{}.kicad_sym", file_name));
+        if written.contains(&path) {
+            return Err(format!(
+                "File name '{}' gives {} for more than one library, add {{name}}",
+                self.file_name.as_deref().unwrap_or(default),
+                path.display()
+            ));
+        }
+        if let Some(parent) = path.parent() {
+            component::atomic::create_dir_all(parent)?;
+        }
+        written.push(path.clone());
+        Ok(path)
+    }
+
+    /// The footprint a symbol references, an error when the stock KiCad
+    /// libraries have none for the package
+    fn check_footprint(&self, footprint: Option<String>, package: &str) -> Result<(), String> {
+        match (footprint, self.footprint_naming) {
+            (None, FootprintNaming::KicadOfficial) => Err(format!(
+                "No stock KiCad footprint for {}, generate it with --footprint-naming atlantix",
+                package
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Footprint directories the run writes, none with the stock footprints
+    fn footprint_dirs(&self, data_dir: &Path) -> Vec<PathBuf> {
+        match self.footprint_naming {
+            FootprintNaming::Atlantix => vec![data_dir.join("footprints")],
+            FootprintNaming::KicadOfficial => Vec::new(),
+        }
+    }
+}
+
+pub fn inductors(
+    data_dir: &Path,
+    series: &str,
+    packages: &str,
+    shielded: bool,
+    kicad_check: bool,
+    symbols: &SymbolOutput,
+) -> Result<(), String> {
+    use component::inductor::{Inductor, INDUCTOR_DECADES};
+
+    let eseries: usize = series
+        .to_uppercase()
+        .trim_start_matches('E')
+        .parse()
+        .map_err(|_| format!("Unknown inductance series: {}", series))?;
+    let packages = package_list(packages);
+    let packages: Vec<&str> = packages.iter().map(String::as_str).collect();
+
+    let _lock = CategoryLock::acquire(data_dir, "inductor")?;
+    say!("Generating {} inductor libraries...", series);
+
+    let inductor_dir = data_dir.join("libraries/inductor");
+    let symbols_dir = data_dir.join("symbols");
+    let footprint_dirs = symbols.footprint_dirs(data_dir);
+    for dir in [&inductor_dir, &symbols_dir].into_iter().chain(&footprint_dirs) {
+        component::atomic::create_dir_all(dir)?;
+    }
+
+    let mut symbol_libs = Vec::new();
+    for package in &packages {
+        let mut inductor = Inductor::new(eseries, package, shielded).ok_or_else(|| {
+            format!("Unsupported inductor series/package: {} {}", series, package)
+        })?;
+        inductor.set_footprint_naming(symbols.footprint_naming);
+        symbols.check_footprint(inductor.footprint_name(), package)?;
+        let parts = inductor.parts(&INDUCTOR_DECADES);
+        let variant = if shielded { "_Shielded" } else { "" };
+        let name = format!("{}_{}{}", series, package, variant);
+        if all_dropped(&name, parts.len()) {
+            continue;
+        }
+        let footprint = component::kicad_footprint::KicadFootprint::new_smd_inductor(package)
+            .map(|fp| format!("Inductor_SMD:{}", fp.name))
+            .unwrap_or_default();
+
+        let library = InductorLibrary {
+            name: name.clone(),
+            component_type: "inductor".into(),
+            description: format!(
+                "{} {} Inductors in {} package",
+                series,
+                if shielded { "shielded" } else { "unshielded" },
+                package_naming::describe(package)
+            ),
+            package: package.to_string(),
+            footprint,
+            series: series.into(),
+            shielded,
+            tolerance: "20%".into(),
+            pins: vec!["1".into(), "2".into()],
+            prefix: "L".into(),
+            values: parts.iter().map(|p| p.value.clone()).collect(),
+            value_suffixes: [("nH".into(), 1e-9), ("uH".into(), 1e-6), ("µH".into(), 1e-6)]
+                .into_iter()
+                .collect(),
+            parts: parts
+                .iter()
+                .map(|p| InductorEntry {
+                    value: p.value.clone(),
+                    code: p.code.clone(),
+                    mpn: p.mpn.clone(),
+                    rated_current: p.rated_current,
+                    saturation_current: p.saturation_current,
+                    dcr_mohm: p.dcr_mohm,
+                    ipn: p.ipn.clone(),
+                })
+                .collect(),
+            methods: LibraryMethods::default(),
+        };
+
+        let lib_path = inductor_dir.join(format!("{}.json", name));
+        let content = serde_json::to_string_pretty(&library)
+            .map_err(|e| format!("Failed to serialize library: {}", e))?;
+        component::atomic::write(&lib_path, content)?;
+
+        let symbol_path =
+            symbols.symbol_path(&symbols_dir, &mut symbol_libs, &format!("L_{}", name), [("name", &name), ("package", package), ("category", "inductor")])?;
+        inductor
+            .generate_kicad_symbols(&INDUCTOR_DECADES, &symbol_path.to_string_lossy())?;
+        output::written(&symbol_path);
+
+        update_manifest(data_dir, "inductor", &name, &format!("inductor/{}.json", name))?;
+
+        say!("  Created: inductor::{} ({} values)", name, parts.len());
+        output::created(&format!("inductor::{}", name), &lib_path, parts.len());
+    }
+
+    for footprints_dir in &footprint_dirs {
+        Inductor::generate_kicad_footprints(&packages, &footprints_dir.to_string_lossy())?;
+        output::written(footprints_dir);
+    }
+
+    if kicad_check {
+        super::kicad_cli::report_generated(&symbol_libs, &footprint_dirs);
+    }
+
+    say!("\nDone! Libraries available at: {}", inductor_dir.display());
+    Ok(())
+}
+
+pub fn ferrites(
+    data_dir: &Path,
+    packages: &str,
+    impedances: Option<&str>,
+    kicad_check: bool,
+    symbols: &SymbolOutput,
+) -> Result<(), String> {
+    use component::ferrite::{FerriteBead, FERRITE_IMPEDANCES, FERRITE_PACKAGES};
+
+    let impedances: Vec<f64> = match impedances {
+        Some(list) => list
+            .split(',')
+            .map(|z| z.trim().parse::<f64>().map_err(|_| format!("Invalid impedance: {}", z)))
+            .collect::<Result<_, _>>()?,
+        None => FERRITE_IMPEDANCES.to_vec(),
+    };
+    let packages = package_list(packages);
+    let packages: Vec<&str> = packages.iter().map(String::as_str).collect();
+
+    let _lock = CategoryLock::acquire(data_dir, "ferrite")?;
+    say!("Generating ferrite bead libraries...");
+
+    let ferrite_dir = data_dir.join("libraries/ferrite");
+    let symbols_dir = data_dir.join("symbols");
+    let footprint_dirs = symbols.footprint_dirs(data_dir);
+    for dir in [&ferrite_dir, &symbols_dir].into_iter().chain(&footprint_dirs) {
+        component::atomic::create_dir_all(dir)?;
+    }
+
+    let mut symbol_libs = Vec::new();
+    for package in &packages {
+        let mut bead = FerriteBead::new(package, impedances.clone())
+            .ok_or_else(|| AtlantixError::invalid_package("ferrite bead", package, FERRITE_PACKAGES))?;
+        bead.set_footprint_naming(symbols.footprint_naming);
+        symbols.check_footprint(bead.footprint_name(), package)?;
+        let parts = bead.parts();
+        let name = format!("FB_{}", package);
+        if all_dropped(&name, parts.len()) {
+            continue;
+        }
+
+        let library = FerriteLibrary {
+            name: name.clone(),
+            component_type: "ferrite".into(),
+            description: format!("Ferrite beads in {} package, impedance @ 100MHz", package_naming::describe(package)),
+            package: package.to_string(),
+            footprint: format!("Inductor_SMD:L_{}{}", package, get_metric_suffix(package)),
+            pins: vec!["1".into(), "2".into()],
+            prefix: "FB".into(),
+            values: parts.iter().map(|p| p.value.clone()).collect(),
+            parts: parts
+                .iter()
+                .map(|p| FerriteEntry {
+                    value: p.value.clone(),
+                    impedance_ohms: p.impedance_ohms,
+                    rated_current: p.rated_current,
+                    dcr_mohm: p.dcr_mohm,
+                    murata_mpn: p.murata_mpn.clone(),
+                    tdk_mpn: p.tdk_mpn.clone(),
+                    ipn: p.ipn.clone(),
+                })
+                .collect(),
+            methods: LibraryMethods::default(),
+        };
+
+        let lib_path = ferrite_dir.join(format!("{}.json", name));
+        let content = serde_json::to_string_pretty(&library)
+            .map_err(|e| format!("Failed to serialize library: {}", e))?;
+        component::atomic::write(&lib_path, content)?;
+
+        let symbol_path =
+            symbols.symbol_path(&symbols_dir, &mut symbol_libs, &name, [("name", &name), ("package", package), ("category", "ferrite")])?;
+        bead.generate_kicad_symbols(&symbol_path.to_string_lossy())?;
+        output::written(&symbol_path);
+
+        update_manifest(data_dir, "ferrite", &name, &format!("ferrite/{}.json", name))?;
+
+        say!("  Created: ferrite::{} ({} values)", name, parts.len());
+        output::created(&format!("ferrite::{}", name), &lib_path, parts.len());
+    }
+
+    for footprints_dir in &footprint_dirs {
+        FerriteBead::generate_kicad_footprints(&packages, &footprints_dir.to_string_lossy())?;
+        output::written(footprints_dir);
+    }
+
+    if kicad_check {
+        super::kicad_cli::report_generated(&symbol_libs, &footprint_dirs);
+    }
+
+    say!("\nDone! Libraries available at: {}", ferrite_dir.display());
+    Ok(())
+}
+
+pub fn leds(data_dir: &Path, packages: &str, colors: &str, kicad_check: bool, symbols: &SymbolOutput) -> Result<(), String> {
+    use component::led::{Led, LedColor, LED_PACKAGES};
+
+    let colors: Vec<LedColor> = colors
+        .split(',')
+        .map(|c| c.parse())
+        .collect::<Result<_, _>>()?;
+    let packages = package_list(packages);
+    let packages: Vec<&str> = packages.iter().map(String::as_str).collect();
+
+    let _lock = CategoryLock::acquire(data_dir, "led")?;
+    say!("Generating LED libraries...");
+
+    let led_dir = data_dir.join("libraries/led");
+    let symbols_dir = data_dir.join("symbols");
+    let footprint_dirs = symbols.footprint_dirs(data_dir);
+    for dir in [&led_dir, &symbols_dir].into_iter().chain(&footprint_dirs) {
+        component::atomic::create_dir_all(dir)?;
+    }
+
+    let mut symbol_libs = Vec::new();
+    for package in &packages {
+        let mut led = Led::new(package, colors.clone())
+            .ok_or_else(|| AtlantixError::invalid_package("LED", package, LED_PACKAGES))?;
+        led.set_footprint_naming(symbols.footprint_naming);
+        symbols.check_footprint(led.footprint_name(), package)?;
+        let parts = led.parts();
+        let name = format!("LED_{}", package);
+        if all_dropped(&name, parts.len()) {
+            continue;
+        }
+
+        // Pin 1 is the cathode, pin 2 the anode
+        let library = LedLibrary {
+            name: name.clone(),
+            component_type: "led".into(),
+            description: format!("Chip LEDs in {} package", package_naming::describe(package)),
+            package: package.to_string(),
+            footprint: format!("LED_SMD:LED_{}{}", package, get_metric_suffix(package)),
+            pins: vec!["K".into(), "A".into()],
+            prefix: "D".into(),
+            values: parts.iter().map(|p| p.color.to_string()).collect(),
+            parts: parts
+                .iter()
+                .map(|p| LedEntry {
+                    color: p.color.to_string(),
+                    wavelength_nm: p.wavelength_nm,
+                    forward_voltage: p.forward_voltage,
+                    intensity_mcd: p.intensity_mcd,
+                    kingbright_mpn: p.kingbright_mpn.clone(),
+                    liteon_mpn: p.liteon_mpn.clone(),
+                    digikey_pn: p.digikey_pn(),
+                    ipn: p.ipn.clone(),
+                })
+                .collect(),
+            methods: LibraryMethods::default(),
+        };
+
+        let lib_path = led_dir.join(format!("{}.json", name));
+        let content = serde_json::to_string_pretty(&library)
+            .map_err(|e| format!("Failed to serialize library: {}", e))?;
+        component::atomic::write(&lib_path, content)?;
+
+        let symbol_path =
+            symbols.symbol_path(&symbols_dir, &mut symbol_libs, &name, [("name", &name), ("package", package), ("category", "led")])?;
+        led.generate_kicad_symbols(&symbol_path.to_string_lossy())?;
+        output::written(&symbol_path);
+
+        update_manifest(data_dir, "led", &name, &format!("led/{}.json", name))?;
+
+        say!("  Created: led::{} ({} colors)", name, parts.len());
+        output::created(&format!("led::{}", name), &lib_path, parts.len());
+    }
+
+    for footprints_dir in &footprint_dirs {
+        Led::generate_kicad_footprints(&packages, &footprints_dir.to_string_lossy())?;
+        output::written(footprints_dir);
+    }
+
+    if kicad_check {
+        super::kicad_cli::report_generated(&symbol_libs, &footprint_dirs);
+    }
+
+    say!("\nDone! Libraries available at: {}", led_dir.display());
+    Ok(())
+}
+
+pub fn diodes(data_dir: &Path, kinds: &str, packages: &str, kicad_check: bool, symbols: &SymbolOutput) -> Result<(), String> {
+    use component::diode::{Diode, DiodeKind, DIODE_PACKAGES};
+
+    let kinds: Vec<DiodeKind> = kinds
+        .split(',')
+        .map(|k| k.parse())
+        .collect::<Result<_, _>>()?;
+    let packages = package_list(packages);
+    let packages: Vec<&str> = packages.iter().map(String::as_str).collect();
+    if let Some(unknown) = packages.iter().find(|p| !DIODE_PACKAGES.contains(p)) {
+        return Err(AtlantixError::invalid_package("diode", unknown, DIODE_PACKAGES).into());
+    }
+
+    let _lock = CategoryLock::acquire(data_dir, "diode")?;
+    say!("Generating diode libraries...");
+
+    let diode_dir = data_dir.join("libraries/diode");
+    let symbols_dir = data_dir.join("symbols");
+    let footprint_dirs = symbols.footprint_dirs(data_dir);
+    for dir in [&diode_dir, &symbols_dir].into_iter().chain(&footprint_dirs) {
+        component::atomic::create_dir_all(dir)?;
+    }
+
+    let mut symbol_libs = Vec::new();
+    for kind in &kinds {
+        for package in &packages {
+            let Some(mut diode) = Diode::new(*kind, package) else {
+                say!("  Skipped: no {} diodes in {}", kind, package);
+                output::warning(format!("Skipped: no {} diodes in {}", kind, package));
+                continue;
+            };
+            diode.set_footprint_naming(symbols.footprint_naming);
+            symbols.check_footprint(diode.footprint_name(), package)?;
+            let parts = diode.parts();
+            let name = format!("{}_{}", kind.name_prefix(), package);
+            if all_dropped(&name, parts.len()) {
+                continue;
+            }
+
+            // Pin 1 is the cathode, pin 2 the anode
+            let library = DiodeLibrary {
+                name: name.clone(),
+                component_type: "diode".into(),
+                kind: kind.to_string(),
+                description: format!("{} diodes in {} package", kind, package_naming::describe(package)),
+                package: package.to_string(),
+                footprint: format!("Diode_SMD:D_{}", package),
+                pins: vec!["K".into(), "A".into()],
+                prefix: "D".into(),
+                values: parts.iter().map(|p| p.value.clone()).collect(),
+                parts: parts
+                    .iter()
+                    .map(|p| DiodeEntry {
+                        value: p.value.clone(),
+                        voltage: p.voltage,
+                        rating: p.rating.clone(),
+                        nexperia_mpn: p.nexperia_mpn.clone(),
+                        onsemi_mpn: p.onsemi_mpn.clone(),
+                        ipn: p.ipn.clone(),
+                    })
+                    .collect(),
+                methods: LibraryMethods::default(),
+            };
+
+            let lib_path = diode_dir.join(format!("{}.json", name));
+            let content = serde_json::to_string_pretty(&library)
+                .map_err(|e| format!("Failed to serialize library: {}", e))?;
+            component::atomic::write(&lib_path, content)?;
+
+            let symbol_path =
+                symbols.symbol_path(&symbols_dir, &mut symbol_libs, &name, [("name", &name), ("package", package), ("category", "diode")])?;
+            diode.generate_kicad_symbols(&symbol_path.to_string_lossy())?;
+            output::written(&symbol_path);
+
+            update_manifest(data_dir, "diode", &name, &format!("diode/{}.json", name))?;
+
+            say!("  Created: diode::{} ({} parts)", name, parts.len());
+            output::created(&format!("diode::{}", name), &lib_path, parts.len());
+        }
+    }
+
+    for footprints_dir in &footprint_dirs {
+        Diode::generate_kicad_footprints(&packages, &footprints_dir.to_string_lossy())?;
+        output::written(footprints_dir);
+    }
+
+    if kicad_check {
+        super::kicad_cli::report_generated(&symbol_libs, &footprint_dirs);
+    }
+
+    say!("\nDone! Libraries available at: {}", diode_dir.display());
+    Ok(())
+}
+
+pub fn current_sense(
+    data_dir: &Path,
+    packages: &str,
+    kelvin: bool,
+    min_power: Option<&str>,
+    kicad_check: bool,
+    symbols: &SymbolOutput,
+) -> Result<(), String> {
+    use component::current_sense::{CurrentSense, CURRENT_SENSE_PACKAGES};
+    use component::kicad_footprint::KicadFootprint;
+
+    let min_power = parse_min_power(min_power)?;
+    let packages = package_list(packages);
+    let packages: Vec<&str> = packages.iter().map(String::as_str).collect();
+
+    let _lock = CategoryLock::acquire(data_dir, "resistor")?;
+    say!("Generating current-sense resistor libraries...");
+
+    let resistor_dir = data_dir.join("libraries/resistor");
+    let symbols_dir = data_dir.join("symbols");
+    let footprint_dirs = symbols.footprint_dirs(data_dir);
+    for dir in [&resistor_dir, &symbols_dir].into_iter().chain(&footprint_dirs) {
+        component::atomic::create_dir_all(dir)?;
+    }
+
+    let mut symbol_libs = Vec::new();
+    for package in &packages {
+        let mut shunt = CurrentSense::new(package, kelvin).ok_or_else(|| {
+            if kelvin {
+                AtlantixError::Invalid(format!("No Kelvin (4-terminal) current-sense resistor in package: {}", package))
+            } else {
+                AtlantixError::invalid_package("current-sense", package, CURRENT_SENSE_PACKAGES)
+            }
+        })?;
+        shunt.set_footprint_naming(symbols.footprint_naming);
+        symbols.check_footprint(shunt.footprint_name(), package)?;
+        let parts = shunt.parts();
+        let name = if kelvin {
+            format!("CS_{}_Kelvin", package)
+        } else {
+            format!("CS_{}", package)
+        };
+        if all_dropped(&name, parts.len()) {
+            continue;
+        }
+        let power = parts[0].power;
+        if min_power.is_some_and(|min| power < min) {
+            say!("  Skipped: {} is rated {}", package, power);
+            output::warning(format!("Skipped: {} is rated {}", package, power));
+            continue;
+        }
+        let footprint = KicadFootprint::new_smd_current_sense(package, kelvin)
+            .map(|fp| format!("Resistor_SMD:{}", fp.name))
+            .unwrap_or_default();
+        let pins = if kelvin { vec!["1", "2", "3", "4"] } else { vec!["1", "2"] };
+
+        let library = CurrentSenseLibrary {
+            name: name.clone(),
+            component_type: "resistor".into(),
+            description: format!("Current-sense resistors in {} package", package_naming::describe(package)),
+            package: package.to_string(),
+            footprint,
+            kelvin,
+            tolerance: "1%".into(),
+            power_rating: power.to_string(),
+            power_mw: power.milliwatts(),
+            pins: pins.into_iter().map(String::from).collect(),
+            prefix: "R".into(),
+            values: parts.iter().map(|p| p.value.clone()).collect(),
+            parts: parts
+                .iter()
+                .map(|p| CurrentSenseEntry {
+                    value: p.value.clone(),
+                    ohms: p.ohms,
+                    vishay_mpn: p.vishay_mpn.clone(),
+                    bourns_mpn: p.bourns_mpn.clone(),
+                    ipn: p.ipn.clone(),
+                })
+                .collect(),
+            methods: LibraryMethods::default(),
+        };
+
+        let lib_path = resistor_dir.join(format!("{}.json", name));
+        let content = serde_json::to_string_pretty(&library)
+            .map_err(|e| format!("Failed to serialize library: {}", e))?;
+        component::atomic::write(&lib_path, content)?;
+
+        let symbol_path =
+            symbols.symbol_path(&symbols_dir, &mut symbol_libs, &name, [("name", &name), ("package", package), ("category", "resistor")])?;
+        shunt.generate_kicad_symbols(&symbol_path.to_string_lossy())?;
+        output::written(&symbol_path);
+
+        update_manifest(data_dir, "resistor", &name, &format!("resistor/{}.json", name))?;
+
+        say!("  Created: resistor::{} ({} values)", name, parts.len());
+        output::created(&format!("resistor::{}", name), &lib_path, parts.len());
+    }
+
+    for footprints_dir in &footprint_dirs {
+        CurrentSense::generate_kicad_footprints(&packages, kelvin, &footprints_dir.to_string_lossy())?;
+        output::written(footprints_dir);
+    }
+
+    if kicad_check {
+        super::kicad_cli::report_generated(&symbol_libs, &footprint_dirs);
     }
 
-    println!("\nDone! Libraries available at: {}", capacitor_dir.display());
+    say!("\nDone! Libraries available at: {}", resistor_dir.display());
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trims_resistors_to_a_value_range() {
+        let dir = std::env::temp_dir().join(format!("aeda-generate-range-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let options = ResistorOptions {
+            series: Some("E24".into()),
+            packages: "0603".into(),
+            min_value: Some("10".into()),
+            max_value: Some("1M".into()),
+            ..Default::default()
+        };
+        resistors(&dir, &options).unwrap();
+        let content = fs::read_to_string(dir.join("libraries/resistor/E24_0603.json")).unwrap();
+        let library: serde_json::Value = serde_json::from_str(&content).unwrap();
+        let values = super::super::export::library_resistances(&library);
+        assert_eq!(values.len(), 24 * 5 + 1);
+        assert_eq!(values.first(), Some(&component::Resistance::from_ohms(10.0)));
+        assert_eq!(values.last(), Some(&component::Resistance::from_ohms(1_000_000.0)));
+
+        let options = ResistorOptions { min_value: Some("1M".into()), max_value: Some("10".into()), ..options };
+        let err = resistors(&dir, &options);
+        assert!(err.unwrap_err().contains("above the maximum"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn generates_a_value_list() {
+        let dir = std::env::temp_dir().join(format!("aeda-generate-values-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let list = dir.join("preferred.txt");
+        fs::write(&list, "# Jellybeans\n10k, 1k\n4.7k\n4.8k\n10k\n").unwrap();
+        let options = ResistorOptions {
+            series: Some("E24".into()),
+            packages: "0603".into(),
+            values: list.to_str().map(String::from),
+            ..Default::default()
+        };
+        resistors(&dir, &options).unwrap();
+        let content = fs::read_to_string(dir.join("libraries/resistor/Custom_0603.json")).unwrap();
+        let library: serde_json::Value = serde_json::from_str(&content).unwrap();
+        let values: Vec<String> = super::super::export::library_resistances(&library).iter().map(|value| value.to_string()).collect();
+        assert_eq!(values, ["1.00K", "4.70K", "4.80K", "10.0K"]);
+
+        let base_values = get_e_series("E24").unwrap();
+        let nearest = nearest_series_value(&base_values, "4.8k".parse().unwrap());
+        assert_eq!(nearest, component::Resistance::from_ohms(4_700.0));
+        let nearest = nearest_series_value(&base_values, "9.8k".parse().unwrap());
+        assert_eq!(nearest, component::Resistance::from_ohms(10_000.0));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn combines_several_series_into_one_library() {
+        let dir = std::env::temp_dir().join(format!("aeda-generate-series-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let options = ResistorOptions { series: Some("E96,E24,E96".into()), packages: "0603".into(), ..Default::default() };
+        resistors(&dir, &options).unwrap();
+        assert!(!dir.join("libraries/resistor/E24_0603.json").exists());
+        assert!(!dir.join("libraries/resistor/E96_0603.json").exists());
+        let content = fs::read_to_string(dir.join("libraries/resistor/E24_E96_0603.json")).unwrap();
+        let library: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(library["series"], "E24+E96");
+
+        let rows = super::super::export::part_rows(&dir).unwrap();
+        assert!(rows.iter().any(|row| row.name == "R0603_10.0K_5%"));
+        assert!(rows.iter().any(|row| row.name == "R0603_10.0K_1%"));
+        let mut names: Vec<&str> = rows.iter().map(|row| row.name.as_str()).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), rows.len());
+
+        let err = resistors(&dir, &ResistorOptions { tolerance: Some("1%".into()), ..options.clone() });
+        assert!(err.unwrap_err().contains("own tolerance"));
+        let err = resistors(&dir, &ResistorOptions { tolerance_naming: "never".into(), ..options });
+        assert!(err.unwrap_err().contains("need the tolerance"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    #[test]
+    fn numbers_the_parts_of_every_generator() {
+        use component::ipn::Numbering;
+        use std::sync::{Arc, Mutex};
+
+        let dir = std::env::temp_dir().join(format!("aeda-generate-ipn-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let numbering = Numbering::load("ATX-{family}-{seq:4}".parse().unwrap(), &dir.join(component::ipn::REGISTRY)).unwrap();
+        let settings = component::settings::Settings { ipn: Some(Arc::new(Mutex::new(numbering))), ..Default::default() };
+        settings.scope(|| {
+            leds(&dir, "0603", "red,green", false, &SymbolOutput::default()).unwrap();
+            capacitors(&dir, "X7R", "0603").unwrap();
+        });
+
+        let library = |path: &str| -> serde_json::Value { serde_json::from_str(&fs::read_to_string(dir.join(path)).unwrap()).unwrap() };
+        let leds = library("libraries/led/LED_0603.json");
+        assert_eq!(leds["parts"][0]["ipn"], "ATX-D-0001");
+        assert_eq!(leds["parts"][1]["ipn"], "ATX-D-0002");
+        assert_eq!(library("libraries/capacitor/X7R_0603.json")["ipns"]["10pF"], "ATX-C-0001");
+        // The symbols carry the numbers of the library
+        let symbols = fs::read_to_string(dir.join("symbols/LED_0603.kicad_sym")).unwrap();
+        assert!(symbols.contains("\"IPN\" \"ATX-D-0002\""));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn leaves_the_parts_the_hook_skips_out_of_every_library() {
+        use component::ipn::Numbering;
+        use std::sync::{Arc, Mutex};
+
+        let dir = std::env::temp_dir().join(format!("aeda-generate-hook-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let hook = super::super::script::ScriptHook::compile(
+            r#"
+            if part.value == "2.20K" || part.value == "Green" { part.skip = true; }
+            if part.value == "Red" { part.mpn = "APT1608EC"; }
+            "#,
+        )
+        .unwrap();
+        let numbering = Numbering::load("ATX-{family}-{seq:4}".parse().unwrap(), &dir.join(component::ipn::REGISTRY)).unwrap();
+        let settings = component::settings::Settings {
+            hook: Some(Arc::new(hook)),
+            ipn: Some(Arc::new(Mutex::new(numbering))),
+            ..Default::default()
+        };
+        settings.scope(|| {
+            let options = ResistorOptions {
+                series: Some("E24".into()),
+                packages: "0603".into(),
+                min_value: Some("1K".into()),
+                max_value: Some("9.99K".into()),
+                ..Default::default()
+            };
+            resistors(&dir, &options).unwrap();
+            leds(&dir, "0603", "red,green", false, &SymbolOutput::default()).unwrap();
+        });
+
+        let library = |path: &str| -> serde_json::Value { serde_json::from_str(&fs::read_to_string(dir.join(path)).unwrap()).unwrap() };
+        let resistors = library("libraries/resistor/E24_0603.json");
+        assert_eq!(resistors["dropped"], serde_json::json!(["R0603_2.20K"]));
+        let values = super::super::export::library_resistances(&resistors);
+        assert_eq!(values.len(), 23);
+        assert!(!values.contains(&component::Resistance::from_ohms(2_200.0)));
+
+        // The library and the symbols agree on the parts and their edits
+        let leds = library("libraries/led/LED_0603.json");
+        assert_eq!(leds["parts"].as_array().unwrap().len(), 1);
+        assert_eq!(leds["parts"][0]["kingbright_mpn"], "APT1608EC");
+        let symbols = fs::read_to_string(dir.join("symbols/LED_0603.kicad_sym")).unwrap();
+        assert!(symbols.contains("APT1608EC") && !symbols.contains("LED0603_Green"));
+        // Skipped parts use up no internal part numbers
+        assert_eq!(leds["parts"][0]["ipn"], "ATX-D-0001");
+        assert!(symbols.contains("\"IPN\" \"ATX-D-0001\""));
+        let saved = settings.ipn.as_ref().unwrap().lock().unwrap().save().unwrap();
+        assert_eq!(saved, Some(1));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,121 @@
+//! Identify a part from its body marking
+
+use super::export::library_resistances;
+use super::generate::load_manifest;
+use component::marking::{self, MarkingScheme};
+use component::Resistance;
+use std::fs;
+use std::path::Path;
+
+/// Parsed library JSON of every library in `category`, by library name
+fn libraries(data_dir: &Path, category: &str) -> Result<Vec<(String, serde_json::Value)>, String> {
+    let manifest = load_manifest(data_dir)?;
+    let mut libraries = Vec::new();
+    for (name, path) in manifest.libraries.get(category).into_iter().flatten() {
+        let Ok(content) = fs::read_to_string(data_dir.join("libraries").join(path)) else {
+            continue;
+        };
+        let Ok(library) = serde_json::from_str::<serde_json::Value>(&content) else {
+            continue;
+        };
+        libraries.push((name.clone(), library));
+    }
+    Ok(libraries)
+}
+
+/// Library parts holding `resistance`, as `resistor::E96_0603 -> R0603_49.9`
+fn library_matches(data_dir: &Path, resistance: Resistance) -> Result<Vec<String>, String> {
+    let mut matches = Vec::new();
+    for (name, library) in libraries(data_dir, "resistor")? {
+        if library_resistances(&library).contains(&resistance) {
+            let package = library["package"].as_str().unwrap_or_default();
+            matches.push(format!("resistor::{} -> R{}_{}", name, package, resistance.label()));
+        }
+    }
+    matches.sort();
+    Ok(matches)
+}
+
+/// Library values accepted by `matches_value`, as `capacitor::X7R_0603 -> 100nF`
+fn value_matches(data_dir: &Path, category: &str, matches_value: impl Fn(&str) -> bool) -> Result<Vec<String>, String> {
+    let mut matches = Vec::new();
+    for (name, library) in libraries(data_dir, category)? {
+        let values = library["values"].as_array().into_iter().flatten().filter_map(|v| v.as_str());
+        matches.extend(values.filter(|v| matches_value(v)).map(|v| format!("{}::{} -> {}", category, name, v)));
+    }
+    matches.sort();
+    Ok(matches)
+}
+
+fn print_matches(matches: Vec<String>) {
+    if matches.is_empty() {
+        println!("  Not in the libraries");
+    }
+    for part in matches {
+        println!("  {}", part);
+    }
+}
+
+pub fn marking(data_dir: &Path, code: &str, component_type: &str) -> Result<(), String> {
+    match component_type {
+        "resistor" => resistor_marking(data_dir, code),
+        "capacitor" => {
+            let farads = marking::decode_capacitor_code(code)
+                .ok_or_else(|| format!("'{}' is not a capacitor code. Expected a 3-digit (104, 109) or R (4R7) code", code))?;
+            let value = marking::format_capacitance(farads);
+            println!("{} (capacitor code)", value);
+            let same = |v: &str| marking::parse_capacitance(v).is_some_and(|f| (f - farads).abs() <= farads * 1e-6);
+            print_matches(value_matches(data_dir, "capacitor", same)?);
+            Ok(())
+        }
+        "inductor" => {
+            let uh = marking::decode_inductor_code(code)
+                .ok_or_else(|| format!("'{}' is not an inductor code. Expected a 3-digit (101) or R (4R7, R47) code", code))?;
+            let value = component::inductor::format_inductance(uh);
+            println!("{} (inductor code)", value);
+            let same = |v: &str| v.replace('µ', "u") == value;
+            print_matches(value_matches(data_dir, "inductor", same)?);
+            Ok(())
+        }
+        _ => Err(format!("Unknown type '{}', expected resistor, capacitor or inductor", component_type)),
+    }
+}
+
+fn resistor_marking(data_dir: &Path, code: &str) -> Result<(), String> {
+    let readings = marking::decode(code);
+    if readings.is_empty() {
+        return Err(format!(
+            "'{}' is not a resistor marking. Expected a 3-digit (472, 4R7), 4-digit (1002, 49R9) or EIA-96 (68X) code",
+            code
+        ));
+    }
+
+    for (scheme, resistance) in readings {
+        println!("{} ({} code)", resistance, scheme);
+        if scheme == MarkingScheme::Eia96 {
+            println!("  1% part, small chip (0201-0603)");
+        }
+        let matches = library_matches(data_dir, resistance)?;
+        if matches.is_empty() {
+            println!("  Not in the libraries, see 'aeda request new --value {}'", resistance);
+        }
+        for part in matches {
+            println!("  {}", part);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_marking_is_an_error() {
+        let dir = std::env::temp_dir().join(format!("aeda-identify-test-{}", std::process::id()));
+        let err = marking(&dir, "XYZ", "resistor").err().unwrap();
+        assert!(err.contains("EIA-96"));
+        let err = marking(&dir, "4K7", "inductor").err().unwrap();
+        assert!(err.contains("inductor code"));
+    }
+}
@@ -0,0 +1,325 @@
+//! Import external data into the data directory
+//!
+//! Land pattern exports from IPC-7351 calculators are stored in
+//! `packages.json` and registered with the footprint generator on startup, so
+//! every generated footprint picks up the organisation's pad geometry.
+//! Existing `.kicad_mod` footprints are imported the same way, taking the pad
+//! geometry from pads 1 and 2 and the body from the F.Fab outline.
+//! Digi-Key and Mouser parametric search exports are merged into
+//! `availability.json`, the values each manufacturer makes, and generation
+//! skips the rest.
+
+use component::availability::{self, AvailabilityMatrix};
+use component::kicad_footprint::PackageSpec;
+use component::land_pattern;
+use component::settings::Settings;
+use kiparse::sexpr::{parse_sexpr, SExpr};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PACKAGES_FILE: &str = "packages.json";
+const AVAILABILITY_FILE: &str = "availability.json";
+
+/// Custom package geometries previously imported into the data directory
+pub(crate) fn load_packages(data_dir: &Path) -> Result<Vec<PackageSpec>, String> {
+    let path = data_dir.join(PACKAGES_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// Hand imported package geometries to the footprint generator
+pub(crate) fn load_custom_packages(data_dir: &Path, settings: &mut Settings) -> Result<(), String> {
+    for spec in load_packages(data_dir)? {
+        settings.packages.insert(spec.imperial.clone(), spec);
+    }
+    Ok(())
+}
+
+/// Replace existing packages by name and append new ones
+fn merge_packages(existing: &mut Vec<PackageSpec>, imported: Vec<PackageSpec>) {
+    for spec in imported {
+        match existing.iter_mut().find(|p| p.imperial == spec.imperial) {
+            Some(slot) => *slot = spec,
+            None => existing.push(spec),
+        }
+    }
+    existing.sort_by(|a, b| a.imperial.cmp(&b.imperial));
+}
+
+fn print_spec(spec: &PackageSpec) {
+    println!(
+        "  {:<10} pads {:.2}x{:.2}mm, pitch {:.2}mm, body {:.2}x{:.2}mm",
+        spec.imperial,
+        spec.pad_width,
+        spec.pad_height,
+        spec.pad_center_x * 2.0,
+        spec.body_length,
+        spec.body_width
+    );
+}
+
+/// Merge `imported` into `packages.json` and report the total
+fn save_packages(data_dir: &Path, imported: Vec<PackageSpec>) -> Result<(), String> {
+    let mut packages = load_packages(data_dir)?;
+    merge_packages(&mut packages, imported);
+
+    fs::create_dir_all(data_dir)
+        .map_err(|e| format!("Failed to create {}: {}", data_dir.display(), e))?;
+    let path = data_dir.join(PACKAGES_FILE);
+    let json = serde_json::to_string_pretty(&packages)
+        .map_err(|e| format!("Failed to serialize packages: {}", e))?;
+    component::atomic::write(&path, json)?;
+
+    println!("\n{} custom packages in {}", packages.len(), path.display());
+    Ok(())
+}
+
+pub fn land_patterns(data_dir: &Path, file: &Path) -> Result<(), String> {
+    let content = fs::read_to_string(file)
+        .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+    let extension = file
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    let imported = match extension.as_str() {
+        "csv" => land_pattern::parse_csv(&content)?,
+        "xml" => land_pattern::parse_xml(&content)?,
+        _ => return Err(format!("Unsupported land pattern file '{}'. Expected .csv or .xml", file.display())),
+    };
+
+    println!("Importing {} land patterns from {}", imported.len(), file.display());
+    for spec in &imported {
+        print_spec(spec);
+    }
+    save_packages(data_dir, imported)
+}
+
+/// Numeric child `index` of the first `key` list, e.g. `number(pad, "at", 1)`
+fn number(node: &SExpr, key: &str, index: usize) -> Option<f64> {
+    node.find(key)?.value(index)?.parse().ok()
+}
+
+/// Bounding box `(min_x, min_y, max_x, max_y)` of the F.Fab graphics
+fn fab_outline(footprint: &SExpr) -> Option<(f64, f64, f64, f64)> {
+    let mut points = Vec::new();
+    for item in footprint.items() {
+        let on_fab = item.find("layer").and_then(|layer| layer.value(1)) == Some("F.Fab");
+        match item.head() {
+            Some("fp_line") | Some("fp_rect") if on_fab => {
+                for key in ["start", "end"] {
+                    if let (Some(x), Some(y)) = (number(item, key, 1), number(item, key, 2)) {
+                        points.push((x, y));
+                    }
+                }
+            }
+            Some("fp_poly") if on_fab => {
+                for xy in item.find("pts").into_iter().flat_map(|pts| pts.find_all("xy")) {
+                    if let (Some(x), Some(y)) = (xy.value(1).and_then(|v| v.parse().ok()), xy.value(2).and_then(|v| v.parse().ok())) {
+                        points.push((x, y));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    let (&(x, y), rest) = points.split_first()?;
+    Some(rest.iter().fold((x, y, x, y), |(x0, y0, x1, y1), &(x, y)| {
+        (x0.min(x), y0.min(y), x1.max(x), y1.max(y))
+    }))
+}
+
+/// Package names from a footprint name such as `R_0603_1608Metric`: the
+/// 4-digit imperial size and the metric size, when present
+fn names_from_footprint(name: &str) -> (Option<&str>, Option<&str>) {
+    let parts = name.split('_');
+    let imperial = parts.clone().find(|part| part.len() == 4 && part.chars().all(|c| c.is_ascii_digit()));
+    let metric = parts.clone().find(|part| part.ends_with("Metric"));
+    (imperial, metric)
+}
+
+/// Package geometry of a two-terminal `.kicad_mod` footprint. `name`
+/// overrides the package name taken from the footprint name.
+fn parse_kicad_mod(content: &str, name: Option<&str>) -> Result<PackageSpec, String> {
+    let footprint = parse_sexpr(content).map_err(|e| format!("Failed to parse footprint: {}", e))?;
+    if !matches!(footprint.head(), Some("footprint") | Some("module")) {
+        return Err("Not a KiCad footprint, expected (footprint ...)".to_string());
+    }
+    let footprint_name = footprint.value(1).unwrap_or_default();
+
+    let pad = |number: &str| footprint.find_all("pad").find(|pad| pad.value(1) == Some(number));
+    let (Some(pad1), Some(pad2)) = (pad("1"), pad("2")) else {
+        return Err(format!("Footprint '{}' has no pads 1 and 2, only two-terminal footprints can be imported", footprint_name));
+    };
+    let geometry = |pad: &SExpr| -> Result<(f64, f64, f64, f64), String> {
+        let at = (number(pad, "at", 1), number(pad, "at", 2));
+        let size = (number(pad, "size", 1), number(pad, "size", 2));
+        let (Some(x), Some(y)) = at else {
+            return Err(format!("Pad {} of '{}' has no position", pad.value(1).unwrap_or_default(), footprint_name));
+        };
+        let (Some(w), Some(h)) = size else {
+            return Err(format!("Pad {} of '{}' has no size", pad.value(1).unwrap_or_default(), footprint_name));
+        };
+        // A pad rotated by 90 or 270 degrees has its size given in its own frame
+        let rotation = number(pad, "at", 3).unwrap_or(0.0).rem_euclid(180.0);
+        if (rotation - 90.0).abs() < 1.0 {
+            Ok((x, y, h, w))
+        } else {
+            Ok((x, y, w, h))
+        }
+    };
+    let (x1, y1, size_x, size_y) = geometry(pad1)?;
+    let (x2, y2, ..) = geometry(pad2)?;
+
+    let Some((min_x, min_y, max_x, max_y)) = fab_outline(&footprint) else {
+        return Err(format!("Footprint '{}' has no F.Fab outline to take the body size from", footprint_name));
+    };
+    // PackageSpec is laid out along x; footprints drawn vertically are turned
+    let horizontal = (x2 - x1).abs() >= (y2 - y1).abs();
+    let (pitch, pad_width, pad_height, body_length, body_width) = if horizontal {
+        ((x2 - x1).abs(), size_x, size_y, max_x - min_x, max_y - min_y)
+    } else {
+        ((y2 - y1).abs(), size_y, size_x, max_y - min_y, max_x - min_x)
+    };
+
+    let (imperial, metric) = names_from_footprint(footprint_name);
+    let imperial = match name.or(imperial) {
+        Some(imperial) => imperial.to_string(),
+        None => return Err(format!("No package size in footprint name '{}', pass --name", footprint_name)),
+    };
+    let metric = metric
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{:02.0}{:02.0}Metric", body_length * 10.0, body_width * 10.0));
+
+    Ok(PackageSpec {
+        imperial,
+        metric,
+        body_length,
+        body_width,
+        pad_width,
+        pad_height,
+        pad_center_x: pitch / 2.0,
+    })
+}
+
+pub fn footprint(data_dir: &Path, file: &Path, name: Option<&str>) -> Result<(), String> {
+    let content = fs::read_to_string(file)
+        .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+    let spec = parse_kicad_mod(&content, name)?;
+
+    println!("Importing footprint {}", file.display());
+    print_spec(&spec);
+    println!("  Generated parts reference R_{}_{}", spec.imperial, spec.metric);
+    save_packages(data_dir, vec![spec])
+}
+
+fn load_availability(data_dir: &Path) -> Result<AvailabilityMatrix, String> {
+    let path = data_dir.join(AVAILABILITY_FILE);
+    if !path.exists() {
+        return Ok(AvailabilityMatrix::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// Take the imported availability matrix into `settings`, so generation
+/// skips values the manufacturers do not make
+pub(crate) fn install_availability(data_dir: &Path, settings: &mut Settings) -> Result<(), String> {
+    let matrix = load_availability(data_dir)?;
+    if !matrix.is_empty() {
+        settings.availability = Some(matrix);
+    }
+    Ok(())
+}
+
+/// Merge Digi-Key or Mouser parametric search CSV exports into `availability.json`
+pub fn availability(data_dir: &Path, files: &[PathBuf]) -> Result<(), String> {
+    let mut matrix = load_availability(data_dir)?;
+    for file in files {
+        let content = fs::read_to_string(file)
+            .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+        let (imported, skipped) = availability::parse_parametric_csv(&content)
+            .map_err(|e| format!("{}: {}", file.display(), e))?;
+        println!("Importing {}", file.display());
+        for (manufacturer, package, tolerance, values) in imported.summary() {
+            println!("  {:<12} {:<6} {:<6} {} values", manufacturer, package, tolerance, values);
+        }
+        if skipped > 0 {
+            println!("  Skipped {} rows without a chip size, resistance or known tolerance", skipped);
+        }
+        matrix.merge(imported);
+    }
+
+    fs::create_dir_all(data_dir)
+        .map_err(|e| format!("Failed to create {}: {}", data_dir.display(), e))?;
+    let path = data_dir.join(AVAILABILITY_FILE);
+    let json = serde_json::to_string_pretty(&matrix)
+        .map_err(|e| format!("Failed to serialize availability: {}", e))?;
+    component::atomic::write(&path, json)?;
+
+    let combinations = matrix.summary();
+    println!(
+        "\n{} manufacturer/package/tolerance combinations in {}",
+        combinations.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(name: &str, pad_width: f64) -> PackageSpec {
+        PackageSpec {
+            imperial: name.into(),
+            metric: String::new(),
+            body_length: 1.6,
+            body_width: 0.8,
+            pad_width,
+            pad_height: 0.9,
+            pad_center_x: 0.8,
+        }
+    }
+
+    #[test]
+    fn merge_replaces_by_name_and_keeps_order() {
+        let mut existing = vec![spec("0805_HD", 1.0), spec("0603_HD", 0.8)];
+        merge_packages(&mut existing, vec![spec("0603_HD", 0.95), spec("0402_HD", 0.6)]);
+
+        let names: Vec<&str> = existing.iter().map(|p| p.imperial.as_str()).collect();
+        assert_eq!(names, ["0402_HD", "0603_HD", "0805_HD"]);
+        assert_eq!(existing[1].pad_width, 0.95);
+    }
+
+    #[test]
+    fn footprint_geometry_from_pads_and_fab_outline() {
+        let content = r#"(footprint "R_0603_1608Metric_HandSolder" (version 20221018) (layer "F.Cu")
+          (fp_line (start -0.8 0.4125) (end -0.8 -0.4125) (stroke (width 0.1) (type solid)) (layer "F.Fab"))
+          (fp_line (start 0.8 -0.4125) (end 0.8 0.4125) (stroke (width 0.1) (type solid)) (layer "F.Fab"))
+          (fp_rect (start -1.85 -0.73) (end 1.85 0.73) (stroke (width 0.05) (type solid)) (layer "F.CrtYd"))
+          (pad "1" smd roundrect (at -0.9625 0) (size 1.125 0.95) (layers "F.Cu" "F.Paste" "F.Mask"))
+          (pad "2" smd roundrect (at 0.9625 0 180) (size 1.125 0.95) (layers "F.Cu" "F.Paste" "F.Mask")))"#;
+
+        let spec = parse_kicad_mod(content, None).unwrap();
+        assert_eq!((spec.imperial.as_str(), spec.metric.as_str()), ("0603", "1608Metric"));
+        assert_eq!((spec.pad_width, spec.pad_height, spec.pad_center_x), (1.125, 0.95, 0.9625));
+        assert!((spec.body_length - 1.6).abs() < 1e-9 && (spec.body_width - 0.825).abs() < 1e-9);
+
+        // Turned on its side the pad and body sizes swap back
+        let vertical = content.replace("(at -0.9625 0)", "(at 0 -0.9625 90)").replace("(at 0.9625 0 180)", "(at 0 0.9625 270)");
+        let vertical = vertical.replace("-0.8 0.4125", "0.4125 -0.8").replace("-0.8 -0.4125", "-0.4125 -0.8");
+        let vertical = vertical.replace("0.8 -0.4125", "-0.4125 0.8").replace("0.8 0.4125", "0.4125 0.8");
+        let spec = parse_kicad_mod(&vertical, Some("0603_HS")).unwrap();
+        assert_eq!(spec.imperial, "0603_HS");
+        assert_eq!((spec.pad_width, spec.pad_height, spec.pad_center_x), (1.125, 0.95, 0.9625));
+        assert!((spec.body_length - 1.6).abs() < 1e-9);
+    }
+}
@@ -1,5 +1,7 @@
 //! Show information about a specific library
 
+use super::generate::CombinedSeries;
+use super::output;
 use serde::Deserialize;
 use std::fs;
 use std::path::Path;
@@ -22,8 +24,13 @@ struct ComponentLibrary {
     base_values: Vec<f64>,
     #[serde(default)]
     values: Vec<String>,
+    #[serde(default)]
+    symbol_style: Option<String>,
+    #[serde(default)]
+    combined: Vec<CombinedSeries>,
 }
 
+
 pub fn run(data_dir: &Path, library: &str) -> Result<(), String> {
     // Parse library path like "resistor::E96_0603"
     let parts: Vec<&str> = library.split("::").collect();
@@ -45,10 +52,43 @@ pub fn run(data_dir: &Path, library: &str) -> Result<(), String> {
 
     let content = fs::read_to_string(&lib_path)
         .map_err(|e| format!("Failed to read library: {}", e))?;
+    super::schema::validate(category, &lib_path.display().to_string(), &content)?;
 
     let lib: ComponentLibrary = serde_json::from_str(&content)
         .map_err(|e| format!("Failed to parse library: {}", e))?;
 
+    let parts = super::parts_db::open(data_dir).and_then(|conn| super::parts_db::library_rows(&conn, library));
+    if output::json() {
+        let (parts, warnings) = match parts {
+            Ok(parts) => (parts, Vec::new()),
+            Err(e) => (Vec::new(), vec![e]),
+        };
+        let parts: Vec<serde_json::Value> = parts
+            .iter()
+            .map(|part| serde_json::json!({ "part": part.part, "value": part.value, "manufacturer": part.manufacturer, "mpn": part.mpn }))
+            .collect();
+        return output::print(&serde_json::json!({
+            "library": library,
+            "path": lib_path,
+            "name": lib.name,
+            "type": lib.component_type,
+            "description": lib.description,
+            "package": lib.package,
+            "footprint": lib.footprint,
+            "prefix": lib.prefix,
+            "pins": lib.pins,
+            "tolerance": lib.tolerance,
+            "power_rating": lib.power_rating,
+            "symbol_style": lib.symbol_style,
+            "base_values": lib.base_values,
+            "combined": lib.combined,
+            "values": lib.values,
+            "count": parts.len(),
+            "parts": parts,
+            "warnings": warnings,
+        }));
+    }
+
     println!("Library: {}", library);
     println!("=========={}", "=".repeat(library.len()));
     println!();
@@ -66,12 +106,19 @@ pub fn run(data_dir: &Path, library: &str) -> Result<(), String> {
     if !lib.power_rating.is_empty() {
         println!("Power:       {}", lib.power_rating);
     }
+    if lib.component_type == "resistor" {
+        let style = lib.symbol_style.as_deref().unwrap_or("european");
+        println!("Symbol:      {}  {}", style, component::kicad_symbol::style_glyph(style).unwrap_or_default());
+    }
 
     println!();
     if !lib.base_values.is_empty() {
         println!("Base values: {} values in series", lib.base_values.len());
         println!("  First 10: {:?}", &lib.base_values[..lib.base_values.len().min(10)]);
     }
+    for series in &lib.combined {
+        println!("{} {}: {} values in series", series.series, series.tolerance, series.base_values.len());
+    }
 
     if !lib.values.is_empty() {
         println!("Values: {} discrete values", lib.values.len());
@@ -81,5 +128,20 @@ pub fn run(data_dir: &Path, library: &str) -> Result<(), String> {
         }
     }
 
+    match parts {
+        Ok(parts) if !parts.is_empty() => {
+            println!();
+            println!("Parts: {} generated", parts.len());
+            for part in parts.iter().take(10) {
+                println!("  {:<20} {:<12} {}", part.part, part.manufacturer, part.mpn);
+            }
+            if parts.len() > 10 {
+                println!("  ... and {} more", parts.len() - 10);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("Warning: {}", e),
+    }
+
     Ok(())
 }
@@ -11,6 +11,8 @@ pub fn run(data_dir: &Path) -> Result<(), String> {
         "libraries/resistor",
         "libraries/capacitor",
         "libraries/inductor",
+        "libraries/ferrite",
+        "libraries/led",
         "libraries/diode",
         "libraries/ic",
         "footprints",
@@ -34,6 +36,9 @@ pub fn run(data_dir: &Path) -> Result<(), String> {
 [general]
 # Default output format: kicad, altium, stencil
 default_format = "kicad"
+# Disable every network feature (Octopart, Mouser, link checks) on
+# air-gapped build machines; cached data is still used
+offline = false
 
 [paths]
 # Override default paths (uncomment to customize)
@@ -46,13 +51,58 @@ default_resistor_series = "E96"
 # Default packages for generation
 default_packages = ["0603", "0805", "1206"]
 
+[scripting]
+# Rhai script run on every generated part to edit fields, add properties
+# or skip the part (path relative to this directory)
+# part_hook = "hooks/parts.rhai"
+
+[distributors]
+# SupplierURL target: "search" for a distributor search page, or "product"
+# for the exact product page of parts with cached data from 'aeda enrich'
+supplier_url = "search"
+# Distributors written as Supplier 1, Supplier 2, ... of every part, with
+# their part numbers; Digikey only when unset
+# suppliers = ["digikey", "mouser", "arrow"]
+
+# Datasheet links of the generated symbols and Altium rows, by manufacturer
+# or manufacturer/MPN prefix; {mpn} is replaced by the part number
+# [datasheets]
+# Yageo = "https://www.yageo.com/en/Chart/Download/pdf/{mpn}"
+# "Vishay/TNPW" = "https://www.vishay.com/docs/28758/tnpw_e3.pdf"
+
+# Field length limits per exporter (altium or bom) and column, 0 for
+# unlimited; Altium text fields default to 255 characters
+# [limits.altium]
+# default = 255
+# Datasheet = 0
+
+# Comment column of the Altium CSV rows: "=Value" for Altium's Value
+# parameter, or "value" for the value itself
+# [altium]
+# comment = "=Value"
+
+# Thermal resistance (°C/W) and maximum temperature (°C) of the resistor
+# symbols, per package or package/MPN prefix, replacing those of the
+# standard derating curve (full power to 70 °C, none at 155 °C)
+# [thermal]
+# "0603/TNPW" = { max_temperature = 125 }
+
+# Output profiles bundle the conventions of one downstream team or tool,
+# used with 'aeda export --profile <name>'
+# [profiles.kicad-team-a]
+# target = "kicad"            # kicad, stencil, altium, easyeda, jlcpcb or bom
+# kicad_version = "8"         # KiCad symbol format: 6, 7 or 8
+# footprint_format = "modern" # (footprint ...) with uuids instead of (module ...)
+# value_notation = "european" # 4k70 instead of 4.70K
+# package_naming = "metric" # R1608_4.70K instead of R0603_4.70K
+# output = "kicad_team_a"
+
 [stencil]
 # Path where Stencil looks for libraries
 # This should match library_manager base_path in stencil-bd
 library_path = "libraries"
 "#;
-        fs::write(&config_path, default_config)
-            .map_err(|e| format!("Failed to write config: {}", e))?;
+        component::atomic::write(&config_path, default_config)?;
         println!("  Created: config.toml");
     }
 
@@ -67,13 +117,14 @@ library_path = "libraries"
     "resistor": {},
     "capacitor": {},
     "inductor": {},
+    "ferrite": {},
+    "led": {},
     "diode": {},
     "ic": {}
   }
 }
 "#;
-        fs::write(&manifest_path, default_manifest)
-            .map_err(|e| format!("Failed to write manifest: {}", e))?;
+        component::atomic::write(&manifest_path, default_manifest)?;
         println!("  Created: libraries/manifest.json");
     }
 
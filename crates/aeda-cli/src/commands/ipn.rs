@@ -0,0 +1,84 @@
+//! Internal part numbers from config.toml
+//!
+//! ```toml
+//! [ipn]
+//! scheme = "ATX-{family}-{seq:6}"
+//! ```
+//!
+//! Parts get their numbers as libraries, symbols and BOMs are written; the
+//! numbers handed out are kept in `ipn.toml` in the data directory, so
+//! regenerated parts keep theirs. See `component::ipn` for the fields of a
+//! scheme.
+
+use component::ipn::{IpnScheme, Numbering};
+use component::settings::Settings;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Number parts of `settings` by the `[ipn]` scheme of config.toml, with
+/// the registry of `data_dir`
+pub fn install(data_dir: &Path, settings: &mut Settings) -> Result<(), String> {
+    let config_path = data_dir.join("config.toml");
+    let Ok(content) = fs::read_to_string(&config_path) else {
+        return Ok(());
+    };
+    let config: toml::Table = content
+        .parse()
+        .map_err(|e| format!("Failed to parse {}: {}", config_path.display(), e))?;
+    let Some(section) = config.get("ipn") else {
+        return Ok(());
+    };
+    let scheme = section
+        .get("scheme")
+        .and_then(|scheme| scheme.as_str())
+        .ok_or_else(|| format!("[ipn] in {} needs a scheme, e.g. scheme = \"ATX-{{family}}-{{seq:6}}\"", config_path.display()))?;
+    let scheme: IpnScheme = scheme.parse().map_err(|e| format!("ipn.scheme in {}: {}", config_path.display(), e))?;
+    let numbering = Numbering::load(scheme, &data_dir.join(component::ipn::REGISTRY))?;
+    settings.ipn = Some(Arc::new(Mutex::new(numbering)));
+    Ok(())
+}
+
+/// Keep the numbers handed out in the run of `settings`
+pub fn save(settings: &Settings) -> Result<(), String> {
+    let Some(numbering) = &settings.ipn else {
+        return Ok(());
+    };
+    if let Some(parts) = numbering.lock().unwrap_or_else(|e| e.into_inner()).save()? {
+        println!("IPN registry updated ({} parts)", parts);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_numbers_between_runs() {
+        let dir = std::env::temp_dir().join(format!("aeda_ipn_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("config.toml"), "[ipn]\nscheme = \"ATX-{family}\"\n").unwrap();
+        assert!(install(&dir, &mut Settings::default()).unwrap_err().contains("needs a {seq}"));
+
+        fs::write(dir.join("config.toml"), "[ipn]\nscheme = \"ATX-{family}-{seq:6}\"\n").unwrap();
+        let mut settings = Settings::default();
+        install(&dir, &mut settings).unwrap();
+        let first = settings.scope(|| component::ipn::assign("resistor", "0603", "R0603_4.99K", "1%"));
+        assert_eq!(first.as_deref(), Some("ATX-R-000001"));
+        assert_eq!(component::ipn::assign("resistor", "0603", "R0603_4.99K", "1%"), None);
+        save(&settings).unwrap();
+
+        // The next run continues the registry
+        let mut settings = Settings::default();
+        install(&dir, &mut settings).unwrap();
+        settings.scope(|| {
+            assert_eq!(component::ipn::assign("resistor", "0603", "R0603_10.0K", "1%").as_deref(), Some("ATX-R-000002"));
+            assert_eq!(component::ipn::assign("resistor", "0603", "R0603_4.99K", "1%").as_deref(), Some("ATX-R-000001"));
+        });
+        save(&settings).unwrap();
+        let registry = component::ipn::Registry::load(&dir.join(component::ipn::REGISTRY)).unwrap();
+        assert_eq!(registry.parts.len(), 2);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,152 @@
+//! Shared kicad-cli invocation and post-generation validation
+//!
+//! Generated symbol and footprint libraries are round-tripped through
+//! `kicad-cli sym upgrade` / `kicad-cli fp upgrade` into scratch copies.
+//! KiCad refuses to load malformed files, so anything it reports is a format
+//! problem in our output.
+
+use super::output::say;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Build the kicad-cli argv. Honors `KICAD_CLI` env var (whitespace-split);
+/// otherwise defaults to `flatpak run --command=kicad-cli org.kicad.KiCad`,
+/// which is the stable 10.0.1 install on this machine.
+pub(crate) fn argv() -> Vec<String> {
+    if let Ok(s) = std::env::var("KICAD_CLI") {
+        let parts: Vec<String> = s.split_whitespace().map(|p| p.to_string()).collect();
+        if !parts.is_empty() {
+            return parts;
+        }
+    }
+    vec![
+        "flatpak".to_string(),
+        "run".to_string(),
+        "--command=kicad-cli".to_string(),
+        "org.kicad.KiCad".to_string(),
+    ]
+}
+
+/// Returns the kicad-cli argv and its version if a KiCad installation responds
+pub(crate) fn detect() -> Option<(Vec<String>, String)> {
+    let argv = argv();
+    let output = Command::new(&argv[0]).args(&argv[1..]).arg("version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Some((argv, version))
+}
+
+/// Run kicad-cli and collect anything it reports as an issue
+fn run_check(argv: &[String], args: &[String]) -> Vec<String> {
+    let output = match Command::new(&argv[0]).args(&argv[1..]).args(args).output() {
+        Ok(output) => output,
+        Err(e) => return vec![format!("Failed to invoke kicad-cli ({}): {}", argv[0], e)],
+    };
+
+    let mut issues: Vec<String> = String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .chain(String::from_utf8_lossy(&output.stdout).lines())
+        .map(str::trim)
+        .filter(|line| {
+            let lower = line.to_lowercase();
+            lower.contains("error") || lower.contains("warning") || lower.contains("failed")
+        })
+        .map(String::from)
+        .collect();
+    if !output.status.success() && issues.is_empty() {
+        issues.push(format!("kicad-cli exited with status {}", output.status.code().unwrap_or(-1)));
+    }
+    issues
+}
+
+/// Issues KiCad reports for a generated file, keyed by the file checked
+pub(crate) struct CheckReport {
+    pub(crate) version: String,
+    pub(crate) results: Vec<(PathBuf, Vec<String>)>,
+}
+
+impl CheckReport {
+    pub(crate) fn print(&self) {
+        say!("\nKiCad validation ({}):", self.version);
+        for (path, issues) in &self.results {
+            if issues.is_empty() {
+                say!("  OK      {}", path.display());
+            } else {
+                say!("  ISSUES  {}", path.display());
+                for issue in issues {
+                    say!("          {}", issue);
+                }
+            }
+        }
+    }
+}
+
+/// Validate generated symbol libraries and footprint directories.
+///
+/// Scratch output is written next to the inputs (flatpak sandboxing forbids
+/// /tmp writes) under hidden names and removed afterwards. Returns `None` when
+/// no KiCad installation is found.
+pub(crate) fn check_generated(symbol_libs: &[PathBuf], footprint_dirs: &[PathBuf]) -> Option<CheckReport> {
+    let (argv, version) = detect()?;
+    let pid = std::process::id();
+    let mut results = Vec::new();
+
+    for lib in symbol_libs {
+        let scratch = scratch_path(lib, pid, "kicad_sym");
+        let issues = run_check(
+            &argv,
+            &[
+                "sym".into(),
+                "upgrade".into(),
+                "--force".into(),
+                "-o".into(),
+                scratch.to_string_lossy().into_owned(),
+                lib.to_string_lossy().into_owned(),
+            ],
+        );
+        let _ = fs::remove_file(&scratch);
+        results.push((lib.clone(), issues));
+    }
+
+    for dir in footprint_dirs {
+        let scratch = scratch_path(dir, pid, "pretty");
+        let issues = run_check(
+            &argv,
+            &[
+                "fp".into(),
+                "upgrade".into(),
+                "--force".into(),
+                "-o".into(),
+                scratch.to_string_lossy().into_owned(),
+                dir.to_string_lossy().into_owned(),
+            ],
+        );
+        let _ = fs::remove_dir_all(&scratch);
+        results.push((dir.clone(), issues));
+    }
+
+    Some(CheckReport { version, results })
+}
+
+fn scratch_path(path: &Path, pid: u32, extension: &str) -> PathBuf {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("lib");
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    parent.join(format!(".aeda-check-{}-{}.{}", stem, pid, extension))
+}
+
+/// Print the post-generation KiCad report, or a note when KiCad is missing
+pub(crate) fn report_generated(symbol_libs: &[PathBuf], footprint_dirs: &[PathBuf]) {
+    // Nothing was written to check
+    if component::atomic::dry_run() {
+        return;
+    }
+    match check_generated(symbol_libs, footprint_dirs) {
+        Some(report) => report.print(),
+        None => say!(
+            "\nkicad-cli not found, skipping KiCad validation. Set KICAD_CLI to point at your installation."
+        ),
+    }
+}
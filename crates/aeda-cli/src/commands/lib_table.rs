@@ -0,0 +1,545 @@
+//! KiCad library tables and nickname collisions
+//!
+//! KiCad resolves `Atlantix_Resistors:R_0603_1608Metric` through the
+//! nicknames in `sym-lib-table` and `fp-lib-table`. If the user already has a
+//! library called `Atlantix_Resistors` somewhere else, registering ours under
+//! the same nickname would silently shadow one of them. This module reads
+//! the existing tables and plans each registration as an addition, a no-op
+//! (already registered at the same location), a rename to a free nickname or
+//! a merge into the library that owns the nickname.
+//!
+//! `aeda register-lib-tables` then writes the additions and renames into the
+//! tables, generating a table that does not exist yet and keeping the
+//! original next to it as `<table>.bak`. With `--kicad-project` the libraries
+//! are copied into the project and go into its project-local tables instead.
+//! `aeda export kicad --output` registers the libraries it exports the same
+//! way.
+
+use kiparse::sexpr::parse_sexpr;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TableKind {
+    Symbol,
+    Footprint,
+}
+
+impl TableKind {
+    pub fn file_name(self) -> &'static str {
+        match self {
+            TableKind::Symbol => "sym-lib-table",
+            TableKind::Footprint => "fp-lib-table",
+        }
+    }
+
+    fn root(self) -> &'static str {
+        match self {
+            TableKind::Symbol => "sym_lib_table",
+            TableKind::Footprint => "fp_lib_table",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LibEntry {
+    pub nickname: String,
+    pub uri: String,
+}
+
+#[derive(Debug)]
+pub struct LibTable {
+    pub path: PathBuf,
+    pub entries: Vec<LibEntry>,
+}
+
+impl LibTable {
+    /// Load a table, a missing file is an empty table
+    pub fn load(path: &Path, kind: TableKind) -> Result<LibTable, String> {
+        let mut table = LibTable { path: path.to_path_buf(), entries: Vec::new() };
+        if !path.exists() {
+            return Ok(table);
+        }
+        let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let root = parse_sexpr(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        if root.head() != Some(kind.root()) {
+            return Err(format!("{} is not a {}", path.display(), kind.file_name()));
+        }
+        table.entries = root
+            .find_all("lib")
+            .filter_map(|lib| {
+                let field = |key: &str| lib.find(key).and_then(|f| f.value(1)).map(str::to_string);
+                Some(LibEntry { nickname: field("name")?, uri: field("uri").unwrap_or_default() })
+            })
+            .collect();
+        Ok(table)
+    }
+
+    pub fn get(&self, nickname: &str) -> Option<&LibEntry> {
+        self.entries.iter().find(|entry| entry.nickname == nickname)
+    }
+}
+
+/// KiCad's per-user configuration directory of the newest installed
+/// version, e.g. `~/.config/kicad/8.0`. `KICAD_CONFIG_HOME` overrides it.
+pub fn global_config_dir() -> Option<PathBuf> {
+    let base = match std::env::var_os("KICAD_CONFIG_HOME") {
+        Some(dir) => return Some(PathBuf::from(dir)),
+        None => dirs::preference_dir()?.join("kicad"),
+    };
+    let mut versions: Vec<(Vec<u32>, PathBuf)> = fs::read_dir(&base)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let version: Option<Vec<u32>> = name.split('.').map(|part| part.parse().ok()).collect();
+            version.map(|v| (v, entry.path()))
+        })
+        .collect();
+    versions.sort();
+    versions.pop().map(|(_, path)| path)
+}
+
+/// What to do when a nickname is taken by a different library
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CollisionStrategy {
+    /// Stop and report the collisions
+    #[default]
+    Abort,
+    /// Register ours under the first free `<nickname>_<n>`
+    Rename,
+    /// Keep the existing entry and install our parts into its library
+    Merge,
+}
+
+impl FromStr for CollisionStrategy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "abort" => Ok(CollisionStrategy::Abort),
+            "rename" => Ok(CollisionStrategy::Rename),
+            "merge" => Ok(CollisionStrategy::Merge),
+            _ => Err(format!("Unknown collision strategy '{}'. Expected abort, rename or merge", value)),
+        }
+    }
+}
+
+/// Planned registration of one library
+#[derive(Debug, Clone, PartialEq)]
+pub enum Registration {
+    /// New table entry
+    Add(LibEntry),
+    /// Already registered at the same location
+    Registered(LibEntry),
+    /// Nickname taken, registered under `entry.nickname` instead
+    Renamed { from: String, entry: LibEntry },
+    /// Nickname taken, our parts go into the existing library
+    Merge { ours: LibEntry, existing: LibEntry },
+}
+
+impl fmt::Display for Registration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Registration::Add(entry) => write!(f, "+ {} -> {}", entry.nickname, entry.uri),
+            Registration::Registered(entry) => write!(f, "= {} (already registered)", entry.nickname),
+            Registration::Renamed { from, entry } => {
+                write!(f, "~ {} is taken, registering as {} -> {}", from, entry.nickname, entry.uri)
+            }
+            Registration::Merge { ours, existing } => {
+                write!(f, "> {} is taken, merging into {}", ours.nickname, existing.uri)
+            }
+        }
+    }
+}
+
+/// Expand `${VAR}` references that are set in the environment and
+/// normalize separators, so equal locations compare equal
+fn normalize_uri(uri: &str) -> String {
+    let mut expanded = uri.to_string();
+    while let Some(start) = expanded.find("${") {
+        let Some(end) = expanded[start..].find('}') else { break };
+        let name = &expanded[start + 2..start + end];
+        let Ok(value) = std::env::var(name) else { break };
+        expanded.replace_range(start..start + end + 1, &value);
+    }
+    expanded.replace('\\', "/").trim_end_matches('/').to_string()
+}
+
+/// Plan the registration of `planned` against an existing table
+pub fn plan(table: &LibTable, planned: &[LibEntry], strategy: CollisionStrategy) -> Result<Vec<Registration>, String> {
+    let mut taken: Vec<String> = table.entries.iter().map(|e| e.nickname.clone()).collect();
+    let mut registrations = Vec::new();
+    let mut collisions = Vec::new();
+
+    for ours in planned {
+        let Some(existing) = table.get(&ours.nickname) else {
+            taken.push(ours.nickname.clone());
+            registrations.push(Registration::Add(ours.clone()));
+            continue;
+        };
+        if normalize_uri(&existing.uri) == normalize_uri(&ours.uri) {
+            registrations.push(Registration::Registered(ours.clone()));
+            continue;
+        }
+        match strategy {
+            CollisionStrategy::Abort => {
+                collisions.push(format!("  {} is already registered to {}", ours.nickname, existing.uri));
+            }
+            CollisionStrategy::Rename => {
+                let nickname = (2..)
+                    .map(|n| format!("{}_{}", ours.nickname, n))
+                    .find(|candidate| !taken.contains(candidate))
+                    .expect("unbounded range");
+                taken.push(nickname.clone());
+                registrations.push(Registration::Renamed {
+                    from: ours.nickname.clone(),
+                    entry: LibEntry { nickname, uri: ours.uri.clone() },
+                });
+            }
+            CollisionStrategy::Merge => registrations.push(Registration::Merge {
+                ours: ours.clone(),
+                existing: existing.clone(),
+            }),
+        }
+    }
+
+    if !collisions.is_empty() {
+        return Err(format!(
+            "Library nicknames already used in {}:\n{}\nUse --on-collision rename or --on-collision merge",
+            table.path.display(),
+            collisions.join("\n")
+        ));
+    }
+    Ok(registrations)
+}
+
+/// Libraries generated in the data directory, as they would be registered
+pub fn generated_libraries(data_dir: &Path, kind: TableKind) -> Vec<LibEntry> {
+    match kind {
+        TableKind::Symbol => {
+            let mut entries: Vec<LibEntry> = fs::read_dir(data_dir.join("symbols"))
+                .into_iter()
+                .flatten()
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "kicad_sym"))
+                .map(|path| LibEntry {
+                    nickname: path.file_stem().unwrap_or_default().to_string_lossy().to_string(),
+                    uri: path.to_string_lossy().to_string(),
+                })
+                .collect();
+            entries.sort_by(|a, b| a.nickname.cmp(&b.nickname));
+            entries
+        }
+        TableKind::Footprint => {
+            let footprints = data_dir.join("footprints");
+            if !footprints.is_dir() {
+                return Vec::new();
+            }
+            // The generators reference one nickname per family, all stored in footprints/
+            ["Atlantix_Diodes", "Atlantix_Inductors", "Atlantix_LEDs", "Atlantix_Resistors"]
+                .iter()
+                .map(|nickname| LibEntry {
+                    nickname: nickname.to_string(),
+                    uri: footprints.to_string_lossy().to_string(),
+                })
+                .collect()
+        }
+    }
+}
+
+/// KiCad's `(lib ...)` line of an entry
+fn lib_line(entry: &LibEntry) -> String {
+    let quote = |text: &str| text.replace('\\', "/").replace('"', "\\\"");
+    format!(
+        "  (lib (name \"{}\")(type \"KiCad\")(uri \"{}\")(options \"\")(descr \"Atlantix EDA\"))\n",
+        quote(&entry.nickname),
+        quote(&entry.uri)
+    )
+}
+
+/// Append `entries` to the table at `path`, generating it when missing.
+/// An existing table is copied to `<table>.bak` unless that exists, so the
+/// backup keeps the table from before the first registration. The backup
+/// is returned.
+pub fn append(path: &Path, kind: TableKind, entries: &[LibEntry]) -> Result<Option<PathBuf>, String> {
+    if entries.is_empty() {
+        return Ok(None);
+    }
+    let (content, backup) = if path.exists() {
+        let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let backup = path.with_file_name(format!("{}.bak", kind.file_name()));
+        if !backup.exists() {
+            fs::copy(path, &backup)
+                .map_err(|e| format!("Failed to back up {} to {}: {}", path.display(), backup.display(), e))?;
+        }
+        (content, Some(backup))
+    } else {
+        (format!("({}\n  (version 7)\n)\n", kind.root()), None)
+    };
+
+    let end = content.rfind(')').ok_or_else(|| format!("{} is not a {}", path.display(), kind.file_name()))?;
+    let mut table = content[..end].trim_end().to_string();
+    table.push('\n');
+    for entry in entries {
+        table.push_str(&lib_line(entry));
+    }
+    table.push_str(")\n");
+    if let Some(parent) = path.parent() {
+        component::atomic::create_dir_all(parent)?;
+    }
+    // The table is the user's, the overwrite guard is for generated files
+    component::atomic::replace(path, table)?;
+    Ok(backup)
+}
+
+fn resolve_config_dir(config_dir: Option<&Path>) -> Result<PathBuf, String> {
+    match config_dir {
+        Some(dir) => Ok(dir.to_path_buf()),
+        None => global_config_dir()
+            .ok_or_else(|| "KiCad configuration directory not found. Pass --kicad-config or set KICAD_CONFIG_HOME".to_string()),
+    }
+}
+
+/// Report how the generated libraries would be registered in KiCad's
+/// global tables (or the tables in `config_dir`)
+pub fn check(data_dir: &Path, config_dir: Option<&Path>, strategy: &str) -> Result<(), String> {
+    let strategy: CollisionStrategy = strategy.parse()?;
+    let config_dir = resolve_config_dir(config_dir)?;
+
+    for kind in [TableKind::Symbol, TableKind::Footprint] {
+        let table = LibTable::load(&config_dir.join(kind.file_name()), kind)?;
+        let planned = generated_libraries(data_dir, kind);
+        println!("{} ({} entries):", table.path.display(), table.entries.len());
+        if planned.is_empty() {
+            println!("  No generated libraries");
+            continue;
+        }
+        for registration in plan(&table, &planned, strategy)? {
+            println!("  {}", registration);
+        }
+    }
+    Ok(())
+}
+
+/// Register the generated libraries in KiCad's global tables (or the tables
+/// in `config_dir`), so they show up without adding them in the Library
+/// Manager
+pub fn register(data_dir: &Path, config_dir: Option<&Path>, strategy: &str) -> Result<(), String> {
+    let strategy: CollisionStrategy = strategy.parse()?;
+    let config_dir = resolve_config_dir(config_dir)?;
+    let planned = [TableKind::Symbol, TableKind::Footprint].map(|kind| (kind, generated_libraries(data_dir, kind)));
+    write_tables(&config_dir, &planned, strategy)?;
+    println!();
+    println!("Restart KiCad to pick up the new libraries");
+    Ok(())
+}
+
+/// Directory of a project the libraries are copied into
+const PROJECT_LIBRARIES: &str = "atlantix";
+
+/// Copy the generated libraries next to `project` (a .kicad_pro) and
+/// register them in the project's own tables through `${KIPRJMOD}`, so the
+/// project carries its libraries and needs nothing in the global tables
+pub fn install_project(data_dir: &Path, project: &Path, strategy: &str) -> Result<(), String> {
+    let strategy: CollisionStrategy = strategy.parse()?;
+    if project.extension().is_none_or(|ext| ext != "kicad_pro") || !project.is_file() {
+        return Err(format!("{} is not a KiCad project (.kicad_pro)", project.display()));
+    }
+    let project_dir = project.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let libraries = project_dir.join(PROJECT_LIBRARIES);
+
+    let copied = copy_libraries(data_dir, &libraries)?;
+    println!("Copied {} files into {}", copied, libraries.display());
+
+    let planned = [TableKind::Symbol, TableKind::Footprint].map(|kind| {
+        let entries = generated_libraries(&libraries, kind)
+            .into_iter()
+            .map(|entry| LibEntry { uri: project_uri(project_dir, &entry.uri), ..entry })
+            .collect();
+        (kind, entries)
+    });
+    write_tables(project_dir, &planned, strategy)?;
+    println!();
+    println!("Reopen the project in KiCad to pick up the libraries");
+    Ok(())
+}
+
+/// Copy the symbol and footprint libraries of `data_dir` into the same
+/// layout under `target`, returning the number of files copied
+pub fn copy_libraries(data_dir: &Path, target: &Path) -> Result<usize, String> {
+    let mut copied = 0;
+    for (dir, extension) in [("symbols", "kicad_sym"), ("footprints", "kicad_mod")] {
+        let files: Vec<PathBuf> = fs::read_dir(data_dir.join(dir))
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == extension))
+            .collect();
+        if files.is_empty() {
+            continue;
+        }
+        let target = target.join(dir);
+        component::atomic::create_dir_all(&target)?;
+        for file in files {
+            let content = fs::read(&file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+            component::atomic::write(target.join(file.file_name().unwrap_or_default()), content)?;
+            copied += 1;
+        }
+    }
+    Ok(copied)
+}
+
+/// `uri` relative to the project directory, as KiCad writes it
+fn project_uri(project_dir: &Path, uri: &str) -> String {
+    match Path::new(uri).strip_prefix(project_dir) {
+        Ok(relative) => format!("${{KIPRJMOD}}/{}", relative.to_string_lossy().replace('\\', "/")),
+        Err(_) => uri.to_string(),
+    }
+}
+
+/// Plan both tables in `dir` before writing either, then append the
+/// additions and renames
+fn write_tables(dir: &Path, planned: &[(TableKind, Vec<LibEntry>)], strategy: CollisionStrategy) -> Result<(), String> {
+    let mut plans = Vec::new();
+    for (kind, entries) in planned {
+        let table = LibTable::load(&dir.join(kind.file_name()), *kind)?;
+        plans.push((*kind, plan(&table, entries, strategy)?, table.path));
+    }
+    if plans.iter().all(|(_, registrations, _)| registrations.is_empty()) {
+        return Err("No generated libraries to register. Generate them first".to_string());
+    }
+
+    for (kind, registrations, path) in plans {
+        let additions: Vec<LibEntry> = registrations
+            .iter()
+            .filter_map(|registration| match registration {
+                Registration::Add(entry) | Registration::Renamed { entry, .. } => Some(entry.clone()),
+                Registration::Registered(_) | Registration::Merge { .. } => None,
+            })
+            .collect();
+        let backup = append(&path, kind, &additions)?;
+        println!("{}:", path.display());
+        for registration in &registrations {
+            println!("  {}", registration);
+        }
+        match backup {
+            Some(backup) => println!("  Backup: {}", backup.display()),
+            None if additions.is_empty() => println!("  Unchanged"),
+            None => println!("  Created"),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> LibTable {
+        let path = std::env::temp_dir().join(format!("aeda_sym_lib_table_{}", std::process::id()));
+        fs::write(
+            &path,
+            r#"(sym_lib_table
+  (version 7)
+  (lib (name "Device")(type "KiCad")(uri "${KICAD7_SYMBOL_DIR}/Device.kicad_sym")(options "")(descr ""))
+  (lib (name "Atlantix_Resistors")(type "KiCad")(uri "/home/me/old/Atlantix_Resistors.kicad_sym")(options "")(descr ""))
+  (lib (name "Atlantix_Resistors_2")(type "KiCad")(uri "/home/me/older.kicad_sym")(options "")(descr ""))
+  (lib (name "Atlantix_LEDs")(type "KiCad")(uri "/data/symbols/Atlantix_LEDs.kicad_sym")(options "")(descr ""))
+)
+"#,
+        )
+        .unwrap();
+        let table = LibTable::load(&path, TableKind::Symbol).unwrap();
+        fs::remove_file(path).unwrap();
+        table
+    }
+
+    fn entry(nickname: &str, uri: &str) -> LibEntry {
+        LibEntry { nickname: nickname.into(), uri: uri.into() }
+    }
+
+    #[test]
+    fn plans_collisions_per_strategy() {
+        let table = table();
+        assert_eq!(table.entries.len(), 4);
+        let planned = [
+            entry("Atlantix_Resistors", "/data/symbols/Atlantix_Resistors.kicad_sym"),
+            entry("Atlantix_LEDs", "/data/symbols/Atlantix_LEDs.kicad_sym"),
+            entry("Atlantix_Diodes", "/data/symbols/Atlantix_Diodes.kicad_sym"),
+        ];
+
+        let error = plan(&table, &planned, CollisionStrategy::Abort).unwrap_err();
+        assert!(error.contains("Atlantix_Resistors is already registered to /home/me/old/Atlantix_Resistors.kicad_sym"));
+
+        let renamed = plan(&table, &planned, CollisionStrategy::Rename).unwrap();
+        assert_eq!(
+            renamed[0],
+            Registration::Renamed {
+                from: "Atlantix_Resistors".into(),
+                entry: entry("Atlantix_Resistors_3", "/data/symbols/Atlantix_Resistors.kicad_sym"),
+            }
+        );
+        assert_eq!(renamed[1], Registration::Registered(planned[1].clone()));
+        assert_eq!(renamed[2], Registration::Add(planned[2].clone()));
+
+        let merged = plan(&table, &planned, CollisionStrategy::Merge).unwrap();
+        assert!(matches!(&merged[0], Registration::Merge { existing, .. } if existing.uri.starts_with("/home/me/old")));
+    }
+
+    #[test]
+    fn appends_entries_with_a_backup() {
+        let dir = std::env::temp_dir().join(format!("aeda_lib_table_append_{}", std::process::id()));
+        let path = dir.join("fp-lib-table");
+        let resistors = entry("Atlantix_Resistors", "/data/footprints");
+
+        assert_eq!(append(&path, TableKind::Footprint, std::slice::from_ref(&resistors)).unwrap(), None);
+        let table = LibTable::load(&path, TableKind::Footprint).unwrap();
+        assert_eq!(table.entries[0], resistors);
+
+        let leds = entry("Atlantix_LEDs", "C:\\data\\footprints");
+        let backup = append(&path, TableKind::Footprint, &[leds]).unwrap().unwrap();
+        assert_eq!(LibTable::load(&backup, TableKind::Footprint).unwrap().entries, std::slice::from_ref(&resistors));
+        let table = LibTable::load(&path, TableKind::Footprint).unwrap();
+        assert_eq!(table.entries[1], entry("Atlantix_LEDs", "C:/data/footprints"));
+
+        // Later runs keep the first backup
+        append(&path, TableKind::Footprint, &[entry("Atlantix_Diodes", "/data/footprints")]).unwrap();
+        assert_eq!(LibTable::load(&backup, TableKind::Footprint).unwrap().entries, [resistors]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn installs_into_a_project() {
+        let dir = std::env::temp_dir().join(format!("aeda_lib_table_project_{}", std::process::id()));
+        let data_dir = dir.join("data");
+        fs::create_dir_all(data_dir.join("symbols")).unwrap();
+        fs::create_dir_all(data_dir.join("footprints")).unwrap();
+        fs::write(data_dir.join("symbols/Atlantix_Resistors.kicad_sym"), "(kicad_symbol_lib)").unwrap();
+        fs::write(data_dir.join("footprints/R_0603_1608Metric.kicad_mod"), "(footprint)").unwrap();
+        let project = dir.join("board/board.kicad_pro");
+        fs::create_dir_all(project.parent().unwrap()).unwrap();
+        fs::write(&project, "{}").unwrap();
+
+        install_project(&data_dir, &project, "abort").unwrap();
+        assert!(dir.join("board/atlantix/footprints/R_0603_1608Metric.kicad_mod").is_file());
+        let symbols = LibTable::load(&dir.join("board/sym-lib-table"), TableKind::Symbol).unwrap();
+        assert_eq!(
+            symbols.entries,
+            [entry("Atlantix_Resistors", "${KIPRJMOD}/atlantix/symbols/Atlantix_Resistors.kicad_sym")]
+        );
+        let footprints = LibTable::load(&dir.join("board/fp-lib-table"), TableKind::Footprint).unwrap();
+        assert_eq!(footprints.entries[0].uri, "${KIPRJMOD}/atlantix/footprints");
+
+        // A second install finds everything registered
+        install_project(&data_dir, &project, "abort").unwrap();
+        assert!(!dir.join("board/sym-lib-table.bak").exists());
+        assert!(install_project(&data_dir, &data_dir.join("symbols/Atlantix_Resistors.kicad_sym"), "abort").is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
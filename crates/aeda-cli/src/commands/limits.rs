@@ -0,0 +1,115 @@
+//! Field length limits from config.toml
+//!
+//! Altium CSVs cut every text field to 255 characters unless configured
+//! otherwise; other exporters are unlimited. A `[limits.<exporter>]` table
+//! sets a `default` limit for its columns and limits per column name, 0
+//! meaning unlimited:
+//!
+//! ```toml
+//! [limits.altium]
+//! default = 255
+//! Datasheet = 0
+//!
+//! [limits.bom]
+//! Description = 40
+//! ```
+//!
+//! Values are cut on character boundaries and every cut is reported after
+//! the export.
+
+use component::limits::Limits;
+use component::settings::Settings;
+use std::fs;
+use std::path::Path;
+
+/// Exporters that apply limits
+const EXPORTERS: [&str; 2] = ["altium", "bom"];
+
+/// Most cuts listed before the report is summarized
+const REPORTED: usize = 10;
+
+fn limit(exporter: &str, key: &str, value: &toml::Value) -> Result<usize, String> {
+    value
+        .as_integer()
+        .and_then(|limit| usize::try_from(limit).ok())
+        .ok_or_else(|| format!("Limit '{}' of [limits.{}] must be a non-negative number", key, exporter))
+}
+
+/// Limits of a `[limits.<exporter>]` table
+fn parse(exporter: &str, section: &toml::Value) -> Result<Limits, String> {
+    if !EXPORTERS.contains(&exporter) {
+        return Err(format!("Unknown exporter '{}' in [limits], expected {}", exporter, EXPORTERS.join(" or ")));
+    }
+    let section = section.as_table().ok_or_else(|| format!("[limits.{}] must be a table", exporter))?;
+    let mut limits = Limits::default();
+    for (key, value) in section {
+        let max = limit(exporter, key, value)?;
+        if key == "default" {
+            limits.default = (max > 0).then_some(max);
+        } else {
+            limits.columns.insert(key.clone(), max);
+        }
+    }
+    Ok(limits)
+}
+
+/// Take the `[limits]` tables of config.toml, if any, into `settings`
+pub fn install(data_dir: &Path, settings: &mut Settings) -> Result<(), String> {
+    let config_path = data_dir.join("config.toml");
+    let Ok(content) = fs::read_to_string(&config_path) else {
+        return Ok(());
+    };
+    let config: toml::Table = content
+        .parse()
+        .map_err(|e| format!("Failed to parse {}: {}", config_path.display(), e))?;
+    let Some(section) = config.get("limits") else {
+        return Ok(());
+    };
+    let section = section.as_table().ok_or_else(|| format!("[limits] must be a table in {}", config_path.display()))?;
+    for (exporter, table) in section {
+        let limits = parse(exporter, table).map_err(|e| format!("{} in {}", e, config_path.display()))?;
+        settings.limits.insert(exporter.clone(), limits);
+    }
+    Ok(())
+}
+
+/// Warn about the values cut to their column limits since the last report
+pub fn report() {
+    let truncations = component::limits::take_truncations();
+    if truncations.is_empty() {
+        return;
+    }
+    eprintln!("Warning: {} values cut to their column limits:", truncations.len());
+    for truncation in truncations.iter().take(REPORTED) {
+        eprintln!("  {}", truncation);
+    }
+    if truncations.len() > REPORTED {
+        eprintln!("  ... and {} more", truncations.len() - REPORTED);
+    }
+    eprintln!("Raise them under [limits] in config.toml.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_are_set_per_column() {
+        let config: toml::Table = r#"
+            [limits.altium]
+            default = 255
+            Datasheet = 0
+            Description = 80
+        "#
+        .parse()
+        .unwrap();
+        let limits = parse("altium", &config["limits"]["altium"]).unwrap();
+        assert_eq!(limits.of("Datasheet"), None);
+        assert_eq!(limits.of("Description"), Some(80));
+        assert_eq!(limits.of("Value"), Some(255));
+
+        let negative: toml::Table = "[limits.bom]\nValue = -1".parse().unwrap();
+        assert!(parse("bom", &negative["limits"]["bom"]).unwrap_err().contains("Value"));
+        assert!(parse("plm", &config["limits"]["altium"]).unwrap_err().contains("plm"));
+    }
+}
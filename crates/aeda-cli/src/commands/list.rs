@@ -1,18 +1,37 @@
-//! List available component libraries
+//! List available component libraries, with their part counts from the
+//! parts database
 
-use serde::Deserialize;
-use std::collections::HashMap;
+use super::generate::{load_manifest, parse_min_power};
+use super::output;
+use component::{PartStatus, Power};
+use serde::Serialize;
 use std::fs;
 use std::path::Path;
 
-#[derive(Deserialize)]
-struct Manifest {
-    name: String,
-    version: String,
-    libraries: HashMap<String, HashMap<String, String>>,
+/// Power rating of a library, from `power_mw` or the older `power_rating` string
+fn library_power(data_dir: &Path, path: &str) -> Option<Power> {
+    let content = fs::read_to_string(data_dir.join("libraries").join(path)).ok()?;
+    let library: serde_json::Value = serde_json::from_str(&content).ok()?;
+    if let Some(milliwatts) = library.get("power_mw").and_then(|v| v.as_u64()) {
+        return Some(Power(milliwatts as u32));
+    }
+    library.get("power_rating")?.as_str()?.parse().ok()
+}
+
+/// A library as listed
+#[derive(Debug, Serialize)]
+struct Listed {
+    library: String,
+    path: String,
+    status: PartStatus,
+    #[serde(rename = "power_mw")]
+    power: Option<Power>,
+    parts: usize,
 }
 
-pub fn run(data_dir: &Path, component_type: &str) -> Result<(), String> {
+pub fn run(data_dir: &Path, component_type: &str, status: Option<&str>, min_power: Option<&str>) -> Result<(), String> {
+    let status: Option<PartStatus> = status.map(str::parse).transpose()?;
+    let min_power = parse_min_power(min_power)?;
     let manifest_path = data_dir.join("libraries/manifest.json");
 
     if !manifest_path.exists() {
@@ -22,33 +41,88 @@ pub fn run(data_dir: &Path, component_type: &str) -> Result<(), String> {
         ));
     }
 
-    let content = fs::read_to_string(&manifest_path)
-        .map_err(|e| format!("Failed to read manifest: {}", e))?;
-
-    let manifest: Manifest = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse manifest: {}", e))?;
-
-    println!("Atlantix EDA Libraries ({})", manifest.name);
-    println!("Version: {}\n", manifest.version);
+    let manifest = load_manifest(data_dir)?;
+    let conn = super::parts_db::open(data_dir)?;
+    let part_counts = super::parts_db::part_counts(&conn)?;
 
     let filter_all = component_type == "all";
-
+    let mut categories = Vec::new();
     for (category, items) in &manifest.libraries {
         if !filter_all && category != component_type {
             continue;
         }
+        let mut libraries = Vec::new();
+        for (name, path) in items {
+            let part_status = manifest.status_of(category, name);
+            if status.is_some_and(|s| s != part_status) {
+                continue;
+            }
+            let power = library_power(data_dir, path);
+            if let Some(min) = min_power {
+                // Libraries without a power rating never match a power filter
+                if power.is_none_or(|p| p < min) {
+                    continue;
+                }
+            }
+            let library = format!("{}::{}", category, name);
+            let parts = part_counts.get(&library).copied().unwrap_or(0);
+            libraries.push(Listed { library, path: path.clone(), status: part_status, power, parts });
+        }
+        categories.push((category, items.is_empty(), libraries));
+    }
+
+    let pending: Vec<String> = manifest
+        .pending
+        .iter()
+        .filter(|(category, _)| filter_all || *category == component_type)
+        .flat_map(|(category, items)| items.keys().map(move |name| format!("{}::{}", category, name)))
+        .collect();
+    let families = component::family::families();
 
-        if items.is_empty() {
+    if output::json() {
+        let libraries: Vec<&Listed> = categories.iter().flat_map(|(_, _, libraries)| libraries).collect();
+        let families: Vec<serde_json::Value> = families
+            .iter()
+            .filter(|_| filter_all)
+            .map(|family| serde_json::json!({ "name": family.name(), "description": family.description() }))
+            .collect();
+        return output::print(&serde_json::json!({
+            "name": manifest.name,
+            "version": manifest.version,
+            "count": libraries.len(),
+            "parts": libraries.iter().map(|library| library.parts).sum::<usize>(),
+            "libraries": libraries,
+            "pending": pending,
+            "families": families,
+        }));
+    }
+
+    println!("Atlantix EDA Libraries ({})", manifest.name);
+    println!("Version: {}\n", manifest.version);
+
+    for (category, empty, libraries) in &categories {
+        if *empty {
             println!("{}/ (empty - run 'aeda generate')", category);
         } else {
             println!("{}/", category);
-            for (name, path) in items {
-                println!("  {}::{} -> {}", category, name, path);
+            for listed in libraries {
+                match listed.power {
+                    Some(power) => println!("  {} -> {} [{}] {}, {} parts", listed.library, listed.path, listed.status, power, listed.parts),
+                    None => println!("  {} -> {} [{}] {} parts", listed.library, listed.path, listed.status, listed.parts),
+                }
             }
         }
         println!();
     }
 
+    if !pending.is_empty() {
+        println!("pending/ (awaiting 'aeda approve')");
+        for library in &pending {
+            println!("  {}", library);
+        }
+        println!();
+    }
+
     if manifest.libraries.values().all(|v| v.is_empty()) {
         println!("No libraries generated yet.");
         println!("\nGenerate libraries with:");
@@ -56,5 +130,12 @@ pub fn run(data_dir: &Path, component_type: &str) -> Result<(), String> {
         println!("  aeda generate capacitors --dielectric X7R --packages 0603");
     }
 
+    if filter_all && !families.is_empty() {
+        println!("\nFurther families ('aeda generate <family> --help'):");
+        for family in families {
+            println!("  {:<16} {}", family.name(), family.description());
+        }
+    }
+
     Ok(())
 }
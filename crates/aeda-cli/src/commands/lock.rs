@@ -0,0 +1,229 @@
+//! Per-category edit locks for shared data directories
+//!
+//! Commands that rewrite a library category hold `locks/<category>.lock`
+//! while they run, so two librarians working on the same network data dir
+//! can't regenerate the same category at once. Commands that only read a
+//! category hold a shared lock, `locks/<category>.<host>-<pid>.shared`,
+//! which other readers ignore but writers wait on. Every read-modify-write
+//! of the manifest holds `locks/manifest.lock`, waiting briefly for another
+//! run to finish its update. A lock left behind by a crashed run is cleared
+//! with `aeda unlock <category>` (or `aeda unlock manifest`).
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How long a manifest update waits for another run's update to finish
+const MANIFEST_WAIT: Duration = Duration::from_secs(10);
+
+#[derive(Serialize, Deserialize)]
+struct LockInfo {
+    user: String,
+    host: String,
+    pid: u32,
+    created: String,
+}
+
+impl LockInfo {
+    fn current() -> Self {
+        LockInfo {
+            user: std::env::var("USER")
+                .or_else(|_| std::env::var("USERNAME"))
+                .unwrap_or_else(|_| "unknown".into()),
+            host: hostname(),
+            pid: std::process::id(),
+            created: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for LockInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}@{} (pid {}) since {}", self.user, self.host, self.pid, self.created)
+    }
+}
+
+fn hostname() -> String {
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "unknown".into())
+}
+
+fn lock_path(data_dir: &Path, category: &str) -> PathBuf {
+    data_dir.join("locks").join(format!("{}.lock", category))
+}
+
+/// Shared locks held on `category`, by their owners
+fn shared_locks(data_dir: &Path, category: &str) -> Vec<PathBuf> {
+    let prefix = format!("{}.", category);
+    fs::read_dir(data_dir.join("locks"))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            name.starts_with(&prefix) && name.ends_with(".shared")
+        })
+        .collect()
+}
+
+fn read_owner(path: &Path) -> Option<LockInfo> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn owner(path: &Path) -> String {
+    read_owner(path)
+        .map(|info| info.to_string())
+        .unwrap_or_else(|| "an unknown user".into())
+}
+
+/// Create the lock file at `path` with the current owner, `None` when it exists
+fn create(path: &Path) -> Result<Option<PathBuf>, String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let mut file = match OpenOptions::new().write(true).create_new(true).open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => return Ok(None),
+        Err(e) => return Err(format!("Failed to create lock {}: {}", path.display(), e)),
+    };
+    let content = serde_json::to_string_pretty(&LockInfo::current())
+        .map_err(|e| format!("Failed to serialize lock: {}", e))?;
+    file.write_all(content.as_bytes())
+        .map_err(|e| format!("Failed to write lock {}: {}", path.display(), e))?;
+    Ok(Some(path.to_path_buf()))
+}
+
+fn locked_error(category: &str, owner: &str) -> String {
+    format!(
+        "Category '{}' is locked by {}. If that run is no longer active, clear it with 'aeda unlock {}'",
+        category, owner, category
+    )
+}
+
+/// Held for the duration of a command; the lock file is removed on drop
+pub(crate) struct CategoryLock {
+    path: PathBuf,
+}
+
+impl CategoryLock {
+    /// Exclusive lock, failing while anyone else holds the category
+    pub(crate) fn acquire(data_dir: &Path, category: &str) -> Result<Self, String> {
+        let path = lock_path(data_dir, category);
+        let Some(path) = create(&path)? else {
+            return Err(locked_error(category, &owner(&path)));
+        };
+        let lock = CategoryLock { path };
+        // Readers mark themselves first, so one that got in before us is seen here
+        if let Some(reader) = shared_locks(data_dir, category).first() {
+            return Err(locked_error(category, &owner(reader)));
+        }
+        Ok(lock)
+    }
+
+    /// Shared lock for commands that only read the category, failing while
+    /// it is locked exclusively
+    pub(crate) fn acquire_shared(data_dir: &Path, category: &str) -> Result<Self, String> {
+        let name = format!("{}.{}-{}.shared", category, hostname(), std::process::id());
+        let path = create(&data_dir.join("locks").join(name))?
+            .ok_or_else(|| format!("Category '{}' is already locked by this run", category))?;
+        let lock = CategoryLock { path };
+        let exclusive = lock_path(data_dir, category);
+        if exclusive.exists() {
+            return Err(locked_error(category, &owner(&exclusive)));
+        }
+        Ok(lock)
+    }
+
+    /// Lock on the manifest for one read-modify-write, waiting for another
+    /// run's update to finish
+    pub(crate) fn acquire_manifest(data_dir: &Path) -> Result<Self, String> {
+        let path = lock_path(data_dir, "manifest");
+        let start = Instant::now();
+        loop {
+            if let Some(path) = create(&path)? {
+                return Ok(CategoryLock { path });
+            }
+            if start.elapsed() > MANIFEST_WAIT {
+                return Err(format!(
+                    "The manifest is locked by {}. If that run is no longer active, clear it with 'aeda unlock manifest'",
+                    owner(&path)
+                ));
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+}
+
+impl Drop for CategoryLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+pub fn unlock(data_dir: &Path, category: &str) -> Result<(), String> {
+    let locks: Vec<PathBuf> = std::iter::once(lock_path(data_dir, category))
+        .filter(|path| path.exists())
+        .chain(shared_locks(data_dir, category))
+        .collect();
+    if locks.is_empty() {
+        println!("Category '{}' is not locked.", category);
+        return Ok(());
+    }
+
+    for path in locks {
+        let owner = read_owner(&path);
+        fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        match owner {
+            Some(info) => println!("Removed lock on '{}' held by {}", category, info),
+            None => println!("Removed lock on '{}'", category),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn second_acquire_fails_until_released() {
+        let dir = std::env::temp_dir().join(format!("aeda-lock-test-{}", std::process::id()));
+        let first = CategoryLock::acquire(&dir, "resistor").unwrap();
+        let err = CategoryLock::acquire(&dir, "resistor").err().unwrap();
+        assert!(err.contains("aeda unlock resistor"));
+        assert!(CategoryLock::acquire(&dir, "capacitor").is_ok());
+
+        drop(first);
+        assert!(CategoryLock::acquire(&dir, "resistor").is_ok());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn shared_locks_exclude_writers_only() {
+        let dir = std::env::temp_dir().join(format!("aeda-shared-lock-test-{}", std::process::id()));
+        let reader = CategoryLock::acquire_shared(&dir, "resistor").unwrap();
+        assert!(CategoryLock::acquire(&dir, "resistor").is_err());
+        assert!(CategoryLock::acquire(&dir, "capacitor").is_ok());
+        drop(reader);
+
+        let writer = CategoryLock::acquire(&dir, "resistor").unwrap();
+        assert!(CategoryLock::acquire_shared(&dir, "resistor").is_err());
+        drop(writer);
+        assert!(CategoryLock::acquire_shared(&dir, "resistor").is_ok());
+
+        let manifest = CategoryLock::acquire_manifest(&dir).unwrap();
+        drop(manifest);
+        assert!(CategoryLock::acquire_manifest(&dir).is_ok());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,271 @@
+//! Migrate outputs of older releases to the current conventions
+//!
+//! `aeda migrate v1-to-v2` upgrades, in place, what the 0.1 releases wrote:
+//!
+//! - Altium CSVs in the 13 column layout, ending in `Company,Comment` with a
+//!   ` =Description` comment, get the current header. Comment follows the
+//!   configured Altium comment, AEC-Q200 and Datasheet are left empty.
+//! - Part names are rebuilt from their package and value, so `RES0402_1K`
+//!   becomes `RES0402_1.00K` in the selected value notation.
+//! - KiCad symbols named like the Altium parts, `RES0402_1.00K`, are renamed
+//!   to `R0402_1.00K` together with their unit symbols.
+//! - Resistor libraries without a manufacturer or `power_mw` get Vishay, the
+//!   only manufacturer of 0.1, and the milliwatts of their power rating.
+//!
+//! Files already in the current format are left alone. Anything that cannot
+//! be mapped is kept as it is and listed at the end.
+
+use super::generate::ResistorLibrary;
+use super::output::say;
+use component::{Power, Resistance};
+use kiparse::sexpr::{parse_sexpr, SExpr};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Altium CSV header of 0.1, before Comment was a column of its own
+const V1_HEADER: &str = "Part,Description,Value,Case,Power,Supplier 1,Supplier Part Number 1,Library Path,Library Ref,Footprint Path,Footprint Ref,Company,Comment";
+
+/// The only manufacturer of 0.1 libraries
+const V1_MANUFACTURER: &str = "Vishay";
+
+pub fn v1_to_v2(data_dir: &Path, paths: &[PathBuf]) -> Result<(), String> {
+    let roots = if paths.is_empty() { vec![data_dir.to_path_buf()] } else { paths.to_vec() };
+    let mut files = Vec::new();
+    for root in &roots {
+        if root.is_dir() {
+            collect_files(root, &mut files);
+        } else if root.exists() {
+            files.push(root.clone());
+        } else {
+            return Err(format!("{} does not exist", root.display()));
+        }
+    }
+
+    say!("Migrating {} files from v1 to v2 conventions...", files.len());
+    let mut migrated = 0;
+    let mut unmapped = Vec::new();
+    for path in &files {
+        // Binary files such as 3D models have nothing to migrate
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        let mut notes = Vec::new();
+        let rewritten = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("csv") => migrate_csv(&content, &mut notes),
+            Some("kicad_sym") => migrate_symbols(&content, &mut notes),
+            Some("json") => migrate_library(&content, &mut notes),
+            _ => None,
+        };
+        unmapped.extend(notes.into_iter().map(|note| format!("{}: {}", path.display(), note)));
+        if let Some(rewritten) = rewritten {
+            component::atomic::write(path, rewritten)?;
+            say!("  Migrated {}", path.display());
+            migrated += 1;
+        }
+    }
+
+    say!("\nMigrated {} of {} files", migrated, files.len());
+    if !unmapped.is_empty() {
+        say!("Left unchanged, could not map:");
+        for note in &unmapped {
+            say!("  {}", note);
+        }
+    }
+    Ok(())
+}
+
+/// Files under `dir`, recursively
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir).into_iter().flatten().flatten().map(|entry| entry.path()).collect();
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+/// Package and value of a part name such as `RES0402_1K` or `R0402_1.00K`
+fn parse_name(name: &str) -> Option<(&str, Resistance)> {
+    let rest = name.strip_prefix("RES").or_else(|| name.strip_prefix('R'))?;
+    let (package, value) = rest.split_once('_')?;
+    Some((package, value.parse().ok()?))
+}
+
+/// Fields of a CSV line, split on commas outside quotes
+fn split_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            _ => fields.last_mut().expect("starts with one field").push(c),
+        }
+    }
+    fields
+}
+
+fn quote(field: &str) -> String {
+    if field.contains(',') {
+        format!("\"{}\"", field)
+    } else {
+        field.to_string()
+    }
+}
+
+/// An Altium CSV of 0.1 in the current layout, `None` for other CSVs
+fn migrate_csv(content: &str, unmapped: &mut Vec<String>) -> Option<String> {
+    let mut lines = content.lines();
+    if lines.next()?.trim() != V1_HEADER {
+        return None;
+    }
+    let old_columns: Vec<&str> = V1_HEADER.split(',').collect();
+    let mut csv = component::altium::CSV_HEADER.to_string();
+    for (number, line) in (2..).zip(lines).filter(|(_, line)| !line.trim().is_empty()) {
+        let fields = split_fields(line.trim_end());
+        if fields.len() != old_columns.len() {
+            unmapped.push(format!("line {} has {} columns, expected {}", number, fields.len(), old_columns.len()));
+        }
+        let old = |column: &str| {
+            let index = old_columns.iter().position(|c| *c == column)?;
+            fields.get(index).map(|field| field.trim().to_string())
+        };
+        let part = old("Part").unwrap_or_default();
+        let mapped = parse_name(&part);
+        if mapped.is_none() {
+            unmapped.push(format!("part {} on line {} has no package and value", part, number));
+        }
+        let row: Vec<String> = component::altium::CSV_HEADER
+            .trim_end()
+            .split(',')
+            .map(|column| match (column, mapped) {
+                ("Part", Some((package, value))) => {
+                    component::naming::part_name("RES", package, value, None)
+                }
+                ("Value", Some((_, value))) => value.label(),
+                ("Description", _) => format!("\"{}\"", old(column).unwrap_or_default()),
+                ("Comment", _) => match component::altium::comment() {
+                    component::altium::Comment::ValueParameter => "=Value".to_string(),
+                    component::altium::Comment::Literal => mapped.map(|(_, value)| value.label()).or(old("Value")).unwrap_or_default(),
+                },
+                _ => quote(&old(column).unwrap_or_default()),
+            })
+            .collect();
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+    Some(csv)
+}
+
+/// A symbol library with its `RES` named symbols renamed, `None` when it has none
+fn migrate_symbols(content: &str, unmapped: &mut Vec<String>) -> Option<String> {
+    let mut library = parse_sexpr(content).ok()?;
+    if library.head() != Some("kicad_symbol_lib") {
+        return None;
+    }
+    let mut renamed = 0;
+    for symbol in library.items_mut()?.iter_mut().filter(|item| item.head() == Some("symbol")) {
+        let Some(name) = symbol.value(1).filter(|name| name.starts_with("RES")).map(str::to_string) else {
+            continue;
+        };
+        let Some((package, value)) = parse_name(&name) else {
+            unmapped.push(format!("symbol {} has no package and value", name));
+            continue;
+        };
+        let new_name = component::resistor_name(package, value, None);
+        symbol.set_value(1, SExpr::String(new_name.clone()));
+        // Unit symbols are named after their parent, `RES0402_1.00K_0_1`
+        for unit in symbol.items_mut()?.iter_mut().filter(|item| item.head() == Some("symbol")) {
+            if let Some(suffix) = unit.value(1).and_then(|unit_name| unit_name.strip_prefix(name.as_str())) {
+                let unit_name = format!("{}{}", new_name, suffix);
+                unit.set_value(1, SExpr::String(unit_name));
+            }
+        }
+        renamed += 1;
+    }
+    (renamed > 0).then(|| format!("{}\n", library.to_kicad_string()))
+}
+
+/// A resistor library of 0.1 with the fields added since, `None` for other JSON
+fn migrate_library(content: &str, unmapped: &mut Vec<String>) -> Option<String> {
+    let mut json: serde_json::Value = serde_json::from_str(content).ok()?;
+    if json["type"] != "resistor" || (json.get("manufacturer").is_some() && json.get("power_mw").is_some()) {
+        return None;
+    }
+    if json.get("manufacturer").is_none() {
+        json["manufacturer"] = V1_MANUFACTURER.into();
+    }
+    if json.get("power_mw").is_none() {
+        let rating = json["power_rating"].as_str().unwrap_or_default();
+        match rating.parse::<Power>() {
+            Ok(power) => json["power_mw"] = power.milliwatts().into(),
+            Err(_) => unmapped.push(format!("power rating '{}' is not a power", rating)),
+        }
+    }
+    match serde_json::from_value::<ResistorLibrary>(json) {
+        Ok(library) => serde_json::to_string_pretty(&library).ok(),
+        Err(e) => {
+            unmapped.push(format!("not a resistor library: {}", e));
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn v1_altium_rows_get_the_current_columns() {
+        let v1 = "Part,Description,Value,Case,Power,Supplier 1,Supplier Part Number 1,Library Path,Library Ref,Footprint Path,Footprint Ref,Company,Comment \r\n\
+                  RES0402_1K,\"RES 0402 1KOhm 1/16W\",1K,0402,1/16,Digikey,541-1.00KLCT-ND,Atlantix_R.SchLib,Res1,Atlantix_R.PcbLib,RES0402,Atlantix EDA, =Description\r\n\
+                  RES0402_X,\"RES 0402\",X,0402,1/16,Digikey,,Atlantix_R.SchLib,Res1,Atlantix_R.PcbLib,RES0402,Atlantix EDA, =Description\r\n";
+        let mut unmapped = Vec::new();
+        let csv = migrate_csv(v1, &mut unmapped).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(format!("{}\r\n", lines.next().unwrap()), component::altium::CSV_HEADER);
+        assert_eq!(
+            lines.next().unwrap(),
+            "RES0402_1.00K,\"RES 0402 1KOhm 1/16W\",1.00K,0402,1/16,Digikey,541-1.00KLCT-ND,\
+             Atlantix_R.SchLib,Res1,Atlantix_R.PcbLib,RES0402,Atlantix EDA,=Value,,"
+        );
+        assert!(lines.next().unwrap().starts_with("RES0402_X,"));
+        assert_eq!(unmapped, ["part RES0402_X on line 3 has no package and value"]);
+        // Already migrated
+        assert_eq!(migrate_csv(&csv, &mut unmapped), None);
+    }
+
+    #[test]
+    fn res_symbols_are_renamed_with_their_units() {
+        let v1 = r#"(kicad_symbol_lib (version 20211014) (generator atlantix)
+  (symbol "RES0603_4.99K" (in_bom yes)
+    (symbol "RES0603_4.99K_0_1" (rectangle))
+  )
+  (symbol "R0603_10.0K" (in_bom yes))
+)"#;
+        let mut unmapped = Vec::new();
+        let migrated = migrate_symbols(v1, &mut unmapped).unwrap();
+        assert!(migrated.contains("(symbol \"R0603_4.99K\"") && migrated.contains("(symbol \"R0603_4.99K_0_1\""));
+        assert!(!migrated.contains("RES0603"));
+        assert!(unmapped.is_empty());
+        assert_eq!(migrate_symbols(&migrated, &mut unmapped), None);
+    }
+
+    #[test]
+    fn v1_libraries_get_manufacturer_and_milliwatts() {
+        let v1 = r#"{"name": "E96_0603", "type": "resistor", "description": "E96 Resistors in 0603 package",
+            "package": "0603", "footprint": "Resistor_SMD:R_0603_1608Metric", "tolerance": "1%",
+            "power_rating": "1/10W", "series": "E96", "pins": ["1", "2"], "prefix": "R",
+            "base_values": [1.0], "multipliers": {"K": 1000.0},
+            "methods": {"after_factory": [], "after_value": []}}"#;
+        let mut unmapped = Vec::new();
+        let migrated: serde_json::Value = serde_json::from_str(&migrate_library(v1, &mut unmapped).unwrap()).unwrap();
+        assert_eq!(migrated["manufacturer"], "Vishay");
+        assert_eq!(migrated["power_mw"], 100);
+        assert!(unmapped.is_empty());
+        assert_eq!(migrate_library(&migrated.to_string(), &mut unmapped), None);
+    }
+}
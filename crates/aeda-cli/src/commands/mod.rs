@@ -1,9 +1,47 @@
 //! CLI command implementations
 
+pub mod bom;
+pub mod capabilities;
 pub mod config;
+pub mod dashboard;
+pub mod datasheets;
+pub mod diff;
+pub mod easyeda;
+pub mod enrich;
 pub mod export;
+pub mod fabrication;
+pub mod family;
+pub mod fix_library;
 pub mod generate;
+pub mod identify;
+pub mod import;
 pub mod info;
 pub mod init;
+pub mod ipn;
+pub mod kicad_cli;
+pub mod lib_table;
+pub mod limits;
 pub mod list;
+pub mod lock;
+pub mod migrate;
+pub mod naming;
+pub mod mouser;
+pub mod network;
+pub mod octopart;
+pub mod output;
+pub mod overwrite;
+pub mod parts_db;
+pub mod profile;
+pub mod readme;
+pub mod report;
+pub mod request;
+pub mod schema;
+pub mod script;
+pub mod search;
+pub mod selection;
+pub mod serve;
+pub mod status;
 pub mod sync;
+pub mod template;
+pub mod thermal;
+pub mod validate;
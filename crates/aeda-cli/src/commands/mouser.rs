@@ -0,0 +1,107 @@
+//! Mouser Search API client
+//!
+//! Optional price and stock lookup for Mouser part numbers. The API key is
+//! read from the `MOUSER_API_KEY` env var; without it the lookup is skipped
+//! and the generated files are unaffected.
+
+use component::AtlantixError;
+use serde_json::{json, Value};
+
+const SEARCH_URL: &str = "https://api.mouser.com/api/v1/search/partnumber";
+
+/// Price and availability of a single Mouser part
+#[derive(Debug, PartialEq)]
+pub struct MouserOffer {
+    pub part_number: String,
+    pub availability: String,
+    /// (quantity, unit price) price breaks as reported by Mouser, e.g. "$0.10"
+    pub price_breaks: Vec<(u32, String)>,
+    pub url: Option<String>,
+}
+
+/// API key from the environment, if configured
+pub fn api_key() -> Option<String> {
+    std::env::var("MOUSER_API_KEY").ok().filter(|key| !key.trim().is_empty())
+}
+
+/// Look up an exact Mouser part number. Returns `Ok(None)` when Mouser does
+/// not list the part.
+pub fn lookup(api_key: &str, mouser_pn: &str) -> Result<Option<MouserOffer>, String> {
+    super::network::require("The Mouser lookup")?;
+    let body = json!({
+        "SearchByPartRequest": {
+            "mouserPartNumber": mouser_pn,
+            "partSearchOptions": "Exact",
+        }
+    });
+    let response = super::network::post_json(&format!("{}?apiKey={}", SEARCH_URL, api_key), None, body)
+        .map_err(|e| AtlantixError::api("Mouser", format!("{}: {}", mouser_pn, e)))?;
+
+    if let Some(error) = response["Errors"].as_array().and_then(|errors| errors.first()) {
+        let message = error["Message"].as_str().unwrap_or("unknown error");
+        return Err(AtlantixError::api("Mouser", format!("search for {} rejected: {}", mouser_pn, message)).into());
+    }
+    Ok(parse_offer(&response, mouser_pn))
+}
+
+fn parse_offer(response: &Value, mouser_pn: &str) -> Option<MouserOffer> {
+    let parts = response["SearchResults"]["Parts"].as_array()?;
+    let part = parts
+        .iter()
+        .find(|part| part["MouserPartNumber"].as_str() == Some(mouser_pn))
+        .or_else(|| parts.first())?;
+
+    let availability = part["AvailabilityInStock"]
+        .as_str()
+        .map(|stock| format!("{} In Stock", stock))
+        .or_else(|| part["Availability"].as_str().map(str::to_string))
+        .unwrap_or_else(|| "Unknown".to_string());
+    let price_breaks = part["PriceBreaks"]
+        .as_array()
+        .map(|breaks| {
+            breaks
+                .iter()
+                .filter_map(|b| Some((b["Quantity"].as_u64()? as u32, b["Price"].as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(MouserOffer {
+        part_number: part["MouserPartNumber"].as_str().unwrap_or(mouser_pn).to_string(),
+        availability,
+        price_breaks,
+        url: part["ProductDetailUrl"].as_str().map(str::to_string),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_search_results() {
+        let response = json!({
+            "Errors": [],
+            "SearchResults": {
+                "NumberOfResult": 1,
+                "Parts": [{
+                    "MouserPartNumber": "71-CRCW06031K00FKEA",
+                    "Availability": "125000 In Stock",
+                    "AvailabilityInStock": "125000",
+                    "PriceBreaks": [
+                        { "Quantity": 1, "Price": "$0.10", "Currency": "USD" },
+                        { "Quantity": 100, "Price": "$0.021", "Currency": "USD" }
+                    ],
+                    "ProductDetailUrl": "https://www.mouser.com/ProductDetail/71-CRCW06031K00FKEA"
+                }]
+            }
+        });
+        let offer = parse_offer(&response, "71-CRCW06031K00FKEA").unwrap();
+        assert_eq!(offer.availability, "125000 In Stock");
+        assert_eq!(offer.price_breaks, [(1, "$0.10".to_string()), (100, "$0.021".to_string())]);
+        assert!(offer.url.unwrap().ends_with("71-CRCW06031K00FKEA"));
+
+        let empty = json!({ "Errors": [], "SearchResults": { "NumberOfResult": 0, "Parts": [] } });
+        assert_eq!(parse_offer(&empty, "71-CRCW06031K00FKEA"), None);
+    }
+}
@@ -0,0 +1,73 @@
+//! Part and library name templates from config.toml
+//!
+//! ```toml
+//! [naming]
+//! part = "{prefix}-{package}-{value}-{tolerance}"
+//! library = "RES_{series}_{package}"
+//! ```
+//!
+//! See `component::naming` for the fields. Either key may be left out to
+//! keep the built-in names.
+
+use component::naming::NameTemplate;
+use component::settings::Settings;
+use std::fs;
+use std::path::Path;
+
+/// Take the `[naming]` table of config.toml, if any, into `settings`
+pub fn install(data_dir: &Path, settings: &mut Settings) -> Result<(), String> {
+    let config_path = data_dir.join("config.toml");
+    let Ok(content) = fs::read_to_string(&config_path) else {
+        return Ok(());
+    };
+    let config: toml::Table = content
+        .parse()
+        .map_err(|e| format!("Failed to parse {}: {}", config_path.display(), e))?;
+    let Some(section) = config.get("naming") else {
+        return Ok(());
+    };
+    let section = section.as_table().ok_or_else(|| format!("[naming] must be a table in {}", config_path.display()))?;
+    if let Some(key) = section.keys().find(|key| !["part", "library"].contains(&key.as_str())) {
+        return Err(format!("Unknown key '{}' in [naming] of {}, expected part or library", key, config_path.display()));
+    }
+    let template = |key: &str| -> Result<Option<NameTemplate>, String> {
+        let Some(value) = section.get(key) else {
+            return Ok(None);
+        };
+        let value = value.as_str().ok_or_else(|| format!("naming.{} in {} must be a string", key, config_path.display()))?;
+        let template: NameTemplate = value.parse().map_err(|e| format!("naming.{} in {}: {}", key, config_path.display(), e))?;
+        Ok(Some(template))
+    };
+    let part = template("part")?.map(NameTemplate::for_parts).transpose();
+    let library = template("library")?.map(NameTemplate::for_libraries).transpose();
+    settings.part_template = part.map_err(|e| format!("{} in {}", e, config_path.display()))?;
+    settings.library_template = library.map_err(|e| format!("{} in {}", e, config_path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn installs_templates() {
+        let dir = std::env::temp_dir().join(format!("aeda_naming_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config = |naming: &str| fs::write(dir.join("config.toml"), format!("[naming]\n{}\n", naming)).unwrap();
+
+        let mut settings = Settings::default();
+        config("part = \"R_{package}\"");
+        assert!(install(&dir, &mut settings).unwrap_err().contains("needs {value} or {code}"));
+        config("library = \"{series}_{value}\"");
+        assert!(install(&dir, &mut settings).unwrap_err().contains("cannot use {value}"));
+        config("library = \"{series}_{colour}\"");
+        assert!(install(&dir, &mut settings).unwrap_err().contains("Unknown name field '{colour}'"));
+
+        config("library = \"RES_{series}_{package}_{tolerance}\"");
+        install(&dir, &mut settings).unwrap();
+        let name = settings.scope(|| component::naming::library_name("E96", "0603", None));
+        assert_eq!(name, "RES_E96_0603");
+        assert_eq!(component::naming::library_name("E96", "0603", None), "E96_0603");
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
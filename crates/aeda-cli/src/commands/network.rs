@@ -0,0 +1,178 @@
+//! Network access, and the offline mode of air-gapped build machines
+//!
+//! Octopart enrichment, Mouser lookups and link checks are the only features
+//! reaching the network, and all of them go through this module. Network
+//! access is disabled at compile time by building without the default
+//! `network` feature, which leaves the HTTP client out entirely, or at run
+//! time with
+//!
+//! ```toml
+//! [general]
+//! offline = true
+//! ```
+//!
+//! in config.toml or `AEDA_OFFLINE=1` in the environment. Commands asking
+//! for a network feature then fail with an error saying why, while cached
+//! Octopart data and link checks are still used.
+
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// `[general] offline` of a config.toml
+fn parse(config: &toml::Table) -> Result<bool, String> {
+    match config.get("general").and_then(|general| general.get("offline")) {
+        None => Ok(false),
+        Some(offline) => offline.as_bool().ok_or_else(|| "[general] offline must be true or false".to_string()),
+    }
+}
+
+/// Turn offline mode on when config.toml or `AEDA_OFFLINE` asks for it
+pub fn install(data_dir: &Path) -> Result<(), String> {
+    let from_env = std::env::var("AEDA_OFFLINE").is_ok_and(|value| !matches!(value.trim(), "" | "0" | "false"));
+    let config_path = data_dir.join("config.toml");
+    let from_config = match fs::read_to_string(&config_path) {
+        Ok(content) => {
+            let config: toml::Table = content
+                .parse()
+                .map_err(|e| format!("Failed to parse {}: {}", config_path.display(), e))?;
+            parse(&config).map_err(|e| format!("{} in {}", e, config_path.display()))?
+        }
+        Err(_) => false,
+    };
+    OFFLINE.store(from_env || from_config, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Why the network may not be used, `None` when it may
+fn disabled() -> Option<&'static str> {
+    if !cfg!(feature = "network") {
+        Some("this aeda was built without the 'network' feature")
+    } else if OFFLINE.load(Ordering::Relaxed) {
+        Some("offline mode is on ([general] offline in config.toml or AEDA_OFFLINE)")
+    } else {
+        None
+    }
+}
+
+/// Fail with a clear error when `feature` may not use the network
+pub fn require(feature: &str) -> Result<(), String> {
+    match disabled() {
+        Some(reason) => Err(format!("{} needs network access, but {}", feature, reason)),
+        None => Ok(()),
+    }
+}
+
+/// Status of a URL without following redirects, and the redirect target
+#[derive(Debug, PartialEq)]
+pub(crate) struct Probe {
+    /// HTTP status, 0 when the server could not be reached
+    pub status: u16,
+    pub location: Option<String>,
+    pub error: Option<String>,
+}
+
+#[cfg(feature = "network")]
+mod http {
+    use super::Probe;
+    use once_cell::sync::Lazy;
+    use serde_json::Value;
+    use std::time::Duration;
+
+    /// Link checks look at the first answer only
+    static PROBE_AGENT: Lazy<ureq::Agent> =
+        Lazy::new(|| ureq::AgentBuilder::new().redirects(0).timeout(Duration::from_secs(15)).build());
+
+    fn json(response: Result<ureq::Response, ureq::Error>) -> Result<Value, String> {
+        response
+            .map_err(|e| e.to_string())?
+            .into_json()
+            .map_err(|e| format!("unreadable response: {}", e))
+    }
+
+    pub(super) fn post_json(url: &str, bearer: Option<&str>, body: Value) -> Result<Value, String> {
+        let mut request = ureq::post(url);
+        if let Some(token) = bearer {
+            request = request.set("Authorization", &format!("Bearer {}", token));
+        }
+        json(request.send_json(body))
+    }
+
+    pub(super) fn post_form(url: &str, form: &[(&str, &str)]) -> Result<Value, String> {
+        json(ureq::post(url).send_form(form))
+    }
+
+    pub(super) fn probe(url: &str) -> Probe {
+        let response = match PROBE_AGENT.head(url).call() {
+            // Some servers refuse HEAD, ask again with GET
+            Err(ureq::Error::Status(405, _)) => PROBE_AGENT.get(url).call(),
+            response => response,
+        };
+        match response {
+            Ok(response) => Probe {
+                status: response.status(),
+                location: response.header("location").map(str::to_string),
+                error: None,
+            },
+            Err(ureq::Error::Status(status, _)) => Probe { status, location: None, error: None },
+            Err(e) => Probe { status: 0, location: None, error: Some(e.to_string()) },
+        }
+    }
+}
+
+#[cfg(not(feature = "network"))]
+mod http {
+    use super::Probe;
+    use serde_json::Value;
+
+    const DISABLED: &str = "built without network support";
+
+    pub(super) fn post_json(_url: &str, _bearer: Option<&str>, _body: Value) -> Result<Value, String> {
+        Err(DISABLED.to_string())
+    }
+
+    pub(super) fn post_form(_url: &str, _form: &[(&str, &str)]) -> Result<Value, String> {
+        Err(DISABLED.to_string())
+    }
+
+    pub(super) fn probe(_url: &str) -> Probe {
+        Probe { status: 0, location: None, error: Some(DISABLED.to_string()) }
+    }
+}
+
+/// POST a JSON body, with a bearer token if given, and read a JSON answer
+pub(crate) fn post_json(url: &str, bearer: Option<&str>, body: Value) -> Result<Value, String> {
+    http::post_json(url, bearer, body)
+}
+
+/// POST a form and read a JSON answer
+pub(crate) fn post_form(url: &str, form: &[(&str, &str)]) -> Result<Value, String> {
+    http::post_form(url, form)
+}
+
+/// HEAD `url`, falling back to GET for servers refusing HEAD
+pub(crate) fn probe(url: &str) -> Probe {
+    http::probe(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offline_mode_is_read_from_general() {
+        let offline: toml::Table = "[general]\noffline = true".parse().unwrap();
+        assert!(parse(&offline).unwrap());
+        let unset: toml::Table = "[general]\ndefault_format = \"kicad\"".parse().unwrap();
+        assert!(!parse(&unset).unwrap());
+        let text: toml::Table = "[general]\noffline = \"yes\"".parse().unwrap();
+        assert!(parse(&text).unwrap_err().contains("offline"));
+
+        OFFLINE.store(true, Ordering::Relaxed);
+        assert!(require("Octopart enrichment").unwrap_err().starts_with("Octopart enrichment needs network access"));
+        OFFLINE.store(false, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,414 @@
+//! Octopart (Nexar) client and response cache
+//!
+//! Looks up generated MPNs through the Nexar GraphQL API to collect
+//! offers from every distributor, the best datasheet and the lifecycle
+//! status. Credentials come from the `NEXAR_CLIENT_ID` and
+//! `NEXAR_CLIENT_SECRET` env vars. Every answer, including parts Octopart
+//! does not know, is cached as `data_dir/cache/octopart/<MPN>.json` so
+//! repeated runs only query what is missing or stale.
+//!
+//! Cached offers also carry a link to the distributor's product page. With
+//!
+//! ```toml
+//! [distributors]
+//! supplier_url = "product"   # default "search"
+//! ```
+//!
+//! in config.toml, generated symbols link SupplierURL to that page for every
+//! cached part and fall back to the search page for the rest.
+//!
+//! With several manufacturers enabled, `prefer = "stock"` or `"price"` in
+//! the same section makes the manufacturer with cached stock, or the lowest
+//! unit price, the primary source of each value instead of the first one
+//! listed.
+
+use component::distributor::Distributor;
+use component::settings::Settings;
+use component::AtlantixError;
+use component::sourcing::{SourcingPolicy, StockInfo};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const TOKEN_URL: &str = "https://identity.nexar.com/connect/token";
+const GRAPHQL_URL: &str = "https://api.nexar.com/graphql";
+
+/// MPNs per supMultiMatch request
+const BATCH_SIZE: usize = 20;
+
+const MULTI_MATCH_QUERY: &str = "query Enrich($queries: [SupPartMatchQuery!]!) {
+  supMultiMatch(queries: $queries) {
+    reference
+    parts {
+      mpn
+      manufacturer { name }
+      bestDatasheet { url }
+      specs { attribute { shortname } displayValue }
+      sellers { company { name } offers { sku inventoryLevel clickUrl prices { quantity price currency } } }
+    }
+  }
+}";
+
+/// Octopart data for one MPN
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartInfo {
+    pub mpn: String,
+    /// `None` when Octopart does not list the MPN
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manufacturer: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datasheet: Option<String>,
+    /// Lifecycle status, e.g. "Production" or "Obsolete"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lifecycle: Option<String>,
+    #[serde(default)]
+    pub offers: Vec<Offer>,
+    /// RFC 3339 time of the lookup
+    pub fetched: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Offer {
+    pub distributor: String,
+    pub sku: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stock: Option<i64>,
+    #[serde(default)]
+    pub prices: Vec<PriceBreak>,
+    /// Octopart click-through link, redirecting to the distributor's product page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceBreak {
+    pub quantity: u32,
+    pub price: f64,
+    pub currency: String,
+}
+
+/// Authenticated Nexar session
+pub struct Nexar {
+    token: String,
+}
+
+impl Nexar {
+    /// Request an access token, `Ok(None)` when no credentials are configured
+    pub fn connect() -> Result<Option<Nexar>, String> {
+        let (Ok(id), Ok(secret)) = (std::env::var("NEXAR_CLIENT_ID"), std::env::var("NEXAR_CLIENT_SECRET")) else {
+            return Ok(None);
+        };
+        super::network::require("Octopart enrichment")?;
+        let form = [("grant_type", "client_credentials"), ("client_id", id.as_str()), ("client_secret", secret.as_str())];
+        let response = super::network::post_form(TOKEN_URL, &form)
+            .map_err(|e| AtlantixError::api("Nexar authentication", e))?;
+        let token = response["access_token"]
+            .as_str()
+            .ok_or("Nexar token response has no access_token")?;
+        Ok(Some(Nexar { token: token.to_string() }))
+    }
+
+    /// Look up MPNs in batches. Every MPN gets an entry, unknown parts
+    /// have no manufacturer.
+    pub fn match_parts(&self, mpns: &[String]) -> Result<Vec<PartInfo>, String> {
+        let mut parts = Vec::with_capacity(mpns.len());
+        for batch in mpns.chunks(BATCH_SIZE) {
+            let queries: Vec<Value> = batch
+                .iter()
+                .map(|mpn| json!({ "mpn": mpn, "reference": mpn, "limit": 1 }))
+                .collect();
+            let body = json!({ "query": MULTI_MATCH_QUERY, "variables": { "queries": queries } });
+            let response = super::network::post_json(GRAPHQL_URL, Some(&self.token), body)
+                .map_err(|e| AtlantixError::api("Octopart", e))?;
+            if let Some(error) = response["errors"].as_array().and_then(|errors| errors.first()) {
+                let message = error["message"].as_str().unwrap_or("unknown error");
+                return Err(AtlantixError::api("Octopart", format!("query rejected: {}", message)).into());
+            }
+            parts.extend(parse_multi_match(&response, batch));
+        }
+        Ok(parts)
+    }
+}
+
+fn parse_multi_match(response: &Value, mpns: &[String]) -> Vec<PartInfo> {
+    let fetched = chrono::Utc::now().to_rfc3339();
+    let matches = response["data"]["supMultiMatch"].as_array().cloned().unwrap_or_default();
+    mpns.iter()
+        .map(|mpn| {
+            let part = matches
+                .iter()
+                .find(|m| m["reference"].as_str() == Some(mpn.as_str()))
+                .and_then(|m| m["parts"].as_array())
+                .and_then(|parts| parts.first());
+            let Some(part) = part else {
+                return PartInfo {
+                    mpn: mpn.clone(),
+                    manufacturer: None,
+                    datasheet: None,
+                    lifecycle: None,
+                    offers: Vec::new(),
+                    fetched: fetched.clone(),
+                };
+            };
+            let lifecycle = part["specs"].as_array().and_then(|specs| {
+                specs
+                    .iter()
+                    .find(|spec| spec["attribute"]["shortname"].as_str() == Some("lifecyclestatus"))
+                    .and_then(|spec| spec["displayValue"].as_str())
+                    .map(str::to_string)
+            });
+            let offers = part["sellers"]
+                .as_array()
+                .map(|sellers| {
+                    sellers
+                        .iter()
+                        .flat_map(|seller| {
+                            let distributor = seller["company"]["name"].as_str().unwrap_or_default().to_string();
+                            seller["offers"].as_array().cloned().unwrap_or_default().into_iter().map(move |offer| Offer {
+                                distributor: distributor.clone(),
+                                sku: offer["sku"].as_str().unwrap_or_default().to_string(),
+                                stock: offer["inventoryLevel"].as_i64(),
+                                url: offer["clickUrl"].as_str().map(str::to_string),
+                                prices: offer["prices"]
+                                    .as_array()
+                                    .map(|prices| {
+                                        prices
+                                            .iter()
+                                            .filter_map(|p| {
+                                                Some(PriceBreak {
+                                                    quantity: p["quantity"].as_u64()? as u32,
+                                                    price: p["price"].as_f64()?,
+                                                    currency: p["currency"].as_str().unwrap_or("USD").to_string(),
+                                                })
+                                            })
+                                            .collect()
+                                    })
+                                    .unwrap_or_default(),
+                            })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            PartInfo {
+                mpn: mpn.clone(),
+                manufacturer: part["manufacturer"]["name"].as_str().map(str::to_string),
+                datasheet: part["bestDatasheet"]["url"].as_str().map(str::to_string),
+                lifecycle,
+                offers,
+                fetched: fetched.clone(),
+            }
+        })
+        .collect()
+}
+
+fn cache_path(data_dir: &Path, mpn: &str) -> PathBuf {
+    let file = mpn.replace(['/', '\\', ':'], "_");
+    data_dir.join("cache/octopart").join(format!("{}.json", file))
+}
+
+/// Cached lookup no older than `max_age`
+pub fn cached(data_dir: &Path, mpn: &str, max_age: chrono::Duration) -> Option<PartInfo> {
+    let content = fs::read_to_string(cache_path(data_dir, mpn)).ok()?;
+    let info: PartInfo = serde_json::from_str(&content).ok()?;
+    let fetched = chrono::DateTime::parse_from_rfc3339(&info.fetched).ok()?;
+    (chrono::Utc::now().signed_duration_since(fetched) <= max_age).then_some(info)
+}
+
+pub fn store(data_dir: &Path, info: &PartInfo) -> Result<(), String> {
+    let path = cache_path(data_dir, &info.mpn);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let content = serde_json::to_string_pretty(info)
+        .map_err(|e| format!("Failed to serialize {}: {}", info.mpn, e))?;
+    Ok(component::atomic::write(&path, content)?)
+}
+
+/// Whether config.toml asks for product pages in SupplierURL
+pub fn product_urls_enabled(data_dir: &Path) -> Result<bool, String> {
+    let config_path = data_dir.join("config.toml");
+    let Ok(content) = fs::read_to_string(&config_path) else {
+        return Ok(false);
+    };
+    let config: toml::Table = content
+        .parse()
+        .map_err(|e| format!("Failed to parse {}: {}", config_path.display(), e))?;
+    match config.get("distributors").and_then(|d| d.get("supplier_url")).map(|mode| mode.as_str()) {
+        None | Some(Some("search")) => Ok(false),
+        Some(Some("product")) => Ok(true),
+        Some(_) => Err(format!(
+            "distributors.supplier_url in {} must be \"search\" or \"product\"",
+            config_path.display()
+        )),
+    }
+}
+
+/// Add the product pages of a part's offers to `pages` under the
+/// distributor part number and the MPN, returning the number of pages added
+pub fn register_product_urls(info: &PartInfo, pages: &mut HashMap<(Distributor, String), String>) -> usize {
+    let mut registered = 0;
+    for offer in &info.offers {
+        let Some(url) = &offer.url else {
+            continue;
+        };
+        // "Digi-Key", "DigiKey" or "Mouser Electronics"
+        let Some(distributor) = offer
+            .distributor
+            .split_whitespace()
+            .next()
+            .and_then(|name| name.parse::<Distributor>().ok())
+        else {
+            continue;
+        };
+        pages.entry((distributor, info.mpn.clone())).or_insert_with(|| url.clone());
+        pages.insert((distributor, offer.sku.clone()), url.clone());
+        registered += 1;
+    }
+    registered
+}
+
+/// Link product pages from every cached lookup when config.toml enables
+/// them. Parts without cached offers keep the search URL.
+pub fn install_product_urls(data_dir: &Path, settings: &mut Settings) -> Result<(), String> {
+    if !product_urls_enabled(data_dir)? {
+        return Ok(());
+    }
+    for entry in fs::read_dir(data_dir.join("cache/octopart")).into_iter().flatten().flatten() {
+        let Ok(content) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        if let Ok(info) = serde_json::from_str::<PartInfo>(&content) {
+            register_product_urls(&info, &mut settings.product_pages);
+        }
+    }
+    Ok(())
+}
+
+/// Sourcing policy from `distributors.prefer` in config.toml
+pub fn sourcing_policy(data_dir: &Path) -> Result<SourcingPolicy, String> {
+    let config_path = data_dir.join("config.toml");
+    let Ok(content) = fs::read_to_string(&config_path) else {
+        return Ok(SourcingPolicy::Listed);
+    };
+    let config: toml::Table = content
+        .parse()
+        .map_err(|e| format!("Failed to parse {}: {}", config_path.display(), e))?;
+    match config.get("distributors").and_then(|d| d.get("prefer")) {
+        None => Ok(SourcingPolicy::Listed),
+        Some(prefer) => prefer
+            .as_str()
+            .ok_or_else(|| format!("distributors.prefer in {} must be a string", config_path.display()))?
+            .parse()
+            .map_err(|e| format!("distributors.prefer in {}: {}", config_path.display(), e)),
+    }
+}
+
+/// Write the distributors of `distributors.suppliers` in config.toml as
+/// Supplier 1, 2, ... of the generated libraries
+pub fn install_suppliers(data_dir: &Path, settings: &mut Settings) -> Result<(), String> {
+    let config_path = data_dir.join("config.toml");
+    let Ok(content) = fs::read_to_string(&config_path) else {
+        return Ok(());
+    };
+    let config: toml::Table = content
+        .parse()
+        .map_err(|e| format!("Failed to parse {}: {}", config_path.display(), e))?;
+    let Some(suppliers) = config.get("distributors").and_then(|d| d.get("suppliers")) else {
+        return Ok(());
+    };
+    let suppliers = suppliers
+        .as_array()
+        .ok_or_else(|| format!("distributors.suppliers in {} must be a list", config_path.display()))?
+        .iter()
+        .map(|supplier| {
+            supplier
+                .as_str()
+                .ok_or_else(|| "expected a distributor name".to_string())
+                .and_then(|name| Ok(name.parse::<Distributor>()?))
+                .map_err(|e| format!("distributors.suppliers in {}: {}", config_path.display(), e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    settings.suppliers = suppliers;
+    Ok(())
+}
+
+/// Lowest price over all offers of a single unit or the smallest break
+pub(crate) fn unit_price(info: &PartInfo) -> Option<&PriceBreak> {
+    info.offers
+        .iter()
+        .filter_map(|offer| offer.prices.iter().min_by_key(|price| price.quantity))
+        .min_by(|a, b| a.price.total_cmp(&b.price))
+}
+
+/// Stock over all offers and the unit price
+fn stock_info(info: &PartInfo) -> StockInfo {
+    let stock = info.offers.iter().filter_map(|offer| offer.stock).sum();
+    StockInfo { stock, unit_price: unit_price(info).map(|price| price.price) }
+}
+
+/// Select the sourcing policy from config.toml and take the stock of every
+/// cached lookup for it into `settings`
+pub fn install_sourcing(data_dir: &Path, settings: &mut Settings) -> Result<(), String> {
+    let policy = sourcing_policy(data_dir)?;
+    if policy == SourcingPolicy::Listed {
+        return Ok(());
+    }
+    settings.sourcing = policy;
+    for entry in fs::read_dir(data_dir.join("cache/octopart")).into_iter().flatten().flatten() {
+        let Ok(content) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        if let Ok(info) = serde_json::from_str::<PartInfo>(&content) {
+            if info.manufacturer.is_some() {
+                settings.stock.insert(info.mpn.clone(), stock_info(&info));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_multi_match_response() {
+        let response = json!({
+            "data": { "supMultiMatch": [
+                { "reference": "CRCW06031K00FKEA", "parts": [{
+                    "mpn": "CRCW06031K00FKEA",
+                    "manufacturer": { "name": "Vishay" },
+                    "bestDatasheet": { "url": "https://www.vishay.com/docs/20035/dcrcwe3.pdf" },
+                    "specs": [{ "attribute": { "shortname": "lifecyclestatus" }, "displayValue": "Production" }],
+                    "sellers": [{ "company": { "name": "Mouser" }, "offers": [{
+                        "sku": "71-CRCW06031K00FKEA", "inventoryLevel": 125000,
+                        "clickUrl": "https://octopart.com/click/track?ct=offers&sid=2401&sig=0d2c9a1",
+                        "prices": [{ "quantity": 1, "price": 0.1, "currency": "USD" }]
+                    }]}]
+                }]},
+                { "reference": "CRCW0603XXXXFKEA", "parts": [] }
+            ]}
+        });
+        let mpns = ["CRCW06031K00FKEA".to_string(), "CRCW0603XXXXFKEA".to_string()];
+        let parts = parse_multi_match(&response, &mpns);
+
+        assert_eq!(parts[0].manufacturer.as_deref(), Some("Vishay"));
+        assert_eq!(parts[0].lifecycle.as_deref(), Some("Production"));
+        assert_eq!(parts[0].offers[0].stock, Some(125000));
+        assert_eq!(parts[0].offers[0].prices[0].quantity, 1);
+        assert_eq!(parts[1].manufacturer, None);
+        assert!(parts[1].offers.is_empty());
+
+        // The offer's page replaces the Mouser search for its SKU and MPN
+        let mut settings = Settings::default();
+        assert_eq!(register_product_urls(&parts[0], &mut settings.product_pages), 1);
+        let page = parts[0].offers[0].url.clone();
+        settings.scope(|| {
+            assert_eq!(Distributor::Mouser.product_url("71-CRCW06031K00FKEA"), page);
+            assert_eq!(Distributor::Mouser.supplier_url("CRCW06031K00FKEA"), page.unwrap());
+            assert_eq!(Distributor::Digikey.product_url("CRCW06031K00FKEA"), None);
+        });
+    }
+}
@@ -0,0 +1,150 @@
+//! Machine-readable output of `--json`
+//!
+//! With the global `--json` flag, `list`, `info`, `config`, `capabilities`,
+//! `generate` and `validate` print one JSON document to stdout for scripts and CI. Their
+//! progress lines move to stderr so stdout carries nothing else; errors stay
+//! on stderr with a non-zero exit code. `generate` reports the libraries it
+//! created, every file it wrote and its warnings:
+//!
+//! ```json
+//! {
+//!   "libraries": [{ "library": "resistor::E96_0603", "path": ".../E96_0603.json", "values": 96 }],
+//!   "count": 1,
+//!   "written": [".../E96_0603.json", ".../manifest.json"],
+//!   "warnings": ["Skipped: 0201 is rated 1/20W"],
+//!   "dry_run": false
+//! }
+//! ```
+
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+static JSON: AtomicBool = AtomicBool::new(false);
+
+static BAR: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// `println!` for progress and results, on stderr under `--json` so stdout
+/// carries nothing but the JSON document. Printed above a [`progress`] bar.
+macro_rules! say {
+    ($($arg:tt)*) => {
+        $crate::commands::output::suspend(|| {
+            if $crate::commands::output::json() {
+                eprintln!($($arg)*)
+            } else {
+                println!($($arg)*)
+            }
+        })
+    };
+}
+pub(crate) use say;
+
+pub fn set_json(json: bool) {
+    JSON.store(json, Ordering::Relaxed);
+}
+
+pub(crate) fn json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// Progress of a command over `total` parts, a bar on stderr while it is
+/// a terminal. The bar is cleared when the returned handle is dropped.
+pub(crate) fn progress(total: u64) -> Progress {
+    let bar = ProgressBar::new(total);
+    if let Ok(style) = ProgressStyle::with_template("{bar:40} {pos}/{len} parts {msg}") {
+        bar.set_style(style);
+    }
+    *BAR.lock().unwrap_or_else(|e| e.into_inner()) = Some(bar.clone());
+    Progress(bar)
+}
+
+pub(crate) struct Progress(ProgressBar);
+
+impl Progress {
+    /// `parts` more done, of `package`
+    pub(crate) fn advance(&self, package: &str, parts: u64) {
+        self.0.set_message(package.to_string());
+        self.0.inc(parts);
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.0.finish_and_clear();
+        BAR.lock().unwrap_or_else(|e| e.into_inner()).take();
+    }
+}
+
+/// Run `print` with the progress bar, if any, out of the way
+pub(crate) fn suspend(print: impl FnOnce()) {
+    match BAR.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        Some(bar) => bar.suspend(print),
+        None => print(),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Created {
+    library: String,
+    path: PathBuf,
+    values: usize,
+}
+
+/// What a command created and wrote, and what it warned about
+#[derive(Debug, Default, Serialize)]
+struct Report {
+    libraries: Vec<Created>,
+    count: usize,
+    written: Vec<PathBuf>,
+    warnings: Vec<String>,
+    /// Nothing in `written` was actually written
+    dry_run: bool,
+}
+
+static REPORT: Mutex<Option<Report>> = Mutex::new(None);
+
+fn record(update: impl FnOnce(&mut Report)) {
+    let mut report = REPORT.lock().unwrap_or_else(|e| e.into_inner());
+    update(report.get_or_insert_with(Report::default));
+}
+
+/// Record a library written to `path` with `values` values
+pub(crate) fn created(library: &str, path: &Path, values: usize) {
+    record(|report| {
+        report.libraries.push(Created { library: library.to_string(), path: path.to_path_buf(), values });
+        report.written.push(path.to_path_buf());
+    });
+}
+
+/// Record a written file, or a directory of them
+pub(crate) fn written(path: &Path) {
+    record(|report| report.written.push(path.to_path_buf()));
+}
+
+pub(crate) fn warning(message: String) {
+    record(|report| report.warnings.push(message));
+}
+
+/// Print `value` as the JSON document of the command
+pub(crate) fn print(value: &impl Serialize) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| format!("Failed to serialize output: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+/// Print what was recorded since the command started, under `--json`
+pub(crate) fn finish() -> Result<(), String> {
+    if !json() {
+        return Ok(());
+    }
+    let mut report = REPORT.lock().unwrap_or_else(|e| e.into_inner()).take().unwrap_or_default();
+    report.count = report.libraries.len();
+    report.dry_run = component::atomic::dry_run();
+    // The manifest is rewritten after every library
+    let mut seen = HashSet::new();
+    report.written.retain(|path| seen.insert(path.clone()));
+    print(&report)
+}
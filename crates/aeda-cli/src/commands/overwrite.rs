@@ -0,0 +1,102 @@
+//! Overwrite protection and dry runs of `generate` and `export`
+//!
+//! Both commands refuse to silently replace a file whose content differs
+//! from what they would write, such as a hand-edited symbol library. On a
+//! terminal they ask per file; elsewhere, e.g. in CI, they fail and name the
+//! file. `--force` replaces without asking and `--dry-run` writes nothing,
+//! listing what would be created or replaced instead. aeda's own manifest is
+//! always rewritten.
+//!
+//! With `--zip` both write into a ZIP archive instead, moved into place only
+//! when the whole run succeeded.
+
+use super::output::say;
+use component::atomic::{self, ArchiveLayout, Change};
+use component::settings::Settings;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Answered "all" to a prompt, replace the rest without asking
+static REPLACE_ALL: AtomicBool = AtomicBool::new(false);
+/// Libraries are written from several threads, one prompt at a time
+static PROMPT: Mutex<()> = Mutex::new(());
+
+/// The [`atomic::OverwriteGuard`] of `generate` and `export`
+fn ask(path: &Path) -> io::Result<bool> {
+    let _prompt = PROMPT.lock().unwrap_or_else(|e| e.into_inner());
+    if REPLACE_ALL.load(Ordering::Relaxed) {
+        return Ok(true);
+    }
+    if !io::stdin().is_terminal() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "exists with other content, pass --force to replace it",
+        ));
+    }
+    eprint!("{} exists with other content. Replace it? [y/N/a(ll)] ", path.display());
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    match answer.trim().to_lowercase().as_str() {
+        "y" | "yes" => Ok(true),
+        "a" | "all" => {
+            REPLACE_ALL.store(true, Ordering::Relaxed);
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Set up the run: `dry_run` writes nothing, `force` replaces without asking
+pub fn install(settings: &mut Settings, dry_run: bool, force: bool) {
+    settings.dry_run = dry_run;
+    settings.overwrite_guard = if force { None } else { Some(ask) };
+}
+
+/// List what a dry run would have written and the files kept on request
+pub fn report() {
+    let changes = atomic::take_changes();
+    if atomic::dry_run() {
+        say!("\nDry run, nothing was written. Would write {} files:", changes.len());
+        for (path, change) in &changes {
+            let action = match change {
+                Change::Created => "create",
+                Change::Replaced => "replace",
+                Change::Unchanged | Change::Kept => "unchanged",
+            };
+            say!("  {:<9} {}", action, path.display());
+        }
+        return;
+    }
+    let kept: Vec<_> = changes.iter().filter(|(_, change)| *change == Change::Kept).collect();
+    if !kept.is_empty() {
+        say!("\nKept {} existing files, pass --force to replace them:", kept.len());
+        for (path, _) in kept {
+            say!("  {}", path.display());
+        }
+    }
+}
+
+/// Send the outputs of the run into the archive `zip`, one per output
+/// directory with `per_format`. A dry run writes no archive either.
+pub fn start_archive(zip: &Path, data_dir: &Path, per_format: bool) -> Result<(), String> {
+    if atomic::dry_run() {
+        return Ok(());
+    }
+    let layout = if per_format { ArchiveLayout::PerFormat } else { ArchiveLayout::Combined };
+    Ok(atomic::start_archive(zip, data_dir, layout)?)
+}
+
+/// Complete the archives of a successful run, drop them after a failed one
+pub fn finish_archive(result: Result<(), String>) -> Result<(), String> {
+    if let Err(e) = result {
+        atomic::discard_archive();
+        return Err(e);
+    }
+    for archive in atomic::finish_archive()? {
+        say!("Wrote {}", archive.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,320 @@
+//! SQLite parts database of the data directory
+//!
+//! The library JSON files stay the source of truth; `parts.db` holds every
+//! part they generate, one row per part with its ratings, sources and price,
+//! indexed on value, package, MPN and library, and with the value in ohms or
+//! farads for range searches. Each command opening the database compares the
+//! data directory's files with the state it was built from and rebuilds it
+//! when a library, config.toml or the imported data changed, so `list`,
+//! `info`, `search` and `export bom` answer from it without expanding every
+//! library again.
+
+use super::bom::{self, BomRow};
+use component::Resistance;
+use rusqlite::{params, Connection, Row};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, UNIX_EPOCH};
+
+pub(crate) const FILE_NAME: &str = "parts.db";
+
+/// Bumped when the tables change, dropping databases of older releases
+const SCHEMA_VERSION: i32 = 2;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS parts (
+        part TEXT NOT NULL,
+        library TEXT NOT NULL,
+        value TEXT NOT NULL,
+        package TEXT NOT NULL,
+        footprint TEXT NOT NULL,
+        tolerance TEXT NOT NULL,
+        power TEXT NOT NULL,
+        voltage TEXT NOT NULL,
+        manufacturer TEXT NOT NULL,
+        mpn TEXT NOT NULL,
+        alternates TEXT NOT NULL,
+        digikey TEXT NOT NULL,
+        mouser TEXT NOT NULL,
+        lcsc TEXT NOT NULL,
+        unit_price REAL,
+        currency TEXT NOT NULL,
+        magnitude REAL
+    );
+    CREATE INDEX IF NOT EXISTS parts_value ON parts (value);
+    CREATE INDEX IF NOT EXISTS parts_package ON parts (package);
+    CREATE INDEX IF NOT EXISTS parts_mpn ON parts (mpn);
+    CREATE INDEX IF NOT EXISTS parts_library ON parts (library);
+    CREATE INDEX IF NOT EXISTS parts_magnitude ON parts (magnitude);
+    CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+";
+
+const COLUMNS: &str = "part, library, value, package, footprint, tolerance, power, voltage, \
+    manufacturer, mpn, alternates, digikey, mouser, lcsc, unit_price, currency";
+
+/// Value in ohms or farads of resistor and capacitor parts
+fn magnitude(row: &BomRow) -> Option<f64> {
+    match row.library.split("::").next() {
+        Some("resistor") => row.value.parse::<Resistance>().ok().map(Resistance::ohms),
+        Some("capacitor") => component::marking::parse_capacitance(&row.value),
+        _ => None,
+    }
+}
+
+/// Separates the alternates of a part in their column
+const ALTERNATES_SEPARATOR: &str = "; ";
+
+fn db_error(e: rusqlite::Error) -> String {
+    format!("Failed to query {}: {}", FILE_NAME, e)
+}
+
+/// Create the tables, replacing those of another schema version
+fn create_schema(conn: &Connection) -> rusqlite::Result<()> {
+    let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version != SCHEMA_VERSION {
+        conn.execute_batch("DROP TABLE IF EXISTS parts; DROP TABLE IF EXISTS meta;")?;
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+    }
+    conn.execute_batch(SCHEMA)
+}
+
+/// File count, total size and latest modification of the files parts are
+/// built from, i.e. everything in the data directory but the database
+fn fingerprint(data_dir: &Path) -> String {
+    fn walk(dir: &Path, files: &mut u64, size: &mut u64, latest: &mut Duration) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with(FILE_NAME) {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                walk(&path, files, size, latest);
+                continue;
+            }
+            *files += 1;
+            *size += metadata.len();
+            if let Some(modified) = metadata.modified().ok().and_then(|m| m.duration_since(UNIX_EPOCH).ok()) {
+                *latest = (*latest).max(modified);
+            }
+        }
+    }
+    let (mut files, mut size, mut latest) = (0, 0, Duration::ZERO);
+    walk(data_dir, &mut files, &mut size, &mut latest);
+    format!("{}:{}:{}", files, size, latest.as_nanos())
+}
+
+/// Replace every part with `rows`
+fn store(conn: &mut Connection, rows: &[BomRow], fingerprint: &str) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM parts", [])?;
+    {
+        let mut insert = tx.prepare(&format!(
+            "INSERT INTO parts ({}, magnitude) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+            COLUMNS
+        ))?;
+        for row in rows {
+            insert.execute(params![
+                row.part,
+                row.library,
+                row.value,
+                row.package,
+                row.footprint,
+                row.tolerance,
+                row.power,
+                row.voltage,
+                row.manufacturer,
+                row.mpn,
+                row.alternates.join(ALTERNATES_SEPARATOR),
+                row.digikey,
+                row.mouser,
+                row.lcsc,
+                row.unit_price,
+                row.currency,
+                magnitude(row),
+            ])?;
+        }
+    }
+    tx.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES ('fingerprint', ?1)",
+        [fingerprint],
+    )?;
+    tx.commit()
+}
+
+fn bom_row(row: &Row) -> rusqlite::Result<BomRow> {
+    let alternates: String = row.get(10)?;
+    Ok(BomRow {
+        part: row.get(0)?,
+        library: row.get(1)?,
+        value: row.get(2)?,
+        package: row.get(3)?,
+        footprint: row.get(4)?,
+        tolerance: row.get(5)?,
+        power: row.get(6)?,
+        voltage: row.get(7)?,
+        manufacturer: row.get(8)?,
+        mpn: row.get(9)?,
+        alternates: alternates
+            .split(ALTERNATES_SEPARATOR)
+            .filter(|alternate| !alternate.is_empty())
+            .map(str::to_string)
+            .collect(),
+        digikey: row.get(11)?,
+        mouser: row.get(12)?,
+        lcsc: row.get(13)?,
+        unit_price: row.get(14)?,
+        currency: row.get(15)?,
+        // Assigned when a BOM is written, not stored
+        ipn: String::new(),
+    })
+}
+
+/// Parts matching `condition`, in `order`
+fn select_ordered(
+    conn: &Connection,
+    condition: &str,
+    order: &str,
+    values: &[&dyn rusqlite::ToSql],
+) -> rusqlite::Result<Vec<BomRow>> {
+    let mut query = conn.prepare(&format!("SELECT {} FROM parts WHERE {} ORDER BY {}", COLUMNS, condition, order))?;
+    let rows = query.query_map(values, bom_row)?;
+    rows.collect()
+}
+
+/// Parts matching `condition`, in the order they were generated
+fn select(conn: &Connection, condition: &str, values: &[&dyn rusqlite::ToSql]) -> rusqlite::Result<Vec<BomRow>> {
+    select_ordered(conn, condition, "rowid", values)
+}
+
+/// Open `parts.db`, rebuilding it from the library files when they changed
+pub(crate) fn open(data_dir: &Path) -> Result<Connection, String> {
+    let path = data_dir.join(FILE_NAME);
+    let mut conn = Connection::open(&path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    // Another aeda run may be rebuilding it
+    conn.busy_timeout(Duration::from_secs(10)).map_err(db_error)?;
+    create_schema(&conn).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+
+    let current = fingerprint(data_dir);
+    let stored: Option<String> = conn
+        .query_row("SELECT value FROM meta WHERE key = 'fingerprint'", [], |row| row.get(0))
+        .ok();
+    if stored.as_deref() != Some(current.as_str()) {
+        let rows = bom::bom_rows(data_dir)?;
+        store(&mut conn, &rows, &current).map_err(|e| format!("Failed to update {}: {}", path.display(), e))?;
+    }
+    Ok(conn)
+}
+
+/// Every generated part, by library
+pub(crate) fn rows(data_dir: &Path) -> Result<Vec<BomRow>, String> {
+    let conn = open(data_dir)?;
+    select(&conn, "1", &[]).map_err(db_error)
+}
+
+/// Parts of one library, e.g. `resistor::E96_0603`
+pub(crate) fn library_rows(conn: &Connection, library: &str) -> Result<Vec<BomRow>, String> {
+    select(conn, "library = ?1", &[&library]).map_err(db_error)
+}
+
+/// Parametric part search, every filter optional
+#[derive(Debug, Default)]
+pub(crate) struct Search {
+    /// Library category, e.g. `resistor`, `capacitor` or a family name
+    pub category: Option<String>,
+    /// Value range in ohms or farads, inclusive
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub package: Option<String>,
+    /// Tolerance as written in the libraries, e.g. `1%`
+    pub tolerance: Option<String>,
+    /// Part of a primary or alternate MPN, case-insensitive
+    pub mpn: Option<String>,
+}
+
+/// Parts matching `search`, by value, package and name
+pub(crate) fn search(conn: &Connection, search: &Search) -> Result<Vec<BomRow>, String> {
+    let mut conditions = vec!["1".to_string()];
+    let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+    let mut filter = |condition: &str, value: Box<dyn rusqlite::ToSql>| {
+        values.push(value);
+        conditions.push(condition.replace('?', &format!("?{}", values.len())));
+    };
+    if let Some(category) = &search.category {
+        filter("library LIKE ?", Box::new(format!("{}::%", category)));
+    }
+    if let Some(min) = search.min {
+        filter("magnitude >= ?", Box::new(min));
+    }
+    if let Some(max) = search.max {
+        filter("magnitude <= ?", Box::new(max));
+    }
+    if let Some(package) = &search.package {
+        filter("package = ?", Box::new(package.clone()));
+    }
+    if let Some(tolerance) = &search.tolerance {
+        filter("tolerance = ?", Box::new(tolerance.clone()));
+    }
+    if let Some(mpn) = &search.mpn {
+        let pattern = format!("%{}%", mpn.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+        filter("(mpn LIKE ? ESCAPE '\\' OR alternates LIKE ? ESCAPE '\\')", Box::new(pattern));
+    }
+    let values: Vec<&dyn rusqlite::ToSql> = values.iter().map(|value| value.as_ref()).collect();
+    select_ordered(conn, &conditions.join(" AND "), "magnitude IS NULL, magnitude, package, part", &values).map_err(db_error)
+}
+
+/// Number of parts per library
+pub(crate) fn part_counts(conn: &Connection) -> Result<HashMap<String, usize>, String> {
+    let mut query = conn
+        .prepare("SELECT library, COUNT(*) FROM parts GROUP BY library")
+        .map_err(db_error)?;
+    let counts = query
+        .query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as usize)))
+        .map_err(db_error)?;
+    counts.collect::<rusqlite::Result<_>>().map_err(db_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parts_round_trip_through_the_database() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
+        let resistor = BomRow {
+            part: "R0603_4.99K".into(),
+            library: "resistor::E96_0603".into(),
+            value: "4.99K".into(),
+            package: "0603".into(),
+            manufacturer: "Vishay".into(),
+            mpn: "CRCW06034K99FKEA".into(),
+            alternates: vec!["Yageo RC0603FR-074K99L".into(), "KOA RK73H1JTTD4991F".into()],
+            unit_price: Some(0.1),
+            currency: "USD".into(),
+            ..Default::default()
+        };
+        let capacitor = BomRow {
+            part: "C0603_X7R_100nF".into(),
+            library: "capacitor::X7R_0603".into(),
+            value: "100nF".into(),
+            package: "0603".into(),
+            ..Default::default()
+        };
+        store(&mut conn, &[resistor.clone(), capacitor.clone()], "1:2:3").unwrap();
+
+        assert_eq!(select(&conn, "1", &[]).unwrap(), [resistor.clone(), capacitor]);
+        assert_eq!(library_rows(&conn, "resistor::E96_0603").unwrap(), [resistor]);
+        assert_eq!(part_counts(&conn).unwrap()["capacitor::X7R_0603"], 1);
+
+        // Stored again after a library changed, parts are replaced, not added
+        store(&mut conn, &[], "1:2:4").unwrap();
+        assert!(select(&conn, "1", &[]).unwrap().is_empty());
+    }
+}
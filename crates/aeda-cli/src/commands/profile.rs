@@ -0,0 +1,113 @@
+//! Output profiles from config.toml
+//!
+//! A `[profiles.<name>]` table bundles the conventions one downstream team or
+//! tool expects, so the same data directory can be exported for each of them
+//! with `aeda export --profile <name>`. Options given on the command line
+//! still win over the profile.
+//!
+//! ```toml
+//! [profiles.kicad-team-a]
+//! target = "kicad"
+//! output = "exports/{date}/{format}"
+//! kicad_version = "8"
+//! value_notation = "european"
+//! density = "most"
+//! footprint_format = "modern"
+//! package_naming = "metric"
+//! ```
+
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// Export run by `aeda export` without a format: kicad, stencil, altium, easyeda, jlcpcb or bom
+    pub target: Option<String>,
+    /// Export output directory, may contain {date}, {time}, {format} and {profile}
+    pub output: Option<PathBuf>,
+    /// KiCad symbol format, 6, 7 or 8
+    pub kicad_version: Option<String>,
+    /// decimal (4.70K) or european (4k70)
+    pub value_notation: Option<String>,
+    /// IPC-7351B footprint density, least, nominal or most
+    pub density: Option<String>,
+    /// Footprint file grammar, legacy or modern
+    pub footprint_format: Option<String>,
+    /// Part order in libraries and CSVs, value, name or generation
+    pub sort: Option<String>,
+    /// Package code leading in names, imperial, metric or jis
+    pub package_naming: Option<String>,
+}
+
+fn profiles(data_dir: &Path) -> Result<toml::Table, String> {
+    let config_path = data_dir.join("config.toml");
+    let Ok(content) = fs::read_to_string(&config_path) else {
+        return Ok(toml::Table::new());
+    };
+    let config: toml::Table = content
+        .parse()
+        .map_err(|e| format!("Failed to parse {}: {}", config_path.display(), e))?;
+    match config.get("profiles") {
+        Some(toml::Value::Table(profiles)) => Ok(profiles.clone()),
+        Some(_) => Err(format!("[profiles] in {} must be a table", config_path.display())),
+        None => Ok(toml::Table::new()),
+    }
+}
+
+/// Names of the profiles defined in config.toml
+pub fn names(data_dir: &Path) -> Result<Vec<String>, String> {
+    Ok(profiles(data_dir)?.keys().cloned().collect())
+}
+
+pub fn load(data_dir: &Path, name: &str) -> Result<Profile, String> {
+    let profiles = profiles(data_dir)?;
+    let Some(profile) = profiles.get(name) else {
+        let known: Vec<&str> = profiles.keys().map(String::as_str).collect();
+        if known.is_empty() {
+            return Err(format!("Unknown profile '{}', config.toml defines no [profiles]", name));
+        }
+        return Err(format!("Unknown profile '{}', expected one of: {}", name, known.join(", ")));
+    };
+    profile
+        .clone()
+        .try_into()
+        .map_err(|e| format!("Invalid profile '{}': {}", name, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_named_profiles() {
+        let dir = std::env::temp_dir().join(format!("aeda-profile-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("config.toml"),
+            r#"
+[profiles.kicad-team-a]
+target = "kicad"
+kicad_version = "8"
+value_notation = "european"
+
+[profiles.altium-legacy]
+target = "altium"
+output = "legacy"
+
+[profiles.typo]
+kicad_versoin = "7"
+"#,
+        )
+        .unwrap();
+
+        let profile = load(&dir, "kicad-team-a").unwrap();
+        assert_eq!(profile.kicad_version.as_deref(), Some("8"));
+        assert_eq!(profile.value_notation.as_deref(), Some("european"));
+        assert_eq!(load(&dir, "altium-legacy").unwrap().output, Some(PathBuf::from("legacy")));
+        assert!(load(&dir, "typo").unwrap_err().contains("kicad_versoin"));
+        assert!(load(&dir, "missing").unwrap_err().contains("altium-legacy, kicad-team-a, typo"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
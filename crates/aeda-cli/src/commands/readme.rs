@@ -0,0 +1,160 @@
+//! Machine-written usage notes for exported library directories
+//!
+//! Each exported category directory gets a README.md rendered from the
+//! templates in `templates/`, describing how to use the libraries and the
+//! parameters they were generated with.
+
+use super::generate::{CombinedSeries, Manifest};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const README_TEMPLATE: &str = include_str!("../../templates/library_readme.md");
+const SECTION_TEMPLATE: &str = include_str!("../../templates/library_section.md");
+
+#[derive(Deserialize)]
+struct LibrarySummary {
+    name: String,
+    description: String,
+    package: String,
+    footprint: String,
+    #[serde(default)]
+    tolerance: String,
+    #[serde(default)]
+    power_rating: String,
+    #[serde(default)]
+    voltage_rating: String,
+    #[serde(default)]
+    series: String,
+    #[serde(default)]
+    dielectric: String,
+    #[serde(default)]
+    base_values: Vec<f64>,
+    #[serde(default)]
+    multipliers: HashMap<String, f64>,
+    #[serde(default)]
+    values: Vec<String>,
+    #[serde(default)]
+    combined: Vec<CombinedSeries>,
+}
+
+impl LibrarySummary {
+    fn coverage(&self) -> String {
+        if !self.combined.is_empty() {
+            let series: Vec<String> = self
+                .combined
+                .iter()
+                .map(|series| format!("{} {} ({} base values per decade)", series.series, series.tolerance, series.base_values.len()))
+                .collect();
+            series.join(", ")
+        } else if let (Some(first), Some(last)) = (self.base_values.first(), self.base_values.last()) {
+            let mut suffixes: Vec<&str> = self
+                .multipliers
+                .keys()
+                .map(|s| s.as_str())
+                .filter(|s| !s.is_empty())
+                .collect();
+            suffixes.sort_unstable();
+            format!(
+                "{} base values per decade ({:.2} to {:.2}), suffixes: {}",
+                self.base_values.len(),
+                first,
+                last,
+                suffixes.join(", ")
+            )
+        } else if let (Some(first), Some(last)) = (self.values.first(), self.values.last()) {
+            format!("{} values ({} to {})", self.values.len(), first, last)
+        } else {
+            "none".into()
+        }
+    }
+}
+
+/// Substitute `{key}` placeholders in a template
+fn render(template: &str, vars: &[(&str, String)]) -> String {
+    vars.iter().fold(template.to_string(), |out, (key, value)| {
+        out.replace(&format!("{{{}}}", key), value)
+    })
+}
+
+fn or_dash(value: &str) -> String {
+    if value.is_empty() { "-".into() } else { value.to_string() }
+}
+
+/// Write README.md into `output_dir/<category>` for every category in the
+/// manifest that has libraries. Library JSONs are read from `libraries_dir`.
+pub(crate) fn write_readmes(
+    libraries_dir: &Path,
+    output_dir: &Path,
+    manifest: &Manifest,
+) -> Result<usize, String> {
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let mut written = 0;
+
+    let mut categories: Vec<_> = manifest.libraries.iter().filter(|(_, items)| !items.is_empty()).collect();
+    categories.sort_by_key(|(category, _)| category.as_str());
+
+    for (category, items) in categories {
+        let mut names: Vec<_> = items.iter().collect();
+        names.sort();
+
+        let mut sections = Vec::new();
+        for (name, path) in &names {
+            let json_path = libraries_dir.join(path);
+            let content = fs::read_to_string(&json_path)
+                .map_err(|e| format!("Failed to read {}: {}", json_path.display(), e))?;
+            let lib: LibrarySummary = serde_json::from_str(&content)
+                .map_err(|e| format!("Failed to parse {}: {}", json_path.display(), e))?;
+
+            let rating = if lib.power_rating.is_empty() { &lib.voltage_rating } else { &lib.power_rating };
+            let series = if lib.series.is_empty() { &lib.dielectric } else { &lib.series };
+            sections.push(render(
+                SECTION_TEMPLATE,
+                &[
+                    ("category", category.clone()),
+                    ("name", lib.name.clone()),
+                    ("description", lib.description.clone()),
+                    ("package", lib.package.clone()),
+                    ("footprint", lib.footprint.clone()),
+                    ("tolerance", or_dash(&lib.tolerance)),
+                    ("rating", or_dash(rating)),
+                    ("series", or_dash(series)),
+                    ("status", manifest.status_of(category, name).to_string()),
+                    ("coverage", lib.coverage()),
+                ],
+            ));
+        }
+
+        let readme = render(
+            README_TEMPLATE,
+            &[
+                ("category", category.clone()),
+                ("version", env!("CARGO_PKG_VERSION").to_string()),
+                ("date", date.clone()),
+                ("first_library", names[0].0.clone()),
+                ("kicad_nickname", format!("atlantix_{}", category)),
+                ("libraries", sections.join("\n")),
+            ],
+        );
+
+        let category_dir = output_dir.join(category);
+        fs::create_dir_all(&category_dir)
+            .map_err(|e| format!("Failed to create {}: {}", category_dir.display(), e))?;
+        component::atomic::write(category_dir.join("README.md"), readme)?;
+        written += 1;
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_replaces_known_placeholders_only() {
+        let out = render("{a} ${KIPRJMOD}/{b}", &[("a", "x".into()), ("b", "y".into())]);
+        assert_eq!(out, "x ${KIPRJMOD}/y");
+    }
+}
@@ -0,0 +1,213 @@
+//! Reports over generated libraries
+//!
+//! `aeda report links` checks the SupplierURL and Datasheet fields of
+//! generated KiCad symbol libraries. Supplier URLs are built from search
+//! queries without asking the distributor, so some lead nowhere. Every URL
+//! gets a HEAD request without following redirects; answers are cached in
+//! `data_dir/cache/links.json` so repeated runs only check what is new or
+//! stale. Network failures are not cached.
+
+use kiparse::sexpr::parse_sexpr;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CACHE_FILE: &str = "cache/links.json";
+
+/// Symbol properties holding a URL
+const URL_PROPERTIES: [&str; 2] = ["SupplierURL", "Datasheet"];
+
+/// Outcome of one URL check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LinkCheck {
+    /// HTTP status, 0 when the server could not be reached
+    status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    location: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    checked: String,
+}
+
+#[derive(Debug, PartialEq)]
+enum LinkState<'a> {
+    Ok,
+    Redirected(&'a str),
+    Dead(u16),
+    Unreachable(&'a str),
+}
+
+impl LinkCheck {
+    fn state(&self) -> LinkState<'_> {
+        match self.status {
+            0 => LinkState::Unreachable(self.error.as_deref().unwrap_or("no response")),
+            200..=299 => LinkState::Ok,
+            300..=399 => LinkState::Redirected(self.location.as_deref().unwrap_or("?")),
+            status => LinkState::Dead(status),
+        }
+    }
+}
+
+/// A URL and the symbols using it, as `file: symbol (property)`
+type Links = BTreeMap<String, Vec<String>>;
+
+fn collect_links(path: &Path, content: &str, links: &mut Links) -> Result<(), String> {
+    let library = parse_sexpr(content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    let file = path.file_name().map(|f| f.to_string_lossy().into_owned()).unwrap_or_default();
+    for symbol in library.find_all("symbol") {
+        let name = symbol.value(1).unwrap_or_default();
+        for property in symbol.find_all("property") {
+            let (Some(key), Some(url)) = (property.value(1), property.value(2)) else {
+                continue;
+            };
+            if URL_PROPERTIES.contains(&key) && url.starts_with("http") {
+                links.entry(url.to_string()).or_default().push(format!("{}: {} ({})", file, name, key));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Symbol libraries under `dir`, recursively
+pub(crate) fn symbol_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            symbol_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "kicad_sym") {
+            files.push(path);
+        }
+    }
+}
+
+fn load_cache(data_dir: &Path) -> BTreeMap<String, LinkCheck> {
+    fs::read_to_string(data_dir.join(CACHE_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn check(url: &str) -> LinkCheck {
+    let probe = super::network::probe(url);
+    LinkCheck {
+        status: probe.status,
+        location: probe.location,
+        error: probe.error,
+        checked: chrono::Utc::now().to_rfc3339(),
+    }
+}
+
+pub fn links(data_dir: &Path, paths: &[PathBuf], max_age_days: i64, offline: bool) -> Result<(), String> {
+    if !offline {
+        super::network::require("Link checking").map_err(|e| format!("{}. Pass --offline to report from the cache", e))?;
+    }
+    let mut files = paths.to_vec();
+    if files.is_empty() {
+        symbol_files(&data_dir.join("symbols"), &mut files);
+        symbol_files(&data_dir.join("requests"), &mut files);
+        files.sort();
+    }
+    if files.is_empty() {
+        return Err(format!("No symbol libraries found in {}. Generate some first or pass the files", data_dir.display()));
+    }
+
+    let mut links = Links::new();
+    for path in &files {
+        let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        collect_links(path, &content, &mut links)?;
+    }
+    println!("Checking {} URLs in {} symbol libraries...", links.len(), files.len());
+
+    let mut cache = load_cache(data_dir);
+    let max_age = chrono::Duration::days(max_age_days);
+    let is_fresh = |check: &LinkCheck| {
+        chrono::DateTime::parse_from_rfc3339(&check.checked)
+            .is_ok_and(|checked| chrono::Utc::now().signed_duration_since(checked) <= max_age)
+    };
+    let mut queried = 0;
+    let mut unchecked = 0;
+    for url in links.keys() {
+        if cache.get(url).is_some_and(is_fresh) {
+            continue;
+        }
+        if offline {
+            unchecked += 1;
+            continue;
+        }
+        cache.insert(url.clone(), check(url));
+        queried += 1;
+    }
+    if queried > 0 {
+        let path = data_dir.join(CACHE_FILE);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        // Network failures are retried on the next run rather than cached
+        let answered: BTreeMap<&String, &LinkCheck> = cache.iter().filter(|(_, check)| check.status != 0).collect();
+        let content = serde_json::to_string_pretty(&answered).map_err(|e| format!("Failed to serialize link cache: {}", e))?;
+        component::atomic::write(&path, content)?;
+    }
+
+    let mut ok = 0;
+    let mut problems = 0;
+    for (url, used_by) in &links {
+        let Some(check) = cache.get(url) else {
+            continue;
+        };
+        let problem = match check.state() {
+            LinkState::Ok => {
+                ok += 1;
+                continue;
+            }
+            LinkState::Redirected(location) => format!("REDIRECTED -> {}", location),
+            LinkState::Dead(status) => format!("DEAD (HTTP {})", status),
+            LinkState::Unreachable(error) => format!("UNREACHABLE ({})", error),
+        };
+        problems += 1;
+        println!("\n{} {}", problem, url);
+        for symbol in used_by.iter().take(5) {
+            println!("  {}", symbol);
+        }
+        if used_by.len() > 5 {
+            println!("  ... and {} more", used_by.len() - 5);
+        }
+    }
+
+    println!();
+    println!("{} OK, {} dead, redirected or unreachable ({} checked now, {} from cache)", ok, problems, queried, links.len() - queried - unchecked);
+    if unchecked > 0 {
+        println!("{} URLs not checked yet (--offline)", unchecked);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_urls_and_classifies_answers() {
+        let content = r#"(kicad_symbol_lib (version 20211014) (generator atlantix-eda)
+  (symbol "R0603_1.00K"
+    (property "Datasheet" "~" (at 0 0 0))
+    (property "SupplierURL" "https://www.digikey.com/products/en?keywords=541-1.00KHCT-ND" (at 0 0 0)))
+  (symbol "R0603_1.02K"
+    (property "Datasheet" "https://www.vishay.com/docs/20035/dcrcwe3.pdf" (at 0 0 0))))"#;
+        let mut links = Links::new();
+        collect_links(Path::new("R.kicad_sym"), content, &mut links).unwrap();
+        assert_eq!(links.len(), 2);
+        assert_eq!(links["https://www.vishay.com/docs/20035/dcrcwe3.pdf"], ["R.kicad_sym: R0603_1.02K (Datasheet)"]);
+
+        let answer = |status, location: Option<&str>| LinkCheck {
+            status,
+            location: location.map(str::to_string),
+            error: None,
+            checked: String::new(),
+        };
+        assert_eq!(answer(200, None).state(), LinkState::Ok);
+        assert_eq!(answer(301, Some("https://x")).state(), LinkState::Redirected("https://x"));
+        assert_eq!(answer(404, None).state(), LinkState::Dead(404));
+        assert_eq!(answer(0, None).state(), LinkState::Unreachable("no response"));
+    }
+}
@@ -0,0 +1,337 @@
+//! Part request workflow
+//!
+//! `aeda request new` generates a single-part stub (symbol, footprint, CSV row
+//! and Stencil library entry) under `requests/` and records it in the manifest
+//! as pending approval. `aeda approve` promotes the stub into the regular
+//! library directories and marks it Approved.
+
+use super::generate::{
+    get_metric_suffix, get_power_rating, get_tolerance, load_manifest, save_manifest,
+    LibraryMethods, ResistorLibrary,
+};
+use super::lock::CategoryLock;
+use super::status::default_reviewer;
+use component::capabilities::E_SERIES;
+use component::package_naming;
+use component::{AtlantixError, PartStatus};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path};
+
+#[allow(clippy::too_many_arguments)]
+pub fn new(
+    data_dir: &Path,
+    component_type: &str,
+    value: &str,
+    package: &str,
+    series: &str,
+    distributor: &str,
+    manufacturer: &str,
+    symbol_style: &str,
+    kicad_check: bool,
+) -> Result<(), String> {
+    if component_type != "resistor" {
+        return Err(format!(
+            "Unsupported component type '{}'. Only 'resistor' requests are supported.",
+            component_type
+        ));
+    }
+
+    let package = package_naming::resolve(package).unwrap_or_else(|| package.to_string());
+    let package = package.as_str();
+    let resistance: component::Resistance = value.parse()?;
+    let (base, decade) = component::split_decade(resistance.ohms()).ok_or_else(|| {
+        format!("Resistance {} is outside the supported 1 mOhm to 10 MOhm range", value)
+    })?;
+    let eseries: usize = series
+        .to_uppercase()
+        .trim_start_matches('E')
+        .parse()
+        .map_err(|_| AtlantixError::invalid_series(series, E_SERIES.map(|n| format!("E{}", n))))?;
+    let distributor: component::Distributor = distributor.parse()?;
+    let manufacturers = component::manufacturer::parse_list(manufacturer)?;
+    let manufacturer = manufacturers[0].clone();
+    let symbol_styles: component::kicad_symbol::ResistorStyles = symbol_style.parse()?;
+    let symbol_style = symbol_styles.for_package(package);
+
+    let mut resistor = component::Resistor::with_values(eseries, package.to_string(), vec![base]);
+    resistor.set_status(PartStatus::Draft);
+    resistor.set_distributor(distributor);
+    resistor.set_manufacturer(manufacturer.clone());
+    resistor.set_alternate_manufacturers(manufacturers[1..].to_vec());
+    let csv_row = resistor.generate(decade);
+    let name = component::resistor_name(package, resistor.value(), None);
+    // A stub without an MPN has no supplier part numbers either
+    if resistor.generate_mpn().is_empty() {
+        return Err(format!(
+            "{} makes no {} {} resistor in {}. Request another package or --manufacturer.",
+            manufacturer,
+            resistor.value().label(),
+            get_tolerance(series),
+            package
+        ));
+    }
+
+    let _lock = CategoryLock::acquire(data_dir, component_type)?;
+    let request_dir = data_dir.join("requests").join(&name);
+    if request_dir.exists() {
+        return Err(format!("A request for {} already exists at {}", name, request_dir.display()));
+    }
+    fs::create_dir_all(&request_dir)
+        .map_err(|e| format!("Failed to create {}: {}", request_dir.display(), e))?;
+
+    println!("Creating part request {}...", name);
+
+    let symbol_path = request_dir.join(format!("{}.kicad_sym", name));
+    resistor
+        .generate_kicad_symbols(vec![decade], &symbol_path.to_string_lossy(), symbol_style)?;
+    println!("  Created: {}", symbol_path.display());
+
+    resistor
+        .generate_kicad_footprints(vec![package], &request_dir.to_string_lossy())?;
+    // Packages without a land pattern get no footprint
+    let mut footprints = Vec::new();
+    for entry in fs::read_dir(&request_dir).map_err(|e| format!("Failed to read {}: {}", request_dir.display(), e))? {
+        let path = entry.map_err(|e| format!("Failed to read request: {}", e))?.path();
+        if path.extension().is_some_and(|e| e == "kicad_mod") {
+            footprints.push(path);
+        }
+    }
+    footprints.sort();
+    for footprint in &footprints {
+        println!("  Created: {}", footprint.display());
+    }
+    if footprints.is_empty() {
+        println!("  Warning: no footprint for package {}, add one before approving", package);
+    }
+
+    let csv_path = request_dir.join(format!("{}.csv", name));
+    component::atomic::write(&csv_path, format!("{}{}", component::altium::csv_header(resistor.suppliers().len()), csv_row))?;
+    println!("  Created: {}", csv_path.display());
+    super::limits::report();
+
+    let library = ResistorLibrary {
+        name: name.clone(),
+        component_type: "resistor".into(),
+        description: format!("Requested {} resistor in {} package", value, package_naming::describe(package)),
+        package: package.to_string(),
+        footprint: format!("Resistor_SMD:R_{}{}", package, get_metric_suffix(package)),
+        tolerance: get_tolerance(series).into(),
+        manufacturer: manufacturer.to_string(),
+        alternate_manufacturers: manufacturers[1..].iter().map(|m| m.to_string()).collect(),
+        automotive: false,
+        power_rating: get_power_rating(package).to_string(),
+        power_mw: get_power_rating(package).milliwatts(),
+        series: series.into(),
+        pins: vec!["1".into(), "2".into()],
+        prefix: "R".into(),
+        base_values: vec![base],
+        multipliers: [("".into(), decade)].into_iter().collect(),
+        values: Vec::new(),
+        value_notation: super::generate::value_notation(),
+        symbol_style: super::generate::symbol_style(symbol_style),
+        tolerance_in_name: false,
+        min_value: None,
+        max_value: None,
+        combined: Vec::new(),
+        dropped: Vec::new(),
+        methods: LibraryMethods::default(),
+    };
+    let json_path = request_dir.join(format!("{}.json", name));
+    let content = serde_json::to_string_pretty(&library)
+        .map_err(|e| format!("Failed to serialize library: {}", e))?;
+    component::atomic::write(&json_path, content)?;
+    println!("  Created: {}", json_path.display());
+
+    fs::create_dir_all(data_dir.join("libraries"))
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    let _manifest_lock = CategoryLock::acquire_manifest(data_dir)?;
+    let mut manifest = load_manifest(data_dir)?;
+    manifest
+        .pending
+        .entry("resistor".to_string())
+        .or_insert_with(HashMap::new)
+        .insert(name.clone(), format!("../requests/{}/{}.json", name, name));
+    manifest.set_status("resistor", &name, PartStatus::Draft, default_reviewer());
+    save_manifest(data_dir, &manifest)?;
+
+    if kicad_check {
+        super::kicad_cli::report_generated(&[symbol_path], std::slice::from_ref(&request_dir));
+    }
+    if distributor == component::Distributor::Mouser {
+        let mouser_pn = component::distributor::mouser_pn(&manufacturer.to_string(), &resistor.generate_mpn()).unwrap_or_default();
+        report_mouser_offer(&mouser_pn);
+    }
+
+    println!("\nRequest resistor::{} is pending approval.", name);
+    println!("Promote it with:");
+    println!("  aeda approve resistor::{}", name);
+    Ok(())
+}
+
+/// Print Mouser price and stock when an API key is configured. Lookup
+/// failures are reported but never fail the request.
+fn report_mouser_offer(mouser_pn: &str) {
+    let Some(key) = super::mouser::api_key() else {
+        println!("\nSet MOUSER_API_KEY to look up Mouser price and stock for {}.", mouser_pn);
+        return;
+    };
+    match super::mouser::lookup(&key, mouser_pn) {
+        Ok(Some(offer)) => {
+            println!("\nMouser {}: {}", offer.part_number, offer.availability);
+            for (quantity, price) in &offer.price_breaks {
+                println!("  {:>6} pcs  {}", quantity, price);
+            }
+            if let Some(url) = offer.url {
+                println!("  {}", url);
+            }
+        }
+        Ok(None) => println!("\nMouser does not list {}.", mouser_pn),
+        Err(e) => println!("\nWarning: {}", e),
+    }
+}
+
+pub fn list(data_dir: &Path) -> Result<(), String> {
+    let manifest = load_manifest(data_dir)?;
+
+    if manifest.pending.values().all(|v| v.is_empty()) {
+        println!("No pending part requests.");
+        return Ok(());
+    }
+
+    println!("Pending part requests:");
+    for (category, items) in &manifest.pending {
+        for (name, path) in items {
+            println!("  {}::{} -> {}", category, name, path);
+        }
+    }
+    Ok(())
+}
+
+pub fn approve(data_dir: &Path, part: &str, reviewer: Option<String>) -> Result<(), String> {
+    let (category, name) = part.split_once("::").ok_or_else(|| {
+        format!(
+            "Invalid part '{}'. Expected format: category::name (e.g., resistor::R0201_3.32K)",
+            part
+        )
+    })?;
+    check_name(part, category)?;
+    check_name(part, name)?;
+    let reviewer = reviewer.or_else(default_reviewer);
+    let _lock = CategoryLock::acquire(data_dir, category)?;
+    let _manifest_lock = CategoryLock::acquire_manifest(data_dir)?;
+
+    let mut manifest = load_manifest(data_dir)?;
+    let pending = manifest
+        .pending
+        .get_mut(category)
+        .and_then(|items| items.remove(name))
+        .is_some();
+    if manifest.pending.get(category).is_some_and(|items| items.is_empty()) {
+        manifest.pending.remove(category);
+    }
+
+    if !pending {
+        // Already promoted libraries only need their status updated
+        let known = manifest
+            .libraries
+            .get(category)
+            .is_some_and(|items| items.contains_key(name));
+        if !known {
+            return Err(format!("No pending request or library named {}", part));
+        }
+        manifest.set_status(category, name, PartStatus::Approved, reviewer);
+        save_manifest(data_dir, &manifest)?;
+        println!("{} marked as Approved.", part);
+        return Ok(());
+    }
+
+    let request_dir = data_dir.join("requests").join(name);
+    let library_dir = data_dir.join("libraries").join(category);
+    let symbols_dir = data_dir.join("symbols");
+    let footprints_dir = data_dir.join("footprints");
+    let altium_dir = data_dir.join("altium");
+    for dir in [&library_dir, &symbols_dir, &footprints_dir, &altium_dir] {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+
+    println!("Approving {}...", part);
+
+    let entries = fs::read_dir(&request_dir)
+        .map_err(|e| format!("Failed to read {}: {}", request_dir.display(), e))?;
+    for entry in entries {
+        let path = entry.map_err(|e| format!("Failed to read request: {}", e))?.path();
+        let target_dir = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => &library_dir,
+            Some("kicad_sym") => &symbols_dir,
+            Some("kicad_mod") => &footprints_dir,
+            Some("csv") => &altium_dir,
+            _ => continue,
+        };
+        let target = target_dir.join(path.file_name().unwrap());
+        let mut content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if target_dir == &symbols_dir {
+            content = content.replace(
+                &format!("(property \"Status\" \"{}\"", PartStatus::Draft),
+                &format!("(property \"Status\" \"{}\"", PartStatus::Approved),
+            );
+        }
+        component::atomic::write(&target, content)?;
+        println!("  Promoted: {}", target.display());
+    }
+
+    manifest
+        .libraries
+        .entry(category.to_string())
+        .or_insert_with(HashMap::new)
+        .insert(name.to_string(), format!("{}/{}.json", category, name));
+    manifest.set_status(category, name, PartStatus::Approved, reviewer);
+    save_manifest(data_dir, &manifest)?;
+
+    fs::remove_dir_all(&request_dir)
+        .map_err(|e| format!("Failed to remove {}: {}", request_dir.display(), e))?;
+
+    println!("\n{} approved and added to the library.", part);
+    Ok(())
+}
+
+/// Reject names that would leave their directory, such as `../config`
+fn check_name(part: &str, name: &str) -> Result<(), String> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) if !name.contains(['/', '\\']) => Ok(()),
+        _ => Err(format!("Invalid part '{}': '{}' is not a plain name", part, name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_names_outside_the_data_dir() {
+        assert!(check_name("resistor::R0603_1.00K", "R0603_1.00K").is_ok());
+        for name in ["../config", "..", "a/b", "/etc", "a\\b", ""] {
+            assert!(check_name("resistor::x", name).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn writes_the_part_numbers_of_every_vishay_size() {
+        let dir = std::env::temp_dir().join(format!("aeda-request-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        new(&dir, "resistor", "3.32k", "0201", "E96", "digikey", "vishay", "european", false).unwrap();
+        let csv = fs::read_to_string(dir.join("requests/R0201_3.32K/R0201_3.32K.csv")).unwrap();
+        assert!(csv.contains("CRCW02013K32FKED"), "{}", csv);
+        let symbol = fs::read_to_string(dir.join("requests/R0201_3.32K/R0201_3.32K.kicad_sym")).unwrap();
+        assert!(symbol.contains(r#"(property "SupplierPN" "541-CRCW02013K32FKEDCT-ND""#), "{}", symbol);
+
+        // No MPN, no stub
+        let err = new(&dir, "resistor", "3.32k", "1218", "E96", "digikey", "koa", "european", false).unwrap_err();
+        assert!(err.contains("no 3.32K 1% resistor in 1218"), "{}", err);
+        assert!(!dir.join("requests/R1218_3.32K").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
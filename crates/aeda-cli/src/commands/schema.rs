@@ -0,0 +1,66 @@
+//! JSON Schemas of the library files
+//!
+//! The schemas are generated from the same types that write the libraries,
+//! so the Stencil tool and third parties can validate library JSON without
+//! this CLI. Libraries read by this CLI are checked against those types on
+//! load, and a file that does not match names the offending field.
+
+use super::generate::{CapacitorLibrary, ResistorLibrary};
+use schemars::schema::RootSchema;
+use schemars::schema_for;
+
+/// Categories with a published schema
+pub(crate) const CATEGORIES: [&str; 2] = ["resistor", "capacitor"];
+
+fn schema(category: &str) -> Result<RootSchema, String> {
+    match category {
+        "resistor" => Ok(schema_for!(ResistorLibrary)),
+        "capacitor" => Ok(schema_for!(CapacitorLibrary)),
+        _ => Err(format!(
+            "No schema for '{}', expected one of: {}",
+            category,
+            CATEGORIES.join(", ")
+        )),
+    }
+}
+
+/// Check a library file of `category` against its schema. Categories
+/// without a schema always pass.
+pub(crate) fn validate(category: &str, path: &str, content: &str) -> Result<(), String> {
+    let result = match category {
+        "resistor" => serde_json::from_str::<ResistorLibrary>(content).map(drop),
+        "capacitor" => serde_json::from_str::<CapacitorLibrary>(content).map(drop),
+        _ => return Ok(()),
+    };
+    result.map_err(|e| format!("{} does not match the {} library schema: {}", path, category, e))
+}
+
+pub fn run(category: &str) -> Result<(), String> {
+    let schema = schema(category)?;
+    let json = serde_json::to_string_pretty(&schema).map_err(|e| format!("Failed to serialize schema: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_matches_what_validation_accepts() {
+        let schema = serde_json::to_value(schema("resistor").unwrap()).unwrap();
+        let required: Vec<&str> = schema["required"].as_array().unwrap().iter().filter_map(|v| v.as_str()).collect();
+        assert!(required.contains(&"base_values") && required.contains(&"methods"));
+        // Optional fields stay optional, older libraries have no power_mw
+        assert!(!required.contains(&"power_mw") && !required.contains(&"symbol_style"));
+
+        let library = r#"{"name": "E24_0603", "type": "resistor", "description": "", "package": "0603",
+            "footprint": "", "tolerance": "5%", "manufacturer": "Vishay", "power_rating": "0.1W",
+            "series": "E24", "pins": ["1", "2"], "prefix": "R", "base_values": [1.0],
+            "multipliers": {"K": 1000.0}, "methods": {"after_factory": [], "after_value": []}}"#;
+        assert!(validate("resistor", "E24_0603.json", library).is_ok());
+        let broken = library.replace("[1.0]", "\"1.0\"");
+        let err = validate("resistor", "E24_0603.json", &broken).unwrap_err();
+        assert!(err.contains("E24_0603.json") && err.contains("invalid type"), "{}", err);
+    }
+}
@@ -0,0 +1,253 @@
+//! Per-part scripting hook
+//!
+//! An optional Rhai script, configured in config.toml, runs once for every
+//! generated part, before the library JSON, the symbols or the Altium rows
+//! are written:
+//!
+//! ```toml
+//! [scripting]
+//! part_hook = "hooks/parts.rhai"   # relative to the data directory
+//! ```
+//!
+//! The script sees the part as the `part` object map with the string fields
+//! `name`, `reference`, `value`, `footprint`, `datasheet`, `keywords`,
+//! `description`, `manufacturer`, `mpn`, `supplier`, `supplier_pn` and
+//! `supplier_url`, the `properties` map of extra symbol properties, and a
+//! `skip` flag that drops the part from every output when set. `si(text)`
+//! turns values such as "4.99K", "4K7" or "10uH" into numbers:
+//!
+//! ```text
+//! if si(part.value) > 1e6 { part.properties.Note = "check leakage"; }
+//! if part.footprint.contains("0201") { part.skip = true; }
+//! ```
+
+use component::hook::{HookAction, PartHook};
+use component::kicad_symbol::KicadSymbol;
+use component::settings::Settings;
+use component::AtlantixError;
+use rhai::{Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Upper bound on script operations per part, so a runaway loop fails
+/// instead of hanging generation
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+pub struct ScriptHook {
+    engine: Engine,
+    ast: AST,
+}
+
+impl ScriptHook {
+    pub fn compile(source: &str) -> Result<ScriptHook, String> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.register_fn("si", |text: &str| -> Result<f64, Box<EvalAltResult>> {
+            si_value(text).ok_or_else(|| format!("'{}' is not a number", text).into())
+        });
+        let ast = engine.compile(source).map_err(|e| e.to_string())?;
+        Ok(ScriptHook { engine, ast })
+    }
+}
+
+impl PartHook for ScriptHook {
+    fn apply(&self, symbol: &mut KicadSymbol) -> Result<HookAction, AtlantixError> {
+        let mut scope = Scope::new();
+        scope.push("part", to_map(symbol));
+        self.engine
+            .run_ast_with_scope(&mut scope, &self.ast)
+            .map_err(|e| AtlantixError::Invalid(e.to_string()))?;
+        let part = scope.get_value::<Map>("part").ok_or_else(|| {
+            AtlantixError::Invalid("the script replaced `part` with something other than an object map".to_string())
+        })?;
+        if part.get("skip").is_some_and(|skip| skip.as_bool() == Ok(true)) {
+            return Ok(HookAction::Skip);
+        }
+        from_map(&part, symbol).map_err(AtlantixError::Invalid)?;
+        Ok(HookAction::Keep)
+    }
+}
+
+fn fields(symbol: &mut KicadSymbol) -> [(&'static str, &mut String); 12] {
+    [
+        ("name", &mut symbol.name),
+        ("reference", &mut symbol.reference),
+        ("value", &mut symbol.value),
+        ("footprint", &mut symbol.footprint),
+        ("datasheet", &mut symbol.datasheet),
+        ("keywords", &mut symbol.keywords),
+        ("description", &mut symbol.description),
+        ("manufacturer", &mut symbol.manufacturer),
+        ("mpn", &mut symbol.mpn),
+        ("supplier", &mut symbol.supplier),
+        ("supplier_pn", &mut symbol.supplier_pn),
+        ("supplier_url", &mut symbol.supplier_url),
+    ]
+}
+
+fn to_map(symbol: &KicadSymbol) -> Map {
+    let mut copy = symbol.clone();
+    let mut part: Map = fields(&mut copy)
+        .into_iter()
+        .map(|(key, value)| (key.into(), Dynamic::from(value.clone())))
+        .collect();
+    let properties: Map = symbol
+        .properties
+        .iter()
+        .map(|(key, value)| (key.as_str().into(), Dynamic::from(value.clone())))
+        .collect();
+    part.insert("properties".into(), properties.into());
+    part.insert("skip".into(), false.into());
+    part
+}
+
+fn from_map(part: &Map, symbol: &mut KicadSymbol) -> Result<(), String> {
+    for (key, field) in fields(symbol) {
+        if let Some(value) = part.get(key) {
+            *field = text(key, value)?;
+        }
+    }
+    let properties = match part.get("properties") {
+        Some(value) => value
+            .read_lock::<Map>()
+            .map(|map| map.clone())
+            .ok_or("part.properties must be an object map")?,
+        None => Map::new(),
+    };
+    // Keep the existing property order, new properties go last
+    let mut updated = Vec::with_capacity(properties.len());
+    for (key, _) in &symbol.properties {
+        if let Some(value) = properties.get(key.as_str()) {
+            updated.push((key.clone(), text(key, value)?));
+        }
+    }
+    for (key, value) in &properties {
+        if !symbol.properties.iter().any(|(existing, _)| existing.as_str() == key.as_str()) {
+            updated.push((key.to_string(), text(key, value)?));
+        }
+    }
+    symbol.properties = updated;
+    Ok(())
+}
+
+fn text(key: &str, value: &Dynamic) -> Result<String, String> {
+    if value.is_string() || value.is_int() || value.is_float() || value.is_bool() {
+        Ok(value.to_string())
+    } else {
+        Err(format!("part.{} must be text, got {}", key, value.type_name()))
+    }
+}
+
+/// Number with an optional SI prefix, which may also stand in for the
+/// decimal point as in "4K7" or "2R2"
+fn si_value(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    let (number, rest) = text.split_at(split);
+    let mut chars = rest.chars();
+    let scale = match chars.next() {
+        Some('p') => 1e-12,
+        Some('n') => 1e-9,
+        Some('u') | Some('µ') => 1e-6,
+        Some('m') => 1e-3,
+        Some('R') | Some('r') => 1.0,
+        Some('k') | Some('K') => 1e3,
+        Some('M') => 1e6,
+        Some('G') => 1e9,
+        _ => return number.parse().ok(),
+    };
+    let fraction: String = chars.take_while(|c| c.is_ascii_digit()).collect();
+    let number = if fraction.is_empty() || number.contains('.') {
+        number.to_string()
+    } else {
+        format!("{}.{}", number, fraction)
+    };
+    number.parse::<f64>().ok().map(|n| n * scale)
+}
+
+/// Script path from the `[scripting]` section of config.toml, if any
+fn configured_script(data_dir: &Path) -> Result<Option<PathBuf>, String> {
+    let config_path = data_dir.join("config.toml");
+    let Ok(content) = fs::read_to_string(&config_path) else {
+        return Ok(None);
+    };
+    let config: toml::Table = content
+        .parse()
+        .map_err(|e| format!("Failed to parse {}: {}", config_path.display(), e))?;
+    let Some(script) = config.get("scripting").and_then(|s| s.get("part_hook")) else {
+        return Ok(None);
+    };
+    let script = script
+        .as_str()
+        .ok_or_else(|| format!("scripting.part_hook in {} must be a path", config_path.display()))?;
+    Ok(Some(data_dir.join(script)))
+}
+
+/// Run the configured part hook, if any, on the parts of `settings`
+pub fn install(data_dir: &Path, settings: &mut Settings) -> Result<(), String> {
+    let Some(path) = configured_script(data_dir)? else {
+        return Ok(());
+    };
+    let source = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read part hook {}: {}", path.display(), e))?;
+    let hook = ScriptHook::compile(&source)
+        .map_err(|e| format!("Failed to compile part hook {}: {}", path.display(), e))?;
+    settings.hook = Some(Arc::new(hook));
+    Ok(())
+}
+
+/// Fail the command when the hook raised errors on any part
+pub fn check_errors() -> Result<(), String> {
+    let errors = component::hook::take_errors();
+    if errors.is_empty() {
+        return Ok(());
+    }
+    for error in &errors {
+        eprintln!("  {}", error);
+    }
+    Err(format!("The part hook failed on {} part(s), those parts were left unchanged", errors.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edits_and_skips_parts() {
+        let hook = ScriptHook::compile(
+            r#"
+            if si(part.value) > 1e6 { part.properties.Note = "check leakage"; }
+            if si(part.value) < 1.0 { part.skip = true; }
+            part.keywords += " thick film";
+            "#,
+        )
+        .unwrap();
+        let symbol = |value: &str| {
+            KicadSymbol::new(format!("R0603_{}", value), value.into(), "Resistor_SMD:R_0603".into(), "european")
+        };
+
+        let mut large = symbol("4.99M");
+        assert_eq!(hook.apply(&mut large).unwrap(), HookAction::Keep);
+        assert_eq!(large.properties, vec![("Note".to_string(), "check leakage".to_string())]);
+        assert_eq!(large.keywords, "R res resistor thick film");
+
+        let mut small = symbol("0R47");
+        assert_eq!(hook.apply(&mut small).unwrap(), HookAction::Skip);
+
+        let broken = ScriptHook::compile("part.value = [];").unwrap();
+        assert!(broken.apply(&mut symbol("10K")).is_err());
+    }
+
+    #[test]
+    fn parses_si_values() {
+        assert_eq!(si_value("4.99K"), Some(4990.0));
+        assert_eq!(si_value("4K7"), Some(4700.0));
+        assert_eq!(si_value("100"), Some(100.0));
+        assert_eq!(si_value("2R2"), Some(2.2));
+        assert!((si_value("10uH").unwrap() - 10e-6).abs() < 1e-15);
+        assert_eq!(si_value("n/a"), None);
+    }
+}
@@ -0,0 +1,179 @@
+//! Parametric search over the generated parts
+//!
+//! `aeda search resistors --min 1k --max 10k --package 0603` answers from
+//! the parts database. Value ranges take resistances (`4k7`, `1M`) for
+//! resistors and capacitances (`100nF`, `4.7u`) for capacitors; packages,
+//! tolerances, power ratings and MPN substrings filter every family. The
+//! parts print as a table, JSON, CSV or Markdown, and a selection of them is
+//! written as a mini-library (see [`selection`]).
+
+use super::bom::{self, BomRow};
+use super::generate::parse_min_power;
+use super::parts_db::{self, Search};
+use super::selection;
+use component::limits::Limits;
+use component::{Power, Resistance};
+use rusqlite::Connection;
+use serde::Deserialize;
+use std::path::Path;
+
+/// Value in farads of a capacitance with or without the trailing `F`
+fn capacitance(value: &str) -> Result<f64, String> {
+    let value = value.trim();
+    let with_unit = if value.ends_with('F') { value.to_string() } else { format!("{}F", value) };
+    component::marking::parse_capacitance(&with_unit).ok_or_else(|| format!("Invalid capacitance '{}', e.g. 100nF or 4.7u", value))
+}
+
+fn resistance(value: &str) -> Result<f64, String> {
+    Ok(value.parse::<Resistance>()?.ohms())
+}
+
+/// Parses a value bound to ohms or farads
+type ValueParser = fn(&str) -> Result<f64, String>;
+
+/// Library category of a component type, and the parser of its values
+fn category(component_type: &str) -> Result<(Option<String>, Option<ValueParser>), String> {
+    match component_type {
+        "all" => Ok((None, None)),
+        "resistor" | "resistors" => Ok((Some("resistor".to_string()), Some(resistance))),
+        "capacitor" | "capacitors" => Ok((Some("capacitor".to_string()), Some(capacitance))),
+        family => {
+            let families = component::family::families();
+            if families.iter().any(|f| f.name() == family) {
+                return Ok((Some(family.to_string()), None));
+            }
+            let names: Vec<&str> = families.iter().map(|f| f.name()).collect();
+            Err(format!(
+                "Unknown component type '{}', expected all, resistors, capacitors{}",
+                family,
+                names.iter().map(|name| format!(", {}", name)).collect::<String>()
+            ))
+        }
+    }
+}
+
+/// Tolerance as the libraries write it, `1` and `1%` alike
+fn tolerance(tolerance: &str) -> String {
+    let tolerance = tolerance.trim();
+    if tolerance.ends_with('%') { tolerance.to_string() } else { format!("{}%", tolerance) }
+}
+
+fn print_table(rows: &[BomRow]) {
+    let header = selection::COLUMNS;
+    let cells: Vec<[&str; 8]> = rows.iter().map(selection::cells).collect();
+    let widths: Vec<usize> = (0..header.len())
+        .map(|column| cells.iter().map(|row| row[column].chars().count()).chain([header[column].len()]).max().unwrap_or(0))
+        .collect();
+    let line = |fields: &[&str]| {
+        let padded: Vec<String> = fields.iter().zip(&widths).map(|(field, width)| format!("{:<width$}", field, width = width)).collect();
+        println!("{}", padded.join("  ").trim_end());
+    };
+    line(&header);
+    for row in &cells {
+        line(row);
+    }
+}
+
+fn all() -> String {
+    "all".to_string()
+}
+
+/// Filters of `aeda search`, every one optional
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Query {
+    /// resistors, capacitors, a family name or all
+    #[serde(default = "all")]
+    pub component_type: String,
+    pub min: Option<String>,
+    pub max: Option<String>,
+    pub package: Option<String>,
+    pub tolerance: Option<String>,
+    pub min_power: Option<String>,
+    pub mpn: Option<String>,
+    /// Names of the parts to select, e.g. `R0603_4.99K`
+    pub parts: Vec<String>,
+}
+
+/// Parts of the database matching `query`
+pub(crate) fn find(conn: &Connection, query: &Query) -> Result<Vec<BomRow>, String> {
+    let (category, parse_value) = category(&query.component_type)?;
+    let value = |bound: Option<&str>| -> Result<Option<f64>, String> {
+        let Some(bound) = bound else {
+            return Ok(None);
+        };
+        match parse_value {
+            Some(parse) => parse(bound).map(Some),
+            None => Err("Value ranges need a component type with values: resistors or capacitors".to_string()),
+        }
+    };
+    let search = Search {
+        category,
+        min: value(query.min.as_deref())?,
+        max: value(query.max.as_deref())?,
+        package: query.package.as_deref().map(|package| component::package_naming::resolve(package).unwrap_or_else(|| package.to_string())),
+        tolerance: query.tolerance.as_deref().map(tolerance),
+        mpn: query.mpn.clone(),
+    };
+    let min_power = parse_min_power(query.min_power.as_deref())?;
+
+    let mut rows = parts_db::search(conn, &search)?;
+    if let Some(min) = min_power {
+        // Parts without a power rating never match a power filter
+        rows.retain(|row| row.power.parse::<Power>().is_ok_and(|power| power >= min));
+    }
+    if !query.parts.is_empty() {
+        selection::pick(&mut rows, &query.parts)?;
+    }
+    Ok(rows)
+}
+
+/// Print the parts matching `query` as a table, csv, markdown or json, or
+/// write them to a mini-library at `library`
+pub fn run(data_dir: &Path, query: &Query, format: &str, library: Option<&Path>) -> Result<(), String> {
+    let conn = parts_db::open(data_dir)?;
+    let rows = find(&conn, query)?;
+
+    if let Some(library) = library {
+        if rows.is_empty() {
+            return Err("No parts match, nothing to write".to_string());
+        }
+        return selection::write_library(data_dir, &rows, library);
+    }
+    match format {
+        "json" => {
+            let json = serde_json::to_string_pretty(&rows).map_err(|e| format!("Failed to serialize parts: {}", e))?;
+            println!("{}", json);
+        }
+        "csv" => print!("{}", bom::csv(&rows, &Limits::default())),
+        "markdown" => print!("{}", selection::markdown(&rows)),
+        "table" if rows.is_empty() => println!("No parts match."),
+        "table" => {
+            print_table(&rows);
+            println!("\n{} parts", rows.len());
+        }
+        _ => return Err(format!("Unknown format '{}', expected table, csv, markdown or json", format)),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_bounds_follow_the_component_type() {
+        let (library, parse) = category("resistors").unwrap();
+        assert_eq!(library.as_deref(), Some("resistor"));
+        assert_eq!(parse.unwrap()("4k7"), Ok(4700.0));
+
+        let (library, parse) = category("capacitors").unwrap();
+        assert_eq!(library.as_deref(), Some("capacitor"));
+        assert_eq!(parse.unwrap()("100n"), Ok(100.0 * 1e-9));
+
+        assert!(matches!(category("all").unwrap(), (None, None)));
+        assert!(category("transistors").unwrap_err().contains("transistors"));
+        assert_eq!(tolerance("1"), "1%");
+        assert_eq!(tolerance("0.1%"), "0.1%");
+    }
+}
@@ -0,0 +1,198 @@
+//! Parts picked with `aeda search`, for pasting or as a mini-library
+//!
+//! `--parts` narrows a search to named rows; the selection prints as CSV in
+//! the BOM columns or as a Markdown table, or is written with `--library` as
+//! a data directory of its own. That directory holds a manifest and one
+//! library JSON per source library with just the selected values, so every
+//! command works on it with `--data-dir`, e.g. `aeda --data-dir <dir> export
+//! bom`. Resistor values of several decades are split into one library per
+//! decade, named after it (`E96_0603_1K`).
+
+use super::bom::BomRow;
+use super::generate::{load_manifest, save_manifest, Manifest};
+use component::Resistance;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// Columns of the search and Markdown tables
+pub(crate) const COLUMNS: [&str; 8] = ["Part", "Value", "Package", "Tol", "Power", "Manufacturer", "MPN", "Library"];
+
+pub(crate) fn cells(row: &BomRow) -> [&str; 8] {
+    [
+        row.part.as_str(),
+        row.value.as_str(),
+        row.package.as_str(),
+        row.tolerance.as_str(),
+        row.power.as_str(),
+        row.manufacturer.as_str(),
+        row.mpn.as_str(),
+        row.library.as_str(),
+    ]
+}
+
+pub(crate) fn markdown(rows: &[BomRow]) -> String {
+    let line = |fields: &[&str]| format!("| {} |\n", fields.iter().map(|field| field.replace('|', "\\|")).collect::<Vec<_>>().join(" | "));
+    let mut table = line(&COLUMNS);
+    table.push_str(&line(&["---"; 8]));
+    for row in rows {
+        table.push_str(&line(&cells(row)));
+    }
+    table
+}
+
+/// Keep the rows of the named parts, failing on names no row has
+pub(crate) fn pick(rows: &mut Vec<BomRow>, parts: &[String]) -> Result<(), String> {
+    let missing: Vec<&str> = parts
+        .iter()
+        .filter(|part| !rows.iter().any(|row| &row.part == *part))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("No parts match {} with these filters", missing.join(", ")));
+    }
+    rows.retain(|row| parts.contains(&row.part));
+    Ok(())
+}
+
+/// `1K` for the 1 kOhm decade
+fn decade_name(decade: f64) -> String {
+    match decade {
+        d if d >= 1e6 => format!("{}M", d / 1e6),
+        d if d >= 1e3 => format!("{}K", d / 1e3),
+        d => format!("{}R", d),
+    }
+}
+
+/// Libraries of the selected values of a resistor library, by name: one per
+/// decade, or the library itself when all values share one
+fn resistor_libraries(name: &str, library: &serde_json::Value, rows: &[&BomRow]) -> Vec<(String, serde_json::Value)> {
+    // A combined library is selected from per series, by their tolerances
+    if library["combined"].is_array() {
+        return super::export::resistor_series(library)
+            .iter()
+            .flat_map(|series| {
+                let tolerance = series["tolerance"].as_str().unwrap_or_default();
+                let rows: Vec<&BomRow> = rows.iter().copied().filter(|row| row.tolerance == tolerance).collect();
+                let name = format!("{}_{}", name, series["series"].as_str().unwrap_or_default());
+                resistor_libraries(&name, series, &rows)
+            })
+            .collect();
+    }
+    let mut decades: BTreeMap<u64, (f64, Vec<f64>)> = BTreeMap::new();
+    for row in rows {
+        let Some((base, decade)) = row.value.parse::<Resistance>().ok().and_then(|value| component::split_decade(value.ohms())) else {
+            continue;
+        };
+        // Positive floats order like their bits
+        decades.entry(decade.to_bits()).or_insert((decade, Vec::new())).1.push(base);
+    }
+    let split = decades.len() > 1;
+    decades
+        .into_values()
+        .map(|(decade, mut bases)| {
+            bases.sort_by(f64::total_cmp);
+            bases.dedup();
+            let name = if split { format!("{}_{}", name, decade_name(decade)) } else { name.to_string() };
+            let mut library = library.clone();
+            library["name"] = name.clone().into();
+            library["base_values"] = bases.into();
+            library["multipliers"] = serde_json::json!({ "": decade });
+            if let Some(library) = library.as_object_mut() {
+                library.remove("values");
+            }
+            (name, library)
+        })
+        .collect()
+}
+
+/// Write the selected parts as a data directory of their own
+pub(crate) fn write_library(data_dir: &Path, rows: &[BomRow], output: &Path) -> Result<(), String> {
+    let source = load_manifest(data_dir)?;
+    let mut manifest = Manifest {
+        name: "selection".into(),
+        version: source.version.clone(),
+        description: format!("Parts selected from {}", source.name),
+        libraries: HashMap::new(),
+        pending: HashMap::new(),
+        status: HashMap::new(),
+    };
+
+    let mut by_library: BTreeMap<&str, Vec<&BomRow>> = BTreeMap::new();
+    for row in rows {
+        by_library.entry(row.library.as_str()).or_default().push(row);
+    }
+    let mut written = 0;
+    for (key, rows) in by_library {
+        let (category, name) = key.split_once("::").ok_or_else(|| format!("Invalid library '{}'", key))?;
+        let path = source
+            .libraries
+            .get(category)
+            .and_then(|items| items.get(name))
+            .ok_or_else(|| format!("Library not found in manifest: {}", key))?;
+        let source_path = data_dir.join("libraries").join(path);
+        let content = fs::read_to_string(&source_path).map_err(|e| format!("Failed to read {}: {}", source_path.display(), e))?;
+        let library: serde_json::Value =
+            serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", source_path.display(), e))?;
+
+        let libraries = match category {
+            "resistor" => resistor_libraries(name, &library, &rows),
+            "capacitor" => {
+                let values: BTreeSet<&str> = rows.iter().map(|row| row.value.as_str()).collect();
+                let mut library = library.clone();
+                if let Some(all) = library["values"].as_array_mut() {
+                    all.retain(|value| value.as_str().is_some_and(|value| values.contains(value)));
+                }
+                vec![(name.to_string(), library)]
+            }
+            _ => {
+                let parts: BTreeSet<&str> = rows.iter().map(|row| row.part.as_str()).collect();
+                let mut library = library.clone();
+                if let Some(all) = library["parts"].as_array_mut() {
+                    all.retain(|part| part["name"].as_str().is_some_and(|part| parts.contains(part)));
+                }
+                vec![(name.to_string(), library)]
+            }
+        };
+        for (name, library) in libraries {
+            let relative = format!("{}/{}.json", category, name);
+            let library_path = output.join("libraries").join(&relative);
+            if let Some(parent) = library_path.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            let content = serde_json::to_string_pretty(&library).map_err(|e| format!("Failed to serialize library: {}", e))?;
+            component::atomic::write(&library_path, content)?;
+            if let Some(record) = source.status.get(key) {
+                manifest.status.insert(format!("{}::{}", category, name), record.clone());
+            }
+            manifest.libraries.entry(category.to_string()).or_default().insert(name, relative);
+            written += 1;
+        }
+    }
+    save_manifest(output, &manifest)?;
+    println!("Wrote {} parts in {} libraries to: {}", rows.len(), written, output.display());
+    println!("Use them with: aeda --data-dir {} export bom", output.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resistor_selections_split_by_decade() {
+        let library = serde_json::json!({ "name": "E96_0603", "base_values": [1.0, 4.99], "multipliers": { "K": 1000.0 } });
+        let row = |value: &str| BomRow { value: value.into(), ..Default::default() };
+        let (one, two) = (row("4.99K"), row("1.00K"));
+        let single = resistor_libraries("E96_0603", &library, &[&one, &two]);
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].0, "E96_0603");
+        assert_eq!(single[0].1["base_values"], serde_json::json!([1.0, 4.99]));
+        assert_eq!(single[0].1["multipliers"], serde_json::json!({ "": 1000.0 }));
+
+        let ohms = row("49.9");
+        let split = resistor_libraries("E96_0603", &library, &[&one, &ohms]);
+        let names: Vec<&str> = split.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["E96_0603_10R", "E96_0603_1K"]);
+    }
+}
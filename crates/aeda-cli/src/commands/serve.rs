@@ -0,0 +1,222 @@
+//! Long-running query server
+//!
+//! `aeda serve` keeps the manifest, the library definitions and an open
+//! parts database in memory and answers JSON requests over TCP, one per
+//! line, so Stencil and other tools resolving libraries or searching parts
+//! over and over skip re-reading the JSON files:
+//!
+//! ```text
+//! {"method": "libraries"}
+//! {"method": "resolve", "library": "resistor::E96_0603"}
+//! {"method": "parts", "library": "resistor::E96_0603"}
+//! {"method": "search", "component_type": "resistors", "min": "1k", "max": "10k", "package": "0603"}
+//! ```
+//!
+//! Each answer is one line, `{"ok": true, "result": ...}` or `{"ok": false,
+//! "error": "..."}`. The cache is dropped when the manifest changes, which
+//! every `generate`, `approve` and `status` does; changes to config.toml
+//! need a restart.
+
+use super::bom::BomRow;
+use super::generate::{load_manifest, Manifest};
+use super::parts_db;
+use super::search::{self, Query};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case", deny_unknown_fields)]
+enum Request {
+    /// Every library of the manifest
+    Libraries,
+    /// Definition of a library, e.g. `resistor::E96_0603`
+    Resolve { library: String },
+    /// Generated parts of a library
+    Parts { library: String },
+    Search(Query),
+}
+
+/// What the server keeps between requests, built on first use
+struct Cache {
+    manifest: Manifest,
+    conn: Connection,
+    /// Library definitions resolved so far
+    definitions: HashMap<String, Value>,
+}
+
+/// The data directory and its cache, valid while the manifest is unchanged
+struct World {
+    data_dir: PathBuf,
+    stamp: Option<(SystemTime, u64)>,
+    cache: Option<Cache>,
+}
+
+impl World {
+    fn new(data_dir: &Path) -> Self {
+        World { data_dir: data_dir.to_path_buf(), stamp: None, cache: None }
+    }
+
+    /// Modification time and size of the manifest
+    fn manifest_stamp(&self) -> Option<(SystemTime, u64)> {
+        let metadata = fs::metadata(self.data_dir.join("libraries/manifest.json")).ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    }
+
+    /// The cache, rebuilt when the manifest changed since it was built
+    fn cache(&mut self) -> Result<&mut Cache, String> {
+        let stamp = self.manifest_stamp();
+        if self.cache.is_none() || stamp != self.stamp {
+            self.cache = None;
+            let manifest = load_manifest(&self.data_dir)?;
+            let conn = parts_db::open(&self.data_dir)?;
+            self.stamp = stamp;
+            self.cache = Some(Cache { manifest, conn, definitions: HashMap::new() });
+        }
+        Ok(self.cache.as_mut().expect("cache was just built"))
+    }
+
+    fn libraries(&mut self) -> Result<Vec<String>, String> {
+        let cache = self.cache()?;
+        let mut libraries: Vec<String> = cache
+            .manifest
+            .libraries
+            .iter()
+            .flat_map(|(category, items)| items.keys().map(move |name| format!("{}::{}", category, name)))
+            .collect();
+        libraries.sort();
+        Ok(libraries)
+    }
+
+    fn resolve(&mut self, library: &str) -> Result<Value, String> {
+        let libraries_dir = self.data_dir.join("libraries");
+        let cache = self.cache()?;
+        if let Some(definition) = cache.definitions.get(library) {
+            return Ok(definition.clone());
+        }
+        let (category, name) = library
+            .split_once("::")
+            .ok_or_else(|| format!("Invalid library '{}', expected category::name", library))?;
+        let path = cache
+            .manifest
+            .libraries
+            .get(category)
+            .and_then(|items| items.get(name))
+            .ok_or_else(|| format!("Library not found in manifest: {}", library))?;
+        let path = libraries_dir.join(path);
+        let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let definition: Value =
+            serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        cache.definitions.insert(library.to_string(), definition.clone());
+        Ok(definition)
+    }
+
+    fn parts(&mut self, library: &str) -> Result<Vec<BomRow>, String> {
+        parts_db::library_rows(&self.cache()?.conn, library)
+    }
+
+    fn search(&mut self, query: &Query) -> Result<Vec<BomRow>, String> {
+        search::find(&self.cache()?.conn, query)
+    }
+
+    /// Answer one request line
+    fn answer(&mut self, line: &str) -> Value {
+        let result = serde_json::from_str::<Request>(line)
+            .map_err(|e| format!("Invalid request: {}", e))
+            .and_then(|request| match request {
+                Request::Libraries => json(self.libraries()),
+                Request::Resolve { library } => self.resolve(&library),
+                Request::Parts { library } => json(self.parts(&library)),
+                Request::Search(query) => json(self.search(&query)),
+            });
+        match result {
+            Ok(result) => serde_json::json!({ "ok": true, "result": result }),
+            Err(error) => serde_json::json!({ "ok": false, "error": error }),
+        }
+    }
+}
+
+fn json<T: Serialize>(value: Result<T, String>) -> Result<Value, String> {
+    serde_json::to_value(value?).map_err(|e| format!("Failed to serialize answer: {}", e))
+}
+
+fn handle(world: &Mutex<World>, stream: TcpStream) -> std::io::Result<()> {
+    // Answers are single small writes, send them without waiting
+    stream.set_nodelay(true)?;
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let answer = world.lock().unwrap_or_else(|e| e.into_inner()).answer(&line);
+        writer.write_all(format!("{}\n", answer).as_bytes())?;
+    }
+    Ok(())
+}
+
+pub fn run(data_dir: &Path, listen: &str) -> Result<(), String> {
+    let world = Arc::new(Mutex::new(World::new(data_dir)));
+    // Build the cache up front so the first request is as fast as the rest
+    world.lock().unwrap_or_else(|e| e.into_inner()).cache()?;
+    let listener = TcpListener::bind(listen).map_err(|e| format!("Failed to listen on {}: {}", listen, e))?;
+    println!("Serving {} on {}", data_dir.display(), listen);
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Warning: failed to accept a connection: {}", e);
+                continue;
+            }
+        };
+        let world = Arc::clone(&world);
+        // Connections answer with the settings of the command line
+        let settings = component::settings::current();
+        std::thread::spawn(move || {
+            component::settings::enter(settings, || {
+                if let Err(e) = handle(&world, stream) {
+                    eprintln!("Warning: connection closed: {}", e);
+                }
+            })
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_are_answered_from_the_cache() {
+        let dir = std::env::temp_dir().join(format!("aeda-serve-test-{}", std::process::id()));
+        fs::create_dir_all(dir.join("libraries/resistor")).unwrap();
+        fs::write(dir.join("libraries/resistor/E3_0603.json"), r#"{"name": "E3_0603"}"#).unwrap();
+        let manifest = r#"{"name": "t", "version": "1", "description": "", "libraries": {"resistor": {"E3_0603": "resistor/E3_0603.json"}}}"#;
+        fs::write(dir.join("libraries/manifest.json"), manifest).unwrap();
+        let mut world = World::new(&dir);
+
+        let answer = world.answer(r#"{"method": "resolve", "library": "resistor::E3_0603"}"#);
+        assert_eq!(answer["result"]["name"], "E3_0603");
+        // Served from memory until the manifest changes
+        fs::write(dir.join("libraries/resistor/E3_0603.json"), r#"{"name": "edited"}"#).unwrap();
+        assert_eq!(world.answer(r#"{"method": "resolve", "library": "resistor::E3_0603"}"#)["result"]["name"], "E3_0603");
+        fs::write(dir.join("libraries/manifest.json"), format!("{}\n", manifest)).unwrap();
+        assert_eq!(world.answer(r#"{"method": "resolve", "library": "resistor::E3_0603"}"#)["result"]["name"], "edited");
+
+        assert_eq!(world.answer(r#"{"method": "libraries"}"#)["result"][0], "resistor::E3_0603");
+        assert_eq!(world.answer(r#"{"method": "search", "component_type": "resistors"}"#)["ok"], true);
+        assert!(world.answer(r#"{"method": "resolve", "library": "nope"}"#)["error"].as_str().unwrap().contains("category::name"));
+        assert_eq!(world.answer("not json")["ok"], false);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
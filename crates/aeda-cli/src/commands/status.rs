@@ -0,0 +1,42 @@
+//! Set the review status of a library
+
+use super::generate::{load_manifest, save_manifest};
+use super::lock::CategoryLock;
+use component::PartStatus;
+use std::path::Path;
+
+/// Reviewer recorded when none is given on the command line
+pub(crate) fn default_reviewer() -> Option<String> {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .ok()
+}
+
+pub fn run(data_dir: &Path, library: &str, status: &str, reviewer: Option<String>) -> Result<(), String> {
+    let status: PartStatus = status.parse()?;
+    let (category, name) = library.split_once("::").ok_or_else(|| {
+        format!(
+            "Invalid library path '{}'. Expected format: category::name (e.g., resistor::E96_0603)",
+            library
+        )
+    })?;
+
+    // Fails while the category is regenerated, other reviews run alongside
+    let _lock = CategoryLock::acquire_shared(data_dir, category)?;
+    let _manifest_lock = CategoryLock::acquire_manifest(data_dir)?;
+    let mut manifest = load_manifest(data_dir)?;
+    let known = manifest
+        .libraries
+        .get(category)
+        .is_some_and(|items| items.contains_key(name));
+    if !known {
+        return Err(format!("Library not found in manifest: {}", library));
+    }
+
+    let previous = manifest.status_of(category, name);
+    manifest.set_status(category, name, status, reviewer.or_else(default_reviewer));
+    save_manifest(data_dir, &manifest)?;
+
+    println!("{}: {} -> {}", library, previous, status);
+    Ok(())
+}
@@ -128,24 +128,6 @@ impl Drop for TempNetlist {
     }
 }
 
-/// Build the kicad-cli argv. Honors `KICAD_CLI` env var (whitespace-split);
-/// otherwise defaults to `flatpak run --command=kicad-cli org.kicad.KiCad`,
-/// which is the stable 10.0.1 install on this machine.
-fn kicad_cli_argv() -> Vec<String> {
-    if let Ok(s) = std::env::var("KICAD_CLI") {
-        let parts: Vec<String> = s.split_whitespace().map(|p| p.to_string()).collect();
-        if !parts.is_empty() {
-            return parts;
-        }
-    }
-    vec![
-        "flatpak".to_string(),
-        "run".to_string(),
-        "--command=kicad-cli".to_string(),
-        "org.kicad.KiCad".to_string(),
-    ]
-}
-
 /// Export a netlist for the given .kicad_sch via kicad-cli. Output is written
 /// next to the schematic (flatpak sandboxing forbids /tmp writes), under a
 /// hidden filename that's removed when the returned guard is dropped.
@@ -163,7 +145,7 @@ fn export_netlist(sch_path: &Path) -> Result<TempNetlist, String> {
     let pid = std::process::id();
     let out_path = parent.join(format!(".aeda-sync-{}-{}.net", stem, pid));
 
-    let mut argv = kicad_cli_argv();
+    let mut argv = super::kicad_cli::argv();
     argv.extend([
         "sch".into(),
         "export".into(),
@@ -0,0 +1,206 @@
+//! Templated export for formats without a built-in exporter
+//!
+//! Renders a Handlebars template for the resistor and capacitor libraries
+//! and those of registered families, either once for the whole library (the template loops over `parts`) or
+//! once per part. Every part offers the fields `name`, `prefix`, `package`,
+//! `value`, `footprint`, `manufacturer`, `mpn`, `lcsc` and `lcsc_class`.
+//! A field mapping adds the names a target format expects:
+//!
+//! ```toml
+//! [fields]
+//! PartType = "name"
+//! Decal = "footprint"
+//! ```
+//!
+//! A PADS or OrCAD CIS CSV library is then a template such as
+//!
+//! ```text
+//! Part,Value,Footprint
+//! {{#each parts}}{{PartType}},{{value}},{{Decal}}
+//! {{/each}}
+//! ```
+//!
+//! Output is not HTML escaped, and unknown fields are an error rather than
+//! an empty string.
+
+use super::export::{family_rows, part_rows, PartRow};
+use handlebars::Handlebars;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Fields every part offers before mapping
+const PART_FIELDS: [&str; 9] = ["name", "prefix", "package", "value", "footprint", "manufacturer", "mpn", "lcsc", "lcsc_class"];
+
+fn part_fields(row: &PartRow) -> Map<String, Value> {
+    let part = row.part.as_ref();
+    let mut fields = Map::new();
+    fields.insert("name".into(), json!(row.name));
+    fields.insert("prefix".into(), json!(row.prefix));
+    fields.insert("package".into(), json!(row.package));
+    fields.insert("value".into(), json!(row.comment));
+    fields.insert("footprint".into(), json!(row.footprint));
+    fields.insert("manufacturer".into(), json!(part.map(|p| p.manufacturer.to_string()).unwrap_or_default()));
+    fields.insert("mpn".into(), json!(part.map(|p| p.mpn.clone()).unwrap_or_default()));
+    fields.insert("lcsc".into(), json!(part.and_then(|p| p.sku).unwrap_or_default()));
+    let class = part.map(|p| if p.basic { "Basic" } else { "Extended" }).unwrap_or_default();
+    fields.insert("lcsc_class".into(), json!(class));
+    fields
+}
+
+/// `[fields]` of a mapping file: template name to part field
+fn load_mapping(path: &Path) -> Result<BTreeMap<String, String>, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let mapping: toml::Table = content
+        .parse()
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    let mut fields = BTreeMap::new();
+    for (name, source) in mapping.get("fields").and_then(|f| f.as_table()).into_iter().flatten() {
+        let source = source
+            .as_str()
+            .ok_or_else(|| format!("fields.{} in {} must be a string", name, path.display()))?;
+        if !PART_FIELDS.contains(&source) {
+            return Err(format!(
+                "fields.{} in {} maps unknown field '{}', expected one of: {}",
+                name,
+                path.display(),
+                source,
+                PART_FIELDS.join(", ")
+            ));
+        }
+        fields.insert(name.clone(), source.to_string());
+    }
+    Ok(fields)
+}
+
+/// Template context of one part, the part fields plus their mapped names
+fn context(row: &PartRow, mapping: &BTreeMap<String, String>) -> Map<String, Value> {
+    let mut fields = part_fields(row);
+    for (name, source) in mapping {
+        let value = fields[source].clone();
+        fields.insert(name.clone(), value);
+    }
+    fields
+}
+
+/// Output file name of a template, `pads.txt.hbs` renders to `pads.txt`
+fn default_file_name(template: &Path) -> String {
+    let name = template.file_name().map(|f| f.to_string_lossy().into_owned()).unwrap_or_default();
+    name.strip_suffix(".hbs").map(str::to_string).unwrap_or(name)
+}
+
+fn render_files(
+    template: &str,
+    rows: &[PartRow],
+    mapping: &BTreeMap<String, String>,
+    per_part: bool,
+    file_name: &str,
+) -> Result<Vec<(String, String)>, String> {
+    let mut handlebars = Handlebars::new();
+    handlebars.register_escape_fn(handlebars::no_escape);
+    handlebars.set_strict_mode(true);
+    handlebars
+        .register_template_string("template", template)
+        .map_err(|e| format!("Failed to parse template: {}", e))?;
+    handlebars
+        .register_template_string("file_name", file_name)
+        .map_err(|e| format!("Failed to parse file name '{}': {}", file_name, e))?;
+
+    let parts: Vec<Map<String, Value>> = rows.iter().map(|row| context(row, mapping)).collect();
+    if !per_part {
+        let data = json!({ "parts": parts, "count": parts.len() });
+        let content = handlebars.render("template", &data).map_err(|e| format!("Failed to render template: {}", e))?;
+        return Ok(vec![(file_name.to_string(), content)]);
+    }
+    parts
+        .iter()
+        .map(|part| {
+            let name = handlebars
+                .render("file_name", part)
+                .map_err(|e| format!("Failed to render file name: {}", e))?;
+            let content = handlebars
+                .render("template", part)
+                .map_err(|e| format!("Failed to render {}: {}", name, e))?;
+            Ok((name, content))
+        })
+        .collect()
+}
+
+pub fn export(
+    data_dir: &Path,
+    template_path: &Path,
+    mapping_path: Option<&Path>,
+    per_part: bool,
+    file_name: Option<&str>,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    let template = fs::read_to_string(template_path)
+        .map_err(|e| format!("Failed to read template {}: {}", template_path.display(), e))?;
+    let mapping = match mapping_path {
+        Some(path) => load_mapping(path)?,
+        None => BTreeMap::new(),
+    };
+    // One file per part is named after the part unless told otherwise
+    let default_name = default_file_name(template_path);
+    let file_name = match (file_name, per_part) {
+        (Some(name), _) => name.to_string(),
+        (None, false) => default_name,
+        (None, true) => match Path::new(&default_name).extension() {
+            Some(ext) => format!("{{{{name}}}}.{}", ext.to_string_lossy()),
+            None => "{{name}}".to_string(),
+        },
+    };
+    let output_dir = output.map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from("./template"));
+
+    let mut rows = part_rows(data_dir)?;
+    rows.extend(family_rows(data_dir)?);
+    let files = render_files(&template, &rows, &mapping, per_part, &file_name)?;
+    component::atomic::create_dir_all(&output_dir)?;
+    for (name, content) in &files {
+        let path = output_dir.join(name);
+        component::atomic::write(&path, content)?;
+    }
+
+    println!();
+    if per_part {
+        println!("Rendered {} parts into {} files in: {}", rows.len(), files.len(), output_dir.display());
+    } else {
+        println!("Rendered {} parts into: {}", rows.len(), output_dir.join(&file_name).display());
+    }
+    super::export::write_usage_notes(data_dir, &output_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_mapped_fields_per_library_and_per_part() {
+        let row = |value: &str| PartRow {
+            name: format!("R0603_{}", value),
+            prefix: "R".into(),
+            package: "0603".into(),
+            comment: value.into(),
+            footprint: "Atlantix_Resistors:R_0603_1608Metric".into(),
+            part: None,
+        };
+        let rows = [row("10.0K"), row("4.99K")];
+        let mapping = [("Decal".to_string(), "footprint".to_string())].into_iter().collect();
+
+        let template = "{{#each parts}}{{name}},{{value}},{{Decal}}\n{{/each}}";
+        let files = render_files(template, &rows, &mapping, false, "parts.csv").unwrap();
+        assert_eq!(
+            files,
+            [(
+                "parts.csv".to_string(),
+                "R0603_10.0K,10.0K,Atlantix_Resistors:R_0603_1608Metric\nR0603_4.99K,4.99K,Atlantix_Resistors:R_0603_1608Metric\n".to_string()
+            )]
+        );
+
+        let files = render_files("*PART* {{name}} {{Decal}}", &rows, &mapping, true, "{{name}}.p").unwrap();
+        assert_eq!(files[1], ("R0603_4.99K.p".to_string(), "*PART* R0603_4.99K Atlantix_Resistors:R_0603_1608Metric".to_string()));
+        // Typos in field names fail instead of rendering empty
+        assert!(render_files("{{Decall}}", &rows, &mapping, true, "{{name}}").is_err());
+    }
+}
@@ -0,0 +1,161 @@
+//! Thermal ratings from config.toml and the power budget report
+//!
+//! Resistor symbols carry the thermal resistance and maximum temperature of
+//! their package, by default those of the standard derating curve (full
+//! power up to 70 °C, none at 155 °C). A `[thermal]` table replaces them per
+//! package, or per package and MPN prefix for a series; a series entry may
+//! set just one rating and keeps the package's other one:
+//!
+//! ```toml
+//! [thermal]
+//! "0603" = { resistance = 600, max_temperature = 155 }
+//! "0603/TNPW" = { max_temperature = 125 }
+//! ```
+//!
+//! `aeda report thermal --ambient 85` lists every resistor library with the
+//! power its parts may dissipate at that ambient, and with `--dissipation`
+//! the temperature they reach and whether that is within their rating.
+
+use component::settings::Settings;
+use component::thermal::{self, Thermal};
+use component::Power;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+fn rating(key: &str, name: &str, value: Option<&toml::Value>) -> Result<Option<f64>, String> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let number = value.as_float().or_else(|| value.as_integer().map(|n| n as f64));
+    match number {
+        Some(number) if number > 0.0 => Ok(Some(number)),
+        _ => Err(format!("'{}' of [thermal] \"{}\" must be a positive number", name, key)),
+    }
+}
+
+/// Ratings of one entry, those it leaves out taken from `base`
+fn entry(key: &str, entry: &toml::Value, base: Option<Thermal>) -> Result<Thermal, String> {
+    let entry = entry
+        .as_table()
+        .ok_or_else(|| format!("[thermal] \"{}\" must be a table of resistance and max_temperature", key))?;
+    if let Some(unknown) = entry.keys().find(|name| !["resistance", "max_temperature"].contains(&name.as_str())) {
+        return Err(format!("Unknown rating '{}' of [thermal] \"{}\", expected resistance or max_temperature", unknown, key));
+    }
+    let resistance = rating(key, "resistance", entry.get("resistance"))?.or(base.map(|t| t.resistance));
+    let max_temperature = rating(key, "max_temperature", entry.get("max_temperature"))?.or(base.map(|t| t.max_temperature));
+    match (resistance, max_temperature) {
+        (Some(resistance), Some(max_temperature)) => Ok(Thermal { resistance, max_temperature }),
+        _ => Err(format!("[thermal] \"{}\" needs resistance and max_temperature, it is no standard package", key)),
+    }
+}
+
+/// Ratings of a `[thermal]` table, by package or `package/MPN prefix`
+fn parse(section: &toml::Value) -> Result<HashMap<String, Thermal>, String> {
+    let section = section.as_table().ok_or("[thermal] must be a table")?;
+    let mut ratings = HashMap::new();
+    // Packages first, series complete from them
+    for (key, value) in section.iter().filter(|(key, _)| !key.contains('/')) {
+        ratings.insert(key.clone(), entry(key, value, thermal::package_thermal(key))?);
+    }
+    for (key, value) in section.iter().filter(|(key, _)| key.contains('/')) {
+        let package = key.split('/').next().unwrap_or_default();
+        let base = ratings.get(package).copied().or_else(|| thermal::package_thermal(package));
+        ratings.insert(key.clone(), entry(key, value, base)?);
+    }
+    Ok(ratings)
+}
+
+/// Take the `[thermal]` table of config.toml, if any, into `settings`
+pub fn install(data_dir: &Path, settings: &mut Settings) -> Result<(), String> {
+    let config_path = data_dir.join("config.toml");
+    let Ok(content) = fs::read_to_string(&config_path) else {
+        return Ok(());
+    };
+    let config: toml::Table = content
+        .parse()
+        .map_err(|e| format!("Failed to parse {}: {}", config_path.display(), e))?;
+    let Some(section) = config.get("thermal") else {
+        return Ok(());
+    };
+    let ratings = parse(section).map_err(|e| format!("{} in {}", e, config_path.display()))?;
+    settings.thermal = Some(ratings);
+    Ok(())
+}
+
+/// Power budget of every resistor library at `ambient` °C
+pub fn report(data_dir: &Path, ambient: f64, dissipation: Option<&str>) -> Result<(), String> {
+    let dissipation = dissipation.map(str::parse::<Power>).transpose()?;
+    let rows = super::parts_db::rows(data_dir)?;
+    // One line per library: its parts share package, rating and series
+    let mut libraries = BTreeMap::new();
+    for row in rows.iter().filter(|row| row.library.starts_with("resistor::")) {
+        libraries.entry(row.library.as_str()).or_insert(row);
+    }
+    if libraries.is_empty() {
+        println!("No resistor libraries.");
+        return Ok(());
+    }
+
+    println!("Power budget at {} °C ambient:\n", ambient);
+    let mut over = 0;
+    for (library, row) in libraries {
+        let name = library.trim_start_matches("resistor::");
+        let (Some(ratings), Ok(rated)) = (thermal::thermal(&row.package, &row.mpn), row.power.parse::<Power>()) else {
+            println!("  {:<20} {:<6} no thermal ratings", name, row.package);
+            continue;
+        };
+        let derated = ratings.derated_power(rated, ambient);
+        let mut line = format!(
+            "  {:<20} {:<6} {:>4} mW rated, {:>4} mW allowed  {} °C/W, max {} °C",
+            name,
+            row.package,
+            rated.milliwatts(),
+            derated.milliwatts(),
+            ratings.resistance,
+            ratings.max_temperature
+        );
+        if let Some(dissipation) = dissipation {
+            let within = dissipation <= derated;
+            if !within {
+                over += 1;
+            }
+            line.push_str(&format!(
+                "  -> {:.0} °C {}",
+                ratings.hot_spot(dissipation, ambient),
+                if within { "ok" } else { "OVER" }
+            ));
+        }
+        println!("{}", line);
+    }
+    if let Some(dissipation) = dissipation {
+        println!("\n{} libraries cannot dissipate {} at {} °C", over, dissipation, ambient);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn series_entries_complete_from_their_package() {
+        let config: toml::Table = r#"
+            [thermal]
+            "0603" = { resistance = 600 }
+            "0603/TNPW" = { max_temperature = 125 }
+            "0402/RG" = { resistance = 1200 }
+        "#
+        .parse()
+        .unwrap();
+        let ratings = parse(&config["thermal"]).unwrap();
+        assert_eq!(ratings["0603"], Thermal { resistance: 600.0, max_temperature: 155.0 });
+        assert_eq!(ratings["0603/TNPW"], Thermal { resistance: 600.0, max_temperature: 125.0 });
+        assert_eq!(ratings["0402/RG"].max_temperature, 155.0);
+
+        let unknown: toml::Table = "[thermal]\n\"9999\" = { resistance = 100 }".parse().unwrap();
+        assert!(parse(&unknown["thermal"]).unwrap_err().contains("9999"));
+        let typo: toml::Table = "[thermal]\n\"0603\" = { max_temp = 100 }".parse().unwrap();
+        assert!(parse(&typo["thermal"]).unwrap_err().contains("max_temp"));
+    }
+}
@@ -0,0 +1,89 @@
+//! Validate the library files of the data directory
+//!
+//! `aeda validate` reads every library of the manifest, or just the named
+//! ones, and checks each against its JSON Schema (see [`super::schema`]);
+//! categories without a schema only have to be valid JSON. It fails when any
+//! library does not pass, for use as a CI gate.
+
+use super::generate::load_manifest;
+use super::output;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Serialize)]
+struct Checked {
+    library: String,
+    path: PathBuf,
+    valid: bool,
+    error: Option<String>,
+}
+
+fn check(category: &str, path: &Path) -> Result<(), String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str::<serde_json::Value>(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    super::schema::validate(category, &path.display().to_string(), &content)
+}
+
+pub fn run(data_dir: &Path, libraries: &[String]) -> Result<(), String> {
+    let manifest = load_manifest(data_dir)?;
+    let mut all: Vec<(String, &str, &String)> = manifest
+        .libraries
+        .iter()
+        .flat_map(|(category, items)| items.iter().map(move |(name, path)| (format!("{}::{}", category, name), category.as_str(), path)))
+        .collect();
+    all.sort();
+    if let Some(missing) = libraries.iter().find(|library| !all.iter().any(|(name, _, _)| name == *library)) {
+        return Err(format!("Library not found in manifest: {}", missing));
+    }
+
+    let checked: Vec<Checked> = all
+        .into_iter()
+        .filter(|(library, _, _)| libraries.is_empty() || libraries.contains(library))
+        .map(|(library, category, path)| {
+            let path = data_dir.join("libraries").join(path);
+            let error = check(category, &path).err();
+            Checked { library, path, valid: error.is_none(), error }
+        })
+        .collect();
+    let invalid = checked.iter().filter(|checked| !checked.valid).count();
+
+    if output::json() {
+        output::print(&serde_json::json!({ "count": checked.len(), "invalid": invalid, "libraries": checked }))?;
+    } else {
+        for checked in &checked {
+            match &checked.error {
+                None => println!("  OK      {}", checked.library),
+                Some(error) => println!("  INVALID {}\n          {}", checked.library, error),
+            }
+        }
+        println!("\n{} libraries checked, {} invalid", checked.len(), invalid);
+    }
+    if invalid > 0 {
+        return Err(format!("{} of {} libraries are invalid", invalid, checked.len()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn broken_libraries_name_their_fault() {
+        let dir = std::env::temp_dir().join(format!("aeda-validate-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("X7R_0603.json");
+
+        fs::write(&path, r#"{"name": "X7R_0603", "values": ["100nF"]}"#).unwrap();
+        assert!(check("capacitor", &path).unwrap_err().contains("capacitor library schema"));
+        fs::write(&path, "{").unwrap();
+        assert!(check("capacitor", &path).unwrap_err().starts_with("Failed to parse"));
+        // Families have no schema, any valid JSON passes
+        assert!(check("ferrite", &path).is_err());
+        fs::write(&path, "{}").unwrap();
+        assert!(check("ferrite", &path).is_ok());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
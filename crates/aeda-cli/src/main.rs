@@ -4,7 +4,9 @@
 
 mod commands;
 
-use clap::{Parser, Subcommand};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand};
+use component::settings::Settings;
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -17,6 +19,64 @@ struct Cli {
     #[arg(long, global = true)]
     data_dir: Option<PathBuf>,
 
+    /// Flush every written file to disk before continuing (slower, survives power loss)
+    #[arg(long, global = true)]
+    fsync: bool,
+
+    /// List what generate, export or migrate would write without writing anything
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Let generate and export replace files with other content without asking
+    #[arg(long, global = true)]
+    force: bool,
+
+    /// Stream everything generate or export writes into this ZIP archive
+    /// instead of the directory tree, e.g. for CI artifacts
+    #[arg(long, global = true)]
+    zip: Option<PathBuf>,
+
+    /// With --zip, write one archive per output directory (libraries,
+    /// symbols, footprints) next to the --zip file instead of a combined one
+    #[arg(long, global = true, requires = "zip")]
+    zip_per_format: bool,
+
+    /// KiCad release whose symbol format is written: 6 (default), 7 or 8
+    #[arg(long, global = true)]
+    kicad_version: Option<String>,
+
+    /// Value notation in part names and values: decimal (4.70K, default) or european (4k70)
+    #[arg(long, global = true)]
+    value_notation: Option<String>,
+
+    /// IPC-7351B density level of generated footprints: least, nominal (default) or most
+    #[arg(long, global = true)]
+    density: Option<String>,
+
+    /// Footprint file grammar: legacy (module, KiCad 5, default) or modern
+    /// (footprint with uuids, in the grammar of --kicad-version)
+    #[arg(long, global = true)]
+    footprint_format: Option<String>,
+
+    /// Package code leading in names and descriptions: imperial (0603, default),
+    /// metric (1608) or jis (metric with body sizes); either code is accepted
+    /// where packages are given
+    #[arg(long, global = true)]
+    package_naming: Option<String>,
+
+    /// Order of the parts in generated libraries and CSVs: value (default), name or generation
+    #[arg(long, global = true)]
+    sort: Option<String>,
+
+    /// Output profile from the [profiles] section of config.toml
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    /// Print JSON to stdout for scripts (list, info, config, capabilities,
+    /// generate, validate, search, diff, sync); progress goes to stderr
+    #[arg(long, global = true)]
+    json: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -28,18 +88,57 @@ enum Commands {
         /// Component type to list (resistors, capacitors, etc.)
         #[arg(default_value = "all")]
         component_type: String,
+
+        /// Only list libraries with this review status (draft, approved, deprecated)
+        #[arg(long)]
+        status: Option<String>,
+
+        /// Only list libraries rated at least this power (e.g. 1/4W, 500mW)
+        #[arg(long)]
+        min_power: Option<String>,
     },
 
     /// Generate component libraries
     Generate {
         #[command(subcommand)]
         what: GenerateCommands,
+
+        /// Footprints the KiCad symbols reference: "atlantix" generates them
+        /// into footprints/, "kicad" uses the stock KiCad libraries
+        /// (Inductor_SMD, LED_SMD, Diode_SMD, Resistor_SMD) and generates none
+        #[arg(long, global = true, default_value = "atlantix")]
+        footprint_naming: String,
+
+        /// KiCad symbol library file name without extension, with {name},
+        /// {package}, {category}, {date} and {time} placeholders, e.g.
+        /// "{date}/{name}" (default: the library name)
+        #[arg(long, global = true)]
+        file_name: Option<String>,
+
+        /// Distributors of the supplier part numbers, written as Supplier 1,
+        /// 2, ... in that order: digikey, mouser or arrow, comma-separated.
+        /// Replaces distributors.suppliers of config.toml
+        #[arg(long, global = true, value_delimiter = ',')]
+        distributor: Vec<String>,
     },
 
     /// Export libraries to different formats
     Export {
+        /// Format to export, taken from the profile's target when omitted
+        #[command(subcommand)]
+        format: Option<ExportCommands>,
+    },
+
+    /// Import external data into the data directory
+    Import {
+        #[command(subcommand)]
+        what: ImportCommands,
+    },
+
+    /// Identify a part from its marking
+    Identify {
         #[command(subcommand)]
-        format: ExportCommands,
+        what: IdentifyCommands,
     },
 
     /// Show information about a specific library
@@ -48,12 +147,200 @@ enum Commands {
         library: String,
     },
 
+    /// Search generated parts by value range, package, tolerance, power or MPN
+    Search {
+        /// Component type to search (resistors, capacitors, a family or all)
+        #[arg(default_value = "all")]
+        component_type: String,
+
+        /// Lowest value, e.g. 1k for resistors or 10nF for capacitors
+        #[arg(long)]
+        min: Option<String>,
+
+        /// Highest value, e.g. 10k or 1uF
+        #[arg(long)]
+        max: Option<String>,
+
+        /// Package, e.g. 0603
+        #[arg(long)]
+        package: Option<String>,
+
+        /// Tolerance, e.g. 1 or 0.1%
+        #[arg(long)]
+        tolerance: Option<String>,
+
+        /// Only parts rated at least this power (e.g. 1/4W, 500mW)
+        #[arg(long)]
+        min_power: Option<String>,
+
+        /// Part of a primary or alternate MPN, case-insensitive
+        #[arg(long)]
+        mpn: Option<String>,
+
+        /// Select these parts by name (comma-separated, e.g. R0603_4.99K,R0603_10.0K)
+        #[arg(long, value_delimiter = ',')]
+        parts: Vec<String>,
+
+        /// Output format: table, csv, markdown or json (json with --json)
+        #[arg(long, default_value = "table")]
+        format: String,
+
+        /// Write the parts as a mini-library (manifest and library JSONs) to this directory
+        #[arg(long)]
+        library: Option<PathBuf>,
+    },
+
+    /// Compare two library generations: symbol libraries, directories of
+    /// them, or data directories
+    Diff {
+        /// Library in use (.kicad_sym, directory or data directory)
+        old: PathBuf,
+
+        /// Regenerated library to review
+        new: PathBuf,
+    },
+
+    /// Request a single part that is missing from the libraries
+    Request {
+        #[command(subcommand)]
+        action: RequestCommands,
+    },
+
+    /// Approve a pending part request and promote it into the libraries
+    Approve {
+        /// Requested part or existing library (e.g., resistor::R0201_3.32K)
+        part: String,
+
+        /// Reviewer recorded in the manifest (defaults to $USER)
+        #[arg(long)]
+        reviewer: Option<String>,
+    },
+
+    /// Set the review status of a library
+    Status {
+        /// Library path (e.g., resistor::E96_0603)
+        library: String,
+
+        /// New status: draft, approved or deprecated
+        status: String,
+
+        /// Reviewer recorded in the manifest (defaults to $USER)
+        #[arg(long)]
+        reviewer: Option<String>,
+    },
+
+    /// Pull offers, datasheets and lifecycle status from Octopart (Nexar)
+    /// into a library JSON and optionally its KiCad symbols. Needs
+    /// NEXAR_CLIENT_ID and NEXAR_CLIENT_SECRET unless results are cached.
+    Enrich {
+        /// Library path (e.g., resistor::E96_0603)
+        library: String,
+
+        /// KiCad symbol libraries to update (Datasheet and Lifecycle properties)
+        #[arg(long, value_delimiter = ',')]
+        symbols: Vec<PathBuf>,
+
+        /// Re-query cached results older than this many days
+        #[arg(long, default_value = "30")]
+        max_age_days: i64,
+    },
+
+    /// Check the generated libraries against KiCad's sym-lib-table and
+    /// fp-lib-table for nickname collisions before registering them
+    CheckLibTables {
+        /// KiCad configuration directory holding the tables (defaults to the
+        /// newest version in the user's KiCad settings)
+        #[arg(long)]
+        kicad_config: Option<PathBuf>,
+
+        /// How to handle a nickname used by another library: abort, rename or merge
+        #[arg(long, default_value = "abort")]
+        on_collision: String,
+    },
+
+    /// Register the generated libraries in KiCad's sym-lib-table and
+    /// fp-lib-table, keeping a .bak of each table it changes
+    RegisterLibTables {
+        /// KiCad configuration directory holding the tables (defaults to the
+        /// newest version in the user's KiCad settings)
+        #[arg(long)]
+        kicad_config: Option<PathBuf>,
+
+        /// Copy the libraries into this project's directory and register them
+        /// in its project-local tables instead of the global ones
+        #[arg(long, value_name = "PROJECT.kicad_pro", conflicts_with = "kicad_config")]
+        kicad_project: Option<PathBuf>,
+
+        /// How to handle a nickname used by another library: abort, rename or merge
+        #[arg(long, default_value = "abort")]
+        on_collision: String,
+    },
+
+    /// Repair hand-edited KiCad symbol libraries: upgrade the format version,
+    /// remove duplicate symbols and properties, put the mandatory properties
+    /// first, normalize property ids and sort symbols. Keeps a .bak copy.
+    FixLibrary {
+        /// .kicad_sym files to fix in place
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+
+    /// Upgrade outputs of older releases to the current conventions, in
+    /// place, listing anything that could not be mapped
+    Migrate {
+        #[command(subcommand)]
+        from: MigrateCommands,
+    },
+
+    /// Remove a stale edit lock left behind on a library category
+    Unlock {
+        /// Category to unlock (e.g., resistor)
+        category: String,
+    },
+
     /// Initialize the data directory structure
     Init,
 
     /// Show current configuration and paths
     Config,
 
+    /// List the supported packages, E-series, manufacturers, exporters and
+    /// KiCad versions
+    Capabilities,
+
+    /// Check the library files against their JSON Schema, failing when
+    /// any does not match
+    Validate {
+        /// Libraries to check (e.g., resistor::E96_0603), default all
+        libraries: Vec<String>,
+    },
+
+    /// Answer library and part queries over TCP from an in-memory cache,
+    /// one JSON request per line, for tools like Stencil
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:7411")]
+        listen: String,
+    },
+
+    /// Library health overview: libraries and their review status, last
+    /// generation, distributor data freshness and pending requests
+    Dashboard,
+
+    /// Reports over the generated libraries
+    Report {
+        #[command(subcommand)]
+        what: ReportCommands,
+    },
+
+    /// Print the JSON Schema of a library file, for validating libraries
+    /// outside this tool
+    Schema {
+        /// Library category (resistor or capacitor)
+        #[arg(default_value = "resistor")]
+        category: String,
+    },
+
     /// Verify reference designators are in sync between a .kicad_pcb and the
     /// schematic. Accepts either a .kicad_sch (auto-exports a fresh netlist
     /// via kicad-cli, never touches your project files) or a pre-exported
@@ -65,24 +352,74 @@ enum Commands {
 
         /// Path to either a .kicad_sch (auto-exported) or a .net (used as-is)
         schematic_or_netlist: PathBuf,
-
-        /// Emit JSON instead of a human-readable report
-        #[arg(long)]
-        json: bool,
     },
 }
 
 #[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)]
 enum GenerateCommands {
     /// Generate resistor libraries
     Resistors {
-        /// E-series to generate (e.g., E96, E24, E12)
-        #[arg(short, long, default_value = "E96")]
-        series: String,
+        /// E-series to generate (e.g., E192, E96, E24, E12). Defaults to E192
+        /// for thin film tolerances and E96 otherwise. Several (E24,E96) are
+        /// combined into one library per package, E24_E96_0603, each in its
+        /// own tolerance, named R0603_10.0K_5% and R0603_10.0K_1%
+        #[arg(short, long)]
+        series: Option<String>,
+
+        /// Tolerance overriding the series default, e.g. 0.1 or 0.05 for thin
+        /// film precision parts (Vishay TNPW, Susumu RG)
+        #[arg(long)]
+        tolerance: Option<String>,
+
+        /// Several tolerances in one run (comma-separated: 1%,5%), each in
+        /// its own library, with the tolerance in the part names
+        /// (R0603_10.0K_1%) and its own MPNs
+        #[arg(long, conflicts_with = "tolerance")]
+        tolerances: Option<String>,
 
         /// Packages to generate (comma-separated: 0402,0603,0805,1206)
         #[arg(short, long, default_value = "0603,0805,1206")]
         packages: String,
+
+        /// Skip packages rated below this power (e.g. 1/8W, 250mW, 0.5W)
+        #[arg(long)]
+        min_power: Option<String>,
+
+        /// Manufacturers whose part numbers the library uses (comma-separated:
+        /// vishay, yageo, koa, stackpole, susumu), the first is the primary source
+        #[arg(long, default_value = "vishay")]
+        manufacturer: String,
+
+        /// Only AEC-Q200 qualified parts (Vishay CRCW/TNPW, Yageo AC, KOA
+        /// RK73H), in separate *_AEC libraries
+        #[arg(long)]
+        automotive: bool,
+
+        /// KiCad symbol body: european or american, with per-package
+        /// overrides such as "european,0402=american"
+        #[arg(long, default_value = "european")]
+        symbol_style: String,
+
+        /// Tolerance at the end of part names (R0603_4.99K_1%): auto (only
+        /// with --tolerances), always or never
+        #[arg(long, default_value = "auto")]
+        tolerance_naming: String,
+
+        /// Lowest value to generate (e.g. 10, 4.7k), the full sweep from 1Ω
+        /// otherwise
+        #[arg(long)]
+        min_value: Option<String>,
+
+        /// Highest value to generate (e.g. 1M), below 1MΩ otherwise
+        #[arg(long)]
+        max_value: Option<String>,
+
+        /// Only these values (e.g. 1k,4.7k,10k,100k) or those of a file, one
+        /// per line, into Custom_<package> libraries. Values off the series
+        /// get a warning naming the nearest.
+        #[arg(long)]
+        values: Option<String>,
     },
 
     /// Generate capacitor libraries
@@ -95,34 +432,354 @@ enum GenerateCommands {
         #[arg(short, long, default_value = "0603,0805,1206")]
         packages: String,
     },
+
+    /// Generate inductor libraries
+    Inductors {
+        /// Inductance series (E6 or E12)
+        #[arg(short, long, default_value = "E12")]
+        series: String,
+
+        /// Packages to generate (chip 0603-1210, power 4040,5050,6060)
+        #[arg(short, long, default_value = "0603,0805,1210,4040")]
+        packages: String,
+
+        /// Generate the shielded variant
+        #[arg(long)]
+        shielded: bool,
+
+        /// Validate the generated KiCad files with kicad-cli, if installed
+        #[arg(long)]
+        kicad_check: bool,
+    },
+
+    /// Generate ferrite bead libraries
+    Ferrites {
+        /// Packages to generate (0402, 0603, 0805, 1206)
+        #[arg(short, long, default_value = "0402,0603,0805")]
+        packages: String,
+
+        /// Impedances at 100 MHz in ohms (comma-separated, defaults to the standard set)
+        #[arg(short, long)]
+        impedances: Option<String>,
+
+        /// Validate the generated KiCad files with kicad-cli, if installed
+        #[arg(long)]
+        kicad_check: bool,
+    },
+
+    /// Generate chip LED libraries
+    Leds {
+        /// Packages to generate (0402, 0603, 0805, 1206)
+        #[arg(short, long, default_value = "0603,0805")]
+        packages: String,
+
+        /// Colors to generate (comma-separated: red,orange,yellow,green,blue,white)
+        #[arg(short, long, default_value = "red,orange,yellow,green,blue,white")]
+        colors: String,
+
+        /// Validate the generated KiCad files with kicad-cli, if installed
+        #[arg(long)]
+        kicad_check: bool,
+    },
+
+    /// Generate current-sense (shunt) resistor libraries, 0.5 mOhm to 1 Ohm
+    CurrentSense {
+        /// Packages to generate (0612, 1206, 1225, 2512; 0612/1225 are wide-terminal)
+        #[arg(short, long, default_value = "0612,1225,2512")]
+        packages: String,
+
+        /// Generate the four-terminal Kelvin variants (0612, 1206, 2512)
+        #[arg(long)]
+        kelvin: bool,
+
+        /// Skip packages rated below this power (e.g. 1W, 2000mW)
+        #[arg(long)]
+        min_power: Option<String>,
+
+        /// Validate the generated KiCad files with kicad-cli, if installed
+        #[arg(long)]
+        kicad_check: bool,
+    },
+
+    /// Generate diode libraries (rectifier, Schottky, Zener, TVS)
+    Diodes {
+        /// Diode kinds (comma-separated: rectifier,schottky,zener,tvs)
+        #[arg(short, long, default_value = "rectifier,schottky,zener,tvs")]
+        kinds: String,
+
+        /// Packages to generate (SOD-123, SOD-323, SOD-523, SMA, SMB)
+        #[arg(short, long, default_value = "SOD-123,SOD-323,SMA")]
+        packages: String,
+
+        /// Validate the generated KiCad files with kicad-cli, if installed
+        #[arg(long)]
+        kicad_check: bool,
+    },
+
+    /// Families registered by plugins or described in <data-dir>/families,
+    /// e.g. `aeda generate oscillator --packages 3225`
+    #[command(external_subcommand)]
+    Family(Vec<String>),
+}
+
+#[derive(Subcommand)]
+enum ImportCommands {
+    /// Import an IPC-7351 land pattern export (.csv or .xml) as custom package geometry
+    LandPatterns {
+        /// Land pattern calculator export
+        file: PathBuf,
+    },
+    /// Import an existing KiCad footprint (.kicad_mod) as custom package geometry
+    Footprint {
+        /// Two-terminal .kicad_mod footprint
+        file: PathBuf,
+
+        /// Package name to register it under (default: the size in the
+        /// footprint name, e.g. 0603 for R_0603_1608Metric)
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// Import Digi-Key or Mouser parametric search exports (.csv) as the
+    /// availability matrix; generation then skips values a manufacturer
+    /// does not make
+    Availability {
+        /// Parametric search CSV exports
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ReportCommands {
+    /// Flag dead or redirected SupplierURL and Datasheet links in generated
+    /// symbol libraries (HEAD requests, cached in cache/links.json)
+    Links {
+        /// Symbol libraries to check (default: every .kicad_sym under
+        /// symbols/ and requests/ in the data directory)
+        paths: Vec<PathBuf>,
+
+        /// Re-check cached results older than this many days
+        #[arg(long, default_value = "7")]
+        max_age_days: i64,
+
+        /// Only report cached results, make no requests
+        #[arg(long)]
+        offline: bool,
+    },
+    /// Power each resistor library may dissipate at an ambient temperature,
+    /// from its thermal ratings
+    Thermal {
+        /// Ambient temperature in °C
+        #[arg(long, default_value = "70", allow_negative_numbers = true)]
+        ambient: f64,
+
+        /// Power each part dissipates, e.g. 50mW or 1/10W, to check against
+        /// the derated rating
+        #[arg(long)]
+        dissipation: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum IdentifyCommands {
+    /// Decode a part's top marking and list the library parts with that
+    /// value: resistors (EIA-96 such as 68X, 3- or 4-digit such as 472 or
+    /// 49R9), capacitors (104 = 100nF) or inductors (4R7 = 4.7uH)
+    Marking {
+        /// Marking printed on the part
+        code: String,
+
+        /// Component type (resistor, capacitor, inductor)
+        #[arg(short = 't', long = "type", default_value = "resistor")]
+        component_type: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum RequestCommands {
+    /// Generate a pending single-part stub (symbol, footprint, CSV row, manifest entry)
+    New {
+        /// Component type (currently only resistor)
+        #[arg(short = 't', long = "type", default_value = "resistor")]
+        component_type: String,
+
+        /// Value of the requested part (e.g., 3.32k, 4k99, 100)
+        #[arg(short, long)]
+        value: String,
+
+        /// Package of the requested part (e.g., 0201)
+        #[arg(short, long)]
+        package: String,
+
+        /// E-series the value belongs to, used for the tolerance
+        #[arg(short, long, default_value = "E96")]
+        series: String,
+
+        /// Distributor for the supplier part number: digikey, mouser or arrow.
+        /// Mouser price and stock are shown when MOUSER_API_KEY is set.
+        #[arg(long, default_value = "digikey")]
+        distributor: String,
+
+        /// Manufacturer of the part: vishay, yageo, koa or stackpole. Further
+        /// comma-separated manufacturers are added as alternate MPNs
+        #[arg(long, default_value = "vishay")]
+        manufacturer: String,
+
+        /// KiCad symbol body: european or american
+        #[arg(long, default_value = "european")]
+        symbol_style: String,
+
+        /// Validate the generated KiCad files with kicad-cli, if installed
+        #[arg(long)]
+        kicad_check: bool,
+    },
+
+    /// List part requests awaiting approval
+    List,
+}
+
+#[derive(Subcommand)]
+enum MigrateCommands {
+    /// Altium CSVs, RES-named KiCad symbols and resistor libraries of the
+    /// 0.1 releases
+    #[command(name = "v1-to-v2")]
+    V1ToV2 {
+        /// Files or directories to migrate (default: the data directory)
+        paths: Vec<PathBuf>,
+    },
 }
 
 #[derive(Subcommand)]
 enum ExportCommands {
     /// Export to KiCad format
     Kicad {
-        /// Output directory
+        /// Target library directory, registered in KiCad's library tables
+        /// (defaults to ./kicad_libs, which is not registered)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// KiCad configuration directory holding the tables (defaults to the
+        /// newest version in the user's KiCad settings)
+        #[arg(long)]
+        kicad_config: Option<PathBuf>,
+
+        /// How to handle a nickname used by another library: abort, rename or merge
+        #[arg(long, default_value = "abort")]
+        on_collision: String,
     },
 
     /// Export to Stencil DSL manifest format
     Stencil {
-        /// Output directory (defaults to data/libraries/)
+        /// Output directory (defaults to ./stencil_libs)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Only export libraries with this review status
+        #[arg(long)]
+        status: Option<String>,
     },
 
-    /// Export to Altium format (future)
+    /// Export the Altium CSV libraries
     Altium {
-        /// Output directory
+        /// Output directory (defaults to ./altium_libs)
         #[arg(short, long)]
         output: Option<PathBuf>,
     },
+
+    /// Export EasyEDA JSON symbols and footprints (imports into EasyEDA Pro)
+    Easyeda {
+        /// Output directory (defaults to ./easyeda)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Add the LCSC supplier part fields for JLCPCB assembly
+        #[arg(long)]
+        lcsc: bool,
+    },
+
+    /// Export a JLCPCB assembly parts list with LCSC part numbers
+    Jlcpcb {
+        /// Output directory (defaults to ./jlcpcb)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Only list JLCPCB basic parts, which avoid the extended part setup fee
+        #[arg(long)]
+        basic_only: bool,
+    },
+
+    /// Export a bill of materials of every generated part for purchasing
+    Bom {
+        /// csv, xlsx, or kicad (CSV in the field names of KiCad's BOM export)
+        #[arg(long, default_value = "csv")]
+        format: String,
+
+        /// Output directory (defaults to ./bom)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Render a Handlebars template, for formats without a built-in exporter
+    Template {
+        /// Template file, e.g. pads.txt.hbs
+        template: PathBuf,
+
+        /// TOML file with a [fields] table naming part fields for the template
+        #[arg(long)]
+        mapping: Option<PathBuf>,
+
+        /// Render the template once per part instead of once per library
+        #[arg(long)]
+        per_part: bool,
+
+        /// Output file name, a template itself with --per-part (e.g. "{{name}}.txt")
+        #[arg(long)]
+        file_name: Option<String>,
+
+        /// Output directory (defaults to ./template)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// The export a profile runs when no format is given on the command line
+fn profile_export(profile: &commands::profile::Profile) -> Result<ExportCommands, String> {
+    let output = None;
+    match profile.target.as_deref() {
+        Some("kicad") => Ok(ExportCommands::Kicad { output, kicad_config: None, on_collision: "abort".to_string() }),
+        Some("stencil") => Ok(ExportCommands::Stencil { output, status: None }),
+        Some("altium") => Ok(ExportCommands::Altium { output }),
+        Some("easyeda") => Ok(ExportCommands::Easyeda { output, lcsc: true }),
+        Some("jlcpcb") => Ok(ExportCommands::Jlcpcb { output, basic_only: false }),
+        Some("bom") => Ok(ExportCommands::Bom { format: "csv".to_string(), output }),
+        Some(target) => Err(format!(
+            "Unknown export target '{}' in profile, expected kicad, stencil, altium, easyeda, jlcpcb or bom",
+            target
+        )),
+        None => Err("No export format given. Name one (aeda export kicad) or pass a --profile with a target".to_string()),
+    }
 }
 
 fn main() {
     let cli = Cli::parse();
+    // Resistor, capacitor and family libraries are Stencil JSON, without
+    // KiCad symbols the symbol options would do nothing
+    if let Commands::Generate { what, footprint_naming, file_name, .. } = &cli.command {
+        let writes_symbols =
+            !matches!(what, GenerateCommands::Resistors { .. } | GenerateCommands::Capacitors { .. } | GenerateCommands::Family(_));
+        if !writes_symbols && (footprint_naming != "atlantix" || file_name.is_some()) {
+            Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--footprint-naming and --file-name only apply to generators that write KiCad symbols: \
+                     inductors, ferrites, leds, current-sense and diodes",
+                )
+                .exit();
+        }
+    }
+    let mut settings = Settings { sync: cli.fsync, ..Settings::default() };
+    commands::output::set_json(cli.json);
+    let json = cli.json;
 
     // Determine data directory
     let data_dir = cli.data_dir.unwrap_or_else(|| {
@@ -131,43 +788,380 @@ fn main() {
             .unwrap_or_else(|| PathBuf::from("atlantix-eda"))
     });
 
-    let result = match cli.command {
-        Commands::List { component_type } => {
-            commands::list::run(&data_dir, &component_type)
+    // Command line options override the selected profile
+    let profile_name = cli.profile.clone();
+    let profile = match cli.profile.as_deref().map(|name| commands::profile::load(&data_dir, name)) {
+        Some(Ok(profile)) => profile,
+        Some(Err(e)) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        None => commands::profile::Profile::default(),
+    };
+    let kicad_version = cli.kicad_version.or_else(|| profile.kicad_version.clone());
+    match kicad_version.as_deref().unwrap_or("6").parse() {
+        Ok(format) => settings.symbol_format = format,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+    let value_notation = cli.value_notation.or_else(|| profile.value_notation.clone());
+    match value_notation.as_deref().unwrap_or("decimal").parse() {
+        Ok(notation) => settings.notation = notation,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    let density = cli.density.or_else(|| profile.density.clone());
+    match density.as_deref().unwrap_or("nominal").parse() {
+        Ok(density) => settings.density = density,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    let footprint_format = cli.footprint_format.or_else(|| profile.footprint_format.clone());
+    match footprint_format.as_deref().unwrap_or("legacy").parse() {
+        Ok(format) => settings.footprint_format = format,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
         }
-        Commands::Generate { what } => match what {
-            GenerateCommands::Resistors { series, packages } => {
-                commands::generate::resistors(&data_dir, &series, &packages)
+    }
+
+    let package_naming = cli.package_naming.or_else(|| profile.package_naming.clone());
+    match package_naming.as_deref().unwrap_or("imperial").parse() {
+        Ok(naming) => settings.package_naming = naming,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    let sort = cli.sort.or_else(|| profile.sort.clone());
+    match sort.as_deref().unwrap_or("value").parse() {
+        Ok(order) => settings.part_order = order,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    // Custom land patterns override the built-in package geometry
+    if let Err(e) = commands::import::load_custom_packages(&data_dir, &mut settings) {
+        eprintln!("Warning: {}", e);
+    }
+
+    // Offline mode of air-gapped build machines
+    if let Err(e) = commands::network::install(&data_dir) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    // Component families described in the data directory
+    if let Err(e) = commands::family::install(&data_dir) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    // Mask, paste and thermal rules of the fabrication house
+    if let Err(e) = commands::fabrication::install(&data_dir, &mut settings) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    // Series datasheet links of config.toml
+    if let Err(e) = commands::datasheets::install(&data_dir, &mut settings) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    // Field length limits of config.toml
+    if let Err(e) = commands::limits::install(&data_dir, &mut settings) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    // Part and library name templates of config.toml
+    if let Err(e) = commands::naming::install(&data_dir, &mut settings) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    // Internal part number scheme of config.toml
+    if let Err(e) = commands::ipn::install(&data_dir, &mut settings) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    // Altium Comment column of config.toml
+    if let Err(e) = commands::export::install_altium(&data_dir, &mut settings) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    // Thermal ratings of config.toml
+    if let Err(e) = commands::thermal::install(&data_dir, &mut settings) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    // Values the manufacturers make, from imported parametric searches
+    if let Err(e) = commands::import::install_availability(&data_dir, &mut settings) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    // Product pages from cached distributor data, when config.toml asks for them
+    if let Err(e) = commands::octopart::install_product_urls(&data_dir, &mut settings) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    // Supplier columns of config.toml, or those of generate --distributor
+    if let Err(e) = commands::octopart::install_suppliers(&data_dir, &mut settings) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+    if let Commands::Generate { distributor, .. } = &cli.command {
+        if !distributor.is_empty() {
+            match distributor.iter().map(|name| name.trim().parse()).collect() {
+                Ok(suppliers) => settings.suppliers = suppliers,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+
+    // Primary source per value from cached stock, when config.toml asks for it
+    if let Err(e) = commands::octopart::install_sourcing(&data_dir, &mut settings) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    // Optional per-part script from config.toml
+    if let Err(e) = commands::script::install(&data_dir, &mut settings) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+
+    // Only the commands writing libraries and exports are protected
+    if matches!(cli.command, Commands::Generate { .. } | Commands::Export { .. }) {
+        commands::overwrite::install(&mut settings, cli.dry_run, cli.force);
+    } else if matches!(cli.command, Commands::Migrate { .. }) && !cli.force {
+        // Rewriting files in place is what migrate is for
+        commands::overwrite::install(&mut settings, cli.dry_run, true);
+    } else if cli.dry_run || cli.force {
+        eprintln!("Error: --dry-run only applies to generate, export and migrate, --force to generate and export");
+        std::process::exit(1);
+    }
+    // Every command runs with the settings gathered above
+    let result = settings.scope(|| {
+        if let Some(zip) = &cli.zip {
+            let result = if matches!(cli.command, Commands::Generate { .. } | Commands::Export { .. }) {
+                commands::overwrite::start_archive(zip, &data_dir, cli.zip_per_format)
+            } else {
+                Err("--zip only applies to generate and export".to_string())
+            };
+            if let Err(e) = result {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
             }
-            GenerateCommands::Capacitors { dielectric, packages } => {
-                commands::generate::capacitors(&data_dir, &dielectric, &packages)
+        }
+
+        let result = match cli.command {
+            Commands::List { component_type, status, min_power } => {
+                commands::list::run(&data_dir, &component_type, status.as_deref(), min_power.as_deref())
             }
-        },
-        Commands::Export { format } => match format {
-            ExportCommands::Kicad { output } => {
-                commands::export::to_kicad(&data_dir, output.as_deref())
+            Commands::Generate { what, footprint_naming, file_name, .. } => commands::generate::SymbolOutput::new(&footprint_naming, file_name)
+                .and_then(|symbols| match what {
+                GenerateCommands::Resistors {
+                    series,
+                    tolerance,
+                    tolerances,
+                    packages,
+                    min_power,
+                    manufacturer,
+                    automotive,
+                    symbol_style,
+                    tolerance_naming,
+                    min_value,
+                    max_value,
+                    values,
+                } => {
+                    let options = commands::generate::ResistorOptions {
+                        series,
+                        tolerance,
+                        tolerances,
+                        packages,
+                        min_power,
+                        manufacturer,
+                        automotive,
+                        symbol_style,
+                        tolerance_naming,
+                        min_value,
+                        max_value,
+                        values,
+                    };
+                    commands::generate::resistors(&data_dir, &options)
+                }
+                GenerateCommands::Inductors { series, packages, shielded, kicad_check } => {
+                    commands::generate::inductors(&data_dir, &series, &packages, shielded, kicad_check, &symbols)
+                }
+                GenerateCommands::Ferrites { packages, impedances, kicad_check } => {
+                    commands::generate::ferrites(&data_dir, &packages, impedances.as_deref(), kicad_check, &symbols)
+                }
+                GenerateCommands::Leds { packages, colors, kicad_check } => {
+                    commands::generate::leds(&data_dir, &packages, &colors, kicad_check, &symbols)
+                }
+                GenerateCommands::CurrentSense { packages, kelvin, min_power, kicad_check } => {
+                    commands::generate::current_sense(&data_dir, &packages, kelvin, min_power.as_deref(), kicad_check, &symbols)
+                }
+                GenerateCommands::Diodes { kinds, packages, kicad_check } => {
+                    commands::generate::diodes(&data_dir, &kinds, &packages, kicad_check, &symbols)
+                }
+                GenerateCommands::Capacitors { dielectric, packages } => {
+                    commands::generate::capacitors(&data_dir, &dielectric, &packages)
+                }
+                GenerateCommands::Family(args) => commands::family::generate(&data_dir, &args),
+            })
+            .map(|()| commands::overwrite::report())
+            .and_then(|()| commands::output::finish()),
+            Commands::Export { format } => match format.map_or_else(|| profile_export(&profile), Ok) {
+                Ok(ExportCommands::Kicad { output, kicad_config, on_collision }) => {
+                    commands::export::output_dir(&data_dir, output.or(profile.output), "kicad", profile_name.as_deref()).and_then(|output| {
+                        commands::export::to_kicad(&data_dir, output.as_deref(), kicad_config.as_deref(), &on_collision)
+                    })
+                }
+                Ok(ExportCommands::Stencil { output, status }) => {
+                    commands::export::output_dir(&data_dir, output.or(profile.output), "stencil", profile_name.as_deref())
+                        .and_then(|output| commands::export::to_stencil(&data_dir, output.as_deref(), status.as_deref()))
+                }
+                Ok(ExportCommands::Altium { output }) => {
+                    commands::export::output_dir(&data_dir, output.or(profile.output), "altium", profile_name.as_deref())
+                        .and_then(|output| commands::export::to_altium(&data_dir, output.as_deref()))
+                }
+                Ok(ExportCommands::Easyeda { output, lcsc }) => {
+                    commands::export::output_dir(&data_dir, output.or(profile.output), "easyeda", profile_name.as_deref())
+                        .and_then(|output| commands::export::to_easyeda(&data_dir, output.as_deref(), lcsc))
+                }
+                Ok(ExportCommands::Jlcpcb { output, basic_only }) => {
+                    commands::export::output_dir(&data_dir, output.or(profile.output), "jlcpcb", profile_name.as_deref())
+                        .and_then(|output| commands::export::to_jlcpcb(&data_dir, output.as_deref(), basic_only))
+                }
+                Ok(ExportCommands::Bom { format, output }) => {
+                    commands::export::output_dir(&data_dir, output.or(profile.output), "bom", profile_name.as_deref())
+                        .and_then(|output| commands::bom::export(&data_dir, &format, output.as_deref()))
+                }
+                Ok(ExportCommands::Template { template, mapping, per_part, file_name, output }) => {
+                    commands::export::output_dir(&data_dir, output.or(profile.output), "template", profile_name.as_deref()).and_then(|output| {
+                        commands::template::export(&data_dir, &template, mapping.as_deref(), per_part, file_name.as_deref(), output.as_deref())
+                    })
+                }
+                Err(e) => Err(e),
             }
-            ExportCommands::Stencil { output } => {
-                commands::export::to_stencil(&data_dir, output.as_deref())
+            .map(|()| commands::overwrite::report()),
+            Commands::Import { what } => match what {
+                ImportCommands::LandPatterns { file } => {
+                    commands::import::land_patterns(&data_dir, &file)
+                }
+                ImportCommands::Footprint { file, name } => {
+                    commands::import::footprint(&data_dir, &file, name.as_deref())
+                }
+                ImportCommands::Availability { files } => {
+                    commands::import::availability(&data_dir, &files)
+                }
+            },
+            Commands::Identify { what } => match what {
+                IdentifyCommands::Marking { code, component_type } => {
+                    commands::identify::marking(&data_dir, &code, &component_type)
+                }
+            },
+            Commands::Info { library } => {
+                commands::info::run(&data_dir, &library)
             }
-            ExportCommands::Altium { output } => {
-                commands::export::to_altium(&data_dir, output.as_deref())
+            Commands::Search { component_type, min, max, package, tolerance, min_power, mpn, parts, format, library } => {
+                let query = commands::search::Query { component_type, min, max, package, tolerance, min_power, mpn, parts };
+                let format = if json { "json" } else { format.as_str() };
+                commands::search::run(&data_dir, &query, format, library.as_deref())
             }
-        },
-        Commands::Info { library } => {
-            commands::info::run(&data_dir, &library)
-        }
-        Commands::Init => {
-            commands::init::run(&data_dir)
-        }
-        Commands::Config => {
-            commands::config::run(&data_dir)
-        }
-        Commands::Sync { pcb, schematic_or_netlist, json } => {
-            commands::sync::run(&pcb, &schematic_or_netlist, json)
-        }
-    };
+            Commands::Diff { old, new } => commands::diff::run(&old, &new, json),
+            Commands::Request { action } => match action {
+                RequestCommands::New { component_type, value, package, series, distributor, manufacturer, symbol_style, kicad_check } => {
+                    commands::request::new(&data_dir, &component_type, &value, &package, &series, &distributor, &manufacturer, &symbol_style, kicad_check)
+                }
+                RequestCommands::List => commands::request::list(&data_dir),
+            },
+            Commands::Approve { part, reviewer } => {
+                commands::request::approve(&data_dir, &part, reviewer)
+            }
+            Commands::Status { library, status, reviewer } => {
+                commands::status::run(&data_dir, &library, &status, reviewer)
+            }
+            Commands::Enrich { library, symbols, max_age_days } => {
+                commands::enrich::run(&data_dir, &library, &symbols, max_age_days)
+            }
+            Commands::CheckLibTables { kicad_config, on_collision } => {
+                commands::lib_table::check(&data_dir, kicad_config.as_deref(), &on_collision)
+            }
+            Commands::RegisterLibTables { kicad_config, kicad_project, on_collision } => match kicad_project {
+                Some(project) => commands::lib_table::install_project(&data_dir, &project, &on_collision),
+                None => commands::lib_table::register(&data_dir, kicad_config.as_deref(), &on_collision),
+            },
+            Commands::FixLibrary { paths } => {
+                commands::fix_library::run(&paths)
+            }
+            Commands::Migrate { from } => match from {
+                MigrateCommands::V1ToV2 { paths } => {
+                    commands::migrate::v1_to_v2(&data_dir, &paths).map(|()| commands::overwrite::report())
+                }
+            },
+            Commands::Unlock { category } => {
+                commands::lock::unlock(&data_dir, &category)
+            }
+            Commands::Init => {
+                commands::init::run(&data_dir)
+            }
+            Commands::Config => {
+                commands::config::run(&data_dir)
+            }
+            Commands::Capabilities => {
+                commands::capabilities::run()
+            }
+            Commands::Validate { libraries } => {
+                commands::validate::run(&data_dir, &libraries)
+            }
+            Commands::Serve { listen } => {
+                commands::serve::run(&data_dir, &listen)
+            }
+            Commands::Dashboard => {
+                commands::dashboard::run(&data_dir)
+            }
+            Commands::Schema { category } => {
+                commands::schema::run(&category)
+            }
+            Commands::Report { what } => match what {
+                ReportCommands::Links { paths, max_age_days, offline } => {
+                    commands::report::links(&data_dir, &paths, max_age_days, offline)
+                }
+                ReportCommands::Thermal { ambient, dissipation } => {
+                    commands::thermal::report(&data_dir, ambient, dissipation.as_deref())
+                }
+            },
+            Commands::Sync { pcb, schematic_or_netlist } => {
+                commands::sync::run(&pcb, &schematic_or_netlist, json)
+            }
+        };
 
+        let result = result.and_then(|()| commands::script::check_errors()).and_then(|()| commands::ipn::save(&settings));
+        commands::overwrite::finish_archive(result)
+    });
     if let Err(e) = result {
         eprintln!("Error: {}", e);
         std::process::exit(1);
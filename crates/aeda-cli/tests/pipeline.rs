@@ -0,0 +1,220 @@
+//! End-to-end contract of the library pipeline: an E24 library in two
+//! packages is generated into a fresh data directory, sent through every
+//! exporter, and the outputs are parsed back and counted.
+
+use component::{Resistor, STANDARD_DECADES};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const PACKAGES: [&str; 2] = ["0603", "0805"];
+
+/// Run `aeda --data-dir <data_dir> <args>` and return its stdout
+fn aeda(data_dir: &Path, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_aeda"))
+        .arg("--data-dir")
+        .arg(data_dir)
+        .args(args)
+        .output()
+        .expect("failed to run aeda");
+    assert!(
+        output.status.success(),
+        "aeda {} failed:\n{}{}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn files(dir: &Path, extension: &str) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("{}: {}", dir.display(), e))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == extension))
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn e24_library_through_every_exporter() {
+    let root = std::env::temp_dir().join(format!("atlantix-pipeline-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let data_dir = root.join("data");
+    let out = root.join("out");
+    let parts_per_package = 24 * STANDARD_DECADES.len();
+    let parts = parts_per_package * PACKAGES.len();
+
+    aeda(&data_dir, &["init"]);
+    aeda(&data_dir, &["generate", "resistors", "--series", "E24", "--packages", &PACKAGES.join(",")]);
+
+    // The manifest lists exactly the generated libraries, all as drafts
+    let manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(data_dir.join("libraries/manifest.json")).unwrap()).unwrap();
+    let resistors = manifest["libraries"]["resistor"].as_object().unwrap();
+    assert_eq!(resistors.len(), PACKAGES.len());
+    for package in PACKAGES {
+        let path = resistors[&format!("E24_{}", package)].as_str().unwrap();
+        let library: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(data_dir.join("libraries").join(path)).unwrap()).unwrap();
+        assert_eq!(library["package"], package);
+        assert_eq!(library["base_values"].as_array().unwrap().len(), 24);
+    }
+    let listing = aeda(&data_dir, &["list"]);
+    assert!(listing.contains("resistor::E24_0603") && listing.contains("resistor::E24_0805"), "{}", listing);
+
+    // Regenerating with another manufacturer would replace the library: a
+    // dry run only lists it, and without a terminal to ask on aeda refuses
+    let library = data_dir.join("libraries/resistor/E24_0603.json");
+    let before = fs::read_to_string(&library).unwrap();
+    let regenerate = ["generate", "resistors", "--series", "E24", "--packages", "0603", "--manufacturer", "yageo"];
+    let plan = aeda(&data_dir, &[&regenerate[..], &["--dry-run"]].concat());
+    assert!(plan.contains("replace   ") && plan.contains("E24_0603.json"), "{}", plan);
+    assert_eq!(fs::read_to_string(&library).unwrap(), before);
+    let refused = Command::new(env!("CARGO_BIN_EXE_aeda")).arg("--data-dir").arg(&data_dir).args(regenerate).output().unwrap();
+    assert!(!refused.status.success());
+    assert!(String::from_utf8_lossy(&refused.stderr).contains("pass --force"));
+    assert_eq!(fs::read_to_string(&library).unwrap(), before);
+
+    // --zip streams the same outputs into one archive and leaves the tree
+    // alone; entry names are stored uncompressed in the central directory
+    let zip = out.join("resistors.zip");
+    aeda(&data_dir, &[&regenerate[..], &["--zip", zip.to_str().unwrap()]].concat());
+    let archive = fs::read(&zip).unwrap();
+    assert!(archive.starts_with(b"PK"));
+    let contains = |name: &str| archive.windows(name.len()).any(|window| window == name.as_bytes());
+    assert!(contains("libraries/resistor/E24_0603.json") && contains("libraries/manifest.json"));
+    assert_eq!(fs::read_to_string(&library).unwrap(), before);
+
+    // Stencil: the draft libraries and a manifest naming only them
+    let stencil = out.join("stencil");
+    aeda(&data_dir, &["export", "stencil", "--status", "draft", "--output", stencil.to_str().unwrap()]);
+    let exported: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(stencil.join("manifest.json")).unwrap()).unwrap();
+    for path in exported["libraries"]["resistor"].as_object().unwrap().values() {
+        assert!(stencil.join(path.as_str().unwrap()).exists(), "{}", path);
+    }
+    assert_eq!(exported["libraries"]["resistor"].as_object().unwrap().len(), PACKAGES.len());
+
+    // JLCPCB: one row per part below the header
+    let jlcpcb = out.join("jlcpcb");
+    aeda(&data_dir, &["export", "jlcpcb", "--output", jlcpcb.to_str().unwrap()]);
+    let csv = fs::read_to_string(jlcpcb.join("jlcpcb_parts.csv")).unwrap();
+    assert_eq!(csv.lines().count(), parts + 1);
+    assert!(csv.lines().skip(1).all(|row| row.split(',').count() == 7), "{}", csv);
+
+    // BOM: one row per part below the header, the primary MPN on every row
+    let bom = out.join("bom");
+    aeda(&data_dir, &["export", "bom", "--output", bom.to_str().unwrap()]);
+    let csv = fs::read_to_string(bom.join("bom.csv")).unwrap();
+    assert_eq!(csv.lines().count(), parts + 1);
+    assert!(csv.lines().skip(1).all(|row| row.split(',').nth(9).is_some_and(|mpn| !mpn.is_empty())), "{}", csv);
+    aeda(&data_dir, &["export", "bom", "--format", "xlsx", "--output", bom.to_str().unwrap()]);
+    assert!(fs::read(bom.join("bom.xlsx")).unwrap().starts_with(b"PK"));
+
+    // EasyEDA: one symbol per part and one footprint per package
+    let easyeda = out.join("easyeda");
+    aeda(&data_dir, &["export", "easyeda", "--output", easyeda.to_str().unwrap()]);
+    let symbols = files(&easyeda.join("symbols"), "json");
+    assert_eq!(symbols.len(), parts);
+    for symbol in &symbols {
+        serde_json::from_str::<serde_json::Value>(&fs::read_to_string(symbol).unwrap()).unwrap();
+    }
+    assert_eq!(files(&easyeda.join("footprints"), "json").len(), PACKAGES.len());
+
+    // Template: one rendered line per part
+    let template = root.join("parts.csv.hbs");
+    fs::write(&template, "{{#each parts}}{{name}},{{value}},{{footprint}}\n{{/each}}").unwrap();
+    let rendered = out.join("template");
+    aeda(&data_dir, &["export", "template", template.to_str().unwrap(), "--output", rendered.to_str().unwrap()]);
+    let lines = fs::read_to_string(rendered.join("parts.csv")).unwrap();
+    assert_eq!(lines.lines().count(), parts);
+
+    // KiCad symbols and footprints, parsed back with kiparse
+    let kicad = out.join("kicad");
+    fs::create_dir_all(&kicad).unwrap();
+    let mut referenced = std::collections::BTreeSet::new();
+    for package in PACKAGES {
+        let mut resistor = Resistor::new(24, package.to_string());
+        let symbol_file = kicad.join(format!("Atlantix_R_{}.kicad_sym", package));
+        resistor
+            .generate_kicad_symbols(STANDARD_DECADES.to_vec(), symbol_file.to_str().unwrap(), "european")
+            .unwrap();
+        let content = fs::read_to_string(&symbol_file).unwrap();
+        assert_eq!(kiparse::parse_symbol_lib(&content).unwrap().len(), parts_per_package);
+        let library = kiparse::sexpr::parse_sexpr(&content).unwrap();
+        assert_eq!(library.head(), Some("kicad_symbol_lib"));
+        for symbol in library.find_all("symbol") {
+            let footprint = symbol.find_all("property").find(|p| p.value(1) == Some("Footprint")).unwrap();
+            referenced.insert(footprint.value(2).unwrap().to_string());
+        }
+    }
+    let pretty = kicad.join("Atlantix_Resistors.pretty");
+    Resistor::new(24, "0603".to_string())
+        .generate_kicad_footprints(PACKAGES.to_vec(), pretty.to_str().unwrap())
+        .unwrap();
+    let footprints = files(&pretty, "kicad_mod");
+    assert_eq!(footprints.len(), PACKAGES.len());
+    for footprint in &footprints {
+        let parsed = kiparse::sexpr::parse_sexpr(&fs::read_to_string(footprint).unwrap()).unwrap();
+        assert_eq!(parsed.find_all("pad").count(), 2, "{}", footprint.display());
+    }
+    // Every symbol references a footprint that was written
+    let written: std::collections::BTreeSet<String> = footprints
+        .iter()
+        .map(|path| format!("Atlantix_Resistors:{}", path.file_stem().unwrap().to_string_lossy()))
+        .collect();
+    assert_eq!(referenced, written);
+
+    // Altium: one CSV row per part below the header
+    let altium = out.join("altium");
+    fs::create_dir_all(&altium).unwrap();
+    let resistors = PACKAGES.iter().map(|package| Resistor::new(24, package.to_string())).collect();
+    let csvs = component::altium::write_package_csvs(resistors, &STANDARD_DECADES, &altium).unwrap();
+    assert_eq!(csvs.len(), PACKAGES.len());
+    for csv in csvs {
+        assert_eq!(fs::read_to_string(&csv).unwrap().lines().count(), parts_per_package + 1, "{}", csv.display());
+    }
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn generate_writes_the_distributors_given() {
+    let data_dir = std::env::temp_dir().join(format!("atlantix-pipeline-distributor-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&data_dir);
+    aeda(&data_dir, &["init"]);
+    aeda(&data_dir, &["generate", "current-sense", "--packages", "2512", "--distributor", "mouser,digikey"]);
+
+    let symbols = files(&data_dir.join("symbols"), "kicad_sym");
+    assert_eq!(symbols.len(), 1);
+    let library = fs::read_to_string(&symbols[0]).unwrap();
+    assert!(library.contains(r#"(property "Supplier" "Mouser""#));
+    assert!(library.contains(r#"(property "SupplierPN" "71-WSL2512"#));
+    assert!(library.contains(r#"(property "Supplier 2" "Digikey""#));
+
+    let unknown = Command::new(env!("CARGO_BIN_EXE_aeda"))
+        .arg("--data-dir")
+        .arg(&data_dir)
+        .args(["generate", "leds", "--packages", "0603", "--distributor", "ebay"])
+        .output()
+        .unwrap();
+    assert!(!unknown.status.success());
+    assert!(String::from_utf8_lossy(&unknown.stderr).contains("Unknown distributor 'ebay'"));
+    fs::remove_dir_all(&data_dir).unwrap();
+}
+
+#[test]
+fn generate_refuses_symbol_options_for_json_only_libraries() {
+    let data_dir = std::env::temp_dir().join(format!("atlantix-pipeline-symbol-options-{}", std::process::id()));
+    for args in [
+        ["generate", "resistors", "--file-name", "{package}"],
+        ["generate", "capacitors", "--footprint-naming", "kicad"],
+    ] {
+        let refused = Command::new(env!("CARGO_BIN_EXE_aeda")).arg("--data-dir").arg(&data_dir).args(args).output().unwrap();
+        assert!(!refused.status.success());
+        assert!(String::from_utf8_lossy(&refused.stderr).contains("only apply to generators that write KiCad symbols"));
+    }
+    assert!(!data_dir.exists());
+}
@@ -0,0 +1,283 @@
+//! Altium database library CSV export.
+//!
+//! [`Resistor::generate`] concatenates every row into one String, which for
+//! a full matrix (E192 over the extended range in every package) means
+//! hundreds of megabytes of reallocation. [`write_csv`] instead formats rows
+//! into a reusable, pre-sized buffer and hands it to the writer in chunks,
+//! and [`write_package_csvs`] writes one file per package on its own thread.
+//! Generated fields are cut to the [`crate::limits`] of the `altium`
+//! exporter, 255 characters unless configured otherwise.
+//!
+//! The Comment column follows the Altium convention of `=Value`, so the
+//! schematic comment tracks the part's Value parameter; [`Comment::Literal`]
+//! writes the value itself instead.
+
+use crate::hook::Outcome;
+use crate::limits::Limits;
+use crate::order::PartOrder;
+use crate::{AtlantixError, Resistor};
+use std::fmt;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub const CSV_HEADER: &str = "Part,Description,Value,Case,Power,Supplier 1,Supplier Part Number 1,Library Path,Library Ref,Footprint Path,Footprint Ref,Company,Comment,AEC-Q200,Datasheet\r\n";
+
+/// [`CSV_HEADER`] for rows with `suppliers` distributors, the second and
+/// further ones as trailing `Supplier N`/`Supplier Part Number N` columns.
+///
+/// ```
+/// use component::altium;
+///
+/// assert_eq!(altium::csv_header(1), altium::CSV_HEADER);
+/// assert!(altium::csv_header(3).ends_with(",Datasheet,Supplier 2,Supplier Part Number 2,Supplier 3,Supplier Part Number 3\r\n"));
+/// ```
+pub fn csv_header(suppliers: usize) -> String {
+    let mut header = CSV_HEADER.trim_end().to_string();
+    for number in 2..=suppliers {
+        header.push_str(&format!(",Supplier {0},Supplier Part Number {0}", number));
+    }
+    header + "\r\n"
+}
+
+/// Columns of [`CSV_HEADER`] every part fills the same way
+const LIBRARY_PATH: &str = "Atlantix_R.SchLib";
+const LIBRARY_REF: &str = "Res1";
+const FOOTPRINT_PATH: &str = "Atlantix_R.PcbLib";
+const COMPANY: &str = "Atlantix EDA";
+
+/// What the Comment column holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Comment {
+    /// `=Value`, Altium shows the Value parameter as the comment
+    #[default]
+    ValueParameter,
+    /// The value itself, e.g. `4.70K`, for tools that do not resolve `=`
+    Literal,
+}
+
+impl FromStr for Comment {
+    type Err = AtlantixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "=value" | "parameter" => Ok(Comment::ValueParameter),
+            "value" | "literal" => Ok(Comment::Literal),
+            _ => Err(AtlantixError::Parse(format!("Unknown Altium comment '{}', expected =Value or value", s))),
+        }
+    }
+}
+
+impl fmt::Display for Comment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Comment::ValueParameter => write!(f, "=Value"),
+            Comment::Literal => write!(f, "value"),
+        }
+    }
+}
+
+/// Comment of Altium rows in the current [`crate::settings`]
+///
+/// ```
+/// use component::altium::{self, Comment};
+/// use component::settings::Settings;
+/// use component::Resistor;
+///
+/// let mut resistor = Resistor::new(24, "0603".to_string());
+/// let mut csv = Vec::new();
+/// altium::write_csv(&mut resistor, &[1_000.0], &mut csv).unwrap();
+/// assert!(String::from_utf8(csv).unwrap().lines().nth(1).unwrap().contains(",Atlantix EDA,=Value,"));
+///
+/// let literal = Settings { altium_comment: "value".parse::<Comment>().unwrap(), ..Settings::default() };
+/// let mut csv = Vec::new();
+/// literal.scope(|| altium::write_csv(&mut resistor, &[1_000.0], &mut csv)).unwrap();
+/// assert!(String::from_utf8(csv).unwrap().lines().nth(1).unwrap().contains(",Atlantix EDA,1.00K,"));
+/// ```
+pub fn comment() -> Comment {
+    crate::settings::with(|settings| settings.altium_comment)
+}
+
+/// The fields of one row, written in [`CSV_HEADER`] order by [`Row::push_to`]
+/// for both [`Resistor`] and the ECS pipeline
+pub(crate) struct Row<'a> {
+    pub part: &'a str,
+    pub description: &'a str,
+    pub value: &'a str,
+    pub case: &'a str,
+    pub power: &'a str,
+    pub supplier: &'a str,
+    pub supplier_pn: &'a str,
+    pub footprint_ref: &'a str,
+    pub aec_q200: bool,
+    pub datasheet: &'a str,
+    /// Supplier 2 onwards with their part numbers
+    pub other_suppliers: &'a [(String, String)],
+}
+
+impl<'a> Row<'a> {
+    /// The row with the fields the part hook changed on the part's symbol
+    pub(crate) fn edited(self, outcome: &'a crate::hook::Outcome) -> Row<'a> {
+        Row {
+            part: outcome.changed(|symbol| &symbol.name).unwrap_or(self.part),
+            description: outcome.changed(|symbol| &symbol.description).unwrap_or(self.description),
+            value: outcome.changed(|symbol| &symbol.value).unwrap_or(self.value),
+            supplier: outcome.changed(|symbol| &symbol.supplier).unwrap_or(self.supplier),
+            supplier_pn: outcome.changed(|symbol| &symbol.supplier_pn).unwrap_or(self.supplier_pn),
+            datasheet: outcome.changed(|symbol| &symbol.datasheet).unwrap_or(self.datasheet),
+            ..self
+        }
+    }
+
+    /// Append the row without its line break, generated fields cut to `limits`
+    pub(crate) fn push_to(&self, row: &mut String, limits: &Limits) {
+        use std::fmt::Write;
+        let fit = |column, field| limits.fit("altium", column, self.part, field);
+        let comment = match comment() {
+            Comment::ValueParameter => "=Value".into(),
+            Comment::Literal => fit("Comment", self.value),
+        };
+        // Writing to a String cannot fail
+        let _ = write!(
+            row,
+            "{},\"{}\",{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            fit("Part", self.part),
+            fit("Description", self.description),
+            fit("Value", self.value),
+            self.case,
+            self.power,
+            self.supplier,
+            fit("Supplier Part Number 1", self.supplier_pn),
+            LIBRARY_PATH,
+            LIBRARY_REF,
+            FOOTPRINT_PATH,
+            self.footprint_ref,
+            COMPANY,
+            comment,
+            if self.aec_q200 { "yes" } else { "" },
+            fit("Datasheet", self.datasheet),
+        );
+        for (number, (distributor, supplier_pn)) in (2..).zip(self.other_suppliers) {
+            let column = format!("Supplier Part Number {}", number);
+            let _ = write!(row, ",{},{}", distributor, limits.fit("altium", &column, self.part, supplier_pn));
+        }
+    }
+}
+
+/// Rows formatted before each write
+const CHUNK_ROWS: usize = 512;
+
+/// Room for one row, generous for the longest supplier part numbers
+const ROW_CAPACITY: usize = 192;
+
+/// Stream the header and one row per value of `decades` to `out`.
+///
+/// ```
+/// use component::{altium, Resistor};
+///
+/// let mut csv = Vec::new();
+/// altium::write_csv(&mut Resistor::new(24, "0603".to_string()), &[1_000.0], &mut csv).unwrap();
+/// let csv = String::from_utf8(csv).unwrap();
+/// assert_eq!(csv.lines().count(), 25);
+/// assert!(csv.lines().nth(1).unwrap().starts_with("RES0603_1.00K,"));
+/// ```
+pub fn write_csv<W: Write + ?Sized>(resistor: &mut Resistor, decades: &[f64], out: &mut W) -> io::Result<()> {
+    out.write_all(csv_header(resistor.suppliers().len()).as_bytes())?;
+    let limits = crate::limits::limits("altium");
+    let count = resistor.series_array.len();
+    // Values the manufacturer is known not to make are left out, the
+    // others keep what the part hook made of them for their row
+    let mut values: Vec<(usize, f64, Outcome)> = decades
+        .iter()
+        .flat_map(|&decade| (0..count).map(move |index| (index, decade)))
+        .filter_map(|(index, decade)| {
+            resistor.update_value_for_decade(index, decade);
+            Some((index, decade, resistor.available_outcome()?))
+        })
+        .collect();
+    match crate::order::order() {
+        PartOrder::Value => values.sort_by(|a, b| {
+            let ohms = |&(index, decade, _): &(usize, f64, Outcome)| decade * resistor.series_array[index];
+            ohms(a).total_cmp(&ohms(b))
+        }),
+        // Names are only known once formatted, so the rows are sorted whole
+        PartOrder::Name => return write_sorted_by_name(resistor, values, &limits, out),
+        PartOrder::Generation => {}
+    }
+
+    let mut chunk = String::with_capacity(CHUNK_ROWS * ROW_CAPACITY);
+    let mut rows = 0;
+    for (index, decade, outcome) in values {
+        resistor.update_value_for_decade(index, decade);
+        resistor.set_supplier_pn();
+        resistor.push_csv_row(&mut chunk, &limits, &outcome);
+        rows += 1;
+        if rows == CHUNK_ROWS {
+            out.write_all(chunk.as_bytes())?;
+            chunk.clear();
+            rows = 0;
+        }
+    }
+    out.write_all(chunk.as_bytes())
+}
+
+fn write_sorted_by_name<W: Write + ?Sized>(
+    resistor: &mut Resistor,
+    values: Vec<(usize, f64, Outcome)>,
+    limits: &Limits,
+    out: &mut W,
+) -> io::Result<()> {
+    let mut rows: Vec<String> = values
+        .into_iter()
+        .map(|(index, decade, outcome)| {
+            let mut row = String::with_capacity(ROW_CAPACITY);
+            resistor.update_value_for_decade(index, decade);
+            resistor.set_supplier_pn();
+            resistor.push_csv_row(&mut row, limits, &outcome);
+            row
+        })
+        .collect();
+    rows.sort_by(|a, b| a.split(',').next().cmp(&b.split(',').next()));
+    for row in rows {
+        out.write_all(row.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Write `resistors_<package>.csv` for every resistor into `output_dir`, one
+/// thread per package with the current [`crate::settings`]. Returns the
+/// written paths in the order given.
+pub fn write_package_csvs(resistors: Vec<Resistor>, decades: &[f64], output_dir: &Path) -> Result<Vec<PathBuf>, AtlantixError> {
+    write_package_csvs_named(resistors, decades, output_dir, |package| format!("resistors_{}", package))
+}
+
+/// [`write_package_csvs`] with the file name, without `.csv`, of each package
+/// given by `file_name`
+pub fn write_package_csvs_named(
+    resistors: Vec<Resistor>,
+    decades: &[f64],
+    output_dir: &Path,
+    file_name: impl Fn(&str) -> String + Sync,
+) -> Result<Vec<PathBuf>, AtlantixError> {
+    let file_name = &file_name;
+    let settings = crate::settings::current();
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = resistors
+            .into_iter()
+            .map(|mut resistor| {
+                let settings = settings.clone();
+                scope.spawn(move || {
+                    crate::settings::enter(settings, || {
+                        let path = output_dir.join(format!("{}.csv", file_name(&resistor.case)));
+                        crate::atomic::write_with(&path, |file| write_csv(&mut resistor, decades, file))?;
+                        Ok(path)
+                    })
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+            .collect()
+    })
+}
@@ -0,0 +1,420 @@
+//! Atomic file writes.
+//!
+//! An interrupted `fs::write` leaves a truncated file behind, and KiCad
+//! refuses to open a half-written `.kicad_sym`. [`write`] puts the content in
+//! a temporary file next to the target and renames it over the target, so
+//! readers only ever see the old or the new file. With `sync` set in the
+//! current [`crate::settings`] the data and the directory entry are flushed
+//! to disk before returning, which also survives a power loss at the cost of
+//! slower runs.
+//!
+//! Front ends can protect hand-edited files: with an [`OverwriteGuard`] in
+//! the settings, a write that would replace a file with different content
+//! asks the guard first, and with `dry_run` nothing is written at all.
+//! Either way every write is recorded for [`take_changes`], so the front end
+//! can report what was, or would have been, created and replaced.
+//!
+//! For server and CI runs, [`start_archive`] sends every following write
+//! into a ZIP archive instead of the directory tree. Entries are streamed
+//! into the archive as they are written, which saves the thousands of small
+//! file writes on network filesystems and leaves one artifact to upload.
+
+use crate::settings::Run;
+use crate::AtlantixError;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Decides whether an existing file with different content is replaced:
+/// `Ok(true)` replaces it, `Ok(false)` keeps it and an error fails the write
+///
+/// ```
+/// use component::atomic::{self, Change};
+/// use component::settings::Settings;
+///
+/// let path = std::env::temp_dir().join("atlantix_guard_doc.kicad_sym");
+/// atomic::write(&path, "hand edited").unwrap();
+/// let keep = Settings { overwrite_guard: Some(|_| Ok(false)), ..Settings::default() };
+/// let changes = keep.scope(|| {
+///     atomic::write(&path, "hand edited").unwrap();
+///     atomic::write(&path, "generated").unwrap();
+///     atomic::take_changes()
+/// });
+/// assert_eq!(std::fs::read_to_string(&path).unwrap(), "hand edited");
+/// let changes: Vec<Change> = changes.into_iter().map(|(_, change)| change).collect();
+/// assert_eq!(changes, [Change::Unchanged, Change::Kept]);
+/// ```
+pub type OverwriteGuard = fn(&Path) -> io::Result<bool>;
+
+/// What a write did to its target, or would have done in a dry run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Created,
+    Replaced,
+    /// The target already had the new content
+    Unchanged,
+    /// The guard kept the existing file
+    Kept,
+}
+
+/// Whether the current [`crate::settings`] leave the disk untouched: writes
+/// and [`create_dir_all`] only record what they would have done
+pub fn dry_run() -> bool {
+    crate::settings::with(|settings| settings.dry_run)
+}
+
+fn guard() -> Option<OverwriteGuard> {
+    crate::settings::with(|settings| settings.overwrite_guard)
+}
+
+fn sync() -> bool {
+    crate::settings::with(|settings| settings.sync)
+}
+
+/// Changes are only recorded while someone is going to report them
+fn record(path: &Path, change: Change) {
+    crate::settings::with(|settings| {
+        if settings.dry_run || settings.overwrite_guard.is_some() {
+            settings.run.changes.lock().unwrap_or_else(|e| e.into_inner()).push((path.to_path_buf(), change));
+        }
+    })
+}
+
+/// Writes of the run since the last call, in order, with a dry run or guard active
+pub fn take_changes() -> Vec<(PathBuf, Change)> {
+    crate::settings::with(|settings| std::mem::take(&mut *settings.run.changes.lock().unwrap_or_else(|e| e.into_inner())))
+}
+
+/// `fs::create_dir_all` that leaves the disk untouched in a dry run or
+/// while writing to an archive
+pub fn create_dir_all<P: AsRef<Path>>(path: P) -> Result<(), AtlantixError> {
+    if dry_run() || archiving() {
+        return Ok(());
+    }
+    fs::create_dir_all(&path).map_err(|e| AtlantixError::io("create", path, e))
+}
+
+/// Drop-in replacement for `fs::write` that never leaves a partial file.
+///
+/// ```
+/// let dir = std::env::temp_dir().join("atlantix_atomic_doc");
+/// std::fs::create_dir_all(&dir).unwrap();
+/// let path = dir.join("lib.kicad_sym");
+/// component::atomic::write(&path, "(kicad_symbol_lib)\n").unwrap();
+/// component::atomic::write(&path, "(kicad_symbol_lib (version 20211014))\n").unwrap();
+/// assert_eq!(std::fs::read_to_string(&path).unwrap(), "(kicad_symbol_lib (version 20211014))\n");
+/// assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+/// ```
+pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> Result<(), AtlantixError> {
+    let path = path.as_ref();
+    let contents = contents.as_ref();
+    if dry_run() {
+        let change = match fs::read(path) {
+            Ok(existing) if existing == contents => Change::Unchanged,
+            Ok(_) => Change::Replaced,
+            Err(_) => Change::Created,
+        };
+        record(path, change);
+        return Ok(());
+    }
+    write_with(path, |file| file.write_all(contents))
+}
+
+/// Like [`write`], but never asks the [`OverwriteGuard`], for files the
+/// front end maintains itself such as manifests. Dry runs still skip it.
+pub fn replace<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> Result<(), AtlantixError> {
+    let path = path.as_ref();
+    if dry_run() {
+        record(path, if path.exists() { Change::Replaced } else { Change::Created });
+        return Ok(());
+    }
+    if archiving() {
+        return replace_entry(path, contents.as_ref()).map_err(|e| AtlantixError::io("write", path, e));
+    }
+    commit(path, |file| file.write_all(contents.as_ref()), None).map_err(|e| AtlantixError::io("write", path, e))
+}
+
+/// Like [`write`], but `fill` streams the content into the temporary file,
+/// for outputs too large to build in memory first. The target is left
+/// untouched when `fill` fails. A dry run does not call `fill` and records
+/// any existing target as replaced.
+pub fn write_with<P, F>(path: P, fill: F) -> Result<(), AtlantixError>
+where
+    P: AsRef<Path>,
+    F: FnOnce(&mut dyn Write) -> io::Result<()>,
+{
+    let path = path.as_ref();
+    if dry_run() {
+        record(path, if path.exists() { Change::Replaced } else { Change::Created });
+        return Ok(());
+    }
+    if archiving() {
+        return add_entry(path, fill).map_err(|e| AtlantixError::io("write", path, e));
+    }
+    commit(path, fill, guard()).map_err(|e| AtlantixError::io("write", path, e))
+}
+
+/// Fill a temporary file and rename it over `path`, unless `guard` keeps
+/// an existing target with different content
+fn commit<F>(path: &Path, fill: F, guard: Option<OverwriteGuard>) -> io::Result<()>
+where
+    F: FnOnce(&mut dyn Write) -> io::Result<()>,
+{
+    let temp = temp_path(path)?;
+    let sync = sync();
+
+    let result = (|| {
+        let mut file = fs::File::create(&temp)?;
+        fill(&mut file)?;
+        if sync {
+            file.sync_all()?;
+        }
+        drop(file);
+        let change = match guard {
+            _ if !path.exists() => Change::Created,
+            None => Change::Replaced,
+            Some(_) if same_content(path, &temp)? => Change::Unchanged,
+            Some(guard) if guard(path)? => Change::Replaced,
+            Some(_) => Change::Kept,
+        };
+        if change == Change::Kept {
+            fs::remove_file(&temp)?;
+        } else {
+            fs::rename(&temp, path)?;
+        }
+        Ok(change)
+    })();
+    let change = match result {
+        Ok(change) => change,
+        Err(e) => {
+            let _ = fs::remove_file(&temp);
+            return Err(e);
+        }
+    };
+    record(path, change);
+    if change == Change::Kept {
+        return Ok(());
+    }
+
+    // Persist the rename itself; directories cannot be opened for syncing on Windows
+    #[cfg(unix)]
+    if sync {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::File::open(parent)?.sync_all()?;
+        }
+    }
+    Ok(())
+}
+
+/// The temporary file next to `path` that is renamed over it
+fn temp_path(path: &Path) -> io::Result<PathBuf> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    Ok(path.with_file_name(format!(".{}.{}.tmp", file_name.to_string_lossy(), std::process::id())))
+}
+
+fn same_content(a: &Path, b: &Path) -> io::Result<bool> {
+    if fs::metadata(a)?.len() != fs::metadata(b)?.len() {
+        return Ok(false);
+    }
+    Ok(fs::read(a)? == fs::read(b)?)
+}
+
+/// How [`start_archive`] splits the outputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArchiveLayout {
+    /// Everything in the one archive
+    #[default]
+    Combined,
+    /// One archive per top level directory, `libs-symbols.zip` and
+    /// `libs-footprints.zip` next to `libs.zip`
+    PerFormat,
+}
+
+pub(crate) struct Archive {
+    path: PathBuf,
+    root: PathBuf,
+    layout: ArchiveLayout,
+    /// Archives being written to their temporary file, by target path
+    open: BTreeMap<PathBuf, (PathBuf, ZipWriter<BufWriter<fs::File>>)>,
+    /// Entries written with [`replace`], added last so the last content wins
+    replaced: BTreeMap<(PathBuf, String), Vec<u8>>,
+}
+
+/// The current run, whose archive is shared by every thread writing into it
+fn run() -> Arc<Run> {
+    crate::settings::current().run.clone()
+}
+
+/// Send every following write of the run into the ZIP archive at `path`
+/// instead of the disk, until [`finish_archive`]. Entries are named after the written path
+/// relative to `root`, other paths keep their relative components.
+///
+/// ```
+/// use component::atomic::{self, ArchiveLayout};
+///
+/// let dir = std::env::temp_dir().join("atlantix_archive_doc");
+/// let zip = std::env::temp_dir().join("atlantix_archive_doc.zip");
+/// atomic::start_archive(&zip, &dir, ArchiveLayout::Combined).unwrap();
+/// atomic::create_dir_all(dir.join("symbols")).unwrap();
+/// atomic::write(dir.join("symbols/R_0603.kicad_sym"), "(kicad_symbol_lib)\n").unwrap();
+/// atomic::replace(dir.join("manifest.json"), "{}").unwrap();
+/// assert_eq!(atomic::finish_archive().unwrap(), [zip.clone()]);
+/// assert!(!dir.exists());
+/// assert!(std::fs::metadata(&zip).unwrap().len() > 0);
+/// ```
+pub fn start_archive<P: AsRef<Path>, R: AsRef<Path>>(path: P, root: R, layout: ArchiveLayout) -> Result<(), AtlantixError> {
+    let path = path.as_ref();
+    let mut archive = Archive {
+        path: path.to_path_buf(),
+        root: root.as_ref().to_path_buf(),
+        layout,
+        open: BTreeMap::new(),
+        replaced: BTreeMap::new(),
+    };
+    // A combined archive is written even when nothing else is
+    if layout == ArchiveLayout::Combined {
+        archive.writer(path).map_err(|e| AtlantixError::io("create", path, e))?;
+    }
+    if let Some(previous) = run().archive.lock().unwrap_or_else(|e| e.into_inner()).replace(archive) {
+        previous.discard();
+    }
+    Ok(())
+}
+
+/// Whether writes currently go to an archive
+pub fn archiving() -> bool {
+    crate::settings::with(|settings| settings.run.archive.lock().unwrap_or_else(|e| e.into_inner()).is_some())
+}
+
+/// Complete the archives of [`start_archive`] and move them into place.
+/// Returns their paths, empty when no archive was started.
+pub fn finish_archive() -> Result<Vec<PathBuf>, AtlantixError> {
+    let Some(mut archive) = run().archive.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+        return Ok(Vec::new());
+    };
+    for ((target, name), contents) in std::mem::take(&mut archive.replaced) {
+        let result = archive.writer(&target).and_then(|writer| {
+            writer.start_file(name, entry_options())?;
+            writer.write_all(&contents)
+        });
+        if let Err(e) = result {
+            archive.discard();
+            return Err(AtlantixError::io("write", target, e));
+        }
+    }
+
+    let sync = sync();
+    let mut open = std::mem::take(&mut archive.open).into_iter();
+    let mut finished = Vec::new();
+    while let Some((target, (temp, writer))) = open.next() {
+        let result = (|| {
+            let file = writer.finish()?.into_inner().map_err(|e| e.into_error())?;
+            if sync {
+                file.sync_all()?;
+            }
+            fs::rename(&temp, &target)
+        })();
+        if let Err(e) = result {
+            let _ = fs::remove_file(&temp);
+            for (_, (temp, _)) in open {
+                let _ = fs::remove_file(temp);
+            }
+            return Err(AtlantixError::io("write", target, e));
+        }
+        finished.push(target);
+    }
+    Ok(finished)
+}
+
+/// Drop the archives of [`start_archive`] after a failed run, leaving any
+/// archive from an earlier run in place
+pub fn discard_archive() {
+    if let Some(archive) = run().archive.lock().unwrap_or_else(|e| e.into_inner()).take() {
+        archive.discard();
+    }
+}
+
+impl Archive {
+    /// The archive `path` goes to and its entry name there
+    fn locate(&self, path: &Path) -> (PathBuf, String) {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        let parts: Vec<String> = relative
+            .components()
+            .filter_map(|part| match part {
+                Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect();
+        let target = match (self.layout, parts.as_slice()) {
+            (ArchiveLayout::PerFormat, [directory, _, ..]) => {
+                let stem = self.path.file_stem().unwrap_or_default().to_string_lossy();
+                self.path.with_file_name(format!("{}-{}.zip", stem, directory))
+            }
+            _ => self.path.clone(),
+        };
+        (target, parts.join("/"))
+    }
+
+    fn writer(&mut self, target: &Path) -> io::Result<&mut ZipWriter<BufWriter<fs::File>>> {
+        match self.open.entry(target.to_path_buf()) {
+            Entry::Occupied(open) => Ok(&mut open.into_mut().1),
+            Entry::Vacant(vacant) => {
+                let temp = temp_path(target)?;
+                if let Some(parent) = target.parent().filter(|p| !p.as_os_str().is_empty()) {
+                    fs::create_dir_all(parent)?;
+                }
+                let writer = ZipWriter::new(BufWriter::new(fs::File::create(&temp)?));
+                Ok(&mut vacant.insert((temp, writer)).1)
+            }
+        }
+    }
+
+    fn discard(self) {
+        for (temp, _) in self.open.into_values() {
+            let _ = fs::remove_file(temp);
+        }
+    }
+}
+
+/// Deflated entries with the fixed DOS epoch as timestamp, so the same
+/// outputs give the same archive
+fn entry_options() -> SimpleFileOptions {
+    SimpleFileOptions::default().compression_method(CompressionMethod::Deflated).unix_permissions(0o644)
+}
+
+/// Stream an entry into the running archive. A failing `fill` leaves the
+/// archive unusable, the front end discards it.
+fn add_entry<F>(path: &Path, fill: F) -> io::Result<()>
+where
+    F: FnOnce(&mut dyn Write) -> io::Result<()>,
+{
+    let run = run();
+    let mut lock = run.archive.lock().unwrap_or_else(|e| e.into_inner());
+    let archive = lock.as_mut().ok_or_else(|| io::Error::other("no archive started"))?;
+    let (target, name) = archive.locate(path);
+    let writer = archive.writer(&target)?;
+    writer.start_file(name, entry_options())?;
+    fill(writer)?;
+    drop(lock);
+    record(path, Change::Created);
+    Ok(())
+}
+
+/// Keep the latest content of a [`replace`]d entry until the archive is finished
+fn replace_entry(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let run = run();
+    let mut lock = run.archive.lock().unwrap_or_else(|e| e.into_inner());
+    let archive = lock.as_mut().ok_or_else(|| io::Error::other("no archive started"))?;
+    let located = archive.locate(path);
+    archive.replaced.insert(located, contents.to_vec());
+    drop(lock);
+    record(path, Change::Created);
+    Ok(())
+}
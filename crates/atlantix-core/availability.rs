@@ -0,0 +1,202 @@
+//! Which values, sizes and tolerances each manufacturer actually makes.
+//!
+//! The part number schemes build an MPN for every value of a series, whether
+//! or not the manufacturer stocks it. An [`AvailabilityMatrix`] lists the
+//! combinations that exist, built from Digi-Key or Mouser parametric search
+//! exports with [`parse_parametric_csv`], so it can be maintained without API
+//! access. Once set in the `availability` settings, generation skips values a
+//! manufacturer is known not to make. Combinations of manufacturer, package
+//! and tolerance without imported data are assumed to be made in full.
+
+use crate::{manufacturer, tolerance, AtlantixError, Resistance};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Values made per manufacturer, package and tolerance, e.g.
+/// `{"Vishay": {"0603": {"1%": [...]}}}`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AvailabilityMatrix(BTreeMap<String, BTreeMap<String, BTreeMap<String, BTreeSet<Resistance>>>>);
+
+/// Registered name of a manufacturer, so "Vishay Dale" and "Vishay" share an entry
+fn canonical_manufacturer(name: &str) -> String {
+    manufacturer::lookup(name).map_or_else(|| name.trim().to_string(), |m| m.name().to_string())
+}
+
+impl AvailabilityMatrix {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, manufacturer: &str, package: &str, tolerance: &str, value: Resistance) {
+        self.0
+            .entry(canonical_manufacturer(manufacturer))
+            .or_default()
+            .entry(package.to_string())
+            .or_default()
+            .entry(tolerance.to_string())
+            .or_default()
+            .insert(value);
+    }
+
+    /// Add every combination of `other`
+    pub fn merge(&mut self, other: AvailabilityMatrix) {
+        for (manufacturer, packages) in other.0 {
+            for (package, tolerances) in packages {
+                for (tolerance, values) in tolerances {
+                    for value in values {
+                        self.insert(&manufacturer, &package, &tolerance, value);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whether `manufacturer` makes the part, `None` without data for the
+    /// manufacturer's package and tolerance. An export of one size or
+    /// tolerance says nothing about the others, so those are not pruned.
+    ///
+    /// ```
+    /// use component::availability::AvailabilityMatrix;
+    /// use component::Resistance;
+    ///
+    /// let mut matrix = AvailabilityMatrix::new();
+    /// matrix.insert("Vishay Dale", "0603", "1%", Resistance::from_ohms(4_990.0));
+    /// assert_eq!(matrix.makes("Vishay", "0603", "1%", Resistance::from_ohms(4_990.0)), Some(true));
+    /// assert_eq!(matrix.makes("Vishay", "0603", "1%", Resistance::from_ohms(5_110.0)), Some(false));
+    /// assert_eq!(matrix.makes("Vishay", "0805", "1%", Resistance::from_ohms(4_990.0)), None);
+    /// assert_eq!(matrix.makes("Yageo", "0603", "1%", Resistance::from_ohms(4_990.0)), None);
+    /// ```
+    pub fn makes(&self, manufacturer: &str, package: &str, tolerance: &str, value: Resistance) -> Option<bool> {
+        let values = self.0.get(&canonical_manufacturer(manufacturer))?.get(package)?.get(tolerance)?;
+        Some(values.contains(&value))
+    }
+
+    /// Number of values per manufacturer, package and tolerance
+    pub fn summary(&self) -> Vec<(&str, &str, &str, usize)> {
+        self.0
+            .iter()
+            .flat_map(|(manufacturer, packages)| {
+                packages.iter().flat_map(move |(package, tolerances)| {
+                    tolerances
+                        .iter()
+                        .map(move |(tolerance, values)| (manufacturer.as_str(), package.as_str(), tolerance.as_str(), values.len()))
+                })
+            })
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Whether a part should be generated: made according to the matrix of the
+/// current [`crate::settings`], or not covered by it
+pub fn available(manufacturer: &str, package: &str, tolerance: &str, value: Resistance) -> bool {
+    crate::settings::with(|settings| {
+        settings
+            .availability
+            .as_ref()
+            .and_then(|matrix| matrix.makes(manufacturer, package, tolerance, value))
+            .unwrap_or(true)
+    })
+}
+
+/// Split one CSV line, honouring quoted fields with commas and `""` escapes
+fn csv_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields.into_iter().map(|field| field.trim().to_string()).collect()
+}
+
+/// Column of the first header matching one of `names`, compared without
+/// case, spaces and punctuation
+fn column(header: &[String], names: &[&str]) -> Option<usize> {
+    let normalized: Vec<String> = header
+        .iter()
+        .map(|h| h.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase())
+        .collect();
+    names.iter().find_map(|name| normalized.iter().position(|h| h == name))
+}
+
+/// Imperial size in a package field such as `0603 (1608 Metric)`
+fn package_size(field: &str) -> Option<&str> {
+    field
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .find(|token| token.len() == 4 && token.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Resistance field such as `4.99 kOhms` or `100 mOhms`
+fn resistance(field: &str) -> Option<Resistance> {
+    let value = field.replace(['Ω', ' '], "");
+    let value = value.trim_end_matches("Ohms").trim_end_matches("Ohm").trim_end_matches("ohms");
+    if value.is_empty() {
+        return None;
+    }
+    value.parse().ok()
+}
+
+/// Build a matrix from a Digi-Key or Mouser parametric search CSV export.
+/// Returns the matrix and the number of rows skipped for a missing or
+/// unrecognised package, resistance or tolerance.
+///
+/// ```
+/// use component::availability::parse_parametric_csv;
+/// use component::Resistance;
+///
+/// let digikey = "\"Mfr Part #\",\"Mfr\",\"Resistance\",\"Tolerance\",\"Package / Case\"\n\
+///     \"CRCW06034K99FKEA\",\"Vishay Dale\",\"4.99 kOhms\",\"±1%\",\"0603 (1608 Metric)\"\n\
+///     \"CRCW0603JUMP\",\"Vishay Dale\",\"-\",\"Jumper\",\"0603 (1608 Metric)\"\n";
+/// let (matrix, skipped) = parse_parametric_csv(digikey).unwrap();
+/// assert_eq!(matrix.makes("Vishay", "0603", "1%", Resistance::from_ohms(4_990.0)), Some(true));
+/// assert_eq!(skipped, 1);
+///
+/// let mouser = "Mfr. #,Manufacturer,Resistance,Tolerance,Case Code - in\n\
+///     RC0603FR-0710KL,YAGEO,10 kOhms,1 %,0603\n";
+/// let (matrix, _) = parse_parametric_csv(mouser).unwrap();
+/// assert_eq!(matrix.makes("Yageo", "0603", "1%", Resistance::from_ohms(10_000.0)), Some(true));
+/// ```
+pub fn parse_parametric_csv(content: &str) -> Result<(AvailabilityMatrix, usize), AtlantixError> {
+    let content = content.trim_start_matches('\u{feff}');
+    let mut lines = content.lines().filter(|line| !line.trim().is_empty());
+    let header = csv_fields(lines.next().ok_or_else(|| AtlantixError::Parse("Empty parametric CSV".to_string()))?);
+    let required = |names: &[&str], what: &str| {
+        column(&header, names).ok_or_else(|| AtlantixError::Parse(format!("Parametric CSV has no {} column", what)))
+    };
+    let manufacturer_column = required(&["manufacturer", "mfr"], "manufacturer")?;
+    let resistance_column = required(&["resistance"], "resistance")?;
+    let tolerance_column = required(&["tolerance"], "tolerance")?;
+    let package_column = required(
+        &["supplierdevicepackage", "casecodein", "packagecase", "package", "case"],
+        "package/case",
+    )?;
+
+    let mut matrix = AvailabilityMatrix::new();
+    let mut skipped = 0;
+    for line in lines {
+        let fields = csv_fields(line);
+        let field = |column: usize| fields.get(column).map(String::as_str).unwrap_or_default();
+        let manufacturer = field(manufacturer_column);
+        let package = package_size(field(package_column));
+        let value = resistance(field(resistance_column));
+        let tolerance = tolerance::parse(field(tolerance_column).trim_start_matches('±')).ok();
+        match (manufacturer.is_empty(), package, value, tolerance) {
+            (false, Some(package), Some(value), Some(tolerance)) => matrix.insert(manufacturer, package, tolerance, value),
+            _ => skipped += 1,
+        }
+    }
+    Ok((matrix, skipped))
+}
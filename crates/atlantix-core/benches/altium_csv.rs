@@ -0,0 +1,78 @@
+//! Altium CSV export: String concatenation vs chunked streaming.
+//!
+//! Run with `cargo bench -p atlantix-core --bench altium_csv`. Generates the
+//! full E192 matrix over the extended 1 mOhm to 10 MOhm range for every
+//! package three ways and prints the best of a few runs for each.
+
+use component::{altium, Resistor, EXTENDED_DECADES};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const PACKAGES: [&str; 8] = ["0201", "0402", "0603", "0805", "1206", "1210", "2010", "2512"];
+const SERIES: usize = 192;
+const RUNS: usize = 3;
+
+fn resistor(package: &str) -> Resistor {
+    Resistor::new(SERIES, package.to_string())
+}
+
+/// The original path: every row appended to one String per package
+fn concatenated(output_dir: &Path) {
+    for package in PACKAGES {
+        let mut resistor = resistor(package);
+        let mut csv = String::new();
+        for decade in EXTENDED_DECADES {
+            // generate returns everything accumulated so far
+            csv = resistor.generate(decade);
+        }
+        let path = output_dir.join(format!("resistors_{}.csv", package));
+        component::atomic::write(path, format!("{}{}", altium::CSV_HEADER, csv)).unwrap();
+    }
+}
+
+fn streamed_sequential(output_dir: &Path) {
+    for package in PACKAGES {
+        let mut resistor = resistor(package);
+        let path = output_dir.join(format!("resistors_{}.csv", package));
+        component::atomic::write_with(path, |file| altium::write_csv(&mut resistor, &EXTENDED_DECADES, file)).unwrap();
+    }
+}
+
+fn streamed_parallel(output_dir: &Path) {
+    altium::write_package_csvs(PACKAGES.map(resistor).to_vec(), &EXTENDED_DECADES, output_dir).unwrap();
+}
+
+fn best_of(name: &str, output_dir: &Path, run: fn(&Path)) -> Duration {
+    let best = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            run(output_dir);
+            start.elapsed()
+        })
+        .min()
+        .unwrap();
+    println!("{:<24} {:>10.1} ms", name, best.as_secs_f64() * 1000.0);
+    best
+}
+
+fn main() {
+    let output_dir = std::env::temp_dir().join("atlantix_altium_bench");
+    std::fs::create_dir_all(&output_dir).unwrap();
+    println!(
+        "E{} x {} decades x {} packages = {} rows",
+        SERIES,
+        EXTENDED_DECADES.len(),
+        PACKAGES.len(),
+        SERIES * EXTENDED_DECADES.len() * PACKAGES.len()
+    );
+
+    let baseline = best_of("concatenated", &output_dir, concatenated);
+    let sequential = best_of("streamed", &output_dir, streamed_sequential);
+    let parallel = best_of("streamed, per package", &output_dir, streamed_parallel);
+    println!(
+        "speedup: {:.1}x streamed, {:.1}x streamed per package",
+        baseline.as_secs_f64() / sequential.as_secs_f64(),
+        baseline.as_secs_f64() / parallel.as_secs_f64()
+    );
+    let _ = std::fs::remove_dir_all(&output_dir);
+}
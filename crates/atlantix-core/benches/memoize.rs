@@ -0,0 +1,54 @@
+//! Generation with and without memoized MPN and rating text.
+//!
+//! Run with `cargo bench -p atlantix-core --bench memoize`. Generates the
+//! E192 values of one package with two alternate manufacturers, the way
+//! `gen_resistor --manufacturer vishay,yageo,koa` does, into a single
+//! Altium CSV and into one CSV per distributor. Every iteration starts from
+//! empty caches, as a real run does.
+
+use component::settings::Settings;
+use component::{altium, memo, Distributor, Manufacturer, Resistor, STANDARD_DECADES};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::hint::black_box;
+use std::io;
+
+const PACKAGE: &str = "0603";
+
+fn resistor() -> Resistor {
+    let mut resistor = Resistor::new(192, PACKAGE.to_string());
+    resistor.set_alternate_manufacturers(vec!["yageo".parse::<Manufacturer>().unwrap(), "koa".parse().unwrap()]);
+    resistor
+}
+
+fn one_csv() {
+    memo::clear();
+    altium::write_csv(&mut resistor(), &STANDARD_DECADES, &mut io::sink()).unwrap();
+}
+
+fn csv_per_distributor() {
+    memo::clear();
+    let mut resistor = resistor();
+    for distributor in [Distributor::Digikey, Distributor::Mouser] {
+        resistor.set_distributor(distributor);
+        altium::write_csv(&mut resistor, &STANDARD_DECADES, &mut io::sink()).unwrap();
+    }
+}
+
+fn memoization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("E192");
+    group.sample_size(50);
+    let runs: [(&str, fn()); 2] = [("one csv", one_csv), ("csv per distributor", csv_per_distributor)];
+    for (run_name, run) in runs {
+        for enabled in [false, true] {
+            let name = if enabled { "memoized" } else { "formatted" };
+            let settings = Settings { memoize: enabled, ..Settings::default() };
+            group.bench_function(BenchmarkId::new(name, run_name), |b| {
+                settings.scope(|| b.iter(|| black_box(run)()));
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, memoization);
+criterion_main!(benches);
@@ -0,0 +1,99 @@
+//! What this build can generate, for front ends to offer.
+//!
+//! GUIs, the CLI and third-party front ends fill their package, series,
+//! manufacturer, exporter and KiCad version pickers from [`capabilities`]
+//! instead of hard-coding them, so custom packages of the current
+//! [`crate::settings`] and manufacturers added with
+//! [`crate::manufacturer::register`] show up without changes to the front
+//! end. Everything serializes to JSON for front ends in other languages.
+
+use crate::kicad_footprint::{supported_packages, PackageSpec};
+use crate::kicad_symbol::KicadFormatVersion;
+use crate::{chip_resistor_power, manufacturer, tolerance, Power};
+use serde::Serialize;
+
+/// E-series the generators compute values for
+pub const E_SERIES: [usize; 7] = [3, 6, 12, 24, 48, 96, 192];
+
+/// Chip package with its land pattern
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Package {
+    #[serde(flatten)]
+    pub spec: PackageSpec,
+    /// Rated power of a chip resistor, `None` for packages without one
+    #[serde(rename = "power_mw")]
+    pub power: Option<Power>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Series {
+    /// `E24`
+    pub name: String,
+    /// Values per decade
+    pub values: usize,
+    /// Tolerance the series implies, `5%`
+    pub tolerance: &'static str,
+}
+
+/// Output format the library writes
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Exporter {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KicadVersion {
+    /// KiCad major release, `8`
+    pub major: u32,
+    /// `(version ...)` of the symbol libraries written for it
+    pub format: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Capabilities {
+    pub packages: Vec<Package>,
+    pub series: Vec<Series>,
+    pub manufacturers: Vec<String>,
+    pub exporters: Vec<Exporter>,
+    pub kicad_versions: Vec<KicadVersion>,
+}
+
+const EXPORTERS: [Exporter; 4] = [
+    Exporter { name: "kicad", description: "KiCad symbol libraries and footprints" },
+    Exporter { name: "altium", description: "Altium database library CSV" },
+    Exporter { name: "spice", description: "SPICE subcircuit libraries" },
+    Exporter { name: "3d-models", description: "VRML and STEP package models" },
+];
+
+/// Packages, series, manufacturers, exporters and KiCad versions supported
+/// right now, registered packages and manufacturers included
+///
+/// ```
+/// let capabilities = component::capabilities();
+///
+/// let e24 = capabilities.series.iter().find(|series| series.values == 24).unwrap();
+/// assert_eq!((e24.name.as_str(), e24.tolerance), ("E24", "5%"));
+/// let package = capabilities.packages.iter().find(|package| package.spec.imperial == "0603").unwrap();
+/// assert_eq!(package.spec.metric, "1608Metric");
+/// assert!(capabilities.manufacturers.iter().any(|name| name == "Vishay"));
+/// assert_eq!(capabilities.kicad_versions.last().map(|version| version.major), Some(8));
+/// ```
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        packages: supported_packages()
+            .into_iter()
+            .map(|spec| Package { power: chip_resistor_power(&spec.imperial), spec })
+            .collect(),
+        series: E_SERIES
+            .iter()
+            .map(|&values| Series { name: format!("E{}", values), values, tolerance: tolerance::for_series(values) })
+            .collect(),
+        manufacturers: manufacturer::names(),
+        exporters: EXPORTERS.to_vec(),
+        kicad_versions: KicadFormatVersion::ALL
+            .iter()
+            .map(|version| KicadVersion { major: version.major(), format: version.version() })
+            .collect(),
+    }
+}
@@ -0,0 +1,270 @@
+//! Current-sense (shunt) resistor library generation.
+//!
+//! Values run from 0.5 mOhm to 1 Ohm. Besides the standard 1206 and 2512
+//! chips, the wide-terminal 0612 and 1225 packages (reversed 1206/2512
+//! geometry, terminals on the long sides) are supported for their higher
+//! power rating and lower thermal resistance. Four-terminal Kelvin variants
+//! get separate sense pads and pins.
+//!
+//! Part numbers follow Vishay WSL (two terminal), Vishay WSK (Kelvin) and
+//! Bourns CSS. Power ratings are the family nominals, confirm against the
+//! datasheet before release.
+
+use crate::kicad_footprint::{self, FootprintNaming, KicadFootprint};
+use crate::kicad_symbol::{KicadSymbol, KicadSymbolLib};
+use crate::power::Power;
+use crate::status::PartStatus;
+use crate::AtlantixError;
+
+/// Standard current-sense values in ohms, 0.5 mOhm to 1 Ohm
+pub const CURRENT_SENSE_VALUES: [f64; 30] = [
+    0.0005, 0.001, 0.0015, 0.002, 0.0025, 0.003, 0.004, 0.005, 0.006, 0.008,
+    0.01, 0.012, 0.015, 0.02, 0.025, 0.03, 0.04, 0.05, 0.068, 0.075,
+    0.1, 0.12, 0.15, 0.2, 0.25, 0.3, 0.33, 0.5, 0.68, 1.0,
+];
+
+/// Packages supported by the current-sense generator
+pub const CURRENT_SENSE_PACKAGES: [&str; 4] = ["0612", "1206", "1225", "2512"];
+
+struct ShuntPackage {
+    power: Power,
+    /// Kelvin power rating, `None` when no four-terminal part exists
+    kelvin_power: Option<Power>,
+    bourns_series: Option<&'static str>,
+}
+
+fn package_info(package: &str) -> Option<ShuntPackage> {
+    let (milliwatts, kelvin_milliwatts, bourns_series) = match package {
+        "0612" => (1000, Some(1000), None),
+        "1206" => (500, Some(500), None),
+        "1225" => (2000, None, None),
+        "2512" => (1000, Some(1000), Some("CSS2H-2512R")),
+        _ => return None,
+    };
+    Some(ShuntPackage {
+        power: Power(milliwatts),
+        kelvin_power: kelvin_milliwatts.map(Power),
+        bourns_series,
+    })
+}
+
+/// Vishay WSL/WSK value code: `R` as the decimal point with four digits
+/// below 1 Ohm, `L` as the milliohm decimal point below 1 mOhm.
+///
+/// ```
+/// use component::current_sense::vishay_shunt_code;
+/// assert_eq!(vishay_shunt_code(0.01), "R0100");
+/// assert_eq!(vishay_shunt_code(0.0005), "L5000");
+/// assert_eq!(vishay_shunt_code(1.0), "1R000");
+/// ```
+pub fn vishay_shunt_code(ohms: f64) -> String {
+    if ohms >= 1.0 {
+        format!("{:.3}", ohms).replace('.', "R")
+    } else if ohms >= 0.001 {
+        format!("R{:04}", (ohms * 10_000.0).round() as u32)
+    } else {
+        format!("L{:04}", (ohms * 10_000_000.0).round() as u32)
+    }
+}
+
+/// Bourns CSS value code, three digits with `L` as the milliohm and `R` as
+/// the ohm decimal point.
+///
+/// ```
+/// use component::current_sense::bourns_shunt_code;
+/// assert_eq!(bourns_shunt_code(0.0005), "L500");
+/// assert_eq!(bourns_shunt_code(0.01), "10L0");
+/// assert_eq!(bourns_shunt_code(0.1), "100L");
+/// assert_eq!(bourns_shunt_code(1.0), "1R00");
+/// ```
+pub fn bourns_shunt_code(ohms: f64) -> String {
+    let (scaled, mark) = if ohms >= 1.0 { (ohms, 'R') } else { (ohms * 1000.0, 'L') };
+    let decimals = match scaled {
+        s if s < 1.0 => 3,
+        s if s < 10.0 => 2,
+        s if s < 100.0 => 1,
+        _ => 0,
+    };
+    let code = format!("{:.*}", decimals, scaled);
+    let code = code.trim_start_matches('0');
+    if code.contains('.') {
+        code.replace('.', &mark.to_string())
+    } else {
+        format!("{}{}", code, mark)
+    }
+}
+
+/// One generated current-sense part
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrentSensePart {
+    pub name: String,
+    pub value: String,
+    pub ohms: f64,
+    pub package: String,
+    pub kelvin: bool,
+    pub power: Power,
+    pub vishay_mpn: String,
+    pub bourns_mpn: Option<String>,
+    /// Internal part number, see [`crate::ipn`], given by `parts()` to parts
+    /// the hook keeps
+    pub ipn: Option<String>,
+}
+
+///
+/// Current-sense resistor generator for one package, two terminal or Kelvin.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrentSense {
+    package: String,
+    kelvin: bool,
+    values: Vec<f64>,
+    status: Option<PartStatus>,
+    footprint_naming: FootprintNaming,
+}
+
+impl CurrentSense {
+    /// Returns `None` for unsupported packages and for Kelvin variants of
+    /// packages without a four-terminal part.
+    pub fn new(package: &str, kelvin: bool) -> Option<CurrentSense> {
+        let info = package_info(package)?;
+        if kelvin && info.kelvin_power.is_none() {
+            return None;
+        }
+        Some(CurrentSense {
+            package: package.to_string(),
+            kelvin,
+            values: CURRENT_SENSE_VALUES.to_vec(),
+            status: None,
+            footprint_naming: FootprintNaming::Atlantix,
+        })
+    }
+
+    /// Restricts generation to the given values in ohms
+    pub fn set_values(&mut self, values: Vec<f64>) {
+        self.values = values;
+    }
+
+    /// Stamps every subsequently generated KiCad symbol with a hidden `Status` property.
+    pub fn set_status(&mut self, status: PartStatus) {
+        self.status = Some(status);
+    }
+
+    /// With `FootprintNaming::KicadOfficial` the symbols reference the stock
+    /// KiCad footprints of the package in place of the generated ones.
+    pub fn set_footprint_naming(&mut self, naming: FootprintNaming) {
+        self.footprint_naming = naming;
+    }
+
+    /// One part per value, as the part hook leaves them
+    pub fn parts(&self) -> Vec<CurrentSensePart> {
+        let footprint = self.footprint_name().unwrap_or_default();
+        self.generated_parts()
+            .into_iter()
+            .filter_map(|mut part| {
+                let outcome = crate::hook::outcome(|| self.kicad_symbol(&part, &footprint))?;
+                for (field, changed) in [
+                    (&mut part.name, outcome.changed(|symbol| &symbol.name)),
+                    (&mut part.value, outcome.changed(|symbol| &symbol.value)),
+                    (&mut part.vishay_mpn, outcome.changed(|symbol| &symbol.mpn)),
+                ] {
+                    if let Some(changed) = changed {
+                        *field = changed.to_string();
+                    }
+                }
+                part.ipn = crate::ipn::assign("resistor", &self.package, &part.name, "");
+                Some(part)
+            })
+            .collect()
+    }
+
+    fn generated_parts(&self) -> Vec<CurrentSensePart> {
+        let info = package_info(&self.package).expect("package validated in new()");
+        let (family, power) = if self.kelvin {
+            ("WSK", info.kelvin_power.expect("Kelvin variant validated in new()"))
+        } else {
+            ("WSL", info.power)
+        };
+        self.values
+            .iter()
+            .map(|&ohms| {
+                let value = crate::Resistance::from_ohms(ohms).label();
+                let suffix = if self.kelvin { "K" } else { "" };
+                let name = format!("RS{}{}_{}", crate::package_naming::label(&self.package), suffix, value);
+                CurrentSensePart {
+                    ipn: None,
+                    name,
+                    value,
+                    ohms,
+                    package: self.package.clone(),
+                    kelvin: self.kelvin,
+                    power,
+                    vishay_mpn: format!("{}{}{}FEA", family, self.package, vishay_shunt_code(ohms)),
+                    bourns_mpn: info
+                        .bourns_series
+                        .filter(|_| !self.kelvin)
+                        .map(|series| format!("{}-{}F", series, bourns_shunt_code(ohms))),
+                }
+            })
+            .collect()
+    }
+
+    /// Footprint the symbols reference, `None` without one for the package
+    pub fn footprint_name(&self) -> Option<String> {
+        match self.footprint_naming {
+            FootprintNaming::Atlantix => KicadFootprint::new_smd_current_sense(&self.package, self.kelvin)
+                .map(|fp| format!("Atlantix_Resistors:{}", fp.name)),
+            // The Kelvin land patterns have no stock counterpart
+            FootprintNaming::KicadOfficial if self.kelvin => None,
+            FootprintNaming::KicadOfficial => kicad_footprint::official_footprint("Resistor_SMD", "R", &self.package),
+        }
+    }
+
+    pub fn generate_kicad_symbols(&self, output_path: &str) -> Result<(), AtlantixError> {
+        let mut symbol_lib = KicadSymbolLib::new();
+        let footprint = self.footprint_name().unwrap_or_default();
+
+        for part in self.generated_parts() {
+            if let Some(mut symbol) = crate::hook::apply(self.kicad_symbol(&part, &footprint)) {
+                crate::ipn::assign_symbol("resistor", &self.package, &mut symbol);
+                symbol_lib.add_symbol(symbol);
+            }
+        }
+
+        crate::atomic::write(output_path, symbol_lib.generate_library())
+    }
+
+    /// The KiCad symbol of a generated part, before the part hook
+    fn kicad_symbol(&self, part: &CurrentSensePart, footprint: &str) -> KicadSymbol {
+        let description = format!(
+            "RES SMT CURRENT SENSE {}ohms, {}{}, 1%, {}",
+            part.value,
+            self.package,
+            if self.kelvin { " 4-terminal" } else { "" },
+            part.power
+        );
+        let mut symbol = KicadSymbol::new_current_sense(part.name.clone(), part.value.clone(), footprint.to_string(), self.kelvin)
+            .with_sources("Vishay", &part.vishay_mpn, &part.vishay_mpn);
+        symbol.description = description;
+        symbol.properties.extend([
+            ("Power".to_string(), part.power.to_string()),
+            ("Tolerance".to_string(), "1%".to_string()),
+        ]);
+        if let Some(bourns) = &part.bourns_mpn {
+            symbol.properties.push(("MPN2".to_string(), bourns.clone()));
+            symbol.properties.push(("Manufacturer2".to_string(), "Bourns".to_string()));
+        }
+        symbol.status = self.status;
+        symbol
+    }
+
+    pub fn generate_kicad_footprints(packages: &[&str], kelvin: bool, output_dir: &str) -> Result<(), AtlantixError> {
+        crate::atomic::create_dir_all(output_dir)?;
+        for package in packages.iter().filter(|p| CurrentSense::new(p, kelvin).is_some()) {
+            if let Some(footprint) = KicadFootprint::new_smd_current_sense(package, kelvin) {
+                let filename = format!("{}/{}.kicad_mod", output_dir, footprint.name);
+                crate::atomic::write(filename, footprint.generate_footprint())?;
+            }
+        }
+        Ok(())
+    }
+}
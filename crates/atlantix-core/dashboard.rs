@@ -0,0 +1,185 @@
+//! Library health overview of a data directory.
+//!
+//! [`summarize`] reads the library manifest, the distributor cache and the
+//! edit locks of a data directory once, so `aeda dashboard` and the GUI's
+//! Dashboard tab report the same numbers.
+//!
+//! ```
+//! use component::dashboard;
+//!
+//! let dir = std::env::temp_dir().join("atlantix-dashboard-doctest");
+//! assert!(dashboard::summarize(&dir.join("nowhere")).is_err());
+//! ```
+
+use crate::{AtlantixError, PartStatus};
+use chrono::{DateTime, FixedOffset, Local};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// Cached distributor data older than this is due for a refresh, the same
+/// default `aeda enrich` uses
+pub const STALE_DAYS: i64 = 30;
+
+#[derive(Debug, Default)]
+pub struct CategorySummary {
+    pub libraries: usize,
+    pub by_status: BTreeMap<String, usize>,
+    /// Library JSON files listed in the manifest but missing on disk
+    pub missing: Vec<String>,
+    pub pending: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct Summary {
+    pub name: String,
+    pub version: String,
+    pub categories: BTreeMap<String, CategorySummary>,
+    /// Newest library JSON write
+    pub last_generated: Option<DateTime<Local>>,
+    pub cached_parts: usize,
+    pub stale_parts: usize,
+    pub oldest_fetch: Option<DateTime<FixedOffset>>,
+    /// Categories with an edit lock held
+    pub locked: Vec<String>,
+}
+
+impl Summary {
+    /// Library files the manifest lists that are not on disk, as `category::name`
+    pub fn missing(&self) -> Vec<String> {
+        self.categories.values().flat_map(|entry| entry.missing.iter().cloned()).collect()
+    }
+
+    /// Part requests awaiting approval across every category
+    pub fn pending(&self) -> usize {
+        self.categories.values().map(|entry| entry.pending).sum()
+    }
+
+    /// Libraries still awaiting review
+    pub fn drafts(&self) -> usize {
+        self.categories
+            .values()
+            .filter_map(|entry| entry.by_status.get(&PartStatus::Draft.to_string()))
+            .sum()
+    }
+}
+
+/// The parts of `libraries/manifest.json` the overview reads
+#[derive(Deserialize)]
+struct Manifest {
+    name: String,
+    version: String,
+    libraries: HashMap<String, HashMap<String, String>>,
+    #[serde(default)]
+    pending: HashMap<String, HashMap<String, String>>,
+    #[serde(default)]
+    status: HashMap<String, StatusRecord>,
+}
+
+#[derive(Deserialize)]
+struct StatusRecord {
+    status: PartStatus,
+}
+
+fn modified(path: &Path) -> Option<DateTime<Local>> {
+    fs::metadata(path).and_then(|m| m.modified()).ok().map(Into::into)
+}
+
+pub fn summarize(data_dir: &Path) -> Result<Summary, AtlantixError> {
+    let manifest_path = data_dir.join("libraries/manifest.json");
+    if !manifest_path.exists() {
+        return Err(AtlantixError::Invalid(format!(
+            "Manifest not found at {}. Run 'aeda init' first.",
+            manifest_path.display()
+        )));
+    }
+    let content = fs::read_to_string(&manifest_path).map_err(|e| AtlantixError::io("read", &manifest_path, e))?;
+    let manifest: Manifest =
+        serde_json::from_str(&content).map_err(|e| AtlantixError::Parse(format!("Failed to parse manifest: {}", e)))?;
+    let mut summary = Summary {
+        name: manifest.name.clone(),
+        version: manifest.version.clone(),
+        ..Summary::default()
+    };
+
+    for (category, items) in &manifest.libraries {
+        let entry = summary.categories.entry(category.clone()).or_default();
+        for (name, path) in items {
+            let path = data_dir.join("libraries").join(path);
+            // Libraries without a record are drafts
+            let status = manifest
+                .status
+                .get(&format!("{}::{}", category, name))
+                .map(|record| record.status)
+                .unwrap_or_default();
+            entry.libraries += 1;
+            *entry.by_status.entry(status.to_string()).or_default() += 1;
+            match modified(&path) {
+                Some(time) => summary.last_generated = summary.last_generated.max(Some(time)),
+                None => entry.missing.push(format!("{}::{}", category, name)),
+            }
+        }
+    }
+    for (category, items) in &manifest.pending {
+        summary.categories.entry(category.clone()).or_default().pending += items.len();
+    }
+
+    let now = chrono::Utc::now();
+    let cache = fs::read_dir(data_dir.join("cache/octopart")).into_iter().flatten().flatten();
+    for entry in cache {
+        let Ok(content) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) else {
+            continue;
+        };
+        let Some(fetched) = json["fetched"].as_str().and_then(|f| DateTime::parse_from_rfc3339(f).ok()) else {
+            continue;
+        };
+        summary.cached_parts += 1;
+        if now.signed_duration_since(fetched) > chrono::Duration::days(STALE_DAYS) {
+            summary.stale_parts += 1;
+        }
+        summary.oldest_fetch = Some(summary.oldest_fetch.map_or(fetched, |oldest| oldest.min(fetched)));
+    }
+
+    let locks = fs::read_dir(data_dir.join("locks")).into_iter().flatten().flatten();
+    for entry in locks {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "lock") {
+            summary.locked.extend(path.file_stem().map(|stem| stem.to_string_lossy().into_owned()));
+        }
+    }
+    summary.locked.sort();
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_missing_libraries_and_pending_requests() {
+        let dir = std::env::temp_dir().join(format!("atlantix-dashboard-test-{}", std::process::id()));
+        assert!(summarize(&dir).is_err());
+
+        fs::create_dir_all(dir.join("libraries")).unwrap();
+        let manifest = r#"{
+            "name": "test", "version": "1.0.0", "description": "",
+            "libraries": {"resistor": {"E96_0603": "resistor/E96_0603.json"}},
+            "pending": {"resistor": {"R0201_3.32K": "requests/resistor/R0201_3.32K.json"}},
+            "status": {"resistor::E96_0603": {"status": "approved", "updated": "2024-01-01"}}
+        }"#;
+        fs::write(dir.join("libraries/manifest.json"), manifest).unwrap();
+        let summary = summarize(&dir).unwrap();
+        let resistors = &summary.categories["resistor"];
+        assert_eq!(resistors.libraries, 1);
+        assert_eq!(resistors.by_status["Approved"], 1);
+        assert_eq!(summary.missing(), vec!["resistor::E96_0603".to_string()]);
+        assert_eq!(summary.pending(), 1);
+        assert_eq!(summary.drafts(), 0);
+        assert!(summary.last_generated.is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,84 @@
+//! Datasheet links of generated parts.
+//!
+//! Symbols and CSV rows link the datasheet of the part's series, looked up
+//! by manufacturer and MPN prefix. Templates may contain `{mpn}` for vendors
+//! with one page per part. Front ends override or extend the built-in table
+//! through the `datasheets` settings, keyed by manufacturer (`"Yageo"`) or by
+//! manufacturer and series prefix (`"Yageo/AC"`). Parts without a template
+//! keep KiCad's empty `~`.
+
+use crate::manufacturer;
+use std::collections::HashMap;
+
+/// Series datasheets of the built-in manufacturers
+const BUILT_IN: [(&str, &str); 6] = [
+    ("Vishay/CRCW", "https://www.vishay.com/docs/20035/dcrcwe3.pdf"),
+    ("Vishay/TNPW", "https://www.vishay.com/docs/28758/tnpw_e3.pdf"),
+    ("Yageo/RC", "https://www.yageo.com/upload/media/product/productsearch/datasheet/rchip/PYu-RC_Group_51_RoHS_L_12.pdf"),
+    ("KOA Speer/RK73H", "https://www.koaspeer.com/pdfs/RK73H.pdf"),
+    ("Stackpole/RMCF", "https://www.seielect.com/catalog/sei-rmcf_rmcp.pdf"),
+    ("Susumu/RG", "https://www.susumu.co.jp/common/pdf/n_catalog_partition07_en.pdf"),
+];
+
+/// Templates keyed the way [`url`] looks them up, for the `datasheets` of
+/// [`crate::settings::Settings`], where they come ahead of the built-in
+/// ones. Manufacturers may be given by any of their names, e.g. `KOA` for
+/// KOA Speer.
+pub fn templates(templates: HashMap<String, String>) -> HashMap<String, String> {
+    templates
+        .into_iter()
+        .map(|(key, template)| {
+            let (name, prefix) = key.split_once('/').unwrap_or((&key, ""));
+            let name = manufacturer::lookup(name).map_or_else(|| name.trim().to_string(), |m| m.name().to_string());
+            let key = if prefix.is_empty() { name } else { format!("{}/{}", name, prefix) };
+            (key, template)
+        })
+        .collect()
+}
+
+/// Template of the most specific key: the longest series prefix of `mpn`,
+/// then the manufacturer alone
+fn template<'a>(entries: impl Iterator<Item = (&'a str, &'a str)>, manufacturer: &str, mpn: &str) -> Option<&'a str> {
+    entries
+        .filter_map(|(key, template)| {
+            let (name, prefix) = key.split_once('/').unwrap_or((key, ""));
+            (name == manufacturer && mpn.starts_with(prefix)).then_some((prefix.len(), template))
+        })
+        .max_by_key(|(length, _)| *length)
+        .map(|(_, template)| template)
+}
+
+/// Datasheet of a part, `None` when no template covers it.
+///
+/// ```
+/// use component::datasheet::{templates, url};
+/// use component::settings::Settings;
+///
+/// assert_eq!(url("Vishay", "CRCW06034K99FKEA").as_deref(), Some("https://www.vishay.com/docs/20035/dcrcwe3.pdf"));
+/// assert_eq!(url("Yageo", "AC0603FR-074K99L"), None);
+///
+/// let settings = Settings {
+///     datasheets: templates([
+///         ("Yageo/AC".to_string(), "https://example.com/yageo/ac.pdf".to_string()),
+///         ("Acme".to_string(), "https://example.com/parts/{mpn}.pdf".to_string()),
+///     ].into_iter().collect()),
+///     ..Settings::default()
+/// };
+/// settings.scope(|| {
+///     assert_eq!(url("Yageo", "AC0603FR-074K99L").as_deref(), Some("https://example.com/yageo/ac.pdf"));
+///     assert_eq!(url("Acme", "X-100").as_deref(), Some("https://example.com/parts/X-100.pdf"));
+///     assert!(url("Yageo", "RC0603FR-074K99L").unwrap().contains("PYu-RC"));
+/// });
+/// ```
+pub fn url(manufacturer: &str, mpn: &str) -> Option<String> {
+    crate::settings::with(|settings| {
+        let configured = template(settings.datasheets.iter().map(|(k, v)| (k.as_str(), v.as_str())), manufacturer, mpn);
+        let template = configured.or_else(|| template(BUILT_IN.into_iter(), manufacturer, mpn))?;
+        Some(template.replace("{mpn}", mpn))
+    })
+}
+
+/// Value of a `Datasheet` field: the link, or `~` without one
+pub fn field(manufacturer: &str, mpn: &str) -> String {
+    url(manufacturer, mpn).unwrap_or_else(|| "~".to_string())
+}
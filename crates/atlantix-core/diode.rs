@@ -0,0 +1,336 @@
+//! Discrete diode library generation.
+//!
+//! Covers standard rectifiers (small-signal switching diodes in the SOD
+//! packages), Schottky, Zener (E24 voltages from 2.4 V to 75 V) and
+//! unidirectional TVS diodes in SOD-123, SOD-323, SOD-523, SMA and SMB.
+//! Part numbers follow the Nexperia and Onsemi schemes; not every kind is
+//! made in every package, and a kind/package pair without a template is
+//! rejected.
+//!
+//! Ratings are the nominal values of each family. Confirm against the
+//! datasheet before release.
+
+use crate::kicad_footprint::{self, FootprintNaming, KicadFootprint};
+use crate::kicad_symbol::{KicadSymbol, KicadSymbolLib};
+use crate::status::PartStatus;
+use crate::AtlantixError;
+use std::fmt;
+use std::str::FromStr;
+
+/// Packages supported by the diode generator
+pub const DIODE_PACKAGES: [&str; 5] = ["SOD-123", "SOD-323", "SOD-523", "SMA", "SMB"];
+
+/// E24 Zener voltages from 2.4 V to 75 V
+pub const ZENER_VOLTAGES: [f64; 37] = [
+    2.4, 2.7, 3.0, 3.3, 3.6, 3.9, 4.3, 4.7, 5.1, 5.6, 6.2, 6.8, 7.5, 8.2, 9.1,
+    10.0, 11.0, 12.0, 13.0, 15.0, 16.0, 18.0, 20.0, 22.0, 24.0, 27.0, 30.0,
+    33.0, 36.0, 39.0, 43.0, 47.0, 51.0, 56.0, 62.0, 68.0, 75.0,
+];
+
+/// Standard TVS reverse standoff voltages
+pub const TVS_VOLTAGES: [f64; 24] = [
+    5.0, 6.0, 6.5, 7.0, 7.5, 8.0, 8.5, 9.0, 10.0, 11.0, 12.0, 13.0, 14.0,
+    15.0, 16.0, 18.0, 20.0, 22.0, 24.0, 26.0, 28.0, 30.0, 33.0, 36.0,
+];
+
+const ESD_VOLTAGES: [f64; 5] = [3.3, 5.0, 12.0, 15.0, 24.0];
+const RECTIFIER_VOLTAGES: [f64; 5] = [50.0, 100.0, 200.0, 400.0, 600.0];
+const SCHOTTKY_VOLTAGES: [f64; 4] = [20.0, 30.0, 40.0, 60.0];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiodeKind {
+    Rectifier,
+    Schottky,
+    Zener,
+    Tvs,
+}
+
+impl DiodeKind {
+    pub const ALL: [DiodeKind; 4] = [
+        DiodeKind::Rectifier,
+        DiodeKind::Schottky,
+        DiodeKind::Zener,
+        DiodeKind::Tvs,
+    ];
+
+    /// Prefix of generated part names
+    pub fn name_prefix(self) -> &'static str {
+        match self {
+            DiodeKind::Rectifier => "D",
+            DiodeKind::Schottky => "DS",
+            DiodeKind::Zener => "DZ",
+            DiodeKind::Tvs => "TVS",
+        }
+    }
+
+    /// Symbol style understood by `KicadSymbol::new_diode`
+    pub fn symbol_style(self) -> &'static str {
+        match self {
+            DiodeKind::Rectifier => "diode",
+            DiodeKind::Schottky => "schottky",
+            DiodeKind::Zener => "zener",
+            DiodeKind::Tvs => "tvs",
+        }
+    }
+}
+
+impl fmt::Display for DiodeKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            DiodeKind::Rectifier => "Rectifier",
+            DiodeKind::Schottky => "Schottky",
+            DiodeKind::Zener => "Zener",
+            DiodeKind::Tvs => "TVS",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for DiodeKind {
+    type Err = AtlantixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DiodeKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| AtlantixError::Parse(format!("Unknown diode kind '{}'", s)))
+    }
+}
+
+/// Voltage in the `5V1` notation used by Zener part numbers. Whole voltages
+/// of 10 V and above drop the decimal digit.
+///
+/// ```
+/// use component::diode::voltage_code;
+/// assert_eq!(voltage_code(5.1), "5V1");
+/// assert_eq!(voltage_code(3.0), "3V0");
+/// assert_eq!(voltage_code(12.0), "12V");
+/// ```
+pub fn voltage_code(volts: f64) -> String {
+    let tenths = (volts * 10.0).round() as u32;
+    if tenths < 100 || !tenths.is_multiple_of(10) {
+        format!("{}V{}", tenths / 10, tenths % 10)
+    } else {
+        format!("{}V", tenths / 10)
+    }
+}
+
+/// Voltage as written in Onsemi `1SMA`/`1SMB` part numbers: `5.0`, `6.5`, `15`
+fn onsemi_tvs_voltage(volts: f64) -> String {
+    if volts < 10.0 {
+        format!("{:.1}", volts)
+    } else {
+        format!("{}", volts.round() as u32)
+    }
+}
+
+/// Builds a part number from the part voltage
+type MpnTemplate = Option<fn(f64) -> String>;
+
+/// Part number families available for a kind in a package
+struct DiodeFamily {
+    voltages: &'static [f64],
+    rating: &'static str,
+    nexperia: MpnTemplate,
+    onsemi: MpnTemplate,
+}
+
+fn family(kind: DiodeKind, package: &str) -> Option<DiodeFamily> {
+    let (voltages, rating, nexperia, onsemi): (&'static [f64], &'static str, MpnTemplate, MpnTemplate) =
+        match (kind, package) {
+            (DiodeKind::Rectifier, "SOD-123") => (&[100.0], "150mA", Some(|_| "1N4148W".into()), Some(|_| "MMSD4148T1G".into())),
+            (DiodeKind::Rectifier, "SOD-323") => (&[100.0], "250mA", Some(|_| "BAS316".into()), Some(|_| "MMDL914T1G".into())),
+            (DiodeKind::Rectifier, "SOD-523") => (&[100.0], "250mA", Some(|_| "BAS516".into()), None),
+            (DiodeKind::Rectifier, "SMA") => (&RECTIFIER_VOLTAGES, "1A", None, Some(|v| format!("MURA1{:02}T3G", v as u32 / 10))),
+            (DiodeKind::Rectifier, "SMB") => (&RECTIFIER_VOLTAGES, "3A", None, Some(|v| format!("MURS3{:02}T3G", v as u32 / 10))),
+            (DiodeKind::Schottky, "SOD-123") => (&SCHOTTKY_VOLTAGES[..3], "500mA", Some(|v| format!("PMEG{}05EH", v)), Some(|v| format!("MBR05{}T1G", v))),
+            (DiodeKind::Schottky, "SOD-323") => (&SCHOTTKY_VOLTAGES[..3], "500mA", Some(|v| format!("PMEG{}05EJ", v)), Some(|v| format!("NSR05{}HT1G", v))),
+            (DiodeKind::Schottky, "SOD-523") => (&[30.0], "200mA", None, Some(|_| "RB521S30T1G".into())),
+            (DiodeKind::Schottky, "SMA") => (&SCHOTTKY_VOLTAGES, "1A", None, Some(|v| format!("MBRA1{}T3G", v))),
+            (DiodeKind::Schottky, "SMB") => (&SCHOTTKY_VOLTAGES, "3A", None, Some(|v| format!("MBRS3{}T3G", v))),
+            (DiodeKind::Zener, "SOD-123") => (&ZENER_VOLTAGES, "500mW", Some(|v| format!("BZT52H-C{}", voltage_code(v))), Some(|v| format!("MMSZ{}T1G", voltage_code(v)))),
+            (DiodeKind::Zener, "SOD-323") => (&ZENER_VOLTAGES, "300mW", Some(|v| format!("BZX384-C{}", voltage_code(v))), Some(|v| format!("MM3Z{}T1G", voltage_code(v)))),
+            (DiodeKind::Zener, "SOD-523") => (&ZENER_VOLTAGES, "200mW", Some(|v| format!("BZX585-C{}", voltage_code(v))), Some(|v| format!("MM5Z{}T1G", voltage_code(v)))),
+            (DiodeKind::Tvs, "SOD-323") => (&ESD_VOLTAGES, "200W", Some(|v| format!("PESD{}S1UA", voltage_code(v))), None),
+            (DiodeKind::Tvs, "SOD-523") => (&ESD_VOLTAGES, "150W", Some(|v| format!("PESD{}S1UB", voltage_code(v))), None),
+            (DiodeKind::Tvs, "SMA") => (&TVS_VOLTAGES, "400W", None, Some(|v| format!("1SMA{}AT3G", onsemi_tvs_voltage(v)))),
+            (DiodeKind::Tvs, "SMB") => (&TVS_VOLTAGES, "600W", None, Some(|v| format!("1SMB{}AT3G", onsemi_tvs_voltage(v)))),
+            _ => return None,
+        };
+    Some(DiodeFamily { voltages, rating, nexperia, onsemi })
+}
+
+/// One generated diode part
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiodePart {
+    pub name: String,
+    pub kind: DiodeKind,
+    pub package: String,
+    pub value: String,
+    /// Reverse, Zener or standoff voltage depending on the kind
+    pub voltage: f64,
+    /// Forward current for rectifiers, power for Zener and TVS diodes
+    pub rating: String,
+    pub nexperia_mpn: Option<String>,
+    pub onsemi_mpn: Option<String>,
+    /// Internal part number, see [`crate::ipn`], given by `parts()` to parts
+    /// the hook keeps
+    pub ipn: Option<String>,
+}
+
+impl DiodePart {
+    /// Preferred (manufacturer, MPN), Nexperia first
+    pub fn primary_mpn(&self) -> (&'static str, &str) {
+        match (&self.nexperia_mpn, &self.onsemi_mpn) {
+            (Some(mpn), _) => ("Nexperia", mpn),
+            (None, Some(mpn)) => ("Onsemi", mpn),
+            (None, None) => ("", ""),
+        }
+    }
+}
+
+///
+/// Diode generator for one kind in one package.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diode {
+    kind: DiodeKind,
+    package: String,
+    status: Option<PartStatus>,
+    footprint_naming: FootprintNaming,
+}
+
+impl Diode {
+    /// Returns `None` when the kind is not made in the package.
+    pub fn new(kind: DiodeKind, package: &str) -> Option<Diode> {
+        family(kind, package)?;
+        Some(Diode {
+            kind,
+            package: package.to_string(),
+            status: None,
+            footprint_naming: FootprintNaming::Atlantix,
+        })
+    }
+
+    /// Packages the kind is available in
+    pub fn packages(kind: DiodeKind) -> Vec<&'static str> {
+        DIODE_PACKAGES
+            .iter()
+            .copied()
+            .filter(|package| family(kind, package).is_some())
+            .collect()
+    }
+
+    /// Stamps every subsequently generated KiCad symbol with a hidden `Status` property.
+    pub fn set_status(&mut self, status: PartStatus) {
+        self.status = Some(status);
+    }
+
+    /// With `FootprintNaming::KicadOfficial` the symbols reference the stock
+    /// KiCad footprints of the package in place of the generated ones.
+    pub fn set_footprint_naming(&mut self, naming: FootprintNaming) {
+        self.footprint_naming = naming;
+    }
+
+    /// One part per voltage of the family, as the part hook leaves them
+    pub fn parts(&self) -> Vec<DiodePart> {
+        let footprint = self.footprint_name().unwrap_or_default();
+        self.generated_parts()
+            .into_iter()
+            .filter_map(|mut part| {
+                let outcome = crate::hook::outcome(|| self.kicad_symbol(&part, &footprint))?;
+                for (field, changed) in [
+                    (&mut part.name, outcome.changed(|symbol| &symbol.name)),
+                    (&mut part.value, outcome.changed(|symbol| &symbol.value)),
+                ] {
+                    if let Some(changed) = changed {
+                        *field = changed.to_string();
+                    }
+                }
+                part.ipn = crate::ipn::assign("diode", &self.package, &part.name, "");
+                Some(part)
+            })
+            .collect()
+    }
+
+    fn generated_parts(&self) -> Vec<DiodePart> {
+        let family = family(self.kind, &self.package).expect("package validated in new()");
+        family
+            .voltages
+            .iter()
+            .map(|&voltage| {
+                let value = voltage_code(voltage);
+                let name = format!("{}_{}_{}", self.kind.name_prefix(), self.package, value);
+                DiodePart {
+                    ipn: None,
+                    name,
+                    kind: self.kind,
+                    package: self.package.clone(),
+                    value,
+                    voltage,
+                    rating: family.rating.to_string(),
+                    nexperia_mpn: family.nexperia.map(|mpn| mpn(voltage)),
+                    onsemi_mpn: family.onsemi.map(|mpn| mpn(voltage)),
+                }
+            })
+            .collect()
+    }
+
+    /// Footprint the symbols reference, `None` without one for the package
+    pub fn footprint_name(&self) -> Option<String> {
+        match self.footprint_naming {
+            FootprintNaming::Atlantix => KicadFootprint::new_smd_diode(&self.package)
+                .map(|fp| format!("Atlantix_Diodes:{}", fp.name)),
+            FootprintNaming::KicadOfficial => kicad_footprint::official_footprint("Diode_SMD", "D", &self.package),
+        }
+    }
+
+    pub fn generate_kicad_symbols(&self, output_path: &str) -> Result<(), AtlantixError> {
+        let mut symbol_lib = KicadSymbolLib::new();
+        let footprint = self.footprint_name().unwrap_or_default();
+
+        for part in self.generated_parts() {
+            if let Some(mut symbol) = crate::hook::apply(self.kicad_symbol(&part, &footprint)) {
+                crate::ipn::assign_symbol("diode", &self.package, &mut symbol);
+                symbol_lib.add_symbol(symbol);
+            }
+        }
+
+        crate::atomic::write(output_path, symbol_lib.generate_library())
+    }
+
+    /// The KiCad symbol of a generated part, before the part hook
+    fn kicad_symbol(&self, part: &DiodePart, footprint: &str) -> KicadSymbol {
+        let (manufacturer, mpn) = part.primary_mpn();
+        let description = format!(
+            "DIODE {} {}, {}, {}",
+            self.kind, part.value, self.package, part.rating
+        );
+        let mut symbol = KicadSymbol::new_diode(part.name.clone(), part.value.clone(), footprint.to_string(), self.kind.symbol_style())
+            .with_sources(manufacturer, mpn, mpn);
+        symbol.description = description;
+        symbol.properties.extend([
+            ("Kind".to_string(), self.kind.to_string()),
+            ("Voltage".to_string(), format!("{}V", part.voltage)),
+            ("Rating".to_string(), part.rating.clone()),
+        ]);
+        if let (Some(_), Some(onsemi)) = (&part.nexperia_mpn, &part.onsemi_mpn) {
+            symbol.properties.push(("MPN2".to_string(), onsemi.clone()));
+            symbol.properties.push(("Manufacturer2".to_string(), "Onsemi".to_string()));
+        }
+        symbol.status = self.status;
+        symbol
+    }
+
+    pub fn generate_kicad_footprints(packages: &[&str], output_dir: &str) -> Result<(), AtlantixError> {
+        crate::atomic::create_dir_all(output_dir)?;
+        for package in packages {
+            if let Some(footprint) = KicadFootprint::new_smd_diode(package) {
+                let filename = format!("{}/{}.kicad_mod", output_dir, footprint.name);
+                crate::atomic::write(filename, footprint.generate_footprint())?;
+            }
+        }
+        Ok(())
+    }
+}
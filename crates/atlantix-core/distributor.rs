@@ -0,0 +1,173 @@
+//! Distributor part numbers.
+//!
+//! Digi-Key part numbers are derived per manufacturer and package by the
+//! generators. Mouser part numbers are the manufacturer part number behind a
+//! numeric manufacturer prefix (`71-` Vishay Dale, `603-` Yageo, `660-` KOA
+//! Speer), so they can be built for any generated MPN. Arrow lists parts
+//! under their MPN.
+//!
+//! Libraries record one distributor as `Supplier 1` unless the `suppliers`
+//! of the current [`crate::settings`] list several, which are then written
+//! as `Supplier 1`, `Supplier 2`, ... in that order.
+//!
+//! SupplierURL links to the distributor's search page unless the settings
+//! hold a product page for the part, e.g. from cached distributor API data.
+
+use crate::AtlantixError;
+use std::fmt;
+use std::str::FromStr;
+
+/// Distributor recorded as `Supplier 1` in the generated libraries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Distributor {
+    #[default]
+    Digikey,
+    Mouser,
+    Arrow,
+}
+
+impl Distributor {
+    pub const ALL: [Distributor; 3] = [Distributor::Digikey, Distributor::Mouser, Distributor::Arrow];
+
+    /// Search page for a distributor part number
+    pub fn search_url(self, part_number: &str) -> String {
+        match self {
+            Distributor::Digikey => format!("https://www.digikey.com/products/en?keywords={}", part_number),
+            Distributor::Mouser => format!("https://www.mouser.com/c/?q={}", part_number),
+            Distributor::Arrow => format!("https://www.arrow.com/en/products/search?q={}", part_number),
+        }
+    }
+
+    /// Product page of a distributor or manufacturer part number in the
+    /// current [`crate::settings`]
+    pub fn product_url(self, part_number: &str) -> Option<String> {
+        crate::settings::with(|settings| settings.product_pages.get(&(self, part_number.to_string())).cloned())
+    }
+
+    /// Link for the SupplierURL field: the product page when one is
+    /// known, the search page otherwise, and none without a part number.
+    ///
+    /// ```
+    /// use component::distributor::Distributor;
+    /// use component::settings::Settings;
+    ///
+    /// let url = "https://www.digikey.com/en/products/detail/vishay-dale/CRCW06031K00FKEA/1174891";
+    /// let mut settings = Settings::default();
+    /// settings.product_pages.insert((Distributor::Digikey, "541-1.00KHCT-ND".to_string()), url.to_string());
+    /// settings.scope(|| {
+    ///     assert_eq!(Distributor::Digikey.supplier_url("541-1.00KHCT-ND"), url);
+    ///     assert_eq!(
+    ///         Distributor::Digikey.supplier_url("541-1.02KHCT-ND"),
+    ///         "https://www.digikey.com/products/en?keywords=541-1.02KHCT-ND"
+    ///     );
+    ///     assert_eq!(Distributor::Mouser.supplier_url("541-1.00KHCT-ND"), "https://www.mouser.com/c/?q=541-1.00KHCT-ND");
+    ///     assert_eq!(Distributor::Digikey.supplier_url(""), "");
+    /// });
+    /// ```
+    pub fn supplier_url(self, part_number: &str) -> String {
+        if part_number.is_empty() {
+            return String::new();
+        }
+        self.product_url(part_number).unwrap_or_else(|| self.search_url(part_number))
+    }
+}
+
+impl fmt::Display for Distributor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Distributor::Digikey => write!(f, "Digikey"),
+            Distributor::Mouser => write!(f, "Mouser"),
+            Distributor::Arrow => write!(f, "Arrow"),
+        }
+    }
+}
+
+impl FromStr for Distributor {
+    type Err = AtlantixError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().replace('-', "").as_str() {
+            "digikey" => Ok(Distributor::Digikey),
+            "mouser" => Ok(Distributor::Mouser),
+            "arrow" => Ok(Distributor::Arrow),
+            _ => Err(AtlantixError::Parse(format!("Unknown distributor '{}'. Expected digikey, mouser or arrow", value))),
+        }
+    }
+}
+
+/// Distributors written as `Supplier 1`, `Supplier 2`, ... in that order,
+/// replacing the single distributor of each generator. An empty list, the
+/// default, keeps the single distributor.
+///
+/// ```
+/// use component::distributor::{self, Distributor};
+/// use component::settings::Settings;
+///
+/// let settings = Settings { suppliers: vec![Distributor::Mouser, Distributor::Arrow], ..Settings::default() };
+/// assert_eq!(settings.scope(distributor::suppliers), [Distributor::Mouser, Distributor::Arrow]);
+/// assert!(distributor::suppliers().is_empty());
+/// ```
+pub fn suppliers() -> Vec<Distributor> {
+    crate::settings::with(|settings| settings.suppliers.clone())
+}
+
+/// Distributors of a part of `manufacturer` with their part numbers: those
+/// of [`suppliers`], or Digi-Key alone when the settings list none.
+/// Mouser's is built with [`mouser_pn`]; a distributor without a number of
+/// its own for the part lists it under the MPN.
+///
+/// ```
+/// use component::distributor::{self, Distributor};
+/// use component::settings::Settings;
+///
+/// let digikey = distributor::part_numbers("Vishay", "WSL0603R0100FEA", "WSL0603R0100FEACT-ND");
+/// assert_eq!(digikey, [(Distributor::Digikey, "WSL0603R0100FEACT-ND".to_string())]);
+///
+/// let settings = Settings { suppliers: vec![Distributor::Mouser, Distributor::Arrow], ..Settings::default() };
+/// let parts = settings.scope(|| distributor::part_numbers("Vishay", "WSL0603R0100FEA", "WSL0603R0100FEACT-ND"));
+/// assert_eq!(parts[0], (Distributor::Mouser, "71-WSL0603R0100FEA".to_string()));
+/// assert_eq!(parts[1], (Distributor::Arrow, "WSL0603R0100FEA".to_string()));
+/// ```
+pub fn part_numbers(manufacturer: &str, mpn: &str, digikey_pn: &str) -> Vec<(Distributor, String)> {
+    let mut suppliers = suppliers();
+    if suppliers.is_empty() {
+        suppliers.push(Distributor::Digikey);
+    }
+    suppliers
+        .into_iter()
+        .map(|distributor| {
+            let part_number = match distributor {
+                Distributor::Digikey => digikey_pn.to_string(),
+                Distributor::Mouser => mouser_pn(manufacturer, mpn).unwrap_or_else(|| mpn.to_string()),
+                Distributor::Arrow => mpn.to_string(),
+            };
+            (distributor, part_number)
+        })
+        .collect()
+}
+
+/// Mouser's manufacturer prefix for the resistor manufacturers we generate
+pub fn mouser_prefix(manufacturer: &str) -> Option<&'static str> {
+    match manufacturer {
+        "Vishay" | "Vishay Dale" => Some("71"),
+        "Yageo" => Some("603"),
+        "KOA" | "KOA Speer" => Some("660"),
+        "Stackpole" | "Stackpole Electronics" => Some("708"),
+        "Susumu" => Some("754"),
+        _ => None,
+    }
+}
+
+/// Mouser part number for a manufacturer part number.
+///
+/// ```
+/// use component::distributor::mouser_pn;
+/// assert_eq!(mouser_pn("Vishay", "CRCW06031K05FKEA").as_deref(), Some("71-CRCW06031K05FKEA"));
+/// assert_eq!(mouser_pn("Yageo", "RC0603FR-071K05L").as_deref(), Some("603-RC0603FR-071K05L"));
+/// assert_eq!(mouser_pn("KOA Speer", "RK73H1JTTD1051F").as_deref(), Some("660-RK73H1JTTD1051F"));
+/// assert_eq!(mouser_pn("Stackpole", "RMCF0603FT1K05").as_deref(), Some("708-RMCF0603FT1K05"));
+/// assert_eq!(mouser_pn("Acme", "X1"), None);
+/// ```
+pub fn mouser_pn(manufacturer: &str, mpn: &str) -> Option<String> {
+    mouser_prefix(manufacturer).map(|prefix| format!("{}-{}", prefix, mpn))
+}
@@ -3,7 +3,7 @@ use bevy_ecs::prelude::*;
 // Core resistor components
 #[derive(Component, Debug, Clone)]
 pub struct ResistorValue {
-    pub ohms: f64,
+    pub resistance: crate::Resistance,  // whole micro-ohms, no float drift
     pub formatted: String,  // "1.33K", "100", etc.
 }
 
@@ -18,10 +18,10 @@ pub struct Package {
 }
 
 #[derive(Component, Debug, Clone)]
-pub struct Tolerance(pub String);  // "1%", "2%", "5%"
+pub struct Tolerance(pub String);  // "1%", "2%", "5%", "0.1%" selects thin film
 
-#[derive(Component, Debug, Clone)]
-pub struct PowerRating(pub String);  // "1/10W", "1/4W"
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PowerRating(pub crate::Power);  // milliwatts, displays as "1/10W"
 
 // Manufacturer components
 #[derive(Component, Debug, Clone)]
@@ -39,6 +39,8 @@ pub struct ManufacturerPart {
     pub mpn: String,              // Manufacturer Part Number
     pub distributor: String,      // "Digikey", "Mouser"
     pub distributor_pn: String,   // Distributor Part Number
+    /// Supplier 2 onwards with their part numbers, see [`crate::distributor::suppliers`]
+    pub other_suppliers: Vec<(String, String)>,
 }
 
 // Allow multiple manufacturers per resistor
@@ -57,6 +59,10 @@ pub struct KicadSymbol {
     pub symbol_content: String,
 }
 
+/// The part's symbol as the part hook left it, see [`crate::hook`]
+#[derive(Component, Debug, Clone)]
+pub struct HookedSymbol(pub crate::kicad_symbol::KicadSymbol);
+
 #[derive(Component, Debug, Clone)]
 pub struct KicadFootprint {
     pub name: String,
@@ -80,4 +86,29 @@ pub struct ResistorBundle {
     pub description: Description,
     pub part_number: PartNumber,
     pub manufacturers: ManufacturerParts,
-}
\ No newline at end of file
+}
+
+// Ferrite bead components
+#[derive(Component, Debug, Clone)]
+pub struct FerriteTemplate {
+    pub impedances: Vec<f64>,  // ohms @ 100MHz, expanded by generate_ferrite_beads
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FerriteImpedance(pub f64);  // ohms @ 100MHz
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct FerriteRatings {
+    pub rated_current: f64,  // A
+    pub dcr_mohm: f64,
+}
+
+#[derive(Bundle)]
+pub struct FerriteBundle {
+    pub impedance: FerriteImpedance,
+    pub ratings: FerriteRatings,
+    pub package: Package,
+    pub description: Description,
+    pub part_number: PartNumber,
+    pub manufacturers: ManufacturerParts,
+}
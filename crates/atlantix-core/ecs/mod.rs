@@ -3,6 +3,16 @@ pub mod systems;
 pub mod resources;
 
 use bevy_ecs::prelude::*;
+use crate::order::PartOrder;
+use resources::{AltiumCsvBuffer, CancelToken, FootprintBuffer, Progress, ProgressReporter, SymbolLibraryBuffer};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+/// Default Altium CSV name, the one [`crate::altium::write_package_csvs`] uses
+pub const ALTIUM_FILE_PATTERN: &str = "resistors_{package}.csv";
+
+/// Symbol library name of each package, as in the `gen_kicad_resistor` example
+pub const SYMBOL_FILE_PATTERN: &str = "resistors_{package}.kicad_sym";
 
 /// Initialize the ECS world with default systems
 pub fn build_resistor_world() -> World {
@@ -10,22 +20,283 @@ pub fn build_resistor_world() -> World {
     
     // Register resources
     world.insert_resource(resources::GeneratorConfig::default());
+    world.insert_resource(SymbolLibraryBuffer::default());
+    world.insert_resource(AltiumCsvBuffer::default());
+    world.insert_resource(FootprintBuffer::default());
+    world.insert_resource(resources::WrittenOutputs::default());
+    world.insert_resource(ProgressReporter::default());
+    world.insert_resource(CancelToken::default());
     
     world
 }
 
-/// Run the resistor generation pipeline
-pub fn run_generation_pipeline(world: &mut World) {
+/// Stages of the generation pipeline, in the order they run. Commands are
+/// applied between stages, so entities spawned in one are populated by the
+/// next within the same schedule run.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GenerationSet {
+    /// Expand E-series and ferrite bead templates into one entity per part
+    Spawn,
+    /// Descriptions and tolerances
+    Attributes,
+    /// Manufacturer and distributor part numbers, then the part hook
+    Parts,
+    /// Symbols, footprints and Altium rows into the output buffers
+    Format,
+    /// Buffers to disk, when [`resources::GeneratorConfig::output_dir`] is set
+    Write,
+}
+
+impl GenerationSet {
+    /// Every stage, in the order they run
+    pub const ALL: [GenerationSet; 5] = [
+        GenerationSet::Spawn,
+        GenerationSet::Attributes,
+        GenerationSet::Parts,
+        GenerationSet::Format,
+        GenerationSet::Write,
+    ];
+}
+
+/// The generation schedule, one system set per [`GenerationSet`] stage.
+/// Front ends add their own systems to a stage with `.in_set(..)`.
+pub fn generation_schedule() -> Schedule {
     let mut schedule = Schedule::default();
-    
-    // Add systems in order
+    let [spawn, attributes, parts, format, write] = GenerationSet::ALL;
+    schedule.configure_sets((spawn, attributes, parts, format, write).chain());
     schedule.add_systems((
-        systems::generate_eseries_values,
-        systems::assign_package_attributes,
-        systems::calculate_tolerances,
-        systems::generate_manufacturer_parts,
-        systems::format_outputs,
+        (systems::generate_eseries_values, systems::generate_ferrite_beads).in_set(GenerationSet::Spawn),
+        (systems::assign_package_attributes, systems::calculate_tolerances).in_set(GenerationSet::Attributes),
+        (systems::generate_manufacturer_parts, systems::apply_part_hook).chain().in_set(GenerationSet::Parts),
+        systems::format_outputs.in_set(GenerationSet::Format),
+        systems::write_outputs.in_set(GenerationSet::Write),
     ));
-    
-    schedule.run(world);
-}
\ No newline at end of file
+    // Spawned parts must exist before they are populated
+    schedule.add_systems(apply_deferred.after(GenerationSet::Spawn).before(GenerationSet::Attributes));
+    // Parts the hook drops are gone before outputs are formatted
+    schedule.add_systems(apply_deferred.after(GenerationSet::Parts).before(GenerationSet::Format));
+    schedule
+}
+
+/// Run the resistor generation pipeline. This is the entry point for every
+/// front end: one run turns the templates in `world` into fully populated
+/// parts and fills the output buffers.
+///
+/// ```
+/// use component::ecs::{self, components::*, resources::*};
+/// use component::ValueRange;
+///
+/// let mut world = ecs::build_resistor_world();
+/// world.insert_resource(GeneratorConfig { value_range: ValueRange::parse(Some("100"), Some("9.99K")).unwrap(), ..Default::default() });
+/// world.insert_resource(ESeriesCache::default());
+/// world.spawn((ESeries(12), Package { name: "0402".into(), imperial: "0402".into(), metric: String::new() }));
+/// ecs::run_generation_pipeline(&mut world);
+///
+/// let mut parts = world.query::<(&Description, &ManufacturerParts)>();
+/// assert_eq!(parts.iter(&world).count(), 24);
+/// assert!(parts.iter(&world).all(|(description, mfrs)| !description.0.is_empty() && !mfrs.0.is_empty()));
+/// assert_eq!(world.resource::<SymbolLibraryBuffer>().libraries["0402"].len(), 24);
+/// ```
+pub fn run_generation_pipeline(world: &mut World) {
+    generation_schedule().run(world);
+}
+
+/// Receive [`Progress`] from the pipeline runs on `world` from now on,
+/// e.g. on a GUI thread while another thread runs the schedule. Each stage
+/// reports the parts it has done by package; the write stage reports each
+/// file with the parts in it.
+///
+/// ```
+/// use component::ecs::{self, components::*, resources::*, GenerationSet};
+/// use component::ValueRange;
+///
+/// let mut world = ecs::build_resistor_world();
+/// world.insert_resource(GeneratorConfig { value_range: ValueRange::parse(Some("1K"), Some("9.99K")).unwrap(), ..Default::default() });
+/// world.insert_resource(ESeriesCache::default());
+/// for package in ["0603", "0805"] {
+///     world.spawn((ESeries(12), Package { name: package.into(), imperial: package.into(), metric: String::new() }));
+/// }
+/// let progress = ecs::progress_channel(&mut world);
+/// ecs::run_generation_pipeline(&mut world);
+///
+/// let updates: Vec<Progress> = progress.try_iter().collect();
+/// let spawned: Vec<_> = updates.iter().filter(|p| p.stage == GenerationSet::Spawn).map(|p| p.done).collect();
+/// assert_eq!(spawned, [12, 24]);
+/// let last = updates.iter().rfind(|p| p.stage == GenerationSet::Format).unwrap();
+/// assert_eq!((last.done, last.total), (24, 24));
+/// assert_eq!(last.fraction(), 0.8);
+/// ```
+pub fn progress_channel(world: &mut World) -> mpsc::Receiver<Progress> {
+    let (sender, receiver) = mpsc::channel();
+    world.insert_resource(ProgressReporter { sender: Some(sender) });
+    receiver
+}
+
+/// A fresh token to cancel the next pipeline run on `world`, e.g. from a
+/// GUI thread while a worker runs the schedule. A cancelled run stops at the
+/// next part, removes the files it already wrote and records
+/// [`crate::AtlantixError::Cancelled`] in [`resources::WrittenOutputs`].
+///
+/// ```
+/// use component::ecs::{self, components::*, resources::*};
+/// use component::AtlantixError;
+/// use component::ValueRange;
+///
+/// let dir = std::env::temp_dir().join(format!("ecs-cancel-doctest-{}", std::process::id()));
+/// let mut world = ecs::build_resistor_world();
+/// world.insert_resource(GeneratorConfig { value_range: ValueRange::parse(Some("1K"), Some("9.99K")).unwrap(), output_dir: Some(dir.clone()), ..Default::default() });
+/// world.insert_resource(ESeriesCache::default());
+/// world.spawn((ESeries(96), Package { name: "0603".into(), imperial: "0603".into(), metric: String::new() }));
+///
+/// let cancel = ecs::cancel_token(&mut world);
+/// cancel.cancel();
+/// ecs::run_generation_pipeline(&mut world);
+///
+/// let written = world.resource::<WrittenOutputs>();
+/// assert!(matches!(written.error, Some(AtlantixError::Cancelled)));
+/// assert!(written.files.is_empty());
+/// assert!(!dir.join("kicad/symbols/resistors_0603.kicad_sym").exists());
+/// ```
+pub fn cancel_token(world: &mut World) -> CancelToken {
+    let token = CancelToken::default();
+    world.insert_resource(token.clone());
+    token
+}
+
+/// Write the Altium rows of the generated resistors, one CSV per package
+/// into `output_dir`. `file_pattern` names each file, with `{package}`
+/// replaced by the package, with the settings of the world's
+/// `GeneratorConfig`. Rows follow [`crate::order::order`]. Returns
+/// every written path and its row count, sorted by package.
+///
+/// ```
+/// use component::ecs::{self, components::*, resources::*};
+/// use component::ValueRange;
+///
+/// let mut world = ecs::build_resistor_world();
+/// world.insert_resource(GeneratorConfig {
+///     output_formats: vec![OutputFormat::Altium],
+///     value_range: ValueRange::parse(Some("1K"), Some("9.99K")).unwrap(),
+///     ..Default::default()
+/// });
+/// world.insert_resource(ESeriesCache::default());
+/// for package in ["0603", "0805"] {
+///     world.spawn((ESeries(24), Package { name: package.into(), imperial: package.into(), metric: String::new() }));
+/// }
+/// ecs::run_generation_pipeline(&mut world);
+///
+/// let dir = std::env::temp_dir().join(format!("ecs-altium-doctest-{}", std::process::id()));
+/// std::fs::create_dir_all(&dir).unwrap();
+/// let written = ecs::write_altium_csvs(&mut world, &dir, "Atlantix_R_{package}.csv").unwrap();
+/// assert_eq!(written, [(dir.join("Atlantix_R_0603.csv"), 24), (dir.join("Atlantix_R_0805.csv"), 24)]);
+/// let csv = std::fs::read_to_string(dir.join("Atlantix_R_0603.csv")).unwrap();
+/// assert!(csv.lines().nth(1).unwrap().starts_with("R0603_1.00K,\"RES SMT 1.00Kohms, 0603, 5%, "));
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn write_altium_csvs(world: &mut World, output_dir: &Path, file_pattern: &str) -> Result<Vec<(PathBuf, usize)>, crate::AtlantixError> {
+    let settings = world.get_resource::<resources::GeneratorConfig>().map(|config| config.settings.clone()).unwrap_or_default();
+    match world.get_resource::<AltiumCsvBuffer>() {
+        Some(buffer) => settings.scope(|| write_altium(buffer, output_dir, file_pattern)),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Write the collected outputs the way the `Resistor` path does:
+/// `kicad/symbols/resistors_<package>.kicad_sym`, one footprint per package
+/// in `kicad/footprints.pretty` and the CSVs into `altium`, named by
+/// `altium_file_pattern` such as [`ALTIUM_FILE_PATTERN`]. Returns every
+/// written path with its part count.
+///
+/// ```
+/// use component::ecs::{self, components::*, resources::*};
+/// use component::ValueRange;
+///
+/// let dir = std::env::temp_dir().join(format!("ecs-outputs-doctest-{}", std::process::id()));
+/// let mut world = ecs::build_resistor_world();
+/// world.insert_resource(GeneratorConfig {
+///     output_formats: vec![OutputFormat::KicadSymbols, OutputFormat::KicadFootprints, OutputFormat::Altium],
+///     value_range: ValueRange::parse(Some("1K"), Some("9.99K")).unwrap(),
+///     output_dir: Some(dir.clone()),
+///     ..Default::default()
+/// });
+/// world.insert_resource(ESeriesCache::default());
+/// world.spawn((ESeries(24), Package { name: "0603".into(), imperial: "0603".into(), metric: String::new() }));
+/// ecs::run_generation_pipeline(&mut world);
+///
+/// let written = world.resource::<WrittenOutputs>();
+/// assert!(written.error.is_none());
+/// assert_eq!(written.files, [
+///     (dir.join("kicad/symbols/resistors_0603.kicad_sym"), 24),
+///     (dir.join("kicad/footprints.pretty/R_0603_1608Metric.kicad_mod"), 1),
+///     (dir.join("altium/resistors_0603.csv"), 24),
+/// ]);
+/// let symbols = std::fs::read_to_string(dir.join("kicad/symbols/resistors_0603.kicad_sym")).unwrap();
+/// assert!(symbols.contains("(symbol \"R0603_1.00K\"") && symbols.contains("CRCW06031K00JNEA"));
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn write_outputs(
+    output_dir: &Path,
+    symbols: &SymbolLibraryBuffer,
+    altium: &AltiumCsvBuffer,
+    footprints: &FootprintBuffer,
+    altium_file_pattern: &str,
+) -> Result<Vec<(PathBuf, usize)>, crate::AtlantixError> {
+    let mut written = Vec::new();
+    if !symbols.libraries.is_empty() {
+        let symbols_dir = output_dir.join("kicad/symbols");
+        crate::atomic::create_dir_all(&symbols_dir)?;
+        for (package, symbols) in &symbols.libraries {
+            let mut symbols = symbols.clone();
+            match crate::order::order() {
+                PartOrder::Value => symbols.sort_by_key(|(resistance, _)| *resistance),
+                PartOrder::Name => symbols.sort_by(|(_, a), (_, b)| a.name.cmp(&b.name)),
+                PartOrder::Generation => {}
+            }
+            let mut library = crate::kicad_symbol::KicadSymbolLib::new();
+            for (_, symbol) in symbols {
+                library.add_symbol(symbol);
+            }
+            let path = symbols_dir.join(SYMBOL_FILE_PATTERN.replace("{package}", package));
+            crate::atomic::write(&path, library.generate_library())?;
+            written.push((path, library.symbols.len()));
+        }
+    }
+    if !footprints.packages.is_empty() {
+        let pretty = output_dir.join("kicad/footprints.pretty");
+        crate::atomic::create_dir_all(&pretty)?;
+        for package in &footprints.packages {
+            if let Some(footprint) = crate::kicad_footprint::KicadFootprint::new_smd_resistor(package) {
+                let path = pretty.join(format!("{}.kicad_mod", footprint.name));
+                crate::atomic::write(&path, footprint.generate_footprint())?;
+                written.push((path, 1));
+            }
+        }
+    }
+    if !altium.packages.is_empty() {
+        let altium_dir = output_dir.join("altium");
+        crate::atomic::create_dir_all(&altium_dir)?;
+        written.extend(write_altium(altium, &altium_dir, altium_file_pattern)?);
+    }
+    Ok(written)
+}
+
+fn write_altium(buffer: &AltiumCsvBuffer, output_dir: &Path, file_pattern: &str) -> Result<Vec<(PathBuf, usize)>, crate::AtlantixError> {
+    let mut written = Vec::with_capacity(buffer.packages.len());
+    for (package, rows) in &buffer.packages {
+        let mut rows: Vec<&(crate::Resistance, String)> = rows.iter().collect();
+        match crate::order::order() {
+            PartOrder::Value => rows.sort_by_key(|(resistance, _)| *resistance),
+            PartOrder::Name => rows.sort_by_key(|(_, row)| row.split(',').next().unwrap_or_default()),
+            PartOrder::Generation => {}
+        }
+        let mut csv = crate::altium::csv_header(crate::distributor::suppliers().len());
+        for (_, row) in &rows {
+            csv.push_str(row);
+            csv.push_str("\r\n");
+        }
+        let path = output_dir.join(file_pattern.replace("{package}", package));
+        crate::atomic::write(&path, csv)?;
+        written.push((path, rows.len()));
+    }
+    Ok(written)
+}
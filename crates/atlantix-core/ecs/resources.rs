@@ -1,11 +1,35 @@
 use bevy_ecs::prelude::*;
+use crate::kicad_symbol::KicadSymbol;
+use crate::Resistance;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
 
 /// Global configuration for the generator
 #[derive(Resource, Debug, Clone)]
 pub struct GeneratorConfig {
     pub output_formats: Vec<OutputFormat>,
     pub manufacturers: Vec<String>,
-    pub decades: Vec<u32>,
+    /// Values outside are not generated, the pipeline sweeps the decades
+    /// covering it, see [`crate::ValueRange::decades`]
+    pub value_range: crate::ValueRange,
+    /// Skip packages rated below this power
+    pub min_power: Option<crate::Power>,
+    /// Distributor for every manufacturer part, `None` keeps each
+    /// manufacturer's usual distributor (Digikey for Vishay/KOA, Mouser for Yageo)
+    pub distributor: Option<crate::Distributor>,
+    /// Directory `write_outputs` writes the libraries into, `None` keeps
+    /// them in the output buffers
+    pub output_dir: Option<PathBuf>,
+    /// Resistor symbol style, "european" or "american", followed by any
+    /// package overrides, e.g. "european,0402=american"
+    pub symbol_style: String,
+    /// Name of the Altium CSV of each package, `{package}` replaced by it
+    pub altium_file_pattern: String,
+    /// Formats, naming and sourcing data every system runs with
+    pub settings: crate::settings::Settings,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -20,11 +44,28 @@ impl Default for GeneratorConfig {
         Self {
             output_formats: vec![OutputFormat::KicadSymbols, OutputFormat::KicadFootprints],
             manufacturers: vec!["Vishay".to_string()],
-            decades: vec![1, 10, 100, 1000, 10000, 100000],
+            value_range: crate::ValueRange::default(),
+            min_power: None,
+            distributor: None,
+            output_dir: None,
+            symbol_style: "european".to_string(),
+            altium_file_pattern: crate::ecs::ALTIUM_FILE_PATTERN.to_string(),
+            settings: crate::settings::Settings::default(),
         }
     }
 }
 
+impl GeneratorConfig {
+    /// Symbol style of each package. A style that does not parse is kept
+    /// as it is for every package.
+    pub fn symbol_styles(&self) -> crate::kicad_symbol::ResistorStyles {
+        self.symbol_style.parse().unwrap_or_else(|_| crate::kicad_symbol::ResistorStyles {
+            default: self.symbol_style.clone(),
+            packages: Default::default(),
+        })
+    }
+}
+
 /// Cache for E-series values to avoid recalculation
 #[derive(Resource, Debug, Default)]
 pub struct ESeriesCache {
@@ -42,4 +83,80 @@ impl ESeriesCache {
             values
         }).clone()
     }
-}
\ No newline at end of file
+}
+/// KiCad symbols collected by `format_outputs`, with their value, by package
+#[derive(Resource, Debug, Default)]
+pub struct SymbolLibraryBuffer {
+    pub libraries: BTreeMap<String, Vec<(Resistance, KicadSymbol)>>,
+}
+
+/// Altium CSV rows collected by `format_outputs`, with their value, by package
+#[derive(Resource, Debug, Default)]
+pub struct AltiumCsvBuffer {
+    pub packages: BTreeMap<String, Vec<(Resistance, String)>>,
+}
+
+/// Packages `format_outputs` found that need a footprint
+#[derive(Resource, Debug, Default)]
+pub struct FootprintBuffer {
+    pub packages: BTreeSet<String>,
+}
+
+/// Files `write_outputs` wrote with their part count, or why it stopped
+#[derive(Resource, Debug, Default)]
+pub struct WrittenOutputs {
+    pub files: Vec<(PathBuf, usize)>,
+    pub error: Option<crate::AtlantixError>,
+}
+
+/// How far a pipeline run has come, sent by the generation systems to the
+/// receiver of [`crate::ecs::progress_channel`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Progress {
+    pub stage: crate::ecs::GenerationSet,
+    /// Package of the parts just done, in the write stage the file written
+    pub package: String,
+    /// Parts done in this stage, of `total`
+    pub done: usize,
+    pub total: usize,
+}
+
+impl Progress {
+    /// Share of the whole run done, from 0.0 to 1.0, each stage counting
+    /// the same
+    pub fn fraction(&self) -> f32 {
+        let stages = crate::ecs::GenerationSet::ALL.len() as f32;
+        let stage = if self.total == 0 { 1.0 } else { self.done as f32 / self.total as f32 };
+        (self.stage as usize as f32 + stage) / stages
+    }
+}
+
+/// Sends [`Progress`] while a receiver is connected, does nothing otherwise
+#[derive(Resource, Debug, Default)]
+pub struct ProgressReporter {
+    pub(crate) sender: Option<Sender<Progress>>,
+}
+
+impl ProgressReporter {
+    pub fn report(&self, stage: crate::ecs::GenerationSet, package: &str, done: usize, total: usize) {
+        if let Some(sender) = &self.sender {
+            // A front end that dropped its receiver no longer wants updates
+            let _ = sender.send(Progress { stage, package: package.to_string(), done, total });
+        }
+    }
+}
+
+/// Stops a pipeline run from another thread. The systems check it between
+/// parts, so a cancelled run ends at the next part and writes nothing.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
@@ -1,54 +1,170 @@
 use bevy_ecs::prelude::*;
 use crate::ecs::components::*;
 use crate::ecs::resources::*;
+use crate::ecs::GenerationSet;
+use std::collections::{HashMap, HashSet};
 
 /// Generate E-series values for resistors
+///
+/// Templates of several series for one package, such as E24 5% jellybeans
+/// next to E96 1% precision parts, end up in one symbol library. Part names
+/// then carry the tolerance, `R0603_1.00K_5%` and `R0603_1.00K_1%`, and a
+/// value two templates share in the same tolerance is spawned once.
+///
+/// ```
+/// use component::ecs::{self, components::*, resources::*};
+/// use component::ValueRange;
+///
+/// let mut world = ecs::build_resistor_world();
+/// world.insert_resource(GeneratorConfig { value_range: ValueRange::parse(Some("1K"), Some("9.99K")).unwrap(), ..Default::default() });
+/// world.insert_resource(ESeriesCache::default());
+/// let package = Package { name: "0603".into(), imperial: "0603".into(), metric: String::new() };
+/// world.spawn((ESeries(24), package.clone()));
+/// world.spawn((ESeries(96), package.clone()));
+/// world.spawn((ESeries(48), package, Tolerance("1%".into())));
+/// ecs::run_generation_pipeline(&mut world);
+///
+/// let symbols = &world.resource::<SymbolLibraryBuffer>().libraries["0603"];
+/// assert_eq!(symbols.len(), 24 + 96);
+/// let names: Vec<&str> = symbols.iter().map(|(_, symbol)| symbol.name.as_str()).collect();
+/// assert!(names.contains(&"R0603_1.00K_5%") && names.contains(&"R0603_1.00K_1%"));
+/// ```
 pub fn generate_eseries_values(
     mut commands: Commands,
     config: Res<GeneratorConfig>,
     mut eseries_cache: ResMut<ESeriesCache>,
-    query: Query<(Entity, &ESeries, &Package), Without<ResistorValue>>,
+    progress: Res<ProgressReporter>,
+    cancel: Res<CancelToken>,
+    query: Query<(Entity, &ESeries, &Package, Option<&Tolerance>), Without<ResistorValue>>,
 ) {
-    for (entity, series, package) in &query {
-        let base_values = eseries_cache.get_or_calculate(series.0);
-        
-        // Generate values for all decades
-        for decade in &config.decades {
-            for base_value in &base_values {
-                let ohms = base_value * (*decade as f64);
-                let formatted = format_resistance(ohms);
-                
+    config.settings.scope(|| {
+        // Values of every template first, so progress knows the total
+        let mut templates = Vec::new();
+        for (entity, series, package, tolerance) in &query {
+            // Remove the template entity, its parts replace it
+            commands.entity(entity).despawn();
+            let power = get_power_from_package(&package.name);
+            if config.min_power.is_some_and(|min| power < min) {
+                continue;
+            }
+            let base_values = eseries_cache.get_or_calculate(series.0);
+            // A Tolerance on the template, e.g. 0.1% for thin film, overrides the series
+            let tolerance = tolerance.cloned().unwrap_or_else(|| Tolerance(get_tolerance_from_series(series.0)));
+            // Generate values for the decades of the range, skipping values that round to one already spawned
+            let (values, _duplicates) = crate::estimate::unique_values(&base_values, &config.value_range);
+            templates.push((package, tolerance, power, values));
+        }
+
+        // Packages with parts of several tolerances, whose names need them
+        let mut tolerances: HashMap<&str, HashSet<&str>> = HashMap::new();
+        for (package, tolerance, _, _) in &templates {
+            tolerances.entry(package.name.as_str()).or_default().insert(tolerance.0.as_str());
+        }
+        let combined: HashSet<String> = tolerances
+            .into_iter()
+            .filter(|(_, tolerances)| tolerances.len() > 1)
+            .map(|(package, _)| package.to_string())
+            .collect();
+
+        let total = templates.iter().map(|(_, _, _, values)| values.len()).sum();
+        let mut done = 0;
+        let mut spawned = HashSet::new();
+        for (package, tolerance, power, values) in templates {
+            if cancel.is_cancelled() {
+                return;
+            }
+            log::debug!("Spawning {} {} parts", values.len(), package.name);
+            let in_name = combined.contains(&package.name).then_some(tolerance.0.as_str());
+            for resistance in values {
+                done += 1;
+                if !spawned.insert((package.name.clone(), tolerance.0.clone(), resistance)) {
+                    continue;
+                }
+                let formatted = resistance.label();
+            
                 // Spawn a new resistor entity for each value
                 commands.spawn(ResistorBundle {
-                    value: ResistorValue { ohms, formatted: formatted.clone() },
+                    value: ResistorValue { resistance, formatted: formatted.clone() },
                     package: package.clone(),
-                    tolerance: Tolerance(get_tolerance_from_series(series.0)),
-                    power: PowerRating(get_power_from_package(&package.name)),
+                    tolerance: tolerance.clone(),
+                    power: PowerRating(power),
                     description: Description(String::new()), // Will be filled by another system
-                    part_number: PartNumber(format!("R{}_{}", package.name, formatted)),
+                    part_number: PartNumber(crate::resistor_name(&package.name, resistance, in_name)),
                     manufacturers: ManufacturerParts::default(),
                 });
             }
+            progress.report(GenerationSet::Spawn, &package.name, done, total);
         }
-        
-        // Remove the template entity
-        commands.entity(entity).despawn();
-    }
+    })
+}
+
+/// Expand ferrite bead templates into one entity per impedance
+pub fn generate_ferrite_beads(
+    mut commands: Commands,
+    query: Query<(Entity, &FerriteTemplate, &Package)>,
+    config: Res<GeneratorConfig>,
+) {
+    config.settings.scope(|| {
+        for (entity, template, package) in &query {
+            for impedance in &template.impedances {
+                let Some(part) = crate::ferrite::ferrite_part(&package.name, *impedance) else {
+                    continue;
+                };
+                commands.spawn(FerriteBundle {
+                    impedance: FerriteImpedance(part.impedance_ohms),
+                    ratings: FerriteRatings {
+                        rated_current: part.rated_current,
+                        dcr_mohm: part.dcr_mohm,
+                    },
+                    package: package.clone(),
+                    description: Description(format!(
+                        "FERRITE BEAD SMT {} @ 100MHz, {}, {}A, DCR {}mOhm",
+                        part.value, package.name, part.rated_current, part.dcr_mohm
+                    )),
+                    part_number: PartNumber(part.name),
+                    manufacturers: ManufacturerParts(vec![
+                        ManufacturerPart {
+                            manufacturer: "Murata".to_string(),
+                            mpn: part.murata_mpn.clone(),
+                            distributor: "Digikey".to_string(),
+                            distributor_pn: part.murata_mpn,
+                            other_suppliers: Vec::new(),
+                        },
+                        ManufacturerPart {
+                            manufacturer: "TDK".to_string(),
+                            mpn: part.tdk_mpn.clone(),
+                            distributor: "Digikey".to_string(),
+                            distributor_pn: part.tdk_mpn,
+                            other_suppliers: Vec::new(),
+                        },
+                    ]),
+                });
+            }
+
+            // Remove the template entity
+            commands.entity(entity).despawn();
+        }
+    })
 }
 
 /// Assign package-specific attributes
 pub fn assign_package_attributes(
     mut query: Query<(&mut Description, &ResistorValue, &Package, &Tolerance, &PowerRating), Added<ResistorValue>>,
+    progress: Res<ProgressReporter>,
+    cancel: Res<CancelToken>,
+    config: Res<GeneratorConfig>,
 ) {
-    for (mut description, value, package, tolerance, power) in &mut query {
-        description.0 = format!(
-            "RES SMT {}ohms, {}, {}, {}",
-            value.formatted,
-            package.name,
-            tolerance.0,
-            power.0
-        );
-    }
+    config.settings.scope(|| {
+        let total = query.iter().count();
+        for (done, (mut description, value, package, tolerance, power)) in (1..).zip(&mut query) {
+            if cancel.is_cancelled() {
+                return;
+            }
+            let ratings = crate::memo::ratings(&package.name, &tolerance.0, power.0);
+            description.0 = format!("RES SMT {}ohms, {}", value.formatted, ratings.description);
+            progress.report(GenerationSet::Attributes, &package.name, done, total);
+        }
+    })
 }
 
 /// Calculate tolerances based on E-series
@@ -64,216 +180,283 @@ pub fn calculate_tolerances(
 
 /// Generate manufacturer-specific part numbers
 pub fn generate_manufacturer_parts(
-    mut query: Query<(&mut ManufacturerParts, &ResistorValue, &Package)>,
+    mut query: Query<(&mut ManufacturerParts, &ResistorValue, &Package, &Tolerance)>,
     config: Res<GeneratorConfig>,
+    progress: Res<ProgressReporter>,
+    cancel: Res<CancelToken>,
 ) {
-    for (mut mfr_parts, value, package) in &mut query {
-        let mut parts = Vec::new();
+    config.settings.scope(|| {
+        let total = query.iter().count();
+        for (done, (mut mfr_parts, value, package, tolerance)) in (1..).zip(&mut query) {
+            if cancel.is_cancelled() {
+                return;
+            }
+            let mut parts = Vec::new();
         
-        for manufacturer in &config.manufacturers {
-            match manufacturer.as_str() {
-                "Vishay" => {
-                    parts.push(ManufacturerPart {
-                        manufacturer: "Vishay".to_string(),
-                        mpn: generate_vishay_mpn(&value.ohms, &package.name),
-                        distributor: "Digikey".to_string(),
-                        distributor_pn: generate_vishay_digikey_pn(&value.formatted, &package.name),
-                    });
+            // Any manufacturer in the registry, including custom schemes
+            for name in &config.manufacturers {
+                let Some(manufacturer) = crate::manufacturer::lookup(name) else {
+                    continue;
+                };
+                if !crate::availability::available(manufacturer.name(), &package.name, &tolerance.0, value.resistance) {
+                    continue;
                 }
-                "Yageo" => {
-                    parts.push(ManufacturerPart {
-                        manufacturer: "Yageo".to_string(),
-                        mpn: generate_yageo_mpn(&value.ohms, &package.name),
-                        distributor: "Mouser".to_string(),
-                        distributor_pn: generate_yageo_mouser_pn(&value.formatted, &package.name),
-                    });
+                let mpn = crate::memo::mpn(&manufacturer, &package.name, value.resistance, &tolerance.0, false, || {
+                    manufacturer.resistor_mpn(&package.name, value.resistance, &tolerance.0)
+                });
+                let Some(mpn) = mpn else {
+                    continue;
+                };
+                // Configured suppliers replace the single distributor
+                let mut suppliers = crate::distributor::suppliers();
+                if suppliers.is_empty() {
+                    suppliers.push(config.distributor.unwrap_or_else(|| manufacturer.preferred_distributor()));
                 }
-                "KOA" => {
-                    parts.push(ManufacturerPart {
-                        manufacturer: "KOA Speer".to_string(),
-                        mpn: generate_koa_mpn(&value.ohms, &package.name),
-                        distributor: "Digikey".to_string(),
-                        distributor_pn: generate_koa_digikey_pn(&value.ohms, &package.name),
-                    });
+                let mut suppliers = suppliers.into_iter().map(|distributor| {
+                    let distributor_pn = manufacturer
+                        .distributor_pn(distributor, &package.name, value.resistance, &tolerance.0)
+                        .unwrap_or_default();
+                    (distributor.to_string(), distributor_pn)
+                });
+                let (distributor, distributor_pn) = suppliers.next().unwrap_or_default();
+                parts.push(ManufacturerPart {
+                    manufacturer: manufacturer.name().to_string(),
+                    mpn,
+                    distributor,
+                    distributor_pn,
+                    other_suppliers: suppliers.collect(),
+                });
+            }
+            // The preferred source first, as the sourcing policy ranks them
+            crate::sourcing::rank(&mut parts, |part| Some(part.mpn.as_str()));
+            mfr_parts.0 = parts;
+            progress.report(GenerationSet::Parts, &package.name, done, total);
+        }
+    })
+}
+
+/// Run the part hook of the settings on every resistor, see [`crate::hook`].
+/// Parts it drops are despawned before any output is formatted. Its edits
+/// go back into the components, and the symbol it kept is stored as
+/// [`HookedSymbol`] for [`format_outputs`].
+#[allow(clippy::type_complexity)]
+pub fn apply_part_hook(
+    mut query: Query<(Entity, &mut ResistorValue, &Package, &Tolerance, &mut Description, &mut PartNumber, &mut ManufacturerParts)>,
+    config: Res<GeneratorConfig>,
+    mut commands: Commands,
+) {
+    config.settings.scope(|| {
+        if config.settings.hook.is_none() {
+            return;
+        }
+        let styles = config.symbol_styles();
+        for (entity, mut value, package, tolerance, mut description, mut part_number, mut mfr_parts) in &mut query {
+            let outcome = crate::hook::outcome(|| {
+                kicad_symbol(styles.for_package(&package.name), &value, package, tolerance, &description, &part_number, &mfr_parts)
+            });
+            let Some(outcome) = outcome else {
+                commands.entity(entity).despawn();
+                continue;
+            };
+            let edit = |field: &mut String, changed: Option<&str>| {
+                if let Some(changed) = changed {
+                    *field = changed.to_string();
                 }
-                _ => {}
+            };
+            edit(&mut part_number.0, outcome.changed(|symbol| &symbol.name));
+            edit(&mut value.formatted, outcome.changed(|symbol| &symbol.value));
+            edit(&mut description.0, outcome.changed(|symbol| &symbol.description));
+            if let Some(primary) = mfr_parts.0.first_mut() {
+                edit(&mut primary.manufacturer, outcome.changed(|symbol| &symbol.manufacturer));
+                edit(&mut primary.mpn, outcome.changed(|symbol| &symbol.mpn));
+                edit(&mut primary.distributor, outcome.changed(|symbol| &symbol.supplier));
+                edit(&mut primary.distributor_pn, outcome.changed(|symbol| &symbol.supplier_pn));
+            }
+            if let Some(symbol) = outcome.into_symbol() {
+                commands.entity(entity).insert(HookedSymbol(symbol));
             }
         }
-        
-        mfr_parts.0 = parts;
-    }
+    })
 }
 
-/// Format outputs based on configuration
+/// Format outputs based on configuration. Symbols, Altium rows and the
+/// packages needing footprints are collected in the output buffers for
+/// [`write_outputs`]; Altium rows are also kept on the entity as [`AltiumData`].
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub fn format_outputs(
-    query: Query<(&ResistorValue, &Package, &Description, &PartNumber, &ManufacturerParts)>,
+    query: Query<(
+        Entity,
+        &ResistorValue,
+        &Package,
+        &Tolerance,
+        &Description,
+        &PartNumber,
+        &ManufacturerParts,
+        &PowerRating,
+        Option<&HookedSymbol>,
+    )>,
     config: Res<GeneratorConfig>,
-    _commands: Commands,
+    mut symbols: ResMut<SymbolLibraryBuffer>,
+    mut altium: ResMut<AltiumCsvBuffer>,
+    mut footprints: ResMut<FootprintBuffer>,
+    progress: Res<ProgressReporter>,
+    cancel: Res<CancelToken>,
+    mut commands: Commands,
 ) {
-    for (value, package, description, part_number, mfr_parts) in &query {
-        for format in &config.output_formats {
-            match format {
-                OutputFormat::KicadSymbols => {
-                    // Generate KiCad symbol with manufacturer fields
-                    let _symbol = generate_kicad_symbol_with_mfrs(
-                        &part_number.0,
-                        &value.formatted,
-                        &format!("Atlantix_Resistors:R_{}_{}", package.imperial, package.metric),
-                        &description.0,
-                        &mfr_parts.0,
-                    );
-                    // In a real implementation, we'd collect these for file output
-                }
-                OutputFormat::Altium => {
-                    // Generate Altium CSV line
-                    if let Some(first_mfr) = mfr_parts.0.first() {
-                        let _csv_line = format!(
-                            "{},{},{},{},{},{},{},Atlantix_R.SchLib,Res1,Atlantix_R.PcbLib,RES{},Atlantix EDA,=Description",
-                            part_number.0,
-                            description.0,
-                            value.formatted,
-                            package.name,
-                            get_power_from_package(&package.name),
-                            first_mfr.distributor,
-                            first_mfr.distributor_pn,
-                            package.name
-                        );
-                        // In a real implementation, we'd collect these for file output
+    config.settings.scope(|| {
+        // Every run formats all parts again
+        symbols.libraries.clear();
+        altium.packages.clear();
+        footprints.packages.clear();
+        let limits = crate::limits::limits("altium");
+        let styles = config.symbol_styles();
+        let total = query.iter().count();
+        for (done, (entity, value, package, tolerance, description, part_number, mfr_parts, power, hooked)) in (1..).zip(&query) {
+            if cancel.is_cancelled() {
+                return;
+            }
+            for format in &config.output_formats {
+                match format {
+                    OutputFormat::KicadSymbols => {
+                        let mut symbol = match hooked {
+                            Some(hooked) => hooked.0.clone(),
+                            None => kicad_symbol(styles.for_package(&package.name), value, package, tolerance, description, part_number, mfr_parts),
+                        };
+                        // Parts the hook dropped are gone by now, only kept ones get an IPN
+                        crate::ipn::assign_symbol("resistor", &package.name, &mut symbol);
+                        symbols.libraries.entry(package.name.clone()).or_default().push((value.resistance, symbol));
+                    }
+                    OutputFormat::KicadFootprints => {
+                        footprints.packages.insert(package.name.clone());
+                    }
+                    OutputFormat::Altium => {
+                        if let Some(first_mfr) = mfr_parts.0.first() {
+                            let datasheet = crate::datasheet::url(&first_mfr.manufacturer, &first_mfr.mpn).unwrap_or_default();
+                            let mut csv_line = String::new();
+                            crate::altium::Row {
+                                part: &part_number.0,
+                                description: &description.0,
+                                value: &value.formatted,
+                                case: &package.name,
+                                power: &crate::memo::ratings(&package.name, &tolerance.0, power.0).fraction,
+                                supplier: &first_mfr.distributor,
+                                supplier_pn: &first_mfr.distributor_pn,
+                                footprint_ref: &format!("RES{}", package.name),
+                                aec_q200: false,
+                                datasheet: &datasheet,
+                                other_suppliers: &first_mfr.other_suppliers,
+                            }
+                            .push_to(&mut csv_line, &limits);
+                            altium.packages.entry(package.name.clone()).or_default().push((value.resistance, csv_line.clone()));
+                            commands.entity(entity).insert(AltiumData { csv_line });
+                        }
                     }
                 }
-                _ => {}
             }
+            progress.report(GenerationSet::Format, &package.name, done, total);
         }
-    }
+    })
 }
 
-// Helper functions
-fn format_resistance(ohms: f64) -> String {
-    match ohms {
-        o if o < 10.0 => format!("{:.2}", o),
-        o if o < 100.0 => format!("{:.1}", o),
-        o if o < 1000.0 => format!("{:.0}", o),
-        o if o < 10000.0 => format!("{:.2}K", o / 1000.0),
-        o if o < 100000.0 => format!("{:.1}K", o / 1000.0),
-        o if o < 1000000.0 => format!("{:.0}K", o / 1000.0),
-        _ => format!("{:.2}M", ohms / 1000000.0),
-    }
+/// Write the collected outputs into [`GeneratorConfig::output_dir`], laid
+/// out like the examples of the `Resistor` path, see [`crate::ecs::write_outputs`].
+/// Does nothing without an output directory. A cancelled run writes
+/// nothing, and a run cancelled while writing removes what it wrote.
+pub fn write_outputs(
+    config: Res<GeneratorConfig>,
+    symbols: Res<SymbolLibraryBuffer>,
+    altium: Res<AltiumCsvBuffer>,
+    footprints: Res<FootprintBuffer>,
+    mut written: ResMut<WrittenOutputs>,
+    progress: Res<ProgressReporter>,
+    cancel: Res<CancelToken>,
+) {
+    config.settings.scope(|| {
+        let cancelled = WrittenOutputs { files: Vec::new(), error: Some(crate::AtlantixError::Cancelled) };
+        if cancel.is_cancelled() {
+            *written = cancelled;
+            return;
+        }
+        let Some(output_dir) = &config.output_dir else {
+            return;
+        };
+        *written = match crate::ecs::write_outputs(output_dir, &symbols, &altium, &footprints, &config.altium_file_pattern) {
+            Ok(files) if cancel.is_cancelled() => {
+                // No library of a half finished run is left behind
+                for (path, _) in &files {
+                    let _ = std::fs::remove_file(path);
+                }
+                log::warn!("Generation cancelled, removed {} files", files.len());
+                cancelled
+            }
+            Ok(files) => {
+                let total = files.iter().map(|(_, parts)| parts).sum();
+                let mut done = 0;
+                for (path, parts) in &files {
+                    log::info!("Wrote {} ({} parts)", path.display(), parts);
+                    done += parts;
+                    progress.report(GenerationSet::Write, &path.display().to_string(), done, total);
+                }
+                WrittenOutputs { files, error: None }
+            }
+            Err(e) => {
+                log::error!("{}", e);
+                WrittenOutputs { files: Vec::new(), error: Some(e) }
+            }
+        };
+    })
 }
 
+// Helper functions
 fn get_tolerance_from_series(series: usize) -> String {
-    match series {
-        192 => "0.5%",
-        96 => "1%",
-        48 => "2%",
-        24 => "5%",
-        12 => "10%",
-        6 => "20%",
-        _ => "1%",
-    }.to_string()
-}
-
-fn get_power_from_package(package: &str) -> String {
-    match package {
-        "0201" => "1/20W",
-        "0402" => "1/16W",
-        "0603" => "1/10W",
-        "0805" => "1/8W",
-        "1206" => "1/4W",
-        "1210" => "1/2W",
-        "2010" => "3/4W",
-        "2512" => "1W",
-        _ => "1/10W",
-    }.to_string()
-}
-
-fn generate_vishay_mpn(ohms: &f64, package: &str) -> String {
-    // Simplified - real implementation would be more complex
-    format!("CRCW{}{:04.0}FKEA", package, ohms)
-}
-
-fn generate_vishay_digikey_pn(formatted: &str, _package: &str) -> String {
-    format!("541-{}CT-ND", formatted)
+    crate::tolerance::for_series(series).to_string()
 }
 
-fn generate_yageo_mpn(ohms: &f64, package: &str) -> String {
-    format!("RC{}FR-07{}L", package, format_resistance(*ohms))
+fn get_power_from_package(package: &str) -> crate::Power {
+    crate::chip_resistor_power(package).unwrap_or(crate::Power(100))
 }
 
-fn generate_yageo_mouser_pn(formatted: &str, package: &str) -> String {
-    format!("603-RC{}FR-07{}", package, formatted)
-}
-
-fn generate_koa_mpn(ohms: &f64, package: &str) -> String {
-    // KOA Speer part numbering: RK73H[size][tolerance]TD[value][tolerance_letter]
-    // RK73H = Thick film chip resistor series
-    // Size codes: 1E = 0402, 1J = 0603, 2A = 0805, 2B = 1206, 2E = 1210, 3A = 2010, 3E = 2512
-    let size_code = match package {
-        "0402" => "1E",
-        "0603" => "1J",
-        "0805" => "2A",
-        "1206" => "2B",
-        "1210" => "2E",
-        "2010" => "3A",
-        "2512" => "3E",
-        _ => "1J",
-    };
-    
-    // Convert resistance to KOA format (4 digits)
-    let value_code = format_koa_resistance(*ohms);
-    
-    // TTD = Thin Thick Film, F = 1% tolerance
-    format!("RK73H{}TTD{}F", size_code, value_code)
-}
-
-fn generate_koa_digikey_pn(ohms: &f64, package: &str) -> String {
-    // Generate Digikey part number for KOA parts
-    let mpn = generate_koa_mpn(ohms, package);
-    format!("{}-ND", mpn)
-}
-
-fn format_koa_resistance(ohms: f64) -> String {
-    // KOA uses a 4-digit code system
-    // Examples: 1001 = 1.00K, 4701 = 4.70K, 1000 = 100Ω, 10R0 = 10.0Ω
-    match ohms {
-        o if o < 10.0 => {
-            // For values less than 10 ohms, use R notation
-            let value = (o * 10.0).round() as i32;
-            format!("{:02}R{}", value / 10, value % 10)
-        }
-        o if o < 100.0 => {
-            // 10-99 ohms: multiply by 10 to get 3 digits + 0
-            format!("{:03}0", (o * 10.0).round() as i32)
-        }
-        o if o < 1000.0 => {
-            // 100-999 ohms: use value + 1 as multiplier
-            format!("{:03}1", o.round() as i32)
-        }
-        o if o < 10000.0 => {
-            // 1K-9.99K: divide by 10
-            format!("{:03}2", (o / 10.0).round() as i32)
-        }
-        o if o < 100000.0 => {
-            // 10K-99.9K: divide by 100
-            format!("{:03}3", (o / 100.0).round() as i32)
-        }
-        o if o < 1000000.0 => {
-            // 100K-999K: divide by 1000
-            format!("{:03}4", (o / 1000.0).round() as i32)
+/// The KiCad symbol of a resistor, with the fields the `Resistor` path writes
+fn kicad_symbol(
+    symbol_style: &str,
+    value: &ResistorValue,
+    package: &Package,
+    tolerance: &Tolerance,
+    description: &Description,
+    part_number: &PartNumber,
+    mfr_parts: &ManufacturerParts,
+) -> crate::kicad_symbol::KicadSymbol {
+    let (first, second) = crate::kicad_footprint::get_package_specs(&package.imperial)
+        .map(|spec| crate::package_naming::footprint_codes(&spec))
+        .unwrap_or_else(|| (package.imperial.clone(), package.metric.clone()));
+    let footprint = format!("Atlantix_Resistors:R_{}_{}{}", first, second, crate::kicad_footprint::density().suffix());
+    let mut symbol = crate::kicad_symbol::KicadSymbol::new(part_number.0.clone(), value.formatted.clone(), footprint, symbol_style)
+        .with_tolerance(&tolerance.0);
+    if let Some((primary, alternates)) = mfr_parts.0.split_first() {
+        let supplier_url = |distributor: &str, part_number: &str| {
+            distributor.parse::<crate::Distributor>().map(|d| d.supplier_url(part_number)).unwrap_or_default()
+        };
+        symbol = symbol.with_manufacturer_info(
+            primary.manufacturer.clone(),
+            primary.mpn.clone(),
+            primary.distributor.clone(),
+            primary.distributor_pn.clone(),
+            supplier_url(&primary.distributor, &primary.distributor_pn),
+        );
+        symbol.datasheet = crate::datasheet::field(&primary.manufacturer, &primary.mpn);
+        for (number, alternate) in (2..).zip(alternates) {
+            symbol.properties.push((format!("Manufacturer {}", number), alternate.manufacturer.clone()));
+            symbol.properties.push((format!("MPN {}", number), alternate.mpn.clone()));
         }
-        _ => {
-            // 1M and above: divide by 10000
-            format!("{:03}5", (ohms / 10000.0).round() as i32)
+        for (number, (distributor, part_number)) in (2..).zip(&primary.other_suppliers) {
+            symbol.properties.push((format!("Supplier {}", number), distributor.clone()));
+            symbol.properties.push((format!("SupplierPN {}", number), part_number.clone()));
+            symbol.properties.push((format!("SupplierURL {}", number), supplier_url(distributor, part_number)));
         }
     }
+    if let Some(marking) = crate::marking::marking(&package.name, value.resistance, &tolerance.0) {
+        symbol.properties.push(("Marking".to_string(), marking));
+    }
+    symbol.description = description.0.clone();
+    symbol
 }
-
-fn generate_kicad_symbol_with_mfrs(
-    name: &str,
-    _value: &str,
-    _footprint: &str,
-    _description: &str,
-    _manufacturers: &[ManufacturerPart],
-) -> String {
-    // Simplified - would generate full KiCad symbol with manufacturer fields
-    format!("(symbol \"{}\" ...)", name)
-}
\ No newline at end of file
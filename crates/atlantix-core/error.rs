@@ -0,0 +1,86 @@
+//! Errors of the library generators.
+//!
+//! Every fallible public function returns [`AtlantixError`], whose message
+//! names what to fix: the file that could not be written, the value that did
+//! not parse and the values that would have been accepted.
+//!
+//! ```
+//! use component::{tolerance, AtlantixError};
+//!
+//! let err = tolerance::parse("3").unwrap_err();
+//! assert!(matches!(err, AtlantixError::Parse(_)));
+//! assert_eq!(err.to_string(), "Unknown tolerance '3'. Expected one of: 0.05%, 0.1%, 0.25%, 0.5%, 1%, 2%, 5%, 10%, 20%, 50%");
+//!
+//! let err = AtlantixError::invalid_series("E7", ["E12", "E24"]);
+//! assert_eq!(err.to_string(), "Unknown E-series 'E7'. Expected one of: E12, E24");
+//! ```
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, thiserror::Error)]
+pub enum AtlantixError {
+    /// A file or directory could not be read or written
+    #[error("Failed to {action} {}: {source}", path.display())]
+    Io {
+        action: &'static str,
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    /// Text that does not parse, such as a value, a tolerance or an imported file
+    #[error("{0}")]
+    Parse(String),
+    /// A package the generators have no data for
+    #[error("Unknown {kind} package '{package}'. Expected one of: {}", expected.join(", "))]
+    InvalidPackage {
+        kind: String,
+        package: String,
+        expected: Vec<String>,
+    },
+    /// An E-series other than E3 to E192
+    #[error("Unknown E-series '{series}'. Expected one of: {}", expected.join(", "))]
+    InvalidSeries { series: String, expected: Vec<String> },
+    /// A distributor or data service refused or failed a request
+    #[error("{service} request failed: {message}")]
+    Api { service: String, message: String },
+    /// A request the library cannot carry out, e.g. registering a family twice
+    #[error("{0}")]
+    Invalid(String),
+    /// The run was cancelled before it finished, its outputs were removed
+    #[error("Generation cancelled")]
+    Cancelled,
+}
+
+impl AtlantixError {
+    /// I/O error on `path` while trying to `action` it, e.g. "write"
+    pub fn io(action: &'static str, path: impl AsRef<Path>, source: io::Error) -> Self {
+        AtlantixError::Io { action, path: path.as_ref().to_path_buf(), source }
+    }
+
+    pub fn invalid_package<S: ToString>(kind: &str, package: &str, expected: impl IntoIterator<Item = S>) -> Self {
+        AtlantixError::InvalidPackage {
+            kind: kind.to_string(),
+            package: package.to_string(),
+            expected: expected.into_iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    pub fn invalid_series<S: ToString>(series: &str, expected: impl IntoIterator<Item = S>) -> Self {
+        AtlantixError::InvalidSeries {
+            series: series.to_string(),
+            expected: expected.into_iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    pub fn api(service: &str, message: impl ToString) -> Self {
+        AtlantixError::Api { service: service.to_string(), message: message.to_string() }
+    }
+}
+
+/// Front ends reporting errors as text keep using `?`
+impl From<AtlantixError> for String {
+    fn from(error: AtlantixError) -> Self {
+        error.to_string()
+    }
+}
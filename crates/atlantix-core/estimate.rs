@@ -0,0 +1,179 @@
+//! Generation estimates.
+//!
+//! Front ends preview the size of a run before starting it. Multiplying
+//! values × decades × packages overcounts: packages rated below the power
+//! floor are pruned and values that round to the same resistance collapse
+//! into one part. [`estimate`] applies the generators' own rules, sizes each
+//! exporter's output by rendering a representative part and derives the run
+//! time from calibration benchmarks.
+
+use crate::ecs::resources::{ESeriesCache, GeneratorConfig, OutputFormat};
+use crate::kicad_footprint::KicadFootprint;
+use crate::kicad_symbol::KicadSymbolLib;
+use crate::{Resistance, ValueRange};
+use std::collections::HashSet;
+use std::time::Duration;
+
+/// Calibrated costs in nanoseconds, timed on a release build: the ECS
+/// pipeline per part, the Resistor exporters per E96 0603 part (file writes
+/// included) and one footprint file
+const NANOS_PER_PART: u64 = 3_000;
+const NANOS_PER_SYMBOL: u64 = 5_200;
+const NANOS_PER_CSV_ROW: u64 = 2_500;
+const NANOS_PER_FOOTPRINT: u64 = 85_000;
+
+/// Value the output sizes are sampled with, four characters like most of the series
+const SAMPLE_VALUE: f64 = 4.99;
+const SAMPLE_DECADE: f64 = 1_000.0;
+
+/// Expected outcome of a resistor generation run
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    /// Parts that will be generated, after pruning and deduplication
+    pub parts: usize,
+    /// Packages that produce parts
+    pub packages: Vec<String>,
+    /// Packages skipped because they are rated below `min_power`
+    pub pruned_packages: Vec<String>,
+    /// Values per package that round to a resistance already generated
+    pub duplicates: usize,
+    /// Estimated output size in bytes for each configured exporter
+    pub file_sizes: Vec<(OutputFormat, u64)>,
+    pub generation_time: Duration,
+}
+
+impl Estimate {
+    /// Total size of all outputs in bytes
+    pub fn total_size(&self) -> u64 {
+        self.file_sizes.iter().map(|(_, size)| size).sum()
+    }
+}
+
+/// Distinct resistances for a set of base values within `range`, in
+/// generation order, together with the number of duplicates dropped.
+pub(crate) fn unique_values(base_values: &[f64], range: &ValueRange) -> (Vec<Resistance>, usize) {
+    let mut seen = HashSet::new();
+    let mut values = Vec::new();
+    let mut duplicates = 0;
+    for decade in range.decades() {
+        for base_value in base_values {
+            let resistance = Resistance::from_ohms(base_value * decade);
+            if !range.contains(resistance) || !crate::is_supported(resistance) {
+                continue;
+            }
+            if seen.insert(resistance) {
+                values.push(resistance);
+            } else {
+                duplicates += 1;
+            }
+        }
+    }
+    (values, duplicates)
+}
+
+/// Estimate the parts, output sizes and run time of generating an E-series
+/// over `packages` with `config`, without generating anything.
+///
+/// ```
+/// use component::ecs::resources::GeneratorConfig;
+/// use component::Power;
+///
+/// let config = GeneratorConfig { min_power: Some(Power(125)), ..Default::default() };
+/// let estimate = component::estimate::estimate(&config, 96, &["0402", "0805", "1206"]);
+/// assert_eq!(estimate.parts, 2 * 96 * 6);
+/// assert_eq!(estimate.pruned_packages, ["0402"]);
+/// assert!(estimate.total_size() > 0);
+/// ```
+pub fn estimate(config: &GeneratorConfig, series: usize, packages: &[&str]) -> Estimate {
+    let base_values = ESeriesCache::default().get_or_calculate(series);
+    let (values, duplicates) = unique_values(&base_values, &config.value_range);
+
+    let (kept, pruned): (Vec<&str>, Vec<&str>) = packages.iter().partition(|package| {
+        let power = crate::chip_resistor_power(package).unwrap_or(crate::Power(100));
+        config.min_power.is_none_or(|min| power >= min)
+    });
+    let parts = values.len() * kept.len();
+
+    let mut file_sizes = Vec::new();
+    let mut nanos = NANOS_PER_PART * parts as u64;
+    for format in &config.output_formats {
+        let (size, cost) = match format {
+            OutputFormat::KicadSymbols => {
+                let header = KicadSymbolLib::new().generate_library().len() as u64;
+                let symbols: u64 = kept
+                    .iter()
+                    .map(|package| {
+                        let lib = sample_resistor(series, package).kicad_symbol_library(&[SAMPLE_DECADE], "european");
+                        (lib.generate_library().len() as u64 - header) * values.len() as u64
+                    })
+                    .sum();
+                (header + symbols, NANOS_PER_SYMBOL * parts as u64)
+            }
+            OutputFormat::Altium => {
+                let rows: u64 = kept
+                    .iter()
+                    .map(|package| sample_resistor(series, package).generate(SAMPLE_DECADE).len() as u64 * values.len() as u64)
+                    .sum();
+                (rows, NANOS_PER_CSV_ROW * parts as u64)
+            }
+            OutputFormat::KicadFootprints => {
+                let footprints: Vec<u64> = kept
+                    .iter()
+                    .filter_map(|package| KicadFootprint::new_smd_resistor(package))
+                    .map(|footprint| footprint.generate_footprint().len() as u64)
+                    .collect();
+                (footprints.iter().sum(), NANOS_PER_FOOTPRINT * footprints.len() as u64)
+            }
+        };
+        file_sizes.push((format.clone(), size));
+        nanos += cost;
+    }
+
+    Estimate {
+        parts,
+        packages: kept.iter().map(|p| p.to_string()).collect(),
+        pruned_packages: pruned.iter().map(|p| p.to_string()).collect(),
+        duplicates: duplicates * kept.len(),
+        file_sizes,
+        generation_time: Duration::from_nanos(nanos),
+    }
+}
+
+fn sample_resistor(series: usize, package: &str) -> crate::Resistor {
+    crate::Resistor::with_values(series, package.to_string(), vec![SAMPLE_VALUE])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::components::{ESeries, Package, ResistorValue};
+
+    #[test]
+    fn matches_ecs_generation() {
+        let config = GeneratorConfig {
+            value_range: ValueRange::parse(Some("1"), Some("999")).unwrap(),
+            min_power: Some(crate::Power(100)),
+            ..Default::default()
+        };
+        let packages = ["0201", "0603", "1206"];
+
+        let mut world = crate::ecs::build_resistor_world();
+        world.insert_resource(config.clone());
+        world.insert_resource(ESeriesCache::default());
+        for package in packages {
+            world.spawn((
+                ESeries(24),
+                Package { name: package.into(), imperial: package.into(), metric: String::new() },
+            ));
+        }
+        crate::ecs::run_generation_pipeline(&mut world);
+        let generated = world.query::<&ResistorValue>().iter(&world).count();
+
+        let estimate = estimate(&config, 24, &packages);
+        assert_eq!(estimate.parts, generated);
+        assert_eq!(estimate.parts, 2 * 24 * 3);
+        assert_eq!(estimate.duplicates, 0);
+        assert_eq!(estimate.pruned_packages, ["0201"]);
+        assert_eq!(estimate.file_sizes.len(), 2);
+    }
+}
@@ -0,0 +1,32 @@
+extern crate component;
+use component::current_sense::CURRENT_SENSE_PACKAGES;
+use component::CurrentSense;
+use component::AtlantixError;
+
+fn main() -> Result<(), AtlantixError> {
+    println!("Generating KiCad current-sense resistor libraries...");
+
+    component::atomic::create_dir_all("outputs/kicad/symbols")?;
+    for kelvin in [false, true] {
+        for package in CURRENT_SENSE_PACKAGES {
+            // Not every package has a four-terminal part
+            let Some(shunt) = CurrentSense::new(package, kelvin) else {
+                continue;
+            };
+            let variant = if kelvin { "_kelvin" } else { "" };
+            let symbol_file = format!("outputs/kicad/symbols/current_sense_{}{}.kicad_sym", package, variant);
+            match shunt.generate_kicad_symbols(&symbol_file) {
+                Ok(()) => println!("Successfully generated {}", symbol_file),
+                Err(e) => eprintln!("Error generating symbols for {}: {}", package, e),
+            }
+        }
+
+        match CurrentSense::generate_kicad_footprints(&CURRENT_SENSE_PACKAGES, kelvin, "outputs/kicad/footprints.pretty") {
+            Ok(()) => println!("Successfully generated footprints"),
+            Err(e) => eprintln!("Error generating footprints: {}", e),
+        }
+    }
+
+    println!("KiCad current-sense library generation complete!");
+    Ok(())
+}
@@ -0,0 +1,48 @@
+extern crate bevy_ecs;
+extern crate component;
+
+use bevy_ecs::prelude::*;
+use component::ecs::{components::*, systems};
+use component::ferrite::{FERRITE_IMPEDANCES, FERRITE_PACKAGES};
+use component::FerriteBead;
+use component::AtlantixError;
+
+fn main() -> Result<(), AtlantixError> {
+    println!("Generating KiCad ferrite bead libraries...");
+
+    component::atomic::create_dir_all("outputs/kicad/symbols")?;
+    for package in FERRITE_PACKAGES {
+        let bead = FerriteBead::new(package, FERRITE_IMPEDANCES.to_vec()).expect("supported package");
+        let symbol_file = format!("outputs/kicad/symbols/ferrite_beads_{}.kicad_sym", package);
+        match bead.generate_kicad_symbols(&symbol_file) {
+            Ok(()) => println!("Successfully generated {}", symbol_file),
+            Err(e) => eprintln!("Error generating symbols for {}: {}", package, e),
+        }
+    }
+    match FerriteBead::generate_kicad_footprints(&FERRITE_PACKAGES, "outputs/kicad/footprints.pretty") {
+        Ok(()) => println!("Successfully generated footprints"),
+        Err(e) => eprintln!("Error generating footprints: {}", e),
+    }
+
+    // The same parts through the ECS pipeline
+    let mut world = World::new();
+    world.spawn((
+        FerriteTemplate { impedances: FERRITE_IMPEDANCES.to_vec() },
+        Package {
+            name: "0603".to_string(),
+            imperial: "0603".to_string(),
+            metric: "1608Metric".to_string(),
+        },
+    ));
+    let mut schedule = Schedule::default();
+    schedule.add_systems(systems::generate_ferrite_beads);
+    schedule.run(&mut world);
+
+    println!("\nECS generated ferrite beads:");
+    let mut query = world.query::<(&PartNumber, &Description, &ManufacturerParts)>();
+    for (part_num, desc, mfrs) in query.iter(&world) {
+        let mpns: Vec<&str> = mfrs.0.iter().map(|m| m.mpn.as_str()).collect();
+        println!("  {}: {} [{}]", part_num.0, desc.0, mpns.join(", "));
+    }
+    Ok(())
+}
@@ -0,0 +1,32 @@
+extern crate component;
+use component::diode::DIODE_PACKAGES;
+use component::{Diode, DiodeKind};
+use component::AtlantixError;
+
+fn main() -> Result<(), AtlantixError> {
+    println!("Generating KiCad diode libraries...");
+
+    component::atomic::create_dir_all("outputs/kicad/symbols")?;
+    for kind in DiodeKind::ALL {
+        for package in Diode::packages(kind) {
+            let diode = Diode::new(kind, package).expect("supported package");
+            let symbol_file = format!(
+                "outputs/kicad/symbols/diodes_{}_{}.kicad_sym",
+                kind.to_string().to_lowercase(),
+                package
+            );
+            match diode.generate_kicad_symbols(&symbol_file) {
+                Ok(()) => println!("Successfully generated {}", symbol_file),
+                Err(e) => eprintln!("Error generating {} symbols for {}: {}", kind, package, e),
+            }
+        }
+    }
+
+    match Diode::generate_kicad_footprints(&DIODE_PACKAGES, "outputs/kicad/footprints.pretty") {
+        Ok(()) => println!("Successfully generated footprints"),
+        Err(e) => eprintln!("Error generating footprints: {}", e),
+    }
+
+    println!("KiCad diode library generation complete!");
+    Ok(())
+}
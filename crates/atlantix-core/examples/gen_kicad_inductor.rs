@@ -0,0 +1,32 @@
+extern crate component;
+use component::inductor::{INDUCTOR_DECADES, INDUCTOR_PACKAGES};
+use component::Inductor;
+use component::AtlantixError;
+
+fn main() -> Result<(), AtlantixError> {
+    println!("Generating KiCad inductor libraries...");
+
+    component::atomic::create_dir_all("outputs/kicad/symbols")?;
+
+    for package in INDUCTOR_PACKAGES {
+        for shielded in [false, true] {
+            let inductor = Inductor::new(12, package, shielded).expect("supported package");
+            let variant = if shielded { "_shielded" } else { "" };
+            let symbol_file = format!("outputs/kicad/symbols/inductors_{}{}.kicad_sym", package, variant);
+
+            match inductor.generate_kicad_symbols(&INDUCTOR_DECADES, &symbol_file) {
+                Ok(()) => println!("Successfully generated {}", symbol_file),
+                Err(e) => eprintln!("Error generating symbols for {}: {}", package, e),
+            }
+        }
+    }
+
+    println!("Generating footprints...");
+    match Inductor::generate_kicad_footprints(&INDUCTOR_PACKAGES, "outputs/kicad/footprints.pretty") {
+        Ok(()) => println!("Successfully generated footprints"),
+        Err(e) => eprintln!("Error generating footprints: {}", e),
+    }
+
+    println!("KiCad inductor library generation complete!");
+    Ok(())
+}
@@ -0,0 +1,26 @@
+extern crate component;
+use component::led::LED_PACKAGES;
+use component::{Led, LedColor};
+use component::AtlantixError;
+
+fn main() -> Result<(), AtlantixError> {
+    println!("Generating KiCad LED libraries...");
+
+    component::atomic::create_dir_all("outputs/kicad/symbols")?;
+    for package in LED_PACKAGES {
+        let led = Led::new(package, LedColor::ALL.to_vec()).expect("supported package");
+        let symbol_file = format!("outputs/kicad/symbols/leds_{}.kicad_sym", package);
+        match led.generate_kicad_symbols(&symbol_file) {
+            Ok(()) => println!("Successfully generated {}", symbol_file),
+            Err(e) => eprintln!("Error generating symbols for {}: {}", package, e),
+        }
+    }
+
+    match Led::generate_kicad_footprints(&LED_PACKAGES, "outputs/kicad/footprints.pretty") {
+        Ok(()) => println!("Successfully generated footprints"),
+        Err(e) => eprintln!("Error generating footprints: {}", e),
+    }
+
+    println!("KiCad LED library generation complete!");
+    Ok(())
+}
@@ -1,15 +1,15 @@
 extern crate component;
-use std::fs;
+use component::AtlantixError;
 
-fn main() {
+fn main() -> Result<(), AtlantixError> {
     println!("Generating KiCad resistor libraries...");
     
-    let decades = vec![1, 10, 100, 1000, 10000, 100000];
+    let decades = component::STANDARD_DECADES.to_vec();
     let packages = vec!["0402", "0603", "0805", "1206"];
     
     // Create output directories
-    fs::create_dir_all("outputs/kicad/symbols").expect("Failed to create symbols directory");
-    fs::create_dir_all("outputs/kicad/footprints.pretty").expect("Failed to create footprints directory");
+    component::atomic::create_dir_all("outputs/kicad/symbols")?;
+    component::atomic::create_dir_all("outputs/kicad/footprints.pretty")?;
     
     // Generate symbols for each package
     for package in &packages {
@@ -18,7 +18,7 @@ fn main() {
         let mut resistor = component::Resistor::new(96, package.to_string());
         let symbol_file = format!("outputs/kicad/symbols/resistors_{}.kicad_sym", package);
         
-        match resistor.generate_kicad_symbols(decades.clone(), &symbol_file) {
+        match resistor.generate_kicad_symbols(decades.clone(), &symbol_file, "european") {
             Ok(()) => println!("Successfully generated {}", symbol_file),
             Err(e) => eprintln!("Error generating symbols for {}: {}", package, e),
         }
@@ -42,4 +42,5 @@ fn main() {
     println!("1. Copy symbol files to your KiCad project or global library");
     println!("2. Copy footprint .pretty directory to your KiCad footprint libraries");
     println!("3. Add libraries to your project in Symbol Library Manager and Footprint Library Manager");
+    Ok(())
 }
\ No newline at end of file
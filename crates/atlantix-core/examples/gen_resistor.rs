@@ -1,6 +1,11 @@
 extern crate component;
 extern crate clap;
 use clap::{Parser, ValueEnum};
+use component::kicad_footprint::{FootprintNaming, SolderProcess};
+use component::kicad_symbol::{style_glyph, ResistorStyles};
+use component::settings::Settings;
+use component::spice::SpiceModel;
+use component::{AtlantixError, Manufacturer};
 use std::fs;
 
 #[derive(Debug, Clone, ValueEnum, PartialEq)]
@@ -22,25 +27,114 @@ struct Args {
     #[arg(long, default_value = "0402,0603,0805,1206")]
     packages: String,
     
-    /// Output directory
+    /// Output directory, may contain {date}, {time}, {format} and {series},
+    /// e.g. "outputs/{date}/{format}" to keep runs side by side
     #[arg(long, default_value = "outputs")]
     output_dir: String,
+
+    /// Library file name without extension, may contain {package} and the
+    /// --output-dir placeholders. Defaults to Atlantix_R_{package} for kicad
+    /// and resistors_{package} for altium
+    #[arg(long)]
+    file_name: Option<String>,
     
-    /// E-series (24, 48, 96)
-    #[arg(long, default_value = "96")]
-    series: usize,
+    /// E-series (24, 48, 96, 192), defaults to 192 for thin film tolerances
+    /// and 96 otherwise
+    #[arg(long)]
+    series: Option<usize>,
+
+    /// Tolerance overriding the series default, e.g. 0.1 or 0.05 for thin
+    /// film precision parts (Vishay TNPW, Susumu RG)
+    #[arg(long)]
+    tolerance: Option<String>,
+
+    /// Only AEC-Q200 qualified parts (Vishay CRCW/TNPW, Yageo AC, KOA RK73H),
+    /// marked with an AEC-Q200 field
+    #[arg(long)]
+    automotive: bool,
     
     /// KiCad target library directory (for --format kicad only)
     #[arg(long)]
     kicad_target_lib: Option<String>,
     
-    /// Manufacturer (currently only Vishay is supported)
-    #[arg(long, default_value = "Vishay")]
+    /// Manufacturers (comma-separated: vishay, yageo, koa, stackpole, susumu). The first
+    /// is the primary source, the others become alternate MPN fields
+    #[arg(long, default_value = "vishay")]
     manufacturer: String,
     
-    /// Resistor symbol style (for --format kicad only)
+    /// Resistor symbol style (for --format kicad only): european or american,
+    /// with per-package overrides such as "european,0402=american"
     #[arg(long, default_value = "european")]
     symbol_style: String,
+
+    /// Generate 1 mOhm to 10 MOhm instead of the standard 1 Ohm to 1 MOhm
+    #[arg(long)]
+    extended_range: bool,
+
+    /// Footprints referenced by the symbols (for --format kicad only): "atlantix"
+    /// generates Atlantix_Resistors, "kicad" uses the stock Resistor_SMD library
+    #[arg(long, default_value = "atlantix")]
+    footprint_naming: String,
+
+    /// KiCad release whose symbol format is written (for --format kicad only): 6, 7 or 8
+    #[arg(long, default_value = "6")]
+    kicad_version: String,
+
+    /// Value notation in part names and values: decimal (4.70K) or european (4k70)
+    #[arg(long, default_value = "decimal")]
+    value_notation: String,
+
+    /// Package code leading in names: imperial (0603), metric (1608) or jis
+    #[arg(long, default_value = "imperial")]
+    package_naming: String,
+
+    /// Comment column of Altium rows (for --format altium only): =Value or value
+    #[arg(long, default_value = "=Value")]
+    comment: String,
+
+    /// Write a SPICE .lib next to each symbol library and link the symbols to
+    /// it (for --format kicad only): ideal, or parasitic for package L and C
+    #[arg(long)]
+    spice: Option<String>,
+
+    /// Write WRL and STEP chip models into Atlantix_Resistors.3dshapes and
+    /// point the footprints at them (for --format kicad, atlantix footprints only)
+    #[arg(long = "3d-models")]
+    models_3d: bool,
+
+    /// IPC-7351B footprint density level (for --format kicad only): least,
+    /// nominal or most. Non-nominal footprints get an _L or _M suffix
+    #[arg(long, default_value = "nominal")]
+    density: String,
+
+    /// Footprint file grammar (for --format kicad only): legacy (module,
+    /// KiCad 5) or modern (footprint with uuids, following --kicad-version)
+    #[arg(long, default_value = "legacy")]
+    footprint_format: String,
+
+    /// Also write hand or wave soldering alternates of each footprint
+    /// (comma-separated: hand, wave) and list them in the symbols' footprint
+    /// filters (for --format kicad only)
+    #[arg(long, default_value = "")]
+    solder_variants: String,
+
+    /// Digi-Key or Mouser parametric search exports (comma-separated .csv);
+    /// values the manufacturer does not make there are skipped
+    #[arg(long)]
+    availability: Option<String>,
+
+    /// Order of the parts in the libraries and CSVs: value, name or generation
+    #[arg(long, default_value = "value")]
+    sort: String,
+
+    /// Primary source among several manufacturers: listed (as given to
+    /// --manufacturer), stock or price, from the --stock data
+    #[arg(long, default_value = "listed")]
+    prefer: String,
+
+    /// CSV of `MPN,stock[,unit price]` lines used by --prefer
+    #[arg(long)]
+    stock: Option<String>,
 }
 
 fn main() {
@@ -48,56 +142,252 @@ fn main() {
     
     println!("Atlantix EDA Resistor Library Generator v0.2.0");
     println!("Format: {:?}", args.format);
-    println!("Series: E-{}", args.series);
+    let tolerance = match args.tolerance.as_deref().map(component::tolerance::parse).transpose() {
+        Ok(tolerance) => tolerance,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let precision = tolerance.is_some_and(component::tolerance::is_thin_film);
+    let series = args.series.unwrap_or(if precision { 192 } else { 96 });
+    let tolerance = tolerance.unwrap_or_else(|| component::tolerance::for_series(series));
+    println!("Series: E-{}", series);
+    println!("Tolerance: {}", tolerance);
     
-    let packages: Vec<&str> = args.packages.split(',').map(|s| s.trim()).collect();
+    let mut packages: Vec<&str> = args.packages.split(',').map(|s| s.trim()).collect();
     println!("Packages: {:?}", packages);
     
-    if args.manufacturer != "Vishay" {
-        eprintln!("Error: Currently only Vishay is supported as a manufacturer");
-        std::process::exit(1);
+    let manufacturers = match component::manufacturer::parse_list(&args.manufacturer) {
+        Ok(manufacturers) => manufacturers,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let names: Vec<String> = manufacturers.iter().map(|m| m.to_string()).collect();
+    println!("Manufacturers: {}", names.join(", "));
+    if args.automotive {
+        println!("Grade: AEC-Q200");
+        let sample = component::Resistance::from_ohms(1_000.0);
+        packages.retain(|package| {
+            let qualified = manufacturers[0].aec_q200_mpn(package, sample, tolerance).is_some();
+            if !qualified {
+                println!("Skipping {}: no AEC-Q200 qualified {} {} part", package, manufacturers[0], tolerance);
+            }
+            qualified
+        });
     }
-    println!("Manufacturer: {}", args.manufacturer);
     
-    if args.symbol_style != "european" && args.symbol_style != "american" {
-        eprintln!("Error: Symbol style must be 'european' or 'american'");
-        std::process::exit(1);
+    let symbol_styles: ResistorStyles = match args.symbol_style.parse() {
+        Ok(styles) => styles,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let spice_model: Option<SpiceModel> = match args.spice.as_deref().map(str::parse).transpose() {
+        Ok(model) => model,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let mut settings = Settings::default();
+    if let Some(files) = &args.availability {
+        let mut matrix = component::availability::AvailabilityMatrix::new();
+        for file in files.split(',').map(str::trim) {
+            let imported = fs::read_to_string(file)
+                .map_err(|e| AtlantixError::io("read", file, e))
+                .and_then(|content| component::availability::parse_parametric_csv(&content));
+            match imported {
+                Ok((imported, _)) => matrix.merge(imported),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        println!("Availability: {} manufacturer/package/tolerance combinations", matrix.summary().len());
+        settings.availability = Some(matrix);
+    }
+    match args.prefer.parse() {
+        Ok(policy) => settings.sourcing = policy,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+    if let Some(file) = &args.stock {
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Error: Failed to read {}: {}", file, e);
+                std::process::exit(1);
+            }
+        };
+        for line in content.lines() {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let Some(Ok(stock)) = fields.get(1).map(|stock| stock.parse()) else {
+                continue;
+            };
+            let unit_price = fields.get(2).and_then(|price| price.parse().ok());
+            settings.stock.insert(fields[0].to_string(), component::sourcing::StockInfo { stock, unit_price });
+        }
+    }
+    match args.sort.parse() {
+        Ok(order) => settings.part_order = order,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+    let solder_processes = match SolderProcess::parse_list(&args.solder_variants) {
+        Ok(processes) => processes,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let footprint_naming: FootprintNaming = match args.footprint_naming.parse() {
+        Ok(naming) => naming,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let kicad_version: component::kicad_symbol::KicadFormatVersion = match args.kicad_version.parse() {
+        Ok(version) => version,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    settings.symbol_format = kicad_version;
+    match args.density.parse() {
+        Ok(density) => settings.density = density,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+    match args.footprint_format.parse() {
+        Ok(format) => settings.footprint_format = format,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+    match args.value_notation.parse() {
+        Ok(notation) => settings.notation = notation,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+    match args.comment.parse() {
+        Ok(comment) => settings.altium_comment = comment,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+    match args.package_naming.parse() {
+        Ok(naming) => settings.package_naming = naming,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
     }
     if args.format == OutputFormat::Kicad {
-        println!("Symbol style: {}", args.symbol_style);
+        println!("Symbol style: {}", style_line(&symbol_styles.default));
+        for (package, style) in &symbol_styles.packages {
+            println!("  {}: {}", package, style_line(style));
+        }
+        println!("Footprints: {:?}", footprint_naming);
+        println!("Symbol format: {}", kicad_version);
+        println!("Footprint density: {}", settings.density);
+        println!("Footprint format: {}", settings.footprint_format);
+        if !solder_processes.is_empty() {
+            let names: Vec<String> = solder_processes.iter().map(|p| p.to_string()).collect();
+            println!("Solder variants: {}", names.join(", "));
+        }
     }
     
-    let decades = vec![1, 10, 100, 1000, 10000, 100000];
+    // Every library of the run is written with the settings chosen above
+    settings.scope(|| {
+        let format_name = match args.format {
+            OutputFormat::Altium => "altium",
+            OutputFormat::Kicad => "kicad",
+        };
+        let series_name = format!("E{}", series);
+        let placeholders = [("format", format_name), ("series", series_name.as_str())];
+        let output_dir = match component::output_path::expand(&args.output_dir, &placeholders) {
+            Ok(dir) => dir,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        };
+        let file_template = args.file_name.clone().unwrap_or_else(|| match args.format {
+            OutputFormat::Altium => "resistors_{package}".to_string(),
+            OutputFormat::Kicad => "Atlantix_R_{package}".to_string(),
+        });
+        let file_name = |package: &str| {
+            let mut values = placeholders.to_vec();
+            values.push(("package", package));
+            component::output_path::expand(&file_template, &values)
+        };
+        if let Err(e) = packages.iter().try_for_each(|package| file_name(package).map(drop)) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        let file_name = |package: &str| file_name(package).expect("file name template checked above");
+        println!("Output directory: {}", output_dir);
+
+        let decades = if args.extended_range {
+            component::EXTENDED_DECADES.to_vec()
+        } else {
+            component::STANDARD_DECADES.to_vec()
+        };
     
-    match args.format {
-        OutputFormat::Altium => generate_altium_libraries(&packages, &args.output_dir, args.series, &decades),
-        OutputFormat::Kicad => generate_kicad_libraries(&packages, &args.output_dir, args.series, &decades, args.kicad_target_lib.as_deref(), &args.symbol_style),
-    }
+        match args.format {
+            OutputFormat::Altium => generate_altium_libraries(&packages, &output_dir, &file_name, series, tolerance, args.automotive, &decades, manufacturers[0].clone()),
+            OutputFormat::Kicad => generate_kicad_libraries(&packages, &output_dir, &file_name, series, tolerance, args.automotive, &decades, args.kicad_target_lib.as_deref(), &symbol_styles, spice_model, footprint_naming, args.models_3d, &solder_processes, &manufacturers),
+        }
+    })
 }
 
-fn generate_altium_libraries(packages: &[&str], output_dir: &str, series: usize, decades: &[u32]) {
+#[allow(clippy::too_many_arguments)]
+fn generate_altium_libraries(packages: &[&str], output_dir: &str, file_name: &(dyn Fn(&str) -> String + Sync), series: usize, tolerance: &'static str, automotive: bool, decades: &[f64], manufacturer: Manufacturer) {
     println!("\nGenerating Altium CSV libraries...");
     
     fs::create_dir_all(output_dir).expect("Failed to create output directory");
-    
-    for package in packages {
-        println!("Generating {} package...", package);
-        
-        let mut resistor = component::Resistor::new(series, package.to_string());
-        let mut full_series = String::new();
-        
-        for decade in decades {
-            let series_data = resistor.generate(*decade);
-            full_series.push_str(&series_data);
+
+    // One writer thread per package, rows are streamed to disk in chunks
+    let resistors = packages
+        .iter()
+        .map(|package| {
+            let mut resistor = component::Resistor::new(series, package.to_string());
+            resistor.set_manufacturer(manufacturer.clone());
+            resistor.set_tolerance(tolerance);
+            resistor.set_automotive(automotive);
+            resistor
+        })
+        .collect();
+    match component::altium::write_package_csvs_named(resistors, decades, std::path::Path::new(output_dir), file_name) {
+        Ok(paths) => {
+            for path in paths {
+                println!("Successfully generated {}", path.display());
+            }
         }
-        
-        let filename = format!("{}/resistors_{}.csv", output_dir, package);
-        let csv_header = "Part,Description,Value,Case,Power,Supplier 1,Supplier Part Number 1,Library Path,Library Ref,Footprint Path,Footprint Ref,Company,Comment\r\n";
-        let full_content = format!("{}{}", csv_header, full_series);
-        
-        match fs::write(&filename, full_content) {
-            Ok(()) => println!("Successfully generated {}", filename),
-            Err(e) => eprintln!("Error generating {}: {}", filename, e),
+        Err(e) => eprintln!("Error generating Altium libraries: {}", e),
+    }
+    let truncations = component::limits::take_truncations();
+    if !truncations.is_empty() {
+        eprintln!("Warning: {} values cut to their column limits:", truncations.len());
+        for truncation in &truncations {
+            eprintln!("  {}", truncation);
         }
     }
     
@@ -106,50 +396,91 @@ fn generate_altium_libraries(packages: &[&str], output_dir: &str, series: usize,
     println!("Import these CSV files into Altium Designer's Database Library.");
 }
 
-fn generate_kicad_libraries(packages: &[&str], output_dir: &str, series: usize, decades: &[u32], kicad_target_lib: Option<&str>, symbol_style: &str) {
+#[allow(clippy::too_many_arguments)]
+fn generate_kicad_libraries(packages: &[&str], output_dir: &str, file_name: &(dyn Fn(&str) -> String + Sync), series: usize, tolerance: &'static str, automotive: bool, decades: &[f64], kicad_target_lib: Option<&str>, symbol_styles: &ResistorStyles, spice_model: Option<SpiceModel>, footprint_naming: FootprintNaming, models_3d: bool, solder_processes: &[SolderProcess], manufacturers: &[Manufacturer]) {
     println!("\nGenerating KiCad libraries...");
     
-    let (symbols_dir, footprints_dir) = if let Some(root) = kicad_target_lib {
+    let (symbols_dir, footprints_dir, models_root) = if let Some(root) = kicad_target_lib {
         (
             format!("{}/symbols", root),
-            format!("{}/footprints/Atlantix_Resistors.pretty", root)
+            format!("{}/footprints/Atlantix_Resistors.pretty", root),
+            format!("{}/3dmodels", root)
         )
     } else {
         (
             format!("{}/kicad/symbols", output_dir),
-            format!("{}/kicad/Atlantix_Resistors.pretty", output_dir)
+            format!("{}/kicad/Atlantix_Resistors.pretty", output_dir),
+            format!("{}/kicad", output_dir)
         )
     };
+    let models_dir = format!("{}/Atlantix_Resistors.3dshapes", models_root);
     
     fs::create_dir_all(&symbols_dir).expect("Failed to create symbols directory");
-    fs::create_dir_all(&footprints_dir).expect("Failed to create footprints directory");
+    if footprint_naming == FootprintNaming::Atlantix {
+        fs::create_dir_all(&footprints_dir).expect("Failed to create footprints directory");
+    }
     
     // Generate symbols for each package
     for package in packages {
         println!("Generating symbols for {} package...", package);
         
         let mut resistor = component::Resistor::new(series, package.to_string());
-        let symbol_file = format!("{}/Atlantix_R_{}.kicad_sym", symbols_dir, package);
+        resistor.set_footprint_naming(footprint_naming);
+        resistor.set_solder_processes(solder_processes.to_vec());
+        resistor.set_tolerance(tolerance);
+        resistor.set_automotive(automotive);
+        resistor.set_manufacturer(manufacturers[0].clone());
+        resistor.set_alternate_manufacturers(manufacturers[1..].to_vec());
+        let symbol_file = format!("{}/{}.kicad_sym", symbols_dir, file_name(package));
+        let spice_file = format!("{}/{}.lib", symbols_dir, file_name(package));
+        if let Some(model) = spice_model {
+            resistor.set_spice_model(model, &spice_file);
+        }
         
-        match resistor.generate_kicad_symbols(decades.to_vec(), &symbol_file, symbol_style) {
+        match resistor.generate_kicad_symbols(decades.to_vec(), &symbol_file, symbol_styles.for_package(package)) {
             Ok(()) => println!("Successfully generated {}", symbol_file),
             Err(e) => eprintln!("Error generating symbols for {}: {}", package, e),
         }
+        if spice_model.is_some() {
+            match resistor.generate_spice_library(decades, &spice_file) {
+                Ok(()) => println!("Successfully generated {}", spice_file),
+                Err(e) => eprintln!("Error generating SPICE models for {}: {}", package, e),
+            }
+        }
     }
     
-    // Generate footprints
-    println!("Generating footprints...");
-    let resistor = component::Resistor::new(series, "0603".to_string());
-    
-    match resistor.generate_kicad_footprints(packages.to_vec(), &footprints_dir) {
-        Ok(()) => println!("Successfully generated footprints"),
-        Err(e) => eprintln!("Error generating footprints: {}", e),
+    // Generate footprints, the stock Resistor_SMD footprints need none
+    if footprint_naming == FootprintNaming::KicadOfficial {
+        println!("Symbols reference the official KiCad Resistor_SMD footprints, skipping footprint generation");
+    } else {
+        println!("Generating footprints...");
+        let mut resistor = component::Resistor::new(series, "0603".to_string());
+        resistor.set_solder_processes(solder_processes.to_vec());
+        if models_3d {
+            match resistor.generate_3d_models(packages.to_vec(), &models_dir) {
+                Ok(()) => println!("Successfully generated 3D models"),
+                Err(e) => eprintln!("Error generating 3D models: {}", e),
+            }
+            // Absolute, so the footprints find their models wherever the library is added
+            let root = fs::canonicalize(&models_root).map(|p| p.display().to_string()).unwrap_or(models_root.clone());
+            resistor.set_3d_model_dir(&root);
+        }
+
+        match resistor.generate_kicad_footprints(packages.to_vec(), &footprints_dir) {
+            Ok(()) => println!("Successfully generated footprints"),
+            Err(e) => eprintln!("Error generating footprints: {}", e),
+        }
     }
     
     println!("\nKiCad library generation complete!");
     println!("Files generated:");
-    println!("  Symbols: {}/Atlantix_R_*.kicad_sym", symbols_dir);
-    println!("  Footprints: {}/*.kicad_mod", footprints_dir);
+    println!("  Symbols: {}/*.kicad_sym", symbols_dir);
+    if footprint_naming == FootprintNaming::Atlantix {
+        println!("  Footprints: {}/*.kicad_mod", footprints_dir);
+        if models_3d {
+            println!("  3D models: {}/*.wrl, *.step", models_dir);
+        }
+    }
     if kicad_target_lib.is_some() {
         println!("  Libraries installed to your KiCad target library!");
     }
@@ -165,4 +496,8 @@ fn generate_kicad_libraries(packages: &[&str], output_dir: &str, series: usize,
         println!("2. Copy footprint .pretty directory to your KiCad footprint libraries");
         println!("3. Add libraries to your project in Symbol Library Manager and Footprint Library Manager");
     }
-}
\ No newline at end of file
+}
+/// A style name with a sketch of its body, e.g. "american  --/\/\/\--"
+fn style_line(style: &str) -> String {
+    format!("{}  {}", style, style_glyph(style).unwrap_or_default())
+}
@@ -2,29 +2,32 @@ extern crate component;
 extern crate bevy_ecs;
 
 use bevy_ecs::prelude::*;
-use component::ecs::{components::*, resources::*, systems};
+use component::ecs::{components::*, resources::*};
+use component::AtlantixError;
 
-fn main() {
+fn main() -> Result<(), AtlantixError> {
     println!("Atlantix EDA - Bevy ECS Resistor Generator Demo");
     
-    // Create the ECS world
-    let mut world = World::new();
+    // Create the ECS world, with the output buffers
+    let mut world = component::ecs::build_resistor_world();
     
     // Add resources
-    world.insert_resource(GeneratorConfig {
+    let config = GeneratorConfig {
         output_formats: vec![
             OutputFormat::KicadSymbols,
             OutputFormat::KicadFootprints,
             OutputFormat::Altium,
         ],
-        manufacturers: vec!["Vishay".to_string(), "Yageo".to_string(), "KOA".to_string()],
-        decades: vec![1, 10, 100, 1000, 10000, 100000],
-    });
+        manufacturers: vec!["Vishay".to_string(), "Yageo".to_string(), "KOA".to_string(), "Stackpole".to_string(), "Susumu".to_string()],
+        output_dir: Some("outputs/ecs".into()),
+        ..Default::default()
+    };
+    world.insert_resource(config.clone());
     world.insert_resource(ESeriesCache::default());
     
     // Spawn template entities for each package
     let packages = vec!["0603", "0805", "1206"];
-    for package_name in packages {
+    for package_name in packages.iter().copied() {
         world.spawn((
             ESeries(96),
             Package {
@@ -34,30 +37,34 @@ fn main() {
             },
         ));
     }
+
+    // Precision thin film parts: E192 at 0.1% (Vishay TNPW, Susumu RG)
+    world.spawn((
+        ESeries(192),
+        Package {
+            name: "0603".to_string(),
+            imperial: "0603".to_string(),
+            metric: get_metric_name("0603"),
+        },
+        Tolerance("0.1%".to_string()),
+    ));
     
     println!("Spawned {} package templates", world.query::<&Package>().iter(&world).count());
     
-    // Create and run the generation schedule
-    let mut schedule = Schedule::default();
-    
-    // Note: Systems run in the order they're added
-    schedule.add_systems((
-        systems::generate_eseries_values,
-        systems::assign_package_attributes,
-        systems::generate_manufacturer_parts,
-    ));
-    
+    let estimate = component::estimate::estimate(&config, 96, &packages);
+    println!(
+        "Estimated {} parts, {:.1} KB of output, {:.1} ms",
+        estimate.parts,
+        estimate.total_size() as f64 / 1024.0,
+        estimate.generation_time.as_secs_f64() * 1000.0
+    );
+
     println!("Running generation pipeline...");
-    schedule.run(&mut world);
-    
-    // Run the assignment and manufacturer systems again to ensure all data is filled
-    // (This is a workaround for the ordering issue with spawned entities)
-    let mut post_generation_schedule = Schedule::default();
-    post_generation_schedule.add_systems((
-        systems::assign_package_attributes,
-        systems::generate_manufacturer_parts,
-    ));
-    post_generation_schedule.run(&mut world);
+    let progress = component::ecs::progress_channel(&mut world);
+    component::ecs::run_generation_pipeline(&mut world);
+    for stage in progress.try_iter().filter(|update| update.done == update.total) {
+        println!("  {:?}: {} parts ({:.0}%)", stage.stage, stage.total, stage.fraction() * 100.0);
+    }
     
     // Query results
     let resistor_count = world.query::<&ResistorValue>().iter(&world).count();
@@ -103,6 +110,14 @@ fn main() {
         .filter(|(pkg, _)| pkg.name == "0603")
         .count();
     println!("  0603 package resistors: {}", package_0603_count);
+
+    // Query resistors rated 1/8W or more, power is numeric so it compares directly
+    let min_power = PowerRating(component::Power(125));
+    let eighth_watt_count = world.query::<&PowerRating>()
+        .iter(&world)
+        .filter(|power| **power >= min_power)
+        .count();
+    println!("  Resistors rated 1/8W or more: {}", eighth_watt_count);
     
     // Query resistors with manufacturer alternates
     let yageo_count = world.query::<&ManufacturerParts>()
@@ -116,6 +131,17 @@ fn main() {
         .filter(|mfrs| mfrs.0.iter().any(|m| m.manufacturer == "KOA Speer"))
         .count();
     println!("  Resistors with KOA Speer alternates: {}", koa_count);
+
+    // Libraries written by write_outputs from the collected buffers
+    let mut written = world.resource_mut::<WrittenOutputs>();
+    if let Some(e) = written.error.take() {
+        return Err(e);
+    }
+    println!("\nLibraries:");
+    for (path, parts) in &written.files {
+        println!("  {} parts -> {}", parts, path.display());
+    }
+    Ok(())
 }
 
 fn get_metric_name(package: &str) -> String {
@@ -61,7 +61,7 @@
 //! the decades ranging from 1 ohm to 1 Meg-Ohm
 //!
 //! ```
-//! let decades = [1,10,100,1000,10000,100000];
+//! let decades = [1.0, 10.0, 100.0, 1000.0, 10000.0, 100000.0];
 //! let mut r0603 = generate::component::Resistor::new(96, "0603".to_string());
 //!
 //!	for decade in decades.iter() {
@@ -75,7 +75,7 @@
 //!
 //! ```
 //! fn main() {
-//! 		let decades = [1,10,100,1000,10000,100000];
+//! 		let decades = [1.0, 10.0, 100.0, 1000.0, 10000.0, 100000.0];
 //!			let mut name_0402 : String = " ".to_string();
 //!
 //!			let mut r0402 = generate::component::Resistor::new(96, "0402".to_string());
@@ -112,8 +112,8 @@ use std::io::prelude::*;
 
 /// The main module which instantiates the generate module as an object. The generator can yield values over decade ranges.
 fn main() -> std::io::Result<()> {
-    //let decades = [1,10,100,1000,10000,100000];
-    let decades = [1, 10, 100, 1000, 10000, 100000];
+    //let decades = [1.0, 10.0, 100.0, 1000.0, 10000.0, 100000.0];
+    let decades = [1.0, 10.0, 100.0, 1000.0, 10000.0, 100000.0];
     let mut name_0402: String = " ".to_string();
     let mut name_0603: String = " ".to_string();
     let mut name_0805: String = " ".to_string();
@@ -0,0 +1,185 @@
+//! Component families contributed from outside the crate.
+//!
+//! Resistors, capacitors and the other built-in families have their own
+//! generators. Further families, e.g. relays or oscillators, implement
+//! [`ComponentFamily`] and are added once at startup with [`register`]. Front
+//! ends discover them with [`families`] and offer them next to the built-in
+//! ones, the CLI as `aeda generate <family>`.
+
+use crate::AtlantixError;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+/// A setting of a family's generator, e.g. the packages to generate
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    pub name: String,
+    pub description: String,
+    /// Value used when none is given, `None` for a required parameter
+    pub default: Option<String>,
+}
+
+impl Parameter {
+    pub fn new(name: &str, description: &str) -> Self {
+        Parameter { name: name.to_string(), description: description.to_string(), default: None }
+    }
+
+    pub fn with_default(mut self, default: &str) -> Self {
+        self.default = Some(default.to_string());
+        self
+    }
+}
+
+/// Exporters that can handle a family's libraries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exporter {
+    /// The library JSON itself, as read by Stencil
+    Stencil,
+    /// `aeda export template`, one part per generated value
+    Template,
+}
+
+impl fmt::Display for Exporter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Exporter::Stencil => write!(f, "stencil"),
+            Exporter::Template => write!(f, "template"),
+        }
+    }
+}
+
+/// One generated part of a family
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FamilyPart {
+    pub name: String,
+    pub value: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub manufacturer: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub mpn: String,
+}
+
+/// Parts of one package, written as one library
+#[derive(Debug, Clone, PartialEq)]
+pub struct FamilyLibrary {
+    pub name: String,
+    pub description: String,
+    pub package: String,
+    pub footprint: String,
+    pub parts: Vec<FamilyPart>,
+}
+
+pub trait ComponentFamily: Send + Sync {
+    /// Name on the command line and library category, e.g. `relay`
+    fn name(&self) -> &str;
+
+    fn description(&self) -> &str;
+
+    /// Reference designator prefix, e.g. `K`
+    fn prefix(&self) -> &str;
+
+    fn parameters(&self) -> Vec<Parameter>;
+
+    /// Generate the libraries for `parameters`, which hold a value for every
+    /// parameter of the family, see [`resolve`]
+    fn generate(&self, parameters: &BTreeMap<String, String>) -> Result<Vec<FamilyLibrary>, AtlantixError>;
+
+    fn exporters(&self) -> Vec<Exporter> {
+        vec![Exporter::Stencil, Exporter::Template]
+    }
+}
+
+static FAMILIES: RwLock<Vec<Arc<dyn ComponentFamily>>> = RwLock::new(Vec::new());
+
+/// Offer `family` to every front end from now on. Names are unique.
+pub fn register(family: impl ComponentFamily + 'static) -> Result<(), AtlantixError> {
+    let mut families = FAMILIES.write().unwrap_or_else(|e| e.into_inner());
+    if families.iter().any(|f| f.name() == family.name()) {
+        return Err(AtlantixError::Invalid(format!("Component family '{}' is already registered", family.name())));
+    }
+    families.push(Arc::new(family));
+    Ok(())
+}
+
+/// Registered families, in registration order
+pub fn families() -> Vec<Arc<dyn ComponentFamily>> {
+    FAMILIES.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn find(name: &str) -> Option<Arc<dyn ComponentFamily>> {
+    FAMILIES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|f| f.name() == name)
+        .cloned()
+}
+
+/// Complete the `given` parameter values with the family's defaults. Unknown
+/// and missing required parameters are errors.
+///
+/// ```
+/// use component::family::{find, register, resolve, ComponentFamily, FamilyLibrary, FamilyPart, Parameter};
+/// use component::AtlantixError;
+/// use std::collections::BTreeMap;
+///
+/// struct Oscillators;
+///
+/// impl ComponentFamily for Oscillators {
+///     fn name(&self) -> &str { "oscillator" }
+///     fn description(&self) -> &str { "Crystal oscillators" }
+///     fn prefix(&self) -> &str { "Y" }
+///     fn parameters(&self) -> Vec<Parameter> {
+///         vec![Parameter::new("package", "Package size").with_default("3225"), Parameter::new("frequencies", "MHz, comma-separated")]
+///     }
+///     fn generate(&self, parameters: &BTreeMap<String, String>) -> Result<Vec<FamilyLibrary>, AtlantixError> {
+///         let package = &parameters["package"];
+///         let parts = parameters["frequencies"]
+///             .split(',')
+///             .map(|f| FamilyPart { name: format!("Y{}_{}MHz", package, f), value: format!("{}MHz", f), ..Default::default() })
+///             .collect();
+///         Ok(vec![FamilyLibrary { name: format!("Y_{}", package), description: self.description().into(), package: package.clone(), footprint: String::new(), parts }])
+///     }
+/// }
+///
+/// register(Oscillators).unwrap();
+/// assert!(register(Oscillators).is_err());
+///
+/// let family = find("oscillator").unwrap();
+/// let parameters = resolve(family.as_ref(), &[("frequencies".into(), "8,25".into())]).unwrap();
+/// assert_eq!(parameters["package"], "3225");
+/// assert_eq!(family.generate(&parameters).unwrap()[0].parts[1].value, "25MHz");
+///
+/// assert!(resolve(family.as_ref(), &[]).unwrap_err().to_string().contains("--frequencies"));
+/// assert!(resolve(family.as_ref(), &[("colour".into(), "red".into())]).unwrap_err().to_string().contains("colour"));
+/// ```
+pub fn resolve(family: &dyn ComponentFamily, given: &[(String, String)]) -> Result<BTreeMap<String, String>, AtlantixError> {
+    let parameters = family.parameters();
+    let mut values = BTreeMap::new();
+    for (name, value) in given {
+        if !parameters.iter().any(|p| &p.name == name) {
+            let known: Vec<&str> = parameters.iter().map(|p| p.name.as_str()).collect();
+            return Err(AtlantixError::Parse(format!(
+                "Unknown parameter '{}' for {}, expected one of: {}",
+                name,
+                family.name(),
+                known.join(", ")
+            )));
+        }
+        values.insert(name.clone(), value.clone());
+    }
+    for parameter in &parameters {
+        if values.contains_key(&parameter.name) {
+            continue;
+        }
+        let default = parameter
+            .default
+            .clone()
+            .ok_or_else(|| AtlantixError::Parse(format!("{} needs --{} ({})", family.name(), parameter.name, parameter.description)))?;
+        values.insert(parameter.name.clone(), default);
+    }
+    Ok(values)
+}
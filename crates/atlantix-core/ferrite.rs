@@ -0,0 +1,200 @@
+//! Ferrite bead library generation.
+//!
+//! Beads are specified by their impedance at 100 MHz. Footprints reuse the
+//! SMD chip land patterns; part numbers follow the Murata BLM and TDK MPZ
+//! schemes.
+//!
+//! Rated current and DCR are nominal, scaled from a 120 Ohm reference part in
+//! each package: current falls with the square root of impedance and DCR
+//! rises linearly. Confirm against the datasheet before release.
+
+use crate::kicad_footprint::{self, FootprintNaming, KicadFootprint};
+use crate::kicad_symbol::{KicadSymbol, KicadSymbolLib};
+use crate::status::PartStatus;
+use crate::AtlantixError;
+
+/// Standard impedances at 100 MHz, in ohms.
+pub const FERRITE_IMPEDANCES: [f64; 10] = [30.0, 60.0, 120.0, 220.0, 330.0, 470.0, 600.0, 1000.0, 1500.0, 2200.0];
+
+/// Packages supported by the ferrite bead generator
+pub const FERRITE_PACKAGES: [&str; 4] = ["0402", "0603", "0805", "1206"];
+
+struct FerritePackage {
+    murata_size: &'static str,
+    tdk_size: &'static str,
+    /// Ratings of a 120 Ohm part in this package
+    rated_current: f64,
+    dcr_mohm: f64,
+}
+
+fn package_info(package: &str) -> Option<FerritePackage> {
+    let (murata_size, tdk_size, rated_current, dcr_mohm) = match package {
+        "0402" => ("15", "1005", 1.0, 100.0),
+        "0603" => ("18", "1608", 1.5, 60.0),
+        "0805" => ("21", "2012", 2.5, 40.0),
+        "1206" => ("31", "3216", 3.0, 30.0),
+        _ => return None,
+    };
+    Some(FerritePackage { murata_size, tdk_size, rated_current, dcr_mohm })
+}
+
+/// Three digit impedance code, two significant digits and a multiplier:
+/// `300` for 30 Ohm, `121` for 120 Ohm, `102` for 1 kOhm.
+///
+/// ```
+/// assert_eq!(component::ferrite::impedance_code(30.0), "300");
+/// assert_eq!(component::ferrite::impedance_code(600.0), "601");
+/// assert_eq!(component::ferrite::impedance_code(2200.0), "222");
+/// ```
+pub fn impedance_code(ohms: f64) -> String {
+    let exponent = (ohms.log10().floor() as i32 - 1).max(0);
+    let digits = (ohms / 10f64.powi(exponent)).round() as u32;
+    format!("{}{}", digits, exponent)
+}
+
+/// One generated ferrite bead part
+#[derive(Debug, Clone, PartialEq)]
+pub struct FerriteBeadPart {
+    pub name: String,
+    pub value: String,
+    pub impedance_ohms: f64,
+    pub package: String,
+    pub rated_current: f64,
+    pub dcr_mohm: f64,
+    pub murata_mpn: String,
+    pub tdk_mpn: String,
+    /// Internal part number, see [`crate::ipn`], given by `parts()` to parts
+    /// the hook keeps
+    pub ipn: Option<String>,
+}
+
+/// Build a single part, or `None` for unsupported packages.
+pub fn ferrite_part(package: &str, impedance_ohms: f64) -> Option<FerriteBeadPart> {
+    let info = package_info(package)?;
+    let ratio = impedance_ohms / 120.0;
+    let code = impedance_code(impedance_ohms);
+    let value = format!("{}R", impedance_ohms.round() as u32);
+    let name = format!("FB{}_{}", crate::package_naming::label(package), value);
+    Some(FerriteBeadPart {
+        ipn: None,
+        name,
+        value,
+        impedance_ohms,
+        package: package.to_string(),
+        rated_current: ((info.rated_current / ratio.sqrt()) * 100.0).round() / 100.0,
+        dcr_mohm: (info.dcr_mohm * ratio).round(),
+        murata_mpn: format!("BLM{}AG{}SN1D", info.murata_size, code),
+        tdk_mpn: format!("MPZ{}S{}ATA00", info.tdk_size, code),
+    })
+}
+
+///
+/// Ferrite bead generator for one package.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct FerriteBead {
+    package: String,
+    impedances: Vec<f64>,
+    status: Option<PartStatus>,
+    footprint_naming: FootprintNaming,
+}
+
+impl FerriteBead {
+    /// Returns `None` for unsupported packages.
+    pub fn new(package: &str, impedances: Vec<f64>) -> Option<FerriteBead> {
+        package_info(package)?;
+        Some(FerriteBead {
+            package: package.to_string(),
+            impedances,
+            status: None,
+            footprint_naming: FootprintNaming::Atlantix,
+        })
+    }
+
+    /// Stamps every subsequently generated KiCad symbol with a hidden `Status` property.
+    pub fn set_status(&mut self, status: PartStatus) {
+        self.status = Some(status);
+    }
+
+    /// With `FootprintNaming::KicadOfficial` the symbols reference the stock
+    /// KiCad footprints of the package in place of the generated ones.
+    pub fn set_footprint_naming(&mut self, naming: FootprintNaming) {
+        self.footprint_naming = naming;
+    }
+
+    /// The parts of every impedance, as the part hook leaves them
+    pub fn parts(&self) -> Vec<FerriteBeadPart> {
+        let footprint = self.footprint_name().unwrap_or_default();
+        self.generated_parts()
+            .into_iter()
+            .filter_map(|mut part| {
+                let outcome = crate::hook::outcome(|| self.kicad_symbol(&part, &footprint))?;
+                for (field, changed) in [
+                    (&mut part.name, outcome.changed(|symbol| &symbol.name)),
+                    (&mut part.value, outcome.changed(|symbol| &symbol.value)),
+                    (&mut part.murata_mpn, outcome.changed(|symbol| &symbol.mpn)),
+                ] {
+                    if let Some(changed) = changed {
+                        *field = changed.to_string();
+                    }
+                }
+                part.ipn = crate::ipn::assign("ferrite", &self.package, &part.name, "");
+                Some(part)
+            })
+            .collect()
+    }
+
+    fn generated_parts(&self) -> Vec<FerriteBeadPart> {
+        self.impedances
+            .iter()
+            .filter_map(|z| ferrite_part(&self.package, *z))
+            .collect()
+    }
+
+    /// Footprint the symbols reference, `None` without one for the package
+    pub fn footprint_name(&self) -> Option<String> {
+        match self.footprint_naming {
+            FootprintNaming::Atlantix => KicadFootprint::new_smd_inductor(&self.package)
+                .map(|fp| format!("Atlantix_Inductors:{}", fp.name)),
+            FootprintNaming::KicadOfficial => kicad_footprint::official_footprint("Inductor_SMD", "L", &self.package),
+        }
+    }
+
+    pub fn generate_kicad_symbols(&self, output_path: &str) -> Result<(), AtlantixError> {
+        let mut symbol_lib = KicadSymbolLib::new();
+        let footprint = self.footprint_name().unwrap_or_default();
+
+        for part in self.generated_parts() {
+            if let Some(mut symbol) = crate::hook::apply(self.kicad_symbol(&part, &footprint)) {
+                crate::ipn::assign_symbol("ferrite", &self.package, &mut symbol);
+                symbol_lib.add_symbol(symbol);
+            }
+        }
+
+        crate::atomic::write(output_path, symbol_lib.generate_library())
+    }
+
+    /// The KiCad symbol of a generated part, before the part hook
+    fn kicad_symbol(&self, part: &FerriteBeadPart, footprint: &str) -> KicadSymbol {
+        let description = format!(
+            "FERRITE BEAD SMT {} @ 100MHz, {}, {}A, DCR {}mOhm",
+            part.value, self.package, part.rated_current, part.dcr_mohm
+        );
+        let mut symbol = KicadSymbol::new_ferrite_bead(part.name.clone(), part.value.clone(), footprint.to_string())
+            .with_sources("Murata", &part.murata_mpn, &part.murata_mpn);
+        symbol.description = description;
+        symbol.status = self.status;
+        symbol
+    }
+
+    pub fn generate_kicad_footprints(packages: &[&str], output_dir: &str) -> Result<(), AtlantixError> {
+        crate::atomic::create_dir_all(output_dir)?;
+        for package in packages.iter().filter(|p| package_info(p).is_some()) {
+            if let Some(footprint) = KicadFootprint::new_smd_inductor(package) {
+                let filename = format!("{}/{}.kicad_mod", output_dir, footprint.name);
+                crate::atomic::write(filename, footprint.generate_footprint())?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,393 @@
+//! Settings of the desktop front end, saved as TOML
+//!
+//! File > Save/Load Configuration write and read an [`AppConfig`] anywhere
+//! on disk. The configuration in use is also kept as `gui.toml` in the data
+//! directory, the one the CLI uses, and loaded again on the next start.
+//!
+//! The export conventions use the keys of a `[profiles.<name>]` table of
+//! the CLI's config.toml, so a profile loads into the GUI and the GUI's
+//! conventions save back as a profile for `aeda export --profile <name>`.
+
+use super::family::Family;
+use crate::ecs::resources::{GeneratorConfig, OutputFormat};
+use crate::kicad_symbol::ResistorStyles;
+use crate::naming::NameTemplate;
+use crate::settings::Settings;
+use crate::{AtlantixError, ValueRange};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Name of the configuration kept in the data directory between runs
+pub const LAST_CONFIG: &str = "gui.toml";
+
+/// Keys of AppConfig that are also keys of a CLI profile
+const PROFILE_KEYS: [&str; 8] = [
+    "target",
+    "output",
+    "kicad_version",
+    "value_notation",
+    "density",
+    "footprint_format",
+    "sort",
+    "package_naming",
+];
+
+/// What the GUI generates and how
+///
+/// ```
+/// use component::gui::config::AppConfig;
+///
+/// let dir = std::env::temp_dir().join(format!("gui-config-doctest-{}", std::process::id()));
+/// std::fs::create_dir_all(&dir).unwrap();
+/// let config = AppConfig { series: 192, value_notation: Some("european".into()), ..Default::default() };
+/// config.save(&dir.join("precision.toml")).unwrap();
+/// assert_eq!(AppConfig::load(&dir.join("precision.toml")).unwrap(), config);
+///
+/// // The conventions round trip through a profile of config.toml
+/// config.save_profile(&dir, "precision").unwrap();
+/// let profile = AppConfig::from_profile(&dir, "precision").unwrap();
+/// assert_eq!(profile.value_notation.as_deref(), Some("european"));
+/// assert_eq!(profile.series, 96);
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub family: Family,
+    /// E-series of resistors, 3 to 192
+    pub series: usize,
+    pub packages: Vec<String>,
+    /// Tolerance of every part, the series' own when `None`
+    pub tolerance: Option<String>,
+    /// Lowest resistor value, e.g. "10", the standard sweep's when `None`
+    pub min_value: Option<String>,
+    /// Highest resistor value, e.g. "1M"
+    pub max_value: Option<String>,
+    /// Preferred manufacturer first
+    pub manufacturers: Vec<String>,
+    /// "european" or "american"
+    pub symbol_style: String,
+    /// Symbol style by package in place of `symbol_style`
+    pub package_styles: BTreeMap<String, String>,
+    /// E-series of inductors, 6 or 12
+    pub inductor_series: usize,
+    pub shielded: bool,
+    /// LED colors, e.g. "Red"
+    pub led_colors: Vec<String>,
+    /// Part name template, see [`crate::naming`], the built-in names when `None`
+    pub part_template: Option<String>,
+    /// Library name template, the built-in names when `None`
+    pub library_template: Option<String>,
+    /// Distributors written as Supplier 1, 2, ..., e.g. "mouser", each
+    /// generator's own when empty
+    pub suppliers: Vec<String>,
+    pub kicad_symbols: bool,
+    pub kicad_footprints: bool,
+    pub altium: bool,
+    /// Altium CSV of each package, e.g. "Atlantix_R_{package}.csv",
+    /// [`crate::ecs::ALTIUM_FILE_PATTERN`] when `None`
+    pub altium_file: Option<String>,
+    /// Export run without a format: kicad, stencil, altium, easyeda, jlcpcb or bom
+    pub target: Option<String>,
+    /// Output directory, may contain {date}, {time}, {format} and {profile}
+    pub output: Option<PathBuf>,
+    /// KiCad symbol format, 6, 7 or 8
+    pub kicad_version: Option<String>,
+    /// decimal (4.70K) or european (4k70)
+    pub value_notation: Option<String>,
+    /// IPC-7351B footprint density, least, nominal or most
+    pub density: Option<String>,
+    /// Footprint file grammar, legacy or modern
+    pub footprint_format: Option<String>,
+    /// Part order in libraries and CSVs, value, name or generation
+    pub sort: Option<String>,
+    /// Package code leading in names, imperial, metric or jis
+    pub package_naming: Option<String>,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        AppConfig {
+            family: Family::Resistors,
+            series: 96,
+            packages: vec!["0603".to_string(), "0805".to_string()],
+            tolerance: None,
+            min_value: None,
+            max_value: None,
+            manufacturers: vec!["Vishay".to_string()],
+            symbol_style: "european".to_string(),
+            package_styles: BTreeMap::new(),
+            inductor_series: 12,
+            shielded: true,
+            led_colors: crate::led::LedColor::ALL.iter().map(|color| color.to_string()).collect(),
+            part_template: None,
+            library_template: None,
+            suppliers: Vec::new(),
+            kicad_symbols: true,
+            kicad_footprints: true,
+            altium: false,
+            altium_file: None,
+            target: None,
+            output: None,
+            kicad_version: None,
+            value_notation: None,
+            density: None,
+            footprint_format: None,
+            sort: None,
+            package_naming: None,
+        }
+    }
+}
+
+impl AppConfig {
+    pub fn load(path: &Path) -> Result<Self, AtlantixError> {
+        let content = fs::read_to_string(path).map_err(|e| AtlantixError::io("read", path, e))?;
+        toml::from_str(&content).map_err(|e| AtlantixError::Parse(format!("Invalid configuration {}: {}", path.display(), e)))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), AtlantixError> {
+        let content = toml::to_string_pretty(self).map_err(|e| AtlantixError::Invalid(format!("Failed to serialize configuration: {}", e)))?;
+        crate::atomic::write(path, content)
+    }
+
+    /// The configuration of the last run from `data_dir`, the default on a
+    /// first start or when it no longer parses
+    pub fn load_last(data_dir: &Path) -> Self {
+        AppConfig::load(&data_dir.join(LAST_CONFIG)).unwrap_or_default()
+    }
+
+    /// Keep this configuration in `data_dir` for the next start
+    pub fn save_last(&self, data_dir: &Path) -> Result<(), AtlantixError> {
+        crate::atomic::create_dir_all(data_dir)?;
+        self.save(&data_dir.join(LAST_CONFIG))
+    }
+
+    /// Names of the profiles in the config.toml of `data_dir`
+    pub fn profile_names(data_dir: &Path) -> Result<Vec<String>, AtlantixError> {
+        let config = read_config(data_dir)?;
+        Ok(config.get("profiles").and_then(toml::Value::as_table).map(|profiles| profiles.keys().cloned().collect()).unwrap_or_default())
+    }
+
+    /// The default configuration with the conventions of `[profiles.<name>]`
+    /// in the config.toml of `data_dir`
+    pub fn from_profile(data_dir: &Path, name: &str) -> Result<Self, AtlantixError> {
+        let config = read_config(data_dir)?;
+        let profile = config
+            .get("profiles")
+            .and_then(|profiles| profiles.get(name))
+            .ok_or_else(|| AtlantixError::Invalid(format!("Unknown profile '{}' in {}", name, data_dir.join("config.toml").display())))?;
+        if let Some(key) = profile.as_table().and_then(|table| table.keys().find(|key| !PROFILE_KEYS.contains(&key.as_str()))) {
+            return Err(AtlantixError::Parse(format!("Invalid profile '{}': unknown key '{}'", name, key)));
+        }
+        profile.clone().try_into().map_err(|e| AtlantixError::Parse(format!("Invalid profile '{}': {}", name, e)))
+    }
+
+    /// Write the conventions as `[profiles.<name>]` into the config.toml of
+    /// `data_dir`, replacing the keys of a profile of that name. The rest of
+    /// the file is kept as it is, comments included.
+    pub fn save_profile(&self, data_dir: &Path, name: &str) -> Result<(), AtlantixError> {
+        let path = data_dir.join("config.toml");
+        let mut document: toml_edit::DocumentMut = match fs::read_to_string(&path) {
+            Ok(content) => content.parse().map_err(|e| AtlantixError::Parse(format!("Failed to parse {}: {}", path.display(), e)))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => toml_edit::DocumentMut::new(),
+            Err(e) => return Err(AtlantixError::io("read", &path, e)),
+        };
+        let toml::Value::Table(values) = toml::Value::try_from(self).map_err(|e| AtlantixError::Invalid(e.to_string()))? else {
+            unreachable!("AppConfig serializes to a table");
+        };
+        let not_a_table = |what: &str| AtlantixError::Parse(format!("{} in {} must be a table", what, path.display()));
+        let profiles = document
+            .entry("profiles")
+            .or_insert_with(|| {
+                let mut profiles = toml_edit::Table::new();
+                profiles.set_implicit(true);
+                toml_edit::Item::Table(profiles)
+            })
+            .as_table_mut()
+            .ok_or_else(|| not_a_table("[profiles]"))?;
+        let profile = profiles
+            .entry(name)
+            .or_insert_with(toml_edit::table)
+            .as_table_mut()
+            .ok_or_else(|| not_a_table(&format!("[profiles.{}]", name)))?;
+        // Every profile key is a string. A value replaced keeps its comment.
+        for key in PROFILE_KEYS {
+            match values.get(key).and_then(toml::Value::as_str) {
+                Some(value) => match profile.get_mut(key).and_then(toml_edit::Item::as_value_mut) {
+                    Some(existing) => {
+                        let decor = existing.decor().clone();
+                        *existing = value.into();
+                        *existing.decor_mut() = decor;
+                    }
+                    None => profile[key] = toml_edit::value(value),
+                },
+                None => {
+                    profile.remove(key);
+                }
+            }
+        }
+        crate::atomic::write(&path, document.to_string())
+    }
+
+    /// Settings of a run with this configuration's conventions and name
+    /// templates
+    ///
+    /// ```
+    /// use component::gui::config::AppConfig;
+    /// use component::resistance::ValueNotation;
+    ///
+    /// let config = AppConfig { value_notation: Some("european".into()), ..Default::default() };
+    /// assert_eq!(config.settings().unwrap().notation, ValueNotation::European);
+    /// let config = AppConfig { part_template: Some("{prefix}_{package}".into()), ..Default::default() };
+    /// assert!(config.settings().is_err());
+    /// ```
+    pub fn settings(&self) -> Result<Settings, AtlantixError> {
+        fn parse<T: FromStr<Err = AtlantixError> + Default>(value: &Option<String>) -> Result<T, AtlantixError> {
+            value.as_deref().map_or_else(|| Ok(T::default()), str::parse)
+        }
+        let template = |template: &Option<String>| template.as_deref().map(str::parse::<NameTemplate>).transpose();
+        Ok(Settings {
+            symbol_format: parse(&self.kicad_version)?,
+            notation: parse(&self.value_notation)?,
+            density: parse(&self.density)?,
+            footprint_format: parse(&self.footprint_format)?,
+            part_order: parse(&self.sort)?,
+            package_naming: parse(&self.package_naming)?,
+            part_template: template(&self.part_template)?.map(NameTemplate::for_parts).transpose()?,
+            library_template: template(&self.library_template)?.map(NameTemplate::for_libraries).transpose()?,
+            suppliers: self.suppliers.iter().map(|supplier| supplier.parse()).collect::<Result<_, _>>()?,
+            ..Settings::default()
+        })
+    }
+
+    /// Tolerance of every resistor, the series' own when `None`
+    ///
+    /// ```
+    /// use component::gui::config::AppConfig;
+    ///
+    /// let config = AppConfig { series: 192, tolerance: Some("0.1".into()), ..Default::default() };
+    /// assert_eq!(config.resistor_tolerance().unwrap(), Some("0.1%"));
+    /// assert!(AppConfig { tolerance: Some("3%".into()), ..Default::default() }.resistor_tolerance().is_err());
+    /// ```
+    pub fn resistor_tolerance(&self) -> Result<Option<&'static str>, AtlantixError> {
+        self.tolerance.as_deref().map(crate::tolerance::parse).transpose()
+    }
+
+    /// Symbol style of each package
+    ///
+    /// ```
+    /// use component::gui::config::AppConfig;
+    ///
+    /// let mut config = AppConfig::default();
+    /// config.package_styles.insert("0402".into(), "american".into());
+    /// assert_eq!(config.symbol_styles().for_package("0402"), "american");
+    /// assert_eq!(config.generator_config().symbol_style, "european,0402=american");
+    /// ```
+    pub fn symbol_styles(&self) -> ResistorStyles {
+        ResistorStyles { default: self.symbol_style.clone(), packages: self.package_styles.clone() }
+    }
+
+    /// Name of the Altium CSV of each package, the default when the one set
+    /// has no `{package}` and every package would overwrite the same file
+    ///
+    /// ```
+    /// use component::gui::config::AppConfig;
+    ///
+    /// let config = AppConfig { altium_file: Some("Atlantix_R_{package}.csv".into()), ..Default::default() };
+    /// assert_eq!(config.altium_file(), "Atlantix_R_{package}.csv");
+    /// assert_eq!(AppConfig { altium_file: Some("R.csv".into()), ..Default::default() }.altium_file(), "resistors_{package}.csv");
+    /// ```
+    pub fn altium_file(&self) -> &str {
+        match self.altium_file.as_deref() {
+            Some(pattern) if pattern.contains("{package}") => pattern,
+            _ => crate::ecs::ALTIUM_FILE_PATTERN,
+        }
+    }
+
+    /// Resistor values to generate
+    pub fn value_range(&self) -> Result<ValueRange, AtlantixError> {
+        ValueRange::parse(self.min_value.as_deref(), self.max_value.as_deref())
+    }
+
+    /// Pipeline configuration of this setup, writing into `output` when set.
+    /// A value range that does not parse generates the full sweep, settings
+    /// that do not parse the defaults; check them with [`AppConfig::settings`].
+    ///
+    /// ```
+    /// use component::gui::config::AppConfig;
+    ///
+    /// let config = AppConfig { min_value: Some("10".into()), max_value: Some("100K".into()), ..Default::default() };
+    /// assert_eq!(config.generator_config().value_range.decades(), [10.0, 100.0, 1_000.0, 10_000.0, 100_000.0]);
+    /// ```
+    pub fn generator_config(&self) -> GeneratorConfig {
+        let formats = [
+            (self.kicad_symbols, OutputFormat::KicadSymbols),
+            (self.kicad_footprints, OutputFormat::KicadFootprints),
+            (self.altium, OutputFormat::Altium),
+        ];
+        GeneratorConfig {
+            output_formats: formats.into_iter().filter(|(enabled, _)| *enabled).map(|(_, format)| format).collect(),
+            manufacturers: self.manufacturers.clone(),
+            output_dir: self.output.clone(),
+            symbol_style: self.symbol_styles().to_string(),
+            altium_file_pattern: self.altium_file().to_string(),
+            value_range: self.value_range().unwrap_or_default(),
+            settings: self.settings().unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+}
+
+fn read_config(data_dir: &Path) -> Result<toml::Table, AtlantixError> {
+    let path = data_dir.join("config.toml");
+    match fs::read_to_string(&path) {
+        Ok(content) => content.parse().map_err(|e| AtlantixError::Parse(format!("Failed to parse {}: {}", path.display(), e))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(toml::Table::new()),
+        Err(e) => Err(AtlantixError::io("read", &path, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saving_a_profile_keeps_the_rest_of_config_toml() {
+        let dir = std::env::temp_dir().join(format!("gui-config-profile-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("config.toml"),
+            r#"# Output profiles, used with 'aeda export --profile <name>'
+# [profiles.kicad-team-a]
+# target = "kicad"
+
+[stencil]
+# Path where Stencil looks for libraries
+library_path = "libraries"
+
+# The team's KiCad 8 conventions
+[profiles.team]
+kicad_version = "8" # KiCad symbol format
+density = "most"
+"#,
+        )
+        .unwrap();
+
+        let config = AppConfig { kicad_version: Some("7".into()), value_notation: Some("european".into()), ..Default::default() };
+        config.save_profile(&dir, "team").unwrap();
+        config.save_profile(&dir, "precision").unwrap();
+
+        let content = fs::read_to_string(dir.join("config.toml")).unwrap();
+        for comment in ["# [profiles.kicad-team-a]", "# Path where Stencil looks for libraries", "# The team's KiCad 8 conventions", "# KiCad symbol format"] {
+            assert!(content.contains(comment), "lost {:?}:\n{}", comment, content);
+        }
+        assert!(!content.contains("density"));
+        assert_eq!(AppConfig::profile_names(&dir).unwrap(), ["precision", "team"]);
+        let team = AppConfig::from_profile(&dir, "team").unwrap();
+        assert_eq!((team.kicad_version.as_deref(), team.value_notation.as_deref()), (Some("7"), Some("european")));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,66 @@
+//! Library health of the data directory, what `aeda dashboard` prints
+
+use crate::dashboard::{Summary, STALE_DAYS};
+
+/// Libraries by category and review status, missing files, cache freshness,
+/// pending requests and held locks of `summary`
+///
+/// ```
+/// use component::dashboard::{CategorySummary, Summary};
+/// use component::gui::dashboard;
+///
+/// let mut summary = Summary { name: "atlantix_eda".into(), version: "1.0.0".into(), ..Default::default() };
+/// summary.categories.insert("resistor".into(), CategorySummary { libraries: 2, pending: 1, ..Default::default() });
+///
+/// let ctx = egui::Context::default();
+/// let _ = ctx.run(Default::default(), |ctx| {
+///     egui::CentralPanel::default().show(ctx, |ui| dashboard::summary_ui(ui, &summary));
+/// });
+/// ```
+pub fn summary_ui(ui: &mut egui::Ui, summary: &Summary) {
+    ui.heading(format!("{} {}", summary.name, summary.version));
+    match summary.last_generated {
+        Some(time) => ui.label(format!("Last generated: {}", time.format("%Y-%m-%d %H:%M"))),
+        None => ui.label("Last generated: never"),
+    };
+    ui.separator();
+
+    ui.strong("Libraries");
+    egui::Grid::new("dashboard_libraries").striped(true).show(ui, |ui| {
+        for (category, entry) in &summary.categories {
+            ui.label(category);
+            ui.label(entry.libraries.to_string());
+            let statuses: Vec<String> = entry.by_status.iter().map(|(status, n)| format!("{} {}", n, status)).collect();
+            ui.label(statuses.join(", "));
+            ui.end_row();
+        }
+    });
+    ui.separator();
+
+    ui.strong("Validation");
+    let missing = summary.missing();
+    if missing.is_empty() {
+        ui.label("All manifest entries present on disk");
+    } else {
+        ui.colored_label(ui.visuals().warn_fg_color, format!("Missing library files: {}", missing.join(", ")));
+    }
+    ui.label(format!("Awaiting review: {} draft libraries", summary.drafts()));
+    ui.separator();
+
+    ui.strong("Distributor data (Octopart cache)");
+    match summary.oldest_fetch {
+        Some(oldest) => {
+            ui.label(format!("{} parts cached, oldest fetched {}", summary.cached_parts, oldest.format("%Y-%m-%d")));
+            ui.label(format!("{} older than {} days", summary.stale_parts, STALE_DAYS));
+        }
+        None => {
+            ui.label("No cached data");
+        }
+    }
+    ui.separator();
+
+    ui.label(format!("Pending part requests: {}", summary.pending()));
+    if !summary.locked.is_empty() {
+        ui.colored_label(ui.visuals().warn_fg_color, format!("Locked categories: {}", summary.locked.join(", ")));
+    }
+}
@@ -0,0 +1,298 @@
+//! Symbol and footprint drawings of a part, painted to scale
+//!
+//! The geometry is read back from the generated KiCad text, so the preview
+//! shows exactly what a library will contain: the European rectangle or the
+//! American zigzag of the symbol, and the pads, courtyard, silkscreen and
+//! fabrication outline of the footprint.
+
+use crate::kicad_footprint::KicadFootprint;
+use crate::kicad_symbol::KicadSymbol;
+use crate::preview::PreviewRow;
+use crate::AtlantixError;
+use egui::{Color32, Pos2, Rect, Stroke, Vec2};
+use kiparse::sexpr::{parse_sexpr, SExpr};
+
+/// What a shape belongs to, which decides its colour
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    /// Symbol body
+    Body,
+    /// Symbol pins
+    Pin,
+    Copper,
+    Silkscreen,
+    Courtyard,
+    Fabrication,
+}
+
+impl Layer {
+    fn from_footprint(layer: &str) -> Option<Layer> {
+        match layer {
+            "F.SilkS" => Some(Layer::Silkscreen),
+            "F.CrtYd" => Some(Layer::Courtyard),
+            "F.Fab" => Some(Layer::Fabrication),
+            _ => None,
+        }
+    }
+
+    fn color(self) -> Color32 {
+        match self {
+            Layer::Body => Color32::from_rgb(132, 0, 0),
+            Layer::Pin => Color32::from_rgb(132, 0, 0),
+            Layer::Copper => Color32::from_rgb(200, 52, 52),
+            Layer::Silkscreen => Color32::from_rgb(242, 237, 161),
+            Layer::Courtyard => Color32::from_rgb(255, 38, 226),
+            Layer::Fabrication => Color32::from_rgb(175, 175, 175),
+        }
+    }
+}
+
+/// A shape in millimetres, y pointing down as on screen
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Polyline { points: Vec<(f64, f64)>, layer: Layer },
+    Rectangle { start: (f64, f64), end: (f64, f64), layer: Layer },
+    Arc { start: (f64, f64), mid: (f64, f64), end: (f64, f64), layer: Layer },
+    Pad { center: (f64, f64), size: (f64, f64), rounded: bool },
+}
+
+impl Shape {
+    fn points(&self) -> Vec<(f64, f64)> {
+        match self {
+            Shape::Polyline { points, .. } => points.clone(),
+            Shape::Rectangle { start, end, .. } => vec![*start, *end],
+            Shape::Arc { start, mid, end, .. } => vec![*start, *mid, *end],
+            Shape::Pad { center, size, .. } => vec![
+                (center.0 - size.0 / 2.0, center.1 - size.1 / 2.0),
+                (center.0 + size.0 / 2.0, center.1 + size.1 / 2.0),
+            ],
+        }
+    }
+}
+
+/// The shapes of one symbol or footprint
+///
+/// ```
+/// use component::gui::drawing::{Drawing, Shape};
+/// use component::kicad_footprint::KicadFootprint;
+/// use component::kicad_symbol::KicadSymbol;
+///
+/// let symbol = KicadSymbol::new("R0603_1.00K".into(), "1.00K".into(), String::new(), "european");
+/// let drawing = Drawing::symbol(&symbol).unwrap();
+/// assert!(matches!(drawing.shapes[0], Shape::Rectangle { start: (-1.016, 2.54), end: (1.016, -2.54), .. }));
+/// // Body and both pins, 7.62 mm from pin end to pin end
+/// assert_eq!(drawing.shapes.len(), 3);
+/// assert_eq!(drawing.size(), (2.032, 7.62));
+///
+/// let footprint = KicadFootprint::new_smd_resistor("0603").unwrap();
+/// let drawing = Drawing::footprint(&footprint).unwrap();
+/// let pads = drawing.shapes.iter().filter(|shape| matches!(shape, Shape::Pad { .. })).count();
+/// assert_eq!(pads, 2);
+///
+/// let config = component::ecs::resources::GeneratorConfig { value_range: component::ValueRange::parse(Some("1K"), Some("9.99K")).unwrap(), ..Default::default() };
+/// let part = &component::preview::generate(&config, 12, &["0603"])[0];
+/// let ctx = egui::Context::default();
+/// let _ = ctx.run(Default::default(), |ctx| {
+///     egui::CentralPanel::default().show(ctx, |ui| component::gui::drawing::part_ui(ui, part, "american"));
+/// });
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Drawing {
+    pub shapes: Vec<Shape>,
+}
+
+impl Drawing {
+    /// Body and pins of `symbol`, in its own symbol style
+    pub fn symbol(symbol: &KicadSymbol) -> Result<Self, AtlantixError> {
+        let root = parse(&symbol.generate_symbol())?;
+        let mut drawing = Drawing::default();
+        // Symbols have y pointing up
+        let at = |node: Option<&SExpr>| xy(node).map(|(x, y)| (x, -y));
+        walk(&root, &mut |node| {
+            let shape = match node.head() {
+                Some("rectangle") => Shape::Rectangle {
+                    start: at(node.find("start"))?,
+                    end: at(node.find("end"))?,
+                    layer: Layer::Body,
+                },
+                Some("polyline") => Shape::Polyline {
+                    points: node.find("pts")?.find_all("xy").map(|point| at(Some(point))).collect::<Option<_>>()?,
+                    layer: Layer::Body,
+                },
+                Some("arc") => Shape::Arc {
+                    start: at(node.find("start"))?,
+                    mid: at(node.find("mid"))?,
+                    end: at(node.find("end"))?,
+                    layer: Layer::Body,
+                },
+                Some("pin") => {
+                    let position = node.find("at")?;
+                    let (x, y) = at(Some(position))?;
+                    let angle = number(position.value(3)?)?.to_radians();
+                    let length = number(node.find("length")?.value(1)?)?;
+                    let end = (x + length * angle.cos(), y - length * angle.sin());
+                    Shape::Polyline { points: vec![(x, y), end], layer: Layer::Pin }
+                }
+                _ => return None,
+            };
+            drawing.shapes.push(shape);
+            Some(())
+        });
+        Ok(drawing)
+    }
+
+    /// Pads and outlines of `footprint`
+    pub fn footprint(footprint: &KicadFootprint) -> Result<Self, AtlantixError> {
+        let root = parse(&footprint.generate_footprint())?;
+        let mut drawing = Drawing::default();
+        walk(&root, &mut |node| {
+            let shape = match node.head() {
+                Some("fp_line") => Shape::Polyline {
+                    points: vec![xy(node.find("start"))?, xy(node.find("end"))?],
+                    layer: Layer::from_footprint(node.find("layer")?.value(1)?)?,
+                },
+                Some("pad") => Shape::Pad {
+                    center: xy(node.find("at"))?,
+                    size: xy(node.find("size"))?,
+                    rounded: node.value(3) == Some("roundrect"),
+                },
+                _ => return None,
+            };
+            drawing.shapes.push(shape);
+            Some(())
+        });
+        Ok(drawing)
+    }
+
+    /// Smallest and largest corner of all shapes
+    fn bounds(&self) -> ((f64, f64), (f64, f64)) {
+        let points: Vec<(f64, f64)> = self.shapes.iter().flat_map(Shape::points).collect();
+        let fold = |pick: fn(f64, f64) -> f64, init: f64| {
+            points.iter().fold((init, init), |acc, point| (pick(acc.0, point.0), pick(acc.1, point.1)))
+        };
+        if points.is_empty() {
+            return ((0.0, 0.0), (0.0, 0.0));
+        }
+        (fold(f64::min, f64::INFINITY), fold(f64::max, f64::NEG_INFINITY))
+    }
+
+    /// Width and height in millimetres
+    pub fn size(&self) -> (f64, f64) {
+        let (min, max) = self.bounds();
+        (round(max.0 - min.0), round(max.1 - min.1))
+    }
+
+    /// Paint into a `size` square, scaled to fit with the same scale on both
+    /// axes, and a one millimetre scale bar underneath
+    pub fn ui(&self, ui: &mut egui::Ui, size: f32) {
+        let (response, painter) = ui.allocate_painter(Vec2::splat(size), egui::Sense::hover());
+        let rect = response.rect;
+        painter.rect_filled(rect, 4.0, Color32::from_gray(24));
+        let ((min_x, min_y), (max_x, max_y)) = self.bounds();
+        let margin = 24.0;
+        let scale = ((size - 2.0 * margin) / (max_x - min_x).max(max_y - min_y).max(0.1) as f32).min(200.0);
+        let center = Pos2::new(((min_x + max_x) / 2.0) as f32, ((min_y + max_y) / 2.0) as f32);
+        let to_screen = |(x, y): (f64, f64)| rect.center() + (Pos2::new(x as f32, y as f32) - center) * scale;
+
+        for shape in &self.shapes {
+            match shape {
+                Shape::Polyline { points, layer } => {
+                    let points = points.iter().copied().map(to_screen).collect();
+                    painter.add(egui::Shape::line(points, Stroke::new(1.5, layer.color())));
+                }
+                Shape::Rectangle { start, end, layer } => {
+                    let outline = Rect::from_two_pos(to_screen(*start), to_screen(*end));
+                    painter.rect_stroke(outline, 0.0, Stroke::new(1.5, layer.color()));
+                }
+                Shape::Arc { start, mid, end, layer } => {
+                    // A quadratic Bézier through the three points of the arc
+                    let (start, mid, end) = (to_screen(*start), to_screen(*mid), to_screen(*end));
+                    let control = mid + (mid - start.lerp(end, 0.5));
+                    painter.add(egui::epaint::QuadraticBezierShape::from_points_stroke(
+                        [start, control, end],
+                        false,
+                        Color32::TRANSPARENT,
+                        Stroke::new(1.5, layer.color()),
+                    ));
+                }
+                Shape::Pad { center, size, rounded } => {
+                    let half = (size.0 / 2.0, size.1 / 2.0);
+                    let pad = Rect::from_two_pos(
+                        to_screen((center.0 - half.0, center.1 - half.1)),
+                        to_screen((center.0 + half.0, center.1 + half.1)),
+                    );
+                    let rounding = if *rounded { 0.25 * pad.width().min(pad.height()) } else { 0.0 };
+                    painter.rect_filled(pad, rounding, Layer::Copper.color());
+                }
+            }
+        }
+
+        let bar_start = rect.left_bottom() + Vec2::new(8.0, -10.0);
+        painter.line_segment([bar_start, bar_start + Vec2::new(scale, 0.0)], Stroke::new(2.0, Color32::WHITE));
+        painter.text(
+            bar_start + Vec2::new(scale + 6.0, 0.0),
+            egui::Align2::LEFT_CENTER,
+            "1 mm",
+            egui::FontId::proportional(11.0),
+            Color32::WHITE,
+        );
+    }
+}
+
+/// Symbol and footprint of a previewed part side by side, the symbol in
+/// `symbol_style`
+pub fn part_ui(ui: &mut egui::Ui, part: &PreviewRow, symbol_style: &str) {
+    let Some(footprint) = KicadFootprint::new_smd_resistor(&part.package) else {
+        ui.label(format!("No footprint for package {}", part.package));
+        return;
+    };
+    let symbol = KicadSymbol::new(
+        part.part_number.clone(),
+        part.formatted.clone(),
+        format!("Resistor_SMD:{}", footprint.name),
+        symbol_style,
+    );
+    ui.horizontal(|ui| {
+        for (title, drawing) in [("Symbol", Drawing::symbol(&symbol)), ("Footprint", Drawing::footprint(&footprint))] {
+            ui.vertical(|ui| {
+                match &drawing {
+                    Ok(drawing) => {
+                        let (width, height) = drawing.size();
+                        ui.strong(format!("{} ({:.2} × {:.2} mm)", title, width, height));
+                        drawing.ui(ui, 240.0);
+                    }
+                    Err(e) => {
+                        ui.label(format!("{}: {}", title, e));
+                    }
+                }
+            });
+        }
+    });
+}
+
+fn parse(text: &str) -> Result<SExpr, AtlantixError> {
+    parse_sexpr(text).map_err(|e| AtlantixError::Parse(format!("Generated KiCad text does not parse: {}", e)))
+}
+
+/// Call `visit` on every list below `node`
+fn walk(node: &SExpr, visit: &mut impl FnMut(&SExpr) -> Option<()>) {
+    for item in node.items() {
+        visit(item);
+        walk(item, visit);
+    }
+}
+
+fn number(text: &str) -> Option<f64> {
+    text.parse().ok()
+}
+
+/// `x` and `y` of `(key x y ...)`
+fn xy(node: Option<&SExpr>) -> Option<(f64, f64)> {
+    let node = node?;
+    Some((number(node.value(1)?)?, number(node.value(2)?)?))
+}
+
+/// Drop float noise from sums of millimetres
+fn round(mm: f64) -> f64 {
+    (mm * 1_000.0).round() / 1_000.0
+}
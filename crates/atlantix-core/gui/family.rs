@@ -0,0 +1,249 @@
+//! The component family selector and the settings panel of each family
+//!
+//! Resistors, inductors, ferrite beads and LEDs have generators in this
+//! crate. Capacitors join the selector once the core has a generator for
+//! them; until then `aeda generate capacitors` is the only way to make them.
+
+use super::config::AppConfig;
+use crate::kicad_symbol::RESISTOR_STYLES;
+use crate::led::LedColor;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Chip resistor sizes with a power rating, see [`crate::chip_resistor_power`]
+pub const RESISTOR_PACKAGES: [&str; 9] = ["0201", "0402", "0603", "0805", "1206", "1210", "1218", "2010", "2512"];
+
+/// E-series offered for resistors
+const RESISTOR_SERIES: [usize; 7] = [3, 6, 12, 24, 48, 96, 192];
+
+/// E-series inductors are sold in, see [`crate::inductor::inductance_series`]
+const INDUCTOR_SERIES: [usize; 2] = [6, 12];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Family {
+    #[default]
+    Resistors,
+    Inductors,
+    Ferrites,
+    Leds,
+}
+
+impl Family {
+    pub const ALL: [Family; 4] = [Family::Resistors, Family::Inductors, Family::Ferrites, Family::Leds];
+
+    /// Packages the family's generator has data for
+    pub fn packages(self) -> &'static [&'static str] {
+        match self {
+            Family::Resistors => &RESISTOR_PACKAGES,
+            Family::Inductors => &crate::inductor::INDUCTOR_PACKAGES,
+            Family::Ferrites => &crate::ferrite::FERRITE_PACKAGES,
+            Family::Leds => &crate::led::LED_PACKAGES,
+        }
+    }
+}
+
+impl fmt::Display for Family {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Family::Resistors => write!(f, "Resistors"),
+            Family::Inductors => write!(f, "Inductors"),
+            Family::Ferrites => write!(f, "Ferrite beads"),
+            Family::Leds => write!(f, "LEDs"),
+        }
+    }
+}
+
+/// Family selector, the packages of the selected family and its own
+/// settings: series, tolerance, value range, manufacturers, symbol style and
+/// part names of resistors, series and shielding of inductors, colors of LEDs. Switching
+/// family keeps only the packages the new family has.
+///
+/// ```
+/// use component::gui::config::AppConfig;
+/// use component::gui::family::{self, Family};
+///
+/// let mut config = AppConfig { family: Family::Leds, packages: vec!["0603".into(), "2512".into()], ..Default::default() };
+/// family::select(&mut config, Family::Leds);
+/// assert_eq!(config.packages, ["0603"]);
+///
+/// let ctx = egui::Context::default();
+/// let _ = ctx.run(Default::default(), |ctx| {
+///     egui::CentralPanel::default().show(ctx, |ui| family::settings_ui(ui, &mut config));
+/// });
+/// ```
+pub fn settings_ui(ui: &mut egui::Ui, config: &mut AppConfig) {
+    let mut family = config.family;
+    egui::ComboBox::from_label("Family").selected_text(family.to_string()).show_ui(ui, |ui| {
+        for option in Family::ALL {
+            ui.selectable_value(&mut family, option, option.to_string());
+        }
+    });
+    if family != config.family {
+        select(config, family);
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Packages");
+        for package in config.family.packages() {
+            let mut checked = config.packages.iter().any(|p| p == package);
+            if ui.checkbox(&mut checked, *package).changed() {
+                if checked {
+                    config.packages.push(package.to_string());
+                } else {
+                    config.packages.retain(|p| p != package);
+                }
+            }
+        }
+    });
+
+    match config.family {
+        Family::Resistors => {
+            series_ui(ui, "Series", &mut config.series, &RESISTOR_SERIES);
+            tolerance_ui(ui, config);
+            let mut min = config.min_value.clone().unwrap_or_default();
+            let mut max = config.max_value.clone().unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.label("Values from");
+                ui.add(egui::TextEdit::singleline(&mut min).hint_text("1").desired_width(60.0));
+                ui.label("to");
+                ui.add(egui::TextEdit::singleline(&mut max).hint_text("976K").desired_width(60.0));
+            });
+            config.min_value = Some(min.trim().to_string()).filter(|v| !v.is_empty());
+            config.max_value = Some(max.trim().to_string()).filter(|v| !v.is_empty());
+            if let Err(e) = config.value_range() {
+                ui.colored_label(egui::Color32::from_rgb(230, 80, 80), e.to_string());
+            }
+            manufacturers_ui(ui, &mut config.manufacturers);
+            symbol_style_ui(ui, config);
+            let mut template = config.part_template.clone().unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.label("Part name");
+                ui.add(egui::TextEdit::singleline(&mut template).hint_text(crate::naming::DEFAULT_PART))
+                    .on_hover_text("Fields: {prefix} {package} {metric} {value} {code} {tolerance}");
+            });
+            config.part_template = Some(template.trim().to_string()).filter(|t| !t.is_empty());
+            let invalid = config
+                .part_template
+                .as_deref()
+                .map(|template| template.parse::<crate::naming::NameTemplate>().and_then(|template| template.for_parts()));
+            if let Some(Err(e)) = invalid {
+                ui.colored_label(egui::Color32::from_rgb(230, 80, 80), e.to_string());
+            }
+        }
+        Family::Inductors => {
+            series_ui(ui, "Series", &mut config.inductor_series, &INDUCTOR_SERIES);
+            ui.checkbox(&mut config.shielded, "Shielded");
+        }
+        Family::Ferrites => {}
+        Family::Leds => {
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Colors");
+                for color in LedColor::ALL {
+                    let name = color.to_string();
+                    let mut checked = config.led_colors.contains(&name);
+                    if ui.checkbox(&mut checked, &name).changed() {
+                        if checked {
+                            config.led_colors.push(name);
+                        } else {
+                            config.led_colors.retain(|c| *c != name);
+                        }
+                    }
+                }
+            });
+        }
+    }
+}
+
+/// Switch to `family`, dropping the packages it does not have
+pub fn select(config: &mut AppConfig, family: Family) {
+    config.family = family;
+    config.packages.retain(|package| family.packages().contains(&package.as_str()));
+}
+
+/// Tolerance of every resistor, the series' own by default. 0.1% and 0.05%
+/// switch to thin-film MPNs, see [`crate::tolerance`].
+fn tolerance_ui(ui: &mut egui::Ui, config: &mut AppConfig) {
+    let series_own = format!("Series ({})", crate::tolerance::for_series(config.series));
+    let selected = config.resistor_tolerance().ok().flatten();
+    let mut tolerance = selected;
+    egui::ComboBox::from_label("Tolerance").selected_text(tolerance.unwrap_or(&series_own)).show_ui(ui, |ui| {
+        ui.selectable_value(&mut tolerance, None, &series_own);
+        for option in crate::tolerance::TOLERANCES {
+            ui.selectable_value(&mut tolerance, Some(option), option);
+        }
+    });
+    if tolerance != selected {
+        config.tolerance = tolerance.map(str::to_string);
+    }
+    if tolerance.is_some_and(crate::tolerance::is_thin_film) {
+        ui.label("Thin film: Vishay TNPW, Susumu RG");
+    }
+}
+
+/// Symbol style of the resistors and of any package drawn otherwise, each
+/// with a sketch of its body next to the choice
+fn symbol_style_ui(ui: &mut egui::Ui, config: &mut AppConfig) {
+    let glyph = |ui: &mut egui::Ui, style: &str| ui.monospace(crate::kicad_symbol::style_glyph(style).unwrap_or_default());
+    ui.horizontal(|ui| {
+        ui.label("Symbol");
+        for style in RESISTOR_STYLES {
+            ui.radio_value(&mut config.symbol_style, style.to_string(), style);
+        }
+        glyph(ui, &config.symbol_style);
+    });
+    egui::CollapsingHeader::new("Symbol per package").show(ui, |ui| {
+        for package in &config.packages {
+            let selected = config.package_styles.get(package).cloned();
+            let mut style = selected.clone();
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_label(package)
+                    .selected_text(style.as_deref().unwrap_or("default"))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut style, None, "default");
+                        for option in RESISTOR_STYLES {
+                            ui.selectable_value(&mut style, Some(option.to_string()), option);
+                        }
+                    });
+                glyph(ui, style.as_deref().unwrap_or(&config.symbol_style));
+            });
+            if style != selected {
+                match style {
+                    Some(style) => config.package_styles.insert(package.clone(), style),
+                    None => config.package_styles.remove(package),
+                };
+            }
+        }
+    });
+}
+
+/// Manufacturers of the registry, the first ticked the preferred source and
+/// the others alternate MPNs
+fn manufacturers_ui(ui: &mut egui::Ui, manufacturers: &mut Vec<String>) {
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Manufacturers");
+        for name in crate::manufacturer::names() {
+            let mut checked = manufacturers.iter().any(|m| m.eq_ignore_ascii_case(&name));
+            if ui.checkbox(&mut checked, &name).changed() {
+                if checked {
+                    manufacturers.push(name);
+                } else {
+                    manufacturers.retain(|m| !m.eq_ignore_ascii_case(&name));
+                }
+            }
+        }
+    })
+    .response
+    .on_hover_text("The first ticked is the preferred source, the others alternates");
+    if manufacturers.is_empty() {
+        ui.colored_label(egui::Color32::from_rgb(230, 80, 80), "Tick a manufacturer for the parts to have MPNs");
+    }
+}
+
+fn series_ui(ui: &mut egui::Ui, label: &str, series: &mut usize, options: &[usize]) {
+    egui::ComboBox::from_label(label).selected_text(format!("E{}", series)).show_ui(ui, |ui| {
+        for option in options {
+            ui.selectable_value(series, *option, format!("E{}", option));
+        }
+    });
+}
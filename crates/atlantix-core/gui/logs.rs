@@ -0,0 +1,221 @@
+//! The Logs tab: `log` and `tracing` output of every thread, the generation
+//! worker included, captured into a shared buffer
+
+use crate::AtlantixError;
+use std::collections::VecDeque;
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Entries kept, the oldest are dropped first
+const MAX_ENTRIES: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    /// Local time, `14:03:07.250`
+    pub time: String,
+    pub level: log::Level,
+    /// Module the entry comes from
+    pub target: String,
+    pub message: String,
+}
+
+impl fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {:<5} {}: {}", self.time, self.level, self.target, self.message)
+    }
+}
+
+/// Log entries shared between the loggers and the panel
+#[derive(Debug, Clone, Default)]
+pub struct LogBuffer(Arc<Mutex<VecDeque<LogEntry>>>);
+
+impl LogBuffer {
+    pub fn push(&self, level: log::Level, target: &str, message: String) {
+        let mut entries = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() == MAX_ENTRIES {
+            entries.pop_front();
+        }
+        let time = chrono::Local::now().format("%H:%M:%S%.3f").to_string();
+        entries.push_back(LogEntry { time, level, target: target.to_string(), message });
+    }
+
+    pub fn entries(&self) -> Vec<LogEntry> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+/// Send `log` and `tracing` records up to `level` into a new buffer. Call
+/// once at startup; both loggers are process wide.
+///
+/// ```
+/// let buffer = component::gui::logs::capture(log::LevelFilter::Debug).unwrap();
+/// log::info!("from log");
+/// std::thread::spawn(|| tracing::warn!(parts = 96, "from a worker")).join().unwrap();
+/// log::trace!("too verbose");
+///
+/// let messages: Vec<String> = buffer.entries().into_iter().map(|entry| entry.message).collect();
+/// assert_eq!(messages, ["from log", "from a worker parts=96"]);
+/// ```
+pub fn capture(level: log::LevelFilter) -> Result<LogBuffer, AtlantixError> {
+    let buffer = LogBuffer::default();
+    log::set_boxed_logger(Box::new(Capture(buffer.clone())))
+        .map_err(|_| AtlantixError::Invalid("A logger is already installed".to_string()))?;
+    log::set_max_level(level);
+    let subscriber = tracing_subscriber::registry().with(Capture(buffer.clone()));
+    tracing::subscriber::set_global_default(subscriber)
+        .map_err(|_| AtlantixError::Invalid("A tracing subscriber is already installed".to_string()))?;
+    Ok(buffer)
+}
+
+struct Capture(LogBuffer);
+
+impl log::Log for Capture {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            self.0.push(record.level(), record.target(), record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for Capture {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+        let level = match *event.metadata().level() {
+            tracing::Level::ERROR => log::Level::Error,
+            tracing::Level::WARN => log::Level::Warn,
+            tracing::Level::INFO => log::Level::Info,
+            tracing::Level::DEBUG => log::Level::Debug,
+            tracing::Level::TRACE => log::Level::Trace,
+        };
+        if level > log::max_level() {
+            return;
+        }
+        let mut message = Message::default();
+        event.record(&mut message);
+        self.0.push(level, event.metadata().target(), message.0);
+    }
+}
+
+/// The `message` of a tracing event followed by its other fields
+#[derive(Default)]
+struct Message(String);
+
+impl tracing::field::Visit for Message {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.0.insert_str(0, &format!("{:?}", value));
+        } else {
+            self.0.push_str(&format!(" {}={:?}", field.name(), value));
+        }
+    }
+}
+
+/// Captured entries with a level filter, a search box and buttons to copy
+/// or clear them
+///
+/// ```
+/// use component::gui::logs::{LogBuffer, LogPanel};
+///
+/// let buffer = LogBuffer::default();
+/// buffer.push(log::Level::Info, "component::ecs", "Wrote resistors_0603.kicad_sym".into());
+/// buffer.push(log::Level::Debug, "component::ecs", "Spawned 96 parts for 0603".into());
+/// let mut panel = LogPanel::new(buffer);
+/// panel.level = log::LevelFilter::Info;
+/// assert_eq!(panel.visible().len(), 1);
+/// panel.level = log::LevelFilter::Trace;
+/// panel.search = "spawned".into();
+/// assert_eq!(panel.visible()[0].message, "Spawned 96 parts for 0603");
+///
+/// let ctx = egui::Context::default();
+/// let _ = ctx.run(Default::default(), |ctx| {
+///     egui::CentralPanel::default().show(ctx, |ui| panel.ui(ui));
+/// });
+/// ```
+#[derive(Debug)]
+pub struct LogPanel {
+    buffer: LogBuffer,
+    /// Most verbose level shown
+    pub level: log::LevelFilter,
+    /// Text in the message or target, ignoring case
+    pub search: String,
+}
+
+impl LogPanel {
+    pub fn new(buffer: LogBuffer) -> Self {
+        LogPanel { buffer, level: log::LevelFilter::Info, search: String::new() }
+    }
+
+    /// Entries passing the level filter and the search
+    pub fn visible(&self) -> Vec<LogEntry> {
+        let search = self.search.trim().to_lowercase();
+        self.buffer
+            .entries()
+            .into_iter()
+            .filter(|entry| entry.level <= self.level)
+            .filter(|entry| {
+                search.is_empty()
+                    || entry.message.to_lowercase().contains(&search)
+                    || entry.target.to_lowercase().contains(&search)
+            })
+            .collect()
+    }
+
+    /// The entries shown, one per line
+    pub fn text(&self) -> String {
+        self.visible().iter().map(|entry| format!("{}\n", entry)).collect()
+    }
+
+    /// Write the entries shown to `path`
+    pub fn export(&self, path: &Path) -> Result<(), AtlantixError> {
+        crate::atomic::write(path, self.text())
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("Level")
+                .selected_text(self.level.to_string())
+                .show_ui(ui, |ui| {
+                    for level in log::LevelFilter::iter().skip(1) {
+                        ui.selectable_value(&mut self.level, level, level.to_string());
+                    }
+                });
+            ui.label("Search");
+            ui.text_edit_singleline(&mut self.search);
+            if ui.button("Copy").clicked() {
+                ui.ctx().copy_text(self.text());
+            }
+            if ui.button("Clear").clicked() {
+                self.buffer.clear();
+            }
+        });
+
+        let entries = self.visible();
+        egui::ScrollArea::vertical().stick_to_bottom(true).auto_shrink(false).show_rows(
+            ui,
+            ui.text_style_height(&egui::TextStyle::Monospace),
+            entries.len(),
+            |ui, rows| {
+                for entry in &entries[rows] {
+                    let color = match entry.level {
+                        log::Level::Error => egui::Color32::from_rgb(230, 80, 80),
+                        log::Level::Warn => egui::Color32::from_rgb(230, 180, 60),
+                        log::Level::Info => ui.visuals().text_color(),
+                        log::Level::Debug | log::Level::Trace => ui.visuals().weak_text_color(),
+                    };
+                    ui.label(egui::RichText::new(entry.to_string()).monospace().color(color));
+                }
+            },
+        );
+    }
+}
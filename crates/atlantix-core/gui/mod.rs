@@ -0,0 +1,16 @@
+//! egui widgets of the desktop front end, which the `atlantix-gui` crate
+//! mounts in its window. They hold no generation state of their own: what they show comes from [`crate::preview`] and the ECS
+//! pipeline, so the GUI, the CLI and the examples agree on every part.
+
+pub mod config;
+pub mod dashboard;
+pub mod drawing;
+pub mod family;
+pub mod logs;
+pub mod output;
+pub mod part_table;
+
+pub use config::AppConfig;
+pub use drawing::Drawing;
+pub use logs::LogPanel;
+pub use part_table::PartTable;
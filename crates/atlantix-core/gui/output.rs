@@ -0,0 +1,115 @@
+//! Settings every family shares: where the parts are sourced and what is
+//! written
+
+use super::config::AppConfig;
+use super::family::Family;
+use crate::distributor::Distributor;
+use crate::kicad_footprint::DensityLevel;
+use crate::kicad_symbol::KicadFormatVersion;
+
+/// Formats written, suppliers of the generated parts, the first ticked
+/// written as Supplier 1, the KiCad release the symbols are written for and
+/// the IPC-7351B density of the footprints
+///
+/// ```
+/// use component::distributor::Distributor;
+/// use component::gui::config::AppConfig;
+/// use component::kicad_symbol::KicadFormatVersion;
+/// use component::gui::output;
+/// use component::kicad_footprint::DensityLevel;
+///
+/// let mut config = AppConfig {
+///     suppliers: vec!["mouser".into(), "digikey".into()],
+///     kicad_version: Some("8".into()),
+///     density: Some("most".into()),
+///     ..Default::default()
+/// };
+/// let settings = config.settings().unwrap();
+/// assert_eq!(settings.suppliers, [Distributor::Mouser, Distributor::Digikey]);
+/// assert_eq!(settings.symbol_format, KicadFormatVersion::V8);
+/// assert_eq!(settings.density, DensityLevel::Most);
+///
+/// let ctx = egui::Context::default();
+/// let _ = ctx.run(Default::default(), |ctx| {
+///     egui::CentralPanel::default().show(ctx, |ui| output::settings_ui(ui, &mut config));
+/// });
+/// ```
+pub fn settings_ui(ui: &mut egui::Ui, config: &mut AppConfig) {
+    formats_ui(ui, config);
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Suppliers");
+        for distributor in Distributor::ALL {
+            let name = distributor.to_string().to_lowercase();
+            let mut checked = config.suppliers.contains(&name);
+            if ui.checkbox(&mut checked, distributor.to_string()).changed() {
+                if checked {
+                    config.suppliers.push(name);
+                } else {
+                    config.suppliers.retain(|supplier| *supplier != name);
+                }
+            }
+        }
+    })
+    .response
+    .on_hover_text("Written in the order ticked; none keeps each generator's own");
+    kicad_version_ui(ui, config);
+    density_ui(ui, config);
+}
+
+/// KiCad libraries and, for resistors, the Altium CSV of each package
+fn formats_ui(ui: &mut egui::Ui, config: &mut AppConfig) {
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Write");
+        ui.checkbox(&mut config.kicad_symbols, "KiCad symbols");
+        ui.checkbox(&mut config.kicad_footprints, "KiCad footprints");
+        // Only the resistor pipeline has an Altium exporter
+        let resistors = config.family == Family::Resistors;
+        ui.add_enabled(resistors, egui::Checkbox::new(&mut config.altium, "Altium CSV"))
+            .on_disabled_hover_text("Altium libraries are generated for resistors");
+    });
+    if config.family == Family::Resistors && config.altium {
+        let mut file = config.altium_file.clone().unwrap_or_default();
+        ui.horizontal(|ui| {
+            ui.label("CSV name");
+            let edit = egui::TextEdit::singleline(&mut file).hint_text(crate::ecs::ALTIUM_FILE_PATTERN);
+            if ui.add(edit).on_hover_text("One file per package, {package} is replaced by it").changed() {
+                config.altium_file = Some(file.trim().to_string()).filter(|file| !file.is_empty());
+            }
+        });
+        if !file.trim().is_empty() && !file.contains("{package}") {
+            ui.colored_label(ui.visuals().warn_fg_color, format!("No {{package}}, writing {}", crate::ecs::ALTIUM_FILE_PATTERN));
+        }
+    }
+}
+
+/// KiCad release the symbol libraries are written for, the oldest when unset
+fn kicad_version_ui(ui: &mut egui::Ui, config: &mut AppConfig) {
+    let selected: KicadFormatVersion = config.kicad_version.as_deref().and_then(|version| version.parse().ok()).unwrap_or_default();
+    egui::ComboBox::from_label("Symbol format")
+        .selected_text(selected.to_string())
+        .show_ui(ui, |ui| {
+            for version in KicadFormatVersion::ALL {
+                if ui.selectable_label(version == selected, version.to_string()).clicked() {
+                    config.kicad_version = Some(version.major().to_string());
+                }
+            }
+        })
+        .response
+        .on_hover_text("KiCad 6 libraries open in every later release");
+}
+
+/// IPC-7351B land pattern density of the footprints, nominal when unset
+fn density_ui(ui: &mut egui::Ui, config: &mut AppConfig) {
+    let selected: DensityLevel = config.density.as_deref().and_then(|density| density.parse().ok()).unwrap_or_default();
+    egui::ComboBox::from_label("Footprint density")
+        .selected_text(selected.to_string())
+        .show_ui(ui, |ui| {
+            for level in DensityLevel::ALL {
+                if ui.selectable_label(level == selected, level.to_string()).clicked() {
+                    config.density = Some(level.to_string());
+                }
+            }
+        })
+        .response
+        .on_hover_text("least: small pads for dense boards, most: long toes for hand soldering (_L, _M names)");
+}
@@ -0,0 +1,132 @@
+//! Scrollable table of previewed parts, with a search box and a package filter
+
+use crate::preview::{Filter, PreviewRow};
+use egui_extras::{Column, TableBuilder};
+
+const ROW_HEIGHT: f32 = 18.0;
+
+/// The Preview tab: every part of [`crate::preview::generate`], narrowed
+/// down with the search box and the package selector. Clicking a row selects
+/// the part whose symbol and footprint [`crate::gui::drawing::part_ui`] shows.
+///
+/// ```
+/// use component::ecs::resources::GeneratorConfig;
+/// use component::gui::PartTable;
+/// use component::ValueRange;
+///
+/// let config = GeneratorConfig { value_range: ValueRange::parse(Some("1K"), Some("9.99K")).unwrap(), ..Default::default() };
+/// let mut table = PartTable::new(component::preview::generate(&config, 12, &["0603"]));
+/// table.filter.search = "2.15".into();
+/// assert_eq!(table.visible().len(), 1);
+/// assert!(table.selected().is_none());
+///
+/// let ctx = egui::Context::default();
+/// let _ = ctx.run(Default::default(), |ctx| {
+///     egui::CentralPanel::default().show(ctx, |ui| table.ui(ui));
+/// });
+/// ```
+#[derive(Debug, Default)]
+pub struct PartTable {
+    rows: Vec<PreviewRow>,
+    packages: Vec<String>,
+    /// Index into `rows`
+    selected: Option<usize>,
+    pub filter: Filter,
+}
+
+impl PartTable {
+    pub fn new(rows: Vec<PreviewRow>) -> Self {
+        let mut table = PartTable::default();
+        table.set_rows(rows);
+        table
+    }
+
+    /// Show a new preview, e.g. after the configuration changed. A package
+    /// filter the new rows do not have is dropped.
+    pub fn set_rows(&mut self, rows: Vec<PreviewRow>) {
+        self.packages = crate::preview::packages(&rows);
+        if self.filter.package.as_ref().is_some_and(|package| !self.packages.contains(package)) {
+            self.filter.package = None;
+        }
+        // Keep the same part selected when it is still there
+        let selected = self.selected().map(|row| row.part_number.clone());
+        self.selected = selected.and_then(|part| rows.iter().position(|row| row.part_number == part));
+        self.rows = rows;
+    }
+
+    pub fn selected(&self) -> Option<&PreviewRow> {
+        self.selected.and_then(|index| self.rows.get(index))
+    }
+
+    /// Rows passing the filter
+    pub fn visible(&self) -> Vec<&PreviewRow> {
+        self.visible_indices().into_iter().map(|index| &self.rows[index]).collect()
+    }
+
+    fn visible_indices(&self) -> Vec<usize> {
+        (0..self.rows.len()).filter(|&index| self.filter.matches(&self.rows[index])).collect()
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Search");
+            ui.text_edit_singleline(&mut self.filter.search);
+            egui::ComboBox::from_label("Package")
+                .selected_text(self.filter.package.as_deref().unwrap_or("All"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.filter.package, None, "All");
+                    for package in &self.packages {
+                        ui.selectable_value(&mut self.filter.package, Some(package.clone()), package);
+                    }
+                });
+        });
+
+        let visible = self.visible_indices();
+        ui.label(format!("{} of {} parts", visible.len(), self.rows.len()));
+        let mut clicked = None;
+        TableBuilder::new(ui)
+            .striped(true)
+            .sense(egui::Sense::click())
+            .column(Column::auto().at_least(120.0))
+            .column(Column::auto().at_least(60.0))
+            .column(Column::auto().at_least(60.0))
+            .column(Column::initial(320.0).clip(true))
+            .column(Column::remainder())
+            .header(ROW_HEIGHT + 2.0, |mut header| {
+                for title in ["Part", "Value", "Package", "Description", "MPN"] {
+                    header.col(|ui| {
+                        ui.strong(title);
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(ROW_HEIGHT, visible.len(), |mut row| {
+                    let index = visible[row.index()];
+                    let part = &self.rows[index];
+                    row.set_selected(self.selected == Some(index));
+                    row.col(|ui| {
+                        ui.label(&part.part_number);
+                    });
+                    row.col(|ui| {
+                        ui.label(&part.formatted);
+                    });
+                    row.col(|ui| {
+                        ui.label(&part.package);
+                    });
+                    row.col(|ui| {
+                        ui.label(&part.description);
+                    });
+                    row.col(|ui| {
+                        let mpns: Vec<&str> = part.mpns.iter().map(|(_, mpn)| mpn.as_str()).collect();
+                        ui.label(mpns.join(", "));
+                    });
+                    if row.response().clicked() {
+                        clicked = Some(index);
+                    }
+                });
+            });
+        if clicked.is_some() {
+            self.selected = clicked;
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! Per-part hook run once on every generated part.
+//!
+//! Front ends put a [`PartHook`] into the run's [`crate::settings`], e.g. a
+//! script configured by the user. The hook sees each part as its KiCad
+//! symbol and can change any field, add properties or drop the part. It
+//! decides once per part and run: the generators' `parts()`, the symbol
+//! libraries, the Altium rows and the ECS pipeline all take the same
+//! outcome, so a skipped part is missing from every output. Pins and the
+//! symbol body stay those of the exporter. A failing hook leaves the part
+//! unchanged and records the error, see [`take_errors`].
+
+use crate::kicad_symbol::KicadSymbol;
+use crate::settings::Settings;
+use crate::AtlantixError;
+
+/// What to do with a part after the hook ran
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookAction {
+    Keep,
+    Skip,
+}
+
+pub trait PartHook: Send + Sync {
+    fn apply(&self, symbol: &mut KicadSymbol) -> Result<HookAction, AtlantixError>;
+}
+
+/// Errors the hook raised in the current run since the last call, one per
+/// failing part
+pub fn take_errors() -> Vec<String> {
+    crate::settings::with(|settings| std::mem::take(&mut *settings.run.hook_errors.lock().unwrap_or_else(|e| e.into_inner())))
+}
+
+/// Pass a generated symbol through the hook of the current settings,
+/// `None` when the part is dropped
+pub(crate) fn apply(symbol: KicadSymbol) -> Option<KicadSymbol> {
+    let settings = crate::settings::current();
+    if settings.hook.is_none() {
+        return Some(symbol);
+    }
+    let edited = decide(&settings, &symbol)?;
+    Some(KicadSymbol { pins: symbol.pins, sense_pins: symbol.sense_pins, symbol_style: symbol.symbol_style, ..edited })
+}
+
+/// What the hook made of one part, see [`outcome`]
+pub(crate) struct Outcome(Option<(KicadSymbol, KicadSymbol)>);
+
+impl Outcome {
+    /// The symbol the hook kept, `None` without a hook
+    pub(crate) fn into_symbol(self) -> Option<KicadSymbol> {
+        self.0.map(|(_, edited)| edited)
+    }
+
+    /// A field of the part's symbol after the hook, `None` where the hook
+    /// left it as generated
+    pub(crate) fn changed(&self, field: impl Fn(&KicadSymbol) -> &String) -> Option<&str> {
+        let (generated, edited) = self.0.as_ref()?;
+        (field(generated) != field(edited)).then(|| field(edited).as_str())
+    }
+}
+
+/// The hook's outcome for the part whose symbol `symbol` builds, `None` when
+/// it is dropped. Without a hook nothing is built.
+pub(crate) fn outcome(symbol: impl FnOnce() -> KicadSymbol) -> Option<Outcome> {
+    let settings = crate::settings::current();
+    if settings.hook.is_none() {
+        return Some(Outcome(None));
+    }
+    let generated = symbol();
+    let edited = decide(&settings, &generated)?;
+    Some(Outcome(Some((generated, edited))))
+}
+
+/// Run the hook on a part once per run, parts are told apart by the name
+/// and fields they were generated with
+fn decide(settings: &Settings, symbol: &KicadSymbol) -> Option<KicadSymbol> {
+    let hook = settings.hook.as_ref()?;
+    let decided = settings
+        .run
+        .hooked
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&symbol.name)
+        .filter(|(generated, _)| same_part(generated, symbol))
+        .map(|(_, edited)| edited.clone());
+    if let Some(edited) = decided {
+        return edited;
+    }
+    let mut edited = symbol.clone();
+    let edited = match hook.apply(&mut edited) {
+        Ok(HookAction::Keep) => Some(edited),
+        Ok(HookAction::Skip) => None,
+        Err(e) => {
+            settings
+                .run
+                .hook_errors
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(format!("{}: {}", symbol.name, e));
+            Some(symbol.clone())
+        }
+    };
+    settings
+        .run
+        .hooked
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(symbol.name.clone(), (symbol.clone(), edited.clone()));
+    edited
+}
+
+/// Whether two symbols are the same part, whatever body and pins their
+/// exporters drew
+fn same_part(a: &KicadSymbol, b: &KicadSymbol) -> bool {
+    *a == KicadSymbol { pins: a.pins.clone(), sense_pins: a.sense_pins, symbol_style: a.symbol_style.clone(), ..b.clone() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::components::{ESeries, Package, PartNumber};
+    use crate::ecs::resources::{AltiumCsvBuffer, ESeriesCache, GeneratorConfig, OutputFormat, SymbolLibraryBuffer};
+    use crate::ecs;
+    use crate::{Resistor, ValueRange};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Drops 1.00K and notes the rest, counting the parts it saw
+    #[derive(Default)]
+    struct Counting(AtomicUsize);
+
+    impl PartHook for Counting {
+        fn apply(&self, symbol: &mut KicadSymbol) -> Result<HookAction, AtlantixError> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            if symbol.value == "1.00K" {
+                return Ok(HookAction::Skip);
+            }
+            symbol.description = "checked".to_string();
+            Ok(HookAction::Keep)
+        }
+    }
+
+    #[test]
+    fn every_output_takes_the_one_outcome_of_a_part() {
+        let hook = Arc::new(Counting::default());
+        let settings = Settings { hook: Some(hook.clone()), ..Settings::default() };
+        settings.scope(|| {
+            let mut resistor = Resistor::new(24, "0603".to_string());
+            let symbols = resistor.kicad_symbol_library(&[1_000.0], "american");
+            assert_eq!(symbols.symbols.len(), 23);
+            assert!(symbols.symbols.iter().all(|symbol| symbol.description == "checked" && symbol.symbol_style == "american"));
+            let mut csv = Vec::new();
+            crate::altium::write_csv(&mut resistor, &[1_000.0], &mut csv).unwrap();
+            let csv = String::from_utf8(csv).unwrap();
+            assert_eq!(csv.lines().count(), 1 + 23);
+            assert!(!csv.contains("RES0603_1.00K") && csv.contains(",\"checked\","));
+            assert_eq!(resistor.iter_parts(&[1_000.0]).count(), 23);
+            assert_eq!(resistor.dropped_parts(&[1_000.0]), ["R0603_1.00K"]);
+        });
+        assert_eq!(hook.0.load(Ordering::Relaxed), 24);
+
+        let mut world = ecs::build_resistor_world();
+        world.insert_resource(GeneratorConfig {
+            output_formats: vec![OutputFormat::KicadSymbols, OutputFormat::Altium],
+            value_range: ValueRange::parse(Some("1K"), Some("9.99K")).unwrap(),
+            settings,
+            ..Default::default()
+        });
+        world.insert_resource(ESeriesCache::default());
+        world.spawn((ESeries(24), Package { name: "0603".into(), imperial: "0603".into(), metric: String::new() }));
+        ecs::run_generation_pipeline(&mut world);
+        assert_eq!(world.query::<&PartNumber>().iter(&world).count(), 23);
+        let symbols = &world.resource::<SymbolLibraryBuffer>().libraries["0603"];
+        assert!(symbols.iter().all(|(_, symbol)| symbol.description == "checked"));
+        assert_eq!(world.resource::<AltiumCsvBuffer>().packages["0603"].len(), 23);
+    }
+}
@@ -0,0 +1,293 @@
+//! Inductor library generation.
+//!
+//! Covers multilayer chip inductors (0603 to 1210) and wirewound power
+//! inductor cases (4040, 5050, 6060) in shielded and unshielded variants.
+//! Part numbers follow the Murata LQM (chip) and LQH (power) schemes.
+//!
+//! Current and DCR ratings are nominal, scaled from a 1 uH reference part in
+//! each package: saturation and rated current fall with the square root of
+//! inductance and DCR rises linearly. Confirm against the datasheet before
+//! release.
+
+use crate::kicad_footprint::{self, FootprintNaming, KicadFootprint};
+use crate::kicad_symbol::{KicadSymbol, KicadSymbolLib};
+use crate::status::PartStatus;
+use crate::AtlantixError;
+
+/// Inductance decades in uH, covering 10 nH up to 680 uH with E6/E12 bases.
+pub const INDUCTOR_DECADES: [f64; 5] = [0.01, 0.1, 1.0, 10.0, 100.0];
+
+/// Inductance series base values. E6 and E12 are the series inductors are
+/// actually sold in, so they are tabulated rather than computed.
+pub fn inductance_series(eseries: usize) -> Option<Vec<f64>> {
+    match eseries {
+        6 => Some(vec![1.0, 1.5, 2.2, 3.3, 4.7, 6.8]),
+        12 => Some(vec![1.0, 1.2, 1.5, 1.8, 2.2, 2.7, 3.3, 3.9, 4.7, 5.6, 6.8, 8.2]),
+        _ => None,
+    }
+}
+
+struct InductorPackage {
+    /// Murata series prefix, e.g. LQM18P
+    murata_series: &'static str,
+    /// Murata packaging/option suffix after the tolerance letter
+    murata_suffix: &'static str,
+    /// Ratings of a 1 uH part in this package
+    rated_current: f64,
+    saturation_current: f64,
+    dcr_mohm: f64,
+    /// Inductance range supported by the package, in uH
+    min_uh: f64,
+    max_uh: f64,
+}
+
+fn package_info(package: &str) -> Option<InductorPackage> {
+    let (murata_series, murata_suffix, rated_current, saturation_current, dcr_mohm, min_uh, max_uh) = match package {
+        "0603" => ("LQM18P", "FRL", 0.5, 0.4, 190.0, 0.01, 4.7),
+        "0805" => ("LQM21P", "FRL", 0.8, 0.6, 120.0, 0.01, 10.0),
+        "1008" => ("LQM2HP", "GRL", 1.5, 1.2, 60.0, 0.1, 10.0),
+        "1206" => ("LQM31P", "FRL", 1.2, 1.0, 85.0, 0.1, 10.0),
+        "1210" => ("LQH32P", "R0L", 2.0, 1.8, 45.0, 0.47, 47.0),
+        "4040" => ("LQH44P", "R0L", 2.5, 3.0, 36.0, 1.0, 100.0),
+        "5050" => ("LQH5BP", "T0L", 4.0, 4.5, 18.0, 1.0, 220.0),
+        "6060" => ("LQH6PP", "R0L", 4.3, 5.5, 13.0, 1.0, 680.0),
+        _ => return None,
+    };
+    Some(InductorPackage {
+        murata_series,
+        murata_suffix,
+        rated_current,
+        saturation_current,
+        dcr_mohm,
+        min_uh,
+        max_uh,
+    })
+}
+
+/// Packages supported by the inductor generator
+pub const INDUCTOR_PACKAGES: [&str; 8] = ["0603", "0805", "1008", "1206", "1210", "4040", "5050", "6060"];
+
+/// Format an inductance in uH the way it appears in part names: `47nH`, `4.7uH`, `100uH`.
+///
+/// ```
+/// assert_eq!(component::inductor::format_inductance(0.047), "47nH");
+/// assert_eq!(component::inductor::format_inductance(4.7), "4.7uH");
+/// assert_eq!(component::inductor::format_inductance(100.0), "100uH");
+/// ```
+pub fn format_inductance(uh: f64) -> String {
+    if uh < 1.0 {
+        format!("{}nH", trim_float(uh * 1000.0))
+    } else {
+        format!("{}uH", trim_float(uh))
+    }
+}
+
+fn trim_float(value: f64) -> String {
+    let rounded = (value * 10.0).round() / 10.0;
+    if rounded.fract() == 0.0 {
+        format!("{}", rounded as u64)
+    } else {
+        format!("{:.1}", rounded)
+    }
+}
+
+/// Encode an inductance in uH as a Murata three character code, with R as the
+/// decimal point below 10 uH (`R47`, `4R7`) and a multiplier digit above (`220`, `101`).
+///
+/// ```
+/// assert_eq!(component::inductor::murata_inductance_code(0.47), "R47");
+/// assert_eq!(component::inductor::murata_inductance_code(4.7), "4R7");
+/// assert_eq!(component::inductor::murata_inductance_code(22.0), "220");
+/// assert_eq!(component::inductor::murata_inductance_code(100.0), "101");
+/// ```
+pub fn murata_inductance_code(uh: f64) -> String {
+    crate::marking::inductor_code(uh).unwrap_or_default()
+}
+
+/// One generated inductor part
+#[derive(Debug, Clone, PartialEq)]
+pub struct InductorPart {
+    pub name: String,
+    pub value: String,
+    pub inductance_uh: f64,
+    pub package: String,
+    pub shielded: bool,
+    pub rated_current: f64,
+    pub saturation_current: f64,
+    pub dcr_mohm: f64,
+    pub mpn: String,
+    /// Value code printed on the part, `4R7` for 4.7uH
+    pub code: String,
+    /// Internal part number, see [`crate::ipn`], given by `parts()` to parts
+    /// the hook keeps
+    pub ipn: Option<String>,
+}
+
+///
+/// Inductor generator for one series/package/shielding combination.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inductor {
+    series: usize,
+    package: String,
+    shielded: bool,
+    series_array: Vec<f64>,
+    status: Option<PartStatus>,
+    footprint_naming: FootprintNaming,
+}
+
+impl Inductor {
+    /// Returns `None` for unsupported series or packages.
+    pub fn new(eseries: usize, package: &str, shielded: bool) -> Option<Inductor> {
+        package_info(package)?;
+        Some(Inductor {
+            series: eseries,
+            package: package.to_string(),
+            shielded,
+            series_array: inductance_series(eseries)?,
+            status: None,
+            footprint_naming: FootprintNaming::Atlantix,
+        })
+    }
+
+    /// Stamps every subsequently generated KiCad symbol with a hidden `Status` property.
+    pub fn set_status(&mut self, status: PartStatus) {
+        self.status = Some(status);
+    }
+
+    /// With `FootprintNaming::KicadOfficial` the symbols reference the stock
+    /// KiCad footprints of the package in place of the generated ones.
+    pub fn set_footprint_naming(&mut self, naming: FootprintNaming) {
+        self.footprint_naming = naming;
+    }
+
+    pub fn series(&self) -> usize {
+        self.series
+    }
+
+    /// All parts for the given decades (in uH) that fall inside the package's
+    /// inductance range, as the part hook leaves them.
+    pub fn parts(&self, decades: &[f64]) -> Vec<InductorPart> {
+        let footprint = self.footprint_name().unwrap_or_default();
+        self.generated_parts(decades)
+            .into_iter()
+            .filter_map(|mut part| {
+                let outcome = crate::hook::outcome(|| self.kicad_symbol(&part, &footprint))?;
+                for (field, changed) in [
+                    (&mut part.name, outcome.changed(|symbol| &symbol.name)),
+                    (&mut part.value, outcome.changed(|symbol| &symbol.value)),
+                    (&mut part.mpn, outcome.changed(|symbol| &symbol.mpn)),
+                ] {
+                    if let Some(changed) = changed {
+                        *field = changed.to_string();
+                    }
+                }
+                part.ipn = crate::ipn::assign("inductor", &self.package, &part.name, "");
+                Some(part)
+            })
+            .collect()
+    }
+
+    fn generated_parts(&self, decades: &[f64]) -> Vec<InductorPart> {
+        let info = package_info(&self.package).expect("package validated in new()");
+        let mut parts = Vec::new();
+        for decade in decades {
+            for base in &self.series_array {
+                let uh = ((base * decade) * 1000.0).round() / 1000.0;
+                if uh < info.min_uh || uh > info.max_uh {
+                    continue;
+                }
+                parts.push(self.part(&info, uh));
+            }
+        }
+        parts
+    }
+
+    fn part(&self, info: &InductorPackage, uh: f64) -> InductorPart {
+        let value = format_inductance(uh);
+        let scale = uh.sqrt();
+        // Shielded cores saturate earlier and run warmer
+        let shield_derating = if self.shielded { 0.8 } else { 1.0 };
+        let shield_code = if self.shielded { "H" } else { "N" };
+        let variant = if self.shielded { "_Shielded" } else { "" };
+        let name = format!("L{}_{}{}", crate::package_naming::label(&self.package), value, variant);
+        InductorPart {
+            ipn: None,
+            name,
+            value,
+            inductance_uh: uh,
+            package: self.package.clone(),
+            shielded: self.shielded,
+            rated_current: round2(info.rated_current / scale * shield_derating),
+            saturation_current: round2(info.saturation_current / scale * shield_derating),
+            dcr_mohm: round2(info.dcr_mohm * uh),
+            mpn: format!(
+                "{}{}{}M{}",
+                info.murata_series,
+                shield_code,
+                murata_inductance_code(uh),
+                info.murata_suffix
+            ),
+            code: murata_inductance_code(uh),
+        }
+    }
+
+    /// Footprint the symbols reference, `None` without one for the package
+    pub fn footprint_name(&self) -> Option<String> {
+        match self.footprint_naming {
+            FootprintNaming::Atlantix => {
+                KicadFootprint::new_smd_inductor(&self.package).map(|fp| format!("Atlantix_Inductors:{}", fp.name))
+            }
+            FootprintNaming::KicadOfficial => kicad_footprint::official_footprint("Inductor_SMD", "L", &self.package),
+        }
+    }
+
+    pub fn generate_kicad_symbols(&self, decades: &[f64], output_path: &str) -> Result<(), AtlantixError> {
+        let mut symbol_lib = KicadSymbolLib::new();
+        let footprint = self.footprint_name().unwrap_or_default();
+
+        for part in self.generated_parts(decades) {
+            if let Some(mut symbol) = crate::hook::apply(self.kicad_symbol(&part, &footprint)) {
+                crate::ipn::assign_symbol("inductor", &self.package, &mut symbol);
+                symbol_lib.add_symbol(symbol);
+            }
+        }
+
+        crate::atomic::write(output_path, symbol_lib.generate_library())
+    }
+
+    /// The KiCad symbol of a generated part, before the part hook
+    fn kicad_symbol(&self, part: &InductorPart, footprint: &str) -> KicadSymbol {
+        let description = format!(
+            "IND SMT {} ({}), {}, {}, Irated {}A, Isat {}A, DCR {}mOhm",
+            part.value,
+            part.code,
+            self.package,
+            if self.shielded { "shielded" } else { "unshielded" },
+            part.rated_current,
+            part.saturation_current,
+            part.dcr_mohm
+        );
+        let mut symbol = KicadSymbol::new_inductor(part.name.clone(), part.value.clone(), footprint.to_string())
+            .with_sources("Murata", &part.mpn, &part.mpn);
+        symbol.properties.push(("Marking".to_string(), part.code.clone()));
+        symbol.description = description;
+        symbol.status = self.status;
+        symbol
+    }
+
+    pub fn generate_kicad_footprints(packages: &[&str], output_dir: &str) -> Result<(), AtlantixError> {
+        crate::atomic::create_dir_all(output_dir)?;
+        for package in packages {
+            if let Some(footprint) = KicadFootprint::new_smd_inductor(package) {
+                let filename = format!("{}/{}.kicad_mod", output_dir, footprint.name);
+                crate::atomic::write(filename, footprint.generate_footprint())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
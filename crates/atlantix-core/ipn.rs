@@ -0,0 +1,255 @@
+//! Internal part numbers.
+//!
+//! Companies that keep their own part numbers next to the MPNs, such as
+//! `ATX-R-000123`, describe them with a scheme of literal text and fields:
+//! `{family}`, the family code (`R`, `C`, `L`, `FB`, `D` or the upper-case
+//! family name), `{package}` and `{seq}` or `{seq:N}`, a sequence number
+//! zero-padded to N digits. Every distinct prefix before the sequence counts
+//! on its own, so `ATX-{family}-{seq:6}` numbers resistors and capacitors
+//! separately.
+//!
+//! Numbers once handed out are kept in a registry file by part name and
+//! tolerance, so generating again gives every part the IPN it had before
+//! and new parts the next free numbers, and a 1% and a 5% part of the same
+//! name get numbers of their own. A [`Numbering`] pairs a scheme with its
+//! registry; with one in the current settings, [`assign`] looks numbers up,
+//! and the front end saves the registry once the run is done. Symbols get
+//! theirs with [`assign_symbol`] once the part hook has kept them, so
+//! dropped parts use up no numbers.
+
+use crate::kicad_symbol::KicadSymbol;
+use crate::AtlantixError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Name of the registry in the data directory
+pub const REGISTRY: &str = "ipn.toml";
+
+/// Code of a family in IPNs
+pub fn family_code(category: &str) -> String {
+    match category {
+        "resistor" => "R".to_string(),
+        "capacitor" => "C".to_string(),
+        "inductor" => "L".to_string(),
+        "ferrite" => "FB".to_string(),
+        "led" | "diode" => "D".to_string(),
+        other => other.to_uppercase(),
+    }
+}
+
+/// A parsed IPN scheme
+///
+/// ```
+/// use component::ipn::IpnScheme;
+///
+/// let scheme: IpnScheme = "ATX-{family}-{seq:6}".parse().unwrap();
+/// assert_eq!(scheme.prefix("resistor", "0603"), "ATX-R-");
+/// assert_eq!(scheme.number("resistor", "0603", 123), "ATX-R-000123");
+/// assert!("ATX-{family}".parse::<IpnScheme>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct IpnScheme {
+    source: String,
+    /// Text up to `{seq}`, the digits and the text after it
+    before: String,
+    width: usize,
+    after: String,
+}
+
+impl FromStr for IpnScheme {
+    type Err = AtlantixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let start = s
+            .find("{seq")
+            .ok_or_else(|| AtlantixError::Parse(format!("IPN scheme '{}' needs a {{seq}} or {{seq:N}} field", s)))?;
+        let end = start
+            + s[start..].find('}').ok_or_else(|| AtlantixError::Parse(format!("Unclosed '{{' in IPN scheme '{}'", s)))?;
+        let width = match &s[start + 4..end] {
+            "" => 0,
+            digits => digits
+                .strip_prefix(':')
+                .and_then(|digits| digits.parse().ok())
+                .ok_or_else(|| AtlantixError::Parse(format!("Invalid '{}' in IPN scheme '{}', expected {{seq:N}}", &s[start..=end], s)))?,
+        };
+        let scheme = IpnScheme { source: s.to_string(), before: s[..start].to_string(), width, after: s[end + 1..].to_string() };
+        for text in [&scheme.before, &scheme.after] {
+            let fields = text.replace("{family}", "").replace("{package}", "");
+            if fields.contains(['{', '}']) {
+                return Err(AtlantixError::Parse(format!(
+                    "Unknown field in IPN scheme '{}', expected {{family}}, {{package}} and {{seq}}",
+                    s
+                )));
+            }
+        }
+        Ok(scheme)
+    }
+}
+
+impl fmt::Display for IpnScheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl IpnScheme {
+    fn fill(text: &str, category: &str, package: &str) -> String {
+        text.replace("{family}", &family_code(category)).replace("{package}", package)
+    }
+
+    /// Text before the sequence number, the key of its counter
+    pub fn prefix(&self, category: &str, package: &str) -> String {
+        IpnScheme::fill(&self.before, category, package)
+    }
+
+    pub fn number(&self, category: &str, package: &str, sequence: u64) -> String {
+        format!(
+            "{}{:0width$}{}",
+            self.prefix(category, package),
+            sequence,
+            IpnScheme::fill(&self.after, category, package),
+            width = self.width
+        )
+    }
+}
+
+/// IPNs handed out so far
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Registry {
+    /// Next sequence number by prefix
+    #[serde(default)]
+    pub next: BTreeMap<String, u64>,
+    /// IPN by part name and tolerance, `R0603_4.99K 1%`
+    #[serde(default)]
+    pub parts: BTreeMap<String, String>,
+}
+
+impl Registry {
+    /// Read a registry, a missing file is an empty one
+    pub fn load(path: &Path) -> Result<Self, AtlantixError> {
+        match fs::read_to_string(path) {
+            Ok(content) => toml::from_str(&content)
+                .map_err(|e| AtlantixError::Parse(format!("Invalid IPN registry {}: {}", path.display(), e))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Registry::default()),
+            Err(e) => Err(AtlantixError::io("read", path, e)),
+        }
+    }
+
+    /// Registry key of a part, its name alone for parts without a tolerance
+    fn key(part: &str, tolerance: &str) -> String {
+        match tolerance {
+            "" => part.to_string(),
+            tolerance => format!("{} {}", part, tolerance),
+        }
+    }
+
+    /// IPN of `part` in `tolerance`, the next free number of its prefix when
+    /// it has none
+    ///
+    /// ```
+    /// use component::ipn::{IpnScheme, Registry};
+    ///
+    /// let scheme: IpnScheme = "ATX-{family}-{seq:6}".parse().unwrap();
+    /// let mut registry = Registry::default();
+    /// assert_eq!(registry.assign(&scheme, "resistor", "0603", "R0603_4.99K", "1%"), "ATX-R-000001");
+    /// assert_eq!(registry.assign(&scheme, "capacitor", "0603", "C0603_X7R_100nF", ""), "ATX-C-000001");
+    /// assert_eq!(registry.assign(&scheme, "resistor", "0603", "R0603_10.0K", "1%"), "ATX-R-000002");
+    /// // Parts keep their numbers, other tolerances get their own
+    /// assert_eq!(registry.assign(&scheme, "resistor", "0603", "R0603_4.99K", "1%"), "ATX-R-000001");
+    /// assert_eq!(registry.assign(&scheme, "resistor", "0603", "R0603_4.99K", "5%"), "ATX-R-000003");
+    /// // Numbers of registries keyed by name alone carry over
+    /// registry.parts.insert("R0603_1.00K".into(), "ATX-R-000042".into());
+    /// assert_eq!(registry.assign(&scheme, "resistor", "0603", "R0603_1.00K", "1%"), "ATX-R-000042");
+    /// assert!(!registry.parts.contains_key("R0603_1.00K"));
+    /// ```
+    pub fn assign(&mut self, scheme: &IpnScheme, category: &str, package: &str, part: &str, tolerance: &str) -> String {
+        let key = Registry::key(part, tolerance);
+        if let Some(ipn) = self.parts.get(&key) {
+            return ipn.clone();
+        }
+        // Registries written before tolerances were part of the key hold the
+        // name alone, its number goes to the first tolerance asking for it
+        let ipn = self.parts.remove(part).unwrap_or_else(|| {
+            let next = self.next.entry(scheme.prefix(category, package)).or_insert(1);
+            let ipn = scheme.number(category, package, *next);
+            *next += 1;
+            ipn
+        });
+        self.parts.insert(key, ipn.clone());
+        ipn
+    }
+}
+
+/// A scheme with the registry it continues, as carried by the `ipn`
+/// settings of a run
+///
+/// ```
+/// use component::ipn::{self, Numbering};
+/// use component::settings::Settings;
+/// use std::sync::{Arc, Mutex};
+///
+/// let path = std::env::temp_dir().join("atlantix_ipn_doc.toml");
+/// let numbering = Numbering::load("ATX-{family}-{seq:4}".parse().unwrap(), &path).unwrap();
+/// let settings = Settings { ipn: Some(Arc::new(Mutex::new(numbering))), ..Settings::default() };
+/// settings.scope(|| {
+///     assert_eq!(ipn::assign("resistor", "0603", "R0603_4.99K", "1%").as_deref(), Some("ATX-R-0001"));
+///     assert_eq!(ipn::assign("resistor", "0603", "R0603_4.99K", "1%").as_deref(), Some("ATX-R-0001"));
+/// });
+/// assert_eq!(ipn::assign("resistor", "0603", "R0603_4.99K", "1%"), None);
+/// ```
+#[derive(Debug)]
+pub struct Numbering {
+    scheme: IpnScheme,
+    registry: Registry,
+    path: PathBuf,
+    changed: bool,
+}
+
+impl Numbering {
+    /// Number parts with `scheme`, continuing the registry at `path`
+    pub fn load(scheme: IpnScheme, path: &Path) -> Result<Self, AtlantixError> {
+        let registry = Registry::load(path)?;
+        Ok(Numbering { scheme, registry, path: path.to_path_buf(), changed: false })
+    }
+
+    /// IPN of a part, a new one for parts not numbered before
+    pub fn assign(&mut self, category: &str, package: &str, part: &str, tolerance: &str) -> String {
+        let known = self.registry.parts.contains_key(&Registry::key(part, tolerance));
+        let ipn = self.registry.assign(&self.scheme, category, package, part, tolerance);
+        self.changed |= !known;
+        ipn
+    }
+
+    /// Write the registry back if parts got new numbers, returning the
+    /// number of parts it holds then
+    pub fn save(&mut self) -> Result<Option<usize>, AtlantixError> {
+        if !self.changed {
+            return Ok(None);
+        }
+        let content = toml::to_string_pretty(&self.registry)
+            .map_err(|e| AtlantixError::Invalid(format!("Failed to serialize IPN registry: {}", e)))?;
+        crate::atomic::write(&self.path, content)?;
+        self.changed = false;
+        Ok(Some(self.registry.parts.len()))
+    }
+}
+
+/// IPN of a part with the numbering of the current [`crate::settings`],
+/// `None` without one
+pub fn assign(category: &str, package: &str, part: &str, tolerance: &str) -> Option<String> {
+    let numbering = crate::settings::with(|settings| settings.ipn.clone())?;
+    let ipn = numbering.lock().unwrap_or_else(|e| e.into_inner()).assign(category, package, part, tolerance);
+    Some(ipn)
+}
+
+/// Add the IPN field to a symbol the part hook kept, numbered by its name
+/// and tolerance
+pub fn assign_symbol(category: &str, package: &str, symbol: &mut KicadSymbol) {
+    if let Some(ipn) = assign(category, package, &symbol.name, &symbol.tolerance) {
+        symbol.properties.push(("IPN".to_string(), ipn));
+    }
+}
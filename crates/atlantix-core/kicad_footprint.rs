@@ -1,4 +1,376 @@
+use crate::kicad_symbol::{self, KicadFormatVersion};
+use crate::AtlantixError;
 use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Which footprint libraries generated symbols reference
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FootprintNaming {
+    /// Footprints generated by atlantix into the `Atlantix_*` libraries
+    #[default]
+    Atlantix,
+    /// Stock footprints from the official KiCad libraries (`Resistor_SMD`, ...).
+    /// No footprints are generated, so custom land patterns do not apply.
+    KicadOfficial,
+}
+
+/// Stock KiCad footprint of a chip or diode package in its official
+/// `library`, `None` for packages the official libraries name otherwise
+///
+/// ```
+/// use component::kicad_footprint::official_footprint;
+///
+/// assert_eq!(official_footprint("Inductor_SMD", "L", "0603").as_deref(), Some("Inductor_SMD:L_0603_1608Metric"));
+/// assert_eq!(official_footprint("Diode_SMD", "D", "SOD-123").as_deref(), Some("Diode_SMD:D_SOD-123"));
+/// assert_eq!(official_footprint("Inductor_SMD", "L", "4040"), None);
+/// ```
+pub fn official_footprint(library: &str, prefix: &str, package: &str) -> Option<String> {
+    let name = match get_package_specs(package) {
+        Some(specs) => format!("{}_{}_{}", prefix, specs.imperial, specs.metric),
+        None => format!("{}_{}", prefix, get_diode_specs(package)?.imperial),
+    };
+    Some(format!("{}:{}", library, name))
+}
+
+impl std::str::FromStr for FootprintNaming {
+    type Err = AtlantixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "atlantix" => Ok(FootprintNaming::Atlantix),
+            "kicad" | "kicad-official" | "official" => Ok(FootprintNaming::KicadOfficial),
+            _ => Err(AtlantixError::Parse(format!("Unknown footprint naming '{}', expected atlantix or kicad", s))),
+        }
+    }
+}
+
+/// IPC-7351B density level of the generated land patterns. The built-in
+/// and imported patterns are nominal, the other levels grow or shrink the
+/// pad toes and sides and the courtyard excess from there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DensityLevel {
+    /// Least (L): small pads and a tight courtyard for dense boards
+    Least,
+    #[default]
+    Nominal,
+    /// Most (M): long toes and a wide courtyard for hand soldering and rework
+    Most,
+}
+
+impl DensityLevel {
+    /// Every level, smallest pads first
+    pub const ALL: [DensityLevel; 3] = [DensityLevel::Least, DensityLevel::Nominal, DensityLevel::Most];
+
+    /// Footprint name suffix, none for nominal
+    pub fn suffix(self) -> &'static str {
+        match self {
+            DensityLevel::Least => "_L",
+            DensityLevel::Nominal => "",
+            DensityLevel::Most => "_M",
+        }
+    }
+
+    /// Courtyard excess around the body in mm
+    pub fn courtyard_margin(self) -> f64 {
+        match self {
+            DensityLevel::Least => 0.1,
+            DensityLevel::Nominal => 0.25,
+            DensityLevel::Most => 0.5,
+        }
+    }
+
+    /// Land pattern at this level. Toes grow outward from the nominal pad,
+    /// the heel stays put. Chips below 0603 get half the toe change.
+    ///
+    /// ```
+    /// use component::kicad_footprint::{get_package_specs, DensityLevel};
+    ///
+    /// let nominal = get_package_specs("0805").unwrap();
+    /// let most = DensityLevel::Most.adjust(&nominal);
+    /// assert!((most.pad_width - nominal.pad_width - 0.2).abs() < 1e-9);
+    /// assert!((most.pad_center_x - nominal.pad_center_x - 0.1).abs() < 1e-9);
+    /// assert!((most.pad_height - nominal.pad_height - 0.1).abs() < 1e-9);
+    /// assert_eq!(DensityLevel::Nominal.adjust(&nominal), nominal);
+    /// ```
+    pub fn adjust(self, spec: &PackageSpec) -> PackageSpec {
+        let small = spec.body_length < 1.6;
+        let (toe, side) = match (self, small) {
+            (DensityLevel::Nominal, _) => return spec.clone(),
+            (DensityLevel::Least, false) => (-0.2, -0.05),
+            (DensityLevel::Least, true) => (-0.1, 0.0),
+            (DensityLevel::Most, false) => (0.2, 0.05),
+            (DensityLevel::Most, true) => (0.1, 0.05),
+        };
+        // Never shrink a pad below a tenth of a millimetre
+        let pad_width = (spec.pad_width + toe).max(0.1);
+        PackageSpec {
+            pad_width,
+            pad_height: (spec.pad_height + 2.0 * side).max(0.1),
+            pad_center_x: spec.pad_center_x + (pad_width - spec.pad_width) / 2.0,
+            ..spec.clone()
+        }
+    }
+}
+
+impl std::str::FromStr for DensityLevel {
+    type Err = AtlantixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "l" | "least" => Ok(DensityLevel::Least),
+            "n" | "nominal" => Ok(DensityLevel::Nominal),
+            "m" | "most" => Ok(DensityLevel::Most),
+            _ => Err(AtlantixError::Parse(format!("Unknown density level '{}', expected least, nominal or most (L, N, M)", s))),
+        }
+    }
+}
+
+impl std::fmt::Display for DensityLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DensityLevel::Least => write!(f, "least"),
+            DensityLevel::Nominal => write!(f, "nominal"),
+            DensityLevel::Most => write!(f, "most"),
+        }
+    }
+}
+
+/// Density level of new footprints in the current [`crate::settings`], so
+/// front ends select it once for every generator
+///
+/// ```
+/// use component::kicad_footprint::{DensityLevel, KicadFootprint};
+/// use component::settings::Settings;
+///
+/// let least = Settings { density: DensityLevel::Least, ..Settings::default() };
+/// let footprint = least.scope(|| KicadFootprint::new_smd_resistor("0603").unwrap());
+/// assert_eq!(footprint.name, "R_0603_1608Metric_L");
+/// assert_eq!(footprint.model_name, "R_0603_1608Metric");
+/// assert_eq!(footprint.courtyard_margin, 0.1);
+/// ```
+pub fn density() -> DensityLevel {
+    crate::settings::with(|settings| settings.density)
+}
+
+/// Grammar of the written `.kicad_mod` files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FootprintFormat {
+    /// `(module ...)` with `tedit`, as KiCad 5 writes it. Newer releases
+    /// read it but warn about the old format.
+    #[default]
+    Legacy,
+    /// `(footprint ...)` with version, generator and an id on every item, in
+    /// the grammar of the selected [`KicadFormatVersion`]: `tstamp` ids for
+    /// KiCad 6 and 7, `uuid`s and `property` texts for KiCad 8
+    Modern,
+}
+
+impl std::str::FromStr for FootprintFormat {
+    type Err = AtlantixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "legacy" | "module" | "5" | "kicad5" => Ok(FootprintFormat::Legacy),
+            "modern" | "footprint" => Ok(FootprintFormat::Modern),
+            _ => Err(AtlantixError::Parse(format!("Unknown footprint format '{}', expected legacy or modern", s))),
+        }
+    }
+}
+
+impl std::fmt::Display for FootprintFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FootprintFormat::Legacy => write!(f, "legacy"),
+            FootprintFormat::Modern => write!(f, "modern"),
+        }
+    }
+}
+
+/// Grammar of written footprints in the current [`crate::settings`]
+///
+/// ```
+/// use component::kicad_footprint::{FootprintFormat, KicadFootprint};
+/// use component::kicad_symbol::KicadFormatVersion;
+/// use component::settings::Settings;
+///
+/// let mut settings = Settings { footprint_format: FootprintFormat::Modern, ..Settings::default() };
+/// settings.symbol_format = KicadFormatVersion::V8;
+/// let generate = || KicadFootprint::new_smd_resistor("0603").unwrap().generate_footprint();
+/// let footprint = settings.scope(generate);
+/// assert!(footprint.starts_with("(footprint \"R_0603_1608Metric\" (version 20240108) (generator \"atlantix\")"));
+/// assert!(footprint.contains("(property \"Reference\" \"REF**\""));
+/// assert!(footprint.contains("(pad \"1\" smd roundrect"));
+/// assert!(!footprint.contains("tedit") && !footprint.contains("tstamp"));
+/// // Every line, text and pad has its own id, the same on every run
+/// let ids: std::collections::HashSet<&str> = footprint.match_indices("(uuid ").map(|(i, _)| &footprint[i..i + 45]).collect();
+/// assert_eq!(ids.len(), footprint.matches("(uuid ").count());
+/// assert_eq!(footprint, settings.scope(generate));
+///
+/// settings.symbol_format = KicadFormatVersion::V7;
+/// let footprint = settings.scope(generate);
+/// assert!(footprint.contains("(version 20221018)") && footprint.contains("(fp_text reference \"REF**\""));
+/// assert!(footprint.contains("(tstamp ") && !footprint.contains("(uuid "));
+/// ```
+pub fn footprint_format() -> FootprintFormat {
+    crate::settings::with(|settings| settings.footprint_format)
+}
+
+/// Soldering process a footprint is shaped for. Reflow uses the plain land
+/// pattern, the other processes get alternates named with their suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SolderProcess {
+    #[default]
+    Reflow,
+    /// Pads elongated outward so an iron tip reaches the fillet
+    HandSoldering,
+    /// Longer toes for the wave to wet, narrower pads against bridging
+    Wave,
+}
+
+impl SolderProcess {
+    /// Footprint name suffix, none for reflow
+    pub fn suffix(self) -> &'static str {
+        match self {
+            SolderProcess::Reflow => "",
+            SolderProcess::HandSoldering => "_HandSoldering",
+            SolderProcess::Wave => "_Wave",
+        }
+    }
+
+    /// Parse a comma-separated list such as "hand,wave"
+    pub fn parse_list(list: &str) -> Result<Vec<SolderProcess>, AtlantixError> {
+        list.split(',').filter(|p| !p.trim().is_empty()).map(str::parse).collect()
+    }
+}
+
+impl std::str::FromStr for SolderProcess {
+    type Err = AtlantixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "reflow" => Ok(SolderProcess::Reflow),
+            "hand" | "handsolder" | "handsoldering" => Ok(SolderProcess::HandSoldering),
+            "wave" => Ok(SolderProcess::Wave),
+            _ => Err(AtlantixError::Parse(format!("Unknown solder process '{}', expected reflow, hand or wave", s))),
+        }
+    }
+}
+
+impl std::fmt::Display for SolderProcess {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SolderProcess::Reflow => write!(f, "reflow"),
+            SolderProcess::HandSoldering => write!(f, "hand"),
+            SolderProcess::Wave => write!(f, "wave"),
+        }
+    }
+}
+
+/// How copper zones connect to the pads of a footprint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ZoneConnection {
+    None,
+    Thermal,
+    Solid,
+}
+
+impl ZoneConnection {
+    /// Value of the footprint's `zone_connect` token
+    fn code(self) -> u8 {
+        match self {
+            ZoneConnection::None => 0,
+            ZoneConnection::Thermal => 1,
+            ZoneConnection::Solid => 2,
+        }
+    }
+}
+
+/// Fabrication house rules written into a footprint, in mm. Unset values
+/// leave the board's design rules in charge.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FabricationRules {
+    /// Solder mask opening beyond the pad, negative to shrink it
+    pub solder_mask_margin: Option<f64>,
+    /// Paste aperture beyond the pad, negative to shrink it
+    pub solder_paste_margin: Option<f64>,
+    /// Paste aperture change as a fraction of the pad size, e.g. -0.1 for 90%
+    pub solder_paste_ratio: Option<f64>,
+    pub zone_connect: Option<ZoneConnection>,
+    /// Spoke width of thermal reliefs
+    pub thermal_width: Option<f64>,
+    /// Gap between pad and zone around thermal reliefs
+    pub thermal_gap: Option<f64>,
+}
+
+impl FabricationRules {
+    /// These rules with the unset values taken from `defaults`
+    pub fn or(self, defaults: FabricationRules) -> FabricationRules {
+        FabricationRules {
+            solder_mask_margin: self.solder_mask_margin.or(defaults.solder_mask_margin),
+            solder_paste_margin: self.solder_paste_margin.or(defaults.solder_paste_margin),
+            solder_paste_ratio: self.solder_paste_ratio.or(defaults.solder_paste_ratio),
+            zone_connect: self.zone_connect.or(defaults.zone_connect),
+            thermal_width: self.thermal_width.or(defaults.thermal_width),
+            thermal_gap: self.thermal_gap.or(defaults.thermal_gap),
+        }
+    }
+
+    /// Footprint level tokens, one per line
+    fn tokens(&self) -> String {
+        let mut tokens = String::new();
+        let mut push = |name: &str, value: Option<f64>| {
+            if let Some(value) = value {
+                tokens.push_str(&format!("  ({} {})\n", name, value));
+            }
+        };
+        push("solder_mask_margin", self.solder_mask_margin);
+        push("solder_paste_margin", self.solder_paste_margin);
+        push("solder_paste_ratio", self.solder_paste_ratio);
+        push("zone_connect", self.zone_connect.map(|z| f64::from(z.code())));
+        push("thermal_width", self.thermal_width);
+        push("thermal_gap", self.thermal_gap);
+        tokens
+    }
+}
+
+/// Default rules and per-package overrides keyed by the imperial size.
+/// Unset override values fall back to `defaults`.
+///
+/// ```
+/// use component::kicad_footprint::{FabricationConfig, FabricationRules, KicadFootprint};
+/// use component::settings::Settings;
+/// use std::collections::HashMap;
+///
+/// let defaults = FabricationRules { solder_mask_margin: Some(0.05), ..Default::default() };
+/// let small = FabricationRules { solder_paste_ratio: Some(-0.1), ..Default::default() };
+/// let fabrication = FabricationConfig { defaults, packages: HashMap::from([("0402".to_string(), small)]) };
+/// let settings = Settings { fabrication: Some(fabrication), ..Settings::default() };
+///
+/// let footprint = settings.scope(|| KicadFootprint::new_smd_resistor("0402").unwrap().generate_footprint());
+/// assert!(footprint.contains("(solder_mask_margin 0.05)"));
+/// assert!(footprint.contains("(solder_paste_ratio -0.1)"));
+/// let footprint = settings.scope(|| KicadFootprint::new_smd_resistor("0603").unwrap().generate_footprint());
+/// assert!(!footprint.contains("solder_paste_ratio"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FabricationConfig {
+    pub defaults: FabricationRules,
+    pub packages: HashMap<String, FabricationRules>,
+}
+
+/// Rules applying to a package in the current [`crate::settings`]
+pub fn fabrication_rules(package: &str) -> FabricationRules {
+    crate::settings::with(|settings| {
+        let Some(config) = settings.fabrication.as_ref() else {
+            return FabricationRules::default();
+        };
+        let rules = config.packages.get(package).copied().unwrap_or_default();
+        rules.or(config.defaults)
+    })
+}
 
 #[derive(Debug, Clone)]
 pub struct Pad {
@@ -21,18 +393,128 @@ pub struct KicadFootprint {
     pub body_size_x: f64,
     pub body_size_y: f64,
     pub courtyard_margin: f64,
+    pub model_library: String,
+    /// 3D model file name without extension, the footprint name without its density suffix
+    pub model_name: String,
+    /// Directory holding `{model_library}.3dshapes`, a path or a KiCad path variable
+    pub model_dir: String,
+    /// Draw a silkscreen bar next to pad 1 (diode/LED cathode)
+    pub cathode_mark: bool,
+    /// Mask, paste and thermal relief settings of the fabrication house
+    pub fabrication: FabricationRules,
 }
 
 impl KicadFootprint {
     pub fn new_smd_resistor(package: &str) -> Option<Self> {
         let specs = get_package_specs(package)?;
-        
-        let name = format!("R_{}_{}", specs.imperial, specs.metric);
+        let (first, second) = crate::package_naming::footprint_codes(&specs);
+        let name = format!("R_{}_{}", first, second);
         let description = format!(
             "Resistor SMD {} ({}), square (rectangular) end terminal, IPC_7351 nominal",
-            specs.imperial, specs.metric
+            first, second
         );
         
+        Some(Self::two_terminal(name, description, "resistor", &specs, "Resistor_SMD"))
+    }
+
+    /// Current-sense resistor. Wide-terminal packages (0612, 1225) have the
+    /// terminals on the long sides. The Kelvin variant splits each terminal
+    /// into a force pad (1, 2) and a narrower sense pad (3, 4).
+    pub fn new_smd_current_sense(package: &str, kelvin: bool) -> Option<Self> {
+        let specs = get_package_specs(package)?;
+        if !kelvin {
+            let (first, second) = crate::package_naming::footprint_codes(&specs);
+            let name = format!("R_{}_{}", first, second);
+            let description = format!(
+                "Current sense resistor SMD {} ({}), IPC_7351 nominal",
+                first, second
+            );
+            return Some(Self::two_terminal(name, description, "resistor shunt current sense", &specs, "Resistor_SMD"));
+        }
+
+        let (first, second) = crate::package_naming::footprint_codes(&specs);
+        let name = format!("R_Shunt_{}_{}_Kelvin", first, second);
+        let description = format!(
+            "Current sense resistor SMD {} ({}), 4-terminal Kelvin connection",
+            first, second
+        );
+        let mut footprint = Self::two_terminal(name, description, "resistor shunt current sense kelvin 4-terminal", &specs, "Resistor_SMD");
+
+        // Force pads take the upper 60% of each terminal, sense pads the lower 30%
+        let terminal = footprint.pads[1].clone();
+        let gap = terminal.size_y * 0.1;
+        let force_height = terminal.size_y * 0.6;
+        let sense_height = terminal.size_y - force_height - gap;
+        let top = -terminal.size_y / 2.0;
+        for pad in &mut footprint.pads {
+            pad.size_y = force_height;
+            pad.at_y = top + force_height / 2.0;
+        }
+        for (number, at_x) in [("3", -terminal.at_x), ("4", terminal.at_x)] {
+            footprint.pads.push(Pad {
+                number: number.to_string(),
+                pad_type: "smd".to_string(),
+                shape: "roundrect".to_string(),
+                at_x,
+                at_y: terminal.size_y / 2.0 - sense_height / 2.0,
+                size_x: terminal.size_x,
+                size_y: sense_height,
+                roundrect_rratio: Some(0.25),
+            });
+        }
+        Some(footprint)
+    }
+
+    /// Chip inductors reuse the SMD chip land patterns; power inductor cases
+    /// (4040, 5050, 6060) get their own larger pads.
+    pub fn new_smd_inductor(package: &str) -> Option<Self> {
+        if let Some(specs) = get_package_specs(package) {
+            let (first, second) = crate::package_naming::footprint_codes(&specs);
+            let name = format!("L_{}_{}", first, second);
+            let description = format!(
+                "Inductor SMD {} ({}), square (rectangular) end terminal, IPC_7351 nominal",
+                first, second
+            );
+            return Some(Self::two_terminal(name, description, "inductor", &specs, "Inductor_SMD"));
+        }
+
+        let specs = get_power_inductor_specs(package)?;
+        let name = format!("L_{}_{:.1}x{:.1}mm", specs.imperial, specs.body_length, specs.body_width);
+        let description = format!(
+            "Inductor SMD power case {} ({:.1}x{:.1}mm)",
+            specs.imperial, specs.body_length, specs.body_width
+        );
+        Some(Self::two_terminal(name, description, "inductor power", &specs, "Inductor_SMD"))
+    }
+
+    /// Chip LEDs on the SMD chip land patterns, cathode on pad 1
+    pub fn new_smd_led(package: &str) -> Option<Self> {
+        let specs = get_package_specs(package)?;
+        let (first, second) = crate::package_naming::footprint_codes(&specs);
+        let name = format!("LED_{}_{}", first, second);
+        let description = format!(
+            "LED SMD {} ({}), square (rectangular) end terminal, IPC_7351 nominal",
+            first, second
+        );
+        let mut footprint = Self::two_terminal(name, description, "LED diode", &specs, "LED_SMD");
+        footprint.cathode_mark = true;
+        Some(footprint)
+    }
+
+    /// Diodes in SOD and DO-214 (SMA/SMB) packages, cathode on pad 1
+    pub fn new_smd_diode(package: &str) -> Option<Self> {
+        let specs = get_package_specs(package).or_else(|| get_diode_specs(package))?;
+        let name = format!("D_{}", specs.imperial);
+        let description = format!("Diode SMD {}, IPC_7351 nominal", specs.imperial);
+        let mut footprint = Self::two_terminal(name, description, "diode", &specs, "Diode_SMD");
+        footprint.cathode_mark = true;
+        Some(footprint)
+    }
+
+    /// Two pad footprint at the selected [`density`], named with its suffix
+    fn two_terminal(name: String, description: String, tags: &str, specs: &PackageSpec, model_library: &str) -> Self {
+        let density = density();
+        let specs = &density.adjust(specs);
         let pads = vec![
             Pad {
                 number: "1".to_string(),
@@ -56,136 +538,355 @@ impl KicadFootprint {
             },
         ];
         
-        Some(KicadFootprint {
-            name,
-            description,
-            tags: "resistor".to_string(),
+        KicadFootprint {
+            name: format!("{}{}", name, density.suffix()),
+            description: description.replace("IPC_7351 nominal", &format!("IPC_7351 {}", density)),
+            tags: tags.to_string(),
             pads,
             body_size_x: specs.body_length,
             body_size_y: specs.body_width,
-            courtyard_margin: 0.25,
-        })
+            courtyard_margin: density.courtyard_margin(),
+            model_library: model_library.to_string(),
+            model_name: name,
+            model_dir: "${KICAD6_3DMODEL_DIR}".to_string(),
+            cathode_mark: false,
+            fabrication: fabrication_rules(&specs.imperial),
+        }
     }
     
+    /// Alternate of this footprint for a soldering process. Hand soldering
+    /// extends every pad outward by half its length (0.2 to 0.6 mm), wave
+    /// soldering by 0.3 mm and narrows it to 85%. The heel stays put and the
+    /// 3D model is shared with the reflow footprint.
+    ///
+    /// ```
+    /// use component::kicad_footprint::{KicadFootprint, SolderProcess};
+    ///
+    /// let reflow = KicadFootprint::new_smd_resistor("0603").unwrap();
+    /// let hand = reflow.for_process(SolderProcess::HandSoldering);
+    /// assert_eq!(hand.name, format!("{}_HandSoldering", reflow.name));
+    /// assert_eq!(hand.model_name, reflow.model_name);
+    /// let inner_edge = |fp: &KicadFootprint| fp.pads[1].at_x - fp.pads[1].size_x / 2.0;
+    /// assert!((inner_edge(&hand) - inner_edge(&reflow)).abs() < 1e-9);
+    /// assert!(hand.pads[1].size_x > reflow.pads[1].size_x);
+    /// let wave = reflow.for_process(SolderProcess::Wave);
+    /// assert!(wave.pads[1].size_y < reflow.pads[1].size_y);
+    /// ```
+    pub fn for_process(&self, process: SolderProcess) -> Self {
+        let mut footprint = self.clone();
+        for pad in &mut footprint.pads {
+            let (toe, height) = match process {
+                SolderProcess::Reflow => return self.clone(),
+                SolderProcess::HandSoldering => ((pad.size_x / 2.0).clamp(0.2, 0.6), pad.size_y),
+                SolderProcess::Wave => (0.3, pad.size_y * 0.85),
+            };
+            pad.size_x += toe;
+            pad.at_x += toe / 2.0 * pad.at_x.signum();
+            pad.size_y = height;
+        }
+        footprint.name.push_str(process.suffix());
+        footprint.description.push_str(&format!(", {} soldering pads", process));
+        footprint
+    }
+
+    /// Silkscreen lines above and below the body, shortened to keep
+    /// [`SILK_PAD_CLEARANCE`] from the copper. Returns the half length and the
+    /// y offset of the lines, `None` when the gap between the pads is too
+    /// narrow for a line.
+    fn silk_lines(&self) -> Option<(f64, f64)> {
+        let half_x = self.body_size_x / 2.0;
+        let silk_y = self.body_size_y / 2.0 + SILK_BODY_OFFSET;
+        let mut half_length = half_x;
+        for pad in &self.pads {
+            let pad_top = pad.at_y.abs() + pad.size_y / 2.0;
+            let dy = (silk_y - pad_top).max(0.0);
+            if dy >= SILK_PAD_CLEARANCE {
+                continue;
+            }
+            let inner_edge = pad.at_x.abs() - pad.size_x / 2.0;
+            let dx = (SILK_PAD_CLEARANCE.powi(2) - dy.powi(2)).sqrt();
+            half_length = half_length.min(inner_edge - dx);
+        }
+        (half_length >= SILK_MIN_HALF_LENGTH).then_some((half_length, silk_y))
+    }
+
+    /// Courtyard half extents: the outermost body or pad edge plus the
+    /// margin, rounded up to the 0.01 mm courtyard grid
+    fn courtyard(&self) -> (f64, f64) {
+        let extent = |size: f64, pad_extent: fn(&Pad) -> f64| {
+            let edge = self.pads.iter().map(pad_extent).fold(size / 2.0, f64::max) + self.courtyard_margin;
+            // Tolerate float noise such as 1.4750000000000001
+            (edge / COURTYARD_GRID - 1e-6).ceil() * COURTYARD_GRID
+        };
+        (
+            extent(self.body_size_x, |pad| pad.at_x.abs() + pad.size_x / 2.0),
+            extent(self.body_size_y, |pad| pad.at_y.abs() + pad.size_y / 2.0),
+        )
+    }
+
+    /// Closed layout of a footprint: the F.Fab body outline, two
+    /// silkscreen lines clear of the pads and a courtyard around both on the
+    /// 0.01 mm grid, following the KiCad library conventions.
+    ///
+    /// ```
+    /// use component::kicad_footprint::KicadFootprint;
+    ///
+    /// let footprint = KicadFootprint::new_smd_resistor("0805").unwrap().generate_footprint();
+    /// let courtyard: Vec<&str> = footprint.lines().filter(|l| l.contains("F.CrtYd")).collect();
+    /// assert_eq!(courtyard.len(), 4);
+    /// assert!(courtyard[0].contains("(start -1.70 -0.98) (end -1.70 0.98)"));
+    /// assert_eq!(footprint.lines().filter(|l| l.contains("F.SilkS") && l.contains("fp_line")).count(), 2);
+    /// ```
     pub fn generate_footprint(&self) -> String {
-        let timestamp = Utc::now().format("%Y%m%d%H%M%S");
-        let courtyard_x = self.body_size_x / 2.0 + self.courtyard_margin;
-        let courtyard_y = self.body_size_y / 2.0 + self.courtyard_margin;
+        let grammar = Grammar::new(footprint_format(), kicad_symbol::default_format(), &self.name);
+        let (courtyard_x, courtyard_y) = self.courtyard();
         
-        let mut footprint = format!(
-            r#"(module {} (layer F.Cu) (tedit {})
-  (descr "{}")
-  (tags {})
-  (attr smd)
-  (fp_text reference REF** (at 0 -{:.2}) (layer F.SilkS)
-    (effects (font (size 1 1) (thickness 0.15)))
-  )
-  (fp_text value {} (at 0 {:.2}) (layer F.Fab)
-    (effects (font (size 1 1) (thickness 0.15)))
-  )
-"#,
-            self.name,
-            timestamp,
-            self.description,
-            self.tags,
-            self.body_size_y / 2.0 + 1.0,
-            self.name,
-            self.body_size_y / 2.0 + 1.0
-        );
+        let mut footprint = match grammar.modern {
+            None => format!(
+                "(module {} (layer F.Cu) (tedit {})\n  (descr \"{}\")\n  (tags {})\n",
+                self.name,
+                Utc::now().format("%Y%m%d%H%M%S"),
+                self.description,
+                self.tags
+            ),
+            Some(version) => format!(
+                "(footprint \"{}\" (version {}) (generator \"atlantix\"){} (layer \"F.Cu\")\n  (descr \"{}\")\n  (tags \"{}\")\n",
+                self.name,
+                footprint_version(version),
+                if version == KicadFormatVersion::V8 {
+                    format!(" (generator_version \"{}\")", GENERATOR_VERSION)
+                } else {
+                    String::new()
+                },
+                self.description,
+                self.tags
+            ),
+        };
+        footprint.push_str(&format!("{}  (attr smd)\n", self.fabrication.tokens()));
+        footprint.push_str(&grammar.text("reference", "REF**", -(courtyard_y + TEXT_OFFSET), "F.SilkS"));
+        footprint.push_str(&grammar.text("value", &self.name, courtyard_y + TEXT_OFFSET, "F.Fab"));
         
-        // Fabrication layer outline
+        // Fabrication layer outline, the body
         let half_x = self.body_size_x / 2.0;
         let half_y = self.body_size_y / 2.0;
-        footprint.push_str(&format!(
-            "  (fp_line (start -{:.3} {:.3}) (end -{:.3} -{:.3}) (layer F.Fab) (width 0.1))\n",
-            half_x, half_y, half_x, half_y
-        ));
-        footprint.push_str(&format!(
-            "  (fp_line (start -{:.3} -{:.3}) (end {:.3} -{:.3}) (layer F.Fab) (width 0.1))\n",
-            half_x, half_y, half_x, half_y
-        ));
-        footprint.push_str(&format!(
-            "  (fp_line (start {:.3} -{:.3}) (end {:.3} {:.3}) (layer F.Fab) (width 0.1))\n",
-            half_x, half_y, half_x, half_y
-        ));
-        footprint.push_str(&format!(
-            "  (fp_line (start {:.3} {:.3}) (end -{:.3} {:.3}) (layer F.Fab) (width 0.1))\n",
-            half_x, half_y, half_x, half_y
-        ));
+        footprint.push_str(&grammar.rectangle(half_x, half_y, "F.Fab", 0.1, 3));
         
-        // Silkscreen lines (partial, not over pads)
-        let silk_offset = 0.15;
-        let silk_x = half_x - self.pads[0].size_x / 2.0 - silk_offset;
-        footprint.push_str(&format!(
-            "  (fp_line (start -{:.3} -{:.3}) (end {:.3} -{:.3}) (layer F.SilkS) (width 0.12))\n",
-            silk_x, half_y + 0.11, silk_x, half_y + 0.11
-        ));
-        footprint.push_str(&format!(
-            "  (fp_line (start -{:.3} {:.3}) (end {:.3} {:.3}) (layer F.SilkS) (width 0.12))\n",
-            silk_x, half_y + 0.11, silk_x, half_y + 0.11
-        ));
+        // Silkscreen along the long sides, between the pads
+        if let Some((half_length, silk_y)) = self.silk_lines() {
+            for y in [-silk_y, silk_y] {
+                footprint.push_str(&grammar.line((-half_length, y), (half_length, y), "F.SilkS", 0.12, 3));
+            }
+        }
         
+        // Cathode bar outside pad 1
+        if self.cathode_mark {
+            let pad = &self.pads[0];
+            let bar_x = pad.at_x - pad.size_x / 2.0 - 0.25;
+            let half_y = pad.size_y / 2.0;
+            footprint.push_str(&grammar.line((bar_x, -half_y), (bar_x, half_y), "F.SilkS", 0.12, 3));
+        }
+
         // Courtyard
-        footprint.push_str(&format!(
-            "  (fp_line (start -{:.2} {:.2}) (end -{:.2} -{:.2}) (layer F.CrtYd) (width 0.05))\n",
-            courtyard_x, courtyard_y, courtyard_x, courtyard_y
-        ));
-        footprint.push_str(&format!(
-            "  (fp_line (start -{:.2} -{:.2}) (end {:.2} -{:.2}) (layer F.CrtYd) (width 0.05))\n",
-            courtyard_x, courtyard_y, courtyard_x, courtyard_y
-        ));
-        footprint.push_str(&format!(
-            "  (fp_line (start {:.2} -{:.2}) (end {:.2} {:.2}) (layer F.CrtYd) (width 0.05))\n",
-            courtyard_x, courtyard_y, courtyard_x, courtyard_y
-        ));
-        footprint.push_str(&format!(
-            "  (fp_line (start {:.2} {:.2}) (end -{:.2} {:.2}) (layer F.CrtYd) (width 0.05))\n",
-            courtyard_x, courtyard_y, courtyard_x, courtyard_y
-        ));
+        footprint.push_str(&grammar.rectangle(courtyard_x, courtyard_y, "F.CrtYd", 0.05, 2));
         
         // Pads
         for pad in &self.pads {
             footprint.push_str(&format!(
-                "  (pad {} {} {} (at {:.3} {:.3}) (size {:.2} {:.2}) (layers F.Cu F.Paste F.Mask)",
-                pad.number, pad.pad_type, pad.shape, pad.at_x, pad.at_y, pad.size_x, pad.size_y
+                "  (pad {} {} {} (at {:.3} {:.3}) (size {:.2} {:.2}) (layers {} {} {})",
+                grammar.quote(&pad.number),
+                pad.pad_type,
+                pad.shape,
+                pad.at_x,
+                pad.at_y,
+                pad.size_x,
+                pad.size_y,
+                grammar.quote("F.Cu"),
+                grammar.quote("F.Paste"),
+                grammar.quote("F.Mask")
             ));
             if let Some(rratio) = pad.roundrect_rratio {
                 footprint.push_str(&format!(" (roundrect_rratio {:.2})", rratio));
             }
+            footprint.push_str(&grammar.id());
             footprint.push_str(")\n");
         }
         
         // 3D model reference
+        let path = format!("{}/{}.3dshapes/{}.wrl", self.model_dir, self.model_library, self.model_name);
         footprint.push_str(&format!(
-            r#"  (model ${{KICAD6_3DMODEL_DIR}}/Resistor_SMD.3dshapes/{}.wrl
-    (at (xyz 0 0 0))
+            r#"  (model {}
+    ({} (xyz 0 0 0))
     (scale (xyz 1 1 1))
     (rotate (xyz 0 0 0))
   )
 )
 "#,
-            self.name
+            grammar.quote(&path),
+            if grammar.modern.is_some() { "offset" } else { "at" }
         ));
         
         footprint
     }
 }
 
-struct PackageSpec {
-    imperial: &'static str,
-    metric: &'static str,
-    body_length: f64,
-    body_width: f64,
-    pad_width: f64,
-    pad_height: f64,
-    pad_center_x: f64,
+/// Value of `generator_version` in KiCad 8 footprints
+const GENERATOR_VERSION: &str = "0.2";
+
+/// The `(version ...)` of a footprint written for a KiCad release
+fn footprint_version(version: KicadFormatVersion) -> u32 {
+    match version {
+        KicadFormatVersion::V6 => 20211014,
+        KicadFormatVersion::V7 => 20221018,
+        KicadFormatVersion::V8 => 20240108,
+    }
+}
+
+/// Deterministic version 4 style UUID of `seed`, so regenerated footprints
+/// only differ where their content does
+fn uuid(seed: &str) -> String {
+    // Two FNV-1a passes with different offsets give the 128 bits, the
+    // splitmix64 finalizer spreads the few changing seed bytes over all of them
+    let hash = |offset: u64| {
+        let hash = seed.bytes().fold(offset, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3));
+        let hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        let hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+        hash ^ (hash >> 31)
+    };
+    let bits = ((hash(0xcbf29ce484222325) as u128) << 64) | hash(0x84222325cbf29ce4) as u128;
+    let bits = (bits & !(0xf << 76) & !(0x3 << 62)) | (0x4 << 76) | (0x2 << 62);
+    let hex = format!("{:032x}", bits);
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+/// Legacy or modern spelling of the items of one footprint
+struct Grammar {
+    /// Release whose grammar is written, `None` for the legacy format
+    modern: Option<KicadFormatVersion>,
+    name: String,
+    /// Items given an id so far
+    items: std::cell::Cell<usize>,
+}
+
+impl Grammar {
+    fn new(format: FootprintFormat, version: KicadFormatVersion, name: &str) -> Self {
+        let modern = (format == FootprintFormat::Modern).then_some(version);
+        Grammar { modern, name: name.to_string(), items: std::cell::Cell::new(0) }
+    }
+
+    /// Strings are quoted in the modern grammar
+    fn quote(&self, value: &str) -> String {
+        match self.modern {
+            None => value.to_string(),
+            Some(_) => format!("\"{}\"", value),
+        }
+    }
+
+    /// Id of the next item, empty in the legacy grammar
+    fn id(&self) -> String {
+        let item = self.items.get();
+        self.items.set(item + 1);
+        match self.modern {
+            None => String::new(),
+            Some(KicadFormatVersion::V8) => format!(" (uuid \"{}\")", uuid(&format!("{}/{}", self.name, item))),
+            Some(_) => format!(" (tstamp {})", uuid(&format!("{}/{}", self.name, item))),
+        }
+    }
+
+    fn line(&self, start: (f64, f64), end: (f64, f64), layer: &str, width: f64, precision: usize) -> String {
+        let stroke = match self.modern {
+            None => format!("(layer {}) (width {})", layer, width),
+            Some(_) => format!("(stroke (width {}) (type solid)) (layer \"{}\")", width, layer),
+        };
+        format!(
+            "  (fp_line (start {:.p$} {:.p$}) (end {:.p$} {:.p$}) {}{})\n",
+            start.0, start.1, end.0, end.1, stroke, self.id(), p = precision
+        )
+    }
+
+    /// Closed rectangle of four lines centred on the origin, counter-clockwise
+    /// from the top left corner
+    fn rectangle(&self, half_x: f64, half_y: f64, layer: &str, width: f64, precision: usize) -> String {
+        let corners = [(-half_x, -half_y), (-half_x, half_y), (half_x, half_y), (half_x, -half_y)];
+        (0..4)
+            .map(|i| self.line(corners[i], corners[(i + 1) % 4], layer, width, precision))
+            .collect()
+    }
+
+    /// Reference or value text, `property` fields in KiCad 8
+    fn text(&self, kind: &str, text: &str, y: f64, layer: &str) -> String {
+        let effects = "    (effects (font (size 1 1) (thickness 0.15)))\n";
+        match self.modern {
+            None => format!("  (fp_text {} {} (at 0 {:.2}) (layer {})\n{}  )\n", kind, text, y, layer, effects),
+            Some(KicadFormatVersion::V8) => {
+                let field = if kind == "reference" { "Reference" } else { "Value" };
+                format!("  (property \"{}\" \"{}\" (at 0 {:.2} 0) (layer \"{}\"){}\n{}  )\n", field, text, y, layer, self.id(), effects)
+            }
+            Some(_) => format!("  (fp_text {} \"{}\" (at 0 {:.2}) (layer \"{}\"){}\n{}  )\n", kind, text, y, layer, self.id(), effects),
+        }
+    }
+}
+
+/// Distance from the body edge to the centre of a silkscreen line
+const SILK_BODY_OFFSET: f64 = 0.11;
+
+/// Distance from copper to the centre of a silkscreen line, the 0.12 mm
+/// line plus clearance
+const SILK_PAD_CLEARANCE: f64 = 0.2;
+
+/// Shorter silkscreen lines are left out, as on 0201 parts
+const SILK_MIN_HALF_LENGTH: f64 = 0.05;
+
+/// Courtyard coordinates are rounded outward to this grid
+const COURTYARD_GRID: f64 = 0.01;
+
+/// Distance from the courtyard to the reference and value texts
+const TEXT_OFFSET: f64 = 0.7;
+
+/// Land pattern of a two terminal SMD package, in mm. `pad_width` runs along
+/// the body length, `pad_center_x` is the distance from the origin to each pad
+/// center (half the IPC-7351 C dimension).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackageSpec {
+    pub imperial: String,
+    pub metric: String,
+    pub body_length: f64,
+    pub body_width: f64,
+    pub pad_width: f64,
+    pub pad_height: f64,
+    pub pad_center_x: f64,
+}
+
+/// Custom land patterns of the current [`crate::settings`], e.g. from an
+/// IPC-7351 calculator export. They take precedence over the built-in specs
+/// for the same package.
+pub fn registered_packages() -> Vec<PackageSpec> {
+    let mut specs: Vec<PackageSpec> = crate::settings::with(|settings| settings.packages.values().cloned().collect());
+    specs.sort_by(|a, b| a.imperial.cmp(&b.imperial));
+    specs
 }
 
-fn get_package_specs(package: &str) -> Option<PackageSpec> {
+/// Chip packages with a built-in land pattern, by imperial code
+const BUILT_IN_PACKAGES: [&str; 11] = ["0201", "0402", "0603", "0805", "1206", "0612", "1008", "1210", "1225", "2010", "2512"];
+
+/// Every chip package footprints can be generated for: the built-in ones,
+/// with registered custom patterns in their place, then the other
+/// registered packages
+pub fn supported_packages() -> Vec<PackageSpec> {
+    let registered = registered_packages()
+        .into_iter()
+        .filter(|spec| !BUILT_IN_PACKAGES.contains(&spec.imperial.as_str()));
+    BUILT_IN_PACKAGES.iter().filter_map(|package| get_package_specs(package)).chain(registered).collect()
+}
+
+/// Land pattern for a package, preferring registered custom patterns
+pub fn get_package_specs(package: &str) -> Option<PackageSpec> {
+    if let Some(spec) = crate::settings::with(|settings| settings.packages.get(package).cloned()) {
+        return Some(spec);
+    }
     match package {
         "0201" => Some(PackageSpec {
-            imperial: "0201",
-            metric: "0603Metric",
+            imperial: "0201".into(),
+            metric: "0603Metric".into(),
             body_length: 0.6,
             body_width: 0.3,
             pad_width: 0.28,
@@ -193,8 +894,8 @@ fn get_package_specs(package: &str) -> Option<PackageSpec> {
             pad_center_x: 0.26,
         }),
         "0402" => Some(PackageSpec {
-            imperial: "0402",
-            metric: "1005Metric",
+            imperial: "0402".into(),
+            metric: "1005Metric".into(),
             body_length: 1.0,
             body_width: 0.5,
             pad_width: 0.6,
@@ -202,8 +903,8 @@ fn get_package_specs(package: &str) -> Option<PackageSpec> {
             pad_center_x: 0.48,
         }),
         "0603" => Some(PackageSpec {
-            imperial: "0603",
-            metric: "1608Metric",
+            imperial: "0603".into(),
+            metric: "1608Metric".into(),
             body_length: 1.6,
             body_width: 0.8,
             pad_width: 0.9,
@@ -211,8 +912,8 @@ fn get_package_specs(package: &str) -> Option<PackageSpec> {
             pad_center_x: 0.775,
         }),
         "0805" => Some(PackageSpec {
-            imperial: "0805",
-            metric: "2012Metric",
+            imperial: "0805".into(),
+            metric: "2012Metric".into(),
             body_length: 2.0,
             body_width: 1.25,
             pad_width: 1.0,
@@ -220,26 +921,55 @@ fn get_package_specs(package: &str) -> Option<PackageSpec> {
             pad_center_x: 0.95,
         }),
         "1206" => Some(PackageSpec {
-            imperial: "1206",
-            metric: "3216Metric",
+            imperial: "1206".into(),
+            metric: "3216Metric".into(),
             body_length: 3.2,
             body_width: 1.6,
             pad_width: 1.15,
             pad_height: 1.8,
             pad_center_x: 1.475,
         }),
+        // Wide-terminal (reversed geometry) 1206, terminals on the long sides
+        "0612" => Some(PackageSpec {
+            imperial: "0612".into(),
+            metric: "1632Metric".into(),
+            body_length: 1.6,
+            body_width: 3.2,
+            pad_width: 0.7,
+            pad_height: 3.4,
+            pad_center_x: 0.75,
+        }),
+        "1008" => Some(PackageSpec {
+            imperial: "1008".into(),
+            metric: "2520Metric".into(),
+            body_length: 2.5,
+            body_width: 2.0,
+            pad_width: 1.0,
+            pad_height: 2.2,
+            pad_center_x: 1.125,
+        }),
         "1210" => Some(PackageSpec {
-            imperial: "1210",
-            metric: "3225Metric",
+            imperial: "1210".into(),
+            metric: "3225Metric".into(),
             body_length: 3.2,
             body_width: 2.5,
             pad_width: 1.15,
             pad_height: 2.7,
             pad_center_x: 1.475,
         }),
+        // Wide-terminal (reversed geometry) 2512
+        "1225" => Some(PackageSpec {
+            imperial: "1225".into(),
+            metric: "3263Metric".into(),
+            body_length: 3.2,
+            body_width: 6.3,
+            pad_width: 1.3,
+            pad_height: 6.6,
+            pad_center_x: 1.45,
+        }),
         "2010" => Some(PackageSpec {
-            imperial: "2010",
-            metric: "5025Metric",
+            imperial: "2010".into(),
+            metric: "5025Metric".into(),
             body_length: 5.0,
             body_width: 2.5,
             pad_width: 1.5,
@@ -247,8 +977,8 @@ fn get_package_specs(package: &str) -> Option<PackageSpec> {
             pad_center_x: 2.25,
         }),
         "2512" => Some(PackageSpec {
-            imperial: "2512",
-            metric: "6332Metric",
+            imperial: "2512".into(),
+            metric: "6332Metric".into(),
             body_length: 6.35,
             body_width: 3.2,
             pad_width: 1.6,
@@ -257,4 +987,88 @@ fn get_package_specs(package: &str) -> Option<PackageSpec> {
         }),
         _ => None,
     }
-}
\ No newline at end of file
+}
+
+fn get_power_inductor_specs(package: &str) -> Option<PackageSpec> {
+    match package {
+        "4040" => Some(PackageSpec {
+            imperial: "4040".into(),
+            metric: "".into(),
+            body_length: 4.0,
+            body_width: 4.0,
+            pad_width: 1.5,
+            pad_height: 3.6,
+            pad_center_x: 1.55,
+        }),
+        "5050" => Some(PackageSpec {
+            imperial: "5050".into(),
+            metric: "".into(),
+            body_length: 5.0,
+            body_width: 5.0,
+            pad_width: 1.6,
+            pad_height: 4.5,
+            pad_center_x: 2.0,
+        }),
+        "6060" => Some(PackageSpec {
+            imperial: "6060".into(),
+            metric: "".into(),
+            body_length: 6.0,
+            body_width: 6.0,
+            pad_width: 2.0,
+            pad_height: 5.4,
+            pad_center_x: 2.4,
+        }),
+        _ => None,
+    }
+}
+
+fn get_diode_specs(package: &str) -> Option<PackageSpec> {
+    match package {
+        "SOD-123" => Some(PackageSpec {
+            imperial: "SOD-123".into(),
+            metric: "".into(),
+            body_length: 2.7,
+            body_width: 1.6,
+            pad_width: 0.9,
+            pad_height: 1.2,
+            pad_center_x: 1.65,
+        }),
+        "SOD-323" => Some(PackageSpec {
+            imperial: "SOD-323".into(),
+            metric: "".into(),
+            body_length: 1.7,
+            body_width: 1.25,
+            pad_width: 0.6,
+            pad_height: 0.45,
+            pad_center_x: 1.05,
+        }),
+        "SOD-523" => Some(PackageSpec {
+            imperial: "SOD-523".into(),
+            metric: "".into(),
+            body_length: 1.2,
+            body_width: 0.8,
+            pad_width: 0.6,
+            pad_height: 0.7,
+            pad_center_x: 0.7,
+        }),
+        "SMA" => Some(PackageSpec {
+            imperial: "SMA".into(),
+            metric: "".into(),
+            body_length: 4.3,
+            body_width: 2.6,
+            pad_width: 2.5,
+            pad_height: 1.8,
+            pad_center_x: 2.0,
+        }),
+        "SMB" => Some(PackageSpec {
+            imperial: "SMB".into(),
+            metric: "".into(),
+            body_length: 4.3,
+            body_width: 3.6,
+            pad_width: 2.5,
+            pad_height: 2.3,
+            pad_center_x: 2.15,
+        }),
+        _ => None,
+    }
+}
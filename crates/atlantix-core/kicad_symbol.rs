@@ -1,6 +1,182 @@
-use chrono::Utc;
+use crate::status::PartStatus;
+use crate::AtlantixError;
+use std::io::{self, Write};
 
-#[derive(Debug, Clone)]
+/// Font size of every field
+const TEXT_SIZE: f64 = 1.27;
+
+/// Distance from a body outline to the centre line of the rotated text next to it
+const TEXT_OFFSET: f64 = 1.016;
+
+/// Clearance between value text placed inside a body and the body ends
+const TEXT_CLEARANCE: f64 = 0.254;
+
+/// Approximate rendered width of `text` in KiCad's stroke font, where
+/// punctuation is narrower than letters and digits
+fn text_width(text: &str) -> f64 {
+    text.chars()
+        .map(|c| if matches!(c, '.' | ',' | ':' | '\'') { 0.4 } else { 0.8 })
+        .sum::<f64>()
+        * TEXT_SIZE
+}
+
+/// KiCad release whose symbol library grammar is written.
+///
+/// KiCad 6 numbers every property with `(id N)`, KiCad 7 dropped the ids and
+/// KiCad 8 writes `(hide yes)` instead of a bare `hide`, a `Description` field
+/// instead of `ki_description` and marks parts for simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum KicadFormatVersion {
+    #[default]
+    V6,
+    V7,
+    V8,
+}
+
+impl KicadFormatVersion {
+    /// Every release a library can be written for, oldest first
+    pub const ALL: [KicadFormatVersion; 3] = [KicadFormatVersion::V6, KicadFormatVersion::V7, KicadFormatVersion::V8];
+
+    /// KiCad major release, 6 for KiCad 6
+    pub const fn major(self) -> u32 {
+        match self {
+            KicadFormatVersion::V6 => 6,
+            KicadFormatVersion::V7 => 7,
+            KicadFormatVersion::V8 => 8,
+        }
+    }
+
+    /// The `(version ...)` written in the library header
+    pub const fn version(self) -> u32 {
+        match self {
+            KicadFormatVersion::V6 => 20211014,
+            KicadFormatVersion::V7 => 20220914,
+            KicadFormatVersion::V8 => 20231120,
+        }
+    }
+
+    fn hide(self) -> &'static str {
+        match self {
+            KicadFormatVersion::V8 => "(hide yes)",
+            _ => "hide",
+        }
+    }
+}
+
+impl std::str::FromStr for KicadFormatVersion {
+    type Err = AtlantixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().trim_start_matches("kicad").trim_start_matches('v') {
+            "6" => Ok(KicadFormatVersion::V6),
+            "7" => Ok(KicadFormatVersion::V7),
+            "8" => Ok(KicadFormatVersion::V8),
+            _ => Err(AtlantixError::Parse(format!("Unknown KiCad version '{}', expected 6, 7 or 8", s))),
+        }
+    }
+}
+
+impl std::fmt::Display for KicadFormatVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "KiCad {}", self.major())
+    }
+}
+
+/// Format of new symbols and libraries in the current [`crate::settings`],
+/// so front ends select it once for every generator
+pub fn default_format() -> KicadFormatVersion {
+    crate::settings::with(|settings| settings.symbol_format)
+}
+
+/// Body styles a resistor symbol can be drawn in
+pub const RESISTOR_STYLES: [&str; 2] = ["european", "american"];
+
+/// Text sketch of a style's body between its two pins, for showing the
+/// choice next to a style selector. `None` for unknown styles.
+///
+/// ```
+/// use component::kicad_symbol::style_glyph;
+/// assert_eq!(style_glyph("european"), Some("--[    ]--"));
+/// assert_eq!(style_glyph("american"), Some("--/\\/\\/\\--"));
+/// assert_eq!(style_glyph("zigzag"), None);
+/// ```
+pub fn style_glyph(style: &str) -> Option<&'static str> {
+    match style {
+        "european" => Some("--[    ]--"),
+        "american" => Some("--/\\/\\/\\--"),
+        "inductor" => Some("--nnnn--"),
+        "ferrite" => Some("--[####]--"),
+        "led" => Some("-->|-- //"),
+        "diode" | "schottky" | "zener" | "tvs" => Some("-->|--"),
+        _ => None,
+    }
+}
+
+/// Resistor symbol style per package, parsed from a default style followed
+/// by `package=style` overrides.
+///
+/// ```
+/// use component::kicad_symbol::ResistorStyles;
+/// let styles: ResistorStyles = "european,0402=american".parse().unwrap();
+/// assert_eq!(styles.for_package("0402"), "american");
+/// assert_eq!(styles.for_package("0603"), "european");
+/// assert_eq!(styles.to_string(), "european,0402=american");
+/// assert!("0402=zigzag".parse::<ResistorStyles>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResistorStyles {
+    pub default: String,
+    pub packages: std::collections::BTreeMap<String, String>,
+}
+
+impl Default for ResistorStyles {
+    fn default() -> Self {
+        ResistorStyles { default: RESISTOR_STYLES[0].to_string(), packages: Default::default() }
+    }
+}
+
+impl ResistorStyles {
+    pub fn for_package(&self, package: &str) -> &str {
+        self.packages.get(package).unwrap_or(&self.default)
+    }
+}
+
+impl std::fmt::Display for ResistorStyles {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.default)?;
+        for (package, style) in &self.packages {
+            write!(f, ",{}={}", package, style)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for ResistorStyles {
+    type Err = AtlantixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut styles = ResistorStyles::default();
+        for entry in s.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (package, style) = match entry.split_once('=') {
+                Some((package, style)) => (Some(package.trim()), style.trim()),
+                None => (None, entry),
+            };
+            let style = style.to_lowercase();
+            if !RESISTOR_STYLES.contains(&style.as_str()) {
+                return Err(AtlantixError::Parse(format!("Unknown symbol style '{}', expected {}", style, RESISTOR_STYLES.join(" or "))));
+            }
+            match package {
+                Some(package) => {
+                    styles.packages.insert(package.to_string(), style);
+                }
+                None => styles.default = style,
+            }
+        }
+        Ok(styles)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct KicadSymbol {
     pub name: String,
     pub reference: String,
@@ -9,12 +185,23 @@ pub struct KicadSymbol {
     pub datasheet: String,
     pub keywords: String,
     pub description: String,
+    pub fp_filters: String,
+    /// Tolerance, e.g. "1%", written as the Tolerance field when set
+    pub tolerance: String,
+    /// (number, name) of the top and bottom pin
+    pub pins: [(String, String); 2],
+    /// Additional hidden properties, e.g. LED color or forward voltage
+    pub properties: Vec<(String, String)>,
+    /// Adds Kelvin sense pins 3 and 4 next to the top and bottom terminals
+    pub sense_pins: bool,
     pub symbol_style: String,
     pub manufacturer: String,
     pub mpn: String,
     pub supplier: String,
     pub supplier_pn: String,
     pub supplier_url: String,
+    pub status: Option<PartStatus>,
+    pub format: KicadFormatVersion,
 }
 
 impl KicadSymbol {
@@ -28,13 +215,98 @@ impl KicadSymbol {
             datasheet: "~".to_string(),
             keywords: "R res resistor".to_string(),
             description,
+            fp_filters: "R_*".to_string(),
+            tolerance: String::new(),
+            pins: [("1".to_string(), "~".to_string()), ("2".to_string(), "~".to_string())],
+            properties: Vec::new(),
+            sense_pins: false,
             symbol_style: symbol_style.to_string(),
             manufacturer: String::new(),
             mpn: String::new(),
             supplier: String::new(),
             supplier_pn: String::new(),
             supplier_url: String::new(),
+            status: None,
+            format: default_format(),
+        }
+    }
+
+    /// Inductor symbol drawn as four half-turn arcs, filtered to `L_*` footprints
+    pub fn new_inductor(name: String, value: String, footprint: String) -> Self {
+        let mut symbol = KicadSymbol::new(name, value, footprint, "inductor");
+        symbol.reference = "L".to_string();
+        symbol.keywords = "L inductor choke coil".to_string();
+        symbol.description = format!("Inductor, {}", symbol.value);
+        symbol.fp_filters = "L_*".to_string();
+        symbol
+    }
+
+    /// Current-sense resistor. Four-terminal parts get sense pins 3 (top)
+    /// and 4 (bottom) on the right and reference `R_Shunt*` footprints.
+    pub fn new_current_sense(name: String, value: String, footprint: String, kelvin: bool) -> Self {
+        let mut symbol = KicadSymbol::new(name, value, footprint, "european");
+        symbol.keywords = "R res resistor shunt current sense".to_string();
+        symbol.description = format!("Current sense resistor, {}", symbol.value);
+        if kelvin {
+            symbol.fp_filters = "R_Shunt*".to_string();
+            symbol.sense_pins = true;
+        }
+        symbol
+    }
+
+    /// Ferrite bead symbol drawn as a slanted block, filtered to inductor footprints
+    pub fn new_ferrite_bead(name: String, value: String, footprint: String) -> Self {
+        let mut symbol = KicadSymbol::new(name, value, footprint, "ferrite");
+        symbol.reference = "FB".to_string();
+        symbol.keywords = "L ferrite bead inductor filter".to_string();
+        symbol.description = format!("Ferrite bead, {} @ 100MHz", symbol.value);
+        symbol.fp_filters = "L_* Inductor_*".to_string();
+        symbol
+    }
+
+    /// LED symbol with the anode on top (pin 2, "A") and the cathode below
+    /// (pin 1, "K"), matching the cathode-on-pad-1 footprints
+    pub fn new_led(name: String, value: String, footprint: String) -> Self {
+        let mut symbol = KicadSymbol::new(name, value, footprint, "led");
+        symbol.reference = "D".to_string();
+        symbol.keywords = "LED diode light".to_string();
+        symbol.description = format!("Light emitting diode, {}", symbol.value);
+        symbol.fp_filters = "LED_*".to_string();
+        symbol.pins = [("2".to_string(), "A".to_string()), ("1".to_string(), "K".to_string())];
+        symbol
+    }
+
+    /// Diode symbol, anode on top (pin 2) and cathode below (pin 1). `style`
+    /// selects the cathode bar: `diode`, `schottky`, `zener` or `tvs`.
+    pub fn new_diode(name: String, value: String, footprint: String, style: &str) -> Self {
+        let mut symbol = KicadSymbol::new(name, value, footprint, style);
+        symbol.reference = "D".to_string();
+        symbol.keywords = match style {
+            "schottky" => "diode Schottky",
+            "zener" => "diode Zener reference",
+            "tvs" => "diode TVS transient voltage suppressor ESD",
+            _ => "diode rectifier",
         }
+        .to_string();
+        symbol.description = format!("Diode, {}", symbol.value);
+        symbol.fp_filters = "D_*".to_string();
+        symbol.pins = [("2".to_string(), "A".to_string()), ("1".to_string(), "K".to_string())];
+        symbol
+    }
+
+    /// Set the tolerance and add it to the description, `Resistor, 4.99K, 1%`
+    ///
+    /// ```
+    /// use component::kicad_symbol::KicadSymbol;
+    ///
+    /// let symbol = KicadSymbol::new("R0603_4.99K_1%".into(), "4.99K".into(), String::new(), "european").with_tolerance("1%");
+    /// assert_eq!(symbol.description, "Resistor, 4.99K, 1%");
+    /// assert!(symbol.generate_symbol().contains(r#"(property "Tolerance" "1%""#));
+    /// ```
+    pub fn with_tolerance(mut self, tolerance: &str) -> Self {
+        self.tolerance = tolerance.to_string();
+        self.description = format!("{}, {}", self.description, tolerance);
+        self
     }
 
     pub fn with_manufacturer_info(mut self, manufacturer: String, mpn: String, supplier: String, supplier_pn: String, supplier_url: String) -> Self {
@@ -46,60 +318,183 @@ impl KicadSymbol {
         self
     }
 
+    /// Manufacturer and MPN with the supplier fields of
+    /// [`crate::distributor::part_numbers`]: the first distributor as
+    /// `Supplier`, further ones as `Supplier 2`, `Supplier 3`, ...
+    pub fn with_sources(mut self, manufacturer: &str, mpn: &str, digikey_pn: &str) -> Self {
+        self.manufacturer = manufacturer.to_string();
+        self.mpn = mpn.to_string();
+        for (number, (distributor, part_number)) in (1..).zip(crate::distributor::part_numbers(manufacturer, mpn, digikey_pn)) {
+            let url = distributor.supplier_url(&part_number);
+            if number == 1 {
+                self.supplier = distributor.to_string();
+                self.supplier_pn = part_number;
+                self.supplier_url = url;
+            } else {
+                self.properties.push((format!("Supplier {}", number), distributor.to_string()));
+                self.properties.push((format!("SupplierPN {}", number), part_number));
+                self.properties.push((format!("SupplierURL {}", number), url));
+            }
+        }
+        self
+    }
+
     pub fn generate_symbol(&self) -> String {
+        self.generate_symbol_as(self.format)
+    }
+
+    /// Symbol text in the grammar of `format`, regardless of [`Self::format`]
+    pub fn generate_symbol_as(&self, format: KicadFormatVersion) -> String {
         let symbol_geometry = match self.symbol_style.as_str() {
             "american" => self.generate_american_geometry(),
+            "inductor" => self.generate_inductor_geometry(),
+            "ferrite" => self.generate_ferrite_geometry(),
+            "led" => self.generate_led_geometry(),
+            "diode" | "schottky" | "zener" | "tvs" => self.generate_diode_geometry(),
             "european" | _ => self.generate_european_geometry(),
         };
 
-        let manufacturer_properties = if !self.manufacturer.is_empty() {
-            format!(r#"
-    (property "Manufacturer" "{}" (at 0 0 0) (effects (font (size 1.27 1.27)) hide))
-    (property "MPN" "{}" (at 0 0 0) (effects (font (size 1.27 1.27)) hide))
-    (property "Supplier" "{}" (at 0 0 0) (effects (font (size 1.27 1.27)) hide))
-    (property "SupplierPN" "{}" (at 0 0 0) (effects (font (size 1.27 1.27)) hide))
-    (property "SupplierURL" "{}" (at 0 0 0) (effects (font (size 1.27 1.27)) hide))"#, 
-                self.manufacturer, self.mpn, self.supplier, self.supplier_pn, self.supplier_url)
+        let sense_pins = if self.sense_pins {
+            r#"
+      (pin passive line (at 5.08 2.54 180) (length 2.54)
+        (name "~" (effects (font (size 1.27 1.27))))
+        (number "3" (effects (font (size 1.27 1.27))))
+      )
+      (pin passive line (at 5.08 -2.54 180) (length 2.54)
+        (name "~" (effects (font (size 1.27 1.27))))
+        (number "4" (effects (font (size 1.27 1.27))))
+      )"#
         } else {
-            String::new()
+            ""
+        };
+        let symbol_geometry = if self.sense_pins {
+            format!(
+                "{}
+      (polyline
+        (pts (xy 1.016 2.54) (xy 2.54 2.54))
+        (stroke (width 0) (type default) (color 0 0 0 0))
+        (fill (type none))
+      )
+      (polyline
+        (pts (xy 1.016 -2.54) (xy 2.54 -2.54))
+        (stroke (width 0) (type default) (color 0 0 0 0))
+        (fill (type none))
+      )",
+                symbol_geometry
+            )
+        } else {
+            symbol_geometry
         };
 
-        format!(r#"  (symbol "{}" (pin_numbers hide) (pin_names (offset 0)) (in_bom yes) (on_board yes)
-    (property "Reference" "{}" (at 2.032 0 90) (effects (font (size 1.27 1.27))))
-    (property "Value" "{}" (at 0 0 90) (effects (font (size 1.27 1.27))))
-    (property "Footprint" "{}" (at -1.778 0 90) (effects (font (size 1.27 1.27)) hide))
-    (property "Datasheet" "{}" (at 0 0 0) (effects (font (size 1.27 1.27)) hide))
-    (property "ki_keywords" "{}" (at 0 0 0) (effects (font (size 1.27 1.27)) hide))
-    (property "ki_description" "{}" (at 0 0 0) (effects (font (size 1.27 1.27)) hide))
-    (property "ki_fp_filters" "R_*" (at 0 0 0) (effects (font (size 1.27 1.27)) hide)){}
+        let (reference_x, value_x) = self.text_positions();
+        let description_key = match format {
+            KicadFormatVersion::V8 => "Description",
+            _ => "ki_description",
+        };
+        let mut properties = vec![
+            ("Reference", self.reference.as_str(), format!("{} 0 90", reference_x), false),
+            ("Value", self.value.as_str(), format!("{} 0 90", value_x), false),
+            ("Footprint", self.footprint.as_str(), "-1.778 0 90".to_string(), true),
+            ("Datasheet", self.datasheet.as_str(), "0 0 0".to_string(), true),
+            ("ki_keywords", self.keywords.as_str(), "0 0 0".to_string(), true),
+            (description_key, self.description.as_str(), "0 0 0".to_string(), true),
+            ("ki_fp_filters", self.fp_filters.as_str(), "0 0 0".to_string(), true),
+        ];
+        if !self.tolerance.is_empty() {
+            properties.push(("Tolerance", self.tolerance.as_str(), "0 0 0".to_string(), true));
+        }
+        if !self.manufacturer.is_empty() {
+            for (key, value) in [
+                ("Manufacturer", &self.manufacturer),
+                ("MPN", &self.mpn),
+                ("Supplier", &self.supplier),
+                ("SupplierPN", &self.supplier_pn),
+                ("SupplierURL", &self.supplier_url),
+            ] {
+                properties.push((key, value.as_str(), "0 0 0".to_string(), true));
+            }
+        }
+        let status = self.status.map(|status| status.to_string());
+        if let Some(status) = &status {
+            properties.push(("Status", status.as_str(), "0 0 0".to_string(), true));
+        }
+        for (key, value) in &self.properties {
+            properties.push((key.as_str(), value.as_str(), "0 0 0".to_string(), true));
+        }
+
+        let properties: String = properties
+            .iter()
+            .enumerate()
+            .map(|(id, (key, value, at, hidden))| {
+                let id = match format {
+                    KicadFormatVersion::V6 => format!(" (id {})", id),
+                    _ => String::new(),
+                };
+                let hide = if *hidden { format!(" {}", format.hide()) } else { String::new() };
+                format!(
+                    "\n    (property \"{}\" \"{}\"{} (at {}) (effects (font (size 1.27 1.27)){}))",
+                    key, value, id, at, hide
+                )
+            })
+            .collect();
+        let flags = match format {
+            KicadFormatVersion::V8 => "(pin_numbers (hide yes)) (pin_names (offset 0)) (exclude_from_sim no) (in_bom yes) (on_board yes)",
+            _ => "(pin_numbers hide) (pin_names (offset 0)) (in_bom yes) (on_board yes)",
+        };
+
+        format!(r#"  (symbol "{}" {}{}
     (symbol "{}_0_1"
 {}
     )
     (symbol "{}_1_1"
       (pin passive line (at 0 3.81 270) (length 1.27)
-        (name "~" (effects (font (size 1.27 1.27))))
-        (number "1" (effects (font (size 1.27 1.27))))
+        (name "{}" (effects (font (size 1.27 1.27))))
+        (number "{}" (effects (font (size 1.27 1.27))))
       )
       (pin passive line (at 0 -3.81 90) (length 1.27)
-        (name "~" (effects (font (size 1.27 1.27))))
-        (number "2" (effects (font (size 1.27 1.27))))
-      )
+        (name "{}" (effects (font (size 1.27 1.27))))
+        (number "{}" (effects (font (size 1.27 1.27))))
+      ){}
     )
   )"#,
             self.name,
-            self.reference,
-            self.value,
-            self.footprint,
-            self.datasheet,
-            self.keywords,
-            self.description,
-            manufacturer_properties,
+            flags,
+            properties,
             self.name,
             symbol_geometry,
-            self.name
+            self.name,
+            self.pins[0].1,
+            self.pins[0].0,
+            self.pins[1].1,
+            self.pins[1].0,
+            sense_pins
         )
     }
 
+    /// How far the body drawing reaches left and right of the pin axis, and
+    /// whether it is the European rectangle the value can be written into
+    fn body_extent(&self) -> (f64, f64, bool) {
+        match self.symbol_style.as_str() {
+            "american" | "inductor" => (0.635, 0.635, false),
+            "ferrite" => (1.693, 1.693, false),
+            // The emission arrows reach out to the right
+            "led" => (1.27, 2.54, false),
+            "diode" | "schottky" | "zener" | "tvs" => (1.27, 1.27, false),
+            _ => (1.016, 1.016, true),
+        }
+    }
+
+    /// X positions of the Reference and Value fields. The reference goes to
+    /// the right of the body. The value sits inside the European rectangle
+    /// when it fits along the body, otherwise to the left, clear of zigzags,
+    /// arcs and triangles.
+    fn text_positions(&self) -> (f64, f64) {
+        let (left, right, rectangle) = self.body_extent();
+        let fits_inside = rectangle && text_width(&self.value) <= 2.0 * (2.54 - TEXT_CLEARANCE);
+        let value_x = if fits_inside { 0.0 } else { -(left + TEXT_OFFSET) };
+        (right + TEXT_OFFSET, value_x)
+    }
+
     fn generate_european_geometry(&self) -> String {
         "      (rectangle (start -1.016 -2.54) (end 1.016 2.54)
         (stroke (width 0.254) (type default) (color 0 0 0 0))
@@ -107,6 +502,113 @@ impl KicadSymbol {
       )".to_string()
     }
 
+    fn generate_inductor_geometry(&self) -> String {
+        [(-2.54, -1.27), (-1.27, 0.0), (0.0, 1.27), (1.27, 2.54)]
+            .iter()
+            .map(|(start, end)| {
+                format!(
+                    "      (arc (start 0 {:.3}) (mid 0.635 {:.3}) (end 0 {:.3})
+        (stroke (width 0.254) (type default) (color 0 0 0 0))
+        (fill (type none))
+      )",
+                    start,
+                    (start + end) / 2.0,
+                    end
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn generate_ferrite_geometry(&self) -> String {
+        r#"      (polyline
+        (pts
+          (xy 0.307 -2.132)
+          (xy 1.693 -1.332)
+          (xy -0.307 2.132)
+          (xy -1.693 1.332)
+          (xy 0.307 -2.132)
+        )
+        (stroke (width 0.254) (type default) (color 0 0 0 0))
+        (fill (type none))
+      )
+      (polyline
+        (pts (xy 0 -2.54) (xy 0 -1.6))
+        (stroke (width 0) (type default) (color 0 0 0 0))
+        (fill (type none))
+      )
+      (polyline
+        (pts (xy 0 1.6) (xy 0 2.54))
+        (stroke (width 0) (type default) (color 0 0 0 0))
+        (fill (type none))
+      )"#.to_string()
+    }
+
+    fn generate_led_geometry(&self) -> String {
+        r#"      (polyline
+        (pts (xy -1.27 1.27) (xy 1.27 1.27) (xy 0 -1.27) (xy -1.27 1.27))
+        (stroke (width 0.254) (type default) (color 0 0 0 0))
+        (fill (type none))
+      )
+      (polyline
+        (pts (xy -1.27 -1.27) (xy 1.27 -1.27))
+        (stroke (width 0.254) (type default) (color 0 0 0 0))
+        (fill (type none))
+      )
+      (polyline
+        (pts (xy 0 2.54) (xy 0 1.27))
+        (stroke (width 0) (type default) (color 0 0 0 0))
+        (fill (type none))
+      )
+      (polyline
+        (pts (xy 0 -1.27) (xy 0 -2.54))
+        (stroke (width 0) (type default) (color 0 0 0 0))
+        (fill (type none))
+      )
+      (polyline
+        (pts (xy 1.524 0.254) (xy 2.54 -0.762) (xy 2.032 -0.762) (xy 2.54 -0.762) (xy 2.54 -0.254))
+        (stroke (width 0) (type default) (color 0 0 0 0))
+        (fill (type none))
+      )
+      (polyline
+        (pts (xy 1.524 1.27) (xy 2.54 0.254) (xy 2.032 0.254) (xy 2.54 0.254) (xy 2.54 0.762))
+        (stroke (width 0) (type default) (color 0 0 0 0))
+        (fill (type none))
+      )"#.to_string()
+    }
+
+    fn generate_diode_geometry(&self) -> String {
+        let bar = match self.symbol_style.as_str() {
+            "schottky" => "(xy -0.762 -0.762) (xy -1.27 -0.762) (xy -1.27 -1.27) (xy 1.27 -1.27) (xy 1.27 -1.778) (xy 0.762 -1.778)",
+            "zener" => "(xy -1.27 -1.27) (xy 1.27 -1.27) (xy 1.27 -0.762)",
+            "tvs" => "(xy -1.27 -1.778) (xy -1.27 -1.27) (xy 1.27 -1.27) (xy 1.27 -0.762)",
+            _ => "(xy -1.27 -1.27) (xy 1.27 -1.27)",
+        };
+        format!(
+            r#"      (polyline
+        (pts (xy -1.27 1.27) (xy 1.27 1.27) (xy 0 -1.27) (xy -1.27 1.27))
+        (stroke (width 0.254) (type default) (color 0 0 0 0))
+        (fill (type none))
+      )
+      (polyline
+        (pts {})
+        (stroke (width 0.254) (type default) (color 0 0 0 0))
+        (fill (type none))
+      )
+      (polyline
+        (pts (xy 0 2.54) (xy 0 1.27))
+        (stroke (width 0) (type default) (color 0 0 0 0))
+        (fill (type none))
+      )
+      (polyline
+        (pts (xy 0 -1.27) (xy 0 -2.54))
+        (stroke (width 0) (type default) (color 0 0 0 0))
+        (fill (type none))
+      )"#,
+            bar
+        )
+    }
+
     fn generate_american_geometry(&self) -> String {
         r#"      (polyline
         (pts
@@ -125,31 +627,50 @@ impl KicadSymbol {
 
 pub struct KicadSymbolLib {
     pub symbols: Vec<KicadSymbol>,
+    /// Grammar of the whole library, overrides the format of each symbol
+    pub format: KicadFormatVersion,
 }
 
 impl KicadSymbolLib {
     pub fn new() -> Self {
         KicadSymbolLib {
             symbols: Vec::new(),
+            format: default_format(),
         }
     }
 
+    pub fn with_format(mut self, format: KicadFormatVersion) -> Self {
+        self.format = format;
+        self
+    }
+
     pub fn add_symbol(&mut self, symbol: KicadSymbol) {
         self.symbols.push(symbol);
     }
 
     pub fn generate_library(&self) -> String {
-        let _timestamp = Utc::now().format("%Y%m%d");
-        let mut lib_content = format!(
-            "(kicad_symbol_lib (version 20211014) (generator atlantix-eda)\n"
-        );
+        let mut library = Vec::new();
+        // Writing to a Vec cannot fail
+        let _ = self.write_library(&mut library);
+        String::from_utf8(library).expect("symbols are UTF-8")
+    }
 
-        for symbol in &self.symbols {
-            lib_content.push_str(&symbol.generate_symbol());
-            lib_content.push('\n');
+    /// Stream the library to `out` one symbol at a time
+    pub fn write_library<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        match self.format {
+            KicadFormatVersion::V8 => writeln!(
+                out,
+                "(kicad_symbol_lib (version {}) (generator \"atlantix-eda\") (generator_version \"8.0\")",
+                self.format.version()
+            )?,
+            _ => writeln!(out, "(kicad_symbol_lib (version {}) (generator atlantix-eda)", self.format.version())?,
         }
 
-        lib_content.push_str(")\n");
-        lib_content
+        let mut symbols: Vec<&KicadSymbol> = self.symbols.iter().collect();
+        crate::order::sort(&mut symbols, |symbol| (symbol.name.as_str(), symbol.value.as_str()));
+        for symbol in symbols {
+            writeln!(out, "{}", symbol.generate_symbol_as(self.format))?;
+        }
+        writeln!(out, ")")
     }
 }
\ No newline at end of file
@@ -0,0 +1,48 @@
+//! KOA Speer RK73H thick film chip resistor part numbers.
+//!
+//! `RK73H1JTTD4991F` reads as: series (`RK73H`, the 1% / 0.5% grade), size
+//! (`1J` = 0603), tin termination (`T`), packaging (`TD` 4 mm paper tape),
+//! resistance and tolerance (`F` = 1%). From 100 Ohm up the resistance is
+//! three significant digits and a decade digit (`4991` = 4.99K); below that
+//! an R marks the decimal point (`49R9`, `4R99`).
+//!
+//! The part numbers are built by [`atlantix_mpn::koa`], which also decodes
+//! them, for tools that need them without the generators.
+
+use crate::Resistance;
+
+/// RK73H resistance code, `None` outside the 1 Ohm to 10 MOhm range
+///
+/// ```
+/// use component::{koa, Resistance};
+///
+/// assert_eq!(koa::value_code(Resistance::from_ohms(4_990.0)).as_deref(), Some("4991"));
+/// assert_eq!(koa::value_code(Resistance::from_ohms(100.0)).as_deref(), Some("1000"));
+/// assert_eq!(koa::value_code(Resistance::from_ohms(49.9)).as_deref(), Some("49R9"));
+/// assert_eq!(koa::value_code(Resistance::from_ohms(4.99)).as_deref(), Some("4R99"));
+/// assert_eq!(koa::value_code(Resistance::from_ohms(10_000_000.0)).as_deref(), Some("1005"));
+/// assert_eq!(koa::value_code(Resistance::from_ohms(0.47)), None);
+/// ```
+pub fn value_code(resistance: Resistance) -> Option<String> {
+    atlantix_mpn::koa::value_code(resistance.micro_ohms())
+}
+
+/// RK73H part number, `None` for sizes, values or tolerances KOA does not
+/// make in this series.
+///
+/// ```
+/// use component::{koa, Resistance};
+///
+/// let r = Resistance::from_ohms(4_990.0);
+/// assert_eq!(koa::rk73h_mpn("0603", r, "1%").as_deref(), Some("RK73H1JTTD4991F"));
+/// assert_eq!(koa::rk73h_mpn("2512", r, "1%").as_deref(), Some("RK73H3ATTE4991F"));
+/// assert_eq!(koa::rk73h_mpn("0603", r, "0.1%"), None);
+/// ```
+pub fn rk73h_mpn(package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+    atlantix_mpn::koa::rk73h_mpn(package, resistance.micro_ohms(), tolerance)
+}
+
+/// Digi-Key cut tape part number, e.g. `2019-RK73H1JTTD4991FCT-ND`
+pub fn digikey_pn(package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+    atlantix_mpn::koa::digikey_pn(package, resistance.micro_ohms(), tolerance)
+}
@@ -0,0 +1,200 @@
+//! Import of land pattern exports from IPC-7351 calculators.
+//!
+//! Both CSV and XML exports are accepted. Each record describes one two
+//! terminal package either by pad size and pitch (`C`, `X`, `Y`) or by the
+//! outer/inner extents (`Z`, `G`, `X`), plus the body size (`L`, `W`).
+//! Column and attribute names are matched case-insensitively, with the
+//! common long forms accepted as aliases.
+
+use crate::kicad_footprint::PackageSpec;
+use crate::AtlantixError;
+use std::collections::HashMap;
+
+fn canonical_key(key: &str) -> Option<&'static str> {
+    let key = key.trim().to_lowercase().replace([' ', '_', '-'], "");
+    let canonical = match key.as_str() {
+        "package" | "name" | "imperial" | "size" => "package",
+        "metric" | "metricname" => "metric",
+        "c" | "padpitch" | "pitch" | "centertocenter" => "c",
+        "z" | "outer" | "overall" => "z",
+        "g" | "gap" | "inner" => "g",
+        "x" | "padwidth" => "x",
+        "y" | "padlength" => "y",
+        "l" | "bodylength" | "length" => "l",
+        "w" | "bodywidth" | "width" => "w",
+        _ => return None,
+    };
+    Some(canonical)
+}
+
+/// Turn one record of canonical keys into a package spec
+fn record_to_spec(record: &HashMap<&'static str, String>) -> Result<PackageSpec, AtlantixError> {
+    let package = record
+        .get("package")
+        .filter(|p| !p.is_empty())
+        .ok_or_else(|| AtlantixError::Parse("Land pattern record without a package name".to_string()))?
+        .clone();
+    let number = |key: &str| -> Result<Option<f64>, AtlantixError> {
+        match record.get(key).map(|v| v.trim()).filter(|v| !v.is_empty()) {
+            Some(v) => v
+                .parse::<f64>()
+                .map(Some)
+                .map_err(|_| AtlantixError::Parse(format!("{}: invalid {} value '{}'", package, key.to_uppercase(), v))),
+            None => Ok(None),
+        }
+    };
+    let required = |key: &str| -> Result<f64, AtlantixError> {
+        number(key)?.ok_or_else(|| AtlantixError::Parse(format!("{}: missing {} dimension", package, key.to_uppercase())))
+    };
+
+    let pad_height = required("x")?;
+    let (pad_width, pitch) = match (number("c")?, number("y")?, number("z")?, number("g")?) {
+        (Some(c), Some(y), _, _) => (y, c),
+        (_, _, Some(z), Some(g)) => ((z - g) / 2.0, (z + g) / 2.0),
+        _ => return Err(AtlantixError::Parse(format!("{}: need either C and Y or Z and G", package))),
+    };
+    let body_length = required("l")?;
+    let body_width = required("w")?;
+
+    Ok(PackageSpec {
+        metric: record.get("metric").cloned().unwrap_or_default(),
+        imperial: package,
+        body_length,
+        body_width,
+        pad_width,
+        pad_height,
+        pad_center_x: pitch / 2.0,
+    })
+}
+
+/// Parse a CSV land pattern export with a header row.
+///
+/// ```
+/// let csv = "Package,Metric,C,X,Y,L,W\n0603_HD,1608Metric,1.6,1.0,0.95,1.6,0.8\n";
+/// let specs = component::land_pattern::parse_csv(csv).unwrap();
+/// assert_eq!(specs[0].imperial, "0603_HD");
+/// assert_eq!(specs[0].pad_center_x, 0.8);
+/// assert_eq!(specs[0].pad_width, 0.95);
+/// ```
+pub fn parse_csv(content: &str) -> Result<Vec<PackageSpec>, AtlantixError> {
+    let mut lines = content.lines().filter(|l| !l.trim().is_empty());
+    let header: Vec<Option<&'static str>> = lines
+        .next()
+        .ok_or_else(|| AtlantixError::Parse("Empty land pattern CSV".to_string()))?
+        .split(',')
+        .map(|h| canonical_key(h.trim_matches('"')))
+        .collect();
+    if !header.contains(&Some("package")) {
+        return Err(AtlantixError::Parse("Land pattern CSV has no package/name column".to_string()));
+    }
+
+    lines
+        .map(|line| {
+            let record = header
+                .iter()
+                .zip(line.split(','))
+                .filter_map(|(key, value)| key.map(|k| (k, value.trim().trim_matches('"').to_string())))
+                .collect();
+            record_to_spec(&record)
+        })
+        .collect()
+}
+
+/// Parse an XML land pattern export. Each `<Package>` or `<LandPattern>`
+/// element may carry the dimensions as attributes or as child elements.
+///
+/// ```
+/// let xml = r#"<LandPatterns>
+///   <LandPattern name="0805_HD" Z="3.0" G="0.5" X="1.5" L="2.0" W="1.25"/>
+///   <LandPattern name="1206_HD"><C>2.9</C><X>1.8</X><Y>1.15</Y><L>3.2</L><W>1.6</W></LandPattern>
+/// </LandPatterns>"#;
+/// let specs = component::land_pattern::parse_xml(xml).unwrap();
+/// assert_eq!(specs[0].pad_width, 1.25);
+/// assert_eq!(specs[0].pad_center_x, 0.875);
+/// assert_eq!(specs[1].pad_height, 1.8);
+/// ```
+pub fn parse_xml(content: &str) -> Result<Vec<PackageSpec>, AtlantixError> {
+    let mut specs = Vec::new();
+    let mut rest = content;
+
+    while let Some(start) = find_record_start(rest) {
+        rest = &rest[start..];
+        let tag_end = rest.find('>').ok_or_else(|| AtlantixError::Parse("Unterminated XML element".to_string()))?;
+        let tag = &rest[..tag_end];
+        let tag_name: String = tag[1..].chars().take_while(|c| c.is_alphanumeric()).collect();
+
+        let mut record: HashMap<&'static str, String> = HashMap::new();
+        for (key, value) in attributes(tag) {
+            if let Some(k) = canonical_key(&key) {
+                record.insert(k, value);
+            }
+        }
+
+        let body_end = if tag.ends_with('/') {
+            tag_end + 1
+        } else {
+            let close = format!("</{}>", tag_name);
+            let close_at = rest.find(&close).ok_or_else(|| AtlantixError::Parse(format!("Missing {}", close)))?;
+            for (key, value) in child_elements(&rest[tag_end + 1..close_at]) {
+                if let Some(k) = canonical_key(&key) {
+                    record.insert(k, value);
+                }
+            }
+            close_at + close.len()
+        };
+
+        specs.push(record_to_spec(&record)?);
+        rest = &rest[body_end..];
+    }
+
+    if specs.is_empty() {
+        return Err(AtlantixError::Parse("No <Package> or <LandPattern> elements found".to_string()));
+    }
+    Ok(specs)
+}
+
+fn find_record_start(content: &str) -> Option<usize> {
+    ["<Package", "<LandPattern"]
+        .iter()
+        .filter_map(|tag| {
+            content.match_indices(tag).find_map(|(i, _)| {
+                // Skip container elements such as <LandPatterns> or <Packages>
+                let next = content[i + tag.len()..].chars().next()?;
+                (next.is_whitespace() || next == '>' || next == '/').then_some(i)
+            })
+        })
+        .min()
+}
+
+/// `key="value"` pairs inside a start tag
+fn attributes(tag: &str) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    let mut rest = tag;
+    while let Some(eq) = rest.find("=\"") {
+        let key = rest[..eq].rsplit(|c: char| c.is_whitespace()).next().unwrap_or("").to_string();
+        let after = &rest[eq + 2..];
+        let Some(end) = after.find('"') else { break };
+        pairs.push((key, after[..end].to_string()));
+        rest = &after[end + 1..];
+    }
+    pairs
+}
+
+/// `<Key>value</Key>` children of a record element
+fn child_elements(body: &str) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    let mut rest = body;
+    while let Some(open) = rest.find('<') {
+        let after = &rest[open + 1..];
+        let Some(close) = after.find('>') else { break };
+        let name = &after[..close];
+        let end_tag = format!("</{}>", name);
+        let Some(end) = after.find(&end_tag) else {
+            rest = &after[close + 1..];
+            continue;
+        };
+        pairs.push((name.to_string(), after[close + 1..end].trim().to_string()));
+        rest = &after[end + end_tag.len()..];
+    }
+    pairs
+}
@@ -0,0 +1,119 @@
+//! LCSC part numbers for JLCPCB assembly.
+//!
+//! JLCPCB assembles from LCSC stock and charges a feeder setup fee for every
+//! "extended" part, while "basic" parts stay loaded on the machines. Chip
+//! resistors map onto UNI-ROYAL's 1% thick film range, whose part numbers
+//! follow a fixed scheme. LCSC SKUs (`C25804`) are catalogue numbers with no
+//! scheme, so only the curated basic parts below carry one; other parts are
+//! matched by JLCPCB on the manufacturer part number.
+
+use crate::Resistance;
+
+/// An LCSC orderable part
+#[derive(Debug, Clone, PartialEq)]
+pub struct LcscPart {
+    pub manufacturer: &'static str,
+    pub mpn: String,
+    /// LCSC SKU, known for the JLCPCB basic parts
+    pub sku: Option<&'static str>,
+    /// Part of JLCPCB's basic library (no feeder setup fee)
+    pub basic: bool,
+}
+
+/// JLCPCB basic chip resistors, UNI-ROYAL part number to LCSC SKU
+const BASIC_RESISTORS: [(&str, &str); 10] = [
+    ("0402WGF1001TCE", "C11702"),
+    ("0402WGF1002TCE", "C25744"),
+    ("0603WAF1000T5E", "C22775"),
+    ("0603WAF1001T5E", "C21190"),
+    ("0603WAF2201T5E", "C4190"),
+    ("0603WAF4701T5E", "C23162"),
+    ("0603WAF5101T5E", "C23186"),
+    ("0603WAF1002T5E", "C25804"),
+    ("0603WAF1003T5E", "C25803"),
+    ("0805W8F1002T5E", "C17414"),
+];
+
+/// JLCPCB basic MLCCs: package, dielectric, value, manufacturer, MPN, LCSC SKU
+const BASIC_CAPACITORS: [(&str, &str, &str, &str, &str, &str); 10] = [
+    ("0402", "X7R", "10nF", "Samsung", "CL05B103KB5NNNC", "C15195"),
+    ("0402", "X7R", "100nF", "Samsung", "CL05B104KO5NNNC", "C1525"),
+    ("0402", "X5R", "1uF", "Samsung", "CL05A105KA5NQNC", "C52923"),
+    ("0603", "C0G", "22pF", "Samsung", "CL10C220JB8NNNC", "C1653"),
+    ("0603", "X7R", "10nF", "Samsung", "CL10B103KB8NNNC", "C57112"),
+    ("0603", "X7R", "100nF", "Yageo", "CC0603KRX7R9BB104", "C14663"),
+    ("0603", "X5R", "1uF", "Samsung", "CL10A105KB8NNNC", "C15849"),
+    ("0603", "X5R", "4.7uF", "Samsung", "CL10A475KO8NNNC", "C19666"),
+    ("0603", "X5R", "10uF", "Samsung", "CL10A106KP8NNNC", "C19702"),
+    ("0805", "X5R", "10uF", "Samsung", "CL21A106KAYNNNC", "C15850"),
+];
+
+/// UNI-ROYAL 1% thick film chip resistor part number, e.g. `0603WAF1002T5E`.
+///
+/// Returns `None` for packages outside 0201 to 1206 and values below 0.1 Ohm.
+///
+/// ```
+/// use component::{lcsc, Resistance};
+///
+/// assert_eq!(lcsc::uniroyal_mpn("0603", Resistance::from_ohms(10_000.0)).as_deref(), Some("0603WAF1002T5E"));
+/// assert_eq!(lcsc::uniroyal_mpn("0402", Resistance::from_ohms(49.9)).as_deref(), Some("0402WGF499JTCE"));
+/// assert_eq!(lcsc::uniroyal_mpn("0805", Resistance::from_ohms(1.0)).as_deref(), Some("0805W8F100KT5E"));
+/// assert_eq!(lcsc::uniroyal_mpn("2512", Resistance::from_ohms(1.0)), None);
+/// ```
+pub fn uniroyal_mpn(package: &str, resistance: Resistance) -> Option<String> {
+    let (power, taping) = match package {
+        "0201" => ("M", "TEE"),
+        "0402" => ("G", "TCE"),
+        "0603" => ("A", "T5E"),
+        "0805" => ("8", "T5E"),
+        "1206" => ("4", "T5E"),
+        _ => return None,
+    };
+    // Three significant digits and a multiplier, J and K for 0.1 and 0.01
+    let (digits, exponent) = resistance.significant_digits();
+    let multiplier = match exponent - 2 {
+        m @ 0..=9 => char::from(b'0' + m as u8),
+        -1 => 'J',
+        -2 => 'K',
+        -3 => 'L',
+        _ => return None,
+    };
+    Some(format!("{}W{}F{}{}{}", package, power, digits, multiplier, taping))
+}
+
+/// LCSC part for a generated chip resistor
+///
+/// ```
+/// use component::{lcsc, Resistance};
+///
+/// let part = lcsc::resistor("0603", Resistance::from_ohms(10_000.0)).unwrap();
+/// assert_eq!(part.sku, Some("C25804"));
+/// assert!(part.basic);
+/// assert!(!lcsc::resistor("0603", Resistance::from_ohms(10_200.0)).unwrap().basic);
+/// ```
+pub fn resistor(package: &str, resistance: Resistance) -> Option<LcscPart> {
+    let mpn = uniroyal_mpn(package, resistance)?;
+    let sku = BASIC_RESISTORS.iter().find(|(basic, _)| *basic == mpn).map(|(_, sku)| *sku);
+    Some(LcscPart { manufacturer: "UNI-ROYAL", mpn, sku, basic: sku.is_some() })
+}
+
+/// LCSC part for an MLCC, only known for the JLCPCB basic capacitors.
+/// `value` is written like the capacitor libraries (`100nF`, `4.7uF`).
+///
+/// ```
+/// let part = component::lcsc::capacitor("0603", "x7r", "100nF").unwrap();
+/// assert_eq!((part.mpn.as_str(), part.sku), ("CC0603KRX7R9BB104", Some("C14663")));
+/// assert_eq!(component::lcsc::capacitor("1206", "X7R", "100nF"), None);
+/// ```
+pub fn capacitor(package: &str, dielectric: &str, value: &str) -> Option<LcscPart> {
+    let value = value.replace('µ', "u");
+    BASIC_CAPACITORS
+        .iter()
+        .find(|(p, d, v, ..)| *p == package && d.eq_ignore_ascii_case(dielectric) && *v == value)
+        .map(|(_, _, _, manufacturer, mpn, sku)| LcscPart {
+            manufacturer,
+            mpn: mpn.to_string(),
+            sku: Some(sku),
+            basic: true,
+        })
+}
@@ -0,0 +1,297 @@
+//! Chip LED library generation.
+//!
+//! Generates 0402 to 1206 chip LEDs per color with forward voltage and
+//! luminous intensity attributes. Part numbers follow the Kingbright APT
+//! and Lite-On LTST schemes; Digikey part numbers use the `-ND` suffix on the
+//! manufacturer part number.
+//!
+//! Forward voltage and intensity are typical values at 20 mA (10 mA for
+//! 0402) for standard brightness parts.
+
+use crate::kicad_footprint::{self, FootprintNaming, KicadFootprint};
+use crate::kicad_symbol::{KicadSymbol, KicadSymbolLib};
+use crate::status::PartStatus;
+use crate::AtlantixError;
+use std::fmt;
+use std::str::FromStr;
+
+/// Packages supported by the LED generator
+pub const LED_PACKAGES: [&str; 4] = ["0402", "0603", "0805", "1206"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedColor {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    White,
+}
+
+impl LedColor {
+    pub const ALL: [LedColor; 6] = [
+        LedColor::Red,
+        LedColor::Orange,
+        LedColor::Yellow,
+        LedColor::Green,
+        LedColor::Blue,
+        LedColor::White,
+    ];
+
+    /// Dominant wavelength in nm, `None` for white
+    pub fn wavelength_nm(self) -> Option<u32> {
+        match self {
+            LedColor::Red => Some(630),
+            LedColor::Orange => Some(605),
+            LedColor::Yellow => Some(590),
+            LedColor::Green => Some(525),
+            LedColor::Blue => Some(470),
+            LedColor::White => None,
+        }
+    }
+
+    /// Typical forward voltage in volts
+    pub fn forward_voltage(self) -> f64 {
+        match self {
+            LedColor::Red | LedColor::Orange => 2.0,
+            LedColor::Yellow => 2.1,
+            LedColor::Green | LedColor::Blue | LedColor::White => 3.2,
+        }
+    }
+
+    /// Typical luminous intensity of a 0603 part in mcd
+    fn intensity_mcd(self) -> f64 {
+        match self {
+            LedColor::Red => 90.0,
+            LedColor::Orange => 100.0,
+            LedColor::Yellow => 80.0,
+            LedColor::Green => 300.0,
+            LedColor::Blue => 100.0,
+            LedColor::White => 350.0,
+        }
+    }
+
+    fn kingbright_code(self) -> &'static str {
+        match self {
+            LedColor::Red => "SURCK",
+            LedColor::Orange => "SECK",
+            LedColor::Yellow => "SYCK",
+            LedColor::Green => "ZGCK",
+            LedColor::Blue => "QBC/D",
+            LedColor::White => "QWF/D",
+        }
+    }
+
+    fn liteon_code(self) -> &'static str {
+        match self {
+            LedColor::Red => "KRKT",
+            LedColor::Orange => "KFKT",
+            LedColor::Yellow => "KSKT",
+            LedColor::Green => "KGKT",
+            LedColor::Blue => "TBKT",
+            LedColor::White => "KWKT",
+        }
+    }
+}
+
+impl fmt::Display for LedColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            LedColor::Red => "Red",
+            LedColor::Orange => "Orange",
+            LedColor::Yellow => "Yellow",
+            LedColor::Green => "Green",
+            LedColor::Blue => "Blue",
+            LedColor::White => "White",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for LedColor {
+    type Err = AtlantixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LedColor::ALL
+            .iter()
+            .copied()
+            .find(|color| color.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| AtlantixError::Parse(format!("Unknown LED color '{}'", s)))
+    }
+}
+
+struct LedPackage {
+    kingbright_series: &'static str,
+    liteon_series: &'static str,
+    /// Intensity relative to the 0603 reference
+    intensity_scale: f64,
+}
+
+fn package_info(package: &str) -> Option<LedPackage> {
+    let (kingbright_series, liteon_series, intensity_scale) = match package {
+        "0402" => ("APHHS1005", "LTST-C281", 0.5),
+        "0603" => ("APT1608", "LTST-C191", 1.0),
+        "0805" => ("APT2012", "LTST-C171", 1.2),
+        "1206" => ("APT3216", "LTST-C150", 1.5),
+        _ => return None,
+    };
+    Some(LedPackage { kingbright_series, liteon_series, intensity_scale })
+}
+
+/// One generated LED part
+#[derive(Debug, Clone, PartialEq)]
+pub struct LedPart {
+    pub name: String,
+    pub color: LedColor,
+    pub package: String,
+    pub wavelength_nm: Option<u32>,
+    pub forward_voltage: f64,
+    pub intensity_mcd: f64,
+    pub kingbright_mpn: String,
+    pub liteon_mpn: String,
+    /// Internal part number, see [`crate::ipn`], given by `parts()` to parts
+    /// the hook keeps
+    pub ipn: Option<String>,
+}
+
+impl LedPart {
+    pub fn digikey_pn(&self) -> String {
+        format!("{}-ND", self.kingbright_mpn.replace('/', ""))
+    }
+}
+
+///
+/// LED generator for one package.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Led {
+    package: String,
+    colors: Vec<LedColor>,
+    status: Option<PartStatus>,
+    footprint_naming: FootprintNaming,
+}
+
+impl Led {
+    /// Returns `None` for unsupported packages.
+    pub fn new(package: &str, colors: Vec<LedColor>) -> Option<Led> {
+        package_info(package)?;
+        Some(Led {
+            package: package.to_string(),
+            colors,
+            status: None,
+            footprint_naming: FootprintNaming::Atlantix,
+        })
+    }
+
+    /// Stamps every subsequently generated KiCad symbol with a hidden `Status` property.
+    pub fn set_status(&mut self, status: PartStatus) {
+        self.status = Some(status);
+    }
+
+    /// With `FootprintNaming::KicadOfficial` the symbols reference the stock
+    /// KiCad footprints of the package in place of the generated ones.
+    pub fn set_footprint_naming(&mut self, naming: FootprintNaming) {
+        self.footprint_naming = naming;
+    }
+
+    /// One part per color, as the part hook leaves them
+    pub fn parts(&self) -> Vec<LedPart> {
+        let footprint = self.footprint_name().unwrap_or_default();
+        self.generated_parts()
+            .into_iter()
+            .filter_map(|mut part| {
+                let outcome = crate::hook::outcome(|| self.kicad_symbol(&part, &footprint))?;
+                for (field, changed) in [
+                    (&mut part.name, outcome.changed(|symbol| &symbol.name)),
+                    (&mut part.kingbright_mpn, outcome.changed(|symbol| &symbol.mpn)),
+                ] {
+                    if let Some(changed) = changed {
+                        *field = changed.to_string();
+                    }
+                }
+                part.ipn = crate::ipn::assign("led", &self.package, &part.name, "");
+                Some(part)
+            })
+            .collect()
+    }
+
+    fn generated_parts(&self) -> Vec<LedPart> {
+        let info = package_info(&self.package).expect("package validated in new()");
+        self.colors
+            .iter()
+            .map(|color| {
+                let name = format!("LED{}_{}", crate::package_naming::label(&self.package), color);
+                LedPart {
+                    ipn: None,
+                    name,
+                    color: *color,
+                    package: self.package.clone(),
+                    wavelength_nm: color.wavelength_nm(),
+                    forward_voltage: color.forward_voltage(),
+                    intensity_mcd: (color.intensity_mcd() * info.intensity_scale).round(),
+                    kingbright_mpn: format!("{}{}", info.kingbright_series, color.kingbright_code()),
+                    liteon_mpn: format!("{}{}", info.liteon_series, color.liteon_code()),
+                }
+            })
+            .collect()
+    }
+
+    /// Footprint the symbols reference, `None` without one for the package
+    pub fn footprint_name(&self) -> Option<String> {
+        match self.footprint_naming {
+            FootprintNaming::Atlantix => KicadFootprint::new_smd_led(&self.package)
+                .map(|fp| format!("Atlantix_LEDs:{}", fp.name)),
+            FootprintNaming::KicadOfficial => kicad_footprint::official_footprint("LED_SMD", "LED", &self.package),
+        }
+    }
+
+    pub fn generate_kicad_symbols(&self, output_path: &str) -> Result<(), AtlantixError> {
+        let mut symbol_lib = KicadSymbolLib::new();
+        let footprint = self.footprint_name().unwrap_or_default();
+
+        for part in self.generated_parts() {
+            if let Some(mut symbol) = crate::hook::apply(self.kicad_symbol(&part, &footprint)) {
+                crate::ipn::assign_symbol("led", &self.package, &mut symbol);
+                symbol_lib.add_symbol(symbol);
+            }
+        }
+
+        crate::atomic::write(output_path, symbol_lib.generate_library())
+    }
+
+    /// The KiCad symbol of a generated part, before the part hook
+    fn kicad_symbol(&self, part: &LedPart, footprint: &str) -> KicadSymbol {
+        let wavelength = part
+            .wavelength_nm
+            .map(|nm| format!("{}nm", nm))
+            .unwrap_or_else(|| "white".to_string());
+        let description = format!(
+            "LED SMT {} {}, {}, Vf {}V, {}mcd",
+            part.color, wavelength, self.package, part.forward_voltage, part.intensity_mcd
+        );
+        let mut symbol = KicadSymbol::new_led(part.name.clone(), part.color.to_string(), footprint.to_string())
+            .with_sources("Kingbright", &part.kingbright_mpn, &part.digikey_pn());
+        symbol.description = description;
+        symbol.properties.extend([
+            ("Color".to_string(), part.color.to_string()),
+            ("Wavelength".to_string(), wavelength),
+            ("Vf".to_string(), format!("{}V", part.forward_voltage)),
+            ("Intensity".to_string(), format!("{}mcd", part.intensity_mcd)),
+            ("MPN2".to_string(), part.liteon_mpn.clone()),
+            ("Manufacturer2".to_string(), "Lite-On".to_string()),
+        ]);
+        symbol.status = self.status;
+        symbol
+    }
+
+    pub fn generate_kicad_footprints(packages: &[&str], output_dir: &str) -> Result<(), AtlantixError> {
+        crate::atomic::create_dir_all(output_dir)?;
+        for package in packages.iter().filter(|p| package_info(p).is_some()) {
+            if let Some(footprint) = KicadFootprint::new_smd_led(package) {
+                let filename = format!("{}/{}.kicad_mod", output_dir, footprint.name);
+                crate::atomic::write(filename, footprint.generate_footprint())?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -8,11 +8,175 @@ extern crate bevy_ecs;
 pub mod kicad_symbol;
 pub mod kicad_footprint;
 pub mod ecs;
+pub mod status;
+pub mod resistance;
+pub mod inductor;
+pub mod ferrite;
+pub mod led;
+pub mod diode;
+pub mod current_sense;
+pub mod power;
+pub mod land_pattern;
+pub mod distributor;
+pub mod estimate;
+pub mod preview;
+pub mod gui;
+pub mod lcsc;
+pub mod atomic;
+pub mod manufacturer;
+pub mod yageo;
+pub mod vishay;
+pub mod koa;
+pub mod stackpole;
+pub mod susumu;
+pub mod tolerance;
+pub mod altium;
+pub mod marking;
+pub mod hook;
+pub mod spice;
+pub mod model3d;
+pub mod order;
+pub mod output_path;
+pub mod availability;
+pub mod sourcing;
+pub mod family;
+pub mod datasheet;
+pub mod memo;
+pub mod limits;
+pub mod thermal;
+pub mod package_naming;
+pub mod naming;
+pub mod ipn;
+pub mod capabilities;
+pub mod dashboard;
+pub mod settings;
+pub mod error;
 
 use self::num_traits::Pow;
 use crate::kicad_symbol::{KicadSymbol, KicadSymbolLib};
-use crate::kicad_footprint::KicadFootprint;
-use std::fs;
+use crate::kicad_footprint::{get_package_specs, FootprintNaming, KicadFootprint, SolderProcess};
+pub use crate::status::PartStatus;
+pub use crate::resistance::{Resistance, ValueNotation, ValueRange};
+pub use crate::power::{chip_resistor_power, Power};
+pub use crate::inductor::Inductor;
+pub use crate::ferrite::FerriteBead;
+pub use crate::led::{Led, LedColor};
+pub use crate::diode::{Diode, DiodeKind};
+pub use crate::current_sense::CurrentSense;
+pub use crate::distributor::Distributor;
+pub use crate::manufacturer::Manufacturer;
+pub use crate::capabilities::capabilities;
+pub use crate::error::AtlantixError;
+
+/// Decades covering the standard 1 Ohm to 1 MOhm sweep.
+pub const STANDARD_DECADES: [f64; 6] = [1.0, 10.0, 100.0, 1_000.0, 10_000.0, 100_000.0];
+
+/// Decades covering the full supported range, 1 mOhm up to 10 MOhm. The
+/// last decade only holds its closing 10 MOhm value.
+pub const EXTENDED_DECADES: [f64; 11] = [
+    0.001, 0.01, 0.1, 1.0, 10.0, 100.0, 1_000.0, 10_000.0, 100_000.0, 1_000_000.0, 10_000_000.0,
+];
+
+/// Split an ohmic value into its base value (1.00 to 9.99) and decade
+/// multiplier, as consumed by `Resistor::generate`.
+///
+/// Returns `None` for values outside the supported 1 mOhm to 10 MOhm range.
+///
+/// ```
+/// assert_eq!(component::split_decade(3320.0), Some((3.32, 1000.0)));
+/// assert_eq!(component::split_decade(0.47), Some((4.7, 0.1)));
+/// ```
+pub fn split_decade(ohms: f64) -> Option<(f64, f64)> {
+    if !(0.001..=10_000_000.0).contains(&ohms) {
+        return None;
+    }
+    let (digits, exponent) = significant_digits(ohms);
+    Some((digits as f64 / 100.0, 10f64.powi(exponent)))
+}
+
+/// Whether a value lies in the supported 1 mOhm to 10 MOhm range
+pub(crate) fn is_supported(value: Resistance) -> bool {
+    split_decade(value.ohms()).is_some()
+}
+
+/// Round a value to three significant digits, returned as an integer in
+/// 100..=999 together with the decade exponent of the leading digit.
+fn significant_digits(ohms: f64) -> (u32, i32) {
+    let mut exponent = ohms.log10().floor() as i32;
+    let mut digits = (ohms / 10f64.powi(exponent - 2)).round() as u32;
+    if digits >= 1000 {
+        // 9.996 rounds up into the next decade
+        digits /= 10;
+        exponent += 1;
+    }
+    (digits, exponent)
+}
+
+/// Format an ohmic value the way it appears in part names and values.
+///
+/// Values from 1 Ohm upwards keep three significant digits with a K or M
+/// suffix (`4.99K`, `100`, `1.00M`). Sub-ohm values use the R notation
+/// (`0R47`) and milliohm values a trailing m (`10m`, `4.75m`).
+///
+/// ```
+/// assert_eq!(component::format_value(4990.0), "4.99K");
+/// assert_eq!(component::format_value(0.47), "0R47");
+/// assert_eq!(component::format_value(0.01), "10m");
+/// ```
+pub fn format_value(ohms: f64) -> String {
+    let (digits, exponent) = significant_digits(ohms);
+    format_from_digits(digits, exponent)
+}
+
+/// Name of a resistor part, `R0603_4.99K`, with the tolerance appended,
+/// `R0603_4.99K_1%`, in libraries generated in several tolerances.
+///
+/// ```
+/// use component::Resistance;
+///
+/// let value = Resistance::from_ohms(10_000.0);
+/// assert_eq!(component::resistor_name("0603", value, None), "R0603_10.0K");
+/// assert_eq!(component::resistor_name("0603", value, Some("1%")), "R0603_10.0K_1%");
+/// ```
+pub fn resistor_name(package: &str, resistance: Resistance, tolerance: Option<&str>) -> String {
+    naming::part_name("R", package, resistance, tolerance)
+}
+
+/// Render three significant digits with the decade exponent of the leading digit
+pub(crate) fn format_from_digits(digits: u32, exponent: i32) -> String {
+    atlantix_mpn::digits::label(digits, exponent)
+}
+
+/// Encode a value as a Vishay style four character resistance code, where the
+/// R, K or M letter marks the decimal point (`4K99`, `100R`, `1M00`, `R470`).
+///
+/// ```
+/// assert_eq!(component::vishay_value_code(1050.0), "1K05");
+/// assert_eq!(component::vishay_value_code(0.47), "R470");
+/// ```
+pub fn vishay_value_code(ohms: f64) -> String {
+    let (digits, exponent) = significant_digits(ohms);
+    vishay_code_from_digits(digits, exponent)
+}
+
+pub(crate) fn vishay_code_from_digits(digits: u32, exponent: i32) -> String {
+    atlantix_mpn::digits::letter_code(digits, exponent)
+}
+
+/// European inline-decimal notation, where the unit letter marks the decimal
+/// point and all three significant digits are kept (`4k70`, `2M21`, `100R`,
+/// `4m75`). Sub-ohm values keep the `0R47` form they already have.
+pub(crate) fn european_from_digits(digits: u32, exponent: i32) -> String {
+    match exponent {
+        -1 => format_from_digits(digits, exponent),
+        e if e < -1 => {
+            let digits = digits.to_string();
+            let integer_digits = ((e + 4).max(1) as usize).min(digits.len());
+            format!("{}m{}", &digits[..integer_digits], &digits[integer_digits..])
+        }
+        e => vishay_code_from_digits(digits, e).replace('K', "k"),
+    }
+}
 
 ///
 /// Resistor type data structure
@@ -22,7 +186,7 @@ use std::fs;
 /// * `series`         - The series such as E-96, E-48, E-24 for resistor values.
 /// * `name`           - Resistor name as you want it to appear in your PCB library.
 /// * `full_part_name` - Full name that is CSV formatted and writtent to a file.
-/// * `value`          - Ohmic value, such as 1.00K, 4.99K, 100K, etc.
+/// * `value`          - Ohmic value, rendered as 1.00K, 4.99K, 100K, etc.
 /// * `manuf`          - Vishay, KOA, Panasonic, etc. Currently Vishay is implemented.
 /// * `case`           - The case size, such as 0402, 0603, 0805, 1206, etc.
 /// * `power`          - power rating which is corresponding to the package/case.
@@ -43,11 +207,28 @@ pub struct Resistor {
     name: String,
     full_part_name: String,
     full_series: String,
-    value: String,
+    value: Resistance,
     manuf: String,
     case: String,
-    power: String,
+    power: Power,
     series_array: Vec<f64>,
+    status: Option<PartStatus>,
+    footprint_naming: FootprintNaming,
+    distributor: Distributor,
+    manufacturer: Manufacturer,
+    alternates: Vec<Manufacturer>,
+    tolerance: &'static str,
+    /// Part names end in the tolerance
+    tolerance_in_name: bool,
+    /// Values outside are skipped like unavailable ones
+    value_range: ValueRange,
+    automotive: bool,
+    /// SPICE model and the `.lib` path the symbols reference
+    spice: Option<(spice::SpiceModel, String)>,
+    /// Directory holding `Atlantix_Resistors.3dshapes`, when the footprints use generated models
+    model_dir: Option<String>,
+    /// Hand or wave soldering alternates written next to each footprint
+    solder_processes: Vec<SolderProcess>,
 }
 
 impl Resistor {
@@ -61,60 +242,20 @@ impl Resistor {
     /// object. The Resistor object can then be iterated upon
     /// to generate all the library values for this package/series combination.
     ///
-    /// 	pub fn new(eseries : usize, package : String ) -> Resistor {
-    /// 	let mut alpha = vec![0.0 ; eseries];
-    ///     for index in 0..eseries{
-    ///         let gamma : f64 = Pow::pow(10.0,index as f32/eseries as f32);
-    ///         alpha[index] = (gamma * 100.0).round() / 100.0;
-    ///     }
-    /// 	let watts : String;
-    /// 		match package.as_ref() {
-    /// 			"0201" => watts = "1/20".to_string(),
-    /// 			"0402" => watts = "1/16".to_string(),
-    /// 			"0603" => watts = "1/10".to_string(),
-    /// 			"0805" => watts = "1/8".to_string(),
-    /// 			"1206" => watts = "1/4".to_string(),
-    /// 			"1210" => watts = "1/2".to_string(),
-    /// 			"1812" => watts = "1".to_string(),
-    /// 			"2010" => watts = "3/4".to_string(),
-    /// 			"2512" => watts = "1".to_string(),
-    /// 			_ => watts = "0".to_string(),
-    /// 		};
-    ///
-    /// 		Resistor {
-    /// 			display : false,
-    /// 			series : eseries,
-    /// 			name  : "RES".to_string() + &package + &"_".to_string() + &"1.00K".to_string(),
-    /// 			full_part_name : "RES".to_string() + &package + &"_".to_string() + &"1.00K".to_string(),
-    /// 			full_series : "".to_string(),
-    /// 			value : "1.00K".to_string(),
-    /// 			manuf : "Vishay".to_string(),
-    /// 			case  : package,
-    /// 			power : watts,
-    /// 			series_array : alpha,
-    /// 		}
-    ///
-    /// 	}
+    /// ```
+    /// use component::Resistor;
     ///
+    /// let resistor = Resistor::new(96, "0603".to_string());
+    /// assert_eq!(resistor.value().label(), "1.00K");
+    /// ```
     pub fn new(eseries: usize, package: String) -> Resistor {
         let mut alpha = vec![0.0; eseries];
         for index in 0..eseries {
             let gamma: f64 = Pow::pow(10.0, index as f32 / eseries as f32);
             alpha[index] = (gamma * 100.0).round() / 100.0;
         }
-        let watts: String;
-        match package.as_ref() {
-            "0201" => watts = "1/20".to_string(),
-            "0402" => watts = "1/16".to_string(),
-            "0603" => watts = "1/10".to_string(),
-            "0805" => watts = "1/8".to_string(),
-            "1206" => watts = "1/4".to_string(),
-            "1210" => watts = "1/2".to_string(),
-            "1218" => watts = "1".to_string(),
-            "2010" => watts = "3/4".to_string(),
-            "2512" => watts = "1".to_string(),
-            _ => watts = "0".to_string(),
-        };
+        // Unknown packages carry no rating
+        let watts = chip_resistor_power(&package).unwrap_or_default();
 
         Resistor {
             display: false,
@@ -122,45 +263,47 @@ impl Resistor {
             name: "RES".to_string() + &package + &"_".to_string() + &"1.00K".to_string(),
             full_part_name: "RES".to_string() + &package + &"_".to_string() + &"1.00K".to_string(),
             full_series: "".to_string(),
-            value: "1.00K".to_string(),
+            value: Resistance::from_ohms(1000.0),
             manuf: "Vishay".to_string(),
             case: package,
             power: watts,
             series_array: alpha,
+            status: None,
+            footprint_naming: FootprintNaming::Atlantix,
+            distributor: Distributor::Digikey,
+            manufacturer: Manufacturer::vishay(),
+            alternates: Vec::new(),
+            tolerance: tolerance::for_series(eseries),
+            tolerance_in_name: false,
+            value_range: ValueRange::default(),
+            automotive: false,
+            spice: None,
+            model_dir: None,
+            solder_processes: Vec::new(),
         }
     }
+    /// Impl Function : with_values (constructor)
+    /// #  Remarks
+    ///
+    /// Constructor for a Resistor restricted to an explicit list of base values
+    /// (mantissas in the range 1.00 to 9.99) instead of the full E-series. The
+    /// `eseries` argument is still used to derive tolerance, so a single E96
+    /// value requested by a librarian keeps its 1% rating.
+    ///
+    pub fn with_values(eseries: usize, package: String, values: Vec<f64>) -> Resistor {
+        let mut resistor = Resistor::new(eseries, package);
+        resistor.series_array = values;
+        resistor
+    }
+
     ///  Impl Function : set_digikey_pn  
     ///  #  Remarks
     ///
-    /// This will assign a Digikey distributor part number to the self.manuf field.
-    /// This is true for all decades other than decade 1, which has special exception.
-    ///
-    pub fn set_digikey_pn(&mut self, index: usize, decade: u32) {
-        if decade == 1 {
-            match self.case.as_str() {
-                "0402" => self.manuf = format!("541-{}LLCT-ND", self.series_array[index]),
-                "0603" => self.manuf = format!("541-{}HHCT-ND", self.series_array[index]),
-                "0805" => self.manuf = format!("541-{}CCCT-ND", self.series_array[index]),
-                "1206" => self.manuf = format!("541-{}FFCT-ND", self.series_array[index]),
-                "1210" => self.manuf = format!("541-{}AACT-ND", self.series_array[index]),
-                "1218" => self.manuf = format!("541-{}ANCT-ND", self.series_array[index]),
-                "2010" => self.manuf = format!("541-{}ACCT-ND", self.series_array[index]),
-                "2512" => self.manuf = format!("541-{}AFCT-ND", self.series_array[index]),
-                _ => self.manuf = format!("541-{}XXXX-ND", self.series_array[index]),
-            }
-        } else {
-        match self.case.as_str() {
-            "0402" => self.manuf = format!("541-{}LCT-ND", self.value),
-            "0603" => self.manuf = format!("541-{}HCT-ND", self.value),
-            "0805" => self.manuf = format!("541-{}CCT-ND", self.value),
-            "1206" => self.manuf = format!("541-{}FCT-ND", self.value),
-            "1210" => self.manuf = format!("541-{}VCT-ND", self.value),
-            "1218" => self.manuf = format!("541-{}KANCT-ND", self.value),
-            "2010" => self.manuf = format!("541-{}KACCT-ND", self.value),
-            "2512" => self.manuf = format!("541-{}KAFCT-ND", self.value),
-            _ => self.manuf = format!("541-{}XXX-ND", self.value),
-        }
-    }
+    /// This will assign the Digikey distributor part number of the current
+    /// value to the self.manuf field, left empty when Digikey has none.
+    ///
+    pub fn set_digikey_pn(&mut self) {
+        self.manuf = vishay::digikey_pn(&self.case, self.value, self.tolerance).unwrap_or_default();
     }
 
     ///  Impl Function : set_vishay_mpn
@@ -171,68 +314,238 @@ impl Resistor {
     /// Example: CRCW06031K05FKEA
     ///
     pub fn generate_vishay_mpn(&self) -> String {
-        // Convert package to Vishay format
-        let package_code = match self.case.as_str() {
-            "0402" => "0402",
-            "0603" => "0603", 
-            "0805" => "0805",
-            "1206" => "1206",
-            "1210" => "1210",
-            "2010" => "2010",
-            "2512" => "2512",
-            _ => "0603", // default
-        };
-        
-        // Convert resistance value to Vishay format
-        let resistance_code = self.format_vishay_resistance(&self.value);
-        
-        // F = 1% tolerance, K = 100ppm/°C TCR, E = AEC-Q200 qualified, A = packaging
-        let suffix = "FKEA";
-        
-        format!("CRCW{}{}{}", package_code, resistance_code, suffix)
+        // Unknown packages fall back to 0603
+        vishay::crcw_mpn(&self.case, self.value, self.tolerance)
+            .or_else(|| vishay::crcw_mpn("0603", self.value, self.tolerance))
+            .unwrap_or_default()
     }
 
-    fn format_vishay_resistance(&self, value: &str) -> String {
-        if value.contains("K") {
-            // Convert "1.05K" to "1K05"
-            let numeric_part = value.replace("K", "");
-            if let Ok(num) = numeric_part.parse::<f64>() {
-                if num >= 10.0 {
-                    format!("{}K0", num as i32)
-                } else if num >= 1.0 {
-                    let int_part = num as i32;
-                    let frac_part = ((num - int_part as f64) * 100.0).round() as i32;
-                    if frac_part == 0 {
-                        format!("{}K00", int_part)
-                    } else {
-                        format!("{}K{:02}", int_part, frac_part)
-                    }
-                } else {
-                    format!("R{:03}", (num * 1000.0) as i32)
-                }
-            } else {
-                "1K00".to_string()
-            }
+    ///  Impl Resistor : value
+    ///  #  Remarks
+    ///
+    ///  Returns the value of the most recently generated part, which displays as e.g. "3.32K".
+    ///
+    pub fn value(&self) -> Resistance {
+        self.value
+    }
+
+    /// Rated power of the package, zero for unknown packages
+    pub fn power(&self) -> Power {
+        self.power
+    }
+
+    ///  Impl Resistor : set_status
+    ///  #  Remarks
+    ///
+    ///  Stamps every subsequently generated KiCad symbol with a hidden `Status`
+    ///  property, e.g. for libraries under a controlled release process.
+    ///
+    pub fn set_status(&mut self, status: PartStatus) {
+        self.status = Some(status);
+    }
+
+    ///  Impl Resistor : set_footprint_naming
+    ///  #  Remarks
+    ///
+    ///  With `FootprintNaming::KicadOfficial` the symbols reference the stock
+    ///  `Resistor_SMD` footprints, which carry the same `R_0603_1608Metric`
+    ///  names, and `generate_kicad_footprints` writes nothing.
+    ///
+    pub fn set_footprint_naming(&mut self, naming: FootprintNaming) {
+        self.footprint_naming = naming;
+    }
+
+    ///  Impl Resistor : set_distributor
+    ///  #  Remarks
+    ///
+    ///  Selects the distributor written as `Supplier 1` in the Altium CSV and
+    ///  as `Supplier`/`SupplierPN` in the KiCad symbols. Digikey by default.
+    ///  Suppliers listed in the [`crate::settings`] take precedence.
+    ///
+    pub fn set_distributor(&mut self, distributor: Distributor) {
+        self.distributor = distributor;
+    }
+
+    ///  Impl Resistor : suppliers
+    ///  #  Remarks
+    ///
+    ///  Distributors written as `Supplier 1`, `Supplier 2`, ...: those of
+    ///  [`distributor::suppliers`], or the one of `set_distributor`.
+    ///
+    pub fn suppliers(&self) -> Vec<Distributor> {
+        let suppliers = distributor::suppliers();
+        if suppliers.is_empty() {
+            vec![self.distributor]
         } else {
-            // Convert ohm values like "1.05" to "1R05" 
-            if let Ok(num) = value.parse::<f64>() {
-                if num >= 100.0 {
-                    format!("{:.0}R", num)
-                } else if num >= 10.0 {
-                    format!("{:.0}R0", num)
-                } else {
-                    let int_part = num as i32;
-                    let frac_part = ((num - int_part as f64) * 100.0).round() as i32;
-                    if frac_part == 0 {
-                        format!("{}R00", int_part)
-                    } else {
-                        format!("{}R{:02}", int_part, frac_part)
-                    }
-                }
+            suppliers
+        }
+    }
+
+    ///  Impl Resistor : set_manufacturer
+    ///  #  Remarks
+    ///
+    ///  Selects the manufacturer whose part numbers are generated. Vishay by default.
+    ///
+    pub fn set_manufacturer(&mut self, manufacturer: Manufacturer) {
+        self.manufacturer = manufacturer;
+    }
+
+    ///  Impl Resistor : set_alternate_manufacturers
+    ///  #  Remarks
+    ///
+    ///  Second sources whose part numbers are added to each KiCad symbol as
+    ///  "Manufacturer 2"/"MPN 2", "Manufacturer 3"/"MPN 3" and so on.
+    ///
+    pub fn set_alternate_manufacturers(&mut self, alternates: Vec<Manufacturer>) {
+        self.alternates = alternates;
+    }
+
+    ///  Impl Resistor : set_tolerance
+    ///  #  Remarks
+    ///
+    ///  Overrides the tolerance the E-series implies, e.g. 0.1% for E192
+    ///  precision parts. 0.1% and 0.05% switch to thin film part numbers.
+    ///
+    pub fn set_tolerance(&mut self, tolerance: &'static str) {
+        self.tolerance = tolerance;
+    }
+
+    ///  Impl Resistor : set_automotive
+    ///  #  Remarks
+    ///
+    ///  Uses AEC-Q200 qualified part numbers (Yageo AC instead of RC, for
+    ///  example) and marks every part with an `AEC-Q200` field. Values the
+    ///  manufacturer has no qualified part for get an empty MPN.
+    ///
+    pub fn set_automotive(&mut self, automotive: bool) {
+        self.automotive = automotive;
+    }
+
+    ///  Impl Resistor : set_tolerance_in_name
+    ///  #  Remarks
+    ///
+    ///  Appends the tolerance to part and symbol names, 'R0603_10.0K_1%',
+    ///  so the same values in several tolerances can be used side by side.
+    ///
+    pub fn set_tolerance_in_name(&mut self, tolerance_in_name: bool) {
+        self.tolerance_in_name = tolerance_in_name;
+    }
+
+    ///  Impl Resistor : set_value_range
+    ///  #  Remarks
+    ///
+    ///  Trims the generated values to a range, e.g. 10 Ohm to 1 MOhm.
+    ///  Pass decades from [`ValueRange::decades`] so the range is covered.
+    ///
+    pub fn set_value_range(&mut self, value_range: ValueRange) {
+        self.value_range = value_range;
+    }
+
+    /// Part name of the current value
+    fn part_name(&self) -> String {
+        resistor_name(&self.case, self.value, self.tolerance_in_name.then_some(self.tolerance))
+    }
+
+    ///  Impl Resistor : set_spice_model
+    ///  #  Remarks
+    ///
+    ///  Adds simulator fields to every KiCad symbol, pointing at the part's
+    ///  `.subckt` in `library`, the file written by `generate_spice_library`.
+    ///
+    pub fn set_spice_model(&mut self, model: spice::SpiceModel, library: &str) {
+        self.spice = Some((model, library.to_string()));
+    }
+
+    ///  Impl Resistor : set_3d_model_dir
+    ///  #  Remarks
+    ///
+    ///  Footprints reference the models written by `generate_3d_models` into
+    ///  `{dir}/Atlantix_Resistors.3dshapes` instead of the stock KiCad
+    ///  `Resistor_SMD.3dshapes`. `dir` may be a KiCad path variable.
+    ///
+    pub fn set_3d_model_dir(&mut self, dir: &str) {
+        self.model_dir = Some(dir.to_string());
+    }
+
+    ///  Impl Resistor : set_solder_processes
+    ///  #  Remarks
+    ///
+    ///  `generate_kicad_footprints` also writes the `_HandSoldering` or `_Wave`
+    ///  alternate of each footprint, and the symbols' footprint filters list
+    ///  them next to the reflow footprint.
+    ///
+    pub fn set_solder_processes(&mut self, processes: Vec<SolderProcess>) {
+        self.solder_processes = processes;
+    }
+
+    ///  Impl Resistor : generate_mpn
+    ///  #  Remarks
+    ///
+    ///  Manufacturer part number of the current value, e.g. CRCW06034K99FKEA
+    ///  for Vishay or RC0603FR-074K99L for Yageo. Empty when the manufacturer
+    ///  does not make the value in this size.
+    ///
+    pub fn generate_mpn(&self) -> String {
+        self.mpn_from(&self.manufacturer).unwrap_or_default()
+    }
+
+    /// Commercial or AEC-Q200 part number of the current value
+    fn mpn_from(&self, manufacturer: &Manufacturer) -> Option<String> {
+        memo::mpn(manufacturer, &self.case, self.value, self.tolerance, self.automotive, || {
+            if self.automotive {
+                manufacturer.aec_q200_mpn(&self.case, self.value, self.tolerance)
             } else {
-                "1R00".to_string()
+                manufacturer.resistor_mpn(&self.case, self.value, self.tolerance)
             }
-        }
+        })
+    }
+
+    ///  Impl Resistor : set_supplier_pn
+    ///  #  Remarks
+    ///
+    ///  Assigns the distributor part number of the current value to self.manuf.
+    ///  Parts without a Digikey part number scheme keep their MPN, which
+    ///  Digikey's search resolves.
+    ///
+    pub fn set_supplier_pn(&mut self) {
+        self.manuf = self.supplier_pn(self.suppliers()[0]);
+    }
+
+    /// Part number of the current value at a distributor, empty when the
+    /// distributor does not list it
+    fn supplier_pn(&self, distributor: Distributor) -> String {
+        let tolerance = self.tolerance;
+        let ranked;
+        let manufacturer = if self.alternates.is_empty() || sourcing::policy() == sourcing::SourcingPolicy::Listed {
+            &self.manufacturer
+        } else {
+            ranked = self.sources();
+            &ranked[0].0
+        };
+        let supplier_pn = if self.automotive {
+            manufacturer.aec_q200_distributor_pn(distributor, &self.case, self.value, tolerance)
+        } else {
+            manufacturer.distributor_pn(distributor, &self.case, self.value, tolerance)
+        };
+        supplier_pn.unwrap_or_default()
+    }
+
+    /// Primary and alternate manufacturers of the current value with their
+    /// MPNs, in the order of the [`sourcing`] policy. The first is the
+    /// primary source.
+    fn sources(&self) -> Vec<(Manufacturer, Option<String>)> {
+        let mut sources: Vec<(Manufacturer, Option<String>)> = std::iter::once(&self.manufacturer)
+            .chain(&self.alternates)
+            .map(|manufacturer| (manufacturer.clone(), self.mpn_from(manufacturer)))
+            .collect();
+        sourcing::rank(&mut sources, |(_, mpn)| mpn.as_deref());
+        sources
+    }
+
+    /// Datasheet of the primary source of the current value
+    fn datasheet_url(&self) -> Option<String> {
+        let (manufacturer, mpn) = self.sources().into_iter().next()?;
+        datasheet::url(manufacturer.name(), &mpn?)
     }
 
     ///  Impl Resistor : set_name
@@ -241,12 +554,13 @@ impl Resistor {
     ///  This is a helper function for set_full_name.
     ///
     /// ```
-    /// pub fn set_name(&mut self) -> String {
-    ///		"RES".to_string() + &self.case + &"_".to_string() + &self.value
-    ///	}
+    /// use component::Resistor;
+    ///
+    /// let mut resistor = Resistor::new(24, "0402".to_string());
+    /// assert_eq!(resistor.set_name(), "RES0402_1.00K");
     /// ```
     pub fn set_name(&mut self) -> String {
-        "RES".to_string() + &self.case + &"_".to_string() + &self.value
+        "RES".to_string() + &self.case + &"_".to_string() + &self.value.label()
     }
 
     ///  Impl Resistor : set_full_name
@@ -263,29 +577,47 @@ impl Resistor {
     ///  #  Remarks
     ///
     ///  Populates a string with all the part's information.
-    ///  Item, Description, Value, Case, Power, Supplier 1, Supplier Part Number 1, Library Path, Library Ref, Footprint Path, Footprint Ref, Company
+    ///  Part, Description, Value, Case, Power, Supplier 1, Supplier Part Number 1, Library Path, Library Ref, Footprint Path, Footprint Ref, Company, Comment, AEC-Q200, Datasheet
     /// 
     pub fn set_part(&mut self) -> String {
-        "RES".to_string()
-            + &self.case
-            + &"_".to_string()
-            + &self.value + &",".to_string()
-            + &"\"".to_string() + &"RES " + &self.case + &" ".to_string() +  &self.value + &"Ohm ".to_string() + &self.power + &"W\","
-            + &self.value
-            + &",".to_string()
-            + &self.case
-            + &",".to_string()
-            + &self.power
-            + &",".to_string()
-            + &"Digikey,".to_string()
-            + &self.manuf
-            + &",".to_string()
-            + &"Atlantix_R.SchLib,".to_string()
-            + &"Res1,".to_string()
-            + &"Atlantix_R.PcbLib,".to_string()
-            + &"RES".to_string() + &self.case + &",".to_string()
-            + &"Atlantix EDA, =Description".to_string()
-            + &"\r\n".to_string()
+        let mut row = String::new();
+        if let Some(outcome) = self.hook_outcome() {
+            self.push_csv_row(&mut row, &limits::limits("altium"), &outcome);
+        }
+        row
+    }
+
+    /// Append the Altium CSV row of the current value to `row`, letting bulk
+    /// writers reuse one buffer instead of allocating a String per part.
+    /// Generated fields are cut to the column `limits`, and those the part
+    /// hook changed are taken from its `outcome`.
+    pub(crate) fn push_csv_row(&self, row: &mut String, limits: &limits::Limits, outcome: &hook::Outcome) {
+        let ratings = memo::ratings(&self.case, self.tolerance, self.power);
+        let value = self.value.label();
+        let part = naming::part_name("RES", &self.case, self.value, self.tolerance_in_name.then_some(self.tolerance));
+        let description = format!("RES {} {}Ohm {}", package_naming::describe(&self.case), value, ratings.power);
+        let datasheet = self.datasheet_url().unwrap_or_default();
+        let suppliers = self.suppliers();
+        let other_suppliers: Vec<(String, String)> = suppliers[1..]
+            .iter()
+            .map(|&distributor| (distributor.to_string(), self.supplier_pn(distributor)))
+            .collect();
+        altium::Row {
+            part: &part,
+            description: &description,
+            value: &value,
+            case: &self.case,
+            power: &ratings.fraction,
+            supplier: &suppliers[0].to_string(),
+            supplier_pn: &self.manuf,
+            footprint_ref: &format!("RES{}", package_naming::label(&self.case)),
+            aec_q200: self.automotive,
+            datasheet: &datasheet,
+            other_suppliers: &other_suppliers,
+        }
+        .edited(outcome)
+        .push_to(row, limits);
+        row.push_str("\r\n");
     }
 
     ///  Impl Resistor : function set_full_part_name
@@ -304,123 +636,309 @@ impl Resistor {
     ///  Impl Resistor : function generate
     ///  # Remarks
     ///
-    ///  Assigns the full name of the component, as would be
-    ///  preferred to see in a resistor library, such as
-    ///  'R0402_1.00K' or 'R0805_4.99K' for ease of browsing
-    ///  in tools such as Altium.
+    ///  Appends the Altium CSV rows of one decade to the rows generated so
+    ///  far and returns all of them. Every call copies the whole library,
+    ///  so large libraries are better written with [`Resistor::write_parts`]
+    ///  or walked with [`Resistor::iter_parts`].
+    ///
+    pub fn generate(&mut self, decade: f64) -> String {
+        let limits = limits::limits("altium");
+        let mut rows = std::mem::take(&mut self.full_series);
+        for index in 0..self.series_array.len() {
+            self.update_value_for_decade(index, decade);
+            let Some(outcome) = self.available_outcome() else {
+                continue;
+            };
+            self.set_supplier_pn();
+            self.push_csv_row(&mut rows, &limits, &outcome);
+        }
+        self.full_series = rows;
+        self.full_series.clone()
+    }
+
+    /// Stream the Altium CSV rows of one decade to `out`, one row buffer
+    /// reused for every part. Wrap files in a `BufWriter`.
     ///
+    /// ```
+    /// use component::Resistor;
     ///
-    pub fn generate(&mut self, decade: u32) -> String {
-        for index in 0..self.series {
-            match decade {
-                1 => {
-                    self.value = format!("{:.2}", self.series_array[index]);
-                    self.set_digikey_pn(index, decade)
-                }
-                10 => {
-                    self.value = format!("{:2.1}", (decade as f64) * self.series_array[index]);
-                    self.set_digikey_pn(index, decade)
-                }
-                100 => {
-                    self.value = format!("{:3.0}", (decade as f64) * self.series_array[index]);
-                    self.set_digikey_pn(index, decade)
-                }
-                1000 => {
-                    self.value = format!("{:.2}", self.series_array[index]) + &"K".to_string();
-                    self.set_digikey_pn(index, decade)
-                }
-                10000 => {
-                    self.value = format!("{:2.1}", (10 as f64) * self.series_array[index])
-                        + &"K".to_string();
-                    self.set_digikey_pn(index, decade)
-                }
-                100000 => {
-                    self.value = format!("{:3.0}", (100 as f64) * self.series_array[index])
-                        + &"K".to_string();
-                    self.set_digikey_pn(index, decade)
+    /// let mut resistor = Resistor::new(24, "0603".to_string());
+    /// let mut csv = Vec::new();
+    /// resistor.write_parts(1_000.0, &mut csv).unwrap();
+    /// let csv = String::from_utf8(csv).unwrap();
+    /// assert_eq!(csv.lines().count(), 24);
+    /// assert!(csv.starts_with("RES0603_1.00K,"));
+    /// ```
+    pub fn write_parts<W: std::io::Write>(&mut self, decade: f64, out: &mut W) -> std::io::Result<()> {
+        let limits = limits::limits("altium");
+        let mut row = String::new();
+        for index in 0..self.series_array.len() {
+            self.update_value_for_decade(index, decade);
+            let Some(outcome) = self.available_outcome() else {
+                continue;
+            };
+            self.set_supplier_pn();
+            row.clear();
+            self.push_csv_row(&mut row, &limits, &outcome);
+            out.write_all(row.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// The parts of `decades` one at a time, skipping values the
+    /// manufacturer does not make. Nothing is built up front.
+    ///
+    /// ```
+    /// use component::{Resistance, Resistor};
+    ///
+    /// let mut resistor = Resistor::new(96, "0603".to_string());
+    /// let mut parts = resistor.iter_parts(&[1_000.0, 10_000.0]);
+    /// let first = parts.next().unwrap();
+    /// assert_eq!(first.name, "R0603_1.00K");
+    /// assert_eq!(first.value, Resistance::from_ohms(1_000.0));
+    /// assert_eq!(first.mpn, "CRCW06031K00FKEA");
+    /// assert_eq!(parts.count(), 191);
+    /// ```
+    pub fn iter_parts<'a>(&'a mut self, decades: &'a [f64]) -> Parts<'a> {
+        Parts { resistor: self, decades, decade: 0, index: 0 }
+    }
+
+    /// Names of the parts of `decades` the part hook drops, none without a
+    /// hook, see [`hook`]
+    pub fn dropped_parts(&mut self, decades: &[f64]) -> Vec<String> {
+        let mut dropped = Vec::new();
+        for &decade in decades {
+            for index in 0..self.series_array.len() {
+                self.update_value_for_decade(index, decade);
+                if self.is_generated() && self.hook_outcome().is_none() {
+                    dropped.push(self.part_name());
                 }
-                _ => (),
             }
+        }
+        dropped
+    }
 
-            self.set_full_name();
-            self.set_full_part_name();
-            self.full_series += &self.full_part_name;
+    /// The part of the current value, with the edits of the part hook's
+    /// `outcome`
+    fn current_part(&mut self, outcome: hook::Outcome) -> ResistorPart {
+        self.manuf = self.supplier_pn(self.suppliers()[0]);
+        let (manufacturer, mpn) = self.sources().remove(0);
+        let mut part = ResistorPart {
+            name: self.part_name(),
+            value: self.value,
+            package: self.case.clone(),
+            tolerance: self.tolerance,
+            power: self.power,
+            manufacturer: manufacturer.to_string(),
+            mpn: mpn.unwrap_or_default(),
+            distributor: self.suppliers()[0],
+            supplier_pn: self.manuf.clone(),
+        };
+        let edit = |field: &mut String, changed: Option<&str>| {
+            if let Some(changed) = changed {
+                *field = changed.to_string();
+            }
+        };
+        edit(&mut part.name, outcome.changed(|symbol| &symbol.name));
+        edit(&mut part.manufacturer, outcome.changed(|symbol| &symbol.manufacturer));
+        edit(&mut part.mpn, outcome.changed(|symbol| &symbol.mpn));
+        edit(&mut part.supplier_pn, outcome.changed(|symbol| &symbol.supplier_pn));
+        if let Some(distributor) = outcome.changed(|symbol| &symbol.supplier).and_then(|supplier| supplier.parse().ok()) {
+            part.distributor = distributor;
         }
-        let alpha = &self.full_series;
-        return alpha.to_string();
+        part
     }
 
     /// Generate KiCad symbol library file
-    pub fn generate_kicad_symbols(&mut self, decades: Vec<u32>, output_path: &str, symbol_style: &str) -> Result<(), std::io::Error> {
+    pub fn generate_kicad_symbols(&mut self, decades: Vec<f64>, output_path: &str, symbol_style: &str) -> Result<(), AtlantixError> {
+        let lib_content = self.kicad_symbol_library(&decades, symbol_style).generate_library();
+        crate::atomic::write(output_path, lib_content)?;
+        Ok(())
+    }
+
+    /// Write a SPICE `.lib` with one `.subckt` per value, named like the symbols
+    pub fn generate_spice_library(&mut self, decades: &[f64], output_path: &str) -> Result<(), AtlantixError> {
+        let model = self.spice.as_ref().map(|(model, _)| *model).unwrap_or_default();
+        let mut subckts = Vec::new();
+        for &decade in decades {
+            for index in 0..self.series_array.len() {
+                self.update_value_for_decade(index, decade);
+                if !self.is_available() {
+                    continue;
+                }
+                subckts.push(spice::subckt(&self.part_name(), self.value, &self.case, model));
+            }
+        }
+        let title = format!("Atlantix EDA {} resistors, {} model", self.case, model);
+        crate::atomic::write(output_path, spice::library(&title, &subckts))
+    }
+
+    /// Build the KiCad symbols for every value in the given decades
+    pub(crate) fn kicad_symbol_library(&mut self, decades: &[f64], symbol_style: &str) -> KicadSymbolLib {
         let mut symbol_lib = KicadSymbolLib::new();
         
-        for decade in decades {
-            for index in 0..self.series {
+        for &decade in decades {
+            for index in 0..self.series_array.len() {
                 self.update_value_for_decade(index, decade);
-                
-                // Use same naming convention as Altium: R0603_1.33K
-                let symbol_name = format!("R{}_{}", self.case, self.value);
-                
-                // Use same detailed description as Altium: "RES SMT 1.18Kohms, 0603, 1%, 1/8W"
-                let tolerance = self.get_tolerance_from_series(self.series);
-                let power_rating = self.get_power_rating_from_package(&self.case);
-                let description = format!("RES SMT {}ohms, {}, {}, {}", 
-                    self.format_resistance_for_description(&self.value),
-                    self.case, 
-                    tolerance,
-                    power_rating
-                );
-                
-                let footprint_name = format!("Atlantix_Resistors:R_{}_{}", 
-                    self.get_imperial_name(&self.case),
-                    self.get_metric_name(&self.case)
-                );
-                
-                // Generate Vishay manufacturer information
-                let vishay_mpn = self.generate_vishay_mpn();
-                self.set_digikey_pn(index, decade);
-                let digikey_pn = self.manuf.clone();
-                
-                let manufacturer = "Vishay".to_string();
-                let supplier = "Digikey".to_string();
-                let supplier_url = format!("https://www.digikey.com/products/en?keywords={}", digikey_pn);
-                
-                let mut symbol = KicadSymbol::new(symbol_name, self.value.clone(), footprint_name, symbol_style)
-                    .with_manufacturer_info(manufacturer, vishay_mpn, supplier, digikey_pn, supplier_url);
-                symbol.description = description;
-                symbol_lib.add_symbol(symbol);
+                if !self.is_generated() {
+                    continue;
+                }
+                self.set_supplier_pn();
+                // The hook decides on the symbol built here, no second one,
+                // and only parts it keeps get an IPN
+                if let Some(mut symbol) = hook::apply(self.kicad_symbol(symbol_style)) {
+                    ipn::assign_symbol("resistor", &self.case, &mut symbol);
+                    symbol_lib.add_symbol(symbol);
+                }
             }
         }
+        symbol_lib
+    }
+
+    /// The KiCad symbol of the current value, before the part hook
+    fn kicad_symbol(&self, symbol_style: &str) -> KicadSymbol {
+        // Use same naming convention as Altium: R0603_1.33K
+        let value = self.value.label();
+        let symbol_name = self.part_name();
         
-        let lib_content = symbol_lib.generate_library();
-        fs::write(output_path, lib_content)?;
-        Ok(())
+        // Use same detailed description as Altium: "RES SMT 1.18Kohms, 0603, 1%, 1/8W"
+        let tolerance = self.tolerance;
+        let ratings = memo::ratings(&self.case, tolerance, self.power);
+        let description = format!("RES SMT {}ohms, {}", 
+            self.format_resistance_for_description(&value),
+            ratings.description
+        );
+        
+        // The stock library only has nominal land patterns
+        let (footprint_library, density) = match self.footprint_naming {
+            FootprintNaming::Atlantix => ("Atlantix_Resistors", kicad_footprint::density().suffix()),
+            FootprintNaming::KicadOfficial => ("Resistor_SMD", ""),
+        };
+        let (first, second) = match (self.footprint_naming, get_package_specs(&self.case)) {
+            (FootprintNaming::Atlantix, Some(spec)) => package_naming::footprint_codes(&spec),
+            _ => (self.get_imperial_name(&self.case).to_string(), self.get_metric_name(&self.case)),
+        };
+        let footprint_name = format!("{}:R_{}_{}{}", footprint_library, first, second, density);
+        
+        let mut sources = self.sources();
+        let (primary, mpn) = sources.remove(0);
+        let mpn = mpn.unwrap_or_default();
+        let suppliers = self.suppliers();
+        let supplier_pn = self.supplier_pn(suppliers[0]);
+        
+        let manufacturer = primary.to_string();
+        let supplier = suppliers[0].to_string();
+        let supplier_url = suppliers[0].supplier_url(&supplier_pn);
+        
+        let base_footprint = footprint_name.split(':').nth(1).unwrap_or_default().to_string();
+        let datasheet = datasheet::field(&manufacturer, &mpn);
+        let ratings = thermal::thermal(&self.case, &mpn);
+        let mut symbol = KicadSymbol::new(symbol_name, value, footprint_name, symbol_style)
+            .with_tolerance(tolerance)
+            .with_manufacturer_info(manufacturer, mpn, supplier, supplier_pn, supplier_url);
+        symbol.datasheet = datasheet;
+        if self.footprint_naming == FootprintNaming::Atlantix && !self.solder_processes.is_empty() {
+            let alternates = self.solder_processes.iter().map(|p| format!("{}{}", base_footprint, p.suffix()));
+            symbol.fp_filters = std::iter::once(base_footprint.clone()).chain(alternates).collect::<Vec<_>>().join(" ");
+        }
+        let alternates = sources.into_iter().filter_map(|(alternate, mpn)| Some((alternate, mpn?)));
+        for (number, (alternate, mpn)) in (2..).zip(alternates) {
+            symbol.properties.push((format!("Manufacturer {}", number), alternate.to_string()));
+            symbol.properties.push((format!("MPN {}", number), mpn));
+        }
+        for (number, &distributor) in (2..).zip(&suppliers[1..]) {
+            let supplier_pn = self.supplier_pn(distributor);
+            symbol.properties.push((format!("Supplier {}", number), distributor.to_string()));
+            symbol.properties.push((format!("SupplierPN {}", number), supplier_pn.clone()));
+            symbol.properties.push((format!("SupplierURL {}", number), distributor.supplier_url(&supplier_pn)));
+        }
+        // Color bands for MELF and axial bodies and EIA-96 codes for small
+        // chips, for lab identification
+        if let Some(marking) = marking::marking(&self.case, self.value, tolerance) {
+            symbol.properties.push(("Marking".to_string(), marking));
+        }
+        if self.automotive {
+            symbol.properties.push(("AEC-Q200".to_string(), "yes".to_string()));
+        }
+        if let Some(ratings) = &ratings {
+            symbol.properties.extend(thermal::symbol_fields(ratings));
+        }
+        if let Some((_, library)) = &self.spice {
+            let fields = spice::symbol_fields(symbol.format, library, &symbol.name);
+            symbol.properties.extend(fields);
+        }
+        symbol.description = description;
+        symbol.status = self.status;
+        symbol
+    }
+
+    /// What the part hook makes of the current value, `None` when it drops it.
+    /// With a hook this builds the part's symbol, so callers take it once per
+    /// part and pass it on.
+    fn hook_outcome(&self) -> Option<hook::Outcome> {
+        hook::outcome(|| self.kicad_symbol(&kicad_symbol::ResistorStyles::default().default))
     }
 
-    /// Generate KiCad footprint files
-    pub fn generate_kicad_footprints(&self, packages: Vec<&str>, output_dir: &str) -> Result<(), std::io::Error> {
-        fs::create_dir_all(output_dir)?;
+    /// The hook's outcome for the current value, `None` when it is not
+    /// generated or the hook drops it
+    pub(crate) fn available_outcome(&self) -> Option<hook::Outcome> {
+        if !self.is_generated() {
+            return None;
+        }
+        self.hook_outcome()
+    }
+
+    /// Generate KiCad footprint files, a no-op when referencing the official KiCad footprints
+    pub fn generate_kicad_footprints(&self, packages: Vec<&str>, output_dir: &str) -> Result<(), AtlantixError> {
+        if self.footprint_naming == FootprintNaming::KicadOfficial {
+            return Ok(());
+        }
+        crate::atomic::create_dir_all(output_dir)?;
         
         for package in packages {
-            if let Some(footprint) = KicadFootprint::new_smd_resistor(package) {
-                let filename = format!("{}/{}.kicad_mod", output_dir, footprint.name);
-                let footprint_content = footprint.generate_footprint();
-                fs::write(filename, footprint_content)?;
+            if let Some(mut footprint) = KicadFootprint::new_smd_resistor(package) {
+                if let Some(dir) = &self.model_dir {
+                    footprint.model_dir = dir.clone();
+                    footprint.model_library = model3d::RESISTOR_MODEL_LIBRARY.to_string();
+                }
+                let alternates = self.solder_processes.iter().map(|&process| footprint.for_process(process));
+                for footprint in std::iter::once(footprint.clone()).chain(alternates) {
+                    let filename = format!("{}/{}.kicad_mod", output_dir, footprint.name);
+                    crate::atomic::write(filename, footprint.generate_footprint())?;
+                }
             }
         }
         Ok(())
     }
 
-    fn update_value_for_decade(&mut self, index: usize, decade: u32) {
-        match decade {
-            1 => self.value = format!("{:.2}", self.series_array[index]),
-            10 => self.value = format!("{:2.1}", (decade as f64) * self.series_array[index]),
-            100 => self.value = format!("{:3.0}", (decade as f64) * self.series_array[index]),
-            1000 => self.value = format!("{:.2}K", self.series_array[index]),
-            10000 => self.value = format!("{:2.1}K", (10 as f64) * self.series_array[index]),
-            100000 => self.value = format!("{:3.0}K", (100 as f64) * self.series_array[index]),
-            _ => (),
+    /// Generate a WRL and a STEP model per package into `output_dir`, named
+    /// like the footprints
+    pub fn generate_3d_models(&self, packages: Vec<&str>, output_dir: &str) -> Result<(), AtlantixError> {
+        crate::atomic::create_dir_all(output_dir)?;
+        for package in packages {
+            if let (Some(footprint), Some(spec)) = (KicadFootprint::new_smd_resistor(package), get_package_specs(package)) {
+                model3d::ChipModel::new(&footprint.model_name, &spec).write(std::path::Path::new(output_dir))?;
+            }
         }
+        Ok(())
+    }
+
+    fn update_value_for_decade(&mut self, index: usize, decade: f64) {
+        self.value = Resistance::from_ohms(decade * self.series_array[index]);
+    }
+
+    /// Whether the current value is generated and the part hook keeps it
+    pub(crate) fn is_available(&self) -> bool {
+        self.is_generated() && self.hook_outcome().is_some()
+    }
+
+    /// Whether the current value is in the value range and the primary
+    /// manufacturer makes it, according to the installed [`availability`]
+    /// matrix
+    fn is_generated(&self) -> bool {
+        is_supported(self.value)
+            && self.value_range.contains(self.value)
+            && availability::available(self.manufacturer.name(), &self.case, self.tolerance, self.value)
     }
 
     fn get_imperial_name<'a>(&self, package: &'a str) -> &'a str {
@@ -437,8 +955,8 @@ impl Resistor {
         }
     }
 
-    fn get_metric_name(&self, package: &str) -> &'static str {
-        match package {
+    fn get_metric_name(&self, package: &str) -> String {
+        let metric = match package {
             "0201" => "0603Metric",
             "0402" => "1005Metric",
             "0603" => "1608Metric", 
@@ -447,8 +965,10 @@ impl Resistor {
             "1210" => "3225Metric",
             "2010" => "5025Metric",
             "2512" => "6332Metric",
-            _ => "UnknownMetric",
-        }
+            // Imported packages carry their own metric name
+            _ => return get_package_specs(package).map_or_else(|| "UnknownMetric".to_string(), |spec| spec.metric),
+        };
+        metric.to_string()
     }
 
     fn format_resistance_for_description(&self, value: &str) -> String {
@@ -460,32 +980,373 @@ impl Resistor {
             value.to_string()
         }
     }
+}
+
+/// One part of a resistor library, see [`Resistor::iter_parts`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResistorPart {
+    /// `R0603_4.99K`
+    pub name: String,
+    pub value: Resistance,
+    pub package: String,
+    pub tolerance: &'static str,
+    pub power: Power,
+    /// Primary source under the sourcing policy
+    pub manufacturer: String,
+    pub mpn: String,
+    /// Supplier 1
+    pub distributor: Distributor,
+    pub supplier_pn: String,
+}
+
+/// Iterator over the parts of a [`Resistor`], returned by [`Resistor::iter_parts`]
+pub struct Parts<'a> {
+    resistor: &'a mut Resistor,
+    decades: &'a [f64],
+    decade: usize,
+    index: usize,
+}
 
-    fn get_tolerance_from_series(&self, series: usize) -> &'static str {
-        match series {
-            192 => "0.5%",  // E192 series
-            96 => "1%",     // E96 series  
-            48 => "2%",     // E48 series
-            24 => "5%",     // E24 series
-            12 => "10%",    // E12 series
-            6 => "20%",     // E6 series
-            3 => "50%",     // E3 series (rarely used)
-            _ => "1%",      // Default to 1% for unknown series
+impl Iterator for Parts<'_> {
+    type Item = ResistorPart;
+
+    fn next(&mut self) -> Option<ResistorPart> {
+        let count = self.resistor.series_array.len();
+        while let Some(&decade) = self.decades.get(self.decade).filter(|_| count > 0) {
+            let index = self.index;
+            self.index += 1;
+            if self.index == count {
+                self.index = 0;
+                self.decade += 1;
+            }
+            self.resistor.update_value_for_decade(index, decade);
+            if let Some(outcome) = self.resistor.available_outcome() {
+                return Some(self.resistor.current_part(outcome));
+            }
         }
+        None
     }
+}
 
-    fn get_power_rating_from_package(&self, package: &str) -> &'static str {
-        match package {
-            "0201" => "1/20W",
-            "0402" => "1/16W", 
-            "0603" => "1/10W",
-            "0805" => "1/8W",
-            "1206" => "1/4W",
-            "1210" => "1/2W",
-            "1218" => "1W",
-            "2010" => "3/4W",
-            "2512" => "1W",
-            _ => "1/10W",   // Default
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    const SERIES: [usize; 7] = [3, 6, 12, 24, 48, 96, 192];
+
+    #[test]
+    fn values_are_width_free_in_every_decade_and_series() {
+        for series in SERIES {
+            let resistor = Resistor::new(series, "0603".to_string());
+            for decade in EXTENDED_DECADES {
+                for base in &resistor.series_array {
+                    let ohms = base * decade;
+                    let formatted = Resistance::from_ohms(ohms).to_string();
+                    assert!(
+                        !formatted.contains(char::is_whitespace),
+                        "E{} {} formatted as {:?}",
+                        series,
+                        ohms,
+                        formatted
+                    );
+                    let parsed: Resistance = formatted.parse().unwrap();
+                    assert!(
+                        (parsed.ohms() - ohms).abs() / ohms < 0.005,
+                        "E{} {} round-tripped to {}",
+                        series,
+                        ohms,
+                        parsed.ohms()
+                    );
+                }
+            }
         }
     }
+
+    #[test]
+    fn canonical_form_per_decade() {
+        let expected = [
+            (0.00976, "9.76m"),
+            (0.0976, "97.6m"),
+            (0.976, "0R976"),
+            (9.76, "9.76"),
+            (97.6, "97.6"),
+            (976.0, "976"),
+            (9_760.0, "9.76K"),
+            (97_600.0, "97.6K"),
+            (976_000.0, "976K"),
+            (9_760_000.0, "9.76M"),
+        ];
+        for (ohms, formatted) in expected {
+            assert_eq!(format_value(ohms), formatted);
+        }
+    }
+
+    #[test]
+    fn unnormalized_base_values_do_not_pad_names() {
+        // `{:3.0}` used to render 100 x 0.976 as " 98"
+        let mut resistor = Resistor::with_values(96, "0603".to_string(), vec![0.976]);
+        let row = resistor.generate(100.0);
+        assert!(row.starts_with("RES0603_97.6,"), "{}", row);
+    }
+
+    #[test]
+    fn extended_range_closes_at_ten_megohm() {
+        let mut resistor = Resistor::new(96, "0603".to_string());
+        let values: Vec<Resistance> = resistor.iter_parts(&EXTENDED_DECADES).map(|part| part.value).collect();
+        assert_eq!(values.len(), 96 * 10 + 1);
+        assert_eq!(values.last(), Some(&Resistance::from_ohms(10_000_000.0)));
+    }
+
+    #[test]
+    fn value_text_clears_the_symbol_body() {
+        let value_at = |value: f64, style: &str| {
+            let mut resistor = Resistor::with_values(96, "0603".to_string(), vec![value]);
+            let library = resistor.kicad_symbol_library(&[1_000.0], style).generate_library();
+            let line = library.lines().find(|line| line.contains("(property \"Value\"")).unwrap().to_string();
+            line[line.find("(at ").unwrap()..].split(' ').nth(1).unwrap().to_string()
+        };
+        // Short values fit inside the European rectangle
+        assert_eq!(value_at(9.76, "european"), "0");
+        // The American zigzag runs through the middle
+        assert_eq!(value_at(9.76, "american"), "-1.651");
+        assert_eq!(value_at(976.0, "american"), "-1.651");
+    }
+
+    #[test]
+    fn vishay_part_numbers_follow_the_series_tolerance() {
+        // E192 is 0.5%, which CRCW makes as the D grade
+        let mut resistor = Resistor::with_values(192, "0603".to_string(), vec![1.02]);
+        let library = resistor.kicad_symbol_library(&[1_000.0], "european").generate_library();
+        assert!(library.contains("CRCW06031K02DKEA"), "{}", library);
+        assert!(!library.contains("FKEA"));
+
+        // CRCW is not made in 2%, E48 gets no Vishay part rather than a 1% one
+        let mut resistor = Resistor::with_values(48, "0603".to_string(), vec![1.05]);
+        let library = resistor.kicad_symbol_library(&[1_000.0], "european").generate_library();
+        assert!(!library.contains("CRCW"), "{}", library);
+    }
+
+    #[test]
+    fn symbol_grammar_follows_the_kicad_version() {
+        use crate::kicad_symbol::KicadFormatVersion;
+
+        let library = |format| {
+            let mut resistor = Resistor::with_values(96, "0603".to_string(), vec![1.0]);
+            let mut library = resistor.kicad_symbol_library(&[1_000.0], "european");
+            library.format = format;
+            library.generate_library()
+        };
+        let v6 = library(KicadFormatVersion::V6);
+        assert!(v6.starts_with("(kicad_symbol_lib (version 20211014)"));
+        assert!(v6.contains("(property \"Reference\" \"R\" (id 0)"));
+        assert!(v6.contains("(property \"Datasheet\" \"https://www.vishay.com/docs/20035/dcrcwe3.pdf\" (id 3)"));
+
+        let v7 = library(KicadFormatVersion::V7);
+        assert!(v7.contains("(version 20220914)") && !v7.contains("(id "));
+
+        let v8 = library(KicadFormatVersion::V8);
+        assert!(v8.contains("(version 20231120) (generator \"atlantix-eda\")"));
+        assert!(v8.contains("(pin_numbers (hide yes))") && v8.contains("(exclude_from_sim no)"));
+        assert!(v8.contains("(property \"Description\"") && !v8.contains("ki_description"));
+        assert!(!v8.contains(")) hide)"));
+    }
+
+    #[test]
+    fn every_symbol_has_its_spice_subckt() {
+        let path = std::env::temp_dir().join(format!("atlantix-spice-test-{}.lib", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        let mut resistor = Resistor::new(24, "0603".to_string());
+        resistor.set_spice_model(spice::SpiceModel::Parasitic, &path);
+        let symbols = resistor.kicad_symbol_library(&STANDARD_DECADES, "european");
+        resistor.generate_spice_library(&STANDARD_DECADES, &path).unwrap();
+        let lib = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(lib.matches(".subckt ").count(), symbols.symbols.len());
+        for symbol in &symbols.symbols {
+            let field = |key: &str| symbol.properties.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+            let name = field("Spice_Model").or(field("Sim.Name")).unwrap();
+            assert!(lib.contains(&format!(".subckt {} 1 2\n", name)), "{}", name);
+        }
+        assert!(lib.contains(".subckt R0603_1.00K 1 2\nR1 1 3 1000\nL1 3 2 0.5n\n"));
+    }
+
+    #[test]
+    fn footprints_match_golden_files() {
+        let golden = [
+            ("0402", include_str!("testdata/footprints/R_0402_1005Metric.kicad_mod")),
+            ("0603", include_str!("testdata/footprints/R_0603_1608Metric.kicad_mod")),
+            ("0805", include_str!("testdata/footprints/R_0805_2012Metric.kicad_mod")),
+            ("1206", include_str!("testdata/footprints/R_1206_3216Metric.kicad_mod")),
+        ];
+        for (package, expected) in golden {
+            let footprint = kicad_footprint::KicadFootprint::new_smd_resistor(package).unwrap();
+            let generated = footprint.generate_footprint();
+            let tedit = generated.find("(tedit ").unwrap();
+            let end = tedit + generated[tedit..].find(')').unwrap();
+            let generated = format!("{}(tedit 0{}", &generated[..tedit], &generated[end..]);
+            assert_eq!(generated, expected, "{}", package);
+
+            // KiCad library conventions: line widths, courtyard on the 0.01 mm
+            // grid and silkscreen kept clear of the pads
+            for line in generated.lines().filter(|l| l.contains("fp_line")) {
+                let width = if line.contains("F.SilkS") { "0.12" } else if line.contains("F.CrtYd") { "0.05" } else { "0.1" };
+                assert!(line.ends_with(&format!("(width {}))", width)), "{}", line);
+            }
+            for line in generated.lines().filter(|l| l.contains("F.SilkS") && l.contains("fp_line")) {
+                let end = &line[line.find("(end ").unwrap() + 5..];
+                let x: f64 = end.split(' ').next().unwrap().parse().unwrap();
+                let inner_edge = footprint.pads[1].at_x - footprint.pads[1].size_x / 2.0;
+                assert!(x < inner_edge, "{}: silkscreen over pad: {}", package, line);
+            }
+        }
+    }
+
+    #[test]
+    fn footprints_reference_generated_models() {
+        let root = std::env::temp_dir().join(format!("atlantix-model-test-{}", std::process::id()));
+        let models = root.join("Atlantix_Resistors.3dshapes");
+        let footprints = root.join("Atlantix_Resistors.pretty");
+        let mut resistor = Resistor::new(24, "0603".to_string());
+        resistor.set_3d_model_dir(&root.to_string_lossy());
+        resistor.generate_3d_models(vec!["0603"], &models.to_string_lossy()).unwrap();
+        resistor.generate_kicad_footprints(vec!["0603"], &footprints.to_string_lossy()).unwrap();
+        let footprint = fs::read_to_string(footprints.join("R_0603_1608Metric.kicad_mod")).unwrap();
+        let wrl_exists = models.join("R_0603_1608Metric.wrl").exists();
+        let step_exists = models.join("R_0603_1608Metric.step").exists();
+        let _ = fs::remove_dir_all(&root);
+
+        let model = format!("(model {}/Atlantix_Resistors.3dshapes/R_0603_1608Metric.wrl", root.display());
+        assert!(footprint.contains(&model), "{}", footprint);
+        assert!(wrl_exists && step_exists);
+    }
+
+    #[test]
+    fn symbols_filter_for_their_solder_alternates() {
+        let dir = std::env::temp_dir().join(format!("atlantix-solder-test-{}", std::process::id()));
+        let mut resistor = Resistor::new(24, "0805".to_string());
+        resistor.set_solder_processes(vec![SolderProcess::HandSoldering, SolderProcess::Wave]);
+        resistor.generate_kicad_footprints(vec!["0805"], &dir.to_string_lossy()).unwrap();
+        let mut written: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        written.sort();
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(
+            written,
+            ["R_0805_2012Metric.kicad_mod", "R_0805_2012Metric_HandSoldering.kicad_mod", "R_0805_2012Metric_Wave.kicad_mod"]
+        );
+
+        let symbols = resistor.kicad_symbol_library(&STANDARD_DECADES, "european");
+        assert_eq!(
+            symbols.symbols[0].fp_filters,
+            "R_0805_2012Metric R_0805_2012Metric_HandSoldering R_0805_2012Metric_Wave"
+        );
+    }
+
+    #[test]
+    fn european_names_parse_back_to_their_value() {
+        for series in SERIES {
+            let resistor = Resistor::new(series, "0603".to_string());
+            for decade in EXTENDED_DECADES {
+                for base in &resistor.series_array {
+                    let resistance = Resistance::from_ohms(base * decade);
+                    let name = resistance.format(ValueNotation::European);
+                    assert!(!name.contains('.'), "{}", name);
+                    assert_eq!(name.parse::<Resistance>().unwrap(), resistance, "{}", name);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn series_values_are_exact_micro_ohms() {
+        for series in SERIES {
+            let resistor = Resistor::new(series, "0603".to_string());
+            for decade in EXTENDED_DECADES {
+                for base in &resistor.series_array {
+                    let resistance = Resistance::from_ohms(base * decade);
+                    let centi_base = (base * 100.0).round() as u64;
+                    let expected = centi_base * (decade * 1_000.0).round() as u64 * 10;
+                    assert_eq!(resistance.micro_ohms(), expected, "E{} {} x {}", series, base, decade);
+                    assert_eq!(resistance.to_string(), format_value(base * decade));
+                    assert_eq!(resistance.vishay_code(), vishay_value_code(base * decade));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn altium_rows_have_the_header_columns() {
+        // Fields split on commas outside the quoted Description
+        fn fields(line: &str) -> Vec<String> {
+            let mut fields = vec![String::new()];
+            let mut quoted = false;
+            for c in line.trim_end().chars() {
+                match c {
+                    '"' => quoted = !quoted,
+                    ',' if !quoted => fields.push(String::new()),
+                    _ => fields.last_mut().unwrap().push(c),
+                }
+            }
+            fields
+        }
+        let mut resistor = Resistor::new(24, "0603".to_string());
+        resistor.set_automotive(true);
+        let mut csv = Vec::new();
+        altium::write_csv(&mut resistor, &[1_000.0], &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let mut lines = csv.lines();
+        let header = fields(lines.next().unwrap());
+        let column = |name: &str| header.iter().position(|h| h == name).unwrap();
+        for line in lines {
+            let row = fields(line);
+            assert_eq!(row.len(), header.len(), "{}", line);
+            assert_eq!(row[column("Company")], "Atlantix EDA");
+            assert_eq!(row[column("Comment")], "=Value");
+            assert_eq!(row[column("AEC-Q200")], "yes");
+            assert!(row[column("Description")].starts_with("RES "), "{}", line);
+        }
+    }
+
+    #[test]
+    fn pipelines_name_parts_by_their_own_templates() {
+        let config = |template: &str| ecs::resources::GeneratorConfig {
+            value_range: ValueRange::parse(Some("1K"), Some("1K")).unwrap(),
+            settings: settings::Settings {
+                part_template: Some(template.parse::<naming::NameTemplate>().unwrap().for_parts().unwrap()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        // Both at once, as in the GUI next to a server or another test
+        let threads = ["RES-{package}-{code}", "{prefix}_{value}_{package}"]
+            .map(|template| std::thread::spawn(move || preview::generate(&config(template), 24, &["0603"])[0].part_number.clone()));
+        let names = threads.map(|thread| thread.join().unwrap());
+        assert_eq!(names, ["RES-0603-1K00", "R_1.00K_0603"]);
+        assert_eq!(resistor_name("0603", Resistance::from_ohms(1000.0), None), "R0603_1.00K");
+    }
+
+    #[test]
+    fn memoized_outputs_match_formatted_ones() {
+        let outputs = |memoize| {
+            crate::settings::Settings { memoize, ..Default::default() }.scope(|| {
+                let mut resistor = Resistor::new(96, "0805".to_string());
+                resistor.set_alternate_manufacturers(vec![Manufacturer::vishay(), "yageo".parse().unwrap()]);
+                let mut outputs = Vec::new();
+                for distributor in [Distributor::Digikey, Distributor::Mouser] {
+                    resistor.set_distributor(distributor);
+                    let mut csv = Vec::new();
+                    altium::write_csv(&mut resistor, &STANDARD_DECADES, &mut csv).unwrap();
+                    outputs.push(csv);
+                }
+                outputs.push(resistor.kicad_symbol_library(&STANDARD_DECADES, "european").generate_library().into_bytes());
+                outputs
+            })
+        };
+        let memoized = outputs(true);
+        assert_eq!(memoized, outputs(false));
+    }
 }
@@ -0,0 +1,141 @@
+//! Field length limits of length-limited targets.
+//!
+//! Altium's database libraries and many PLM systems cap text fields, most
+//! at 255 characters, and reject or silently cut longer values such as a
+//! long supplier URL. Exporters look up their [`limits`] once and pass each
+//! field through [`Limits::fit`], which shortens it to the limit of its
+//! column on a character boundary, never inside a UTF-8 sequence, and
+//! records the cut for [`take_truncations`]. Limits are counted in
+//! characters and set per exporter and column in the `limits` of the
+//! current [`crate::settings`].
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Appended to shortened values, within the limit
+const MARKER: &str = "...";
+
+/// Limits of one exporter's columns
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Limits {
+    /// Limit of columns without their own, `None` for unlimited
+    pub default: Option<usize>,
+    /// Per-column limits by column name, 0 for unlimited
+    pub columns: HashMap<String, usize>,
+}
+
+impl Limits {
+    /// Limit of a column, `None` for unlimited
+    pub fn of(&self, column: &str) -> Option<usize> {
+        match self.columns.get(column) {
+            Some(0) => None,
+            Some(&limit) => Some(limit),
+            None => self.default,
+        }
+    }
+
+    /// `value` within the limit of `column`, the cut recorded under
+    /// `exporter` and `part` when it had to be shortened
+    pub fn fit<'a>(&self, exporter: &str, column: &str, part: &str, value: &'a str) -> Cow<'a, str> {
+        let Some(max) = self.of(column) else {
+            return Cow::Borrowed(value);
+        };
+        let fitted = truncate(value, max);
+        if let Cow::Owned(_) = fitted {
+            let truncation = Truncation {
+                exporter: exporter.to_string(),
+                column: column.to_string(),
+                part: part.to_string(),
+                length: value.chars().count(),
+                limit: max,
+            };
+            crate::settings::with(|settings| {
+                settings.run.truncations.lock().unwrap_or_else(|e| e.into_inner()).push(truncation)
+            });
+        }
+        fitted
+    }
+}
+
+/// Exporters with built-in limits: Altium's 255 character text fields
+fn built_in(exporter: &str) -> Limits {
+    match exporter {
+        "altium" => Limits { default: Some(255), columns: HashMap::new() },
+        _ => Limits::default(),
+    }
+}
+
+/// Limits of `exporter` in the current [`crate::settings`], or its built-in ones
+pub fn limits(exporter: &str) -> Limits {
+    crate::settings::with(|settings| settings.limits.get(exporter).cloned()).unwrap_or_else(|| built_in(exporter))
+}
+
+/// Shorten `text` to at most `max` characters, ending in `...` when there is
+/// room for it.
+///
+/// ```
+/// use component::limits::truncate;
+///
+/// assert_eq!(truncate("RES 0603 4.99K", 20), "RES 0603 4.99K");
+/// assert_eq!(truncate("RES 0603 4.99K", 10), "RES 060...");
+/// assert_eq!(truncate("Widerstand 4,99 kΩ ±1 %", 17), "Widerstand 4,9...");
+/// assert_eq!(truncate("ΩΩΩΩ", 2), "ΩΩ");
+/// ```
+pub fn truncate(text: &str, max: usize) -> Cow<'_, str> {
+    // Byte length bounds the character count
+    if text.len() <= max || text.chars().count() <= max {
+        return Cow::Borrowed(text);
+    }
+    let marker = if max > MARKER.len() { MARKER } else { "" };
+    let keep = max - marker.len();
+    let end = text.char_indices().nth(keep).map_or(text.len(), |(index, _)| index);
+    Cow::Owned(format!("{}{}", &text[..end], marker))
+}
+
+/// A value shortened to its column's limit
+#[derive(Debug, Clone, PartialEq)]
+pub struct Truncation {
+    pub exporter: String,
+    pub column: String,
+    /// Part the row describes
+    pub part: String,
+    /// Original length in characters
+    pub length: usize,
+    pub limit: usize,
+}
+
+impl fmt::Display for Truncation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} of {}: {} characters cut to {}",
+            self.exporter, self.column, self.part, self.length, self.limit
+        )
+    }
+}
+
+/// Values the current run shortened since the last call, for a warning report
+///
+/// ```
+/// use component::limits::{limits, take_truncations, Limits};
+/// use component::settings::Settings;
+///
+/// let mut settings = Settings::default();
+/// let plm = Limits { default: None, columns: [("Description".to_string(), 12)].into_iter().collect() };
+/// settings.limits.insert("plm".to_string(), plm);
+/// settings.scope(|| {
+///     let plm = limits("plm");
+///     assert_eq!(plm.fit("plm", "Description", "R0603_4.99K", "RES SMT 4.99Kohms, 0603"), "RES SMT 4...");
+///     assert_eq!(plm.fit("plm", "MPN", "R0603_4.99K", "CRCW06034K99FKEA"), "CRCW06034K99FKEA");
+///     assert_eq!(limits("altium").of("Description"), Some(255));
+/// });
+///
+/// let report = settings.scope(take_truncations);
+/// assert_eq!(report.len(), 1);
+/// assert_eq!(report[0].to_string(), "plm Description of R0603_4.99K: 23 characters cut to 12");
+/// assert!(settings.scope(take_truncations).is_empty());
+/// ```
+pub fn take_truncations() -> Vec<Truncation> {
+    crate::settings::with(|settings| std::mem::take(&mut *settings.run.truncations.lock().unwrap_or_else(|e| e.into_inner())))
+}
@@ -0,0 +1,378 @@
+//! Resistor manufacturers the generators can build part numbers for.
+//!
+//! Every numbering scheme implements [`ManufacturerPartNumber`] and lives in
+//! a process wide registry, so `Resistor`, the ECS systems and the CLI look
+//! vendors up by name instead of matching on a fixed list. Vishay, Yageo,
+//! KOA Speer, Stackpole and Susumu are built in; in-house schemes are added with
+//! [`register`]:
+//!
+//! ```
+//! use component::manufacturer::{self, Manufacturer, ManufacturerPartNumber};
+//! use component::Resistance;
+//!
+//! struct Stock;
+//!
+//! impl ManufacturerPartNumber for Stock {
+//!     fn name(&self) -> &str {
+//!         "Acme Stock"
+//!     }
+//!
+//!     fn mpn(&self, package: &str, resistance: Resistance, _tolerance: &str) -> Option<String> {
+//!         Some(format!("ACME-R{}-{}", package, resistance.vishay_code()))
+//!     }
+//! }
+//!
+//! manufacturer::register(Stock);
+//! let acme: Manufacturer = "acme stock".parse().unwrap();
+//! assert_eq!(acme.resistor_mpn("0603", Resistance::from_ohms(4_990.0), "1%").as_deref(), Some("ACME-R0603-4K99"));
+//! ```
+
+use crate::{distributor, koa, stackpole, susumu, tolerance, vishay, yageo, AtlantixError, Distributor, Resistance};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// A manufacturer's part numbering scheme for chip resistors
+pub trait ManufacturerPartNumber: Send + Sync {
+    /// Name recorded in the `Manufacturer` field, e.g. "Vishay"
+    fn name(&self) -> &str;
+
+    /// Other names accepted when parsing, compared case-insensitively
+    fn aliases(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Part number for a size, value and tolerance such as "1%", `None`
+    /// when the manufacturer does not make that part
+    fn mpn(&self, package: &str, resistance: Resistance, tolerance: &str) -> Option<String>;
+
+    /// Digi-Key part number, `None` when Digi-Key lists the part under its
+    /// MPN
+    fn digikey_pn(&self, _package: &str, _resistance: Resistance, _tolerance: &str) -> Option<String> {
+        None
+    }
+
+    /// AEC-Q200 qualified part number, `None` when the manufacturer has no
+    /// qualified series for the part
+    fn aec_q200_mpn(&self, _package: &str, _resistance: Resistance, _tolerance: &str) -> Option<String> {
+        None
+    }
+
+    /// Prefix Mouser puts in front of the MPN
+    fn mouser_prefix(&self) -> Option<&str> {
+        distributor::mouser_prefix(self.name())
+    }
+
+    /// Distributor the ECS generator uses when none is configured
+    fn preferred_distributor(&self) -> Distributor {
+        Distributor::Digikey
+    }
+}
+
+struct Vishay;
+struct Yageo;
+struct Koa;
+struct Stackpole;
+struct Susumu;
+
+impl ManufacturerPartNumber for Vishay {
+    fn name(&self) -> &str {
+        "Vishay"
+    }
+
+    fn aliases(&self) -> &[&str] {
+        &["vishay dale"]
+    }
+
+    fn mpn(&self, package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+        if tolerance::is_thin_film(tolerance) {
+            return vishay::tnpw_mpn(package, resistance, tolerance);
+        }
+        vishay::crcw_mpn(package, resistance, tolerance)
+    }
+
+    // CRCW e3 and TNPW e3 are qualified as they are
+    fn aec_q200_mpn(&self, package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+        self.mpn(package, resistance, tolerance)
+    }
+
+    fn digikey_pn(&self, package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+        // Digi-Key lists TNPW under the MPN
+        if tolerance::is_thin_film(tolerance) {
+            return None;
+        }
+        vishay::digikey_pn(package, resistance, tolerance)
+    }
+}
+
+impl ManufacturerPartNumber for Yageo {
+    fn name(&self) -> &str {
+        "Yageo"
+    }
+
+    fn mpn(&self, package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+        yageo::rc_mpn(package, resistance, tolerance)
+    }
+
+    fn aec_q200_mpn(&self, package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+        yageo::ac_mpn(package, resistance, tolerance)
+    }
+
+    fn digikey_pn(&self, package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+        yageo::digikey_pn(package, resistance, tolerance)
+    }
+
+    fn preferred_distributor(&self) -> Distributor {
+        Distributor::Mouser
+    }
+}
+
+impl ManufacturerPartNumber for Koa {
+    fn name(&self) -> &str {
+        "KOA Speer"
+    }
+
+    fn aliases(&self) -> &[&str] {
+        &["koa"]
+    }
+
+    fn mpn(&self, package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+        koa::rk73h_mpn(package, resistance, tolerance)
+    }
+
+    // RK73H is qualified as it is
+    fn aec_q200_mpn(&self, package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+        self.mpn(package, resistance, tolerance)
+    }
+
+    fn digikey_pn(&self, package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+        koa::digikey_pn(package, resistance, tolerance)
+    }
+}
+
+impl ManufacturerPartNumber for Stackpole {
+    fn name(&self) -> &str {
+        "Stackpole"
+    }
+
+    fn aliases(&self) -> &[&str] {
+        &["stackpole electronics"]
+    }
+
+    fn mpn(&self, package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+        stackpole::rmcf_mpn(package, resistance, tolerance)
+    }
+
+    fn digikey_pn(&self, package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+        stackpole::digikey_pn(package, resistance, tolerance)
+    }
+}
+
+impl ManufacturerPartNumber for Susumu {
+    fn name(&self) -> &str {
+        "Susumu"
+    }
+
+    fn mpn(&self, package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+        susumu::rg_mpn(package, resistance, tolerance)
+    }
+
+    fn digikey_pn(&self, package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+        susumu::digikey_pn(package, resistance, tolerance)
+    }
+}
+
+type Registry = RwLock<Vec<Arc<dyn ManufacturerPartNumber>>>;
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        RwLock::new(vec![Arc::new(Vishay), Arc::new(Yageo), Arc::new(Koa), Arc::new(Stackpole), Arc::new(Susumu)])
+    })
+}
+
+/// Add a numbering scheme. A scheme with the same name as a registered one
+/// replaces it, so built-in vendors can be overridden too.
+pub fn register<M: ManufacturerPartNumber + 'static>(scheme: M) {
+    let mut schemes = registry().write().unwrap_or_else(|e| e.into_inner());
+    schemes.retain(|existing| !existing.name().eq_ignore_ascii_case(scheme.name()));
+    schemes.push(Arc::new(scheme));
+    crate::memo::clear();
+}
+
+/// Look a manufacturer up by name or alias
+pub fn lookup(name: &str) -> Option<Manufacturer> {
+    let name = name.trim();
+    let schemes = registry().read().unwrap_or_else(|e| e.into_inner());
+    schemes
+        .iter()
+        .find(|scheme| {
+            scheme.name().eq_ignore_ascii_case(name)
+                || scheme.aliases().iter().any(|alias| alias.eq_ignore_ascii_case(name))
+        })
+        .map(|scheme| Manufacturer(scheme.clone()))
+}
+
+/// Names of all registered manufacturers
+pub fn names() -> Vec<String> {
+    let schemes = registry().read().unwrap_or_else(|e| e.into_inner());
+    schemes.iter().map(|scheme| scheme.name().to_string()).collect()
+}
+
+/// Handle to a registered manufacturer, recorded as `Manufacturer`/`MPN` in
+/// the generated libraries
+#[derive(Clone)]
+pub struct Manufacturer(Arc<dyn ManufacturerPartNumber>);
+
+impl Manufacturer {
+    pub fn vishay() -> Manufacturer {
+        lookup("Vishay").unwrap_or_else(|| Manufacturer(Arc::new(Vishay)))
+    }
+
+    pub fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    /// Chip resistor part number, `None` when the manufacturer's series has
+    /// no part for this size, value and tolerance.
+    ///
+    /// ```
+    /// use component::{Manufacturer, Resistance};
+    ///
+    /// let r = Resistance::from_ohms(4_990.0);
+    /// let koa: Manufacturer = "koa".parse().unwrap();
+    /// assert_eq!(Manufacturer::vishay().resistor_mpn("0603", r, "1%").as_deref(), Some("CRCW06034K99FKEA"));
+    /// assert_eq!(koa.resistor_mpn("0603", r, "1%").as_deref(), Some("RK73H1JTTD4991F"));
+    /// ```
+    pub fn resistor_mpn(&self, package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+        self.0.mpn(package, resistance, tolerance)
+    }
+
+    /// Part number at a distributor. Mouser prefixes the MPN; Digi-Key
+    /// falls back to the MPN for parts without a Digi-Key number and Arrow
+    /// lists the MPN.
+    ///
+    /// ```
+    /// use component::{Distributor, Manufacturer, Resistance};
+    ///
+    /// let r = Resistance::from_ohms(4_990.0);
+    /// let yageo: Manufacturer = "yageo".parse().unwrap();
+    /// assert_eq!(yageo.distributor_pn(Distributor::Mouser, "0603", r, "1%").as_deref(), Some("603-RC0603FR-074K99L"));
+    /// assert_eq!(yageo.distributor_pn(Distributor::Digikey, "0603", r, "5%").as_deref(), Some("RC0603JR-074K99L"));
+    /// assert_eq!(yageo.distributor_pn(Distributor::Arrow, "0603", r, "1%").as_deref(), Some("RC0603FR-074K99L"));
+    /// ```
+    pub fn distributor_pn(
+        &self,
+        distributor: Distributor,
+        package: &str,
+        resistance: Resistance,
+        tolerance: &str,
+    ) -> Option<String> {
+        match distributor {
+            Distributor::Digikey => self
+                .0
+                .digikey_pn(package, resistance, tolerance)
+                .or_else(|| self.resistor_mpn(package, resistance, tolerance)),
+            Distributor::Mouser => {
+                let prefix = self.0.mouser_prefix()?;
+                let mpn = self.resistor_mpn(package, resistance, tolerance)?;
+                Some(format!("{}-{}", prefix, mpn))
+            }
+            Distributor::Arrow => self.resistor_mpn(package, resistance, tolerance),
+        }
+    }
+
+    /// AEC-Q200 qualified part number, `None` when the manufacturer has no
+    /// qualified series for this part.
+    ///
+    /// ```
+    /// use component::{Manufacturer, Resistance};
+    ///
+    /// let r = Resistance::from_ohms(4_990.0);
+    /// let yageo: Manufacturer = "yageo".parse().unwrap();
+    /// let stackpole: Manufacturer = "stackpole".parse().unwrap();
+    /// assert_eq!(yageo.aec_q200_mpn("0603", r, "1%").as_deref(), Some("AC0603FR-074K99L"));
+    /// assert_eq!(stackpole.aec_q200_mpn("0603", r, "1%"), None);
+    /// ```
+    pub fn aec_q200_mpn(&self, package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+        self.0.aec_q200_mpn(package, resistance, tolerance)
+    }
+
+    /// Distributor part number of the AEC-Q200 part. Digi-Key's own numbers
+    /// cover the commercial parts only, so both distributors are searched
+    /// by MPN unless the qualified part is the commercial one.
+    pub fn aec_q200_distributor_pn(
+        &self,
+        distributor: Distributor,
+        package: &str,
+        resistance: Resistance,
+        tolerance: &str,
+    ) -> Option<String> {
+        let mpn = self.aec_q200_mpn(package, resistance, tolerance)?;
+        if self.resistor_mpn(package, resistance, tolerance).as_ref() == Some(&mpn) {
+            return self.distributor_pn(distributor, package, resistance, tolerance);
+        }
+        match distributor {
+            Distributor::Digikey | Distributor::Arrow => Some(mpn),
+            Distributor::Mouser => Some(format!("{}-{}", self.0.mouser_prefix()?, mpn)),
+        }
+    }
+
+    pub fn preferred_distributor(&self) -> Distributor {
+        self.0.preferred_distributor()
+    }
+}
+
+impl Default for Manufacturer {
+    fn default() -> Self {
+        Manufacturer::vishay()
+    }
+}
+
+impl PartialEq for Manufacturer {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name()
+    }
+}
+
+impl Eq for Manufacturer {}
+
+impl fmt::Debug for Manufacturer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Manufacturer").field(&self.name()).finish()
+    }
+}
+
+impl fmt::Display for Manufacturer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Manufacturer {
+    type Err = AtlantixError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        lookup(value).ok_or_else(|| {
+            AtlantixError::Parse(format!(
+                "Unknown manufacturer '{}'. Expected one of: {}",
+                value,
+                names().join(", ")
+            ))
+        })
+    }
+}
+
+/// Comma separated manufacturers such as "vishay,koa". The first is the
+/// primary source, the others are alternates.
+pub fn parse_list(list: &str) -> Result<Vec<Manufacturer>, AtlantixError> {
+    let manufacturers = list
+        .split(',')
+        .filter(|name| !name.trim().is_empty())
+        .map(str::parse)
+        .collect::<Result<Vec<Manufacturer>, AtlantixError>>()?;
+    if manufacturers.is_empty() {
+        return Err(AtlantixError::Parse("No manufacturer given".to_string()));
+    }
+    Ok(manufacturers)
+}
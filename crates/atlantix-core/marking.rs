@@ -0,0 +1,463 @@
+//! Resistor body markings.
+//!
+//! MELF and axial leaded resistors carry color bands: two (4-band) or three
+//! (5-band) significant digits, a multiplier and a tolerance band. Laser
+//! marked parts use a 3-digit code (two digits and a power of ten, `472` =
+//! 4.7K) or a 4-digit code (three digits, `1002` = 10.0K), with R as the
+//! decimal point below the first decade (`4R7`, `49R9`). Chips too small
+//! for four digits carry the EIA-96 code instead: the index of the E96 value
+//! and a multiplier letter (`01A` = 100 Ohm, `68X` = 49.9 Ohm).
+//!
+//! Capacitors and inductors use the 3-digit code too, counting in pF and
+//! uH: `104` = 100nF, `4R7` = 4.7uH.
+
+use crate::Resistance;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Brown,
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Violet,
+    Grey,
+    White,
+    Gold,
+    Silver,
+}
+
+const DIGITS: [Color; 10] = [
+    Color::Black,
+    Color::Brown,
+    Color::Red,
+    Color::Orange,
+    Color::Yellow,
+    Color::Green,
+    Color::Blue,
+    Color::Violet,
+    Color::Grey,
+    Color::White,
+];
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Color::Black => "black",
+            Color::Brown => "brown",
+            Color::Red => "red",
+            Color::Orange => "orange",
+            Color::Yellow => "yellow",
+            Color::Green => "green",
+            Color::Blue => "blue",
+            Color::Violet => "violet",
+            Color::Grey => "grey",
+            Color::White => "white",
+            Color::Gold => "gold",
+            Color::Silver => "silver",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Packages that are marked with color bands rather than a printed code
+pub fn is_banded_package(package: &str) -> bool {
+    let package = package.to_uppercase();
+    package.contains("MELF") || package.starts_with("AXIAL") || matches!(package.as_str(), "0102" | "0204" | "0207")
+}
+
+/// Tolerance band, `None` for 20% parts which have no band
+fn tolerance_color(tolerance: &str) -> Option<Color> {
+    match tolerance {
+        "0.1%" => Some(Color::Violet),
+        "0.25%" => Some(Color::Blue),
+        "0.5%" => Some(Color::Green),
+        "1%" => Some(Color::Brown),
+        "2%" => Some(Color::Red),
+        "5%" => Some(Color::Gold),
+        "10%" => Some(Color::Silver),
+        _ => None,
+    }
+}
+
+/// Significant digits and the power of ten they are multiplied by, `None`
+/// when the value needs more digits than `count`
+fn digits_and_multiplier(resistance: Resistance, count: usize) -> Option<(Vec<u32>, i32)> {
+    let (digits, exponent) = resistance.significant_digits();
+    let all = [digits / 100, digits / 10 % 10, digits % 10];
+    if all[count..].iter().any(|&d| d != 0) {
+        return None;
+    }
+    Some((all[..count].to_vec(), exponent + 1 - count as i32))
+}
+
+/// Color bands, read from the end opposite the tolerance band. Precision
+/// parts (1% and tighter) and values with three significant digits get five
+/// bands, everything else four.
+///
+/// ```
+/// use component::marking::{color_bands, Color};
+/// use component::Resistance;
+///
+/// let bands = color_bands(Resistance::from_ohms(4_700.0), "5%").unwrap();
+/// assert_eq!(bands, vec![Color::Yellow, Color::Violet, Color::Red, Color::Gold]);
+/// let bands = color_bands(Resistance::from_ohms(10_000.0), "1%").unwrap();
+/// assert_eq!(bands, vec![Color::Brown, Color::Black, Color::Black, Color::Red, Color::Brown]);
+/// let bands = color_bands(Resistance::from_ohms(0.47), "5%").unwrap();
+/// assert_eq!(bands, vec![Color::Yellow, Color::Violet, Color::Silver, Color::Gold]);
+/// ```
+pub fn color_bands(resistance: Resistance, tolerance: &str) -> Option<Vec<Color>> {
+    let precision = matches!(tolerance, "0.1%" | "0.25%" | "0.5%" | "1%");
+    let (digits, multiplier) = match digits_and_multiplier(resistance, 2) {
+        Some(two) if !precision => two,
+        _ => digits_and_multiplier(resistance, 3)?,
+    };
+    let mut bands: Vec<Color> = digits.iter().map(|&d| DIGITS[d as usize]).collect();
+    bands.push(match multiplier {
+        -2 => Color::Silver,
+        -1 => Color::Gold,
+        0..=9 => DIGITS[multiplier as usize],
+        _ => return None,
+    });
+    bands.extend(tolerance_color(tolerance));
+    Some(bands)
+}
+
+/// 3- or 4-digit laser marking code.
+///
+/// ```
+/// use component::marking::digit_code;
+/// use component::Resistance;
+///
+/// assert_eq!(digit_code(Resistance::from_ohms(10_000.0), 4).as_deref(), Some("1002"));
+/// assert_eq!(digit_code(Resistance::from_ohms(4_700.0), 3).as_deref(), Some("472"));
+/// assert_eq!(digit_code(Resistance::from_ohms(49.9), 4).as_deref(), Some("49R9"));
+/// assert_eq!(digit_code(Resistance::from_ohms(4.7), 3).as_deref(), Some("4R7"));
+/// assert_eq!(digit_code(Resistance::from_ohms(4_990.0), 3), None);
+/// ```
+pub fn digit_code(resistance: Resistance, count: usize) -> Option<String> {
+    let significant = count - 1;
+    let (digits, multiplier) = digits_and_multiplier(resistance, significant)?;
+    let digits: String = digits.iter().map(|d| d.to_string()).collect();
+    if multiplier >= 0 {
+        return (multiplier <= 9).then(|| format!("{}{}", digits, multiplier));
+    }
+    // R takes the place of the decimal point
+    let integer = significant as i32 + multiplier;
+    if integer < 0 {
+        return None;
+    }
+    let (integer, fraction) = digits.split_at(integer as usize);
+    Some(format!("{}R{}", integer, fraction))
+}
+
+/// Chip sizes too small for a 4-digit code, 1% parts carry EIA-96 instead
+fn is_eia96_package(package: &str) -> bool {
+    matches!(package, "0201" | "0402" | "0603")
+}
+
+/// "Marking" property text for a package: the color bands for MELF and
+/// axial parts followed by the equivalent 4-digit code, the EIA-96 code for
+/// small 1% chips, `None` for anything else.
+///
+/// ```
+/// use component::marking::marking;
+/// use component::Resistance;
+///
+/// assert_eq!(
+///     marking("MiniMELF", Resistance::from_ohms(10_000.0), "1%").as_deref(),
+///     Some("brown black black red brown (1002)")
+/// );
+/// assert_eq!(marking("0603", Resistance::from_ohms(10_000.0), "1%").as_deref(), Some("01C"));
+/// assert_eq!(marking("0603", Resistance::from_ohms(10_000.0), "5%"), None);
+/// assert_eq!(marking("1206", Resistance::from_ohms(10_000.0), "1%"), None);
+/// ```
+pub fn marking(package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+    if tolerance == "1%" && is_eia96_package(package) {
+        return eia96_code(resistance);
+    }
+    if !is_banded_package(package) {
+        return None;
+    }
+    let bands = color_bands(resistance, tolerance)?;
+    let bands: Vec<String> = bands.iter().map(Color::to_string).collect();
+    Some(match digit_code(resistance, 4) {
+        Some(code) => format!("{} ({})", bands.join(" "), code),
+        None => bands.join(" "),
+    })
+}
+
+/// E96 significant digits, EIA-96 code 01 is the first entry
+const EIA96_VALUES: [u32; 96] = [
+    100, 102, 105, 107, 110, 113, 115, 118, 121, 124, 127, 130, 133, 137, 140, 143, 147, 150, 154, 158,
+    162, 165, 169, 174, 178, 182, 187, 191, 196, 200, 205, 210, 215, 221, 226, 232, 237, 243, 249, 255,
+    261, 267, 274, 280, 287, 294, 301, 309, 316, 324, 332, 340, 348, 357, 365, 374, 383, 392, 402, 412,
+    422, 432, 442, 453, 464, 475, 487, 499, 511, 523, 536, 549, 562, 576, 590, 604, 619, 634, 649, 665,
+    681, 698, 715, 732, 750, 768, 787, 806, 825, 845, 866, 887, 909, 931, 953, 976,
+];
+
+/// EIA-96 multiplier letter for a power of ten
+fn eia96_letter(power: i32) -> Option<char> {
+    match power {
+        -3 => Some('Z'),
+        -2 => Some('Y'),
+        -1 => Some('X'),
+        0 => Some('A'),
+        1 => Some('B'),
+        2 => Some('C'),
+        3 => Some('D'),
+        4 => Some('E'),
+        5 => Some('F'),
+        _ => None,
+    }
+}
+
+/// Power of ten for an EIA-96 letter, including the R, S and H alternates
+fn eia96_power(letter: char) -> Option<i32> {
+    match letter {
+        'Z' => Some(-3),
+        'Y' | 'R' => Some(-2),
+        'X' | 'S' => Some(-1),
+        'A' => Some(0),
+        'B' | 'H' => Some(1),
+        'C' => Some(2),
+        'D' => Some(3),
+        'E' => Some(4),
+        'F' => Some(5),
+        _ => None,
+    }
+}
+
+/// EIA-96 marking code, `None` for values outside the E96 series.
+///
+/// ```
+/// use component::marking::eia96_code;
+/// use component::Resistance;
+///
+/// assert_eq!(eia96_code(Resistance::from_ohms(100.0)).as_deref(), Some("01A"));
+/// assert_eq!(eia96_code(Resistance::from_ohms(49.9)).as_deref(), Some("68X"));
+/// assert_eq!(eia96_code(Resistance::from_ohms(10_000.0)).as_deref(), Some("01C"));
+/// assert_eq!(eia96_code(Resistance::from_ohms(4_700.0)), None);
+/// ```
+pub fn eia96_code(resistance: Resistance) -> Option<String> {
+    let (digits, exponent) = resistance.significant_digits();
+    let index = EIA96_VALUES.iter().position(|&value| value == digits)?;
+    let letter = eia96_letter(exponent - 2)?;
+    Some(format!("{:02}{}", index + 1, letter))
+}
+
+/// How a marking was read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkingScheme {
+    ThreeDigit,
+    FourDigit,
+    Eia96,
+}
+
+impl fmt::Display for MarkingScheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MarkingScheme::ThreeDigit => write!(f, "3-digit"),
+            MarkingScheme::FourDigit => write!(f, "4-digit"),
+            MarkingScheme::Eia96 => write!(f, "EIA-96"),
+        }
+    }
+}
+
+/// Every reading of a chip's top marking. Some markings are ambiguous, e.g.
+/// `10R` is 10 Ohm as a digit code but 1.24 Ohm as EIA-96.
+///
+/// ```
+/// use component::marking::{decode, MarkingScheme};
+/// use component::Resistance;
+///
+/// assert_eq!(decode("68X"), vec![(MarkingScheme::Eia96, Resistance::from_ohms(49.9))]);
+/// assert_eq!(decode("1002"), vec![(MarkingScheme::FourDigit, Resistance::from_ohms(10_000.0))]);
+/// assert_eq!(decode("472"), vec![(MarkingScheme::ThreeDigit, Resistance::from_ohms(4_700.0))]);
+/// assert_eq!(decode("4r7"), vec![(MarkingScheme::ThreeDigit, Resistance::from_ohms(4.7))]);
+/// assert_eq!(decode("10R").len(), 2);
+/// assert!(decode("XYZ").is_empty());
+/// ```
+pub fn decode(marking: &str) -> Vec<(MarkingScheme, Resistance)> {
+    let marking = marking.trim().to_uppercase();
+    let mut readings = Vec::new();
+
+    let chars: Vec<char> = marking.chars().collect();
+    if let [tens, ones, letter] = chars[..] {
+        if let (Some(tens), Some(ones), Some(power)) = (tens.to_digit(10), ones.to_digit(10), eia96_power(letter)) {
+            let index = (tens * 10 + ones) as usize;
+            if (1..=96).contains(&index) {
+                let ohms = EIA96_VALUES[index - 1] as f64 * 10f64.powi(power);
+                readings.push((MarkingScheme::Eia96, Resistance::from_ohms(ohms)));
+            }
+        }
+    }
+
+    let scheme = match chars.len() {
+        3 => MarkingScheme::ThreeDigit,
+        4 => MarkingScheme::FourDigit,
+        _ => return readings,
+    };
+    let ohms = if chars.iter().all(char::is_ascii_digit) {
+        // Significant digits followed by the power of ten
+        let (digits, power) = marking.split_at(chars.len() - 1);
+        digits.parse::<f64>().ok().zip(power.parse::<i32>().ok()).map(|(d, p)| d * 10f64.powi(p))
+    } else if chars.iter().filter(|&&c| c == 'R').count() == 1
+        && chars.iter().all(|&c| c == 'R' || c.is_ascii_digit())
+    {
+        marking.replace('R', ".").parse::<f64>().ok()
+    } else {
+        None
+    };
+    if let Some(ohms) = ohms {
+        readings.push((scheme, Resistance::from_ohms(ohms)));
+    }
+    readings
+}
+
+/// 3-character value code in the base unit of the part, pF for capacitors
+/// and uH for inductors. R marks the decimal point below 10 (`4R7`, `R47`),
+/// from 10 up two significant digits are followed by the power of ten
+/// (`220`, `104`). `None` for values the code cannot hold.
+///
+/// ```
+/// use component::marking::value_code;
+///
+/// assert_eq!(value_code(100_000.0).as_deref(), Some("104"));
+/// assert_eq!(value_code(22.0).as_deref(), Some("220"));
+/// assert_eq!(value_code(4.7).as_deref(), Some("4R7"));
+/// assert_eq!(value_code(0.47).as_deref(), Some("R47"));
+/// assert_eq!(value_code(0.0), None);
+/// ```
+pub fn value_code(value: f64) -> Option<String> {
+    if value <= 0.0 || !value.is_finite() {
+        return None;
+    }
+    if value < 1.0 {
+        let digits = format!("{:.3}", value);
+        return Some(format!("R{}", digits[2..].trim_end_matches('0')));
+    }
+    if value < 10.0 {
+        return Some(format!("{:.1}", value).replace('.', "R"));
+    }
+    // The epsilon keeps 10.000000000000002 and 9.999999999999998 in one decade
+    let mut exponent = (value.log10() + 1e-9).floor() as i32 - 1;
+    let mut digits = (value / 10f64.powi(exponent)).round() as u32;
+    if digits == 100 {
+        digits = 10;
+        exponent += 1;
+    }
+    (exponent <= 9).then(|| format!("{}{}", digits, exponent))
+}
+
+/// Value of a 3-digit or R code in the part's base unit, the inverse of
+/// [`value_code`]
+///
+/// ```
+/// use component::marking::decode_value_code;
+///
+/// assert_eq!(decode_value_code("104"), Some(100_000.0));
+/// assert_eq!(decode_value_code("4r7"), Some(4.7));
+/// assert_eq!(decode_value_code("R047"), Some(0.047));
+/// assert_eq!(decode_value_code("4K7"), None);
+/// ```
+pub fn decode_value_code(code: &str) -> Option<f64> {
+    let code = code.trim().to_uppercase();
+    if code.matches('R').count() == 1 && code.chars().all(|c| c == 'R' || c.is_ascii_digit()) && (3..=4).contains(&code.len()) {
+        return code.replace('R', ".").parse().ok();
+    }
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (digits, power) = code.split_at(2);
+    let digits: f64 = digits.parse().ok()?;
+    Some(digits * 10f64.powi(power.parse().ok()?))
+}
+
+/// Capacitor value code, `104` for 100nF
+///
+/// ```
+/// use component::marking::capacitor_code;
+///
+/// assert_eq!(capacitor_code(100e-9).as_deref(), Some("104"));
+/// assert_eq!(capacitor_code(10e-12).as_deref(), Some("100"));
+/// assert_eq!(capacitor_code(2.2e-9).as_deref(), Some("222"));
+/// ```
+pub fn capacitor_code(farads: f64) -> Option<String> {
+    value_code(farads * 1e12)
+}
+
+/// Capacitance in farads of a capacitor code. A multiplier digit of 8 or 9
+/// scales by 0.01 or 0.1 (`109` = 1pF), as on small C0G parts.
+///
+/// ```
+/// use component::marking::decode_capacitor_code;
+///
+/// let farads = decode_capacitor_code("104").unwrap();
+/// assert!((farads - 100e-9).abs() < 1e-18);
+/// let farads = decode_capacitor_code("109").unwrap();
+/// assert!((farads - 1e-12).abs() < 1e-21);
+/// ```
+pub fn decode_capacitor_code(code: &str) -> Option<f64> {
+    let picofarads = match code.trim().as_bytes() {
+        [a, b, power @ (b'8' | b'9')] if a.is_ascii_digit() && b.is_ascii_digit() => {
+            let digits = f64::from((a - b'0') * 10 + (b - b'0'));
+            digits * if *power == b'8' { 0.01 } else { 0.1 }
+        }
+        _ => decode_value_code(code)?,
+    };
+    Some(picofarads * 1e-12)
+}
+
+/// Inductor value code from an inductance in uH, `4R7` for 4.7uH
+pub fn inductor_code(uh: f64) -> Option<String> {
+    value_code(uh)
+}
+
+/// Inductance in uH of an inductor code
+pub fn decode_inductor_code(code: &str) -> Option<f64> {
+    decode_value_code(code)
+}
+
+/// Capacitance as the libraries write it: `10pF`, `2.2nF`, `100nF`, `4.7uF`
+///
+/// ```
+/// use component::marking::format_capacitance;
+///
+/// assert_eq!(format_capacitance(100e-9), "100nF");
+/// assert_eq!(format_capacitance(2.2e-9), "2.2nF");
+/// assert_eq!(format_capacitance(4.7e-6), "4.7uF");
+/// assert_eq!(format_capacitance(10e-12), "10pF");
+/// ```
+pub fn format_capacitance(farads: f64) -> String {
+    let picofarads = farads * 1e12;
+    let (value, unit) = if picofarads < 1_000.0 - 1e-6 {
+        (picofarads, "pF")
+    } else if picofarads < 1_000_000.0 - 1e-3 {
+        (picofarads / 1e3, "nF")
+    } else {
+        (picofarads / 1e6, "uF")
+    };
+    let rounded = (value * 100.0).round() / 100.0;
+    format!("{}{}", rounded, unit)
+}
+
+/// Capacitance in farads of a library value such as `100nF` or `4.7µF`
+///
+/// ```
+/// use component::marking::parse_capacitance;
+///
+/// assert_eq!(parse_capacitance("4.7µF"), Some(4.7e-6));
+/// assert_eq!(parse_capacitance("100nF"), Some(100.0 * 1e-9));
+/// assert_eq!(parse_capacitance("100"), None);
+/// ```
+pub fn parse_capacitance(value: &str) -> Option<f64> {
+    let value = value.trim().replace('µ', "u");
+    let (number, scale) = [("pF", 1e-12), ("nF", 1e-9), ("uF", 1e-6)]
+        .iter()
+        .find_map(|(unit, scale)| value.strip_suffix(unit).map(|n| (n, *scale)))?;
+    number.trim().parse::<f64>().ok().map(|n| n * scale)
+}
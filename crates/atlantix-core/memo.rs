@@ -0,0 +1,153 @@
+//! Memoized formatting for large generation runs.
+//!
+//! Every value of a package repeats the same tolerance and power text, and
+//! each output written for the same values, e.g. one Altium CSV per
+//! distributor or the KiCad symbols next to a CSV, formats the MPNs of
+//! every manufacturer again. With memoization enabled these strings are
+//! formatted once per thread and key: ratings by (package, tolerance,
+//! power), MPNs by (manufacturer, value, package). For a single output,
+//! filling the caches costs about what they save, so memoization is off
+//! unless a front end writing several outputs turns it on in its
+//! [`crate::settings`]. Entering or leaving a settings scope starts the
+//! thread's caches afresh, as the text depends on the settings.
+//! `cargo bench -p atlantix-core --bench memoize` compares both ways at E192
+//! scale.
+
+use crate::manufacturer::Manufacturer;
+use crate::power::Power;
+use crate::resistance::Resistance;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Bumped by [`clear`], dropping every thread's caches on their next use
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Rating text shared by every value of a package
+#[derive(Debug, PartialEq)]
+pub(crate) struct Ratings {
+    /// `0603, 1%, 1/10W`, following the value in descriptions
+    pub(crate) description: String,
+    /// `1/10W`
+    pub(crate) power: String,
+    /// `1/10`, the Altium Power column
+    pub(crate) fraction: String,
+}
+
+impl Ratings {
+    fn new(package: &str, tolerance: &str, power: Power) -> Self {
+        Ratings {
+            description: format!("{}, {}, {}", crate::package_naming::describe(package), tolerance, power),
+            power: power.to_string(),
+            fraction: power.fraction(),
+        }
+    }
+}
+
+/// MPNs of one manufacturer, package and tolerance by value and AEC-Q200
+#[derive(Default)]
+struct Mpns {
+    manufacturer: String,
+    package: String,
+    tolerance: String,
+    parts: HashMap<(Resistance, bool), Option<String>>,
+}
+
+/// Entries are few, one per package and tolerance or per manufacturer,
+/// package and tolerance, and consecutive requests nearly always share
+/// them, so each list is searched from the last hit on
+#[derive(Default)]
+struct Cache {
+    generation: u64,
+    ratings: Vec<(String, String, Power, Rc<Ratings>)>,
+    last_ratings: usize,
+    mpns: Vec<Mpns>,
+    last_mpns: usize,
+}
+
+impl Cache {
+    fn current(&mut self) -> &mut Self {
+        let generation = GENERATION.load(Ordering::Relaxed);
+        if self.generation != generation {
+            *self = Cache { generation, ..Default::default() };
+        }
+        self
+    }
+}
+
+/// Index of the first entry matching, starting at `last`
+fn find<T>(entries: &[T], last: usize, matches: impl Fn(&T) -> bool) -> Option<usize> {
+    let len = entries.len();
+    (0..len).map(|offset| (last + offset) % len).find(|&index| matches(&entries[index]))
+}
+
+thread_local! {
+    static CACHE: RefCell<Cache> = RefCell::new(Cache::default());
+}
+
+/// Whether the current settings memoize, or format every string afresh
+pub fn enabled() -> bool {
+    crate::settings::with(|settings| settings.memoize)
+}
+
+/// Forget every memoized string, e.g. after a manufacturer's numbering
+/// scheme was replaced
+pub fn clear() {
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Forget this thread's memoized strings
+pub(crate) fn reset() {
+    CACHE.with_borrow_mut(|cache| *cache = Cache { generation: GENERATION.load(Ordering::Relaxed), ..Default::default() });
+}
+
+pub(crate) fn ratings(package: &str, tolerance: &str, power: Power) -> Rc<Ratings> {
+    if !enabled() {
+        return Rc::new(Ratings::new(package, tolerance, power));
+    }
+    CACHE.with_borrow_mut(|cache| {
+        let cache = cache.current();
+        let found = find(&cache.ratings, cache.last_ratings, |(p, t, w, _)| p == package && t == tolerance && *w == power);
+        let index = found.unwrap_or_else(|| {
+            let ratings = Rc::new(Ratings::new(package, tolerance, power));
+            cache.ratings.push((package.to_string(), tolerance.to_string(), power, ratings));
+            cache.ratings.len() - 1
+        });
+        cache.last_ratings = index;
+        cache.ratings[index].3.clone()
+    })
+}
+
+/// Part number of `manufacturer` for the value, from `format` on the first
+/// request
+pub(crate) fn mpn(
+    manufacturer: &Manufacturer,
+    package: &str,
+    value: Resistance,
+    tolerance: &str,
+    automotive: bool,
+    format: impl FnOnce() -> Option<String>,
+) -> Option<String> {
+    if !enabled() {
+        return format();
+    }
+    CACHE.with_borrow_mut(|cache| {
+        let cache = cache.current();
+        let name = manufacturer.name();
+        let found = find(&cache.mpns, cache.last_mpns, |m| {
+            m.package == package && m.manufacturer == name && m.tolerance == tolerance
+        });
+        let index = found.unwrap_or_else(|| {
+            cache.mpns.push(Mpns {
+                manufacturer: name.to_string(),
+                package: package.to_string(),
+                tolerance: tolerance.to_string(),
+                parts: HashMap::new(),
+            });
+            cache.mpns.len() - 1
+        });
+        cache.last_mpns = index;
+        cache.mpns[index].parts.entry((value, automotive)).or_insert_with(format).clone()
+    })
+}
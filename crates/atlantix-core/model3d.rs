@@ -0,0 +1,262 @@
+//! Parametric 3D models of two terminal chip packages.
+//!
+//! A model is three boxes, the body and a terminal at either end, sized from
+//! the package's land pattern spec and [`body_height`]. Each model is written
+//! as VRML for KiCad's 3D viewer and as a faceted STEP for MCAD export, into
+//! an `Atlantix_Resistors.3dshapes` directory next to the footprints.
+
+use crate::kicad_footprint::PackageSpec;
+use chrono::Utc;
+use std::path::Path;
+
+/// Model library the Atlantix resistor footprints reference
+pub const RESISTOR_MODEL_LIBRARY: &str = "Atlantix_Resistors";
+
+/// KiCad VRML units are 0.1 inch
+const WRL_UNIT_MM: f64 = 2.54;
+
+const BODY_COLOR: [f64; 3] = [0.1, 0.1, 0.1];
+const TERMINAL_COLOR: [f64; 3] = [0.82, 0.82, 0.82];
+
+/// Corners of each box face, counter-clockwise seen from outside, with the
+/// face normal. Corner `i` sits at the max of x, y, z for bits 0, 1, 2 of `i`.
+const FACES: [([f64; 3], [usize; 4]); 6] = [
+    ([0.0, 0.0, -1.0], [0, 2, 3, 1]),
+    ([0.0, 0.0, 1.0], [4, 5, 7, 6]),
+    ([0.0, -1.0, 0.0], [0, 1, 5, 4]),
+    ([0.0, 1.0, 0.0], [2, 6, 7, 3]),
+    ([-1.0, 0.0, 0.0], [0, 4, 6, 2]),
+    ([1.0, 0.0, 0.0], [1, 3, 7, 5]),
+];
+
+/// Typical body height of a thick film chip in mm. Packages without data
+/// get half their body width.
+pub fn body_height(spec: &PackageSpec) -> f64 {
+    match spec.imperial.as_str() {
+        "0201" => 0.23,
+        "0402" => 0.35,
+        "0603" => 0.45,
+        "0805" => 0.5,
+        "1206" | "1210" | "2010" | "2512" => 0.55,
+        _ => spec.body_width / 2.0,
+    }
+}
+
+struct Cuboid {
+    min: [f64; 3],
+    max: [f64; 3],
+    color: [f64; 3],
+}
+
+impl Cuboid {
+    fn corner(&self, i: usize) -> [f64; 3] {
+        let mut corner = self.min;
+        for (axis, value) in corner.iter_mut().enumerate() {
+            if i & (1 << axis) != 0 {
+                *value = self.max[axis];
+            }
+        }
+        corner
+    }
+}
+
+/// Body and terminal boxes of one chip, in mm with the origin at the
+/// footprint center and the board surface at z = 0
+///
+/// ```
+/// use component::kicad_footprint::get_package_specs;
+/// use component::model3d::ChipModel;
+///
+/// let model = ChipModel::new("R_0603_1608Metric", &get_package_specs("0603").unwrap());
+/// let wrl = model.to_wrl();
+/// assert!(wrl.starts_with("#VRML V2.0 utf8"));
+/// assert_eq!(wrl.matches("IndexedFaceSet").count(), 3);
+/// let step = model.to_step();
+/// assert!(step.starts_with("ISO-10303-21;"));
+/// assert_eq!(step.matches("FACE_SURFACE(").count(), 18);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChipModel {
+    pub name: String,
+    pub length: f64,
+    pub width: f64,
+    pub height: f64,
+    /// Length of each terminal along the body
+    pub terminal: f64,
+}
+
+impl ChipModel {
+    pub fn new(name: &str, spec: &PackageSpec) -> Self {
+        ChipModel {
+            name: name.to_string(),
+            length: spec.body_length,
+            width: spec.body_width,
+            height: body_height(spec),
+            terminal: (spec.body_length * 0.2).clamp(0.1, 0.6),
+        }
+    }
+
+    fn cuboids(&self) -> [Cuboid; 3] {
+        let (x, y) = (self.length / 2.0, self.width / 2.0);
+        let inner = x - self.terminal;
+        [
+            Cuboid { min: [-inner, -y, 0.0], max: [inner, y, self.height], color: BODY_COLOR },
+            Cuboid { min: [-x, -y, 0.0], max: [-inner, y, self.height], color: TERMINAL_COLOR },
+            Cuboid { min: [inner, -y, 0.0], max: [x, y, self.height], color: TERMINAL_COLOR },
+        ]
+    }
+
+    /// VRML 2.0 model in KiCad's 0.1 inch units
+    pub fn to_wrl(&self) -> String {
+        let mut wrl = format!("#VRML V2.0 utf8\n# {}\n# Generated by atlantix-eda\n", self.name);
+        for cuboid in self.cuboids() {
+            let points: Vec<String> = (0..8)
+                .map(|i| {
+                    let [x, y, z] = cuboid.corner(i);
+                    format!("{:.4} {:.4} {:.4}", x / WRL_UNIT_MM, y / WRL_UNIT_MM, z / WRL_UNIT_MM)
+                })
+                .collect();
+            let faces: Vec<String> = FACES
+                .iter()
+                .map(|(_, [a, b, c, d])| format!("{},{},{},{},-1", a, b, c, d))
+                .collect();
+            let [r, g, b] = cuboid.color;
+            wrl.push_str(&format!(
+                r#"Shape {{
+  appearance Appearance {{
+    material Material {{ diffuseColor {} {} {} specularColor 0.3 0.3 0.3 shininess 0.4 }}
+  }}
+  geometry IndexedFaceSet {{
+    coord Coordinate {{ point [ {} ] }}
+    coordIndex [ {} ]
+  }}
+}}
+"#,
+                r,
+                g,
+                b,
+                points.join(", "),
+                faces.join(", ")
+            ));
+        }
+        wrl
+    }
+
+    /// AP214 STEP model, one colored faceted B-rep per box, in mm
+    pub fn to_step(&self) -> String {
+        let mut step = Step::default();
+        let context = step.add("APPLICATION_CONTEXT('automotive design')");
+        step.add(format!("APPLICATION_PROTOCOL_DEFINITION('international standard','automotive_design',2000,#{})", context));
+        let product_context = step.add(format!("PRODUCT_CONTEXT('',#{},'mechanical')", context));
+        let product = step.add(format!("PRODUCT('{0}','{0}','',(#{1}))", self.name, product_context));
+        let formation = step.add(format!("PRODUCT_DEFINITION_FORMATION('','',#{})", product));
+        let definition_context = step.add(format!("PRODUCT_DEFINITION_CONTEXT('part definition',#{},'design')", context));
+        let definition = step.add(format!("PRODUCT_DEFINITION('design','',#{},#{})", formation, definition_context));
+        let shape = step.add(format!("PRODUCT_DEFINITION_SHAPE('','',#{})", definition));
+        let length = step.add("(LENGTH_UNIT()NAMED_UNIT(*)SI_UNIT(.MILLI.,.METRE.))");
+        let angle = step.add("(NAMED_UNIT(*)PLANE_ANGLE_UNIT()SI_UNIT($,.RADIAN.))");
+        let solid_angle = step.add("(NAMED_UNIT(*)SI_UNIT($,.STERADIAN.)SOLID_ANGLE_UNIT())");
+        let uncertainty = step.add(format!(
+            "UNCERTAINTY_MEASURE_WITH_UNIT(LENGTH_MEASURE(1.E-07),#{},'distance_accuracy_value','confusion accuracy')",
+            length
+        ));
+        let geometry = step.add(format!(
+            "(GEOMETRIC_REPRESENTATION_CONTEXT(3)GLOBAL_UNCERTAINTY_ASSIGNED_CONTEXT((#{}))GLOBAL_UNIT_ASSIGNED_CONTEXT((#{},#{},#{}))REPRESENTATION_CONTEXT('',''))",
+            uncertainty, length, angle, solid_angle
+        ));
+        let origin = step.point([0.0; 3]);
+        let placement = step.add(format!("AXIS2_PLACEMENT_3D('',#{},$,$)", origin));
+
+        let mut items = vec![placement];
+        let mut styles = Vec::new();
+        for cuboid in self.cuboids() {
+            let brep = step.cuboid(&cuboid);
+            items.push(brep);
+            styles.push(step.color(brep, cuboid.color));
+        }
+        let representation = step.add(format!(
+            "FACETED_BREP_SHAPE_REPRESENTATION('{}',({}),#{})",
+            self.name,
+            refs(&items),
+            geometry
+        ));
+        step.add(format!("SHAPE_DEFINITION_REPRESENTATION(#{},#{})", shape, representation));
+        step.add(format!(
+            "MECHANICAL_DESIGN_GEOMETRIC_PRESENTATION_REPRESENTATION('',({}),#{})",
+            refs(&styles),
+            geometry
+        ));
+
+        let mut out = format!(
+            "ISO-10303-21;\nHEADER;\nFILE_DESCRIPTION(('{0}'),'2;1');\nFILE_NAME('{0}.step','{1}',(''),(''),'atlantix-eda','atlantix-eda','');\nFILE_SCHEMA(('AUTOMOTIVE_DESIGN {{ 1 0 10303 214 1 1 1 1 }}'));\nENDSEC;\nDATA;\n",
+            self.name,
+            Utc::now().format("%Y-%m-%dT%H:%M:%S")
+        );
+        for (i, entity) in step.entities.iter().enumerate() {
+            out.push_str(&format!("#{}={};\n", i + 1, entity));
+        }
+        out.push_str("ENDSEC;\nEND-ISO-10303-21;\n");
+        out
+    }
+
+    /// Write `{name}.wrl` and `{name}.step` into `dir`
+    pub fn write(&self, dir: &Path) -> Result<(), crate::AtlantixError> {
+        crate::atomic::write(dir.join(format!("{}.wrl", self.name)), self.to_wrl())?;
+        crate::atomic::write(dir.join(format!("{}.step", self.name)), self.to_step())
+    }
+}
+
+/// STEP entity list, entity `#n` at index `n - 1`
+#[derive(Default)]
+struct Step {
+    entities: Vec<String>,
+}
+
+impl Step {
+    fn add(&mut self, entity: impl Into<String>) -> usize {
+        self.entities.push(entity.into());
+        self.entities.len()
+    }
+
+    fn point(&mut self, [x, y, z]: [f64; 3]) -> usize {
+        self.add(format!("CARTESIAN_POINT('',({:.4},{:.4},{:.4}))", x, y, z))
+    }
+
+    fn direction(&mut self, [x, y, z]: [f64; 3]) -> usize {
+        self.add(format!("DIRECTION('',({:.1},{:.1},{:.1}))", x, y, z))
+    }
+
+    /// Closed faceted B-rep of a box
+    fn cuboid(&mut self, cuboid: &Cuboid) -> usize {
+        let corners: Vec<usize> = (0..8).map(|i| self.point(cuboid.corner(i))).collect();
+        let mut faces = Vec::with_capacity(FACES.len());
+        for (normal, indices) in FACES {
+            let loop_points: Vec<usize> = indices.iter().map(|&i| corners[i]).collect();
+            let poly_loop = self.add(format!("POLY_LOOP('',({}))", refs(&loop_points)));
+            let bound = self.add(format!("FACE_OUTER_BOUND('',#{},.T.)", poly_loop));
+            let axis = self.direction(normal);
+            let reference = self.direction(if normal[2] == 0.0 { [0.0, 0.0, 1.0] } else { [1.0, 0.0, 0.0] });
+            let placement = self.add(format!("AXIS2_PLACEMENT_3D('',#{},#{},#{})", loop_points[0], axis, reference));
+            let plane = self.add(format!("PLANE('',#{})", placement));
+            faces.push(self.add(format!("FACE_SURFACE('',(#{}),#{},.T.)", bound, plane)));
+        }
+        let shell = self.add(format!("CLOSED_SHELL('',({}))", refs(&faces)));
+        self.add(format!("FACETED_BREP('',#{})", shell))
+    }
+
+    /// Styled item giving `item` a surface color
+    fn color(&mut self, item: usize, [r, g, b]: [f64; 3]) -> usize {
+        let colour = self.add(format!("COLOUR_RGB('',{:.2},{:.2},{:.2})", r, g, b));
+        let fill_colour = self.add(format!("FILL_AREA_STYLE_COLOUR('',#{})", colour));
+        let fill = self.add(format!("FILL_AREA_STYLE('',(#{}))", fill_colour));
+        let surface_fill = self.add(format!("SURFACE_STYLE_FILL_AREA(#{})", fill));
+        let side = self.add(format!("SURFACE_SIDE_STYLE('',(#{}))", surface_fill));
+        let usage = self.add(format!("SURFACE_STYLE_USAGE(.BOTH.,#{})", side));
+        let assignment = self.add(format!("PRESENTATION_STYLE_ASSIGNMENT((#{}))", usage));
+        self.add(format!("STYLED_ITEM('color',(#{}),#{})", assignment, item))
+    }
+}
+
+fn refs(ids: &[usize]) -> String {
+    ids.iter().map(|id| format!("#{}", id)).collect::<Vec<_>>().join(",")
+}
@@ -0,0 +1,244 @@
+//! Part and library name templates.
+//!
+//! Names are built from a template of literal text and `{field}`
+//! placeholders, so an organization can have `R_0603_4K99`,
+//! `RES-0603-4.99K-1%` or `R0603_4.99K` (the default) from the same
+//! generator. Part templates apply to symbol names, the Altium Part column
+//! and every exporter that names resistors; library templates to the names
+//! of generated libraries and their files.
+//!
+//! | Field         | Part  | Library | Example          |
+//! |---------------|-------|---------|------------------|
+//! | `{prefix}`    | yes   |         | `R`, `RES` in Altium |
+//! | `{package}`   | yes   | yes     | `0603`, in parts the code of [`crate::package_naming`] |
+//! | `{metric}`    | yes   | yes     | `1608`           |
+//! | `{value}`     | yes   |         | `4.99K`, in the selected value notation |
+//! | `{code}`      | yes   |         | `4K99`           |
+//! | `{tolerance}` | yes   | yes     | `1%` in parts, `1` in libraries |
+//! | `{series}`    |       | yes     | `E96`            |
+//!
+//! A field without a value, such as `{tolerance}` in a library named after
+//! its series' own tolerance, is left out together with the separator
+//! (`_`, `-`, `.` or a space) in front of it.
+//!
+//! The templates in use are the `part_template` and `library_template` of
+//! the current [`crate::settings`].
+
+use crate::{package_naming, AtlantixError, Resistance};
+use std::fmt;
+use std::str::FromStr;
+
+/// The built-in part names, `R0603_4.99K` or `R0603_4.99K_1%`
+pub const DEFAULT_PART: &str = "{prefix}{package}_{value}_{tolerance}";
+
+/// The built-in library names, `E96_0603` or `E192_0603_0.1`
+pub const DEFAULT_LIBRARY: &str = "{series}_{package}_{tolerance}";
+
+const SEPARATORS: [char; 4] = ['_', '-', '.', ' '];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Prefix,
+    Package,
+    Metric,
+    Value,
+    Code,
+    Tolerance,
+    Series,
+}
+
+impl FromStr for Field {
+    type Err = AtlantixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "prefix" => Ok(Field::Prefix),
+            "package" => Ok(Field::Package),
+            "metric" => Ok(Field::Metric),
+            "value" => Ok(Field::Value),
+            "code" => Ok(Field::Code),
+            "tolerance" => Ok(Field::Tolerance),
+            "series" => Ok(Field::Series),
+            _ => Err(AtlantixError::Parse(format!(
+                "Unknown name field '{{{}}}', expected prefix, package, metric, value, code, tolerance or series",
+                s
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Text(String),
+    Field(Field),
+}
+
+/// A parsed name template
+///
+/// ```
+/// use component::naming::NameTemplate;
+/// use component::Resistance;
+///
+/// let template: NameTemplate = "{prefix}-{package}-{value}-{tolerance}".parse().unwrap();
+/// let value = Resistance::from_ohms(4_990.0);
+/// assert_eq!(template.part("RES", "0603", value, Some("1%")), "RES-0603-4.99K-1%");
+/// assert_eq!(template.part("RES", "0603", value, None), "RES-0603-4.99K");
+///
+/// let template: NameTemplate = "{prefix}_{package}_{code}".parse().unwrap();
+/// assert_eq!(template.part("R", "0603", value, None), "R_0603_4K99");
+///
+/// assert!("{prefix}{package}_{colour}".parse::<NameTemplate>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameTemplate {
+    source: String,
+    pieces: Vec<Piece>,
+}
+
+impl FromStr for NameTemplate {
+    type Err = AtlantixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut pieces = Vec::new();
+        let mut rest = s;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                pieces.push(Piece::Text(rest[..start].to_string()));
+            }
+            let end = rest[start..]
+                .find('}')
+                .ok_or_else(|| AtlantixError::Parse(format!("Unclosed '{{' in name template '{}'", s)))?;
+            pieces.push(Piece::Field(rest[start + 1..start + end].trim().parse()?));
+            rest = &rest[start + end + 1..];
+        }
+        if !rest.is_empty() {
+            pieces.push(Piece::Text(rest.to_string()));
+        }
+        if pieces.iter().any(|piece| matches!(piece, Piece::Text(text) if text.contains('}'))) {
+            return Err(AtlantixError::Parse(format!("Unmatched '}}' in name template '{}'", s)));
+        }
+        Ok(NameTemplate { source: s.to_string(), pieces })
+    }
+}
+
+impl fmt::Display for NameTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl NameTemplate {
+    fn has(&self, field: Field) -> bool {
+        self.pieces.contains(&Piece::Field(field))
+    }
+
+    /// Check the template can name parts: every part needs its value
+    pub fn for_parts(self) -> Result<Self, AtlantixError> {
+        if self.has(Field::Series) {
+            return Err(AtlantixError::Parse(format!("Part template '{}' cannot use {{series}}", self)));
+        }
+        if !self.has(Field::Value) && !self.has(Field::Code) {
+            return Err(AtlantixError::Parse(format!("Part template '{}' needs {{value}} or {{code}} to tell parts apart", self)));
+        }
+        Ok(self)
+    }
+
+    /// Check the template can name libraries, which have no value
+    pub fn for_libraries(self) -> Result<Self, AtlantixError> {
+        if let Some(field) = ["prefix", "value", "code"].into_iter().find(|field| self.has(field.parse().expect("known field"))) {
+            return Err(AtlantixError::Parse(format!("Library template '{}' cannot use {{{}}}", self, field)));
+        }
+        if !self.has(Field::Package) && !self.has(Field::Metric) {
+            return Err(AtlantixError::Parse(format!("Library template '{}' needs {{package}} or {{metric}}", self)));
+        }
+        Ok(self)
+    }
+
+    fn render(&self, value: impl Fn(Field) -> Option<String>) -> String {
+        let mut name = String::new();
+        for piece in &self.pieces {
+            match piece {
+                Piece::Text(text) => name.push_str(text),
+                Piece::Field(field) => match value(*field).filter(|value| !value.is_empty()) {
+                    Some(value) => name.push_str(&value),
+                    None => {
+                        if name.ends_with(SEPARATORS) {
+                            name.pop();
+                        }
+                    }
+                },
+            }
+        }
+        name
+    }
+
+    /// Name of a part, `tolerance` only filled in when the tolerance is part
+    /// of the name
+    pub fn part(&self, prefix: &str, package: &str, resistance: Resistance, tolerance: Option<&str>) -> String {
+        self.render(|field| match field {
+            Field::Prefix => Some(prefix.to_string()),
+            Field::Package => Some(package_naming::label(package)),
+            Field::Metric => package_naming::metric(package).or_else(|| Some(package.to_string())),
+            Field::Value => Some(resistance.label()),
+            Field::Code => Some(resistance.vishay_code()),
+            Field::Tolerance => tolerance.map(str::to_string),
+            Field::Series => None,
+        })
+    }
+
+    /// Name of a library, `tolerance` only given when it is not the one of
+    /// the series. Library names leave out the `%`.
+    pub fn library(&self, series: &str, package: &str, tolerance: Option<&str>) -> String {
+        self.render(|field| match field {
+            // Library files are stored by the imperial code
+            Field::Package => Some(package.to_string()),
+            Field::Metric => package_naming::metric(package).or_else(|| Some(package.to_string())),
+            Field::Tolerance => tolerance.map(|tolerance| tolerance.trim_end_matches('%').to_string()),
+            Field::Series => Some(series.to_string()),
+            Field::Prefix | Field::Value | Field::Code => None,
+        })
+    }
+}
+
+/// Name of a resistor part with the template of the current settings
+///
+/// ```
+/// use component::settings::Settings;
+/// use component::{naming, Resistance};
+///
+/// let value = Resistance::from_ohms(10_000.0);
+/// assert_eq!(naming::part_name("R", "0603", value, Some("1%")), "R0603_10.0K_1%");
+/// let settings = Settings { part_template: Some("{prefix}_{package}_{code}".parse().unwrap()), ..Settings::default() };
+/// assert_eq!(settings.scope(|| naming::part_name("RES", "0603", value, Some("1%"))), "RES_0603_10K0");
+/// ```
+pub fn part_name(prefix: &str, package: &str, resistance: Resistance, tolerance: Option<&str>) -> String {
+    crate::settings::with(|settings| match &settings.part_template {
+        Some(template) => template.part(prefix, package, resistance, tolerance),
+        None => {
+            let name = format!("{}{}_{}", prefix, package_naming::label(package), resistance.label());
+            match tolerance {
+                Some(tolerance) => format!("{}_{}", name, tolerance),
+                None => name,
+            }
+        }
+    })
+}
+
+/// Name of a library with the template of the current settings
+///
+/// ```
+/// assert_eq!(component::naming::library_name("E192", "0603", Some("0.1%")), "E192_0603_0.1");
+/// assert_eq!(component::naming::library_name("E96", "0603", None), "E96_0603");
+/// ```
+pub fn library_name(series: &str, package: &str, tolerance: Option<&str>) -> String {
+    crate::settings::with(|settings| match &settings.library_template {
+        Some(template) => template.library(series, package, tolerance),
+        None => {
+            let name = format!("{}_{}", series, package);
+            match tolerance {
+                Some(tolerance) => format!("{}_{}", name, tolerance.trim_end_matches('%')),
+                None => name,
+            }
+        }
+    })
+}
@@ -0,0 +1,116 @@
+//! Order of the parts within generated symbol libraries and CSVs.
+//!
+//! Generators produce parts decade by decade, which interleaves badly when
+//! decades or series are combined and makes reviews and diffs hard to read.
+//! Libraries and CSVs are therefore sorted before they are written, by
+//! numeric value unless the front end selects another order.
+
+use crate::{AtlantixError, Resistance};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PartOrder {
+    /// Ascending value, parts without a numeric value last
+    #[default]
+    Value,
+    /// Ascending part name
+    Name,
+    /// The order the generator produced the parts in
+    Generation,
+}
+
+impl FromStr for PartOrder {
+    type Err = AtlantixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "value" | "numeric" => Ok(PartOrder::Value),
+            "name" => Ok(PartOrder::Name),
+            "generation" | "none" => Ok(PartOrder::Generation),
+            _ => Err(AtlantixError::Parse(format!("Unknown order '{}', expected value, name or generation", s))),
+        }
+    }
+}
+
+impl fmt::Display for PartOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PartOrder::Value => write!(f, "value"),
+            PartOrder::Name => write!(f, "name"),
+            PartOrder::Generation => write!(f, "generation"),
+        }
+    }
+}
+
+/// Order of libraries and CSVs in the current [`crate::settings`]
+pub fn order() -> PartOrder {
+    crate::settings::with(|settings| settings.part_order)
+}
+
+/// Numeric value of a part value in its base unit: resistances in either
+/// notation, or a number with an SI prefix and unit such as `100nF` or
+/// `4.7uH`. `None` for values such as `Red`.
+///
+/// ```
+/// use component::order::value_key;
+///
+/// assert_eq!(value_key("4.99K"), Some(4_990.0));
+/// assert_eq!(value_key("4k99"), Some(4_990.0));
+/// assert!(value_key("100nF") < value_key("1uF"));
+/// assert!(value_key("4.7uH") > value_key("470nH"));
+/// assert_eq!(value_key("Red"), None);
+/// ```
+pub fn value_key(value: &str) -> Option<f64> {
+    if let Ok(resistance) = value.parse::<Resistance>() {
+        return Some(resistance.ohms());
+    }
+    let value = value.trim().replace('µ', "u");
+    let number_end = value.find(|c: char| !(c.is_ascii_digit() || c == '.'))?;
+    let number: f64 = value[..number_end].parse().ok()?;
+    let scale = match value[number_end..].chars().next()? {
+        'p' => 1e-12,
+        'n' => 1e-9,
+        'u' => 1e-6,
+        'm' => 1e-3,
+        'k' | 'K' => 1e3,
+        'M' => 1e6,
+        'G' => 1e9,
+        'F' | 'H' | 'V' | 'A' | 'W' => 1.0,
+        _ => return None,
+    };
+    Some(number * scale)
+}
+
+fn compare_values(a: (&str, &str), b: (&str, &str)) -> Ordering {
+    match (value_key(a.1), value_key(b.1)) {
+        (Some(x), Some(y)) => x.total_cmp(&y).then_with(|| a.0.cmp(b.0)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.0.cmp(b.0),
+    }
+}
+
+/// Sort `items` into the selected [`order`]. `key` gives the name and value
+/// text of an item; equal values keep a stable order by name.
+///
+/// ```
+/// use component::order::{sort, PartOrder};
+/// use component::settings::Settings;
+///
+/// let mut parts = vec![("R0603_10.0K", "10.0K"), ("R0603_1.00K", "1.00K"), ("R0603_100", "100")];
+/// sort(&mut parts, |p| *p);
+/// assert_eq!(parts, [("R0603_100", "100"), ("R0603_1.00K", "1.00K"), ("R0603_10.0K", "10.0K")]);
+///
+/// let by_name = Settings { part_order: PartOrder::Name, ..Settings::default() };
+/// by_name.scope(|| sort(&mut parts, |p| *p));
+/// assert_eq!(parts[0].0, "R0603_1.00K");
+/// ```
+pub fn sort<T>(items: &mut [T], key: impl Fn(&T) -> (&str, &str)) {
+    match order() {
+        PartOrder::Value => items.sort_by(|a, b| compare_values(key(a), key(b))),
+        PartOrder::Name => items.sort_by(|a, b| key(a).0.cmp(key(b).0)),
+        PartOrder::Generation => {}
+    }
+}
@@ -0,0 +1,61 @@
+//! Templated output paths
+//!
+//! Output directories and file names may contain `{placeholder}`s, so that
+//! repeated runs can be archived side by side without renaming, e.g.
+//! `outputs/{date}/{format}/{series}_{package}`. `{date}` (`2024-05-01`) and
+//! `{time}` (`143005`) are always available and fixed at the first expansion
+//! of the run in the current [`crate::settings`], so every file of one run
+//! lands in the same directory. Front ends supply the other values.
+
+use crate::AtlantixError;
+
+/// Replace the placeholders in `template` by `values` or the run's date and
+/// time. A placeholder without a value is an error naming the known ones.
+///
+/// ```
+/// use component::output_path::expand;
+///
+/// let path = expand("outputs/{format}/{series}_{package}", &[("format", "kicad"), ("series", "E96"), ("package", "0603")]);
+/// assert_eq!(path.unwrap(), "outputs/kicad/E96_0603");
+///
+/// let dated = expand("outputs/{date}", &[]).unwrap();
+/// assert_eq!(dated.len(), "outputs/2024-05-01".len());
+///
+/// let err = expand("outputs/{colour}", &[("format", "kicad")]).unwrap_err().to_string();
+/// assert!(err.contains("{colour}") && err.contains("format"));
+/// ```
+pub fn expand(template: &str, values: &[(&str, &str)]) -> Result<String, AtlantixError> {
+    let started = crate::settings::with(|settings| settings.run.started());
+    let date = started.format("%Y-%m-%d").to_string();
+    let time = started.format("%H%M%S").to_string();
+
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        expanded.push_str(&rest[..open]);
+        let close = rest[open..]
+            .find('}')
+            .ok_or_else(|| AtlantixError::Parse(format!("Unclosed placeholder in output path '{}'", template)))?;
+        let name = &rest[open + 1..open + close];
+        let value = match name {
+            "date" => date.as_str(),
+            "time" => time.as_str(),
+            _ => match values.iter().find(|(key, _)| *key == name) {
+                Some((_, value)) => value,
+                None => {
+                    let known: Vec<&str> = ["date", "time"].into_iter().chain(values.iter().map(|(key, _)| *key)).collect();
+                    return Err(AtlantixError::Parse(format!(
+                        "Unknown placeholder {{{}}} in output path '{}', expected one of: {}",
+                        name,
+                        template,
+                        known.join(", ")
+                    )));
+                }
+            },
+        };
+        expanded.push_str(value);
+        rest = &rest[open + close + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
@@ -0,0 +1,138 @@
+//! Which package code leads in names.
+//!
+//! Chip packages have an imperial code (`0603`, inches) and a metric one
+//! (`1608`, millimetres). Names follow the imperial code by default, as US
+//! distributors and KiCad do; teams working from metric or JIS catalogs can
+//! have the metric code lead in part and symbol names, descriptions and the
+//! Atlantix footprint names instead. Packages are still stored by their
+//! imperial code, and [`resolve`] takes either code wherever a package is
+//! given, so `--package 1608` finds the 0603 parts. Footprints referenced
+//! from the stock KiCad libraries keep their KiCad names.
+
+use crate::kicad_footprint::{get_package_specs, supported_packages, PackageSpec};
+use crate::AtlantixError;
+use std::fmt;
+use std::str::FromStr;
+
+/// Code leading in names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PackageNaming {
+    /// `R0603_4.99K`, footprint `R_0603_1608Metric`
+    #[default]
+    Imperial,
+    /// `R1608_4.99K`, footprint `R_1608Metric_0603Imperial`
+    Metric,
+    /// As metric, with JIS body sizes in descriptions: `1608 (1.6 x 0.8 mm)`
+    Jis,
+}
+
+impl FromStr for PackageNaming {
+    type Err = AtlantixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "imperial" => Ok(PackageNaming::Imperial),
+            "metric" => Ok(PackageNaming::Metric),
+            "jis" => Ok(PackageNaming::Jis),
+            _ => Err(AtlantixError::Parse(format!("Unknown package naming '{}', expected imperial, metric or jis", s))),
+        }
+    }
+}
+
+impl fmt::Display for PackageNaming {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PackageNaming::Imperial => write!(f, "imperial"),
+            PackageNaming::Metric => write!(f, "metric"),
+            PackageNaming::Jis => write!(f, "jis"),
+        }
+    }
+}
+
+/// Naming of the current [`crate::settings`]
+pub fn naming() -> PackageNaming {
+    crate::settings::with(|settings| settings.package_naming)
+}
+
+/// Metric code of a package, `1608` for `0603`
+pub fn metric(package: &str) -> Option<String> {
+    let spec = get_package_specs(package)?;
+    let metric = spec.metric.trim_end_matches("Metric");
+    (!metric.is_empty()).then(|| metric.to_string())
+}
+
+/// Code of `package` in part and symbol names
+///
+/// ```
+/// use component::package_naming::{self, PackageNaming};
+/// use component::settings::Settings;
+///
+/// assert_eq!(package_naming::label("0603"), "0603");
+/// Settings { package_naming: PackageNaming::Metric, ..Settings::default() }.scope(|| {
+///     assert_eq!(package_naming::label("0603"), "1608");
+///     assert_eq!(package_naming::label("0201"), "0603");
+///     // Packages without a metric code keep their own
+///     assert_eq!(package_naming::label("SOD-123"), "SOD-123");
+/// });
+/// ```
+pub fn label(package: &str) -> String {
+    match naming() {
+        PackageNaming::Imperial => package.to_string(),
+        PackageNaming::Metric | PackageNaming::Jis => metric(package).unwrap_or_else(|| package.to_string()),
+    }
+}
+
+/// Package as descriptions write it: the leading code, and the other one or
+/// the JIS body size after it
+pub fn describe(package: &str) -> String {
+    let (Some(spec), Some(metric)) = (get_package_specs(package), metric(package)) else {
+        return package.to_string();
+    };
+    match naming() {
+        PackageNaming::Imperial => package.to_string(),
+        PackageNaming::Metric => format!("{} ({})", metric, spec.imperial),
+        PackageNaming::Jis => format!("{} ({} x {} mm)", metric, spec.body_length, spec.body_width),
+    }
+}
+
+/// Leading and following code of a footprint name, `0603` and `1608Metric`
+/// or `1608Metric` and `0603Imperial`
+pub fn footprint_codes(spec: &PackageSpec) -> (String, String) {
+    match naming() {
+        PackageNaming::Imperial => (spec.imperial.clone(), spec.metric.clone()),
+        PackageNaming::Metric | PackageNaming::Jis => (spec.metric.clone(), format!("{}Imperial", spec.imperial)),
+    }
+}
+
+/// Imperial code of a package given by either code: `0603`, `1608`,
+/// `1608Metric` or `0603Imperial`. Bare codes both systems use, such as
+/// `0603`, are read in the system leading the names.
+///
+/// ```
+/// use component::package_naming;
+///
+/// assert_eq!(package_naming::resolve("1608").as_deref(), Some("0603"));
+/// assert_eq!(package_naming::resolve("1608Metric").as_deref(), Some("0603"));
+/// assert_eq!(package_naming::resolve("0603").as_deref(), Some("0603"));
+/// assert_eq!(package_naming::resolve("9999"), None);
+/// ```
+pub fn resolve(code: &str) -> Option<String> {
+    let code = code.trim();
+    if let Some(imperial) = code.strip_suffix("Imperial") {
+        return get_package_specs(imperial).map(|spec| spec.imperial);
+    }
+    let by_metric = |metric: &str| {
+        supported_packages()
+            .into_iter()
+            .map(|spec| spec.imperial)
+            .find(|package| self::metric(package).as_deref() == Some(metric))
+    };
+    if let Some(metric) = code.strip_suffix("Metric") {
+        return by_metric(metric);
+    }
+    let imperial = get_package_specs(code).map(|spec| spec.imperial);
+    match naming() {
+        PackageNaming::Imperial => imperial.or_else(|| by_metric(code)),
+        PackageNaming::Metric | PackageNaming::Jis => by_metric(code).or(imperial),
+    }
+}
@@ -0,0 +1,111 @@
+//! Typed power ratings.
+//!
+//! Power is carried as integer milliwatts so parts can be filtered and
+//! sorted numerically ("everything rated 1/4 W or more"). The fractional
+//! `1/10W` notation used in part descriptions is only produced for display.
+
+use crate::AtlantixError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Fractional ratings as printed on chip resistor datasheets. 1/16 W is
+/// stored rounded to 63 mW.
+const FRACTIONS: [(u32, &str); 7] = [
+    (50, "1/20"),
+    (63, "1/16"),
+    (100, "1/10"),
+    (125, "1/8"),
+    (250, "1/4"),
+    (500, "1/2"),
+    (750, "3/4"),
+];
+
+/// A power rating in milliwatts.
+///
+/// ```
+/// use component::Power;
+///
+/// let p: Power = "1/8W".parse().unwrap();
+/// assert_eq!(p, Power(125));
+/// assert_eq!(p.to_string(), "1/8W");
+/// assert_eq!("250mW".parse::<Power>().unwrap(), Power(250));
+/// assert!(p < "0.25W".parse().unwrap());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Power(pub u32);
+
+impl Power {
+    pub fn from_watts(watts: f64) -> Self {
+        Power((watts * 1000.0).round() as u32)
+    }
+
+    pub fn milliwatts(self) -> u32 {
+        self.0
+    }
+
+    pub fn watts(self) -> f64 {
+        self.0 as f64 / 1000.0
+    }
+
+    /// Rating in watts without the unit: `1/10`, `1`, `1.5`
+    pub fn fraction(self) -> String {
+        if let Some((_, fraction)) = FRACTIONS.iter().find(|(mw, _)| *mw == self.0) {
+            return fraction.to_string();
+        }
+        format!("{}", self.watts())
+    }
+}
+
+impl fmt::Display for Power {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}W", self.fraction())
+    }
+}
+
+impl FromStr for Power {
+    type Err = AtlantixError;
+
+    /// Parses "1/8W", "1/8", "0.25W", "250mW" or "2".
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || AtlantixError::Parse(format!("Invalid power rating '{}'", value));
+        let cleaned = value.trim().replace(' ', "");
+        if let Some(milliwatts) = cleaned.strip_suffix("mW") {
+            return milliwatts.parse::<f64>().map(|mw| Power(mw.round() as u32)).map_err(|_| invalid());
+        }
+        let watts = cleaned.trim_end_matches(['W', 'w']);
+        let watts = match watts.split_once('/') {
+            Some((numerator, denominator)) => {
+                let numerator: f64 = numerator.parse().map_err(|_| invalid())?;
+                let denominator: f64 = denominator.parse().map_err(|_| invalid())?;
+                if denominator == 0.0 {
+                    return Err(invalid());
+                }
+                numerator / denominator
+            }
+            None => watts.parse().map_err(|_| invalid())?,
+        };
+        if watts < 0.0 {
+            return Err(invalid());
+        }
+        Ok(Power::from_watts(watts))
+    }
+}
+
+/// Rated power of a standard thick film chip resistor package
+pub fn chip_resistor_power(package: &str) -> Option<Power> {
+    let milliwatts = match package {
+        "0201" => 50,
+        "0402" => 63,
+        "0603" => 100,
+        "0805" => 125,
+        "1206" => 250,
+        "1210" => 500,
+        "1218" => 1000,
+        "2010" => 750,
+        "2512" => 1000,
+        _ => return None,
+    };
+    Some(Power(milliwatts))
+}
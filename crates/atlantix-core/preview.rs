@@ -0,0 +1,122 @@
+//! In-memory previews of a generation run.
+//!
+//! Front ends show the parts a configuration makes before any file is
+//! written. [`generate`] runs the ECS pipeline with no outputs and no output
+//! directory and returns one [`PreviewRow`] per part; [`Filter`] narrows
+//! them down by a search text and a package.
+
+use crate::ecs::components::{Description, ManufacturerParts, Package, PartNumber, ResistorValue, Tolerance};
+use crate::ecs::resources::{ESeriesCache, GeneratorConfig};
+use crate::Resistance;
+
+/// One generated part as a front end lists it
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreviewRow {
+    pub part_number: String,
+    pub value: Resistance,
+    /// Value as it appears in the part name, e.g. `4.99K`
+    pub formatted: String,
+    pub package: String,
+    pub tolerance: String,
+    pub description: String,
+    /// Manufacturer and MPN of each source, the preferred one first
+    pub mpns: Vec<(String, String)>,
+}
+
+/// The parts an E-series over `packages` generates with `config`, sorted by
+/// package and value. Nothing is written, whatever `config` says.
+///
+/// ```
+/// use component::ecs::resources::GeneratorConfig;
+/// use component::preview::{self, Filter};
+/// use component::ValueRange;
+///
+/// let config = GeneratorConfig { value_range: ValueRange::parse(Some("1K"), Some("9.99K")).unwrap(), ..Default::default() };
+/// let rows = preview::generate(&config, 24, &["0603", "0805"]);
+/// assert_eq!(rows.len(), 48);
+/// assert_eq!(rows[0].part_number, "R0603_1.00K");
+/// assert_eq!(rows[0].mpns[0], ("Vishay".to_string(), "CRCW06031K00JNEA".to_string()));
+///
+/// let filter = Filter { search: "4.64".into(), package: Some("0805".into()) };
+/// let found: Vec<_> = rows.iter().filter(|row| filter.matches(row)).map(|row| row.part_number.as_str()).collect();
+/// assert_eq!(found, ["R0805_4.64K"]);
+/// ```
+pub fn generate(config: &GeneratorConfig, series: usize, packages: &[&str]) -> Vec<PreviewRow> {
+    generate_with_tolerance(config, series, None, packages)
+}
+
+/// [`generate`] with every part in `tolerance` instead of the series' own,
+/// e.g. 0.1% for thin film
+///
+/// ```
+/// use component::ecs::resources::GeneratorConfig;
+/// use component::ValueRange;
+///
+/// let config = GeneratorConfig { value_range: ValueRange::parse(Some("1K"), Some("9.99K")).unwrap(), ..Default::default() };
+/// let rows = component::preview::generate_with_tolerance(&config, 192, Some("0.1%"), &["0603"]);
+/// assert_eq!(rows.len(), 192);
+/// assert_eq!(rows[0].tolerance, "0.1%");
+/// assert!(rows[0].mpns[0].1.starts_with("TNPW0603"));
+/// ```
+pub fn generate_with_tolerance(config: &GeneratorConfig, series: usize, tolerance: Option<&str>, packages: &[&str]) -> Vec<PreviewRow> {
+    let mut world = crate::ecs::build_resistor_world();
+    world.insert_resource(GeneratorConfig { output_formats: Vec::new(), output_dir: None, ..config.clone() });
+    world.insert_resource(ESeriesCache::default());
+    for package in packages {
+        let mut template = world.spawn((
+            crate::ecs::components::ESeries(series),
+            Package { name: package.to_string(), imperial: package.to_string(), metric: String::new() },
+        ));
+        if let Some(tolerance) = tolerance {
+            template.insert(Tolerance(tolerance.to_string()));
+        }
+    }
+    crate::ecs::run_generation_pipeline(&mut world);
+
+    let mut query = world.query::<(&PartNumber, &ResistorValue, &Package, &Tolerance, &Description, &ManufacturerParts)>();
+    let mut rows: Vec<PreviewRow> = query
+        .iter(&world)
+        .map(|(part_number, value, package, tolerance, description, mfr_parts)| PreviewRow {
+            part_number: part_number.0.clone(),
+            value: value.resistance,
+            formatted: value.formatted.clone(),
+            package: package.name.clone(),
+            tolerance: tolerance.0.clone(),
+            description: description.0.clone(),
+            mpns: mfr_parts.0.iter().map(|part| (part.manufacturer.clone(), part.mpn.clone())).collect(),
+        })
+        .collect();
+    rows.sort_by(|a, b| a.package.cmp(&b.package).then(a.value.cmp(&b.value)));
+    rows
+}
+
+/// Packages of `rows`, in order and without repeats
+pub fn packages(rows: &[PreviewRow]) -> Vec<String> {
+    let mut packages: Vec<String> = rows.iter().map(|row| row.package.clone()).collect();
+    packages.dedup();
+    packages
+}
+
+/// Which rows to show
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Filter {
+    /// Text anywhere in the part number, value, description or an MPN,
+    /// ignoring case. Empty matches every row.
+    pub search: String,
+    /// Only parts in this package
+    pub package: Option<String>,
+}
+
+impl Filter {
+    pub fn matches(&self, row: &PreviewRow) -> bool {
+        if self.package.as_ref().is_some_and(|package| *package != row.package) {
+            return false;
+        }
+        let search = self.search.trim().to_lowercase();
+        search.is_empty()
+            || [&row.part_number, &row.formatted, &row.description]
+                .into_iter()
+                .chain(row.mpns.iter().map(|(_, mpn)| mpn))
+                .any(|text| text.to_lowercase().contains(&search))
+    }
+}
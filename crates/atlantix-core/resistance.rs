@@ -0,0 +1,273 @@
+//! Typed resistance values.
+//!
+//! `Resistance` holds a value as an integer number of micro-ohms so downstream
+//! tools (Stencil export, BOM matching) can compare parts numerically instead
+//! of by their display string, while still rendering the familiar `4.99K` /
+//! `0R1` notation. Keeping the value integral means `9.76 * 10_000.0` style
+//! float products such as 97599.99999 never reach names or part numbers.
+//! Micro-ohms rather than milliohms, because the 1 mOhm decade still carries
+//! three significant digits (`2.15m`).
+//!
+//! Names and value fields can use the European inline-decimal notation
+//! instead (`4k70`, `2M21`), see [`ValueNotation`]. Distributor part numbers
+//! keep the notation the distributor uses.
+
+use crate::AtlantixError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// How values are written in part names and value fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValueNotation {
+    /// `4.70K`, `2.21M`, `100`
+    #[default]
+    Decimal,
+    /// The unit letter marks the decimal point: `4k70`, `2M21`, `100R`
+    European,
+}
+
+impl FromStr for ValueNotation {
+    type Err = AtlantixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "decimal" => Ok(ValueNotation::Decimal),
+            "european" | "rkm" => Ok(ValueNotation::European),
+            _ => Err(AtlantixError::Parse(format!("Unknown value notation '{}', expected decimal or european", s))),
+        }
+    }
+}
+
+impl fmt::Display for ValueNotation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValueNotation::Decimal => write!(f, "decimal"),
+            ValueNotation::European => write!(f, "european"),
+        }
+    }
+}
+
+/// Notation of names and values in the current [`crate::settings`]
+pub fn notation() -> ValueNotation {
+    crate::settings::with(|settings| settings.notation)
+}
+
+/// A resistance, stored as whole micro-ohms.
+///
+/// ```
+/// use component::Resistance;
+///
+/// let r: Resistance = "4k99".parse().unwrap();
+/// assert_eq!(r, Resistance::from_micro_ohms(4_990_000_000));
+/// assert_eq!(r.to_string(), "4.99K");
+/// assert!(r < "10 kΩ".parse().unwrap());
+///
+/// // Float products are snapped to the nearest micro-ohm
+/// assert_eq!(Resistance::from_ohms(9.76 * 10_000.0).micro_ohms(), 97_600_000_000);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Resistance(u64);
+
+impl Resistance {
+    /// Round an ohmic value to the nearest micro-ohm. Negative values clamp to zero.
+    pub fn from_ohms(ohms: f64) -> Self {
+        Resistance((ohms * 1_000_000.0).round().max(0.0) as u64)
+    }
+
+    pub fn from_micro_ohms(micro_ohms: u64) -> Self {
+        Resistance(micro_ohms)
+    }
+
+    pub fn micro_ohms(self) -> u64 {
+        self.0
+    }
+
+    /// Value in ohms
+    pub fn ohms(self) -> f64 {
+        self.0 as f64 / 1_000_000.0
+    }
+
+    /// Value written in `notation`
+    ///
+    /// ```
+    /// use component::resistance::ValueNotation;
+    /// use component::Resistance;
+    ///
+    /// let r = Resistance::from_ohms(4_700.0);
+    /// assert_eq!(r.format(ValueNotation::Decimal), "4.70K");
+    /// assert_eq!(r.format(ValueNotation::European), "4k70");
+    /// assert_eq!(Resistance::from_ohms(2_210_000.0).format(ValueNotation::European), "2M21");
+    /// assert_eq!(Resistance::from_ohms(100.0).format(ValueNotation::European), "100R");
+    /// assert_eq!(Resistance::from_ohms(0.00475).format(ValueNotation::European), "4m75");
+    /// ```
+    pub fn format(self, notation: ValueNotation) -> String {
+        let (digits, exponent) = self.significant_digits();
+        match notation {
+            ValueNotation::Decimal => crate::format_from_digits(digits, exponent),
+            ValueNotation::European => crate::european_from_digits(digits, exponent),
+        }
+    }
+
+    /// Value as it appears in part names and value fields, in the notation
+    /// of the current [`crate::settings`]
+    pub fn label(self) -> String {
+        self.format(notation())
+    }
+
+    /// Vishay style four character resistance code, e.g. `4K99` or `R100`
+    pub fn vishay_code(self) -> String {
+        let (digits, exponent) = self.significant_digits();
+        crate::vishay_code_from_digits(digits, exponent)
+    }
+
+    /// Three significant digits (100..=999) and the decade exponent of the
+    /// leading digit in ohms, computed without floating point.
+    pub(crate) fn significant_digits(self) -> (u32, i32) {
+        atlantix_mpn::digits::significant(self.0)
+    }
+}
+
+impl From<f64> for Resistance {
+    fn from(ohms: f64) -> Self {
+        Resistance::from_ohms(ohms)
+    }
+}
+
+impl fmt::Display for Resistance {
+    /// Renders with three significant digits: `4.99K`, `100`, `1.00M`, `0R1`, `10m`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (digits, exponent) = self.significant_digits();
+        f.write_str(&crate::format_from_digits(digits, exponent))
+    }
+}
+
+impl FromStr for Resistance {
+    type Err = AtlantixError;
+
+    /// Parses user supplied values such as "3.32k", "4k99", "4R7", "0R47",
+    /// "10m", "1M" or "10 kΩ", in either notation ("2M21", "100R", "4m75"
+    /// for European names). The decimal digits are scaled as integers, so
+    /// no float rounding is involved; precision beyond a micro-ohm is rounded.
+    ///
+    /// ```
+    /// use component::Resistance;
+    ///
+    /// assert_eq!("3.32k".parse::<Resistance>().unwrap(), Resistance::from_ohms(3320.0));
+    /// assert_eq!("100".parse::<Resistance>().unwrap(), Resistance::from_ohms(100.0));
+    /// assert_eq!("1M".parse::<Resistance>().unwrap(), Resistance::from_ohms(1_000_000.0));
+    /// assert_eq!("4R7".parse::<Resistance>().unwrap(), Resistance::from_ohms(4.7));
+    /// assert_eq!("0R47".parse::<Resistance>().unwrap(), Resistance::from_ohms(0.47));
+    /// assert_eq!("10m".parse::<Resistance>().unwrap(), Resistance::from_micro_ohms(10_000));
+    /// assert_eq!("2M21".parse::<Resistance>().unwrap(), Resistance::from_ohms(2_210_000.0));
+    /// assert_eq!("100R".parse::<Resistance>().unwrap(), Resistance::from_ohms(100.0));
+    /// assert!("abc".parse::<Resistance>().is_err());
+    /// ```
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || AtlantixError::Parse(format!("Invalid resistance value '{}'", value));
+        let cleaned = value.trim().trim_end_matches('Ω').replace(' ', "");
+        let letter = cleaned.find(|c: char| c.is_ascii_alphabetic());
+        // Power of ten from the unit to micro-ohms
+        let scale: u32 = match letter.map(|pos| &cleaned[pos..pos + 1]) {
+            Some("m") => 3,
+            Some("R") | Some("r") | None => 6,
+            Some("k") | Some("K") => 9,
+            Some("M") => 12,
+            Some(_) => return Err(invalid()),
+        };
+
+        // "4k99" uses the multiplier letter as the decimal point
+        let (integer, fraction) = match letter {
+            Some(pos) if pos + 1 < cleaned.len() && !cleaned.contains('.') => {
+                (&cleaned[..pos], &cleaned[pos + 1..])
+            }
+            Some(pos) => cleaned[..pos].split_once('.').unwrap_or((&cleaned[..pos], "")),
+            None => cleaned.split_once('.').unwrap_or((&cleaned, "")),
+        };
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(invalid());
+        }
+        if !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        let mantissa: u64 = format!("{}{}", integer, fraction).parse().map_err(|_| invalid())?;
+        let shift = scale as i64 - fraction.len() as i64;
+        let micro_ohms = if shift >= 0 {
+            mantissa.checked_mul(10u64.pow(shift as u32)).ok_or_else(invalid)?
+        } else {
+            let divisor = 10u64.checked_pow((-shift) as u32).ok_or_else(invalid)?;
+            (mantissa + divisor / 2) / divisor
+        };
+        Ok(Resistance(micro_ohms))
+    }
+}
+
+/// Values a generation run is trimmed to, both ends inclusive. A side
+/// without a bound ends where the standard 1 Ohm to 1 MOhm sweep does.
+///
+/// ```
+/// use component::resistance::ValueRange;
+/// use component::Resistance;
+///
+/// let range = ValueRange::parse(Some("10"), Some("1M")).unwrap();
+/// assert_eq!(range.decades(), [10.0, 100.0, 1_000.0, 10_000.0, 100_000.0, 1_000_000.0]);
+/// assert!(range.contains(Resistance::from_ohms(1_000_000.0)));
+/// assert!(!range.contains(Resistance::from_ohms(9.76)));
+///
+/// // Unbounded is the standard sweep
+/// assert_eq!(ValueRange::default().decades(), component::STANDARD_DECADES);
+/// assert!(ValueRange::parse(Some("1M"), Some("10")).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ValueRange {
+    pub min: Option<Resistance>,
+    pub max: Option<Resistance>,
+}
+
+impl ValueRange {
+    pub fn new(min: Option<Resistance>, max: Option<Resistance>) -> Result<Self, AtlantixError> {
+        if let (Some(min), Some(max)) = (min, max) {
+            if min > max {
+                return Err(AtlantixError::Invalid(format!("Minimum value {} is above the maximum {}", min, max)));
+            }
+        }
+        Ok(ValueRange { min, max })
+    }
+
+    /// Range of user supplied bounds such as "10" and "1M"
+    pub fn parse(min: Option<&str>, max: Option<&str>) -> Result<Self, AtlantixError> {
+        ValueRange::new(min.map(str::parse).transpose()?, max.map(str::parse).transpose()?)
+    }
+
+    /// Neither end is bounded
+    pub fn is_full(&self) -> bool {
+        self.min.is_none() && self.max.is_none()
+    }
+
+    pub fn contains(&self, value: Resistance) -> bool {
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value <= max)
+    }
+
+    /// Decades holding values of the range, a decade `d` holding `d` up to
+    /// `10 d`
+    pub fn decades(&self) -> Vec<f64> {
+        let lowest = self.min.unwrap_or(Resistance::from_ohms(crate::STANDARD_DECADES[0]));
+        // The standard sweep ends below 1 MOhm
+        let highest = self.max.unwrap_or(Resistance::from_ohms(999_999.0));
+        crate::EXTENDED_DECADES
+            .into_iter()
+            .filter(|&decade| Resistance::from_ohms(decade * 10.0) > lowest && Resistance::from_ohms(decade) <= highest)
+            .collect()
+    }
+
+    /// Every series value within the range
+    pub fn values(&self, base_values: &[f64]) -> Vec<Resistance> {
+        self.decades()
+            .into_iter()
+            .flat_map(|decade| base_values.iter().map(move |base| Resistance::from_ohms(base * decade)))
+            .filter(|&value| self.contains(value))
+            .collect()
+    }
+}
@@ -0,0 +1,208 @@
+//! Configuration of a generation run.
+//!
+//! Formats, naming, sourcing data, the part hook and how files are written
+//! are carried in one [`Settings`] value instead of process-wide state, so a
+//! server, the GUI and tests can run several configurations side by side.
+//! Front ends build the value from their options and run the work inside
+//! [`Settings::scope`]; the ECS pipeline takes it from its
+//! `GeneratorConfig`. Code outside any scope sees `Settings::default()`.
+//!
+//! Settings apply to the thread that entered the scope. Work handed to
+//! other threads takes [`current`] along and enters it there. Clones share
+//! the [`Run`] collecting written files, hook errors and truncated values,
+//! so those reports cover every thread of the run.
+//!
+//! ```
+//! use component::kicad_symbol::{self, KicadFormatVersion};
+//! use component::settings::Settings;
+//!
+//! let settings = Settings { symbol_format: KicadFormatVersion::V8, ..Settings::default() };
+//! assert_eq!(settings.scope(kicad_symbol::default_format), KicadFormatVersion::V8);
+//! assert_eq!(kicad_symbol::default_format(), KicadFormatVersion::V6);
+//! ```
+
+use crate::altium::Comment;
+use crate::atomic::{self, Change, OverwriteGuard};
+use crate::availability::AvailabilityMatrix;
+use crate::distributor::Distributor;
+use crate::hook::PartHook;
+use crate::ipn::Numbering;
+use crate::kicad_footprint::{DensityLevel, FabricationConfig, FootprintFormat, PackageSpec};
+use crate::kicad_symbol::{KicadFormatVersion, KicadSymbol};
+use crate::limits::{Limits, Truncation};
+use crate::naming::NameTemplate;
+use crate::order::PartOrder;
+use crate::package_naming::PackageNaming;
+use crate::resistance::ValueNotation;
+use crate::sourcing::{SourcingPolicy, StockInfo};
+use crate::thermal::Thermal;
+use chrono::{DateTime, Local};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+
+#[derive(Clone, Default)]
+pub struct Settings {
+    /// Grammar of symbols and libraries
+    pub symbol_format: KicadFormatVersion,
+    /// Grammar of the written `.kicad_mod` files
+    pub footprint_format: FootprintFormat,
+    pub density: DensityLevel,
+    /// Mask, paste and thermal rules of the fabrication house
+    pub fabrication: Option<FabricationConfig>,
+    /// Custom land patterns by imperial code, ahead of the built-in ones
+    pub packages: HashMap<String, PackageSpec>,
+    pub notation: ValueNotation,
+    pub package_naming: PackageNaming,
+    pub part_order: PartOrder,
+    /// Part name template, `None` for [`crate::naming::DEFAULT_PART`]
+    pub part_template: Option<NameTemplate>,
+    /// Library name template, `None` for [`crate::naming::DEFAULT_LIBRARY`]
+    pub library_template: Option<NameTemplate>,
+    /// Comment column of Altium rows
+    pub altium_comment: Comment,
+    /// Column limits by exporter, in place of the built-in ones
+    pub limits: HashMap<String, Limits>,
+    /// Distributors written as `Supplier 1`, `Supplier 2`, ... in that
+    /// order, empty for the single distributor of each generator
+    pub suppliers: Vec<Distributor>,
+    /// Product pages by distributor and distributor or manufacturer part number
+    pub product_pages: HashMap<(Distributor, String), String>,
+    pub sourcing: SourcingPolicy,
+    /// Stock by MPN, e.g. from cached distributor offers
+    pub stock: HashMap<String, StockInfo>,
+    /// Values the manufacturers make, `None` to generate every value
+    pub availability: Option<AvailabilityMatrix>,
+    /// Datasheet templates, see [`crate::datasheet::templates`]
+    pub datasheets: HashMap<String, String>,
+    /// Thermal ratings by package or package and MPN prefix, in place of the
+    /// built-in ones
+    pub thermal: Option<HashMap<String, Thermal>>,
+    /// Internal part numbering, shared by every clone
+    pub ipn: Option<Arc<Mutex<Numbering>>>,
+    /// Run once on every generated part, see [`crate::hook`]
+    pub hook: Option<Arc<dyn PartHook>>,
+    /// Memoize formatted ratings and MPNs, see [`crate::memo`]
+    pub memoize: bool,
+    /// Flush written files and their directory entry to disk
+    pub sync: bool,
+    /// Leave the disk untouched, only record what would have been written
+    pub dry_run: bool,
+    /// Consulted before a write replaces a file with different content
+    pub overwrite_guard: Option<OverwriteGuard>,
+    pub run: Arc<Run>,
+}
+
+impl fmt::Debug for Settings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Settings")
+            .field("symbol_format", &self.symbol_format)
+            .field("footprint_format", &self.footprint_format)
+            .field("density", &self.density)
+            .field("notation", &self.notation)
+            .field("package_naming", &self.package_naming)
+            .field("part_order", &self.part_order)
+            .field("part_template", &self.part_template)
+            .field("library_template", &self.library_template)
+            .field("suppliers", &self.suppliers)
+            .field("sourcing", &self.sourcing)
+            .field("hook", &self.hook.is_some())
+            .field("dry_run", &self.dry_run)
+            .finish_non_exhaustive()
+    }
+}
+
+/// What a run produced besides its files, shared by every clone of the
+/// [`Settings`] it started from
+#[derive(Default)]
+pub struct Run {
+    started: OnceLock<DateTime<Local>>,
+    pub(crate) changes: Mutex<Vec<(PathBuf, Change)>>,
+    pub(crate) archive: Mutex<Option<atomic::Archive>>,
+    pub(crate) hook_errors: Mutex<Vec<String>>,
+    /// What the hook made of each part, by generated name: the symbol it
+    /// saw and the one it kept
+    pub(crate) hooked: Mutex<HashMap<String, (KicadSymbol, Option<KicadSymbol>)>>,
+    pub(crate) truncations: Mutex<Vec<Truncation>>,
+}
+
+impl Run {
+    /// When the run first asked for its time, which every dated path shares
+    pub fn started(&self) -> DateTime<Local> {
+        *self.started.get_or_init(Local::now)
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<Settings>>> = const { RefCell::new(None) };
+    static DEFAULT: Arc<Settings> = Arc::new(Settings::default());
+}
+
+/// Restores the enclosing settings, also when the scope panics
+struct Restore(Option<Arc<Settings>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CURRENT.with_borrow_mut(|current| *current = self.0.take());
+        crate::memo::reset();
+    }
+}
+
+impl Settings {
+    /// Run `f` with these settings on the current thread
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        enter(Arc::new(self.clone()), f)
+    }
+}
+
+/// Run `f` with `settings` on the current thread, e.g. the [`current`]
+/// settings of the thread that spawned it
+pub fn enter<R>(settings: Arc<Settings>, f: impl FnOnce() -> R) -> R {
+    let previous = CURRENT.with_borrow_mut(|current| current.replace(settings));
+    crate::memo::reset();
+    let _restore = Restore(previous);
+    f()
+}
+
+/// Settings of the innermost scope on this thread
+pub fn current() -> Arc<Settings> {
+    CURRENT.with_borrow(|current| current.clone()).unwrap_or_else(|| DEFAULT.with(Arc::clone))
+}
+
+/// Read the current settings without taking a reference to them
+pub(crate) fn with<R>(f: impl FnOnce(&Settings) -> R) -> R {
+    CURRENT.with_borrow(|current| match current {
+        Some(settings) => f(settings),
+        None => DEFAULT.with(|settings| f(settings)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scopes_nest_and_restore() {
+        let european = Settings { notation: ValueNotation::European, ..Settings::default() };
+        european.scope(|| {
+            assert_eq!(crate::resistance::notation(), ValueNotation::European);
+            Settings::default().scope(|| assert_eq!(crate::resistance::notation(), ValueNotation::Decimal));
+            assert_eq!(crate::resistance::notation(), ValueNotation::European);
+        });
+        assert_eq!(crate::resistance::notation(), ValueNotation::Decimal);
+    }
+
+    #[test]
+    fn threads_enter_the_settings_they_are_given() {
+        let european = Settings { notation: ValueNotation::European, ..Settings::default() };
+        european.scope(|| {
+            let settings = current();
+            let notation = std::thread::spawn(move || enter(settings, crate::resistance::notation)).join().unwrap();
+            assert_eq!(notation, ValueNotation::European);
+            let unscoped = std::thread::spawn(crate::resistance::notation).join().unwrap();
+            assert_eq!(unscoped, ValueNotation::Decimal);
+        });
+    }
+}
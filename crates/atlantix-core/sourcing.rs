@@ -0,0 +1,119 @@
+//! Choice of the primary source among several manufacturers.
+//!
+//! With alternates enabled, every part lists the manufacturers in the order
+//! they were configured. With stock data from a distributor cache in the
+//! current [`crate::settings`], a [`SourcingPolicy`] can instead put the
+//! manufacturer with stock, or the cheapest one, first for each value. That
+//! manufacturer becomes the primary source in symbols and CSVs, and the rest
+//! follow as alternates.
+
+use crate::AtlantixError;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SourcingPolicy {
+    /// Manufacturers in the configured order
+    #[default]
+    Listed,
+    /// Parts in stock first, the largest stock leading
+    Stock,
+    /// Parts in stock first, the lowest unit price leading
+    Price,
+}
+
+impl FromStr for SourcingPolicy {
+    type Err = AtlantixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "listed" | "none" => Ok(SourcingPolicy::Listed),
+            "stock" => Ok(SourcingPolicy::Stock),
+            "price" => Ok(SourcingPolicy::Price),
+            _ => Err(AtlantixError::Parse(format!("Unknown sourcing policy '{}', expected listed, stock or price", s))),
+        }
+    }
+}
+
+impl fmt::Display for SourcingPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SourcingPolicy::Listed => write!(f, "listed"),
+            SourcingPolicy::Stock => write!(f, "stock"),
+            SourcingPolicy::Price => write!(f, "price"),
+        }
+    }
+}
+
+/// Policy of the current [`crate::settings`]
+pub fn policy() -> SourcingPolicy {
+    crate::settings::with(|settings| settings.sourcing)
+}
+
+/// Stock over all distributors and the lowest single unit price of an MPN
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StockInfo {
+    pub stock: i64,
+    pub unit_price: Option<f64>,
+}
+
+/// Stock of an MPN in the current settings
+pub fn stock(mpn: &str) -> Option<StockInfo> {
+    crate::settings::with(|settings| settings.stock.get(mpn).copied())
+}
+
+/// In stock, no data, out of stock or not made
+fn class(info: Option<StockInfo>, made: bool) -> u8 {
+    match (made, info) {
+        (false, _) => 3,
+        (true, Some(info)) if info.stock > 0 => 0,
+        (true, None) => 1,
+        (true, Some(_)) => 2,
+    }
+}
+
+/// Put `candidates` into the order of the current [`policy`]. `mpn` gives
+/// a candidate's part number, `None` when the manufacturer does not make
+/// the part. Candidates the policy cannot tell apart keep their order.
+///
+/// ```
+/// use component::settings::Settings;
+/// use component::sourcing::{rank, SourcingPolicy, StockInfo};
+///
+/// let mut settings = Settings::default();
+/// settings.stock.insert("CRCW06034K99FKEA".to_string(), StockInfo { stock: 0, unit_price: Some(0.10) });
+/// settings.stock.insert("RC0603FR-074K99L".to_string(), StockInfo { stock: 50_000, unit_price: Some(0.02) });
+/// let mut sources = vec![("Vishay", "CRCW06034K99FKEA"), ("Yageo", "RC0603FR-074K99L")];
+///
+/// settings.scope(|| rank(&mut sources, |(_, mpn)| Some(*mpn)));
+/// assert_eq!(sources[0].0, "Vishay");
+///
+/// settings.sourcing = SourcingPolicy::Stock;
+/// settings.scope(|| rank(&mut sources, |(_, mpn)| Some(*mpn)));
+/// assert_eq!(sources[0].0, "Yageo");
+/// ```
+pub fn rank<T>(candidates: &mut [T], mpn: impl Fn(&T) -> Option<&str>) {
+    let policy = policy();
+    if policy == SourcingPolicy::Listed {
+        return;
+    }
+    let key = |candidate: &T| {
+        let mpn = mpn(candidate);
+        let info = mpn.and_then(stock);
+        (class(info, mpn.is_some()), info)
+    };
+    candidates.sort_by(|a, b| {
+        let ((class_a, info_a), (class_b, info_b)) = (key(a), key(b));
+        class_a.cmp(&class_b).then_with(|| match (policy, info_a, info_b) {
+            (SourcingPolicy::Stock, Some(a), Some(b)) => b.stock.cmp(&a.stock),
+            (SourcingPolicy::Price, Some(a), Some(b)) => match (a.unit_price, b.unit_price) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+            _ => Ordering::Equal,
+        })
+    });
+}
@@ -0,0 +1,136 @@
+//! SPICE models for generated resistors.
+//!
+//! Every resistor becomes a `.subckt` named like its symbol, so KiCad's
+//! simulator and plain ngspice decks share one library. The ideal model is a
+//! single resistor. The parasitic model adds the series inductance and the
+//! parallel capacitance typical of a thick film chip in that package:
+//!
+//! ```text
+//! .subckt R0603_4.99K 1 2
+//! R1 1 3 4990
+//! L1 3 2 0.5n
+//! C1 1 2 0.05p
+//! .ends R0603_4.99K
+//! ```
+
+use crate::kicad_symbol::KicadFormatVersion;
+use crate::{AtlantixError, Resistance};
+use std::fmt;
+use std::str::FromStr;
+
+/// Which model each `.subckt` holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpiceModel {
+    #[default]
+    Ideal,
+    /// Package inductance and capacitance around the ideal resistor
+    Parasitic,
+}
+
+impl FromStr for SpiceModel {
+    type Err = AtlantixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "ideal" => Ok(SpiceModel::Ideal),
+            "parasitic" | "parasitics" => Ok(SpiceModel::Parasitic),
+            _ => Err(AtlantixError::Parse(format!("Unknown SPICE model '{}', expected ideal or parasitic", s))),
+        }
+    }
+}
+
+impl fmt::Display for SpiceModel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpiceModel::Ideal => write!(f, "ideal"),
+            SpiceModel::Parasitic => write!(f, "parasitic"),
+        }
+    }
+}
+
+/// Typical parasitics of a chip resistor package
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Parasitics {
+    pub inductance_nh: f64,
+    pub capacitance_pf: f64,
+}
+
+/// Parasitics of a package, `None` for packages without data
+pub fn parasitics(package: &str) -> Option<Parasitics> {
+    let (inductance_nh, capacitance_pf) = match package {
+        "0201" => (0.2, 0.02),
+        "0402" => (0.4, 0.03),
+        "0603" => (0.5, 0.05),
+        "0805" => (0.7, 0.06),
+        "1206" => (1.0, 0.08),
+        "1210" => (1.1, 0.1),
+        "2010" => (1.5, 0.12),
+        "2512" => (1.8, 0.15),
+        _ => return None,
+    };
+    Some(Parasitics { inductance_nh, capacitance_pf })
+}
+
+/// `.subckt` of one resistor. Packages without parasitic data get the
+/// ideal model.
+///
+/// ```
+/// use component::spice::{subckt, SpiceModel};
+/// use component::Resistance;
+///
+/// let ideal = subckt("R0603_4.99K", Resistance::from_ohms(4_990.0), "0603", SpiceModel::Ideal);
+/// assert_eq!(ideal, ".subckt R0603_4.99K 1 2\nR1 1 2 4990\n.ends R0603_4.99K\n");
+/// let parasitic = subckt("R0603_4.99K", Resistance::from_ohms(4_990.0), "0603", SpiceModel::Parasitic);
+/// assert!(parasitic.contains("R1 1 3 4990\nL1 3 2 0.5n\nC1 1 2 0.05p\n"));
+/// ```
+pub fn subckt(name: &str, resistance: Resistance, package: &str, model: SpiceModel) -> String {
+    let ohms = resistance.ohms();
+    let body = match (model, parasitics(package)) {
+        (SpiceModel::Parasitic, Some(p)) => format!(
+            "R1 1 3 {}\nL1 3 2 {}n\nC1 1 2 {}p\n",
+            ohms, p.inductance_nh, p.capacitance_pf
+        ),
+        _ => format!("R1 1 2 {}\n", ohms),
+    };
+    format!(".subckt {name} 1 2\n{body}.ends {name}\n", name = name, body = body)
+}
+
+/// A `.lib` file holding the given `.subckt`s
+pub fn library(title: &str, subckts: &[String]) -> String {
+    let mut lib = format!("* {}\n* Generated by atlantix-eda\n\n", title);
+    for subckt in subckts {
+        lib.push_str(subckt);
+        lib.push('\n');
+    }
+    lib
+}
+
+/// Symbol fields that point KiCad's simulator at subckt `name` in `library`.
+/// KiCad 6 reads the `Spice_*` fields, KiCad 7 and later the `Sim.*` fields.
+///
+/// ```
+/// use component::kicad_symbol::KicadFormatVersion;
+/// use component::spice::symbol_fields;
+///
+/// let fields = symbol_fields(KicadFormatVersion::V8, "Atlantix_R_0603.lib", "R0603_4.99K");
+/// assert!(fields.contains(&("Sim.Device".to_string(), "SUBCKT".to_string())));
+/// let fields = symbol_fields(KicadFormatVersion::V6, "Atlantix_R_0603.lib", "R0603_4.99K");
+/// assert!(fields.contains(&("Spice_Model".to_string(), "R0603_4.99K".to_string())));
+/// ```
+pub fn symbol_fields(format: KicadFormatVersion, library: &str, name: &str) -> Vec<(String, String)> {
+    let fields = match format {
+        KicadFormatVersion::V6 => [
+            ("Spice_Primitive", "X"),
+            ("Spice_Model", name),
+            ("Spice_Lib_File", library),
+            ("Spice_Netlist_Enabled", "Y"),
+        ],
+        _ => [
+            ("Sim.Library", library),
+            ("Sim.Name", name),
+            ("Sim.Device", "SUBCKT"),
+            ("Sim.Pins", "1=1 2=2"),
+        ],
+    };
+    fields.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+}
@@ -0,0 +1,42 @@
+//! Stackpole RMCF thick film chip resistor part numbers.
+//!
+//! `RMCF0603FT4K99` reads as: series and size (`RMCF0603`), tolerance (`F` =
+//! 1%, `J` = 5%), tape and reel packaging (`T`) and the resistance, three
+//! significant digits with R, K or M as the decimal point (`4K99`, `10K0`,
+//! `100R`, `R470`), the same code Vishay uses.
+
+use crate::Resistance;
+
+const PACKAGES: [&str; 8] = ["0201", "0402", "0603", "0805", "1206", "1210", "2010", "2512"];
+
+fn tolerance_code(tolerance: &str) -> Option<char> {
+    match tolerance {
+        "1%" | "2%" => Some('F'),
+        "5%" | "10%" | "20%" => Some('J'),
+        _ => None,
+    }
+}
+
+/// RMCF part number, `None` for sizes or tolerances outside the series.
+///
+/// ```
+/// use component::{stackpole, Resistance};
+///
+/// let r = Resistance::from_ohms(4_990.0);
+/// assert_eq!(stackpole::rmcf_mpn("0603", r, "1%").as_deref(), Some("RMCF0603FT4K99"));
+/// assert_eq!(stackpole::rmcf_mpn("1206", Resistance::from_ohms(10_000.0), "5%").as_deref(), Some("RMCF1206JT10K0"));
+/// assert_eq!(stackpole::rmcf_mpn("0805", Resistance::from_ohms(0.47), "1%").as_deref(), Some("RMCF0805FTR470"));
+/// assert_eq!(stackpole::rmcf_mpn("0603", r, "0.1%"), None);
+/// ```
+pub fn rmcf_mpn(package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+    if !PACKAGES.contains(&package) {
+        return None;
+    }
+    let tolerance = tolerance_code(tolerance)?;
+    Some(format!("RMCF{}{}T{}", package, tolerance, resistance.vishay_code()))
+}
+
+/// Digi-Key cut tape part number, e.g. `RMCF0603FT4K99CT-ND`
+pub fn digikey_pn(package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+    rmcf_mpn(package, resistance, tolerance).map(|mpn| format!("{}CT-ND", mpn))
+}
@@ -0,0 +1,48 @@
+//! Review status of generated parts and libraries.
+//!
+//! Controlled library processes track whether a part has been reviewed before
+//! it may be used in a design. The status is persisted in the library manifest
+//! and stamped into generated symbols as a hidden `Status` property.
+
+use crate::AtlantixError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PartStatus {
+    /// Generated or requested, not yet reviewed
+    #[default]
+    Draft,
+    /// Reviewed and released for use in designs
+    Approved,
+    /// Kept for existing designs, not to be used in new ones
+    Deprecated,
+}
+
+impl fmt::Display for PartStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PartStatus::Draft => write!(f, "Draft"),
+            PartStatus::Approved => write!(f, "Approved"),
+            PartStatus::Deprecated => write!(f, "Deprecated"),
+        }
+    }
+}
+
+impl FromStr for PartStatus {
+    type Err = AtlantixError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "draft" => Ok(PartStatus::Draft),
+            "approved" => Ok(PartStatus::Approved),
+            "deprecated" => Ok(PartStatus::Deprecated),
+            _ => Err(AtlantixError::Parse(format!(
+                "Unknown part status '{}'. Expected draft, approved or deprecated",
+                s
+            ))),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Susumu RG thin film precision chip resistor part numbers.
+//!
+//! `RG1608P-4991-B-T5` reads as: series and metric size (`RG1608` = 0603),
+//! TCR (`P` = 25 ppm/K), resistance as a 4-digit code (`4991` = 4.99K, `49R9`
+//! = 49.9 Ohm), tolerance (`B` = 0.1%) and tape quantity (`T5` = 5000 pieces).
+
+use crate::marking::digit_code;
+use crate::Resistance;
+
+/// Metric size code and tape quantity
+fn size(package: &str) -> Option<(&'static str, &'static str)> {
+    match package {
+        "0402" => Some(("1005", "T10")),
+        "0603" => Some(("1608", "T5")),
+        "0805" => Some(("2012", "T5")),
+        "1206" => Some(("3216", "T5")),
+        _ => None,
+    }
+}
+
+fn tolerance_code(tolerance: &str) -> Option<char> {
+    match tolerance {
+        "0.05%" => Some('W'),
+        "0.1%" => Some('B'),
+        "0.5%" => Some('D'),
+        _ => None,
+    }
+}
+
+/// RG part number, `None` for sizes or tolerances outside the series.
+///
+/// ```
+/// use component::{susumu, Resistance};
+///
+/// let r = Resistance::from_ohms(4_990.0);
+/// assert_eq!(susumu::rg_mpn("0603", r, "0.1%").as_deref(), Some("RG1608P-4991-B-T5"));
+/// assert_eq!(susumu::rg_mpn("0402", Resistance::from_ohms(49.9), "0.05%").as_deref(), Some("RG1005P-49R9-W-T10"));
+/// assert_eq!(susumu::rg_mpn("0603", r, "1%"), None);
+/// ```
+pub fn rg_mpn(package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+    let (metric, tape) = size(package)?;
+    let tolerance = tolerance_code(tolerance)?;
+    let value = digit_code(resistance, 4)?;
+    Some(format!("RG{}P-{}-{}-{}", metric, value, tolerance, tape))
+}
+
+/// Digi-Key cut tape part number, e.g. `RG16P4.99KBCT-ND`.
+///
+/// ```
+/// use component::{susumu, Resistance};
+///
+/// let r = Resistance::from_ohms(4_990.0);
+/// assert_eq!(susumu::digikey_pn("0603", r, "0.1%").as_deref(), Some("RG16P4.99KBCT-ND"));
+/// ```
+pub fn digikey_pn(package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+    let (metric, _) = size(package)?;
+    let tolerance = tolerance_code(tolerance)?;
+    Some(format!("RG{}P{}{}CT-ND", &metric[..2], resistance, tolerance))
+}
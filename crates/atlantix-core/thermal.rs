@@ -0,0 +1,106 @@
+//! Thermal ratings of resistor packages and series.
+//!
+//! Chip resistors dissipate their rated power up to a 70 °C ambient and are
+//! derated linearly to zero at their upper category temperature, 155 °C for
+//! the thick and thin film series generated here. The built-in thermal
+//! resistance of a package follows from that curve: the rated power heats
+//! the part by the 85 °C between knee and maximum. Measured values replace
+//! the built-in ones per package, or per package and MPN prefix for a series
+//! (`0603/TNPW`), through the `thermal` settings. Generated resistor symbols carry
+//! both ratings as fields, and [`Thermal::derated_power`] answers power
+//! budget questions for a given ambient.
+
+use crate::power::{chip_resistor_power, Power};
+use std::collections::HashMap;
+
+/// Ambient temperature up to which the full rated power applies, °C
+pub const DERATING_KNEE: f64 = 70.0;
+
+/// Upper category temperature of the built-in series, °C
+pub const MAX_TEMPERATURE: f64 = 155.0;
+
+/// Thermal ratings of a part
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Thermal {
+    /// Temperature rise per watt dissipated, °C/W
+    pub resistance: f64,
+    /// Upper category temperature, °C
+    pub max_temperature: f64,
+}
+
+impl Thermal {
+    /// Power a part rated `rated` may dissipate at `ambient` °C
+    ///
+    /// ```
+    /// use component::{thermal, Power};
+    ///
+    /// let chip = thermal::thermal("0603", "CRCW06034K99FKEA").unwrap();
+    /// assert_eq!(chip.resistance, 850.0);
+    /// assert_eq!(chip.derated_power(Power(100), 70.0), Power(100));
+    /// assert_eq!(chip.derated_power(Power(100), 112.5), Power(50));
+    /// assert_eq!(chip.derated_power(Power(100), 155.0), Power(0));
+    /// ```
+    pub fn derated_power(&self, rated: Power, ambient: f64) -> Power {
+        if ambient <= DERATING_KNEE {
+            return rated;
+        }
+        let span = self.max_temperature - DERATING_KNEE;
+        if span <= 0.0 || ambient >= self.max_temperature {
+            return Power(0);
+        }
+        Power::from_watts(rated.watts() * (self.max_temperature - ambient) / span)
+    }
+
+    /// Temperature of a part dissipating `dissipation` at `ambient` °C
+    pub fn hot_spot(&self, dissipation: Power, ambient: f64) -> f64 {
+        ambient + dissipation.watts() * self.resistance
+    }
+}
+
+/// Ratings following from the derating curve of a standard package
+fn built_in(package: &str) -> Option<Thermal> {
+    let rated = chip_resistor_power(package)?;
+    Some(Thermal {
+        resistance: ((MAX_TEMPERATURE - DERATING_KNEE) / rated.watts()).round(),
+        max_temperature: MAX_TEMPERATURE,
+    })
+}
+
+/// Ratings of a package, the built-in ones for configured entries
+/// completing a partial override
+pub fn package_thermal(package: &str) -> Option<Thermal> {
+    built_in(package)
+}
+
+/// Thermal ratings of the part `mpn` in `package`: its series entry, the
+/// longest matching MPN prefix, then the package entry, then the built-in
+/// ratings of the package. Entries come from the current
+/// [`crate::settings`], keyed by package (`0603`) or package and MPN prefix
+/// (`0603/TNPW`).
+pub fn thermal(package: &str, mpn: &str) -> Option<Thermal> {
+    let configured = crate::settings::with(|settings| configured(settings.thermal.as_ref()?, package, mpn));
+    configured.or_else(|| built_in(package))
+}
+
+/// Configured entry of a part: its series entry, then the package entry
+fn configured(ratings: &HashMap<String, Thermal>, package: &str, mpn: &str) -> Option<Thermal> {
+    let series = ratings
+        .iter()
+        .filter_map(|(key, thermal)| {
+            let (key_package, prefix) = key.split_once('/')?;
+            (key_package == package && mpn.starts_with(prefix)).then_some((prefix.len(), thermal))
+        })
+        .max_by_key(|(length, _)| *length);
+    match series {
+        Some((_, thermal)) => Some(*thermal),
+        None => ratings.get(package).copied(),
+    }
+}
+
+/// `Thermal Resistance` and `Max Temperature` symbol fields
+pub fn symbol_fields(thermal: &Thermal) -> [(String, String); 2] {
+    [
+        ("Thermal Resistance".to_string(), format!("{} °C/W", thermal.resistance)),
+        ("Max Temperature".to_string(), format!("{} °C", thermal.max_temperature)),
+    ]
+}
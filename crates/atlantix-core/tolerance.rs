@@ -0,0 +1,49 @@
+//! Resistor tolerances.
+//!
+//! Every E-series has a customary tolerance (E96 is 1%, E24 is 5%). The
+//! precision range, 0.1% and 0.05%, is only made in thin film, so choosing
+//! one of those tolerances switches the part numbers to thin-film series
+//! such as Vishay TNPW and Susumu RG.
+
+use crate::AtlantixError;
+
+/// Tolerances the generators know, tightest first
+pub const TOLERANCES: [&str; 10] = ["0.05%", "0.1%", "0.25%", "0.5%", "1%", "2%", "5%", "10%", "20%", "50%"];
+
+/// Customary tolerance of an E-series given by its number of values
+pub fn for_series(series: usize) -> &'static str {
+    match series {
+        192 => "0.5%",
+        96 => "1%",
+        48 => "2%",
+        24 => "5%",
+        12 => "10%",
+        6 => "20%",
+        3 => "50%",
+        _ => "1%",
+    }
+}
+
+/// Parse a tolerance with or without the percent sign.
+///
+/// ```
+/// use component::tolerance;
+///
+/// assert_eq!(tolerance::parse("0.1").unwrap(), "0.1%");
+/// assert_eq!(tolerance::parse(" 0.05 % ").unwrap(), "0.05%");
+/// assert_eq!(tolerance::parse("1%").unwrap(), "1%");
+/// assert!(tolerance::parse("3").is_err());
+/// ```
+pub fn parse(value: &str) -> Result<&'static str, AtlantixError> {
+    let number = value.trim().trim_end_matches('%').trim();
+    TOLERANCES
+        .iter()
+        .find(|tolerance| tolerance.trim_end_matches('%') == number)
+        .copied()
+        .ok_or_else(|| AtlantixError::Parse(format!("Unknown tolerance '{}'. Expected one of: {}", value, TOLERANCES.join(", "))))
+}
+
+/// Tolerances only made in thin film
+pub fn is_thin_film(tolerance: &str) -> bool {
+    matches!(tolerance, "0.05%" | "0.1%")
+}
@@ -0,0 +1,58 @@
+//! Vishay Dale CRCW thick film chip resistor part numbers.
+//!
+//! `CRCW06034K99FKEA` reads as: series and size (`CRCW0603`), resistance
+//! (`4K99`), tolerance (`F` = 1%), TCR (`K` = 100 ppm/K), packaging (`EA`
+//! paper tape, lead free). 5% and looser parts are `JNEA`, 200 ppm/K.
+//!
+//! Precision parts come from the TNPW thin film series, numbered the same
+//! way: `TNPW06034K99BEEA` is 0.1% (`B`, `A` for 0.05%) at 25 ppm/K (`E`).
+//!
+//! The part numbers are built by [`atlantix_mpn::vishay`], which also
+//! decodes them, for tools that need them without the generators.
+
+use crate::Resistance;
+
+/// CRCW part number, `None` for sizes outside the series.
+///
+/// ```
+/// use component::{vishay, Resistance};
+///
+/// let r = Resistance::from_ohms(4_990.0);
+/// assert_eq!(vishay::crcw_mpn("0603", r, "1%").as_deref(), Some("CRCW06034K99FKEA"));
+/// assert_eq!(vishay::crcw_mpn("0603", r, "5%").as_deref(), Some("CRCW06034K99JNEA"));
+/// assert_eq!(vishay::crcw_mpn("MiniMELF", r, "1%"), None);
+/// ```
+pub fn crcw_mpn(package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+    atlantix_mpn::vishay::crcw_mpn(package, resistance.micro_ohms(), tolerance)
+}
+
+/// TNPW thin film part number, `None` for sizes outside the series or
+/// tolerances other than 0.1% and 0.05%.
+///
+/// ```
+/// use component::{vishay, Resistance};
+///
+/// let r = Resistance::from_ohms(4_990.0);
+/// assert_eq!(vishay::tnpw_mpn("0603", r, "0.1%").as_deref(), Some("TNPW06034K99BEEA"));
+/// assert_eq!(vishay::tnpw_mpn("0805", r, "0.05%").as_deref(), Some("TNPW08054K99AEEA"));
+/// assert_eq!(vishay::tnpw_mpn("0603", r, "1%"), None);
+/// ```
+pub fn tnpw_mpn(package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+    atlantix_mpn::vishay::tnpw_mpn(package, resistance.micro_ohms(), tolerance)
+}
+
+/// Digi-Key cut tape part number. Digi-Key lists 5% parts under the MPN
+/// and doubles the size letter for values below 10 Ohm (`541-4.99HHCT-ND`).
+/// Sub-ohm values have no known Digi-Key number.
+///
+/// ```
+/// use component::{vishay, Resistance};
+///
+/// assert_eq!(vishay::digikey_pn("0603", Resistance::from_ohms(4_990.0), "1%").as_deref(), Some("541-4.99KHCT-ND"));
+/// assert_eq!(vishay::digikey_pn("0603", Resistance::from_ohms(4.99), "1%").as_deref(), Some("541-4.99HHCT-ND"));
+/// assert_eq!(vishay::digikey_pn("0603", Resistance::from_ohms(0.1), "1%"), None);
+/// assert_eq!(vishay::digikey_pn("0603", Resistance::from_ohms(4_700.0), "5%").as_deref(), Some("541-CRCW06034K70JNEACT-ND"));
+/// ```
+pub fn digikey_pn(package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+    atlantix_mpn::vishay::digikey_pn(package, resistance.micro_ohms(), tolerance)
+}
@@ -0,0 +1,144 @@
+//! Yageo RC-series thick film chip resistor part numbers.
+//!
+//! `RC0603FR-074K99L` reads as: series and size (`RC0603`), tolerance
+//! (`F` = 1%), packaging (`R` paper or `K` embossed tape), reel size (`07` =
+//! 7 inch), resistance (`4K99`, with R, K or M marking the decimal point and
+//! trailing zeros dropped) and `L` for lead free.
+
+use crate::Resistance;
+
+/// Reel size part of the packaging code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Reel {
+    #[default]
+    Inch7,
+    Inch10,
+    Inch13,
+}
+
+impl Reel {
+    fn code(self) -> &'static str {
+        match self {
+            Reel::Inch7 => "07",
+            Reel::Inch10 => "10",
+            Reel::Inch13 => "13",
+        }
+    }
+}
+
+/// Yageo resistance code: `4K99`, `10K`, `100R`, `49R9`, `1M`, `0R47`
+///
+/// ```
+/// use component::{yageo, Resistance};
+///
+/// assert_eq!(yageo::value_code(Resistance::from_ohms(4_990.0)), "4K99");
+/// assert_eq!(yageo::value_code(Resistance::from_ohms(10_000.0)), "10K");
+/// assert_eq!(yageo::value_code(Resistance::from_ohms(100.0)), "100R");
+/// assert_eq!(yageo::value_code(Resistance::from_ohms(49.9)), "49R9");
+/// assert_eq!(yageo::value_code(Resistance::from_ohms(1_000_000.0)), "1M");
+/// assert_eq!(yageo::value_code(Resistance::from_ohms(0.047)), "0R047");
+/// ```
+pub fn value_code(resistance: Resistance) -> String {
+    let (digits, exponent) = resistance.significant_digits();
+    let digits = digits.to_string();
+    if exponent < 0 {
+        let zeros = "0".repeat((-1 - exponent) as usize);
+        return format!("0R{}{}", zeros, digits.trim_end_matches('0'));
+    }
+    let group = (exponent / 3).min(2);
+    let letter = ["R", "K", "M"][group as usize];
+    let integer_digits = (exponent - 3 * group + 1) as usize;
+    if integer_digits >= digits.len() {
+        let zeros = "0".repeat(integer_digits - digits.len());
+        return format!("{}{}{}", digits, zeros, letter);
+    }
+    let (integer, fraction) = digits.split_at(integer_digits);
+    format!("{}{}{}", integer, letter, fraction.trim_end_matches('0'))
+}
+
+/// Tolerance letter for a tolerance such as "1%", `None` for tolerances
+/// Yageo does not make, e.g. the 2% of E48
+fn tolerance_code(tolerance: &str) -> Option<char> {
+    match tolerance {
+        "0.1%" => Some('B'),
+        "0.5%" => Some('D'),
+        "1%" => Some('F'),
+        "5%" => Some('J'),
+        _ => None,
+    }
+}
+
+/// RC-series part number on a 7 inch reel, `None` for tolerances outside
+/// the series.
+///
+/// ```
+/// use component::{yageo, Resistance};
+///
+/// let r = Resistance::from_ohms(4_990.0);
+/// assert_eq!(yageo::rc_mpn("0603", r, "1%").as_deref(), Some("RC0603FR-074K99L"));
+/// assert_eq!(yageo::rc_mpn("2512", Resistance::from_ohms(10.0), "5%").as_deref(), Some("RC2512JK-0710RL"));
+/// assert_eq!(yageo::rc_mpn("0603", r, "2%"), None);
+/// ```
+pub fn rc_mpn(package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+    rc_mpn_on_reel(package, resistance, tolerance, Reel::default())
+}
+
+/// RC-series part number for a given reel size
+pub fn rc_mpn_on_reel(package: &str, resistance: Resistance, tolerance: &str, reel: Reel) -> Option<String> {
+    // The large sizes come on embossed tape, everything else on paper tape
+    let packaging = match package {
+        "2010" | "2512" => 'K',
+        _ => 'R',
+    };
+    Some(format!(
+        "RC{}{}{}-{}{}L",
+        package,
+        tolerance_code(tolerance)?,
+        packaging,
+        reel.code(),
+        value_code(resistance)
+    ))
+}
+
+/// AC-series part number, the AEC-Q200 qualified counterpart of RC made in
+/// 1% and 5%.
+///
+/// ```
+/// use component::{yageo, Resistance};
+///
+/// let r = Resistance::from_ohms(4_990.0);
+/// assert_eq!(yageo::ac_mpn("0603", r, "1%").as_deref(), Some("AC0603FR-074K99L"));
+/// assert_eq!(yageo::ac_mpn("0603", r, "0.1%"), None);
+/// ```
+pub fn ac_mpn(package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+    if !matches!(tolerance_code(tolerance), Some('F' | 'J')) {
+        return None;
+    }
+    let rc = rc_mpn(package, resistance, tolerance)?;
+    Some(format!("AC{}", &rc[2..]))
+}
+
+/// Digi-Key cut tape part number for 1% RC-series parts, e.g.
+/// `311-4.99KHRCT-ND`. `None` for other tolerances and sizes, which
+/// Digi-Key lists under their manufacturer part number.
+///
+/// ```
+/// use component::{yageo, Resistance};
+///
+/// let r = Resistance::from_ohms(4_990.0);
+/// assert_eq!(yageo::digikey_pn("0603", r, "1%").as_deref(), Some("311-4.99KHRCT-ND"));
+/// assert_eq!(yageo::digikey_pn("0603", r, "5%"), None);
+/// ```
+pub fn digikey_pn(package: &str, resistance: Resistance, tolerance: &str) -> Option<String> {
+    if tolerance_code(tolerance) != Some('F') {
+        return None;
+    }
+    let size = match package {
+        "0402" => 'L',
+        "0603" => 'H',
+        "0805" => 'C',
+        "1206" => 'F',
+        _ => return None,
+    };
+    Some(format!("311-{}{}RCT-ND", resistance, size))
+}
@@ -0,0 +1,507 @@
+//! The main window: a menu bar, a dock of tabs and what they share
+
+use component::dashboard::Summary;
+use component::ecs::components::{ESeries, Package, PartNumber, Tolerance};
+use component::ecs::resources::ESeriesCache;
+use component::ferrite::{FerriteBead, FERRITE_IMPEDANCES, FERRITE_PACKAGES};
+use component::gui::family::{self, Family};
+use component::gui::dashboard;
+use component::gui::output;
+use component::gui::logs::LogBuffer;
+use component::gui::{AppConfig, LogPanel, PartTable};
+use component::inductor::{Inductor, INDUCTOR_DECADES, INDUCTOR_PACKAGES};
+use component::led::{Led, LedColor, LED_PACKAGES};
+use component::AtlantixError;
+use egui_dock::{DockArea, DockState, Style};
+use egui_file_dialog::{DialogMode, FileDialog};
+use std::path::{Path, PathBuf};
+use std::thread::JoinHandle;
+
+/// Operations of the file dialog
+const EXPORT_LOGS: &str = "export_logs";
+const LOAD_CONFIG: &str = "load_config";
+const SAVE_CONFIG: &str = "save_config";
+
+/// Tabs of the dock
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tab {
+    Preview,
+    Dashboard,
+    Logs,
+}
+
+pub struct App {
+    dock: DockState<Tab>,
+    state: State,
+}
+
+/// Everything the tabs show, apart from the dock holding them
+struct State {
+    config: AppConfig,
+    /// Keeps the configuration between runs and its config.toml the
+    /// profiles; generated files go into it when the configuration names no
+    /// output directory
+    data_dir: PathBuf,
+    /// Name typed into File > Save as Profile
+    profile_name: String,
+    table: PartTable,
+    /// Configuration the table was generated from, `None` before the first
+    /// preview
+    previewed: Option<AppConfig>,
+    logs: LogPanel,
+    /// Health of the data directory, read again when the Dashboard tab is
+    /// next shown after it is cleared
+    summary: Option<Result<Summary, String>>,
+    /// Generation writing the files, logging into the Logs tab as it goes
+    worker: Option<JoinHandle<()>>,
+    file_dialog: FileDialog,
+}
+
+impl App {
+    /// A window showing `logs`, the buffer [`component::gui::logs::capture`]
+    /// fills, starting from the configuration last used with `data_dir`
+    pub fn new(logs: LogBuffer, data_dir: PathBuf) -> Self {
+        App {
+            dock: DockState::new(vec![Tab::Preview, Tab::Dashboard, Tab::Logs]),
+            state: State {
+                config: AppConfig::load_last(&data_dir),
+                data_dir,
+                profile_name: String::new(),
+                table: PartTable::default(),
+                previewed: None,
+                logs: LogPanel::new(logs),
+                summary: None,
+                worker: None,
+                file_dialog: FileDialog::new(),
+            },
+        }
+    }
+
+    /// Lay out one frame
+    fn ui(&mut self, ctx: &egui::Context) {
+        egui::TopBottomPanel::top("menu").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                ui.menu_button("File", |ui| {
+                    self.state.file_menu(ui);
+                    ui.separator();
+                    if ui.button("Quit").clicked() {
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                });
+                let running = self.state.worker.is_some();
+                if ui.add_enabled(!running, egui::Button::new("Generate")).clicked() {
+                    self.state.generate();
+                }
+                if running {
+                    ui.spinner();
+                }
+            });
+        });
+        egui::SidePanel::left("configuration").resizable(true).show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                family::settings_ui(ui, &mut self.state.config);
+                ui.separator();
+                output::settings_ui(ui, &mut self.state.config);
+            });
+        });
+        self.state.poll_worker(ctx);
+        self.state.refresh_preview();
+        DockArea::new(&mut self.dock)
+            .style(Style::from_egui(ctx.style().as_ref()))
+            .show_close_buttons(false)
+            .show(ctx, &mut self.state);
+        self.state.file_dialog(ctx);
+    }
+}
+
+impl eframe::App for App {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.ui(ctx);
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        if let Err(e) = self.state.config.save_last(&self.state.data_dir) {
+            eprintln!("Error: {}", e);
+        }
+    }
+}
+
+impl State {
+    /// Generate the preview again when the configuration changed since the
+    /// last one
+    fn refresh_preview(&mut self) {
+        if self.previewed.as_ref() == Some(&self.config) {
+            return;
+        }
+        // The preview runs the resistor pipeline, other families have none
+        let rows = match self.config.family {
+            Family::Resistors => {
+                let packages: Vec<&str> = self.config.packages.iter().map(String::as_str).collect();
+                let tolerance = self.config.resistor_tolerance().unwrap_or_default();
+                component::preview::generate_with_tolerance(&self.config.generator_config(), self.config.series, tolerance, &packages)
+            }
+            _ => Vec::new(),
+        };
+        self.table.set_rows(rows);
+        self.previewed = Some(self.config.clone());
+    }
+
+    /// Write the libraries of the configuration on a worker thread, into
+    /// `outputs` of the data directory unless the configuration names a
+    /// directory
+    fn generate(&mut self) {
+        let mut config = self.config.clone();
+        let output_dir = config.output.get_or_insert_with(|| self.data_dir.join("outputs")).clone();
+        log::info!("Generating {} in {} into {}", config.family, config.packages.join(", "), output_dir.display());
+        self.worker = Some(std::thread::spawn(move || match config.family {
+            Family::Resistors => generate_resistors(&config),
+            _ => {
+                if let Err(e) = config.settings().unwrap_or_default().scope(|| generate_family(&config, &output_dir)) {
+                    log::error!("{}", e);
+                }
+            }
+        }));
+    }
+
+    /// Take the worker back once it is done, repainting until then so its
+    /// log shows up as it is written
+    fn poll_worker(&mut self, ctx: &egui::Context) {
+        let Some(worker) = self.worker.take_if(|worker| worker.is_finished()) else {
+            if self.worker.is_some() {
+                ctx.request_repaint_after(std::time::Duration::from_millis(100));
+            }
+            return;
+        };
+        if worker.join().is_err() {
+            log::error!("Generation stopped unexpectedly");
+        }
+        self.summary = None;
+    }
+
+    /// Save and load the configuration as a file anywhere on disk or as a
+    /// profile of the CLI's config.toml
+    fn file_menu(&mut self, ui: &mut egui::Ui) {
+        if ui.button("Load Configuration…").clicked() {
+            let _ = self.file_dialog.open(DialogMode::SelectFile, true, Some(LOAD_CONFIG));
+            ui.close_menu();
+        }
+        if ui.button("Save Configuration…").clicked() {
+            let _ = self.file_dialog.open(DialogMode::SaveFile, true, Some(SAVE_CONFIG));
+            ui.close_menu();
+        }
+        ui.separator();
+        ui.menu_button("Load Profile", |ui| match AppConfig::profile_names(&self.data_dir) {
+            Ok(names) if names.is_empty() => {
+                ui.label("config.toml has no [profiles]");
+            }
+            Ok(names) => {
+                for name in names {
+                    if ui.button(&name).clicked() {
+                        match AppConfig::from_profile(&self.data_dir, &name) {
+                            Ok(config) => {
+                                self.config = config;
+                                log::info!("Loaded profile '{}'", name);
+                            }
+                            Err(e) => log::error!("{}", e),
+                        }
+                        ui.close_menu();
+                    }
+                }
+            }
+            Err(e) => {
+                ui.label(e.to_string());
+            }
+        });
+        ui.menu_button("Save as Profile", |ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.profile_name).hint_text("name"));
+            let name = self.profile_name.trim().to_string();
+            if ui.add_enabled(!name.is_empty(), egui::Button::new("Save")).clicked() {
+                match self.config.save_profile(&self.data_dir, &name) {
+                    Ok(()) => log::info!("Saved profile '{}' to {}", name, self.data_dir.join("config.toml").display()),
+                    Err(e) => log::error!("{}", e),
+                }
+                ui.close_menu();
+            }
+        });
+    }
+
+    /// Show the file dialog while it is open and act on the path picked
+    fn file_dialog(&mut self, ctx: &egui::Context) {
+        self.file_dialog.update(ctx);
+        let operation = self.file_dialog.operation_id().map(str::to_string);
+        let Some(path) = self.file_dialog.take_selected() else {
+            return;
+        };
+        let done = match operation.as_deref() {
+            Some(EXPORT_LOGS) => self.logs.export(&path).map(|()| "Saved the log to"),
+            Some(LOAD_CONFIG) => AppConfig::load(&path).map(|config| {
+                self.config = config;
+                "Loaded the configuration from"
+            }),
+            Some(SAVE_CONFIG) => self.config.save(&path).map(|()| "Saved the configuration to"),
+            _ => return,
+        };
+        match done {
+            Ok(done) => log::info!("{} {}", done, path.display()),
+            Err(e) => log::error!("{}", e),
+        }
+    }
+}
+
+/// Run the resistor pipeline of `config`, which writes its outputs
+fn generate_resistors(config: &AppConfig) {
+    let mut world = component::ecs::build_resistor_world();
+    world.insert_resource(config.generator_config());
+    world.insert_resource(ESeriesCache::default());
+    let tolerance = config.resistor_tolerance().unwrap_or_default();
+    for package in &config.packages {
+        let mut template = world.spawn((ESeries(config.series), Package { name: package.clone(), imperial: package.clone(), metric: String::new() }));
+        if let Some(tolerance) = tolerance {
+            template.insert(Tolerance(tolerance.to_string()));
+        }
+    }
+    component::ecs::run_generation_pipeline(&mut world);
+    log::info!("Generated {} parts", world.query::<&PartNumber>().iter(&world).count());
+}
+
+/// Write a symbol library per package of an inductor, ferrite bead or LED
+/// `config` into `symbols`, and its footprints into `footprints`, of
+/// `output_dir`, as far as the configuration asks for them. Libraries are
+/// named as `aeda generate` names them.
+fn generate_family(config: &AppConfig, output_dir: &Path) -> Result<(), AtlantixError> {
+    if config.altium {
+        log::warn!("Altium CSVs are generated for resistors only, skipping them for {}", config.family);
+    }
+    let packages: Vec<&str> = config.packages.iter().map(String::as_str).collect();
+    let colors: Vec<LedColor> = config.led_colors.iter().map(|color| color.parse()).collect::<Result<_, _>>()?;
+    if config.kicad_symbols {
+        let symbols_dir = output_dir.join("symbols");
+        component::atomic::create_dir_all(&symbols_dir)?;
+        for package in &packages {
+            let symbol_path = |name: String| symbols_dir.join(format!("{}.kicad_sym", name));
+            let path = match config.family {
+                Family::Inductors => {
+                    let inductor = Inductor::new(config.inductor_series, package, config.shielded)
+                        .ok_or_else(|| AtlantixError::invalid_package("inductor", package, INDUCTOR_PACKAGES))?;
+                    let variant = if config.shielded { "_Shielded" } else { "" };
+                    let path = symbol_path(format!("L_E{}_{}{}", config.inductor_series, package, variant));
+                    inductor.generate_kicad_symbols(&INDUCTOR_DECADES, &path.to_string_lossy())?;
+                    path
+                }
+                Family::Ferrites => {
+                    let bead = FerriteBead::new(package, FERRITE_IMPEDANCES.to_vec())
+                        .ok_or_else(|| AtlantixError::invalid_package("ferrite bead", package, FERRITE_PACKAGES))?;
+                    let path = symbol_path(format!("FB_{}", package));
+                    bead.generate_kicad_symbols(&path.to_string_lossy())?;
+                    path
+                }
+                Family::Leds => {
+                    let led = Led::new(package, colors.clone()).ok_or_else(|| AtlantixError::invalid_package("LED", package, LED_PACKAGES))?;
+                    let path = symbol_path(format!("LED_{}", package));
+                    led.generate_kicad_symbols(&path.to_string_lossy())?;
+                    path
+                }
+                Family::Resistors => unreachable!("resistors run the pipeline"),
+            };
+            log::info!("Wrote {}", path.display());
+        }
+    }
+    if config.kicad_footprints {
+        let footprints_dir = output_dir.join("footprints");
+        component::atomic::create_dir_all(&footprints_dir)?;
+        let dir = footprints_dir.to_string_lossy();
+        match config.family {
+            Family::Inductors => Inductor::generate_kicad_footprints(&packages, &dir)?,
+            Family::Ferrites => FerriteBead::generate_kicad_footprints(&packages, &dir)?,
+            Family::Leds => Led::generate_kicad_footprints(&packages, &dir)?,
+            Family::Resistors => unreachable!("resistors run the pipeline"),
+        }
+        log::info!("Wrote the footprints into {}", footprints_dir.display());
+    }
+    Ok(())
+}
+
+impl egui_dock::TabViewer for State {
+    type Tab = Tab;
+
+    fn title(&mut self, tab: &mut Tab) -> egui::WidgetText {
+        match tab {
+            Tab::Preview => "Preview".into(),
+            Tab::Dashboard => "Dashboard".into(),
+            Tab::Logs => "Logs".into(),
+        }
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, tab: &mut Tab) {
+        match tab {
+            Tab::Preview if self.config.family != Family::Resistors => {
+                ui.label(format!("The preview lists resistors. Generate writes the {} libraries.", self.config.family));
+            }
+            Tab::Preview => {
+                // The selected part's symbol and footprint under the table
+                if let Some(part) = self.table.selected() {
+                    egui::TopBottomPanel::bottom("drawing").resizable(true).show_inside(ui, |ui| {
+                        // Drawn with the configuration's footprint density
+                        let style = self.config.symbol_styles().for_package(&part.package).to_string();
+                        self.config.settings().unwrap_or_default().scope(|| component::gui::drawing::part_ui(ui, part, &style));
+                    });
+                }
+                self.table.ui(ui);
+            }
+            Tab::Dashboard => {
+                if ui.button("Refresh").clicked() {
+                    self.summary = None;
+                }
+                let data_dir = &self.data_dir;
+                let summary = self
+                    .summary
+                    .get_or_insert_with(|| component::dashboard::summarize(data_dir).map_err(|e| e.to_string()));
+                egui::ScrollArea::vertical().show(ui, |ui| match summary {
+                    Ok(summary) => dashboard::summary_ui(ui, summary),
+                    Err(e) => {
+                        ui.label(e.as_str());
+                    }
+                });
+            }
+            Tab::Logs => {
+                if ui.button("Export…").clicked() {
+                    let _ = self.file_dialog.open(DialogMode::SaveFile, true, Some(EXPORT_LOGS));
+                }
+                self.logs.ui(ui);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(data_dir: PathBuf) -> App {
+        App::new(LogBuffer::default(), data_dir)
+    }
+
+    /// Run one frame without a window
+    fn frame(app: &mut App) {
+        let ctx = egui::Context::default();
+        let _ = ctx.run(Default::default(), |ctx| app.ui(ctx));
+    }
+
+    #[test]
+    fn the_preview_follows_the_configuration() {
+        let mut app = app(std::env::temp_dir().join(format!("aeda-gui-preview-{}", std::process::id())));
+        frame(&mut app);
+        // Six decades of E96 in the default 0603 and 0805
+        assert_eq!(app.state.table.visible().len(), 2 * 6 * 96);
+
+        app.state.config.packages = vec!["1206".to_string()];
+        app.state.config.min_value = Some("1K".to_string());
+        app.state.config.max_value = Some("9.99K".to_string());
+        frame(&mut app);
+        let parts: Vec<&str> = app.state.table.visible().iter().map(|row| row.part_number.as_str()).collect();
+        assert_eq!(parts.len(), 96);
+        assert_eq!(parts[0], "R1206_1.00K");
+
+        // Thin film at 0.1%
+        app.state.config.series = 192;
+        app.state.config.tolerance = Some("0.1%".to_string());
+        frame(&mut app);
+        let first = app.state.table.visible()[0];
+        assert_eq!((first.part_number.as_str(), first.tolerance.as_str()), ("R1206_1.00K", "0.1%"));
+        assert!(first.mpns[0].1.starts_with("TNPW1206"), "{:?}", first.mpns);
+        app.state.config.series = 96;
+        app.state.config.tolerance = None;
+
+        // Yageo as the preferred source, Vishay as the alternate
+        app.state.config.manufacturers = vec!["Yageo".to_string(), "Vishay".to_string()];
+        frame(&mut app);
+        let sources: Vec<&str> = app.state.table.visible()[0].mpns.iter().map(|(manufacturer, _)| manufacturer.as_str()).collect();
+        assert_eq!(sources, ["Yageo", "Vishay"]);
+    }
+
+    #[test]
+    fn generation_writes_into_the_data_directory_on_a_worker() {
+        let data_dir = std::env::temp_dir().join(format!("aeda-gui-generate-{}", std::process::id()));
+        let mut app = app(data_dir.clone());
+        app.state.config.packages = vec!["0603".to_string(), "0805".to_string()];
+        app.state.config.package_styles.insert("0805".to_string(), "american".to_string());
+        app.state.config.min_value = Some("1K".to_string());
+        app.state.config.max_value = Some("9.99K".to_string());
+        app.state.config.kicad_version = Some("8".to_string());
+        app.state.config.density = Some("most".to_string());
+        app.state.config.altium = true;
+        app.state.config.altium_file = Some("Atlantix_R_{package}.csv".to_string());
+        app.state.generate();
+        app.state.worker.take().unwrap().join().unwrap();
+
+        let outputs = data_dir.join("outputs");
+        let library = |package: &str| std::fs::read_to_string(outputs.join(format!("kicad/symbols/resistors_{}.kicad_sym", package))).unwrap();
+        assert!(library("0603").contains("(version 20231120)"), "not KiCad 8");
+        // European rectangles but for the American 0805
+        assert!(library("0603").contains("(rectangle"));
+        assert!(!library("0805").contains("(rectangle"));
+        assert!(outputs.join("kicad/footprints.pretty/R_0603_1608Metric_M.kicad_mod").exists());
+        // A header and the 96 values of 1K to 9.99K
+        let csv = std::fs::read_to_string(outputs.join("altium/Atlantix_R_0603.csv")).unwrap();
+        assert_eq!(csv.lines().count(), 1 + 96);
+        std::fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn the_dashboard_reads_the_data_directory() {
+        let data_dir = std::env::temp_dir().join(format!("aeda-gui-dashboard-{}", std::process::id()));
+        let mut app = app(data_dir.clone());
+        let dashboard = app.dock.find_tab(&Tab::Dashboard).unwrap();
+        app.dock.set_active_tab(dashboard);
+        frame(&mut app);
+        assert!(matches!(app.state.summary, Some(Err(_))), "no manifest yet");
+
+        std::fs::create_dir_all(data_dir.join("libraries")).unwrap();
+        let manifest = r#"{"name": "test", "version": "1.0.0", "description": "", "libraries": {"resistor": {}}}"#;
+        std::fs::write(data_dir.join("libraries/manifest.json"), manifest).unwrap();
+        app.state.summary = None;
+        frame(&mut app);
+        let summary = app.state.summary.as_ref().unwrap().as_ref().unwrap();
+        assert_eq!(summary.name, "test");
+        assert!(summary.categories.contains_key("resistor"));
+        std::fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn starts_from_the_configuration_of_the_last_run() {
+        let data_dir = std::env::temp_dir().join(format!("aeda-gui-last-{}", std::process::id()));
+        let config = AppConfig { family: Family::Ferrites, packages: vec!["0402".to_string()], ..Default::default() };
+        config.save_last(&data_dir).unwrap();
+        assert_eq!(app(data_dir.clone()).state.config, config);
+        std::fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn other_families_write_their_symbols_and_footprints() {
+        let data_dir = std::env::temp_dir().join(format!("aeda-gui-family-{}", std::process::id()));
+        let mut app = app(data_dir.clone());
+        family::select(&mut app.state.config, Family::Leds);
+        app.state.config.led_colors = vec!["Red".to_string()];
+        app.state.config.suppliers = vec!["mouser".to_string(), "digikey".to_string()];
+        frame(&mut app);
+        assert!(app.state.table.visible().is_empty());
+
+        app.state.generate();
+        app.state.worker.take().unwrap().join().unwrap();
+        let outputs = data_dir.join("outputs");
+        let led = std::fs::read_to_string(outputs.join("symbols/LED_0603.kicad_sym")).unwrap();
+        assert!(led.contains("Red"));
+        assert!(led.contains(r#"(property "Supplier" "Mouser""#));
+        assert!(led.contains(r#"(property "Supplier 2" "Digikey""#));
+        assert!(outputs.join("footprints").read_dir().unwrap().next().is_some());
+
+        // Only the formats ticked are written
+        std::fs::remove_dir_all(&outputs).unwrap();
+        app.state.config.kicad_symbols = false;
+        app.state.generate();
+        app.state.worker.take().unwrap().join().unwrap();
+        assert!(!outputs.join("symbols").exists());
+        assert!(outputs.join("footprints").exists());
+        std::fs::remove_dir_all(&data_dir).unwrap();
+    }
+}
@@ -0,0 +1,26 @@
+//! Atlantix EDA desktop front end
+//!
+//! Hosts the widgets of `component::gui` in one window: the family and its
+//! settings on the left; the Preview tab listing the parts of the current
+//! configuration, with the symbol and footprint of the selected one; the
+//! Dashboard tab with the library health of the data directory; the Logs
+//! tab with what generation and the library report, worker threads
+//! included.
+
+mod app;
+
+use component::gui::logs::LogBuffer;
+use std::path::PathBuf;
+
+fn main() -> eframe::Result {
+    let logs = component::gui::logs::capture(log::LevelFilter::Debug).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        LogBuffer::default()
+    });
+    let data_dir = dirs::home_dir().map(|h| h.join("atlantix-eda")).unwrap_or_else(|| PathBuf::from("atlantix-eda"));
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default().with_title("Atlantix EDA").with_inner_size([1280.0, 800.0]),
+        ..Default::default()
+    };
+    eframe::run_native("Atlantix EDA", options, Box::new(|_cc| Ok(Box::new(app::App::new(logs, data_dir)))))
+}
@@ -0,0 +1,131 @@
+//! Value codes shared by the encoders.
+//!
+//! A value is reduced to three significant digits (100..=999) and the decade
+//! exponent of the leading digit in ohms, without floating point: 4.99K is
+//! `(499, 3)`, 0.47 Ohm is `(470, -1)`.
+
+/// Significant digits and exponent of a value in micro-ohms, `(0, 0)` for zero
+///
+/// ```
+/// use atlantix_mpn::digits;
+///
+/// assert_eq!(digits::significant(4_990_000_000), (499, 3));
+/// assert_eq!(digits::significant(470_000), (470, -1));
+/// // 9.995K rounds up into the next decade
+/// assert_eq!(digits::significant(9_995_000_000), (100, 4));
+/// ```
+pub fn significant(micro_ohms: u64) -> (u32, i32) {
+    if micro_ohms == 0 {
+        return (0, 0);
+    }
+    let length = micro_ohms.ilog10() as i32 + 1;
+    let mut exponent = length - 7;
+    let mut digits = if length <= 3 {
+        micro_ohms * 10u64.pow((3 - length) as u32)
+    } else {
+        let divisor = 10u64.pow((length - 3) as u32);
+        (micro_ohms + divisor / 2) / divisor
+    };
+    if digits >= 1000 {
+        digits /= 10;
+        exponent += 1;
+    }
+    (digits as u32, exponent)
+}
+
+/// The value as written in part names and by Digi-Key: `4.99K`, `100`,
+/// `1.00M`, `0R47`, `10m`
+///
+/// ```
+/// use atlantix_mpn::digits;
+///
+/// assert_eq!(digits::label(499, 3), "4.99K");
+/// assert_eq!(digits::label(100, 2), "100");
+/// assert_eq!(digits::label(470, -1), "0R47");
+/// assert_eq!(digits::label(100, -2), "10m");
+/// ```
+pub fn label(digits: u32, exponent: i32) -> String {
+    match exponent {
+        e if e < -1 => {
+            let milliohms = format!("{:.*}", (-1 - e) as usize, digits as f64 * 10f64.powi(e + 1));
+            let trimmed = milliohms.trim_end_matches('0').trim_end_matches('.');
+            format!("{}m", trimmed)
+        }
+        -1 => format!("0R{}", digits.to_string().trim_end_matches('0')),
+        e => {
+            let suffix = ["", "K", "M"][(e / 3).min(2) as usize];
+            let scale = 10f64.powi(3 * (e / 3).min(2));
+            let decimals = (2 - (e - 3 * (e / 3).min(2))).max(0) as usize;
+            format!("{:.*}{}", decimals, digits as f64 * 10f64.powi(e - 2) / scale, suffix)
+        }
+    }
+}
+
+/// Micro-ohms of a [`label`] from 1 Ohm up, `None` for anything else
+///
+/// ```
+/// assert_eq!(atlantix_mpn::digits::parse_label("4.99K"), Some(4_990_000_000));
+/// assert_eq!(atlantix_mpn::digits::parse_label("10.0"), Some(10_000_000));
+/// assert_eq!(atlantix_mpn::digits::parse_label("4.99X"), None);
+/// ```
+pub fn parse_label(label: &str) -> Option<u64> {
+    let (number, scale) = match label.as_bytes().last()? {
+        b'K' => (&label[..label.len() - 1], 9),
+        b'M' => (&label[..label.len() - 1], 12),
+        _ => (label, 6),
+    };
+    let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+    scaled(integer, fraction, scale)
+}
+
+/// Four character code where R, K or M marks the decimal point: `4K99`,
+/// `100R`, `1M00`, `R470`
+///
+/// ```
+/// use atlantix_mpn::digits;
+///
+/// assert_eq!(digits::letter_code(499, 3), "4K99");
+/// assert_eq!(digits::letter_code(100, 2), "100R");
+/// assert_eq!(digits::letter_code(470, -1), "R470");
+/// ```
+pub fn letter_code(digits: u32, exponent: i32) -> String {
+    if exponent < 0 {
+        let zeros = "0".repeat((-1 - exponent) as usize);
+        return format!("R{}{}", zeros, digits);
+    }
+    let letter = ["R", "K", "M"][(exponent / 3).min(2) as usize];
+    let integer_digits = (exponent - 3 * (exponent / 3).min(2) + 1) as usize;
+    let digits = digits.to_string();
+    if integer_digits >= digits.len() {
+        format!("{}{}", digits, letter)
+    } else {
+        format!("{}{}{}", &digits[..integer_digits], letter, &digits[integer_digits..])
+    }
+}
+
+/// Micro-ohms of a [`letter_code`], `None` for anything else
+///
+/// ```
+/// assert_eq!(atlantix_mpn::digits::parse_letter_code("4K99"), Some(4_990_000_000));
+/// assert_eq!(atlantix_mpn::digits::parse_letter_code("R470"), Some(470_000));
+/// assert_eq!(atlantix_mpn::digits::parse_letter_code("4K9K"), None);
+/// ```
+pub fn parse_letter_code(code: &str) -> Option<u64> {
+    let position = code.find(['R', 'K', 'M'])?;
+    let scale = match &code[position..position + 1] {
+        "R" => 6,
+        "K" => 9,
+        _ => 12,
+    };
+    scaled(&code[..position], &code[position + 1..], scale)
+}
+
+/// `integer.fraction` times ten to the `scale`, exactly
+fn scaled(integer: &str, fraction: &str, scale: u32) -> Option<u64> {
+    if integer.len() + fraction.len() == 0 || !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let mantissa: u64 = format!("{}{}", integer, fraction).parse().ok()?;
+    let shift = scale.checked_sub(fraction.len() as u32)?;
+    mantissa.checked_mul(10u64.checked_pow(shift)?)
+}
@@ -0,0 +1,44 @@
+//! The golden part numbers of `testdata/golden.csv`.
+//!
+//! One row per manufacturer, package, value and tolerance the encoders
+//! were pinned with, from sub-ohm to 10 MOhm, with the Digi-Key part number
+//! where Digi-Key stocks the part.
+//!
+//! ```
+//! let row = atlantix_mpn::fixtures::golden().find(|row| row.mpn == "CRCW06034K99FKEA").unwrap();
+//! assert_eq!(row.part.micro_ohms, 4_990_000_000);
+//! assert_eq!(row.digikey_pn, Some("541-4.99KHCT-ND"));
+//! ```
+
+use crate::Part;
+
+/// The table as CSV: `manufacturer,package,micro_ohms,tolerance,mpn,digikey_pn`
+pub const GOLDEN_CSV: &str = include_str!("../testdata/golden.csv");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Golden {
+    pub manufacturer: &'static str,
+    pub part: Part,
+    pub mpn: &'static str,
+    pub digikey_pn: Option<&'static str>,
+}
+
+/// Rows of [`GOLDEN_CSV`] below the header
+pub fn golden() -> impl Iterator<Item = Golden> {
+    GOLDEN_CSV.lines().skip(1).filter(|line| !line.trim().is_empty()).map(|line| {
+        let fields: Vec<&'static str> = line.trim_end().split(',').collect();
+        let [manufacturer, package, micro_ohms, tolerance, mpn, digikey_pn] = fields[..] else {
+            panic!("golden row with {} fields: {}", fields.len(), line);
+        };
+        Golden {
+            manufacturer,
+            part: Part {
+                package,
+                micro_ohms: micro_ohms.parse().unwrap_or_else(|_| panic!("golden row with value {}", micro_ohms)),
+                tolerance,
+            },
+            mpn,
+            digikey_pn: (!digikey_pn.is_empty()).then_some(digikey_pn),
+        }
+    })
+}
@@ -0,0 +1,115 @@
+//! KOA Speer RK73H thick film chip resistors.
+//!
+//! `RK73H1JTTD4991F` reads as: series (`RK73H`, the 1% / 0.5% grade), size
+//! (`1J` = 0603), tin termination (`T`), packaging (`TD` 4 mm paper tape),
+//! resistance and tolerance (`F` = 1%). From 100 Ohm up the resistance is
+//! three significant digits and a decade digit (`4991` = 4.99K); below that
+//! an R marks the decimal point (`49R9`, `4R99`).
+
+use crate::digits;
+use crate::Part;
+
+/// Sizes of the series
+pub const PACKAGES: [&str; 8] = ["0201", "0402", "0603", "0805", "1206", "1210", "2010", "2512"];
+
+/// KOA size code and the tape each size ships on, embossed for the large sizes
+const SIZES: [(&str, &str, &str); 8] = [
+    ("0201", "1H", "TC"),
+    ("0402", "1E", "TP"),
+    ("0603", "1J", "TD"),
+    ("0805", "2A", "TD"),
+    ("1206", "2B", "TD"),
+    ("1210", "2E", "TD"),
+    ("2010", "2H", "TE"),
+    ("2512", "3A", "TE"),
+];
+
+/// RK73H resistance code, `None` outside the 1 Ohm to 10 MOhm range
+///
+/// ```
+/// use atlantix_mpn::koa;
+///
+/// assert_eq!(koa::value_code(4_990_000_000).as_deref(), Some("4991"));
+/// assert_eq!(koa::value_code(100_000_000).as_deref(), Some("1000"));
+/// assert_eq!(koa::value_code(49_900_000).as_deref(), Some("49R9"));
+/// assert_eq!(koa::value_code(4_990_000).as_deref(), Some("4R99"));
+/// assert_eq!(koa::value_code(10_000_000_000_000).as_deref(), Some("1005"));
+/// assert_eq!(koa::value_code(470_000), None);
+/// ```
+pub fn value_code(micro_ohms: u64) -> Option<String> {
+    let (digits, exponent) = digits::significant(micro_ohms);
+    let digits = digits.to_string();
+    match exponent {
+        0 | 1 => {
+            let (integer, fraction) = digits.split_at(exponent as usize + 1);
+            Some(format!("{}R{}", integer, fraction))
+        }
+        2..=7 => Some(format!("{}{}", digits, exponent - 2)),
+        _ => None,
+    }
+}
+
+fn tolerance_code(tolerance: &str) -> Option<char> {
+    match tolerance {
+        "0.1%" | "0.25%" => None,
+        "0.5%" => Some('D'),
+        // Looser E-series ranges get the 1% part
+        _ => Some('F'),
+    }
+}
+
+/// RK73H part number, `None` for sizes, values or tolerances KOA does not
+/// make in this series
+///
+/// ```
+/// use atlantix_mpn::koa;
+///
+/// assert_eq!(koa::rk73h_mpn("0603", 4_990_000_000, "1%").as_deref(), Some("RK73H1JTTD4991F"));
+/// assert_eq!(koa::rk73h_mpn("2512", 4_990_000_000, "1%").as_deref(), Some("RK73H3ATTE4991F"));
+/// assert_eq!(koa::rk73h_mpn("0603", 4_990_000_000, "0.1%"), None);
+/// ```
+pub fn rk73h_mpn(package: &str, micro_ohms: u64, tolerance: &str) -> Option<String> {
+    let (_, size, tape) = SIZES.iter().find(|(size, _, _)| *size == package)?;
+    let value = value_code(micro_ohms)?;
+    let tolerance = tolerance_code(tolerance)?;
+    Some(format!("RK73H{}T{}{}{}", size, tape, value, tolerance))
+}
+
+/// Digi-Key cut tape part number, e.g. `2019-RK73H1JTTD4991FCT-ND`
+pub fn digikey_pn(package: &str, micro_ohms: u64, tolerance: &str) -> Option<String> {
+    rk73h_mpn(package, micro_ohms, tolerance).map(|mpn| format!("2019-{}CT-ND", mpn))
+}
+
+/// The part of an RK73H part number, `None` for anything [`rk73h_mpn`]
+/// does not write
+///
+/// ```
+/// use atlantix_mpn::{koa, Part};
+///
+/// assert_eq!(koa::decode("RK73H1JTTD49R9D"), Some(Part { package: "0603", micro_ohms: 49_900_000, tolerance: "0.5%" }));
+/// assert_eq!(koa::decode("RK73H1JTTE4991F"), None);
+/// ```
+pub fn decode(mpn: &str) -> Option<Part> {
+    let rest = mpn.strip_prefix("RK73H")?;
+    let (package, _, _) = SIZES.iter().find(|(_, size, tape)| rest.get(..5) == Some(&format!("{}T{}", size, tape)))?;
+    let (code, tolerance) = (rest.get(5..9)?, rest.get(9..)?);
+    let tolerance = match tolerance {
+        "F" => "1%",
+        "D" => "0.5%",
+        _ => return None,
+    };
+    let micro_ohms = if code.contains('R') {
+        digits::parse_letter_code(code)?
+    } else {
+        let digits: u64 = code[..3].parse().ok()?;
+        let decade: u32 = code[3..].parse().ok()?;
+        digits.checked_mul(10u64.checked_pow(decade + 6)?)?
+    };
+    let part = Part { package, micro_ohms, tolerance };
+    (rk73h_mpn(package, micro_ohms, tolerance).as_deref() == Some(mpn)).then_some(part)
+}
+
+/// The part of a Digi-Key part number written by [`digikey_pn`]
+pub fn decode_digikey(pn: &str) -> Option<Part> {
+    decode(pn.strip_prefix("2019-")?.strip_suffix("CT-ND")?)
+}
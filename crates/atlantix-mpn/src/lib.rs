@@ -0,0 +1,171 @@
+//! # atlantix-mpn
+//!
+//! Exact manufacturer and Digi-Key part numbers of chip resistors, built
+//! and decoded the way the Atlantix EDA library generators write them, for
+//! tools that need the numbers without the generators: BOM scrubbers,
+//! the Stencil DSL, distributor cross-checks.
+//!
+//! Values are whole micro-ohms, so `4.99K` is `4_990_000_000` and no float
+//! rounding reaches a part number. Every encoder returns `None` for what
+//! the manufacturer does not make, and every decoder only accepts what the
+//! matching encoder writes, so decoding and encoding again gives back the
+//! same part number.
+//!
+//! ```
+//! # #[cfg(all(feature = "vishay", feature = "koa"))] {
+//! use atlantix_mpn::{koa, vishay, Part};
+//!
+//! let mpn = vishay::crcw_mpn("0603", 4_990_000_000, "1%").unwrap();
+//! assert_eq!(mpn, "CRCW06034K99FKEA");
+//! assert_eq!(vishay::decode(&mpn), Some(Part { package: "0603", micro_ohms: 4_990_000_000, tolerance: "1%" }));
+//! assert_eq!(koa::digikey_pn("0603", 4_990_000_000, "1%").as_deref(), Some("2019-RK73H1JTTD4991FCT-ND"));
+//! # }
+//! ```
+//!
+//! ## Stability
+//!
+//! A part number is a contract with the distributors and with every library
+//! already generated: an encoder returning something else for the same input
+//! is a breaking change. `testdata/golden.csv` pins the output of every
+//! encoder and the tests check it row by row; with the `fixtures` feature
+//! downstream crates can check their own use against the same rows.
+//!
+//! ## Features
+//!
+//! - `vishay` (default): Vishay Dale CRCW thick film and TNPW thin film
+//! - `koa` (default): KOA Speer RK73H
+//! - `fixtures`: the golden table as [`fixtures::golden`]
+
+pub mod digits;
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures;
+#[cfg(feature = "koa")]
+pub mod koa;
+#[cfg(feature = "vishay")]
+pub mod vishay;
+
+/// What a part number encodes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Part {
+    /// Imperial package code, e.g. `0603`
+    pub package: &'static str,
+    pub micro_ohms: u64,
+    /// Tolerance the part is sold as, e.g. `1%` or `0.1%`
+    pub tolerance: &'static str,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every value with three significant digits from 1 Ohm to 9.99 MOhm
+    #[cfg(any(feature = "vishay", feature = "koa"))]
+    fn all_values() -> impl Iterator<Item = u64> {
+        (0..7u32).flat_map(|decade| (100..1000u64).map(move |digits| digits * 10u64.pow(decade + 4)))
+    }
+
+    #[test]
+    fn golden_table_has_rows_for_every_manufacturer() {
+        let rows: Vec<_> = fixtures::golden().collect();
+        assert!(rows.iter().any(|row| row.manufacturer == "Vishay"));
+        assert!(rows.iter().any(|row| row.manufacturer == "KOA Speer"));
+        assert!(rows.iter().any(|row| row.digikey_pn.is_none()));
+    }
+
+    #[cfg(feature = "vishay")]
+    #[test]
+    fn vishay_matches_the_golden_table() {
+        for row in fixtures::golden().filter(|row| row.manufacturer == "Vishay") {
+            let Part { package, micro_ohms, tolerance } = row.part;
+            let encoded = match tolerance {
+                "0.05%" | "0.1%" => vishay::tnpw_mpn(package, micro_ohms, tolerance),
+                _ => vishay::crcw_mpn(package, micro_ohms, tolerance),
+            };
+            assert_eq!(encoded.as_deref(), Some(row.mpn), "{:?}", row);
+            assert_eq!(vishay::decode(row.mpn), Some(row.part), "{}", row.mpn);
+            if let Some(digikey_pn) = row.digikey_pn {
+                assert_eq!(vishay::digikey_pn(package, micro_ohms, tolerance).as_deref(), Some(digikey_pn));
+                assert_eq!(vishay::decode_digikey(digikey_pn), Some(row.part), "{}", digikey_pn);
+            }
+        }
+    }
+
+    #[cfg(feature = "koa")]
+    #[test]
+    fn koa_matches_the_golden_table() {
+        for row in fixtures::golden().filter(|row| row.manufacturer == "KOA Speer") {
+            let Part { package, micro_ohms, tolerance } = row.part;
+            assert_eq!(koa::rk73h_mpn(package, micro_ohms, tolerance).as_deref(), Some(row.mpn), "{:?}", row);
+            // Looser tolerances are sold as the 1% part
+            let sold_as = if tolerance == "0.5%" { "0.5%" } else { "1%" };
+            assert_eq!(koa::decode(row.mpn), Some(Part { tolerance: sold_as, ..row.part }), "{}", row.mpn);
+            let digikey_pn = row.digikey_pn.expect("every RK73H part is on Digi-Key");
+            assert_eq!(koa::digikey_pn(package, micro_ohms, tolerance).as_deref(), Some(digikey_pn));
+            assert_eq!(koa::decode_digikey(digikey_pn), Some(Part { tolerance: sold_as, ..row.part }));
+        }
+    }
+
+    #[cfg(feature = "vishay")]
+    #[test]
+    fn every_vishay_part_number_decodes_to_its_part() {
+        for package in vishay::PACKAGES {
+            for micro_ohms in all_values() {
+                for tolerance in ["0.5%", "1%", "5%"] {
+                    let mpn = vishay::crcw_mpn(package, micro_ohms, tolerance).unwrap();
+                    assert_eq!(vishay::decode(&mpn), Some(Part { package, micro_ohms, tolerance }), "{}", mpn);
+                    let digikey_pn = vishay::digikey_pn(package, micro_ohms, tolerance).unwrap();
+                    assert_eq!(vishay::decode_digikey(&digikey_pn), Some(Part { package, micro_ohms, tolerance }), "{}", digikey_pn);
+                }
+            }
+        }
+        for package in vishay::TNPW_PACKAGES {
+            for micro_ohms in all_values() {
+                for tolerance in ["0.05%", "0.1%"] {
+                    let mpn = vishay::tnpw_mpn(package, micro_ohms, tolerance).unwrap();
+                    assert_eq!(vishay::decode(&mpn), Some(Part { package, micro_ohms, tolerance }), "{}", mpn);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "vishay")]
+    #[test]
+    fn vishay_writes_no_part_for_tolerances_crcw_is_not_made_in() {
+        // E192 parts are 0.5%, the D grade, not the 1% F grade
+        assert_eq!(vishay::crcw_mpn("0603", 1_020_000_000, "0.5%").as_deref(), Some("CRCW06031K02DKEA"));
+        assert_eq!(vishay::digikey_pn("0603", 1_020_000_000, "0.5%").as_deref(), Some("541-CRCW06031K02DKEACT-ND"));
+        for tolerance in ["0.25%", "2%"] {
+            assert_eq!(vishay::crcw_mpn("0603", 1_020_000_000, tolerance), None, "{}", tolerance);
+            assert_eq!(vishay::digikey_pn("0603", 1_020_000_000, tolerance), None, "{}", tolerance);
+        }
+    }
+
+    #[cfg(feature = "koa")]
+    #[test]
+    fn every_koa_part_number_decodes_to_its_part() {
+        for package in koa::PACKAGES {
+            for micro_ohms in all_values() {
+                for tolerance in ["0.5%", "1%"] {
+                    let mpn = koa::rk73h_mpn(package, micro_ohms, tolerance).unwrap();
+                    assert_eq!(koa::decode(&mpn), Some(Part { package, micro_ohms, tolerance }), "{}", mpn);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn malformed_part_numbers_do_not_decode() {
+        #[cfg(feature = "vishay")]
+        for mpn in ["", "CRCW", "CRCW0603", "CRCW06034K99FKE", "CRCW06034K9FKEA", "CRCW06034K99XXEA", "CRCW01004K99FKEA", "TNPW06034K99FKEA", "CRCW0603K499FKEA"] {
+            assert_eq!(vishay::decode(mpn), None, "{}", mpn);
+        }
+        #[cfg(feature = "vishay")]
+        for pn in ["541-4.99KHCT", "541-4.99KQCT-ND", "541-4.99KHHCT-ND", "2019-RK73H1JTTD4991FCT-ND"] {
+            assert_eq!(vishay::decode_digikey(pn), None, "{}", pn);
+        }
+        #[cfg(feature = "koa")]
+        for mpn in ["", "RK73H", "RK73H1JTTD499F", "RK73H1JTTE4991F", "RK73H9ZTTD4991F", "RK73H1JTTD4991G", "RK73H1JTTD0991F"] {
+            assert_eq!(koa::decode(mpn), None, "{}", mpn);
+        }
+    }
+}
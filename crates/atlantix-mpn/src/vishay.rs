@@ -0,0 +1,181 @@
+//! Vishay Dale CRCW thick film and TNPW thin film chip resistors.
+//!
+//! `CRCW06034K99FKEA` reads as: series and size (`CRCW0603`), resistance
+//! (`4K99`), tolerance (`F` = 1%), TCR (`K` = 100 ppm/K), packaging (`EA`
+//! paper tape, lead free). 0.5% parts are `DKEA`, 5% and looser parts are
+//! `JNEA`, 200 ppm/K. CRCW is not made in 0.25% or 2%. 0201 parts come on
+//! `ED` tape and 1218 parts on `EK`, `CRCW02013K32FKED`.
+//! `TNPW06034K99BEEA` is 0.1% (`B`, `A` for 0.05%) at 25 ppm/K (`E`), in
+//! every CRCW size but 0201 and 1218.
+
+use crate::digits;
+use crate::Part;
+
+/// Sizes of the CRCW series
+pub const PACKAGES: [&str; 9] = ["0201", "0402", "0603", "0805", "1206", "1210", "1218", "2010", "2512"];
+
+/// Sizes of the TNPW series
+pub const TNPW_PACKAGES: [&str; 7] = ["0402", "0603", "0805", "1206", "1210", "2010", "2512"];
+
+/// Packaging letters of a CRCW size
+fn crcw_packaging(package: &str) -> &'static str {
+    match package {
+        "0201" => "ED",
+        "1218" => "EK",
+        _ => "EA",
+    }
+}
+
+/// Tolerance and TCR letters of a CRCW part, `None` for tolerances the
+/// series is not made in
+fn crcw_grade(tolerance: &str) -> Option<&'static str> {
+    match tolerance {
+        "0.5%" => Some("DK"),
+        "1%" => Some("FK"),
+        "5%" | "10%" | "20%" | "50%" => Some("JN"),
+        _ => None,
+    }
+}
+
+fn value_code(micro_ohms: u64) -> String {
+    let (digits, exponent) = digits::significant(micro_ohms);
+    digits::letter_code(digits, exponent)
+}
+
+/// CRCW part number, `None` for sizes or tolerances outside the series
+///
+/// ```
+/// use atlantix_mpn::vishay;
+///
+/// assert_eq!(vishay::crcw_mpn("0603", 4_990_000_000, "1%").as_deref(), Some("CRCW06034K99FKEA"));
+/// assert_eq!(vishay::crcw_mpn("0603", 4_990_000_000, "5%").as_deref(), Some("CRCW06034K99JNEA"));
+/// assert_eq!(vishay::crcw_mpn("0603", 4_990_000_000, "0.5%").as_deref(), Some("CRCW06034K99DKEA"));
+/// assert_eq!(vishay::crcw_mpn("0201", 3_320_000_000, "1%").as_deref(), Some("CRCW02013K32FKED"));
+/// assert_eq!(vishay::crcw_mpn("1218", 1_000_000_000, "1%").as_deref(), Some("CRCW12181K00FKEK"));
+/// assert_eq!(vishay::crcw_mpn("0603", 4_990_000_000, "2%"), None);
+/// assert_eq!(vishay::crcw_mpn("MiniMELF", 4_990_000_000, "1%"), None);
+/// ```
+pub fn crcw_mpn(package: &str, micro_ohms: u64, tolerance: &str) -> Option<String> {
+    let grade = crcw_grade(tolerance)?;
+    PACKAGES
+        .contains(&package)
+        .then(|| format!("CRCW{}{}{}{}", package, value_code(micro_ohms), grade, crcw_packaging(package)))
+}
+
+/// TNPW part number, `None` for sizes outside the series or tolerances
+/// other than 0.1% and 0.05%
+///
+/// ```
+/// use atlantix_mpn::vishay;
+///
+/// assert_eq!(vishay::tnpw_mpn("0603", 4_990_000_000, "0.1%").as_deref(), Some("TNPW06034K99BEEA"));
+/// assert_eq!(vishay::tnpw_mpn("0805", 4_990_000_000, "0.05%").as_deref(), Some("TNPW08054K99AEEA"));
+/// assert_eq!(vishay::tnpw_mpn("0603", 4_990_000_000, "1%"), None);
+/// ```
+pub fn tnpw_mpn(package: &str, micro_ohms: u64, tolerance: &str) -> Option<String> {
+    let tolerance = match tolerance {
+        "0.05%" => 'A',
+        "0.1%" => 'B',
+        _ => return None,
+    };
+    TNPW_PACKAGES
+        .contains(&package)
+        .then(|| format!("TNPW{}{}{}EEA", package, value_code(micro_ohms), tolerance))
+}
+
+/// Digi-Key size letters of 1% parts from 1 Ohm up, doubled below 10 Ohm
+const DIGIKEY_SIZES: [(&str, &str, &str); 8] = [
+    ("0402", "LL", "L"),
+    ("0603", "HH", "H"),
+    ("0805", "CC", "C"),
+    ("1206", "FF", "F"),
+    ("1210", "AA", "V"),
+    ("1218", "AN", "KAN"),
+    ("2010", "AC", "KAC"),
+    ("2512", "AF", "KAF"),
+];
+
+/// Digi-Key cut tape part number. Digi-Key lists 0.5% and 5% parts and 0201
+/// parts under the MPN and doubles the size letter for values below 10 Ohm
+/// (`541-4.99HHCT-ND`). Sub-ohm values have no known Digi-Key number.
+///
+/// ```
+/// use atlantix_mpn::vishay;
+///
+/// assert_eq!(vishay::digikey_pn("0603", 4_990_000_000, "1%").as_deref(), Some("541-4.99KHCT-ND"));
+/// assert_eq!(vishay::digikey_pn("0603", 4_990_000, "1%").as_deref(), Some("541-4.99HHCT-ND"));
+/// assert_eq!(vishay::digikey_pn("0603", 100_000, "1%"), None);
+/// assert_eq!(vishay::digikey_pn("0603", 4_700_000_000, "5%").as_deref(), Some("541-CRCW06034K70JNEACT-ND"));
+/// assert_eq!(vishay::digikey_pn("0201", 3_320_000_000, "1%").as_deref(), Some("541-CRCW02013K32FKEDCT-ND"));
+/// ```
+pub fn digikey_pn(package: &str, micro_ohms: u64, tolerance: &str) -> Option<String> {
+    if micro_ohms < 1_000_000 {
+        return None;
+    }
+    let size = DIGIKEY_SIZES.iter().find(|(size, _, _)| *size == package);
+    let (Some((_, below_ten, from_ten)), "FK") = (size, crcw_grade(tolerance)?) else {
+        return crcw_mpn(package, micro_ohms, tolerance).map(|mpn| format!("541-{}CT-ND", mpn));
+    };
+    let suffix = if micro_ohms < 10_000_000 { below_ten } else { from_ten };
+    let (digits, exponent) = digits::significant(micro_ohms);
+    Some(format!("541-{}{}CT-ND", digits::label(digits, exponent), suffix))
+}
+
+/// The part of a CRCW or TNPW part number, `None` for anything [`crcw_mpn`]
+/// and [`tnpw_mpn`] do not write
+///
+/// ```
+/// use atlantix_mpn::{vishay, Part};
+///
+/// assert_eq!(vishay::decode("TNPW06034K99BEEA"), Some(Part { package: "0603", micro_ohms: 4_990_000_000, tolerance: "0.1%" }));
+/// assert_eq!(vishay::decode("CRCW06034K9FKEA"), None);
+/// ```
+pub fn decode(mpn: &str) -> Option<Part> {
+    let (series, rest) = (mpn.get(..4)?, mpn.get(4..)?);
+    let package = *PACKAGES.iter().find(|package| rest.starts_with(**package))?;
+    let rest = &rest[package.len()..];
+    let (code, tolerance) = match series {
+        "CRCW" => {
+            let rest = rest.strip_suffix(crcw_packaging(package))?;
+            match (rest.strip_suffix("FK"), rest.strip_suffix("DK")) {
+                (Some(code), _) => (code, "1%"),
+                (_, Some(code)) => (code, "0.5%"),
+                _ => (rest.strip_suffix("JN")?, "5%"),
+            }
+        }
+        "TNPW" => match rest.strip_suffix("AEEA") {
+            Some(code) => (code, "0.05%"),
+            None => (rest.strip_suffix("BEEA")?, "0.1%"),
+        },
+        _ => return None,
+    };
+    let part = Part { package, micro_ohms: digits::parse_letter_code(code)?, tolerance };
+    let encoded = match series {
+        "CRCW" => crcw_mpn(package, part.micro_ohms, tolerance),
+        _ => tnpw_mpn(package, part.micro_ohms, tolerance),
+    };
+    (encoded.as_deref() == Some(mpn)).then_some(part)
+}
+
+/// The part of a Digi-Key part number written by [`digikey_pn`]
+///
+/// ```
+/// use atlantix_mpn::{vishay, Part};
+///
+/// assert_eq!(vishay::decode_digikey("541-4.99HHCT-ND"), Some(Part { package: "0603", micro_ohms: 4_990_000, tolerance: "1%" }));
+/// assert_eq!(vishay::decode_digikey("541-CRCW06034K70JNEACT-ND").map(|part| part.tolerance), Some("5%"));
+/// ```
+pub fn decode_digikey(pn: &str) -> Option<Part> {
+    let rest = pn.strip_prefix("541-")?.strip_suffix("CT-ND")?;
+    if rest.starts_with("CRCW") {
+        return decode(rest).filter(|part| digikey_pn(part.package, part.micro_ohms, part.tolerance).as_deref() == Some(pn));
+    }
+    DIGIKEY_SIZES
+        .iter()
+        .flat_map(|(package, below_ten, from_ten)| [(package, below_ten), (package, from_ten)])
+        .find_map(|(package, suffix)| {
+            let label = rest.strip_suffix(suffix)?;
+            let part = Part { package, micro_ohms: digits::parse_label(label)?, tolerance: "1%" };
+            (digikey_pn(package, part.micro_ohms, "1%").as_deref() == Some(pn)).then_some(part)
+        })
+}
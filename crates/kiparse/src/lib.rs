@@ -40,6 +40,7 @@
 //! 
 //! - [`pcb`] - PCB file layer extraction (.kicad_pcb)
 //! - [`symbol`] - Symbol library parsing (.kicad_sym) 
+//! - [`sexpr`] - Lossless S-expression tree for rewriting files
 //! - [`error`] - Error types and handling
 //! 
 //! ## Performance Characteristics
@@ -52,6 +53,7 @@
 
 pub mod pcb;
 pub mod symbol;
+pub mod sexpr;
 pub mod error;
 pub mod prelude;
 
@@ -0,0 +1,267 @@
+//! Lossless S-expression tree for KiCad files
+//!
+//! The layer and symbol parsers extract just the data they need. Tools that
+//! rewrite files (library cleanup, format upgrades) need the whole tree
+//! instead, so this module parses any KiCad S-expression into [`SExpr`] nodes
+//! and prints them back in KiCad's layout. Quoted strings keep their raw
+//! escaped text, so a parse/print round trip never alters content.
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use kiparse::sexpr::{parse_sexpr, SExpr};
+//!
+//! let mut lib = parse_sexpr(r#"(kicad_symbol_lib (version 20211014) (symbol "R" (property "Value" "R")))"#)?;
+//! assert_eq!(lib.head(), Some("kicad_symbol_lib"));
+//! assert_eq!(lib.find("version").and_then(|v| v.value(1)), Some("20211014"));
+//!
+//! lib.find_mut("version").unwrap().set_value(1, SExpr::Atom("20220914".into()));
+//! assert!(lib.to_kicad_string().starts_with("(kicad_symbol_lib (version 20220914)\n  (symbol \"R\""));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::error::{KicadError, Result};
+
+/// A node of a KiCad S-expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum SExpr {
+    /// `(head child ...)`
+    List(Vec<SExpr>),
+    /// Bare token such as a keyword or number
+    Atom(String),
+    /// Quoted string, stored without the quotes but with escapes intact
+    String(String),
+}
+
+/// Elements KiCad writes on their own line. Their parent is broken over
+/// several lines, everything else stays inline.
+const BLOCK_KEYS: [&str; 14] = [
+    "symbol", "property", "pin", "rectangle", "polyline", "arc", "circle", "bezier", "text",
+    "stroke", "fill", "pts", "name", "number",
+];
+
+impl SExpr {
+    /// First atom of a list, e.g. `property`
+    pub fn head(&self) -> Option<&str> {
+        match self {
+            SExpr::List(items) => match items.first() {
+                Some(SExpr::Atom(head)) => Some(head),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Children of a list, empty for atoms and strings
+    pub fn items(&self) -> &[SExpr] {
+        match self {
+            SExpr::List(items) => items,
+            _ => &[],
+        }
+    }
+
+    pub fn items_mut(&mut self) -> Option<&mut Vec<SExpr>> {
+        match self {
+            SExpr::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Text of an atom or string
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            SExpr::Atom(text) | SExpr::String(text) => Some(text),
+            SExpr::List(_) => None,
+        }
+    }
+
+    /// Text of the child at `index`, e.g. `value(1)` is the name of a property
+    pub fn value(&self, index: usize) -> Option<&str> {
+        self.items().get(index).and_then(SExpr::as_str)
+    }
+
+    /// Replace the child at `index`, ignored when out of range
+    pub fn set_value(&mut self, index: usize, value: SExpr) {
+        if let Some(slot) = self.items_mut().and_then(|items| items.get_mut(index)) {
+            *slot = value;
+        }
+    }
+
+    /// First child list with the given head
+    pub fn find(&self, key: &str) -> Option<&SExpr> {
+        self.items().iter().find(|item| item.head() == Some(key))
+    }
+
+    pub fn find_mut(&mut self, key: &str) -> Option<&mut SExpr> {
+        self.items_mut()?.iter_mut().find(|item| item.head() == Some(key))
+    }
+
+    /// All child lists with the given head
+    pub fn find_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a SExpr> + 'a {
+        self.items().iter().filter(move |item| item.head() == Some(key))
+    }
+
+    fn is_block(&self) -> bool {
+        matches!(self.head(), Some(head) if BLOCK_KEYS.contains(&head))
+    }
+
+    /// Print in KiCad's layout: block elements on their own line with two
+    /// space indentation, everything else inline.
+    pub fn to_kicad_string(&self) -> String {
+        let mut out = String::new();
+        self.write(0, &mut out);
+        out
+    }
+
+    fn write(&self, indent: usize, out: &mut String) {
+        match self {
+            SExpr::Atom(text) => out.push_str(text),
+            SExpr::String(text) => {
+                out.push('"');
+                out.push_str(text);
+                out.push('"');
+            }
+            SExpr::List(items) => {
+                out.push('(');
+                let mut broken = false;
+                for (index, item) in items.iter().enumerate() {
+                    // Once a block element has been written, later lists follow on their own line
+                    if item.is_block() || (broken && matches!(item, SExpr::List(_))) {
+                        broken = true;
+                        out.push('\n');
+                        out.push_str(&"  ".repeat(indent + 1));
+                        item.write(indent + 1, out);
+                    } else {
+                        if index > 0 {
+                            out.push(' ');
+                        }
+                        item.write(indent, out);
+                    }
+                }
+                if broken {
+                    out.push('\n');
+                    out.push_str(&"  ".repeat(indent));
+                }
+                out.push(')');
+            }
+        }
+    }
+}
+
+/// Parse a complete S-expression document with a single root list
+pub fn parse_sexpr(content: &str) -> Result<SExpr> {
+    let mut parser = Parser { bytes: content.as_bytes(), source: content, pos: 0 };
+    parser.skip_whitespace();
+    let root = parser.parse_expr()?;
+    parser.skip_whitespace();
+    if parser.pos < parser.bytes.len() {
+        return Err(KicadError::ParseError(format!(
+            "Unexpected content after the root expression at byte {}",
+            parser.pos
+        )));
+    }
+    Ok(root)
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    source: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.bytes.len() && self.bytes[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn parse_expr(&mut self) -> Result<SExpr> {
+        match self.bytes.get(self.pos) {
+            Some(b'(') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                loop {
+                    self.skip_whitespace();
+                    match self.bytes.get(self.pos) {
+                        Some(b')') => {
+                            self.pos += 1;
+                            return Ok(SExpr::List(items));
+                        }
+                        Some(_) => items.push(self.parse_expr()?),
+                        None => return Err(KicadError::ParseError("Unbalanced parentheses".to_string())),
+                    }
+                }
+            }
+            Some(b'"') => {
+                let start = self.pos + 1;
+                let mut pos = start;
+                while pos < self.bytes.len() && self.bytes[pos] != b'"' {
+                    pos += if self.bytes[pos] == b'\\' { 2 } else { 1 };
+                }
+                if pos >= self.bytes.len() {
+                    return Err(KicadError::ParseError(format!("Unterminated string at byte {}", self.pos)));
+                }
+                self.pos = pos + 1;
+                Ok(SExpr::String(self.source[start..pos].to_string()))
+            }
+            Some(b')') => Err(KicadError::UnexpectedToken(format!("')' at byte {}", self.pos))),
+            Some(_) => {
+                let start = self.pos;
+                while self.pos < self.bytes.len()
+                    && !self.bytes[self.pos].is_ascii_whitespace()
+                    && !matches!(self.bytes[self.pos], b'(' | b')' | b'"')
+                {
+                    self.pos += 1;
+                }
+                Ok(SExpr::Atom(self.source[start..self.pos].to_string()))
+            }
+            None => Err(KicadError::ParseError("Unexpected end of input".to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GENERATED: &str = r#"(kicad_symbol_lib (version 20211014) (generator atlantix-eda)
+  (symbol "R0603_1.00K" (pin_numbers hide) (pin_names (offset 0)) (in_bom yes) (on_board yes)
+    (property "Reference" "R" (at 2.032 0 90) (effects (font (size 1.27 1.27))))
+    (property "ki_description" "RES 1.00K \"thin\" film" (at 0 0 0) (effects (font (size 1.27 1.27)) hide))
+    (symbol "R0603_1.00K_0_1"
+      (rectangle (start -1.016 -2.54) (end 1.016 2.54)
+        (stroke (width 0.254) (type default) (color 0 0 0 0))
+        (fill (type none))
+      )
+    )
+    (symbol "R0603_1.00K_1_1"
+      (pin passive line (at 0 3.81 270) (length 1.27)
+        (name "~" (effects (font (size 1.27 1.27))))
+        (number "1" (effects (font (size 1.27 1.27))))
+      )
+    )
+  )
+)"#;
+
+    #[test]
+    fn round_trips_generated_libraries() {
+        let parsed = parse_sexpr(GENERATED).unwrap();
+        assert_eq!(parsed.to_kicad_string(), GENERATED);
+    }
+
+    #[test]
+    fn keeps_escaped_strings_raw() {
+        let parsed = parse_sexpr(GENERATED).unwrap();
+        let symbol = parsed.find("symbol").unwrap();
+        let description = symbol.find_all("property").nth(1).unwrap();
+        assert_eq!(description.value(2), Some(r#"RES 1.00K \"thin\" film"#));
+    }
+
+    #[test]
+    fn rejects_unbalanced_input() {
+        assert!(parse_sexpr("(kicad_symbol_lib (version 20211014)").is_err());
+        assert!(parse_sexpr("(a) (b)").is_err());
+        assert!(parse_sexpr("(a \"open)").is_err());
+    }
+}